@@ -0,0 +1,321 @@
+//! Defines a builder for batches of writes to a document collection.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, Document, CollectionError, TierListCollection, TransactionalCollection, Transaction,};
+use futures::future::LocalBoxFuture;
+use std::{fmt, slice, iter::FromIterator,};
+
+/// A group of writes which are applied all-or-nothing.
+struct Group<'a, Coll,>
+  where Coll: TierListCollection, {
+  /// The identifiers of the documents in the group.
+  ids: Vec<DocumentId>,
+  /// Commits the group of writes.
+  commit: Box<dyn 'a + FnOnce() -> LocalBoxFuture<'a, Result<(), Coll::Error>>>,
+  /// Clones the error of a failed commit for each document in the group.
+  clone_error: fn(&Coll::Error,) -> Coll::Error,
+}
+
+/// A staged entry in a `WriteBatch`.
+enum Entry<'a, Coll,>
+  where Coll: TierListCollection, {
+  /// A document written on its own.
+  Document(Coll::Document),
+  /// A group of documents written all-or-nothing.
+  Group(Group<'a, Coll,>),
+}
+
+/// A batch of documents to write to a collection.
+/// 
+/// Entries are committed in the order they are pushed; consecutive documents are
+/// written using a single batch write.
+pub struct WriteBatch<'a, Coll,>
+  where Coll: TierListCollection, {
+  /// The collection to write the documents to.
+  collection: &'a Coll,
+  /// The staged entries in the order they were pushed.
+  entries: Vec<Entry<'a, Coll,>>,
+  /// The number of staged documents.
+  len: usize,
+}
+
+impl<'a, Coll,> WriteBatch<'a, Coll,>
+  where Coll: TierListCollection, {
+  /// Returns a new empty `WriteBatch`.
+  /// 
+  /// # Params
+  /// 
+  /// collection --- The collection to write the documents to.  
+  #[inline]
+  pub fn new(collection: &'a Coll,) -> Self {
+    Self { collection, entries: Vec::new(), len: 0, }
+  }
+  /// Returns the number of documents staged in the batch.
+  #[inline]
+  pub fn len(&self,) -> usize { self.len }
+  /// Returns `true` if no documents are staged in the batch.
+  #[inline]
+  pub fn is_empty(&self,) -> bool { self.len == 0 }
+  /// Stages a document to be written.
+  /// 
+  /// # Params
+  /// 
+  /// document --- The document to write to the collection.  
+  pub fn push(&mut self, document: Coll::Document,) -> &mut Self {
+    self.entries.push(Entry::Document(document,),);
+    self.len += 1;
+    self
+  }
+  /// Stages documents to be written.
+  /// 
+  /// # Params
+  /// 
+  /// documents --- The documents to write to the collection.  
+  pub fn push_all<I,>(&mut self, documents: I,) -> &mut Self
+    where I: IntoIterator<Item = Coll::Document>, {
+    for document in documents { self.push(document,); }
+
+    self
+  }
+  /// Writes the staged documents to the collection.
+  /// 
+  /// If a batch write fails outright the error is returned and the remaining entries
+  /// are not written; entries before it may already have been written.
+  pub async fn commit(self,) -> Result<BatchReport<Coll::Error>, Coll::Error> {
+    let Self { collection, entries, len, } = self;
+    let mut results = Vec::with_capacity(len,);
+    let mut documents = Vec::new();
+
+    for entry in entries {
+      match entry {
+        Entry::Document(document) => documents.push(document,),
+        Entry::Group(group) => {
+          //Write the documents staged before the group first.
+          write_all(collection, &mut documents, &mut results,).await?;
+
+          let Group { ids, commit, clone_error, } = group;
+
+          match commit().await {
+            Ok(()) => results.extend(ids.into_iter().map(|id,| (id, Ok(()),),),),
+            Err(e) => results.extend(ids.into_iter().map(|id,| (id, Err(clone_error(&e,),),),),),
+          }
+        },
+      }
+    }
+
+    write_all(collection, &mut documents, &mut results,).await?;
+
+    Ok(results.into_iter().collect())
+  }
+}
+
+impl<'a, Coll,> WriteBatch<'a, Coll,>
+  where Coll: TransactionalCollection,
+    Coll::Error: Clone,
+    Coll::Transaction: 'a,
+    <Coll::Transaction as Transaction>::Commit: 'a, {
+  /// Stages documents to be written all-or-nothing using a transaction.
+  /// 
+  /// If the group fails every document in the group reports the error.
+  /// 
+  /// # Params
+  /// 
+  /// documents --- The documents to write to the collection.  
+  pub fn push_group<I,>(&mut self, documents: I,) -> &mut Self
+    where I: IntoIterator<Item = Coll::Document>, {
+    let mut transaction = self.collection.begin();
+    let mut ids = Vec::new();
+
+    for document in documents {
+      ids.push(*document.get_id(),);
+      transaction.stage_write(&document,);
+    }
+
+    self.len += ids.len();
+    self.entries.push(Entry::Group(Group {
+      ids,
+      commit: Box::new(move || Box::pin(transaction.commit(),),),
+      clone_error: Clone::clone,
+    },),);
+    self
+  }
+}
+
+impl<'a, Coll,> fmt::Debug for WriteBatch<'a, Coll,>
+  where Coll: TierListCollection, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    fmt.debug_struct("WriteBatch",)
+    .field("len", &self.len,)
+    .finish()
+  }
+}
+
+/// Writes the documents as a batch and records the result of each write.
+async fn write_all<Coll,>(
+  collection: &Coll,
+  documents: &mut Vec<Coll::Document>,
+  results: &mut Vec<(DocumentId, Result<(), Coll::Error>,)>,
+) -> Result<(), Coll::Error>
+  where Coll: TierListCollection, {
+  if documents.is_empty() { return Ok(()) }
+
+  results.extend(collection.write_documents_reported(documents.drain(..,),).await?,);
+  Ok(())
+}
+
+/// The result of writing each document in a batch.
+#[derive(PartialEq, Eq, Clone, Debug,)]
+pub struct BatchReport<E,> {
+  /// The identifier and result of each write in the order they were staged.
+  results: Vec<(DocumentId, Result<(), E>,)>,
+}
+
+impl<E,> BatchReport<E,> {
+  /// Returns the number of documents written in the batch.
+  #[inline]
+  pub fn len(&self,) -> usize { self.results.len() }
+  /// Returns `true` if no documents were written in the batch.
+  #[inline]
+  pub fn is_empty(&self,) -> bool { self.results.is_empty() }
+  /// Gets the result of writing a document.
+  /// 
+  /// If the document was staged more than once the result of its last write is
+  /// returned.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the document.  
+  pub fn get(&self, id: &DocumentId,) -> Option<&Result<(), E>> {
+    self.results.iter().rev()
+    .find(|(doc, _,),| doc == id,)
+    .map(|(_, res,),| res,)
+  }
+  /// Iterates over the identifier and result of each write in the order they were staged.
+  #[inline]
+  pub fn iter(&self,) -> slice::Iter<'_, (DocumentId, Result<(), E>,)> { self.results.iter() }
+  /// Iterates over the identifier and error of each failed write.
+  pub fn failures(&self,) -> impl Iterator<Item = (&DocumentId, &E,)> {
+    self.results.iter()
+    .filter_map(|(id, res,),| res.as_ref().err().map(|e,| (id, e,),),)
+  }
+  /// Iterates over the identifiers of the documents which were written.
+  pub fn succeeded_ids(&self,) -> impl Iterator<Item = &DocumentId> {
+    self.results.iter()
+    .filter(|(_, res,),| res.is_ok(),)
+    .map(|(id, _,),| id,)
+  }
+  /// Returns `true` if every document was written.
+  #[inline]
+  pub fn is_complete_success(&self,) -> bool { self.results.iter().all(|(_, res,),| res.is_ok(),) }
+}
+
+impl<E,> BatchReport<E,>
+  where E: CollectionError, {
+  /// Iterates over the identifier and error of each failed write which can be retried.
+  pub fn retryable_failures(&self,) -> impl Iterator<Item = (&DocumentId, &E,)> {
+    self.failures().filter(|(_, e,),| e.is_retryable(),)
+  }
+}
+
+impl<E,> FromIterator<(DocumentId, Result<(), E>,)> for BatchReport<E,> {
+  #[inline]
+  fn from_iter<I,>(iter: I,) -> Self
+    where I: IntoIterator<Item = (DocumentId, Result<(), E>,)>, {
+    Self { results: iter.into_iter().collect(), }
+  }
+}
+
+impl<E,> IntoIterator for BatchReport<E,> {
+  type Item = (DocumentId, Result<(), E>,);
+  type IntoIter = std::vec::IntoIter<Self::Item>;
+
+  #[inline]
+  fn into_iter(self,) -> Self::IntoIter { self.results.into_iter() }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{
+    TierMeta,
+    mock::{ChaosCollection, ChaosError,},
+  };
+  use futures::executor::block_on;
+
+  fn tier(id: DocumentId,) -> TierMeta { TierMeta::new(id.into(), (None, id.into(), id.into(),), None, None,) }
+
+  #[test]
+  fn test_report() {
+    let coll = ChaosCollection::new();
+    let docs = (1..=5u8).map(|i,| tier(DocumentId::from_bytes([i; 20]),),).collect::<Vec<_>>();
+
+    coll.fail_writes(DocumentId::from_bytes([2; 20]),);
+    coll.fail_writes(DocumentId::from_bytes([4; 20]),);
+
+    let mut batch = coll.write_batch();
+    batch.push(docs[0],)
+      .push_all(docs[1..].iter().cloned(),);
+    assert_eq!(batch.len(), 5, "Error staged the wrong number of documents",);
+
+    let report = block_on(batch.commit(),)
+      .expect("Error committing batch");
+
+    assert!(!report.is_complete_success(), "Error failed writes were reported as succeeding",);
+    assert_eq!(report.len(), 5, "Error reporting the wrong number of writes",);
+    assert_eq!(
+      report.failures().collect::<Vec<_>>(),
+      vec![
+        (&DocumentId::from_bytes([2; 20]), &ChaosError::Injected(DocumentId::from_bytes([2; 20])),),
+        (&DocumentId::from_bytes([4; 20]), &ChaosError::Injected(DocumentId::from_bytes([4; 20])),),
+      ],
+      "Error reporting failed writes",
+    );
+    assert_eq!(report.get(&DocumentId::from_bytes([3; 20]),), Some(&Ok(())), "Error reporting a write",);
+    assert_eq!(report.get(&DocumentId::from_bytes([6; 20]),), None, "Error reporting an unstaged write",);
+    assert_eq!(coll.inner().len(), 3, "Error writing documents",);
+  }
+  #[test]
+  fn test_group_failure() {
+    let coll = ChaosCollection::new();
+    let docs = (1..=5u8).map(|i,| tier(DocumentId::from_bytes([i; 20]),),).collect::<Vec<_>>();
+
+    coll.fail_writes(DocumentId::from_bytes([3; 20]),);
+
+    let mut batch = coll.write_batch();
+    batch.push(docs[0],)
+      .push_group(docs[1..4].iter().cloned(),)
+      .push(docs[4],);
+
+    let report = block_on(batch.commit(),)
+      .expect("Error committing batch");
+
+    assert_eq!(
+      report.into_iter().collect::<Vec<_>>(),
+      vec![
+        (DocumentId::from_bytes([1; 20]), Ok(()),),
+        (DocumentId::from_bytes([2; 20]), Err(ChaosError::Injected(DocumentId::from_bytes([3; 20]))),),
+        (DocumentId::from_bytes([3; 20]), Err(ChaosError::Injected(DocumentId::from_bytes([3; 20]))),),
+        (DocumentId::from_bytes([4; 20]), Err(ChaosError::Injected(DocumentId::from_bytes([3; 20]))),),
+        (DocumentId::from_bytes([5; 20]), Ok(()),),
+      ],
+      "Error reporting the group",
+    );
+    assert_eq!(coll.inner().len(), 2, "Error part of the group was written",);
+  }
+  #[test]
+  fn test_group_success() {
+    let coll = ChaosCollection::new();
+    let docs = (1..=3u8).map(|i,| tier(DocumentId::from_bytes([i; 20]),),).collect::<Vec<_>>();
+
+    let mut batch = coll.write_batch();
+    batch.push_group(docs.iter().cloned(),);
+
+    let report = block_on(batch.commit(),)
+      .expect("Error committing batch");
+
+    assert!(report.is_complete_success(), "Error committing the group",);
+    assert_eq!(coll.inner().len(), 3, "Error writing the group",);
+  }
+}
@@ -0,0 +1,257 @@
+//! Defines a synchronous facade over a document collection.
+//! 
+//! Every operation drives the future of the wrapped collection to completion on the
+//! current thread using `futures::executor::block_on`.
+//! 
+//! # Async contexts
+//! 
+//! A `BlockingCollection` must only be used from synchronous code. Calling it from
+//! inside a task blocks the executor thread until the operation completes and, if the
+//! wrapped collection waits on anything driven by that same executor (a timer, a
+//! spawned task, a channel), the operation can never complete and the thread
+//! deadlocks. Calling it from inside another `futures` executor panics instead.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, TierListCollection, Cursor, CursorError, LinkedList,};
+use futures::executor::block_on;
+use std::{fmt, borrow::Borrow,};
+
+/// Wraps a `TierListCollection` so that it can be used without an executor.
+/// 
+/// See the module documentation for why a `BlockingCollection` must not be used from
+/// inside an async context.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug,)]
+pub struct BlockingCollection<Coll,> {
+  /// The wrapped collection.
+  inner: Coll,
+}
+
+impl<Coll,> BlockingCollection<Coll,> {
+  /// Wraps a collection.
+  /// 
+  /// # Params
+  /// 
+  /// inner --- The collection to wrap.  
+  #[inline]
+  pub const fn new(inner: Coll,) -> Self { Self { inner, } }
+  /// Gets the wrapped collection.
+  #[inline]
+  pub const fn get_inner(&self,) -> &Coll { &self.inner }
+  /// Unwraps the collection.
+  #[inline]
+  pub fn into_inner(self,) -> Coll { self.inner }
+}
+
+impl<Coll,> BlockingCollection<Coll,>
+  where Coll: TierListCollection, {
+  /// Gets a batch of documents from the collection.
+  /// 
+  /// # Params
+  /// 
+  /// ids --- The identifiers of the documents in the collection.  
+  pub fn get_documents<I, B,>(&self, ids: I,) -> Result<Vec<Result<Coll::Document, Coll::Error>>, Coll::Error>
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    block_on(self.inner.get_documents(ids,),)
+  }
+  /// Gets a document from the collection.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the document in the collection.  
+  #[inline]
+  pub fn get_document(&self, id: &DocumentId,) -> Result<Coll::Document, Coll::Error> {
+    block_on(self.inner.get_document(id,),)
+  }
+  /// Writes a batch of documents to the collection.
+  /// 
+  /// # Params
+  /// 
+  /// documents --- The documents to write to the collection.  
+  pub fn write_documents<I, T,>(&self, documents: I,) -> Result<Result<(), Vec<Result<(), Coll::Error>>>, Coll::Error>
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Coll::Document>, {
+    block_on(self.inner.write_documents(documents,),)
+  }
+  /// Writes a document to the collection.
+  /// 
+  /// # Params
+  /// 
+  /// document --- The document to write to the collection.  
+  #[inline]
+  pub fn write_document<T,>(&self, document: &T,) -> Result<(), Coll::Error>
+    where T: Borrow<Coll::Document>, {
+    block_on(self.inner.write_document(document,),)
+  }
+  /// Checks if a document is in the collection.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the document in the collection.  
+  #[inline]
+  pub fn exists(&self, id: &DocumentId,) -> Result<bool, Coll::Error> {
+    block_on(self.inner.exists(id,),)
+  }
+  /// Gets a cursor at an item in the collection.
+  /// 
+  /// The returned `BlockingCursor` owns a clone of the collection.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the document in the collection.  
+  pub fn get_cursor<T,>(&self, id: &DocumentId,) -> Result<BlockingCursor<T, Coll,>, Coll::Error>
+    where Coll: Clone,
+      Coll::Document: Into<T>, {
+    block_on(self.inner.ref_cursor(id,),)
+    .map(|cursor,| BlockingCursor::from(cursor.cloned_coll(),),)
+  }
+}
+
+impl<Coll,> From<Coll> for BlockingCollection<Coll,>
+  where Coll: TierListCollection, {
+  #[inline]
+  fn from(from: Coll,) -> Self { Self::new(from,) }
+}
+
+/// A synchronous view into a collection.
+/// 
+/// Mirrors the navigation of `Cursor` using `futures::executor::block_on`; see the
+/// module documentation for why it must not be used from inside an async context.
+#[derive(PartialEq, Eq, Clone, Copy,)]
+pub struct BlockingCursor<T, Coll,>
+  where Coll: TierListCollection, {
+  /// The wrapped `Cursor`.
+  cursor: Cursor<T, Coll,>,
+}
+
+impl<T, Coll,> BlockingCursor<T, Coll,>
+  where Coll: TierListCollection, {
+  /// Breaks the cursor into its component parts.
+  #[inline]
+  pub fn into_parts(self,) -> (Coll, T,) { self.cursor.into_parts() }
+  /// Unwraps the async `Cursor`.
+  #[inline]
+  pub fn into_cursor(self,) -> Cursor<T, Coll,> { self.cursor }
+  /// Gets the collection used by this `BlockingCursor`.
+  #[inline]
+  pub const fn get_collection(&self,) -> &Coll { self.cursor.get_collection() }
+  /// Gets the item at this cursor.
+  #[inline]
+  pub const fn get_item(&self,) -> &T { self.cursor.get_item() }
+}
+
+impl<T, Coll,> BlockingCursor<T, Coll,>
+  where T: LinkedList,
+    Coll: TierListCollection + Clone,
+    Coll::Document: Into<T>, {
+  /// Gets a `BlockingCursor` to the next node in the linked list.
+  pub fn get_next(&self,) -> Result<Option<Self>, CursorError<Coll::Error,>> {
+    block_on(self.cursor.get_next(),).map(|cursor,| cursor.map(Self::from,),)
+  }
+  /// Gets a `BlockingCursor` to the previous node in the linked list.
+  pub fn get_previous(&self,) -> Result<Option<Self>, CursorError<Coll::Error,>> {
+    block_on(self.cursor.get_previous(),).map(|cursor,| cursor.map(Self::from,),)
+  }
+  /// Moves this `BlockingCursor` to the next node in the linked list.
+  /// 
+  /// If there is no next node this `BlockingCursor` is returned unchanged as
+  /// `Err((self, None))`.
+  /// 
+  /// If there was an error getting the next node this `BlockingCursor` is returned
+  /// unchanged as `Err((self, Some(error)))`.
+  pub fn move_next(self,) -> Result<Self, (Self, Option<Coll::Error>,)> {
+    match self.cursor.move_next() {
+      Ok(fut) => block_on(fut,).map(Self::from,)
+        .map_err(|(cursor, e,),| (Self::from(cursor,), Some(e,),),),
+      Err(cursor) => Err((Self::from(cursor,), None,)),
+    }
+  }
+  /// Moves this `BlockingCursor` to the previous node in the linked list.
+  /// 
+  /// If there is no previous node this `BlockingCursor` is returned unchanged as
+  /// `Err((self, None))`.
+  /// 
+  /// If there was an error getting the previous node this `BlockingCursor` is returned
+  /// unchanged as `Err((self, Some(error)))`.
+  pub fn move_previous(self,) -> Result<Self, (Self, Option<Coll::Error>,)> {
+    match self.cursor.move_previous() {
+      Ok(fut) => block_on(fut,).map(Self::from,)
+        .map_err(|(cursor, e,),| (Self::from(cursor,), Some(e,),),),
+      Err(cursor) => Err((Self::from(cursor,), None,)),
+    }
+  }
+}
+
+impl<T, Coll,> fmt::Debug for BlockingCursor<T, Coll,>
+  where T: fmt::Debug,
+    Coll: TierListCollection, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    fmt.debug_struct("BlockingCursor",).field("cursor", &self.cursor,).finish()
+  }
+}
+
+impl<T, Coll,> From<Cursor<T, Coll,>> for BlockingCursor<T, Coll,>
+  where Coll: TierListCollection, {
+  #[inline]
+  fn from(cursor: Cursor<T, Coll,>,) -> Self { Self { cursor, } }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{MemoryCollection, TierMeta,};
+
+  #[test]
+  fn test_blocking_cursor() {
+    let coll = BlockingCollection::new(MemoryCollection::<TierMeta,>::new(),);
+    let id1 = DocumentId::from_bytes([1u8; 20]);
+    let id2 = DocumentId::from_bytes([2u8; 20]);
+    let id3 = DocumentId::from_bytes([3u8; 20]);
+    let id4 = DocumentId::from_bytes([4u8; 20]);
+    let doc1 = TierMeta::new(id1.into(), (None, id1.into(), id1.into(),), None, None,);
+    let doc2 = TierMeta::new(id2.into(), (None, id2.into(), id2.into(),), None, Some(id3.into()),);
+    let doc3 = TierMeta::new(id3.into(), (None, id3.into(), id3.into(),), Some(id2.into()), Some(id4.into()),);
+    let doc4 = TierMeta::new(id4.into(), (None, id4.into(), id4.into(),), Some(id3.into()), None,);
+
+    coll.write_document(&doc1,).expect("Error writing document");
+    coll.write_documents([&doc2, &doc3, &doc4,],)
+      .expect("Error writing documents")
+      .expect("Error writing documents");
+    assert!(coll.exists(&id2,).expect("Error checking document"), "Error document missing",);
+    assert_eq!(
+      coll.get_documents([id1, id4,],).expect("Error reading documents")
+        .into_iter().collect::<Result<Vec<_>, _>>().expect("Error reading documents"),
+      vec![doc1, doc4,],
+      "Error read the wrong documents",
+    );
+
+    let cursor = coll.get_cursor::<TierMeta,>(&id3,).expect("Error reading cursor");
+    assert_eq!(cursor.get_item(), &doc3, "Error Cursor at wrong document",);
+    assert_eq!(cursor.get_collection(), coll.get_inner(), "Error Cursor owns a different collection",);
+
+    let next_cursor = cursor.get_next().unwrap()
+      .expect("No next Cursor");
+    assert_eq!(next_cursor.get_item(), &doc4, "Error next Cursor at wrong document",);
+    assert!(
+      next_cursor.get_next().unwrap().is_none(),
+      "Error next Cursor is not the last document",
+    );
+
+    let other_cursor = next_cursor.move_previous()
+      .expect("Error moving next Cursor back");
+    assert_eq!(other_cursor, cursor, "Error other Cursor at wrong document",);
+
+    let prev_cursor = cursor.get_previous().unwrap()
+      .expect("No previous Cursor");
+    assert_eq!(prev_cursor.get_item(), &doc2, "Error previous Cursor at wrong document",);
+
+    let (prev_cursor, e,) = prev_cursor.move_previous()
+      .expect_err("Error previous Cursor moved before the first document");
+    assert!(e.is_none(), "Error moving previous Cursor failed",);
+    assert_eq!(prev_cursor.get_item(), &doc2, "Error previous Cursor changed",);
+
+    coll.get_document(&DocumentId::from_bytes([5u8; 20]),).expect_err("Error read a missing document");
+  }
+}
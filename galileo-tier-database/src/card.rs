@@ -1,15 +1,17 @@
 //! Defines a representation of a card making up a tier in a document collection.
 //! 
 //! Author --- daniel.bechaz@gmail.com  
-//! Last Moddified --- 2019-06-02
+//! Last Moddified --- 2026-10-14
 
-use crate::{DocumentId, Document, LinkedList,};
+use crate::{DocumentId, CardId, TierId, NilIdError, Document, LinkedList, LinkedListMut, EncodedLen,};
+use serde::{Serialize, Deserialize,};
+use std::cmp::Ordering;
 
 /// Defines an individual `Card`.
-#[derive(PartialEq, Eq, Clone, Debug,)]
+#[derive(PartialEq, Eq, Clone, Hash, Debug, Serialize, Deserialize,)]
 pub struct Card {
   /// The identifier of this `Card`.
-  pub id: DocumentId,
+  pub id: CardId,
   /// The display name of this `Card`.
   pub name: String,
   /// The description of this `Card`.
@@ -20,10 +22,84 @@ pub struct Card {
   pub down_votes: u64,
   /// The bias which drags this `Card` down in addition to down votes.
   pub bias: u64,
+  /// Whether this `Card` can be voted on.
+  #[serde(default,)]
+  pub status: CardStatus,
+  /// The promotions of this `Card` between tiers, oldest first.
+  #[serde(default,)]
+  pub history: Vec<TierTransition>,
   /// The Id of the previous `Card` in the current tier.
-  pub previous_card: Option<DocumentId>,
+  pub previous_card: Option<CardId>,
   /// The Id of the next `Card` in the current tier.
-  pub next_card: Option<DocumentId>,
+  pub next_card: Option<CardId>,
+}
+
+impl Card {
+  /// Gets the score of this `Card`.
+  /// 
+  /// # Params
+  /// 
+  /// kind --- How to score the `Card`.  
+  pub fn score(&self, kind: ScoreKind,) -> i128 {
+    match kind {
+      ScoreKind::Net => self.up_votes as i128 - self.down_votes as i128 - self.bias as i128,
+      ScoreKind::UpVotes => self.up_votes as i128,
+    }
+  }
+  /// Orders this `Card` before `other` if it has the higher score.
+  /// 
+  /// Equal scores are `Ordering::Equal` so that a stable sort keeps tied `Card`s in
+  /// their current order.
+  /// 
+  /// # Params
+  /// 
+  /// other --- The `Card` to compare to.  
+  /// kind --- How to score the `Card`s.  
+  #[inline]
+  pub fn cmp_score(&self, other: &Self, kind: ScoreKind,) -> Ordering {
+    other.score(kind,).cmp(&self.score(kind,),)
+  }
+  /// Checks that this `Card` is not identified by and does not link to the nil
+  /// identifier.
+  pub fn validate(&self,) -> Result<(), NilIdError> {
+    if self.id.is_nil() { return Err(NilIdError::Id) }
+    if self.previous_card.is_some_and(|id,| id.is_nil(),) { return Err(NilIdError::Previous) }
+    if self.next_card.is_some_and(|id,| id.is_nil(),) { return Err(NilIdError::Next) }
+
+    Ok(())
+  }
+}
+
+/// Whether a `Card` can be voted on.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Default, Debug, Serialize, Deserialize,)]
+pub enum CardStatus {
+  /// The `Card` can be voted on.
+  #[default]
+  Open,
+  /// The `Card` is kept in its tier but its votes are frozen.
+  Locked,
+  /// The `Card` is kept for its history and is no longer voted on.
+  Archived,
+}
+
+/// The promotion of a `Card` from one tier to another.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug, Serialize, Deserialize,)]
+pub struct TierTransition {
+  /// The tier the `Card` was moved out of.
+  pub from: TierId,
+  /// The tier the `Card` was moved into.
+  pub to: TierId,
+  /// The net score of the `Card` when it was moved.
+  pub score: i128,
+}
+
+/// The ways of scoring a `Card`.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug,)]
+pub enum ScoreKind {
+  /// The up votes less the down votes and the bias.
+  Net,
+  /// The up votes alone.
+  UpVotes,
 }
 
 impl Document for Card {
@@ -31,9 +107,29 @@ impl Document for Card {
   fn get_id(&self,) -> &DocumentId { &self.id }
 }
 
+impl EncodedLen for Card {
+  fn encoded_len(&self,) -> usize {
+    //The identifier, the votes, the bias, the status, the length of the history and
+    //the tags of the optional identifiers.
+    let fixed = 20 + 3 * 8 + 1 + 8 + 2;
+    let links = 20 * (self.previous_card.is_some() as usize + self.next_card.is_some() as usize);
+    //The identifiers of both tiers and the score of each transition.
+    let history = self.history.len() * (2 * 20 + 16);
+
+    fixed + links + history + self.name.len() + self.description.len()
+  }
+}
+
 impl LinkedList for Card {
   #[inline]
-  fn get_previous_id(&self,) -> Option<&DocumentId> { self.previous_card.as_ref() }
+  fn get_previous_id(&self,) -> Option<&DocumentId> { self.previous_card.as_deref() }
+  #[inline]
+  fn get_next_id(&self,) -> Option<&DocumentId> { self.next_card.as_deref() }
+}
+
+impl LinkedListMut for Card {
+  #[inline]
+  fn set_previous_id(&mut self, id: Option<DocumentId>,) { self.previous_card = id.map(CardId::from,) }
   #[inline]
-  fn get_next_id(&self,) -> Option<&DocumentId> { self.next_card.as_ref() }
+  fn set_next_id(&mut self, id: Option<DocumentId>,) { self.next_card = id.map(CardId::from,) }
 }
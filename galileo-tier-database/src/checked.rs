@@ -0,0 +1,292 @@
+//! Defines a document collection which refuses to overwrite a different document with
+//! the same identifier.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, Document, CollectionError, ErrorKind, TierListCollection, TierListCollectionScan,};
+use futures::{
+  Future, FutureExt, TryFutureExt, TryStreamExt,
+  future::{Map, MapErr, LocalBoxFuture,},
+  stream,
+};
+use std::{fmt, error::Error, hash::Hash, borrow::Borrow, collections::HashMap,};
+
+/// A write which would have replaced a stored document with different content.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub struct Collision<D,> {
+  /// The document stored in the collection, or written earlier in the same batch.
+  pub stored: D,
+  /// The document which was not written.
+  pub written: D,
+}
+
+/// The errors returned by a `CheckedWrites` collection.
+#[derive(PartialEq, Eq, Clone, Debug,)]
+pub enum CheckedError<E, D,> {
+  /// A different document is stored with the identifier of the written document.
+  Collision(Box<Collision<D,>>),
+  /// An error from the inner collection.
+  Inner(E),
+}
+
+impl<E, D,> fmt::Display for CheckedError<E, D,>
+  where E: fmt::Display,
+    D: Document, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      CheckedError::Collision(collision) => write!(fmt, "a different document with the id {:?} is already stored", collision.stored.get_id(),),
+      CheckedError::Inner(e) => e.fmt(fmt,),
+    }
+  }
+}
+
+impl<E, D,> Error for CheckedError<E, D,>
+  where E: Error,
+    D: Document + fmt::Debug, {}
+
+impl<E, D,> CollectionError for CheckedError<E, D,>
+  where E: CollectionError, {
+  #[inline]
+  fn kind(&self,) -> ErrorKind {
+    match self {
+      CheckedError::Collision(_) => ErrorKind::Collision,
+      CheckedError::Inner(e) => e.kind(),
+    }
+  }
+  #[inline]
+  fn is_retryable(&self,) -> bool {
+    match self {
+      CheckedError::Collision(_) => false,
+      CheckedError::Inner(e) => e.is_retryable(),
+    }
+  }
+}
+
+/// The result of a batch read through a `CheckedWrites` collection.
+type BatchResult<D, E,> = Result<Vec<Result<D, CheckedError<E, D,>>>, CheckedError<E, D,>>;
+
+/// Wraps the errors of a batch read.
+fn batch_error<D, E,>(res: Result<Vec<Result<D, E>>, E>,) -> BatchResult<D, E,> {
+  match res {
+    Ok(docs) => Ok(docs.into_iter().map(|doc,| doc.map_err(CheckedError::Inner,),).collect()),
+    Err(e) => Err(CheckedError::Inner(e,)),
+  }
+}
+
+/// Returns the document to write unless it collides with the stored document.
+/// 
+/// # Params
+/// 
+/// stored --- The result of reading the stored document.  
+/// written --- The document to write.  
+fn check<D, E,>(stored: Result<D, E>, written: D,) -> Result<D, CheckedError<E, D,>>
+  where D: Document + Hash,
+    E: CollectionError, {
+  match stored {
+    Ok(stored) if stored.content_fingerprint() != written.content_fingerprint() => {
+      Err(CheckedError::Collision(Box::new(Collision { stored, written, },),))
+    },
+    Ok(_) => Ok(written),
+    Err(e) if e.is_not_found() => Ok(written),
+    Err(e) => Err(CheckedError::Inner(e,)),
+  }
+}
+
+/// A `TierListCollection` which reads every document before it is written and refuses
+/// to replace a stored document whose `content_fingerprint` differs.
+/// 
+/// Content derived identifiers make a collision, or a reimport of a name with different
+/// content, overwrite an unrelated document; this returns a `CheckedError::Collision`
+/// instead. Rewriting identical content is written as normal and a batch write checks
+/// each document against those before it in the batch as well. The read and the write
+/// are not atomic so a concurrent writer can still be overwritten.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub struct CheckedWrites<Coll,> {
+  /// The collection storing the documents.
+  inner: Coll,
+}
+
+impl<Coll,> CheckedWrites<Coll,> {
+  /// Returns a new `CheckedWrites` collection.
+  /// 
+  /// # Params
+  /// 
+  /// inner --- The collection storing the documents.  
+  #[inline]
+  pub const fn new(inner: Coll,) -> Self { Self { inner, } }
+  /// Gets the collection storing the documents.
+  #[inline]
+  pub const fn get_inner(&self,) -> &Coll { &self.inner }
+}
+
+impl<Coll,> TierListCollection for CheckedWrites<Coll,>
+  where Coll: 'static + TierListCollection + Clone,
+    Coll::Document: 'static + Clone + Hash, {
+  type Document = Coll::Document;
+  type Error = CheckedError<Coll::Error, Coll::Document,>;
+  type GetBatchDocuments = Map<Coll::GetBatchDocuments, fn(Result<Vec<Result<Self::Document, Coll::Error>>, Coll::Error>,) -> BatchResult<Self::Document, Coll::Error,>>;
+  type GetDocument = MapErr<Coll::GetDocument, fn(Coll::Error,) -> Self::Error>;
+  type WriteBatchDocuments = LocalBoxFuture<'static, Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>;
+  type WriteDocument = LocalBoxFuture<'static, Result<(), Self::Error>>;
+
+  #[inline]
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    self.inner.get_documents(ids,).map(batch_error as fn(_,) -> _,)
+  }
+  #[inline]
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    self.inner.get_document(id,).map_err(CheckedError::Inner as fn(_,) -> _,)
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let inner = self.inner.clone();
+    let documents = documents.into_iter().map(|doc,| doc.borrow().clone(),).collect::<Vec<_>>();
+
+    Box::pin(async move {
+      let stored = inner.get_documents(documents.iter().map(Document::get_id,),).await
+        .map_err(CheckedError::Inner,)?;
+      let mut accepted = HashMap::new();
+      let checked = documents.into_iter().zip(stored,)
+        .map(|(written, stored,),| {
+          //A document earlier in the batch is checked as if it were stored.
+          let stored = match accepted.get(written.get_id(),) {
+            Some(earlier) => Ok(Clone::clone(earlier,)),
+            None => stored,
+          };
+          let written = check(stored, written,)?;
+
+          accepted.entry(*written.get_id(),).or_insert_with(|| written.clone(),);
+          Ok(written)
+        },)
+        .collect::<Vec<_>>();
+      let res = inner.write_documents(checked.iter().filter_map(|res,| res.as_ref().ok(),),).await
+        .map_err(CheckedError::Inner,)?;
+
+      if res.is_ok() && checked.iter().all(Result::is_ok,) { return Ok(Ok(())) }
+
+      //The results of the writes are in the order of the documents which were written.
+      let mut written = res.err().map(Vec::into_iter,);
+      let results = checked.into_iter()
+        .map(|res,| match res {
+          Ok(_) => written.as_mut().and_then(Iterator::next,).unwrap_or(Ok(()),).map_err(CheckedError::Inner,),
+          Err(e) => Err(e),
+        },)
+        .collect();
+
+      Ok(Err(results))
+    },)
+  }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    let inner = self.inner.clone();
+    let document = document.borrow().clone();
+
+    Box::pin(async move {
+      let stored = inner.get_document(document.get_id(),).await;
+      let document = check(stored, document,)?;
+
+      inner.write_document(&document,).await.map_err(CheckedError::Inner,)
+    },)
+  }
+  #[inline]
+  fn exists(&self, id: &DocumentId,) -> impl Future<Output = Result<bool, Self::Error>> {
+    self.inner.exists(id,).map_err(CheckedError::Inner,)
+  }
+}
+
+impl<Coll,> TierListCollectionScan for CheckedWrites<Coll,>
+  where Coll: 'static + TierListCollectionScan + Clone,
+    Coll::Document: 'static + Clone + Hash, {
+  type Scan = stream::MapErr<Coll::Scan, fn(Coll::Error,) -> Self::Error>;
+
+  #[inline]
+  fn scan(&self,) -> Self::Scan {
+    self.inner.scan().map_err(CheckedError::Inner as fn(_,) -> _,)
+  }
+  #[inline]
+  fn count(&self,) -> impl Future<Output = Result<u64, Self::Error>> {
+    self.inner.count().map_err(CheckedError::Inner,)
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{Card, CardStatus, TierDocument, MemoryCollection, MemoryError, RecordingCollection, Op,};
+  use futures::executor::block_on;
+
+  fn card(id: u8, name: &str,) -> TierDocument {
+    TierDocument::Card(Card {
+      id: DocumentId::from_bytes([id; 20]).into(),
+      name: name.to_owned(),
+      description: String::new(),
+      up_votes: 0,
+      down_votes: 0,
+      bias: 0,
+      status: CardStatus::Open,
+      history: Vec::new(),
+      previous_card: None,
+      next_card: None,
+    },)
+  }
+
+  #[test]
+  fn test_checked_writes() {
+    let inner = RecordingCollection::new(MemoryCollection::new(),);
+    let coll = inner.clone().checked_writes();
+    let gold = card(1, "gold",);
+
+    block_on(async {
+      assert_eq!(coll.write_document(&gold,).await, Ok(()), "Error inserting a card",);
+      assert_eq!(inner.get_document(gold.get_id(),).await, Ok(gold.clone()), "Error writing a card",);
+      //A rewrite of identical content is written as normal.
+      inner.take_log();
+      assert_eq!(coll.write_document(&gold,).await, Ok(()), "Error reinserting a card",);
+      crate::assert_ops!(inner, [Op::Get(*gold.get_id(),), Op::Write(*gold.get_id(),),], "Error rewriting a card",);
+
+      let silver = card(1, "silver",);
+      assert_eq!(
+        coll.write_document(&silver,).await,
+        Err(CheckedError::Collision(Box::new(Collision { stored: gold.clone(), written: silver.clone(), },),)),
+        "Error detecting a collision",
+      );
+      assert_eq!(coll.write_document(&silver,).await.map_err(|e,| e.kind(),), Err(ErrorKind::Collision), "Error classifying a collision",);
+      assert_eq!(inner.get_document(gold.get_id(),).await, Ok(gold.clone()), "Error overwriting on a collision",);
+
+      let bronze = card(2, "bronze",);
+      assert_eq!(
+        coll.write_documents([&gold, &silver, &bronze,],).await,
+        Ok(Err(vec![
+          Ok(()),
+          Err(CheckedError::Collision(Box::new(Collision { stored: gold.clone(), written: silver.clone(), },),)),
+          Ok(()),
+        ])),
+        "Error detecting a collision in a batch",
+      );
+      assert_eq!(inner.get_document(bronze.get_id(),).await, Ok(bronze.clone()), "Error writing the rest of a batch",);
+      assert_eq!(coll.write_documents([&gold, &bronze,],).await, Ok(Ok(())), "Error rewriting a batch",);
+
+      //Documents sharing an id within a batch collide with each other.
+      let (tin, lead,) = (card(3, "tin",), card(3, "lead",),);
+      assert_eq!(
+        coll.write_documents([&tin, &tin, &lead,],).await,
+        Ok(Err(vec![
+          Ok(()),
+          Ok(()),
+          Err(CheckedError::Collision(Box::new(Collision { stored: tin.clone(), written: lead.clone(), },),)),
+        ])),
+        "Error detecting a collision within a batch",
+      );
+      assert_eq!(inner.get_document(tin.get_id(),).await, Ok(tin.clone()), "Error overwriting within a batch",);
+      assert_eq!(
+        coll.get_document(&DocumentId::from_bytes([9; 20]),).await,
+        Err(CheckedError::Inner(MemoryError::NotFound(DocumentId::from_bytes([9; 20]),),)),
+        "Error reading a missing card",
+      );
+    },);
+  }
+}
@@ -0,0 +1,333 @@
+//! Defines a wrapper which compresses the bodies of the documents in a collection.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, Envelope, CollectionError, ErrorKind, TierListCollection,};
+use futures::{
+  Future, FutureExt, TryFutureExt,
+  future::{Map, MapErr,},
+};
+use flate2::{Compression, read::DeflateDecoder, write::DeflateEncoder,};
+use std::{
+  fmt, io,
+  error::Error,
+  io::{Read, Write,},
+  cell::Cell,
+  borrow::Borrow,
+  rc::Rc,
+};
+
+/// The bytes at the start of every compressed body.
+/// 
+/// Bodies without them were written uncompressed and are read back unchanged.
+pub const COMPRESSED_MAGIC: [u8; 4] = *b"\0GTZ";
+
+/// A compression algorithm for the bodies of documents.
+pub trait Codec {
+  /// Compresses a body.
+  /// 
+  /// # Params
+  /// 
+  /// body --- The bytes to compress.  
+  fn compress(&self, body: &[u8],) -> Vec<u8>;
+  /// Decompresses a body.
+  /// 
+  /// # Params
+  /// 
+  /// body --- The bytes to decompress.  
+  fn decompress(&self, body: &[u8],) -> io::Result<Vec<u8>>;
+}
+
+/// The deflate compression algorithm.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub struct Deflate {
+  /// The compression level from `0` to `9`.
+  pub level: u32,
+}
+
+impl Default for Deflate {
+  #[inline]
+  fn default() -> Self { Self { level: 6, } }
+}
+
+impl Codec for Deflate {
+  fn compress(&self, body: &[u8],) -> Vec<u8> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::new(self.level,),);
+
+    //Writing to a `Vec` cannot fail.
+    encoder.write_all(body,).expect("Error compressing body");
+    encoder.finish().expect("Error compressing body")
+  }
+  fn decompress(&self, body: &[u8],) -> io::Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+
+    DeflateDecoder::new(body,).read_to_end(&mut decompressed,)?;
+    Ok(decompressed)
+  }
+}
+
+/// The errors returned by a `CompressedCollection`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum CompressedError<E,> {
+  /// The stored body of the document could not be decompressed.
+  Corrupt(DocumentId),
+  /// An error from the inner collection.
+  Inner(E),
+}
+
+impl<E,> fmt::Display for CompressedError<E,>
+  where E: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      CompressedError::Corrupt(id) => write!(fmt, "the document with the id {:?} could not be decompressed", id,),
+      CompressedError::Inner(e) => e.fmt(fmt,),
+    }
+  }
+}
+
+impl<E,> Error for CompressedError<E,>
+  where E: Error, {}
+
+impl<E,> CollectionError for CompressedError<E,>
+  where E: CollectionError, {
+  #[inline]
+  fn kind(&self,) -> ErrorKind {
+    match self {
+      CompressedError::Corrupt(_) => ErrorKind::Corrupt,
+      CompressedError::Inner(e) => e.kind(),
+    }
+  }
+  #[inline]
+  fn is_retryable(&self,) -> bool {
+    match self {
+      CompressedError::Corrupt(_) => false,
+      CompressedError::Inner(e) => e.is_retryable(),
+    }
+  }
+}
+
+/// The bytes written through a `CompressedCollection`.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug,)]
+struct Stats {
+  /// The length of the bodies before compression.
+  original: u64,
+  /// The length of the bodies after compression.
+  compressed: u64,
+}
+
+/// A `TierListCollection` of `Envelope`s which compresses the body of each document when
+/// it is written and decompresses it when it is read.
+pub struct CompressedCollection<Coll, Cd = Deflate,> {
+  /// The collection storing the compressed documents.
+  inner: Coll,
+  /// The compression algorithm.
+  codec: Rc<Cd>,
+  /// The bytes written through the collection.
+  stats: Rc<Cell<Stats>>,
+}
+
+impl<Coll,> CompressedCollection<Coll,> {
+  /// Returns a new `CompressedCollection` using deflate compression.
+  /// 
+  /// # Params
+  /// 
+  /// inner --- The collection to store the compressed documents in.  
+  #[inline]
+  pub fn new(inner: Coll,) -> Self { Self::with_codec(inner, Deflate::default(),) }
+}
+
+impl<Coll, Cd,> CompressedCollection<Coll, Cd,> {
+  /// Returns a new `CompressedCollection`.
+  /// 
+  /// # Params
+  /// 
+  /// inner --- The collection to store the compressed documents in.  
+  /// codec --- The compression algorithm.  
+  #[inline]
+  pub fn with_codec(inner: Coll, codec: Cd,) -> Self {
+    Self { inner, codec: Rc::new(codec,), stats: Rc::default(), }
+  }
+  /// Gets the collection storing the compressed documents.
+  #[inline]
+  pub const fn get_inner(&self,) -> &Coll { &self.inner }
+  /// Returns the length of the bodies written through this collection before
+  /// compression divided by their length after compression, or `None` if nothing has
+  /// been written.
+  pub fn compression_ratio(&self,) -> Option<f64> {
+    let stats = self.stats.get();
+
+    if stats.compressed == 0 { None }
+    else { Some(stats.original as f64 / stats.compressed as f64) }
+  }
+}
+
+impl<Coll, Cd,> CompressedCollection<Coll, Cd,>
+  where Cd: Codec, {
+  /// Compresses a document and records its size.
+  fn compress(&self, document: &Envelope,) -> Envelope {
+    let mut body = COMPRESSED_MAGIC.to_vec();
+    body.extend(self.codec.compress(&document.body,),);
+
+    let mut stats = self.stats.get();
+    stats.original += document.body.len() as u64;
+    stats.compressed += body.len() as u64;
+    self.stats.set(stats,);
+
+    Envelope::new(document.id, body,)
+  }
+}
+
+impl<Coll, Cd,> Clone for CompressedCollection<Coll, Cd,>
+  where Coll: Clone, {
+  #[inline]
+  fn clone(&self,) -> Self {
+    Self { inner: self.inner.clone(), codec: self.codec.clone(), stats: self.stats.clone(), }
+  }
+}
+
+impl<Coll, Cd,> fmt::Debug for CompressedCollection<Coll, Cd,>
+  where Coll: fmt::Debug, Cd: fmt::Debug, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    fmt.debug_struct("CompressedCollection",)
+    .field("inner", &self.inner,)
+    .field("codec", &self.codec,)
+    .finish()
+  }
+}
+
+/// Decompresses a document read from the inner collection.
+fn decompress<Cd, E,>(codec: &Cd, document: Envelope,) -> Result<Envelope, CompressedError<E,>>
+  where Cd: Codec, {
+  match document.body.strip_prefix(&COMPRESSED_MAGIC[..],) {
+    Some(body) => codec.decompress(body,)
+      .map(|body,| Envelope::new(document.id, body,),)
+      .map_err(|_,| CompressedError::Corrupt(document.id,),),
+    //The document was written uncompressed.
+    None => Ok(document),
+  }
+}
+
+/// The result of a batch read through a `CompressedCollection`.
+type BatchResult<E,> = Result<Vec<Result<Envelope, CompressedError<E,>>>, CompressedError<E,>>;
+/// The result of a batch write through a `CompressedCollection`.
+type BatchWriteResult<E,> = Result<Result<(), Vec<Result<(), CompressedError<E,>>>>, CompressedError<E,>>;
+
+/// Wraps the errors of a batch write.
+fn batch_write_error<E,>(res: Result<Result<(), Vec<Result<(), E>>>, E>,) -> BatchWriteResult<E,> {
+  match res {
+    Ok(Ok(())) => Ok(Ok(())),
+    Ok(Err(results)) => Ok(Err(results.into_iter().map(|res,| res.map_err(CompressedError::Inner,),).collect())),
+    Err(e) => Err(CompressedError::Inner(e,)),
+  }
+}
+
+impl<Coll, Cd,> TierListCollection for CompressedCollection<Coll, Cd,>
+  where Coll: TierListCollection<Document = Envelope>,
+    Cd: 'static + Codec, {
+  type Document = Envelope;
+  type Error = CompressedError<Coll::Error,>;
+  type GetBatchDocuments = Map<Coll::GetBatchDocuments, Box<dyn FnOnce(Result<Vec<Result<Envelope, Coll::Error>>, Coll::Error>,) -> BatchResult<Coll::Error,>>>;
+  type GetDocument = Map<Coll::GetDocument, Box<dyn FnOnce(Result<Envelope, Coll::Error>,) -> Result<Envelope, Self::Error>>>;
+  type WriteBatchDocuments = Map<Coll::WriteBatchDocuments, fn(Result<Result<(), Vec<Result<(), Coll::Error>>>, Coll::Error>,) -> BatchWriteResult<Coll::Error,>>;
+  type WriteDocument = MapErr<Coll::WriteDocument, fn(Coll::Error,) -> Self::Error>;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    let codec = self.codec.clone();
+
+    self.inner.get_documents(ids,).map(Box::new(move |res,| match res {
+      Ok(docs) => Ok(docs.into_iter()
+        .map(|doc,| decompress(&*codec, doc.map_err(CompressedError::Inner,)?,),)
+        .collect()),
+      Err(e) => Err(CompressedError::Inner(e,)),
+    },),)
+  }
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    let codec = self.codec.clone();
+
+    self.inner.get_document(id,).map(Box::new(move |res,| decompress(&*codec, res.map_err(CompressedError::Inner,)?,),),)
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let documents = documents.into_iter().map(|doc,| self.compress(doc.borrow(),),).collect::<Vec<_>>();
+
+    self.inner.write_documents(&documents,).map(batch_write_error as fn(_,) -> _,)
+  }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    self.inner.write_document(&self.compress(document.borrow(),),).map_err(CompressedError::Inner as fn(_,) -> _,)
+  }
+  #[inline]
+  fn exists(&self, id: &DocumentId,) -> impl Future<Output = Result<bool, Self::Error>> {
+    self.inner.exists(id,).map_err(CompressedError::Inner,)
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::MemoryCollection;
+  use futures::executor::block_on;
+
+  #[test]
+  fn test_round_trip() {
+    let inner = MemoryCollection::<Envelope,>::new();
+    let coll = CompressedCollection::new(inner.clone(),);
+    let docs = (0..3u8).map(|i,| Envelope::new(DocumentId::from_bytes([i; 20]), "a long card description ".repeat(20 * (i as usize + 1),).into_bytes(),),)
+      .collect::<Vec<_>>();
+
+    assert_eq!(coll.compression_ratio(), None, "Error reporting empty ratio",);
+    block_on(async {
+      coll.write_document(&docs[0],).await
+        .expect("Error writing document");
+      coll.write_documents(&docs[1..],).await
+        .expect("Error writing documents")
+        .expect("Error writing documents");
+
+      let stored = inner.get_document(&docs[2].id,).await
+        .expect("Error reading stored document");
+      assert!(stored.body.starts_with(&COMPRESSED_MAGIC,), "Error marking compressed body",);
+      assert!(stored.body.len() < docs[2].body.len(), "Error compressing body",);
+
+      assert_eq!(coll.get_document(&docs[0].id,).await, Ok(docs[0].clone()), "Error reading document",);
+      assert_eq!(
+        coll.get_documents(docs.iter().map(|doc,| &doc.id,),).await,
+        Ok(docs.iter().cloned().map(Ok,).collect()),
+        "Error reading documents",
+      );
+    },);
+    assert!(coll.compression_ratio().is_some_and(|ratio,| ratio > 1.0,), "Error reporting ratio",);
+  }
+  #[test]
+  fn test_legacy() {
+    let inner = MemoryCollection::<Envelope,>::new();
+    let coll = CompressedCollection::new(inner.clone(),);
+    let legacy = Envelope::new(DocumentId::from_bytes([1; 20]), br#"{"name":"Card"}"#.to_vec(),);
+
+    block_on(async {
+      inner.write_document(&legacy,).await
+        .expect("Error writing legacy document");
+
+      assert_eq!(coll.get_document(&legacy.id,).await, Ok(legacy), "Error reading legacy document",);
+    },);
+  }
+  #[test]
+  fn test_corrupt() {
+    let inner = MemoryCollection::<Envelope,>::new();
+    let coll = CompressedCollection::new(inner.clone(),);
+    let mut body = COMPRESSED_MAGIC.to_vec();
+    body.extend_from_slice(&[0xff; 16],);
+
+    block_on(async {
+      inner.write_document(&Envelope::new(DocumentId::from_bytes([1; 20]), body,),).await
+        .expect("Error writing corrupt document");
+
+      let res = coll.get_document(&DocumentId::from_bytes([1; 20]),).await;
+      assert_eq!(res, Err(CompressedError::Corrupt(DocumentId::from_bytes([1; 20]),)), "Error reading corrupt document",);
+      assert_eq!(res.map_err(|e,| e.kind(),), Err(ErrorKind::Corrupt), "Error classifying corrupt document",);
+    },);
+  }
+}
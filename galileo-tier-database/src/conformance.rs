@@ -0,0 +1,60 @@
+//! Defines checks that an implementation of `TierListCollection` keeps the contract of
+//! the trait.
+//! 
+//! The checks are intended to be called from the tests of a collection implementation
+//! and panic if the collection breaks the contract.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, Document, CollectionError, TierListCollection,};
+use std::fmt::Debug;
+
+/// Checks that `get_documents` returns one result for each requested id in the order
+/// they were requested, including for duplicated and missing ids.
+/// 
+/// # Params
+/// 
+/// collection --- The collection to check; the documents are written to it.  
+/// documents --- At least two documents with distinct identifiers.  
+/// missing --- The identifier of a document which is not in the collection.  
+/// 
+/// # Panics
+/// 
+/// Panics if the collection breaks the contract or returns an unexpected error.
+pub async fn check_get_documents<Coll,>(collection: &Coll, documents: &[Coll::Document], missing: &DocumentId,)
+  where Coll: TierListCollection,
+    Coll::Document: PartialEq + Debug,
+    Coll::Error: Debug, {
+  assert!(documents.len() >= 2, "`check_get_documents` requires at least two documents",);
+
+  for document in documents {
+    collection.write_document(document,).await
+      .expect("Error writing document");
+  }
+
+  //Request the documents in reverse with a duplicate and a missing id.
+  let mut ids = documents.iter().rev()
+    .map(Document::get_id,)
+    .collect::<Vec<_>>();
+  ids.insert(1, documents[0].get_id(),);
+  ids.push(missing,);
+  ids.push(documents[0].get_id(),);
+
+  let results = collection.get_documents(ids.iter().copied(),).await
+    .expect("Error getting documents");
+  assert_eq!(results.len(), ids.len(), "Error `get_documents` returned the wrong number of results",);
+
+  for (position, (id, result,),) in ids.iter().zip(results,).enumerate() {
+    match result {
+      Ok(document) => {
+        assert_eq!(document.get_id(), *id, "Error `get_documents` returned result {} out of order", position,);
+        assert!(documents.contains(&document,), "Error `get_documents` returned the wrong document",);
+      },
+      Err(e) => assert!(
+        *id == missing && e.is_not_found(),
+        "Error `get_documents` failed result {}: {:?}", position, e,
+      ),
+    }
+  }
+}
@@ -0,0 +1,619 @@
+//! Defines a document collection stored in a CouchDB database.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, Document, CollectionError, ErrorKind, TierListCollection, RevisionedCollection,};
+use futures::{Future, future::BoxFuture,};
+use reqwest::{Client, Method, RequestBuilder, Response, StatusCode,};
+use serde::{Serialize, Deserialize, de::DeserializeOwned,};
+use serde_json::Value;
+use std::{
+  fmt,
+  error::Error,
+  collections::{HashMap, hash_map::Entry,},
+  borrow::Borrow,
+  marker::PhantomData,
+  sync::Arc,
+};
+
+/// The errors returned by a `CouchCollection`.
+#[derive(PartialEq, Eq, Clone, Debug,)]
+pub enum CouchError {
+  /// The document does not exist.
+  NotFound(DocumentId),
+  /// The document did not have the expected revision.
+  Conflict(DocumentId),
+  /// The stored document could not be decoded.
+  Corrupt(DocumentId),
+  /// A request to CouchDB failed.
+  Request(ErrorKind, String),
+}
+
+impl fmt::Display for CouchError {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      CouchError::NotFound(id) => write!(fmt, "could not find a document with the id {:?}", id,),
+      CouchError::Conflict(id) => write!(fmt, "the document with the id {:?} has changed", id,),
+      CouchError::Corrupt(id) => write!(fmt, "the document with the id {:?} could not be decoded", id,),
+      CouchError::Request(_, message) => write!(fmt, "the request to CouchDB failed: {}", message,),
+    }
+  }
+}
+
+impl Error for CouchError {}
+
+impl CollectionError for CouchError {
+  #[inline]
+  fn kind(&self,) -> ErrorKind {
+    match self {
+      CouchError::NotFound(_) => ErrorKind::NotFound,
+      CouchError::Conflict(_) => ErrorKind::Conflict,
+      CouchError::Corrupt(_) => ErrorKind::Corrupt,
+      CouchError::Request(kind, _) => *kind,
+    }
+  }
+}
+
+/// Classifies the status of a failed CouchDB request.
+fn status_kind(status: StatusCode,) -> ErrorKind {
+  match status {
+    StatusCode::NOT_FOUND => ErrorKind::NotFound,
+    StatusCode::CONFLICT | StatusCode::PRECONDITION_FAILED => ErrorKind::Conflict,
+    StatusCode::REQUEST_TIMEOUT => ErrorKind::Timeout,
+    StatusCode::TOO_MANY_REQUESTS => ErrorKind::Transient,
+    _ if status.is_server_error() => ErrorKind::Transient,
+    _ => ErrorKind::Permanent,
+  }
+}
+
+/// Classifies the error of a document in a `_bulk_docs` response.
+fn bulk_error(id: DocumentId, error: &str, reason: &str,) -> CouchError {
+  match error {
+    "conflict" => CouchError::Conflict(id,),
+    "not_found" => CouchError::NotFound(id,),
+    _ => CouchError::Request(ErrorKind::Permanent, format!("{}: {}", error, reason,),),
+  }
+}
+
+/// Converts the error from a request which could not be made.
+fn request_error(error: reqwest::Error,) -> CouchError {
+  let kind = if error.is_timeout() { ErrorKind::Timeout }
+    else if error.is_connect() || error.is_request() { ErrorKind::Transient }
+    else if error.is_decode() { ErrorKind::Corrupt }
+    else { ErrorKind::Permanent };
+
+  CouchError::Request(kind, error.to_string(),)
+}
+
+/// Formats a `DocumentId` as the hex identifier of a CouchDB document.
+#[inline]
+fn hex(id: &DocumentId,) -> String { format!("{:x}", id,) }
+
+/// Parses the hex identifier of a CouchDB document.
+#[inline]
+fn parse_hex(hex: &str,) -> Option<DocumentId> { hex.parse().ok() }
+
+/// The body of a failed response.
+#[derive(Deserialize,)]
+struct ErrorResponse {
+  /// The name of the error.
+  #[serde(default,)]
+  error: String,
+  /// A description of the error.
+  #[serde(default,)]
+  reason: String,
+}
+
+/// A document as it is stored in CouchDB.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug,)]
+struct CouchDocument<D,> {
+  /// The hex identifier of the document.
+  #[serde(rename = "_id",)]
+  id: String,
+  /// The revision of the document being replaced; `None` when creating the document.
+  #[serde(rename = "_rev", default, skip_serializing_if = "Option::is_none",)]
+  rev: Option<String>,
+  /// The document.
+  body: D,
+}
+
+impl<D,> CouchDocument<D,>
+  where D: DeserializeOwned, {
+  /// Decodes a document read from CouchDB.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the document.  
+  /// document --- The JSON of the document.  
+  fn decode(id: DocumentId, document: Value,) -> Result<(D, String,), CouchError> {
+    match serde_json::from_value::<Self>(document,) {
+      Ok(CouchDocument { rev: Some(rev), body, .. }) => Ok((body, rev,)),
+      _ => Err(CouchError::Corrupt(id,)),
+    }
+  }
+}
+
+/// The body of an `_all_docs` request.
+#[derive(Serialize,)]
+struct Keys<'a,> {
+  /// The hex identifiers of the documents.
+  keys: &'a [String],
+}
+
+/// The response to an `_all_docs` request.
+#[derive(Deserialize,)]
+struct AllDocs {
+  /// A row for each requested key.
+  rows: Vec<AllDocsRow>,
+}
+
+/// A row of an `_all_docs` response.
+#[derive(Deserialize,)]
+struct AllDocsRow {
+  /// The requested key.
+  key: String,
+  /// The revision of the document if it exists or was deleted.
+  #[serde(default,)]
+  value: Option<RowValue>,
+  /// The document when `include_docs` was set and the document exists.
+  #[serde(default,)]
+  doc: Option<Value>,
+}
+
+/// The value of an `_all_docs` row.
+#[derive(Deserialize,)]
+struct RowValue {
+  /// The current revision of the document.
+  rev: String,
+  /// Whether the document was deleted.
+  #[serde(default,)]
+  deleted: bool,
+}
+
+/// The body of a `_bulk_docs` request.
+#[derive(Serialize,)]
+struct BulkDocs<'a, D,> {
+  /// The documents to write.
+  docs: &'a [CouchDocument<&'a D,>],
+}
+
+/// The result of writing a document.
+#[derive(Deserialize,)]
+struct WriteResult {
+  /// The new revision of the document if it was written.
+  #[serde(default,)]
+  rev: Option<String>,
+  /// The name of the error if it was not written.
+  #[serde(default,)]
+  error: Option<String>,
+  /// A description of the error.
+  #[serde(default,)]
+  reason: String,
+}
+
+/// A `TierListCollection` whose documents are stored in a CouchDB database.
+/// 
+/// Each document is stored under the hex encoded identifier of the document with the
+/// document in the field `body`. CouchDB revisions are used as the revisions of the
+/// documents so every write must name the revision it replaces; plain writes look up
+/// the current revision first and fail with `CouchError::Conflict` if another writer
+/// changes the document in between.
+/// 
+/// Batch reads are made with a single `_all_docs` request and batch writes with a single
+/// `_bulk_docs` request, which is not atomic: each document succeeds or fails on its own
+/// and the failures are reported in the batch results.
+pub struct CouchCollection<D,> {
+  /// The client to send requests with.
+  client: Client,
+  /// The URL of the database.
+  database: Arc<str>,
+  /// The user name and password to authenticate with.
+  credentials: Option<Arc<(String, String,)>>,
+  /// The type of the documents stored.
+  document: PhantomData<fn() -> D>,
+}
+
+impl<D,> CouchCollection<D,> {
+  /// Returns a new `CouchCollection`.
+  /// 
+  /// # Params
+  /// 
+  /// client --- The client to send requests with.  
+  /// server --- The URL of the CouchDB server, such as `http://localhost:5984`.  
+  /// database --- The name of the database storing the documents.  
+  /// credentials --- The user name and password to authenticate with.  
+  pub fn new(client: Client, server: &str, database: &str, credentials: Option<(&str, &str,)>,) -> Self {
+    Self {
+      client,
+      database: format!("{}/{}", server.trim_end_matches('/',), database.replace('/', "%2F",),).into(),
+      credentials: credentials.map(|(user, password,),| Arc::new((user.to_owned(), password.to_owned(),),),),
+      document: PhantomData,
+    }
+  }
+  /// Gets the URL of the database storing the documents.
+  #[inline]
+  pub fn get_database(&self,) -> &str { &self.database }
+  /// Builds an authenticated request.
+  /// 
+  /// # Params
+  /// 
+  /// method --- The method of the request.  
+  /// path --- The path of the request under the database.  
+  fn request(&self, method: Method, path: &str,) -> RequestBuilder {
+    let request = self.client.request(method, format!("{}{}", self.database, path,),);
+
+    match &self.credentials {
+      Some(credentials) => request.basic_auth(&credentials.0, Some(&credentials.1,),),
+      None => request,
+    }
+  }
+  /// Sends a request, failing if the response is an error.
+  /// 
+  /// # Params
+  /// 
+  /// request --- The request to send.  
+  async fn send(request: RequestBuilder,) -> Result<Response, CouchError> {
+    let response = request.send().await.map_err(request_error,)?;
+    let status = response.status();
+
+    if status.is_success() { return Ok(response) }
+
+    let message = match response.json::<ErrorResponse>().await {
+      Ok(ErrorResponse { error, reason, },) => format!("{}: {}", error, reason,),
+      Err(_) => status.to_string(),
+    };
+
+    Err(CouchError::Request(status_kind(status,), message,))
+  }
+  /// Creates the database storing the documents if it does not exist.
+  pub async fn create_database(&self,) -> Result<(), CouchError> {
+    match Self::send(self.request(Method::PUT, "",),).await {
+      Ok(_) => Ok(()),
+      //The database already exists.
+      Err(CouchError::Request(ErrorKind::Conflict, _)) => Ok(()),
+      Err(e) => Err(e),
+    }
+  }
+  /// Reads the rows of `_all_docs` for documents.
+  /// 
+  /// # Params
+  /// 
+  /// ids --- The identifiers of the documents.  
+  /// include_docs --- Whether the documents are read as well as their revisions.  
+  async fn all_docs(&self, ids: &[DocumentId], include_docs: bool,) -> Result<HashMap<DocumentId, AllDocsRow>, CouchError> {
+    let mut keys = ids.iter().map(hex,).collect::<Vec<_>>();
+    keys.sort_unstable();
+    keys.dedup();
+
+    let path = if include_docs { "/_all_docs?include_docs=true" } else { "/_all_docs" };
+    let response = Self::send(self.request(Method::POST, path,).json(&Keys { keys: &keys, },),).await?
+      .json::<AllDocs>().await
+      .map_err(request_error,)?;
+
+    Ok(response.rows.into_iter().filter_map(|row,| Some((parse_hex(&row.key,)?, row,)),).collect())
+  }
+}
+
+impl<D,> Clone for CouchCollection<D,> {
+  #[inline]
+  fn clone(&self,) -> Self {
+    Self {
+      client: self.client.clone(),
+      database: self.database.clone(),
+      credentials: self.credentials.clone(),
+      document: PhantomData,
+    }
+  }
+}
+
+impl<D,> fmt::Debug for CouchCollection<D,> {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    fmt.debug_struct("CouchCollection",)
+    .field("database", &self.database,)
+    .field("user", &self.credentials.as_ref().map(|credentials,| &credentials.0,),)
+    .finish()
+  }
+}
+
+impl<D,> CouchCollection<D,>
+  where D: Document + Serialize, {
+  /// Writes a document replacing a revision and returns the new revision.
+  /// 
+  /// # Params
+  /// 
+  /// document --- The document to write.  
+  /// rev --- The revision being replaced; `None` if the document does not exist.  
+  async fn put(&self, document: &D, rev: Option<String>,) -> Result<String, CouchError> {
+    let id = *document.get_id();
+    let stored = CouchDocument { id: hex(&id,), rev, body: document, };
+    let request = self.request(Method::PUT, &format!("/{}", stored.id,),).json(&stored,);
+    let result = match Self::send(request,).await {
+      Ok(response) => response.json::<WriteResult>().await.map_err(request_error,)?,
+      Err(CouchError::Request(ErrorKind::Conflict, _)) => return Err(CouchError::Conflict(id,)),
+      Err(e) => return Err(e),
+    };
+
+    let WriteResult { rev, error, reason, } = result;
+
+    rev.ok_or_else(|| bulk_error(id, error.as_deref().unwrap_or_default(), &reason,),)
+  }
+}
+
+impl<D,> TierListCollection for CouchCollection<D,>
+  where D: 'static + Document + Serialize + DeserializeOwned + Clone + Send + Sync, {
+  type Document = D;
+  type Error = CouchError;
+  type GetBatchDocuments = BoxFuture<'static, Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>>;
+  type GetDocument = BoxFuture<'static, Result<Self::Document, Self::Error>>;
+  type WriteBatchDocuments = BoxFuture<'static, Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>;
+  type WriteDocument = BoxFuture<'static, Result<(), Self::Error>>;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    let ids = ids.into_iter().map(|id,| *id.borrow(),).collect::<Vec<_>>();
+    let coll = self.clone();
+
+    Box::pin(async move {
+      if ids.is_empty() { return Ok(Vec::new()) }
+
+      let rows = coll.all_docs(&ids, true,).await?;
+
+      //Duplicate identifiers share a row so the document is cloned out of it.
+      Ok(ids.iter().map(|id,| match rows.get(id,).and_then(|row,| row.doc.clone(),) {
+        Some(document) => CouchDocument::decode(*id, document,).map(|(document, _,),| document,),
+        None => Err(CouchError::NotFound(*id,)),
+      },).collect())
+    },)
+  }
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    let future = self.get_document_rev(id,);
+
+    Box::pin(async move { future.await.map(|(document, _,),| document,) },)
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    //CouchDB rejects the second write of a document in a batch as a conflict so only
+    //the last write of each document is sent.
+    let mut index = HashMap::new();
+    let mut distinct = Vec::new();
+    let mut positions = Vec::new();
+    for document in documents {
+      let document = document.borrow().clone();
+
+      match index.entry(*document.get_id(),) {
+        Entry::Occupied(entry) => { distinct[*entry.get()] = document; positions.push(*entry.get(),) },
+        Entry::Vacant(entry) => { entry.insert(distinct.len(),); positions.push(distinct.len(),); distinct.push(document,) },
+      }
+    }
+    let coll = self.clone();
+
+    Box::pin(async move {
+      if distinct.is_empty() { return Ok(Ok(())) }
+
+      let ids = distinct.iter().map(|doc,| *doc.get_id(),).collect::<Vec<_>>();
+      let revs = coll.all_docs(&ids, false,).await?;
+      let docs = distinct.iter().map(|document,| CouchDocument {
+        id: hex(document.get_id(),),
+        rev: revs.get(document.get_id(),).and_then(|row,| row.value.as_ref(),)
+          .filter(|value,| !value.deleted,).map(|value,| value.rev.clone(),),
+        body: document,
+      },).collect::<Vec<_>>();
+      let results = Self::send(coll.request(Method::POST, "/_bulk_docs",).json(&BulkDocs { docs: &docs, },),).await?
+        .json::<Vec<WriteResult>>().await
+        .map_err(request_error,)?;
+
+      if results.len() != distinct.len() {
+        return Err(CouchError::Request(ErrorKind::Permanent, format!("expected {} results but got {}", distinct.len(), results.len(),),))
+      }
+
+      let results = results.into_iter().zip(&ids,).map(|(result, id,),| match result.error {
+        Some(error) => Err(bulk_error(*id, &error, &result.reason,),),
+        None => Ok(()),
+      },).collect::<Vec<_>>();
+
+      if results.iter().all(Result::is_ok,) { return Ok(Ok(())) }
+
+      Ok(Err(positions.into_iter().map(|i,| results[i].clone(),).collect()))
+    },)
+  }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    let (coll, document,) = (self.clone(), document.borrow().clone(),);
+
+    Box::pin(async move {
+      let revs = coll.all_docs(&[*document.get_id()], false,).await?;
+      let rev = revs.get(document.get_id(),).and_then(|row,| row.value.as_ref(),)
+        .filter(|value,| !value.deleted,).map(|value,| value.rev.clone(),);
+
+      coll.put(&document, rev,).await.map(|_,| (),)
+    },)
+  }
+  fn exists(&self, id: &DocumentId,) -> impl Future<Output = Result<bool, Self::Error>> {
+    let request = self.request(Method::HEAD, &format!("/{}", hex(id,),),);
+
+    async move {
+      match Self::send(request,).await {
+        Ok(_) => Ok(true),
+        Err(CouchError::Request(ErrorKind::NotFound, _)) => Ok(false),
+        Err(e) => Err(e),
+      }
+    }
+  }
+}
+
+/// Revisions are the `_rev`s of the CouchDB documents.
+impl<D,> RevisionedCollection for CouchCollection<D,>
+  where D: 'static + Document + Serialize + DeserializeOwned + Clone + Send + Sync, {
+  type Revision = String;
+  type GetDocumentRev = BoxFuture<'static, Result<(Self::Document, Self::Revision,), Self::Error>>;
+  type WriteDocumentRev = BoxFuture<'static, Result<Self::Revision, Self::Error>>;
+
+  fn get_document_rev(&self, id: &DocumentId,) -> Self::GetDocumentRev {
+    let id = *id;
+    let request = self.request(Method::GET, &format!("/{}", hex(&id,),),);
+
+    Box::pin(async move {
+      let document = match Self::send(request,).await {
+        Ok(response) => response.json::<Value>().await.map_err(request_error,)?,
+        Err(CouchError::Request(ErrorKind::NotFound, _)) => return Err(CouchError::NotFound(id,)),
+        Err(e) => return Err(e),
+      };
+
+      CouchDocument::decode(id, document,)
+    },)
+  }
+  fn write_document_rev<T,>(&self, document: &T, expected: Option<&Self::Revision>,) -> Self::WriteDocumentRev
+    where T: Borrow<Self::Document>, {
+    let (coll, document, expected,) = (self.clone(), document.borrow().clone(), expected.cloned(),);
+
+    Box::pin(async move { coll.put(&document, expected,).await },)
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+  use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher,},
+  };
+
+  /// A document stored by the tests.
+  #[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize,)]
+  struct Doc {
+    id: DocumentId,
+    name: String,
+  }
+
+  impl Document for Doc {
+    #[inline]
+    fn get_id(&self,) -> &DocumentId { &self.id }
+  }
+
+  fn doc(i: u8, name: &str,) -> Doc { Doc { id: DocumentId::from_bytes([i; 20]), name: name.to_owned(), } }
+
+  #[test]
+  fn test_documents() {
+    let document = doc(0xab, "first",);
+    let stored = CouchDocument { id: hex(&document.id,), rev: None, body: &document, };
+
+    assert_eq!(parse_hex(&stored.id,), Some(document.id), "Error parsing identifier",);
+    assert_eq!(parse_hex("abab",), None, "Error parsing short identifier",);
+    assert_eq!(
+      serde_json::to_value(&stored,).expect("Error serialising document"),
+      json!({ "_id": "ab".repeat(20,), "body": { "id": document.id, "name": "first", }, }),
+      "Error serialising new document",
+    );
+    assert_eq!(
+      serde_json::to_value(CouchDocument { rev: Some("1-a".to_owned(),), ..stored },).expect("Error serialising document")["_rev"],
+      json!("1-a"),
+      "Error serialising revision",
+    );
+    assert_eq!(
+      CouchDocument::<Doc,>::decode(document.id, json!({ "_id": "ab".repeat(20,), "_rev": "2-b", "body": { "id": document.id, "name": "first", }, }),),
+      Ok((document.clone(), "2-b".to_owned(),)),
+      "Error decoding document",
+    );
+    assert_eq!(
+      CouchDocument::<Doc,>::decode(document.id, json!({ "_id": "ab".repeat(20,), "_rev": "2-b", }),),
+      Err(CouchError::Corrupt(document.id,)),
+      "Error decoding document without a body",
+    );
+
+    let rows = serde_json::from_value::<AllDocs>(json!({
+      "total_rows": 2,
+      "rows": [
+        { "id": "ab".repeat(20,), "key": "ab".repeat(20,), "value": { "rev": "2-b", }, "doc": { "_id": "ab".repeat(20,), }, },
+        { "id": "cd".repeat(20,), "key": "cd".repeat(20,), "value": { "rev": "3-c", "deleted": true, }, "doc": null, },
+        { "key": "ef".repeat(20,), "error": "not_found", },
+      ],
+    }),).expect("Error parsing rows").rows;
+    assert!(rows[0].doc.is_some() && rows[1].doc.is_none() && rows[2].doc.is_none(), "Error parsing documents",);
+    assert!(rows[1].value.as_ref().is_some_and(|value,| value.deleted,), "Error parsing deleted document",);
+    assert!(rows[2].value.is_none(), "Error parsing missing document",);
+
+    let results = serde_json::from_value::<Vec<WriteResult>>(json!([
+      { "ok": true, "id": "ab".repeat(20,), "rev": "3-d", },
+      { "id": "cd".repeat(20,), "error": "conflict", "reason": "Document update conflict.", },
+    ]),).expect("Error parsing results");
+    assert_eq!(results[0].rev.as_deref(), Some("3-d"), "Error parsing written document",);
+    assert_eq!(
+      bulk_error(DocumentId::from_bytes([0xcd; 20]), results[1].error.as_deref().unwrap_or_default(), &results[1].reason,),
+      CouchError::Conflict(DocumentId::from_bytes([0xcd; 20]),),
+      "Error parsing conflict",
+    );
+  }
+  #[test]
+  fn test_status_kind() {
+    assert_eq!(status_kind(StatusCode::NOT_FOUND,), ErrorKind::NotFound, "Error classifying 404",);
+    assert_eq!(status_kind(StatusCode::CONFLICT,), ErrorKind::Conflict, "Error classifying 409",);
+    assert_eq!(status_kind(StatusCode::SERVICE_UNAVAILABLE,), ErrorKind::Transient, "Error classifying 503",);
+    assert_eq!(status_kind(StatusCode::UNAUTHORIZED,), ErrorKind::Permanent, "Error classifying 401",);
+  }
+  /// Runs against the CouchDB server at `GALILEO_COUCH_URL` when it is set, such as
+  /// `http://localhost:5984`, authenticating as `GALILEO_COUCH_USER` with the password
+  /// `GALILEO_COUCH_PASSWORD` if they are set.
+  #[test]
+  fn test_couch() {
+    let url = match std::env::var("GALILEO_COUCH_URL",) {
+      Ok(url) => url,
+      Err(_) => return,
+    };
+    let user = std::env::var("GALILEO_COUCH_USER",).ok();
+    let password = std::env::var("GALILEO_COUCH_PASSWORD",).unwrap_or_default();
+    let database = format!("galileo_{:x}", RandomState::new().build_hasher().finish(),);
+    let coll = CouchCollection::<Doc,>::new(Client::new(), &url, &database, user.as_deref().map(|user,| (user, &*password,),),);
+    let runtime = tokio::runtime::Runtime::new()
+      .expect("Error starting runtime");
+
+    runtime.block_on(async {
+      coll.create_database().await
+        .expect("Error creating database");
+      coll.create_database().await
+        .expect("Error creating existing database");
+
+      let docs = (0..3u8).map(|i,| doc(i, "first",),).collect::<Vec<_>>();
+      assert_eq!(coll.write_documents(&docs,).await, Ok(Ok(())), "Error writing documents",);
+      assert_eq!(
+        coll.get_documents([DocumentId::from_bytes([2; 20]), DocumentId::from_bytes([9; 20]), DocumentId::from_bytes([0; 20]), DocumentId::from_bytes([2; 20]),],).await,
+        Ok(vec![Ok(docs[2].clone()), Err(CouchError::NotFound(DocumentId::from_bytes([9; 20]),)), Ok(docs[0].clone()), Ok(docs[2].clone()),]),
+        "Error reading documents",
+      );
+
+      //Batches overwrite existing documents and keep the last of duplicates.
+      let updated = [doc(1, "second",), doc(1, "third",), doc(5, "first",),];
+      assert_eq!(coll.write_documents(&updated,).await, Ok(Ok(())), "Error overwriting documents",);
+      assert_eq!(coll.get_document(&DocumentId::from_bytes([1; 20]),).await, Ok(updated[1].clone()), "Error reading overwritten document",);
+      coll.write_document(&doc(2, "fourth",),).await
+        .expect("Error writing document");
+      assert_eq!(coll.get_document(&DocumentId::from_bytes([2; 20]),).await, Ok(doc(2, "fourth",)), "Error reading written document",);
+      assert_eq!(coll.exists(&DocumentId::from_bytes([5; 20]),).await, Ok(true), "Error document does not exist",);
+      assert_eq!(coll.exists(&DocumentId::from_bytes([9; 20]),).await, Ok(false), "Error document exists",);
+
+      //Writing with a stale revision conflicts.
+      let (_, stale,) = coll.get_document_rev(&DocumentId::from_bytes([0; 20]),).await
+        .expect("Error reading revision");
+      let fresh = coll.write_document_rev(&doc(0, "second",), Some(&stale),).await
+        .expect("Error writing revision");
+      assert_eq!(
+        coll.write_document_rev(&doc(0, "third",), Some(&stale),).await,
+        Err(CouchError::Conflict(DocumentId::from_bytes([0; 20]),)),
+        "Error writing stale revision",
+      );
+      assert_eq!(
+        coll.write_document_rev(&doc(0, "third",), None,).await,
+        Err(CouchError::Conflict(DocumentId::from_bytes([0; 20]),)),
+        "Error creating existing document",
+      );
+      assert_eq!(coll.get_document_rev(&DocumentId::from_bytes([0; 20]),).await, Ok((doc(0, "second",), fresh,)), "Error reading fresh revision",);
+      assert!(coll.write_document_rev(&doc(7, "first",), None,).await.is_ok(), "Error creating document",);
+
+      CouchCollection::<Doc,>::send(coll.request(Method::DELETE, "",),).await
+        .expect("Error deleting database");
+    },);
+  }
+}
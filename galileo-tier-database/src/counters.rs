@@ -0,0 +1,148 @@
+//! Defines operations for incrementing the counters of documents atomically.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, Card, TierDocument, TierListCollection,};
+use futures::Future;
+use std::{rc::Rc, sync::Arc,};
+
+/// The counters of a `Card`.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug,)]
+pub enum CounterField {
+  /// The up votes on the card.
+  UpVotes,
+  /// The down votes on the card.
+  DownVotes,
+  /// The bias of the card.
+  Bias,
+}
+
+impl CounterField {
+  /// Gets the name of the field of a `Card` storing this counter.
+  #[inline]
+  pub const fn name(self,) -> &'static str {
+    match self {
+      CounterField::UpVotes => "up_votes",
+      CounterField::DownVotes => "down_votes",
+      CounterField::Bias => "bias",
+    }
+  }
+}
+
+/// A document which may have counters.
+pub trait Counters {
+  /// Gets a counter of this document; `None` if this document has no counters.
+  /// 
+  /// # Params
+  /// 
+  /// field --- The counter to get.  
+  fn counter_mut(&mut self, field: CounterField,) -> Option<&mut u64>;
+}
+
+impl Counters for Card {
+  #[inline]
+  fn counter_mut(&mut self, field: CounterField,) -> Option<&mut u64> {
+    Some(match field {
+      CounterField::UpVotes => &mut self.up_votes,
+      CounterField::DownVotes => &mut self.down_votes,
+      CounterField::Bias => &mut self.bias,
+    })
+  }
+}
+
+impl Counters for TierDocument {
+  #[inline]
+  fn counter_mut(&mut self, field: CounterField,) -> Option<&mut u64> {
+    match self {
+      TierDocument::Card(card) => card.counter_mut(field,),
+      TierDocument::TierMeta(_) => None,
+    }
+  }
+}
+
+/// Adds a change to a counter, saturating at the bounds of the counter.
+/// 
+/// # Params
+/// 
+/// value --- The value of the counter.  
+/// delta --- The change to the counter.  
+#[inline]
+pub(crate) fn add_delta(value: u64, delta: i64,) -> u64 {
+  if delta < 0 { value.saturating_sub(delta.unsigned_abs(),) } else { value.saturating_add(delta as u64,) }
+}
+
+/// A collection which can increment the counters of its documents atomically.
+/// 
+/// Concurrent increments of the same counter are never lost, unlike reading the
+/// document, changing the counter and writing the document back.
+pub trait AtomicCounters: TierListCollection {
+  /// The future type when incrementing a counter.
+  type Increment: Future<Output = Result<u64, Self::Error>>;
+
+  /// Adds `delta` to a counter of a document and returns the new value of the counter.
+  /// 
+  /// The counter saturates at zero and `u64::MAX`. Incrementing a document which has no
+  /// counters fails with an error of kind `Permanent`.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the document in the collection.  
+  /// field --- The counter to increment.  
+  /// delta --- The change to the counter.  
+  fn increment_field(&self, id: &DocumentId, field: CounterField, delta: i64,) -> Self::Increment;
+}
+
+impl<Coll,> AtomicCounters for &Coll
+  where Coll: AtomicCounters, {
+  type Increment = Coll::Increment;
+
+  #[inline]
+  fn increment_field(&self, id: &DocumentId, field: CounterField, delta: i64,) -> Self::Increment {
+    Coll::increment_field(*self, id, field, delta,)
+  }
+}
+
+impl<Coll,> AtomicCounters for Rc<Coll,>
+  where Coll: AtomicCounters, {
+  type Increment = Coll::Increment;
+
+  #[inline]
+  fn increment_field(&self, id: &DocumentId, field: CounterField, delta: i64,) -> Self::Increment {
+    Coll::increment_field(&**self, id, field, delta,)
+  }
+}
+
+impl<Coll,> AtomicCounters for Arc<Coll,>
+  where Coll: AtomicCounters, {
+  type Increment = Coll::Increment;
+
+  #[inline]
+  fn increment_field(&self, id: &DocumentId, field: CounterField, delta: i64,) -> Self::Increment {
+    Coll::increment_field(&**self, id, field, delta,)
+  }
+}
+
+impl<Coll,> AtomicCounters for Box<Coll,>
+  where Coll: AtomicCounters, {
+  type Increment = Coll::Increment;
+
+  #[inline]
+  fn increment_field(&self, id: &DocumentId, field: CounterField, delta: i64,) -> Self::Increment {
+    Coll::increment_field(&**self, id, field, delta,)
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_add_delta() {
+    assert_eq!(add_delta(5, 3,), 8, "Error adding to counter",);
+    assert_eq!(add_delta(5, -3,), 2, "Error subtracting from counter",);
+    assert_eq!(add_delta(5, -10,), 0, "Error saturating at zero",);
+    assert_eq!(add_delta(u64::MAX - 1, 5,), u64::MAX, "Error saturating at the maximum",);
+    assert_eq!(add_delta(u64::MAX, i64::MIN,), u64::MAX - (1 << 63), "Error subtracting the minimum",);
+  }
+}
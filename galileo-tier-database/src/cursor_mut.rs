@@ -0,0 +1,323 @@
+//! Defines a cursor which buffers its edits until they are committed.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, LinkedList, BatchReport, Cursor, TierListCollection, tier_collection::step_position,};
+use std::{fmt, mem, collections::HashMap,};
+
+/// A view into a collection which buffers the edits to the nodes it visits instead of
+/// writing them.
+/// 
+/// Moving back to an edited node serves the buffered version of the node. The edits are
+/// written with one batch write by `commit` or dropped by `rollback`; dropping a
+/// `CursorMut` with uncommitted edits panics in debug builds.
+pub struct CursorMut<T, Coll,>
+  where Coll: TierListCollection, {
+  /// The collection the nodes are read from.
+  collection: Coll,
+  /// The current item.
+  item: T,
+  /// The position of the current item in the list if it is tracked.
+  position: Option<u64>,
+  /// Whether the current item was changed since it was buffered.
+  dirty: bool,
+  /// The buffered versions of the edited nodes.
+  edits: HashMap<DocumentId, T>,
+  /// The identifiers of the edited nodes in the order they were first edited.
+  order: Vec<DocumentId>,
+}
+
+impl<T, Coll,> CursorMut<T, Coll,>
+  where T: LinkedList + Clone,
+    Coll: TierListCollection, {
+  /// Returns a new `CursorMut` at the item of a `Cursor`.
+  /// 
+  /// If the item of the `Cursor` was changed but not saved it is buffered as an edit.
+  /// 
+  /// # Params
+  /// 
+  /// cursor --- The `Cursor` to start at.  
+  pub fn new(cursor: Cursor<T, Coll,>,) -> Self {
+    let (position, dirty,) = (cursor.position(), cursor.is_dirty(),);
+    let (collection, item,) = cursor.into_parts();
+
+    Self { collection, item, position, dirty, edits: HashMap::new(), order: Vec::new(), }
+  }
+  /// Gets the collection used by this `CursorMut`.
+  #[inline]
+  pub const fn get_collection(&self,) -> &Coll { &self.collection }
+  /// Gets the current item.
+  #[inline]
+  pub const fn get_item(&self,) -> &T { &self.item }
+  /// Gets the current item mutably, buffering it as an edit.
+  #[inline]
+  pub fn get_item_mut(&mut self,) -> &mut T { self.dirty = true; &mut self.item }
+  /// Gets the position of the current item in the list if it is tracked.
+  #[inline]
+  pub const fn position(&self,) -> Option<u64> { self.position }
+  /// Returns `true` if there are edits which have not been committed.
+  #[inline]
+  pub fn is_dirty(&self,) -> bool { self.dirty || !self.order.is_empty() }
+  /// Gets the identifiers of the edited nodes in the order they were first edited.
+  pub fn dirty_ids(&self,) -> Vec<DocumentId> {
+    let mut ids = self.order.clone();
+    let id = self.item.get_id();
+
+    if self.dirty && !self.edits.contains_key(id,) { ids.push(*id,) }
+
+    ids
+  }
+  /// Buffers the current item if it was changed.
+  fn stash(&mut self,) {
+    if mem::take(&mut self.dirty,) && self.edits.insert(*self.item.get_id(), self.item.clone(),).is_none() {
+      self.order.push(*self.item.get_id(),);
+    }
+  }
+  /// Moves this `CursorMut` to a neighbouring node in place.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the neighbouring node.  
+  /// forward --- Whether the neighbour is the next node.  
+  async fn step(&mut self, id: Option<DocumentId>, forward: bool,) -> Result<bool, Coll::Error>
+    where Coll::Document: Into<T>, {
+    let id = match id {
+      Some(id) => id,
+      None => return Ok(false),
+    };
+    let item = match self.edits.get(&id,) {
+      Some(item) => item.clone(),
+      None => self.collection.get_document(&id,).await?.into(),
+    };
+
+    self.stash();
+    self.item = item;
+    self.position = step_position(self.position, forward,);
+
+    Ok(true)
+  }
+  /// Moves this `CursorMut` to the next node in the linked list in place.
+  /// 
+  /// Returns `Ok(false)` if there is no next node. If there was an error getting the
+  /// next node this `CursorMut` is unchanged.
+  #[inline]
+  pub async fn move_next(&mut self,) -> Result<bool, Coll::Error>
+    where Coll::Document: Into<T>, {
+    self.step(self.item.get_next_id().copied(), true,).await
+  }
+  /// Moves this `CursorMut` to the previous node in the linked list in place.
+  /// 
+  /// Returns `Ok(false)` if there is no previous node. If there was an error getting
+  /// the previous node this `CursorMut` is unchanged.
+  #[inline]
+  pub async fn move_previous(&mut self,) -> Result<bool, Coll::Error>
+    where Coll::Document: Into<T>, {
+    self.step(self.item.get_previous_id().copied(), false,).await
+  }
+  /// Writes every edited node using a single batch write and reports the result of
+  /// each write.
+  /// 
+  /// The nodes are written in the order they were first edited. The nodes which were
+  /// written are no longer buffered; the nodes which failed to write stay buffered so
+  /// that committing again retries them. If the batch failed as a whole every edit
+  /// stays buffered.
+  pub async fn commit(&mut self,) -> Result<BatchReport<Coll::Error,>, Coll::Error>
+    where T: Into<Coll::Document>, {
+    self.stash();
+    if self.order.is_empty() { return Ok(None.into_iter().collect()) }
+
+    let documents = self.order.iter()
+      .map(|id,| self.edits[id].clone().into(),)
+      .collect::<Vec<Coll::Document>>();
+    let report = self.collection.write_documents_reported(&documents,).await?;
+
+    for id in report.succeeded_ids() { self.edits.remove(id,); }
+    let edits = &self.edits;
+    self.order.retain(|id,| edits.contains_key(id,),);
+
+    Ok(report)
+  }
+  /// Drops every edit which has not been committed.
+  /// 
+  /// If the current item was edited it is read from the collection again; if there was
+  /// an error reading it this `CursorMut` is unchanged.
+  pub async fn rollback(&mut self,) -> Result<(), Coll::Error>
+    where Coll::Document: Into<T>, {
+    let id = *self.item.get_id();
+
+    if self.dirty || self.edits.contains_key(&id,) {
+      self.item = self.collection.get_document(&id,).await?.into();
+    }
+
+    self.dirty = false;
+    self.edits.clear();
+    self.order.clear();
+
+    Ok(())
+  }
+}
+
+impl<T, Coll,> From<Cursor<T, Coll,>> for CursorMut<T, Coll,>
+  where T: LinkedList + Clone,
+    Coll: TierListCollection, {
+  #[inline]
+  fn from(from: Cursor<T, Coll,>,) -> Self { Self::new(from,) }
+}
+
+impl<T, Coll,> Drop for CursorMut<T, Coll,>
+  where Coll: TierListCollection, {
+  fn drop(&mut self,) {
+    debug_assert!(
+      std::thread::panicking() || (!self.dirty && self.order.is_empty()),
+      "`CursorMut` dropped with edits which were not committed or rolled back",
+    );
+  }
+}
+
+impl<T, Coll,> fmt::Debug for CursorMut<T, Coll,>
+  where T: fmt::Debug,
+    Coll: TierListCollection, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    fmt.debug_struct("CursorMut",)
+    .field("collection", &format_args!("{}", std::any::type_name::<Coll>(),),)
+    .field("item", &self.item,)
+    .field("position", &self.position,)
+    .field("dirty", &self.dirty,)
+    .field("edits", &self.order,)
+    .finish()
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{CardId, TierId, TierMeta, MemoryCollection, RecordingCollection, Op, mock::{ChaosCollection, ChaosError,},};
+  use futures::executor::block_on;
+
+  /// Returns a collection storing a list of tiers with the identifiers one to `len`.
+  fn list(len: u8,) -> RecordingCollection<ChaosCollection<TierMeta,>> {
+    let coll = RecordingCollection::new(ChaosCollection::new(),);
+    let tiers = (1..=len).map(|i,| TierMeta::new(
+      TierId::from_bytes([i; 20]),
+      (None, CardId::from_bytes([0; 20]), CardId::from_bytes([0; 20]),),
+      Some(TierId::from_bytes([i - 1; 20]),).filter(|_,| i > 1,),
+      Some(TierId::from_bytes([i + 1; 20]),).filter(|_,| i < len,),
+    ),).collect::<Vec<_>>();
+
+    block_on(coll.get_inner().write_documents(&tiers,),)
+      .expect("Error writing list")
+      .expect("Error writing list");
+
+    coll
+  }
+  /// Marks the current tier as edited by setting its front.
+  fn edit<Coll,>(cursor: &mut CursorMut<TierMeta, Coll,>, front: u8,)
+    where Coll: TierListCollection, {
+    let tier = cursor.get_item_mut();
+
+    *tier = TierMeta::new(tier.id, (None, CardId::from_bytes([front; 20]), CardId::from_bytes([front; 20]),), tier.previous_tier, tier.next_tier,);
+  }
+
+  #[test]
+  fn test_commit() {
+    let coll = list(5,);
+
+    block_on(async {
+      let mut cursor = CursorMut::new(coll.ref_cursor::<TierMeta>(&DocumentId::from_bytes([1; 20]),).await.expect("Error reading head").with_position(0,),);
+
+      edit(&mut cursor, 9,);
+      assert_eq!(cursor.move_next().await, Ok(true), "Error moving next",);
+      assert_eq!(cursor.move_next().await, Ok(true), "Error moving next",);
+      edit(&mut cursor, 9,);
+      assert_eq!(cursor.move_next().await, Ok(true), "Error moving next",);
+      edit(&mut cursor, 9,);
+      assert_eq!(cursor.position(), Some(3), "Error tracking the position",);
+
+      //Revisiting an edited node serves the buffered version.
+      coll.take_ops();
+      assert_eq!(cursor.move_previous().await, Ok(true), "Error moving previous",);
+      assert_eq!(cursor.get_item().list_front(), &DocumentId::from_bytes([9; 20]), "Error serving the buffered node",);
+      crate::assert_ops!(coll, [], "Error reading a buffered node",);
+      assert_eq!(cursor.dirty_ids(), [DocumentId::from_bytes([1; 20]), DocumentId::from_bytes([3; 20]), DocumentId::from_bytes([4; 20]),], "Error tracking the edited nodes",);
+
+      let report = cursor.commit().await
+        .expect("Error committing edits");
+      assert!(report.is_complete_success(), "Error writing edits",);
+      crate::assert_ops!(coll, [Op::WriteBatch(vec![DocumentId::from_bytes([1; 20]), DocumentId::from_bytes([3; 20]), DocumentId::from_bytes([4; 20]),]),], "Error writing exactly the edited nodes",);
+      assert!(!cursor.is_dirty(), "Error edits still buffered after commit",);
+
+      let stored = coll.get_inner().get_documents((1..=5u8).map(|i,| DocumentId::from_bytes([i; 20]),),).await
+        .expect("Error reading list")
+        .into_iter()
+        .map(|tier,| tier.map(|tier,| tier.list_front().as_bytes()[0],),)
+        .collect::<Vec<_>>();
+      assert_eq!(stored, [Ok(9), Ok(0), Ok(9), Ok(9), Ok(0),], "Error persisting the edits",);
+      assert_eq!(cursor.commit().await.map(|report,| report.len(),), Ok(0), "Error committing no edits",);
+      crate::assert_ops!(coll, [], "Error writing with no edits",);
+    },);
+  }
+  #[test]
+  fn test_failed_commit() {
+    let coll = list(3,);
+
+    block_on(async {
+      let mut cursor = CursorMut::new(coll.ref_cursor::<TierMeta>(&DocumentId::from_bytes([1; 20]),).await.expect("Error reading head"),);
+
+      edit(&mut cursor, 9,);
+      cursor.move_next().await
+        .expect("Error moving next");
+      edit(&mut cursor, 9,);
+      coll.get_inner().flaky_writes(DocumentId::from_bytes([1; 20]), 1,);
+      let report = cursor.commit().await
+        .expect("Error committing edits");
+      assert_eq!(report.failures().collect::<Vec<_>>(), [(&DocumentId::from_bytes([1; 20]), &ChaosError::Injected(DocumentId::from_bytes([1; 20]),),),], "Error reporting the failed write",);
+      assert_eq!(cursor.dirty_ids(), [DocumentId::from_bytes([1; 20]),], "Error keeping the failed edit",);
+
+      coll.take_ops();
+      cursor.commit().await
+        .expect("Error committing edits");
+      crate::assert_ops!(coll, [Op::WriteBatch(vec![DocumentId::from_bytes([1; 20]),]),], "Error retrying the failed edit",);
+    },);
+  }
+  #[test]
+  fn test_rollback() {
+    let coll = MemoryCollection::<TierMeta,>::new();
+    let tiers = [
+      TierMeta::new(TierId::from_bytes([1; 20]), (None, CardId::from_bytes([0; 20]), CardId::from_bytes([0; 20]),), None, Some(TierId::from_bytes([2; 20])),),
+      TierMeta::new(TierId::from_bytes([2; 20]), (None, CardId::from_bytes([0; 20]), CardId::from_bytes([0; 20]),), Some(TierId::from_bytes([1; 20])), None,),
+    ];
+
+    block_on(async {
+      coll.write_documents(tiers,).await
+        .expect("Error writing list")
+        .expect("Error writing list");
+      let mut cursor = CursorMut::new(coll.ref_cursor::<TierMeta>(&DocumentId::from_bytes([1; 20]),).await.expect("Error reading head"),);
+
+      edit(&mut cursor, 9,);
+      cursor.move_next().await
+        .expect("Error moving next");
+      edit(&mut cursor, 9,);
+      cursor.rollback().await
+        .expect("Error rolling back");
+      assert!(!cursor.is_dirty(), "Error edits still buffered after rollback",);
+      assert_eq!(cursor.get_item(), &tiers[1], "Error current item still edited",);
+      cursor.move_previous().await
+        .expect("Error moving previous");
+      assert_eq!(cursor.get_item(), &tiers[0], "Error serving a dropped edit",);
+      assert_eq!(coll.get_document(&DocumentId::from_bytes([1; 20]),).await, Ok(tiers[0]), "Error writing a dropped edit",);
+    },);
+  }
+  #[cfg(debug_assertions,)]
+  #[test]
+  #[should_panic(expected = "not committed or rolled back",)]
+  fn test_dirty_drop() {
+    let coll = MemoryCollection::<TierMeta,>::new();
+    let tier = TierMeta::new(TierId::from_bytes([1; 20]), (None, CardId::from_bytes([0; 20]), CardId::from_bytes([0; 20]),), None, None,);
+
+    block_on(coll.write_document(&tier,),)
+      .expect("Error writing tier");
+    let mut cursor = CursorMut::new(Cursor::new(&coll, tier,),);
+    edit(&mut cursor, 9,);
+  }
+}
@@ -0,0 +1,544 @@
+//! Defines the identifiers of documents.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use serde::{
+  Serialize, Serializer, Deserialize, Deserializer,
+  de::{self, Visitor, SeqAccess, Unexpected,},
+};
+use std::{fmt, error::Error, str::FromStr, convert::TryFrom, ops::Deref, borrow::Borrow,};
+
+/// The identifier for a document.
+/// 
+/// Identifiers are formatted and parsed as 40 hexadecimal characters, except for `NIL`
+/// which is displayed as `nil`. They are serialized as hexadecimal for human readable
+/// formats and as their bytes otherwise.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default,)]
+#[repr(transparent,)]
+pub struct DocumentId([u8; 20]);
+
+impl DocumentId {
+  /// The number of bytes in an identifier.
+  pub const LEN: usize = 20;
+  /// The identifier with every byte zero.
+  /// 
+  /// No document may be identified by or link to it so that it can stand in for a
+  /// missing identifier.
+  pub const NIL: Self = Self([0; 20],);
+
+  /// Returns the identifier with the bytes.
+  /// 
+  /// # Params
+  /// 
+  /// bytes --- The bytes of the identifier.  
+  #[inline]
+  pub const fn from_bytes(bytes: [u8; 20],) -> Self { Self(bytes,) }
+  /// Gets the bytes of this identifier.
+  #[inline]
+  pub const fn as_bytes(&self,) -> &[u8; 20] { &self.0 }
+  /// Returns `true` if this is the `NIL` identifier.
+  #[inline]
+  pub fn is_nil(&self,) -> bool { *self == Self::NIL }
+  /// Returns the identifier derived from some content.
+  /// 
+  /// The identifier is the SHA-1 digest of the parts, each prefixed with its length as
+  /// 8 little endian bytes so that no two lists of parts share an identifier by being
+  /// split differently. The derivation is stable between releases.
+  /// 
+  /// # Params
+  /// 
+  /// parts --- The content to derive the identifier from.  
+  pub fn digest(parts: &[&[u8]],) -> Self {
+    let mut message = Vec::with_capacity(parts.iter().map(|part,| part.len() + 8,).sum(),);
+    for part in parts {
+      message.extend_from_slice(&(part.len() as u64).to_le_bytes(),);
+      message.extend_from_slice(part,);
+    }
+
+    Self(sha1(&message,),)
+  }
+  /// Returns a new random identifier.
+  /// 
+  /// The bytes are taken from a cryptographically secure generator so identifiers which
+  /// appear in URLs cannot be guessed.
+  #[cfg(feature = "rand",)]
+  #[inline]
+  pub fn random() -> Self { Self::random_with(&mut rand::thread_rng(),) }
+  /// Returns a new random identifier taking the bytes from `rng`.
+  /// 
+  /// # Params
+  /// 
+  /// rng --- The generator to take the bytes from.  
+  #[cfg(feature = "rand",)]
+  pub fn random_with<R,>(rng: &mut R,) -> Self
+    where R: rand::RngCore + ?Sized, {
+    let mut bytes = [0; 20];
+
+    rng.fill_bytes(&mut bytes,);
+    Self(bytes,)
+  }
+}
+
+/// Computes the SHA-1 digest of a message.
+/// 
+/// # Params
+/// 
+/// message --- The bytes to digest.  
+fn sha1(message: &[u8],) -> [u8; 20] {
+  let mut state: [u32; 5] = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476, 0xc3d2_e1f0,];
+  //Pad the message with a one bit, zeros, and its length in bits to a multiple of 64 bytes.
+  let mut padded = message.to_vec();
+  padded.push(0x80,);
+  while padded.len() % 64 != 56 { padded.push(0,) }
+  padded.extend_from_slice(&((message.len() as u64).wrapping_mul(8,)).to_be_bytes(),);
+
+  for block in padded.chunks(64,) {
+    let mut words = [0u32; 80];
+    for (word, bytes,) in words.iter_mut().zip(block.chunks(4,),) {
+      *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3],],);
+    }
+    for i in 16..80 { words[i] = (words[i - 3] ^ words[i - 8] ^ words[i - 14] ^ words[i - 16]).rotate_left(1,) }
+
+    let [mut a, mut b, mut c, mut d, mut e,] = state;
+    for (i, word,) in words.iter().enumerate() {
+      let (f, k,) = match i {
+        0..=19 => ((b & c) | (!b & d), 0x5a82_7999,),
+        20..=39 => (b ^ c ^ d, 0x6ed9_eba1,),
+        40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1b_bcdc,),
+        _ => (b ^ c ^ d, 0xca62_c1d6,),
+      };
+      let temp = a.rotate_left(5,).wrapping_add(f,).wrapping_add(e,).wrapping_add(k,).wrapping_add(*word,);
+
+      e = d;
+      d = c;
+      c = b.rotate_left(30,);
+      b = a;
+      a = temp;
+    }
+
+    for (state, value,) in state.iter_mut().zip([a, b, c, d, e,],) { *state = state.wrapping_add(value,) }
+  }
+
+  let mut digest = [0; 20];
+  for (bytes, word,) in digest.chunks_mut(4,).zip(state,) { bytes.copy_from_slice(&word.to_be_bytes(),) }
+
+  digest
+}
+
+impl From<[u8; 20]> for DocumentId {
+  #[inline]
+  fn from(from: [u8; 20],) -> Self { Self(from,) }
+}
+
+impl From<DocumentId> for [u8; 20] {
+  #[inline]
+  fn from(from: DocumentId,) -> Self { from.0 }
+}
+
+impl TryFrom<Vec<u8>> for DocumentId {
+  type Error = Vec<u8>;
+
+  #[inline]
+  fn try_from(from: Vec<u8>,) -> Result<Self, Self::Error> { <[u8; 20]>::try_from(from,).map(Self,) }
+}
+
+impl AsRef<[u8]> for DocumentId {
+  #[inline]
+  fn as_ref(&self,) -> &[u8] { &self.0 }
+}
+
+impl fmt::LowerHex for DocumentId {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    for byte in &self.0 { write!(fmt, "{:02x}", byte,)? }
+
+    Ok(())
+  }
+}
+
+impl fmt::Display for DocumentId {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    if self.is_nil() { fmt.pad("nil",) }
+    else { fmt::LowerHex::fmt(self, fmt,) }
+  }
+}
+
+impl fmt::Debug for DocumentId {
+  #[inline]
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result { write!(fmt, "DocumentId({:x})", self,) }
+}
+
+/// The identifier which was nil when validating a document.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum NilIdError {
+  /// The document is identified by `NIL`.
+  Id,
+  /// The document links to `NIL` as the previous document.
+  Previous,
+  /// The document links to `NIL` as the next document.
+  Next,
+  /// A tier which has cards has `NIL` as its front card.
+  Front,
+  /// A tier which has cards has `NIL` as its back card.
+  Back,
+}
+
+impl fmt::Display for NilIdError {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    let field = match self {
+      NilIdError::Id => "id",
+      NilIdError::Previous => "previous id",
+      NilIdError::Next => "next id",
+      NilIdError::Front => "front card id",
+      NilIdError::Back => "back card id",
+    };
+
+    write!(fmt, "the {} of the document is nil", field,)
+  }
+}
+
+impl Error for NilIdError {}
+
+/// The errors when parsing a `DocumentId`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum ParseIdError {
+  /// The string had this many bytes instead of 40.
+  Length(usize),
+  /// The string held a character which is not hexadecimal.
+  Character(char),
+}
+
+impl fmt::Display for ParseIdError {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      ParseIdError::Length(len) => write!(fmt, "an id has 40 characters not {}", len,),
+      ParseIdError::Character(c) => write!(fmt, "{:?} is not a hexadecimal character", c,),
+    }
+  }
+}
+
+impl Error for ParseIdError {}
+
+impl FromStr for DocumentId {
+  type Err = ParseIdError;
+
+  fn from_str(s: &str,) -> Result<Self, Self::Err> {
+    if s == "nil" { return Ok(Self::NIL) }
+
+    let digits = s.chars()
+      .map(|c,| c.to_digit(16,).map(|digit,| digit as u8,).ok_or(ParseIdError::Character(c,),),)
+      .collect::<Result<Vec<_>, _>>()?;
+    if digits.len() != 2 * Self::LEN { return Err(ParseIdError::Length(s.len(),)) }
+
+    let mut bytes = [0; 20];
+    for (byte, pair,) in bytes.iter_mut().zip(digits.chunks(2,),) { *byte = pair[0] << 4 | pair[1] }
+
+    Ok(Self(bytes,))
+  }
+}
+
+impl Serialize for DocumentId {
+  fn serialize<S,>(&self, serializer: S,) -> Result<S::Ok, S::Error>
+    where S: Serializer, {
+    //`NIL` is stored as hexadecimal like any other identifier.
+    if serializer.is_human_readable() { serializer.collect_str(&format_args!("{:x}", self,),) }
+    else { serializer.serialize_bytes(&self.0,) }
+  }
+}
+
+impl<'de,> Deserialize<'de> for DocumentId {
+  fn deserialize<D,>(deserializer: D,) -> Result<Self, D::Error>
+    where D: Deserializer<'de>, {
+    if deserializer.is_human_readable() { deserializer.deserialize_any(IdVisitor,) }
+    else { deserializer.deserialize_bytes(IdVisitor,) }
+  }
+}
+
+/// Deserializes a `DocumentId` from hexadecimal, base64, or its bytes.
+/// 
+/// Base64 and a sequence of bytes are accepted so that documents exported before
+/// identifiers were serialized as hexadecimal can still be read.
+struct IdVisitor;
+
+impl<'de,> Visitor<'de> for IdVisitor {
+  type Value = DocumentId;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    write!(fmt, "{} hexadecimal characters, base64, or {} bytes", 2 * DocumentId::LEN, DocumentId::LEN,)
+  }
+  fn visit_str<E,>(self, s: &str,) -> Result<Self::Value, E>
+    where E: de::Error, {
+    if s.len() == 2 * DocumentId::LEN {
+      return s.parse().map_err(|e,| E::custom(format_args!("invalid id {:?}: {}", s, e,),),)
+    }
+
+    base64(s,).map(DocumentId,).ok_or_else(|| E::invalid_value(Unexpected::Str(s,), &self,),)
+  }
+  fn visit_bytes<E,>(self, bytes: &[u8],) -> Result<Self::Value, E>
+    where E: de::Error, {
+    <[u8; 20]>::try_from(bytes,).map(DocumentId,).map_err(|_,| E::invalid_value(Unexpected::Bytes(bytes,), &self,),)
+  }
+  fn visit_seq<A,>(self, mut seq: A,) -> Result<Self::Value, A::Error>
+    where A: SeqAccess<'de>, {
+    let mut bytes = [0; 20];
+    for (i, byte,) in bytes.iter_mut().enumerate() {
+      *byte = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(i, &self,),)?;
+    }
+    if seq.next_element::<de::IgnoredAny>()?.is_some() { return Err(de::Error::invalid_length(DocumentId::LEN + 1, &self,)) }
+
+    Ok(DocumentId(bytes,))
+  }
+}
+
+/// Decodes the bytes of an identifier from standard base64, with or without padding.
+/// 
+/// # Params
+/// 
+/// s --- The encoded bytes.  
+fn base64(s: &str,) -> Option<[u8; 20]> {
+  /// The number of characters encoding 20 bytes without padding.
+  const CHARS: usize = 27;
+
+  let s = s.strip_suffix('=',).unwrap_or(s,);
+  if s.len() != CHARS { return None }
+
+  let mut bytes = [0; 20];
+  let (mut bits, mut len, mut i,) = (0u32, 0, 0,);
+  for c in s.bytes() {
+    let sextet = match c {
+      b'A'..=b'Z' => c - b'A',
+      b'a'..=b'z' => c - b'a' + 26,
+      b'0'..=b'9' => c - b'0' + 52,
+      b'+' => 62,
+      b'/' => 63,
+      _ => return None,
+    };
+
+    bits = bits << 6 | sextet as u32;
+    len += 6;
+    if len >= 8 {
+      len -= 8;
+      bytes[i] = (bits >> len) as u8;
+      i += 1;
+    }
+  }
+  //The unused bits of the last character must be zero.
+  if bits & ((1 << len) - 1) != 0 { return None }
+
+  Some(bytes,)
+}
+
+/// Defines an identifier which can only be used for one kind of document.
+macro_rules! typed_id {
+  ($(#[$meta:meta])* $name:ident,) => {
+    $(#[$meta])*
+    /// 
+    /// It is serialized exactly as the `DocumentId` it wraps and dereferences to it so
+    /// that it can be passed to anything expecting a `DocumentId`.
+    #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default, Serialize, Deserialize,)]
+    #[serde(transparent,)]
+    #[repr(transparent,)]
+    pub struct $name(DocumentId);
+
+    impl $name {
+      /// Returns the identifier with the bytes.
+      /// 
+      /// # Params
+      /// 
+      /// bytes --- The bytes of the identifier.  
+      #[inline]
+      pub const fn from_bytes(bytes: [u8; 20],) -> Self { Self(DocumentId::from_bytes(bytes,),) }
+    }
+
+    impl $name {
+      /// The `NIL` identifier.
+      pub const NIL: Self = Self(DocumentId::NIL,);
+    }
+
+    impl From<DocumentId> for $name {
+      #[inline]
+      fn from(from: DocumentId,) -> Self { Self(from,) }
+    }
+
+    impl From<$name> for DocumentId {
+      #[inline]
+      fn from(from: $name,) -> Self { from.0 }
+    }
+
+    impl Deref for $name {
+      type Target = DocumentId;
+
+      #[inline]
+      fn deref(&self,) -> &Self::Target { &self.0 }
+    }
+
+    impl AsRef<DocumentId> for $name {
+      #[inline]
+      fn as_ref(&self,) -> &DocumentId { &self.0 }
+    }
+
+    impl Borrow<DocumentId> for $name {
+      #[inline]
+      fn borrow(&self,) -> &DocumentId { &self.0 }
+    }
+
+    impl PartialEq<DocumentId> for $name {
+      #[inline]
+      fn eq(&self, other: &DocumentId,) -> bool { self.0 == *other }
+    }
+
+    impl PartialEq<$name> for DocumentId {
+      #[inline]
+      fn eq(&self, other: &$name,) -> bool { *self == other.0 }
+    }
+
+    impl fmt::Display for $name {
+      #[inline]
+      fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result { fmt::Display::fmt(&self.0, fmt,) }
+    }
+
+    impl fmt::Debug for $name {
+      #[inline]
+      fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result { write!(fmt, concat!(stringify!($name), "({:x})",), self.0,) }
+    }
+
+    impl FromStr for $name {
+      type Err = ParseIdError;
+
+      #[inline]
+      fn from_str(s: &str,) -> Result<Self, Self::Err> { s.parse().map(Self,) }
+    }
+  };
+}
+
+typed_id! {
+  /// The identifier of a `Card`.
+  CardId,
+}
+
+typed_id! {
+  /// The identifier of a `TierMeta`.
+  TierId,
+}
+
+typed_id! {
+  /// The identifier of a tier list, which is the identifier of its first tier.
+  ListId,
+}
+
+impl From<TierId> for ListId {
+  #[inline]
+  fn from(from: TierId,) -> Self { Self(from.0,) }
+}
+
+impl From<ListId> for TierId {
+  #[inline]
+  fn from(from: ListId,) -> Self { Self(from.0,) }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_display() {
+    let mut bytes = [0; 20];
+    bytes[0] = 0xab;
+    bytes[19] = 0x01;
+
+    let id = DocumentId::from_bytes(bytes,);
+    assert_eq!(id.to_string(), "ab00000000000000000000000000000000000001", "Error formatting an id",);
+    assert_eq!(format!("{:x}", id,), id.to_string(), "Error formatting an id as hex",);
+    assert_eq!(format!("{:?}", id,), "DocumentId(ab00000000000000000000000000000000000001)", "Error debugging an id",);
+    assert_eq!(<[u8; 20]>::from(id,), bytes, "Error converting an id to bytes",);
+
+    assert_eq!(DocumentId::NIL.to_string(), "nil", "Error displaying the nil id",);
+    assert_eq!(format!("{:>5}", DocumentId::NIL,), "  nil", "Error padding the nil id",);
+    assert_eq!(format!("{:x}", DocumentId::NIL,), "0".repeat(40,), "Error formatting the nil id as hex",);
+    assert_eq!(CardId::NIL.to_string(), "nil", "Error displaying a nil card id",);
+    assert!(DocumentId::NIL.is_nil() && CardId::NIL.is_nil(), "Error checking the nil id",);
+    assert!(!id.is_nil(), "Error checking an id",);
+  }
+  #[test]
+  fn test_from_str() {
+    let id = DocumentId::from([0x5e; 20],);
+    assert_eq!(id.to_string().parse(), Ok(id), "Error round tripping an id",);
+    assert_eq!("5E".repeat(20,).parse(), Ok(id), "Error parsing upper case",);
+    assert_eq!("nil".parse(), Ok(DocumentId::NIL), "Error parsing the nil id",);
+    assert_eq!("0".repeat(40,).parse(), Ok(DocumentId::NIL), "Error parsing the nil id as hex",);
+
+    assert_eq!("5e".repeat(19,).parse::<DocumentId>(), Err(ParseIdError::Length(38,)), "Error parsing a short id",);
+    assert_eq!("5e".repeat(21,).parse::<DocumentId>(), Err(ParseIdError::Length(42,)), "Error parsing a long id",);
+    assert_eq!("".parse::<DocumentId>(), Err(ParseIdError::Length(0,)), "Error parsing an empty id",);
+    assert_eq!(format!("{}g", "5e".repeat(19,) + "5",).parse::<DocumentId>(), Err(ParseIdError::Character('g',)), "Error parsing a bad character",);
+    assert_eq!("5é".repeat(13,).parse::<DocumentId>(), Err(ParseIdError::Character('é',)), "Error parsing a multi byte character",);
+  }
+  #[test]
+  fn test_serde() {
+    let id = DocumentId::from_bytes([0xab; 20],);
+    let json = serde_json::to_string(&id,).expect("Error serializing to JSON");
+    assert_eq!(json, format!("\"{}\"", "ab".repeat(20,),), "Error serializing as hex",);
+    assert_eq!(serde_json::from_str::<DocumentId>(&json,).ok(), Some(id), "Error round tripping through JSON",);
+    assert_eq!(serde_json::to_string(&DocumentId::NIL,).ok(), Some(format!("\"{}\"", "0".repeat(40,),)), "Error serializing the nil id",);
+
+    let binary = bincode::serialize(&id,).expect("Error serializing to bincode");
+    assert_eq!(&binary[8..], id.as_bytes(), "Error serializing as bytes",);
+    assert_eq!(bincode::deserialize::<DocumentId>(&binary,).ok(), Some(id), "Error round tripping through bincode",);
+
+    let parse = |json: &str,| serde_json::from_str::<DocumentId>(json,).map_err(|e,| e.to_string(),);
+    assert_eq!(parse(&format!("\"{}\"", "AB".repeat(20,),),), Ok(id), "Error deserializing upper case",);
+    assert_eq!(parse("\"q6urq6urq6urq6urq6urq6urq6s=\"",), Ok(id), "Error deserializing base64",);
+    assert_eq!(parse("\"q6urq6urq6urq6urq6urq6urq6s\"",), Ok(id), "Error deserializing unpadded base64",);
+    assert_eq!(parse(&serde_json::to_string(&[0xab; 20],).unwrap(),), Ok(id), "Error deserializing bytes",);
+
+    let bad = "ab".repeat(19,) + "zz";
+    assert!(parse(&format!("\"{}\"", bad,),).unwrap_err().contains(&bad,), "Error naming a bad hex id",);
+    assert!(parse("\"q6urq6urq6urq6urq6urq6urq6t=\"",).unwrap_err().contains("q6urq6urq6urq6urq6urq6urq6t=",), "Error naming a bad base64 id",);
+    assert!(parse("\"short\"",).unwrap_err().contains("short",), "Error naming a short id",);
+    assert!(parse("[1,2,3]",).is_err(), "Error deserializing too few bytes",);
+    assert!(parse(&serde_json::to_string(&[1; 21],).unwrap(),).is_err(), "Error deserializing too many bytes",);
+  }
+  #[test]
+  fn test_typed_id() {
+    let id = DocumentId::from_bytes([0xab; 20],);
+    let card = CardId::from(id,);
+    assert_eq!(*card, id, "Error dereferencing a card id",);
+    assert_eq!(DocumentId::from(card,), id, "Error converting a card id",);
+    assert_eq!(TierId::from(ListId::from(TierId::from(id,),),), TierId::from(id,), "Error converting a list id",);
+    assert_eq!(format!("{:?}", card,), format!("CardId({})", id,), "Error debugging a card id",);
+    assert_eq!(card.to_string().parse(), Ok(card), "Error round tripping a card id",);
+    assert_eq!(serde_json::to_string(&card,).ok(), serde_json::to_string(&id,).ok(), "Error serializing a card id",);
+  }
+  #[test]
+  fn test_digest() {
+    assert_eq!(DocumentId::from_bytes(sha1(b"",),).to_string(), "da39a3ee5e6b4b0d3255bfef95601890afd80709", "Error digesting an empty message",);
+    assert_eq!(DocumentId::from_bytes(sha1(b"abc",),).to_string(), "a9993e364706816aba3e25717850c26c9cd0d89d", "Error digesting a message",);
+    assert_eq!(
+      DocumentId::from_bytes(sha1(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",),).to_string(),
+      "84983e441c3bd26ebaae4aa1f95129e5e54670f1",
+      "Error digesting a message over a block",
+    );
+
+    //The derivation must not change between runs or releases.
+    assert_eq!(DocumentId::digest(&[],).to_string(), "da39a3ee5e6b4b0d3255bfef95601890afd80709", "Error deriving from nothing",);
+    assert_eq!(DocumentId::digest(&[b"ab", b"c",],).to_string(), "c35848f85e6e96b7b7853f66c7b925166315bc18", "Error deriving from parts",);
+    assert_eq!(DocumentId::digest(&[b"a", b"bc",],).to_string(), "afbd418c7c14fa1e8c68b826bb689a4deddc2ac4", "Error deriving from split parts",);
+    assert_eq!(DocumentId::digest(&[b"",],).to_string(), "05fe405753166f125559e7c9ac558654f107c7e9", "Error deriving from an empty part",);
+  }
+  #[cfg(feature = "rand",)]
+  #[test]
+  fn test_random() {
+    use rand::{SeedableRng, rngs::StdRng,};
+    use std::collections::HashSet;
+
+    let ids = (0..10_000).map(|_,| DocumentId::random(),).collect::<HashSet<_>>();
+    assert_eq!(ids.len(), 10_000, "Error generating duplicate ids",);
+
+    let seeded = |seed,| {
+      let mut rng = StdRng::seed_from_u64(seed,);
+      (0..4).map(|_,| DocumentId::random_with(&mut rng,),).collect::<Vec<_>>()
+    };
+    assert_eq!(seeded(7,), seeded(7,), "Error reproducing seeded ids",);
+    assert_ne!(seeded(7,), seeded(8,), "Error seeding ids",);
+  }
+}
@@ -0,0 +1,477 @@
+//! Defines a document collection stored in a DynamoDB table.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, Document, Envelope, CollectionError, ErrorKind, TierListCollection, RevisionedCollection, RetryPolicy, Timer,};
+use futures::future::LocalBoxFuture;
+use aws_sdk_dynamodb::{
+  Client,
+  error::{SdkError, ProvideErrorMetadata, DisplayErrorContext, BuildError,},
+  primitives::Blob,
+  types::{AttributeValue, KeysAndAttributes, PutRequest, WriteRequest,},
+};
+use base64::{Engine, engine::general_purpose::STANDARD,};
+use std::{
+  fmt,
+  error::Error,
+  convert::TryInto,
+  collections::{HashMap, hash_map::RandomState,},
+  hash::{BuildHasher, Hasher,},
+  borrow::Borrow,
+  sync::Arc,
+};
+
+/// The partition key attribute storing the base64 encoded identifier of a document.
+const ID: &str = "id";
+/// The attribute storing the body of a document.
+const BODY: &str = "body";
+/// The attribute storing the revision of a document.
+const REV: &str = "rev";
+/// The most keys DynamoDB accepts in a single `BatchGetItem` request.
+const GET_BATCH_LEN: usize = 100;
+/// The most requests DynamoDB accepts in a single `BatchWriteItem` request.
+const WRITE_BATCH_LEN: usize = 25;
+
+/// The errors returned by a `DynamoCollection`.
+#[derive(PartialEq, Eq, Clone, Debug,)]
+pub enum DynamoError {
+  /// The document does not exist.
+  NotFound(DocumentId),
+  /// The revision of the document was not the expected revision.
+  Conflict(DocumentId),
+  /// The stored item was missing attributes or they had the wrong type.
+  Corrupt(DocumentId),
+  /// DynamoDB did not process the document in a batch before the retries ran out.
+  Unprocessed(DocumentId),
+  /// A request to DynamoDB failed.
+  Request(ErrorKind, String),
+}
+
+impl fmt::Display for DynamoError {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      DynamoError::NotFound(id) => write!(fmt, "could not find a document with the id {:?}", id,),
+      DynamoError::Conflict(id) => write!(fmt, "the document with the id {:?} has changed", id,),
+      DynamoError::Corrupt(id) => write!(fmt, "the item for the document with the id {:?} is malformed", id,),
+      DynamoError::Unprocessed(id) => write!(fmt, "the document with the id {:?} was not processed", id,),
+      DynamoError::Request(_, message) => write!(fmt, "the request to DynamoDB failed: {}", message,),
+    }
+  }
+}
+
+impl Error for DynamoError {}
+
+impl CollectionError for DynamoError {
+  #[inline]
+  fn kind(&self,) -> ErrorKind {
+    match self {
+      DynamoError::NotFound(_) => ErrorKind::NotFound,
+      DynamoError::Conflict(_) => ErrorKind::Conflict,
+      DynamoError::Corrupt(_) => ErrorKind::Corrupt,
+      DynamoError::Unprocessed(_) => ErrorKind::Transient,
+      DynamoError::Request(kind, _) => *kind,
+    }
+  }
+}
+
+/// Classifies the error code of a failed DynamoDB request.
+fn code_kind(code: Option<&str>,) -> ErrorKind {
+  match code {
+    Some("ConditionalCheckFailedException" | "TransactionConflictException",) => ErrorKind::Conflict,
+    Some(
+      "ProvisionedThroughputExceededException" | "RequestLimitExceeded" | "ThrottlingException"
+      | "InternalServerError" | "ServiceUnavailable" | "LimitExceededException",
+    ) => ErrorKind::Transient,
+    _ => ErrorKind::Permanent,
+  }
+}
+
+/// Converts the error from a failed DynamoDB request.
+fn request_error<E, R,>(error: SdkError<E, R,>,) -> DynamoError
+  where E: 'static + Error + ProvideErrorMetadata,
+    R: 'static + fmt::Debug, {
+  let kind = match &error {
+    SdkError::TimeoutError(_) => ErrorKind::Timeout,
+    SdkError::DispatchFailure(_) | SdkError::ResponseError(_) => ErrorKind::Transient,
+    SdkError::ServiceError(_) => code_kind(error.code(),),
+    _ => ErrorKind::Permanent,
+  };
+
+  DynamoError::Request(kind, DisplayErrorContext(&error,).to_string(),)
+}
+
+/// Converts the error from building a malformed request.
+fn build_error(error: BuildError,) -> DynamoError {
+  DynamoError::Request(ErrorKind::Permanent, error.to_string(),)
+}
+
+/// Gets the key of the item storing a document.
+fn key(id: &DocumentId,) -> HashMap<String, AttributeValue> {
+  HashMap::from([(ID.to_owned(), AttributeValue::S(STANDARD.encode(id,),),),],)
+}
+
+/// Gets the identifier of the document an item or key belongs to.
+fn item_id(item: &HashMap<String, AttributeValue>,) -> Option<DocumentId> {
+  let id = item.get(ID,)?.as_s().ok()?;
+
+  STANDARD.decode(id,).ok()?.try_into().ok().map(DocumentId::from_bytes,)
+}
+
+/// Returns a new random revision.
+fn new_revision() -> u64 { RandomState::new().build_hasher().finish() }
+
+/// Gets the item storing a document at a revision.
+fn item(document: &Envelope, revision: u64,) -> HashMap<String, AttributeValue> {
+  let mut item = key(&document.id,);
+
+  item.insert(BODY.to_owned(), AttributeValue::B(Blob::new(document.body.clone(),),),);
+  item.insert(REV.to_owned(), AttributeValue::N(revision.to_string(),),);
+  item
+}
+
+/// Gets the document and revision stored in an item.
+fn document(id: DocumentId, item: &HashMap<String, AttributeValue>,) -> Result<(Envelope, u64,), DynamoError> {
+  let body = item.get(BODY,).and_then(|body,| body.as_b().ok(),)
+    .ok_or(DynamoError::Corrupt(id,),)?;
+  let revision = item.get(REV,).and_then(|rev,| rev.as_n().ok()?.parse().ok(),)
+    .ok_or(DynamoError::Corrupt(id,),)?;
+
+  Ok((Envelope::new(id, body.clone().into_inner(),), revision,))
+}
+
+/// A `TierListCollection` of `Envelope`s stored in a DynamoDB table.
+/// 
+/// Each document is stored as an item whose partition key `id` is the base64 encoded
+/// identifier of the document, with the body stored in `body` and its revision in `rev`.
+/// The table must have a string partition key named `id` and no sort key.
+/// 
+/// Reads are strongly consistent. Batch operations are split into requests DynamoDB
+/// accepts and keys or items which DynamoDB leaves unprocessed are retried according to
+/// the `RetryPolicy`; documents still unprocessed fail with `DynamoError::Unprocessed`.
+/// 
+/// Revisions are random tokens which change on every write, so conditional writes are
+/// checked by DynamoDB using a condition expression.
+#[derive(Clone, Debug,)]
+pub struct DynamoCollection<Tm,> {
+  /// The client to send requests with.
+  client: Client,
+  /// The name of the table storing the documents.
+  table: Arc<str>,
+  /// The timer used to wait between retries of unprocessed documents.
+  timer: Tm,
+  /// How to retry unprocessed documents.
+  policy: RetryPolicy,
+}
+
+impl<Tm,> DynamoCollection<Tm,> {
+  /// Returns a new `DynamoCollection`.
+  /// 
+  /// # Params
+  /// 
+  /// client --- The client to send requests with.  
+  /// table --- The name of the table storing the documents.  
+  /// timer --- The timer used to wait between retries of unprocessed documents.  
+  /// policy --- How to retry unprocessed documents.  
+  pub fn new(client: Client, table: &str, timer: Tm, policy: RetryPolicy,) -> Self {
+    Self { client, table: table.into(), timer, policy, }
+  }
+  /// Gets the client requests are sent with.
+  #[inline]
+  pub const fn get_client(&self,) -> &Client { &self.client }
+  /// Gets the name of the table storing the documents.
+  #[inline]
+  pub fn get_table(&self,) -> &str { &self.table }
+  /// Gets how unprocessed documents are retried.
+  #[inline]
+  pub const fn get_policy(&self,) -> &RetryPolicy { &self.policy }
+}
+
+impl<Tm,> DynamoCollection<Tm,>
+  where Tm: Timer, {
+  /// Reads documents in a single batch, retrying unprocessed keys.
+  /// 
+  /// # Params
+  /// 
+  /// ids --- The distinct identifiers of at most `GET_BATCH_LEN` documents.  
+  /// found --- The documents read.  
+  async fn get_batch(&self, ids: &[DocumentId], found: &mut HashMap<DocumentId, Result<Envelope, DynamoError>>,) -> Result<(), DynamoError> {
+    let mut keys = ids.iter().map(key,).collect::<Vec<_>>();
+    let mut attempt = 1;
+
+    loop {
+      let request = KeysAndAttributes::builder().set_keys(Some(keys,),).consistent_read(true,).build()
+        .map_err(build_error,)?;
+      let output = self.client.batch_get_item().request_items(&*self.table, request,).send().await
+        .map_err(request_error,)?;
+
+      for item in output.responses().and_then(|responses,| responses.get(&*self.table,),).into_iter().flatten() {
+        let id = item_id(item,).ok_or_else(|| DynamoError::Request(ErrorKind::Corrupt, "an item without an id was returned".to_owned(),),)?;
+
+        found.insert(id, document(id, item,).map(|(document, _,),| document,),);
+      }
+
+      keys = output.unprocessed_keys().and_then(|unprocessed,| unprocessed.get(&*self.table,),)
+        .map(|unprocessed,| unprocessed.keys().to_vec(),).unwrap_or_default();
+      if keys.is_empty() { return Ok(()) }
+      if attempt >= self.policy.attempts {
+        for id in keys.iter().filter_map(item_id,) { found.insert(id, Err(DynamoError::Unprocessed(id,)),); }
+        return Ok(())
+      }
+
+      self.timer.sleep(self.policy.delay(attempt,),).await;
+      attempt += 1;
+    }
+  }
+  /// Writes documents in a single batch, retrying unprocessed items.
+  /// 
+  /// # Params
+  /// 
+  /// documents --- At most `WRITE_BATCH_LEN` documents with distinct identifiers.  
+  /// written --- The result of writing each document.  
+  async fn write_batch(&self, documents: &[&Envelope], written: &mut HashMap<DocumentId, Result<(), DynamoError>>,) {
+    let mut requests = Vec::with_capacity(documents.len(),);
+
+    for document in documents {
+      match PutRequest::builder().set_item(Some(item(document, new_revision(),),),).build() {
+        Ok(put) => requests.push(WriteRequest::builder().put_request(put,).build(),),
+        Err(e) => { written.insert(document.id, Err(build_error(e,)),); },
+      }
+    }
+
+    let mut attempt = 1;
+    while !requests.is_empty() {
+      let ids = requests.iter()
+        .filter_map(|request,| item_id(request.put_request()?.item(),),)
+        .collect::<Vec<_>>();
+      let output = match self.client.batch_write_item().request_items(&*self.table, requests,).send().await {
+        Ok(output) => output,
+        Err(e) => {
+          let e = request_error(e,);
+
+          for id in ids { written.insert(id, Err(e.clone()),); }
+          return
+        },
+      };
+
+      requests = output.unprocessed_items().and_then(|unprocessed,| unprocessed.get(&*self.table,),)
+        .cloned().unwrap_or_default();
+      let unprocessed = requests.iter()
+        .filter_map(|request,| item_id(request.put_request()?.item(),),)
+        .collect::<Vec<_>>();
+      for id in ids {
+        written.insert(id, if unprocessed.contains(&id,) { Err(DynamoError::Unprocessed(id,)) } else { Ok(()) },);
+      }
+      if requests.is_empty() || attempt >= self.policy.attempts { return }
+
+      self.timer.sleep(self.policy.delay(attempt,),).await;
+      attempt += 1;
+    }
+  }
+  /// Reads a document and its revision.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the document.  
+  async fn get_item(&self, id: DocumentId,) -> Result<(Envelope, u64,), DynamoError> {
+    let output = self.client.get_item().table_name(&*self.table,).set_key(Some(key(&id,),),).consistent_read(true,).send().await
+      .map_err(request_error,)?;
+
+    document(id, output.item().ok_or(DynamoError::NotFound(id,),)?,)
+  }
+  /// Writes a document, if the condition holds, and returns its new revision.
+  /// 
+  /// # Params
+  /// 
+  /// document --- The document to write.  
+  /// expected --- `None` for an unconditional write, or `Some(None)` if the document  
+  /// must not exist, or `Some(Some(revision))` if the document must be at `revision`.
+  async fn put_item(&self, document: Envelope, expected: Option<Option<u64>>,) -> Result<u64, DynamoError> {
+    let revision = new_revision();
+    let put = self.client.put_item().table_name(&*self.table,).set_item(Some(item(&document, revision,),),);
+    let put = match expected {
+      None => put,
+      Some(None) => put.condition_expression("attribute_not_exists(#id)",)
+        .expression_attribute_names("#id", ID,),
+      Some(Some(expected)) => put.condition_expression("#rev = :rev",)
+        .expression_attribute_names("#rev", REV,)
+        .expression_attribute_values(":rev", AttributeValue::N(expected.to_string(),),),
+    };
+
+    match put.send().await {
+      Ok(_) => Ok(revision),
+      Err(e) => match request_error(e,) {
+        DynamoError::Request(ErrorKind::Conflict, _) if expected.is_some() => Err(DynamoError::Conflict(document.id,)),
+        e => Err(e),
+      },
+    }
+  }
+}
+
+impl<Tm,> TierListCollection for DynamoCollection<Tm,>
+  where Tm: 'static + Timer + Clone, {
+  type Document = Envelope;
+  type Error = DynamoError;
+  type GetBatchDocuments = LocalBoxFuture<'static, Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>>;
+  type GetDocument = LocalBoxFuture<'static, Result<Self::Document, Self::Error>>;
+  type WriteBatchDocuments = LocalBoxFuture<'static, Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>;
+  type WriteDocument = LocalBoxFuture<'static, Result<(), Self::Error>>;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    let ids = ids.into_iter().map(|id,| *id.borrow(),).collect::<Vec<_>>();
+    let coll = self.clone();
+
+    Box::pin(async move {
+      //DynamoDB rejects batches which request the same key twice.
+      let mut distinct = ids.clone();
+      distinct.sort_unstable();
+      distinct.dedup();
+
+      let mut found = HashMap::with_capacity(distinct.len(),);
+      for chunk in distinct.chunks(GET_BATCH_LEN,) { coll.get_batch(chunk, &mut found,).await? }
+
+      Ok(ids.iter().map(|id,| found.get(id,).cloned().unwrap_or(Err(DynamoError::NotFound(*id,)),),).collect())
+    },)
+  }
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    let (coll, id,) = (self.clone(), *id,);
+
+    Box::pin(async move { coll.get_item(id,).await.map(|(document, _,),| document,) },)
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let documents = documents.into_iter().map(|doc,| doc.borrow().clone(),).collect::<Vec<_>>();
+    let coll = self.clone();
+
+    Box::pin(async move {
+      //DynamoDB rejects batches which write the same key twice so only the last write
+      //of each document is sent.
+      let mut last = HashMap::with_capacity(documents.len(),);
+      for document in &documents { last.insert(*document.get_id(), document,); }
+      let distinct = last.into_values().collect::<Vec<_>>();
+
+      let mut written = HashMap::with_capacity(distinct.len(),);
+      for chunk in distinct.chunks(WRITE_BATCH_LEN,) { coll.write_batch(chunk, &mut written,).await }
+
+      let results = documents.iter()
+        .map(|document,| written.get(document.get_id(),).cloned().unwrap_or(Err(DynamoError::Unprocessed(document.id,)),),)
+        .collect::<Vec<_>>();
+
+      if results.iter().all(Result::is_ok,) { Ok(Ok(())) } else { Ok(Err(results)) }
+    },)
+  }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    let (coll, document,) = (self.clone(), document.borrow().clone(),);
+
+    Box::pin(async move { coll.put_item(document, None,).await.map(|_,| (),) },)
+  }
+}
+
+/// Revisions are random tokens stored with each item.
+impl<Tm,> RevisionedCollection for DynamoCollection<Tm,>
+  where Tm: 'static + Timer + Clone, {
+  type Revision = u64;
+  type GetDocumentRev = LocalBoxFuture<'static, Result<(Self::Document, Self::Revision,), Self::Error>>;
+  type WriteDocumentRev = LocalBoxFuture<'static, Result<Self::Revision, Self::Error>>;
+
+  fn get_document_rev(&self, id: &DocumentId,) -> Self::GetDocumentRev {
+    let (coll, id,) = (self.clone(), *id,);
+
+    Box::pin(async move { coll.get_item(id,).await },)
+  }
+  fn write_document_rev<T,>(&self, document: &T, expected: Option<&Self::Revision>,) -> Self::WriteDocumentRev
+    where T: Borrow<Self::Document>, {
+    let (coll, document, expected,) = (self.clone(), document.borrow().clone(), expected.copied(),);
+
+    Box::pin(async move { coll.put_item(document, Some(expected,),).await },)
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use aws_sdk_dynamodb::{
+    Config,
+    config::{BehaviorVersion, Region, Credentials,},
+    types::{AttributeDefinition, KeySchemaElement, KeyType, ScalarAttributeType, BillingMode,},
+  };
+
+  #[test]
+  fn test_items() {
+    let doc = Envelope::new(DocumentId::from_bytes([0xfe; 20]), b"body".to_vec(),);
+    let item = item(&doc, 7,);
+
+    assert_eq!(item.get(ID,), Some(&AttributeValue::S(STANDARD.encode(DocumentId::from_bytes([0xfe; 20]),),)), "Error encoding key",);
+    assert_eq!(item_id(&item,), Some(doc.id), "Error decoding key",);
+    assert_eq!(document(doc.id, &item,), Ok((doc.clone(), 7,)), "Error decoding item",);
+    assert_eq!(document(doc.id, &key(&doc.id,),), Err(DynamoError::Corrupt(doc.id,)), "Error decoding malformed item",);
+
+    let short = HashMap::from([(ID.to_owned(), AttributeValue::S(STANDARD.encode([1; 19],),),),],);
+    assert_eq!(item_id(&short,), None, "Error decoding short key",);
+  }
+  #[test]
+  fn test_code_kind() {
+    assert_eq!(code_kind(Some("ConditionalCheckFailedException",),), ErrorKind::Conflict, "Error classifying condition failure",);
+    assert_eq!(code_kind(Some("ProvisionedThroughputExceededException",),), ErrorKind::Transient, "Error classifying throttling",);
+    assert_eq!(code_kind(Some("ResourceNotFoundException",),), ErrorKind::Permanent, "Error classifying missing table",);
+    assert_eq!(code_kind(None,), ErrorKind::Permanent, "Error classifying unknown error",);
+    assert!(DynamoError::Unprocessed(DocumentId::from_bytes([1; 20]),).is_retryable(), "Error unprocessed documents are not retryable",);
+  }
+  /// Runs against DynamoDB Local when `GALILEO_DYNAMO_ENDPOINT` is set, such as
+  /// `http://localhost:8000`.
+  #[test]
+  fn test_dynamo_local() {
+    let endpoint = match std::env::var("GALILEO_DYNAMO_ENDPOINT",) {
+      Ok(endpoint) => endpoint,
+      Err(_) => return,
+    };
+    let config = Config::builder()
+      .behavior_version(BehaviorVersion::latest(),)
+      .region(Region::new("us-east-1",),)
+      .endpoint_url(endpoint,)
+      .credentials_provider(Credentials::new("local", "local", None, None, "galileo",),)
+      .build();
+    let client = Client::from_conf(config,);
+    let table = format!("galileo-{:x}", new_revision(),);
+    let runtime = tokio::runtime::Runtime::new()
+      .expect("Error starting runtime");
+
+    runtime.block_on(async {
+      client.create_table().table_name(&table,)
+        .attribute_definitions(AttributeDefinition::builder().attribute_name(ID,).attribute_type(ScalarAttributeType::S,).build().unwrap(),)
+        .key_schema(KeySchemaElement::builder().attribute_name(ID,).key_type(KeyType::Hash,).build().unwrap(),)
+        .billing_mode(BillingMode::PayPerRequest,)
+        .send().await
+        .expect("Error creating table");
+
+      let coll = DynamoCollection::new(client.clone(), &table, tokio::time::sleep, RetryPolicy { jitter: false, ..RetryPolicy::default() },);
+      let docs = (0..130u8).map(|i,| Envelope::new(DocumentId::from_bytes([i; 20]), vec![i; i as usize],),).collect::<Vec<_>>();
+
+      //Batches larger than a single request are chunked.
+      assert_eq!(coll.write_documents(&docs,).await, Ok(Ok(())), "Error writing documents",);
+      let mut ids = docs.iter().map(|doc,| doc.id,).collect::<Vec<_>>();
+      ids.extend([DocumentId::from_bytes([0; 20]), DocumentId::from_bytes([200; 20]),],);
+      let mut expected = docs.iter().cloned().map(Ok,).collect::<Vec<_>>();
+      expected.extend([Ok(docs[0].clone()), Err(DynamoError::NotFound(DocumentId::from_bytes([200; 20]),)),],);
+      assert_eq!(coll.get_documents(&ids,).await, Ok(expected), "Error reading documents",);
+
+      //Conditional writes check the revision.
+      let (doc, revision,) = coll.get_document_rev(&DocumentId::from_bytes([1; 20]),).await
+        .expect("Error reading revision");
+      let updated = Envelope::new(doc.id, b"updated".to_vec(),);
+      let next = coll.write_document_rev(&updated, Some(&revision),).await
+        .expect("Error writing revision");
+      assert_eq!(coll.write_document_rev(&updated, Some(&revision),).await, Err(DynamoError::Conflict(doc.id,)), "Error detecting conflict",);
+      assert_eq!(coll.write_document_rev(&updated, None,).await, Err(DynamoError::Conflict(doc.id,)), "Error detecting existing document",);
+      assert_eq!(coll.get_document_rev(&doc.id,).await, Ok((updated, next,)), "Error reading updated document",);
+      assert_eq!(coll.get_document(&DocumentId::from_bytes([200; 20]),).await, Err(DynamoError::NotFound(DocumentId::from_bytes([200; 20]),)), "Error reading missing document",);
+
+      client.delete_table().table_name(&table,).send().await
+        .expect("Error deleting table");
+    },);
+  }
+}
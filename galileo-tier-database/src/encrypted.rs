@@ -0,0 +1,265 @@
+//! Defines a wrapper which encrypts the bodies of the documents in a collection.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, Envelope, CollectionError, ErrorKind, TierListCollection,};
+use futures::{
+  Future, FutureExt, TryFutureExt,
+  future::{Map, MapErr,},
+};
+use chacha20poly1305::{
+  XChaCha20Poly1305, XNonce, Key,
+  aead::{Aead, AeadCore, KeyInit, OsRng, Payload,},
+};
+use std::{fmt, error::Error, borrow::Borrow, rc::Rc,};
+
+/// The length of the nonce at the start of every encrypted body.
+const NONCE_LEN: usize = 24;
+
+/// The errors returned by an `EncryptedCollection`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum EncryptedError<E,> {
+  /// The stored body of the document could not be decrypted or failed authentication.
+  Corrupt(DocumentId),
+  /// An error from the inner collection.
+  Inner(E),
+}
+
+impl<E,> fmt::Display for EncryptedError<E,>
+  where E: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      EncryptedError::Corrupt(id) => write!(fmt, "the document with the id {:?} could not be decrypted", id,),
+      EncryptedError::Inner(e) => e.fmt(fmt,),
+    }
+  }
+}
+
+impl<E,> Error for EncryptedError<E,>
+  where E: Error, {}
+
+impl<E,> CollectionError for EncryptedError<E,>
+  where E: CollectionError, {
+  #[inline]
+  fn kind(&self,) -> ErrorKind {
+    match self {
+      EncryptedError::Corrupt(_) => ErrorKind::Corrupt,
+      EncryptedError::Inner(e) => e.kind(),
+    }
+  }
+  #[inline]
+  fn is_retryable(&self,) -> bool {
+    match self {
+      EncryptedError::Corrupt(_) => false,
+      EncryptedError::Inner(e) => e.is_retryable(),
+    }
+  }
+}
+
+/// A `TierListCollection` of `Envelope`s which encrypts the body of each document when it
+/// is written and decrypts it when it is read.
+/// 
+/// Bodies are encrypted with XChaCha20-Poly1305 under a random nonce which is stored in
+/// front of the ciphertext. The identifier of each document is authenticated with its
+/// body, so a body cannot be moved to another document, but is stored in plaintext so
+/// that the links between documents can still be followed; anyone able to read the
+/// inner collection can see which documents exist and how they are linked.
+pub struct EncryptedCollection<Coll,> {
+  /// The collection storing the encrypted documents.
+  inner: Coll,
+  /// The cipher encrypting the bodies.
+  cipher: Rc<XChaCha20Poly1305>,
+}
+
+impl<Coll,> EncryptedCollection<Coll,> {
+  /// Returns a new `EncryptedCollection`.
+  /// 
+  /// # Params
+  /// 
+  /// inner --- The collection to store the encrypted documents in.  
+  /// key --- The key to encrypt the documents with.  
+  pub fn new(inner: Coll, key: &[u8; 32],) -> Self {
+    Self { inner, cipher: Rc::new(XChaCha20Poly1305::new(Key::from_slice(key,),),), }
+  }
+  /// Gets the collection storing the encrypted documents.
+  #[inline]
+  pub const fn get_inner(&self,) -> &Coll { &self.inner }
+  /// Encrypts a document.
+  fn encrypt(&self, document: &Envelope,) -> Envelope {
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng,);
+    let ciphertext = self.cipher.encrypt(&nonce, Payload { msg: &document.body, aad: document.id.as_bytes(), },)
+      .expect("Error encrypting body");
+    let mut body = nonce.to_vec();
+
+    body.extend(ciphertext,);
+    Envelope::new(document.id, body,)
+  }
+}
+
+impl<Coll,> Clone for EncryptedCollection<Coll,>
+  where Coll: Clone, {
+  #[inline]
+  fn clone(&self,) -> Self { Self { inner: self.inner.clone(), cipher: self.cipher.clone(), } }
+}
+
+/// The key is never printed.
+impl<Coll,> fmt::Debug for EncryptedCollection<Coll,>
+  where Coll: fmt::Debug, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    fmt.debug_struct("EncryptedCollection",)
+    .field("inner", &self.inner,)
+    .finish()
+  }
+}
+
+/// Decrypts a document read from the inner collection.
+fn decrypt<E,>(cipher: &XChaCha20Poly1305, document: Envelope,) -> Result<Envelope, EncryptedError<E,>> {
+  if document.body.len() < NONCE_LEN { return Err(EncryptedError::Corrupt(document.id,)) }
+
+  let (nonce, ciphertext,) = document.body.split_at(NONCE_LEN,);
+
+  cipher.decrypt(XNonce::from_slice(nonce,), Payload { msg: ciphertext, aad: document.id.as_bytes(), },)
+  .map(|body,| Envelope::new(document.id, body,),)
+  .map_err(|_,| EncryptedError::Corrupt(document.id,),)
+}
+
+/// The result of a batch read through an `EncryptedCollection`.
+type BatchResult<E,> = Result<Vec<Result<Envelope, EncryptedError<E,>>>, EncryptedError<E,>>;
+/// The result of a batch write through an `EncryptedCollection`.
+type BatchWriteResult<E,> = Result<Result<(), Vec<Result<(), EncryptedError<E,>>>>, EncryptedError<E,>>;
+
+/// Wraps the errors of a batch write.
+fn batch_write_error<E,>(res: Result<Result<(), Vec<Result<(), E>>>, E>,) -> BatchWriteResult<E,> {
+  match res {
+    Ok(Ok(())) => Ok(Ok(())),
+    Ok(Err(results)) => Ok(Err(results.into_iter().map(|res,| res.map_err(EncryptedError::Inner,),).collect())),
+    Err(e) => Err(EncryptedError::Inner(e,)),
+  }
+}
+
+impl<Coll,> TierListCollection for EncryptedCollection<Coll,>
+  where Coll: TierListCollection<Document = Envelope>, {
+  type Document = Envelope;
+  type Error = EncryptedError<Coll::Error,>;
+  type GetBatchDocuments = Map<Coll::GetBatchDocuments, Box<dyn FnOnce(Result<Vec<Result<Envelope, Coll::Error>>, Coll::Error>,) -> BatchResult<Coll::Error,>>>;
+  type GetDocument = Map<Coll::GetDocument, Box<dyn FnOnce(Result<Envelope, Coll::Error>,) -> Result<Envelope, Self::Error>>>;
+  type WriteBatchDocuments = Map<Coll::WriteBatchDocuments, fn(Result<Result<(), Vec<Result<(), Coll::Error>>>, Coll::Error>,) -> BatchWriteResult<Coll::Error,>>;
+  type WriteDocument = MapErr<Coll::WriteDocument, fn(Coll::Error,) -> Self::Error>;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    let cipher = self.cipher.clone();
+
+    self.inner.get_documents(ids,).map(Box::new(move |res,| match res {
+      Ok(docs) => Ok(docs.into_iter()
+        .map(|doc,| decrypt(&cipher, doc.map_err(EncryptedError::Inner,)?,),)
+        .collect()),
+      Err(e) => Err(EncryptedError::Inner(e,)),
+    },),)
+  }
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    let cipher = self.cipher.clone();
+
+    self.inner.get_document(id,).map(Box::new(move |res,| decrypt(&cipher, res.map_err(EncryptedError::Inner,)?,),),)
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let documents = documents.into_iter().map(|doc,| self.encrypt(doc.borrow(),),).collect::<Vec<_>>();
+
+    self.inner.write_documents(&documents,).map(batch_write_error as fn(_,) -> _,)
+  }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    self.inner.write_document(&self.encrypt(document.borrow(),),).map_err(EncryptedError::Inner as fn(_,) -> _,)
+  }
+  #[inline]
+  fn exists(&self, id: &DocumentId,) -> impl Future<Output = Result<bool, Self::Error>> {
+    self.inner.exists(id,).map_err(EncryptedError::Inner,)
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::MemoryCollection;
+  use futures::executor::block_on;
+
+  const KEY: [u8; 32] = [7; 32];
+
+  #[test]
+  fn test_round_trip() {
+    let inner = MemoryCollection::<Envelope,>::new();
+    let coll = EncryptedCollection::new(inner.clone(), &KEY,);
+    let docs = (0..3u8).map(|i,| Envelope::new(DocumentId::from_bytes([i; 20]), format!("secret card {}", i,).into_bytes(),),)
+      .collect::<Vec<_>>();
+
+    block_on(async {
+      coll.write_document(&docs[0],).await
+        .expect("Error writing document");
+      coll.write_documents(&docs[1..],).await
+        .expect("Error writing documents")
+        .expect("Error writing documents");
+
+      let stored = inner.get_document(&docs[1].id,).await
+        .expect("Error reading stored document");
+      assert_eq!(stored.id, docs[1].id, "Error storing plaintext id",);
+      assert!(!stored.body.windows(docs[1].body.len(),).any(|window,| window == &docs[1].body[..],), "Error encrypting body",);
+
+      assert_eq!(coll.get_document(&docs[0].id,).await, Ok(docs[0].clone()), "Error reading document",);
+      assert_eq!(
+        coll.get_documents(docs.iter().map(|doc,| &doc.id,),).await,
+        Ok(docs.iter().cloned().map(Ok,).collect()),
+        "Error reading documents",
+      );
+    },);
+  }
+  #[test]
+  fn test_wrong_key() {
+    let inner = MemoryCollection::<Envelope,>::new();
+    let coll = EncryptedCollection::new(inner.clone(), &KEY,);
+    let other = EncryptedCollection::new(inner.clone(), &[8; 32],);
+    let doc = Envelope::new(DocumentId::from_bytes([1; 20]), b"secret".to_vec(),);
+
+    block_on(async {
+      coll.write_document(&doc,).await
+        .expect("Error writing document");
+
+      let res = other.get_document(&doc.id,).await;
+      assert_eq!(res, Err(EncryptedError::Corrupt(doc.id,)), "Error reading with the wrong key",);
+      assert_eq!(res.map_err(|e,| e.kind(),), Err(ErrorKind::Corrupt), "Error classifying wrong key",);
+    },);
+  }
+  #[test]
+  fn test_tampered() {
+    let inner = MemoryCollection::<Envelope,>::new();
+    let coll = EncryptedCollection::new(inner.clone(), &KEY,);
+    let doc = Envelope::new(DocumentId::from_bytes([1; 20]), b"secret".to_vec(),);
+
+    block_on(async {
+      coll.write_document(&doc,).await
+        .expect("Error writing document");
+
+      let mut stored = inner.get_document(&doc.id,).await
+        .expect("Error reading stored document");
+      *stored.body.last_mut().expect("Error empty body") ^= 1;
+      inner.write_document(&stored,).await
+        .expect("Error tampering with document");
+      assert_eq!(coll.get_document(&doc.id,).await, Err(EncryptedError::Corrupt(doc.id,)), "Error reading tampered document",);
+
+      //A body cannot be moved to another document.
+      stored.id = DocumentId::from_bytes([2; 20]);
+      *stored.body.last_mut().expect("Error empty body") ^= 1;
+      inner.write_document(&stored,).await
+        .expect("Error moving document");
+      assert_eq!(coll.get_document(&DocumentId::from_bytes([2; 20]),).await, Err(EncryptedError::Corrupt(DocumentId::from_bytes([2; 20]),)), "Error reading moved document",);
+      assert_eq!(coll.get_document(&DocumentId::from_bytes([1; 20]),).await, Err(EncryptedError::Corrupt(DocumentId::from_bytes([1; 20]),)), "Error reading tampered document",);
+
+      inner.write_document(&Envelope::new(DocumentId::from_bytes([3; 20]), vec![0; 4],),).await
+        .expect("Error writing short document");
+      assert_eq!(coll.get_document(&DocumentId::from_bytes([3; 20]),).await, Err(EncryptedError::Corrupt(DocumentId::from_bytes([3; 20]),)), "Error reading short document",);
+    },);
+  }
+}
@@ -0,0 +1,42 @@
+//! Defines a document which stores an opaque body of bytes.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, Document, EncodedLen,};
+
+/// A document whose body is stored as bytes, such as a serialised `TierDocument`.
+/// 
+/// Wrappers which transform the stored bytes, such as compression, operate on
+/// collections of `Envelope`s; the identifier is never transformed.
+#[derive(PartialEq, Eq, Clone, Hash, Debug,)]
+pub struct Envelope {
+  /// The identifier of the document.
+  pub id: DocumentId,
+  /// The body of the document.
+  pub body: Vec<u8>,
+}
+
+impl Envelope {
+  /// Returns a new `Envelope`.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the document.  
+  /// body --- The body of the document.  
+  #[inline]
+  pub const fn new(id: DocumentId, body: Vec<u8>,) -> Self { Self { id, body, } }
+}
+
+impl Document for Envelope {
+  #[inline]
+  fn get_id(&self,) -> &DocumentId { &self.id }
+}
+
+impl EncodedLen for Envelope {
+  #[inline]
+  fn encoded_len(&self,) -> usize {
+    //The identifier and the body.
+    20 + self.body.len()
+  }
+}
@@ -0,0 +1,141 @@
+//! Defines the classification of errors returned by document collections.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use std::{fmt, error::Error, convert::Infallible,};
+
+/// The kinds of error a collection can return.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug,)]
+pub enum ErrorKind {
+  /// The document does not exist.
+  NotFound,
+  /// The operation conflicted with a concurrent change to the document.
+  Conflict,
+  /// The operation failed but may succeed if it is tried again.
+  Transient,
+  /// The operation failed and will fail again if it is tried again.
+  Permanent,
+  /// The stored document could not be decoded.
+  Corrupt,
+  /// The operation did not complete in time.
+  Timeout,
+  /// The write would have replaced a different document with the same identifier.
+  Collision,
+}
+
+/// A trait for errors which can be classified so that generic code can respond to them.
+pub trait CollectionError {
+  /// Gets the kind of this error.
+  fn kind(&self,) -> ErrorKind;
+  /// Returns `true` if the operation which returned this error may succeed if it is
+  /// tried again unchanged.
+  #[inline]
+  fn is_retryable(&self,) -> bool { matches!(self.kind(), ErrorKind::Transient | ErrorKind::Timeout) }
+  /// Returns `true` if this error signals the document does not exist.
+  #[inline]
+  fn is_not_found(&self,) -> bool { self.kind() == ErrorKind::NotFound }
+}
+
+/// Errors from collections which cannot fail.
+impl CollectionError for Infallible {
+  #[inline]
+  fn kind(&self,) -> ErrorKind { match *self {} }
+}
+
+impl<E,> CollectionError for &E
+  where E: CollectionError, {
+  #[inline]
+  fn kind(&self,) -> ErrorKind { E::kind(*self,) }
+  #[inline]
+  fn is_retryable(&self,) -> bool { E::is_retryable(*self,) }
+}
+
+/// The error when getting a document from a collection and converting it to another
+/// type.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum ItemError<E, C,> {
+  /// Getting the document failed.
+  Fetch(E),
+  /// Converting the document failed.
+  Convert(C),
+}
+
+impl<E, C,> fmt::Display for ItemError<E, C,>
+  where E: fmt::Display,
+    C: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      ItemError::Fetch(e) => write!(fmt, "failed to get the document: {}", e,),
+      ItemError::Convert(e) => write!(fmt, "failed to convert the document: {}", e,),
+    }
+  }
+}
+
+impl<E, C,> Error for ItemError<E, C,>
+  where E: Error,
+    C: Error, {}
+
+/// A document which cannot be converted will fail to convert again so conversion
+/// failures are `Permanent`.
+impl<E, C,> CollectionError for ItemError<E, C,>
+  where E: CollectionError, {
+  #[inline]
+  fn kind(&self,) -> ErrorKind {
+    match self {
+      ItemError::Fetch(e) => e.kind(),
+      ItemError::Convert(_) => ErrorKind::Permanent,
+    }
+  }
+  #[inline]
+  fn is_retryable(&self,) -> bool {
+    match self {
+      ItemError::Fetch(e) => e.is_retryable(),
+      ItemError::Convert(_) => false,
+    }
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+
+  #[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+  enum MockError {
+    Missing,
+    Raced,
+    Reset,
+    Corrupt,
+  }
+
+  impl CollectionError for MockError {
+    fn kind(&self,) -> ErrorKind {
+      match self {
+        MockError::Missing => ErrorKind::NotFound,
+        MockError::Raced => ErrorKind::Conflict,
+        MockError::Reset => ErrorKind::Transient,
+        MockError::Corrupt => ErrorKind::Permanent,
+      }
+    }
+  }
+
+  #[test]
+  fn test_kinds() {
+    let errors = [
+      (MockError::Missing, ErrorKind::NotFound, false,),
+      (MockError::Raced, ErrorKind::Conflict, false,),
+      (MockError::Reset, ErrorKind::Transient, true,),
+      (MockError::Corrupt, ErrorKind::Permanent, false,),
+    ];
+
+    for (error, kind, retryable,) in errors.iter() {
+      assert_eq!(error.kind(), *kind, "Error classifying {:?}", error,);
+      assert_eq!(error.is_retryable(), *retryable, "Error retrying {:?}", error,);
+      assert_eq!(error.is_not_found(), *kind == ErrorKind::NotFound, "Error finding {:?}", error,);
+      assert_eq!((&error).kind(), *kind, "Error classifying &{:?}", error,);
+      assert_eq!(ItemError::<_, ()>::Fetch(*error,).kind(), *kind, "Error classifying fetch {:?}", error,);
+    }
+
+    assert_eq!(ItemError::<MockError, _>::Convert((),).kind(), ErrorKind::Permanent, "Error classifying conversion",);
+  }
+}
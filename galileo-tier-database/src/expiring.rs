@@ -0,0 +1,266 @@
+//! Defines a document collection whose documents expire.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{
+  DocumentId, Document, LinkedList, EncodedLen, CollectionError, ErrorKind, Clock,
+  TierListCollection, TierListCollectionScan, TransactionalCollection, Transaction,
+};
+use futures::{FutureExt, TryFutureExt, TryStreamExt, future::{self, Map, MapErr,},};
+use std::{fmt, error::Error, time::SystemTime, borrow::Borrow,};
+
+/// A document which expires at a point in time.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub struct Expiring<D,> {
+  /// The document.
+  pub document: D,
+  /// When the document expires; `None` if it never expires.
+  pub expires: Option<SystemTime>,
+}
+
+impl<D,> Expiring<D,> {
+  /// Returns a new `Expiring` document.
+  /// 
+  /// # Params
+  /// 
+  /// document --- The document.  
+  /// expires --- When the document expires.  
+  #[inline]
+  pub const fn new(document: D, expires: SystemTime,) -> Self {
+    Self { document, expires: Some(expires), }
+  }
+  /// Returns a new `Expiring` document which never expires.
+  /// 
+  /// # Params
+  /// 
+  /// document --- The document.  
+  #[inline]
+  pub const fn never(document: D,) -> Self { Self { document, expires: None, } }
+  /// Returns `true` if the document has expired.
+  /// 
+  /// # Params
+  /// 
+  /// now --- The current time.  
+  #[inline]
+  pub fn is_expired(&self, now: SystemTime,) -> bool {
+    self.expires.is_some_and(|expires,| expires <= now,)
+  }
+  /// Unwraps the document.
+  #[inline]
+  pub fn into_inner(self,) -> D { self.document }
+}
+
+impl<D,> Document for Expiring<D,>
+  where D: Document, {
+  #[inline]
+  fn get_id(&self,) -> &DocumentId { self.document.get_id() }
+}
+
+impl<D,> LinkedList for Expiring<D,>
+  where D: LinkedList, {
+  #[inline]
+  fn get_previous_id(&self,) -> Option<&DocumentId> { self.document.get_previous_id() }
+  #[inline]
+  fn get_next_id(&self,) -> Option<&DocumentId> { self.document.get_next_id() }
+}
+
+impl<D,> EncodedLen for Expiring<D,>
+  where D: EncodedLen, {
+  #[inline]
+  fn encoded_len(&self,) -> usize {
+    //The tag and the seconds and nanoseconds of the expiry.
+    self.document.encoded_len() + 1 + if self.expires.is_some() { 12 } else { 0 }
+  }
+}
+
+/// The errors returned by an `ExpiringCollection`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum ExpiringError<E,> {
+  /// The document has expired.
+  Expired(DocumentId),
+  /// An error from the inner collection.
+  Inner(E),
+}
+
+impl<E,> fmt::Display for ExpiringError<E,>
+  where E: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      ExpiringError::Expired(id) => write!(fmt, "the document with the id {:?} has expired", id,),
+      ExpiringError::Inner(e) => e.fmt(fmt,),
+    }
+  }
+}
+
+impl<E,> Error for ExpiringError<E,>
+  where E: Error, {}
+
+impl<E,> CollectionError for ExpiringError<E,>
+  where E: CollectionError, {
+  #[inline]
+  fn kind(&self,) -> ErrorKind {
+    match self {
+      ExpiringError::Expired(_) => ErrorKind::NotFound,
+      ExpiringError::Inner(e) => e.kind(),
+    }
+  }
+  #[inline]
+  fn is_retryable(&self,) -> bool {
+    match self {
+      ExpiringError::Expired(_) => false,
+      ExpiringError::Inner(e) => e.is_retryable(),
+    }
+  }
+}
+
+/// Fails a read if the document has expired.
+fn check_expiry<D, E,>(res: Result<Expiring<D,>, E>, now: SystemTime,) -> Result<Expiring<D,>, ExpiringError<E,>>
+  where D: Document, {
+  match res {
+    Ok(doc) => if doc.is_expired(now,) { Err(ExpiringError::Expired(*doc.get_id(),)) } else { Ok(doc) },
+    Err(e) => Err(ExpiringError::Inner(e,)),
+  }
+}
+
+/// A `TierListCollection` of `Expiring` documents which treats expired documents as
+/// missing.
+/// 
+/// Expired documents remain in the inner collection until they are overwritten or
+/// purged with `purge_expired`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub struct ExpiringCollection<Coll, Ck,> {
+  /// The collection storing the documents.
+  inner: Coll,
+  /// The clock used to check for expired documents.
+  clock: Ck,
+}
+
+impl<Coll, Ck,> ExpiringCollection<Coll, Ck,> {
+  /// Returns a new `ExpiringCollection`.
+  /// 
+  /// # Params
+  /// 
+  /// inner --- The collection storing the documents.  
+  /// clock --- The clock used to check for expired documents.  
+  #[inline]
+  pub const fn new(inner: Coll, clock: Ck,) -> Self { Self { inner, clock, } }
+  /// Gets the collection storing the documents.
+  #[inline]
+  pub const fn get_inner(&self,) -> &Coll { &self.inner }
+}
+
+impl<Coll, Ck, D,> ExpiringCollection<Coll, Ck,>
+  where Coll: TierListCollectionScan<Document = Expiring<D,>> + TransactionalCollection,
+    D: Document, {
+  /// Deletes every document which has expired from the inner collection and returns
+  /// the number of documents deleted.
+  /// 
+  /// # Params
+  /// 
+  /// now --- The time to check for expired documents at.  
+  pub async fn purge_expired(&self, now: SystemTime,) -> Result<usize, Coll::Error> {
+    let expired = self.inner.scan()
+      .try_filter_map(|doc,| future::ok(if doc.is_expired(now,) { Some(*doc.get_id()) } else { None },),)
+      .try_collect::<Vec<_>>().await?;
+
+    if expired.is_empty() { return Ok(0) }
+
+    let mut transaction = self.inner.begin();
+    for id in expired.iter() { transaction.stage_delete(id,) }
+    transaction.commit().await?;
+
+    Ok(expired.len())
+  }
+}
+
+impl<Coll, Ck, D,> TierListCollection for ExpiringCollection<Coll, Ck,>
+  where Coll: TierListCollection<Document = Expiring<D,>>,
+    Ck: Clock,
+    D: Document, {
+  type Document = Expiring<D,>;
+  type Error = ExpiringError<Coll::Error,>;
+  type GetBatchDocuments = Map<Coll::GetBatchDocuments, Box<dyn FnOnce(Result<Vec<Result<Self::Document, Coll::Error>>, Coll::Error>,) -> Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>>>;
+  type GetDocument = Map<Coll::GetDocument, Box<dyn FnOnce(Result<Self::Document, Coll::Error>,) -> Result<Self::Document, Self::Error>>>;
+  type WriteBatchDocuments = Map<Coll::WriteBatchDocuments, fn(Result<Result<(), Vec<Result<(), Coll::Error>>>, Coll::Error>,) -> Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>;
+  type WriteDocument = MapErr<Coll::WriteDocument, fn(Coll::Error,) -> Self::Error>;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    let now = self.clock.now();
+
+    self.inner.get_documents(ids,)
+    .map(Box::new(move |res,| match res {
+      Ok(docs) => Ok(docs.into_iter().map(|doc,| check_expiry(doc, now,),).collect()),
+      Err(e) => Err(ExpiringError::Inner(e,)),
+    },),)
+  }
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    let now = self.clock.now();
+
+    self.inner.get_document(id,)
+    .map(Box::new(move |res,| check_expiry(res, now,),),)
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    /// Wraps the errors of a batch write.
+    fn wrap<E,>(res: Result<Result<(), Vec<Result<(), E>>>, E>,) -> Result<Result<(), Vec<Result<(), ExpiringError<E,>>>>, ExpiringError<E,>> {
+      match res {
+        Ok(Ok(())) => Ok(Ok(())),
+        Ok(Err(results)) => Ok(Err(results.into_iter().map(|res,| res.map_err(ExpiringError::Inner,),).collect())),
+        Err(e) => Err(ExpiringError::Inner(e,)),
+      }
+    }
+
+    self.inner.write_documents(documents,).map(wrap as fn(_,) -> _,)
+  }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    self.inner.write_document(document,).map_err(ExpiringError::Inner as fn(_,) -> _,)
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{TierId, TierMeta, MemoryCollection,};
+  use futures::executor::block_on;
+  use std::{time::Duration, cell::Cell, rc::Rc,};
+
+  #[test]
+  fn test_expiry() {
+    let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000,);
+    let now = Rc::new(Cell::new(start,),);
+    let inner = MemoryCollection::new();
+    let coll = ExpiringCollection::new(inner.clone(), { let now = now.clone(); move || now.get() },);
+    let id = DocumentId::from_bytes([1; 20]);
+    let session = Expiring::new(TierMeta::new(id.into(), (None, id.into(), id.into(),), None, None,), start + Duration::from_secs(60,),);
+    let forever = Expiring::never(TierMeta::new(TierId::from_bytes([2; 20]), (None, id.into(), id.into(),), None, None,),);
+
+    block_on(async {
+      coll.write_documents([&session, &forever,],).await
+        .expect("Error writing documents")
+        .expect("Error writing documents");
+      assert_eq!(coll.get_document(&id,).await, Ok(session), "Error reading session before expiry",);
+
+      now.set(start + Duration::from_secs(60,),);
+      let expired = coll.get_document(&id,).await
+        .expect_err("Error reading expired session");
+      assert!(expired.is_not_found(), "Error expired session is not missing",);
+      assert_eq!(coll.exists(&id,).await, Ok(false), "Error expired session exists",);
+      assert_eq!(
+        coll.get_documents([&id, forever.get_id(),],).await,
+        Ok(vec![Err(ExpiringError::Expired(id,)), Ok(forever),]),
+        "Error reading documents after expiry",
+      );
+      assert_eq!(inner.len(), 2, "Error expired session was deleted before purging",);
+
+      assert_eq!(coll.purge_expired(now.get(),).await, Ok(1), "Error purging expired session",);
+      assert_eq!(inner.len(), 1, "Error expired session was not deleted",);
+      assert!(inner.get_document(&id,).await.is_err(), "Error expired session is in the inner collection",);
+      assert_eq!(coll.purge_expired(now.get(),).await, Ok(0), "Error purging nothing",);
+    },);
+  }
+}
@@ -0,0 +1,495 @@
+//! Defines a portable snapshot of a tier list which can be exported from and imported
+//! into any collection.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{
+  DocumentId, CardId, TierId, NilIdError, Document, LinkedList, Card, CardStatus, TierTransition, TierMeta, TierPolicy, TierName, TierDocument, WrongKind, CollectionError,
+  TierListCollection, ItemError,
+};
+use serde::{Serialize, Deserialize,};
+use std::{
+  fmt,
+  error::Error,
+  num::NonZeroU64,
+  collections::{HashMap, HashSet,},
+};
+
+/// The number of documents written in each batch when importing a tier list.
+const BATCH_SIZE: usize = 64;
+
+/// A self contained copy of a tier list.
+/// 
+/// The order of the tiers and cards encodes the links between them.
+#[derive(PartialEq, Eq, Clone, Default, Debug, Serialize, Deserialize,)]
+pub struct TierListSnapshot {
+  /// The tiers from the front of the list to the back.
+  pub tiers: Vec<TierSnapshot>,
+}
+
+/// A copy of a tier in a `TierListSnapshot`.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize,)]
+pub struct TierSnapshot {
+  /// The identifier of the tier.
+  pub id: DocumentId,
+  /// The cards in the tier from the front of the tier to the back.
+  pub cards: Vec<CardSnapshot>,
+  /// The net score a card must exceed to be promoted out of the tier.
+  #[serde(default,)]
+  pub promote_threshold: Option<i128>,
+  /// Which cards may be moved into the tier.
+  #[serde(default,)]
+  pub policy: TierPolicy,
+  /// The name of the tier.
+  #[serde(default,)]
+  pub name: TierName,
+}
+
+/// A copy of a card in a `TierListSnapshot`.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize,)]
+pub struct CardSnapshot {
+  /// The identifier of the card.
+  pub id: DocumentId,
+  /// The display name of the card.
+  pub name: String,
+  /// The description of the card.
+  pub description: String,
+  /// The up votes on the card.
+  pub up_votes: u64,
+  /// The down votes on the card.
+  pub down_votes: u64,
+  /// The bias of the card.
+  pub bias: u64,
+  /// Whether the card can be voted on.
+  #[serde(default,)]
+  pub status: CardStatus,
+  /// The promotions of the card from the oldest to the newest.
+  #[serde(default,)]
+  pub history: Vec<TierTransition>,
+}
+
+impl From<Card> for CardSnapshot {
+  #[inline]
+  fn from(from: Card,) -> Self {
+    let Card { id, name, description, up_votes, down_votes, bias, status, history, .. } = from;
+
+    Self { id: *id, name, description, up_votes, down_votes, bias, status, history, }
+  }
+}
+
+/// The errors when exporting a tier list.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum ExportError<E,> {
+  /// Getting a document failed.
+  Fetch(E),
+  /// A document had the wrong kind.
+  WrongKind(WrongKind),
+  /// The document was reached twice while walking the list.
+  Cycle(DocumentId),
+}
+
+impl<E,> From<ItemError<E, WrongKind,>> for ExportError<E,> {
+  #[inline]
+  fn from(from: ItemError<E, WrongKind,>,) -> Self {
+    match from {
+      ItemError::Fetch(e) => ExportError::Fetch(e,),
+      ItemError::Convert(e) => ExportError::WrongKind(e,),
+    }
+  }
+}
+
+impl<E,> fmt::Display for ExportError<E,>
+  where E: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      ExportError::Fetch(e) => write!(fmt, "failed to get a document: {}", e,),
+      ExportError::WrongKind(e) => e.fmt(fmt,),
+      ExportError::Cycle(id) => write!(fmt, "the document with the id {:?} was reached twice", id,),
+    }
+  }
+}
+
+impl<E,> Error for ExportError<E,>
+  where E: Error, {}
+
+/// The errors when importing a tier list.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum ImportError<E,> {
+  /// The snapshot has no tiers.
+  Empty,
+  /// A document of the tier list already exists in the collection.
+  Exists(DocumentId),
+  /// A document of the tier list has a nil identifier.
+  Invalid(DocumentId, NilIdError),
+  /// Getting a document failed.
+  Fetch(E),
+  /// Writing a document failed.
+  Write(E),
+}
+
+impl<E,> fmt::Display for ImportError<E,>
+  where E: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      ImportError::Empty => write!(fmt, "the snapshot has no tiers",),
+      ImportError::Exists(id) => write!(fmt, "the document with the id {:?} already exists", id,),
+      ImportError::Invalid(id, e) => write!(fmt, "the document with the id {:?} is invalid: {}", id, e,),
+      ImportError::Fetch(e) => write!(fmt, "failed to get a document: {}", e,),
+      ImportError::Write(e) => write!(fmt, "failed to write a document: {}", e,),
+    }
+  }
+}
+
+impl<E,> Error for ImportError<E,>
+  where E: Error, {}
+
+/// How a `TierListSnapshot` is imported.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug,)]
+pub struct ImportOptions {
+  /// If `true` the identifiers in the snapshot are used; otherwise new identifiers are
+  /// generated for every tier and card.
+  pub preserve_ids: bool,
+  /// If `true` existing documents with the same identifiers are overwritten; otherwise
+  /// the import fails if any of the documents already exist.
+  pub overwrite: bool,
+}
+
+/// Copies a tier list out of a collection.
+/// 
+/// A tier with no length is walked from its front unless the front is nil, so a front
+/// which is missing or not a card fails instead of exporting an empty tier.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the tier list.  
+/// head --- The identifier of the first tier of the list.  
+pub async fn export_list<Coll,>(collection: &Coll, head: &DocumentId,) -> Result<TierListSnapshot, ExportError<Coll::Error,>>
+  where Coll: TierListCollection<Document = TierDocument>, {
+  let mut visited = HashSet::new();
+  let mut tiers = Vec::new();
+  let mut next_tier = Some(*head,);
+
+  while let Some(tier_id) = next_tier {
+    if !visited.insert(tier_id,) { return Err(ExportError::Cycle(tier_id,)) }
+
+    let tier = collection.get_item_flat::<TierMeta>(&tier_id,).await?;
+    let mut cards = Vec::new();
+    let mut next_card = Some(**tier.list_front(),).filter(|_,| tier.list_len().is_some() || !tier.list_front().is_nil(),);
+
+    while let Some(card_id) = next_card {
+      if !visited.insert(card_id,) { return Err(ExportError::Cycle(card_id,)) }
+
+      let card = collection.get_item_flat::<Card>(&card_id,).await?;
+
+      next_card = card.get_next_id().copied();
+      cards.push(card.into(),);
+    }
+
+    next_tier = tier.get_next_id().copied();
+    tiers.push(TierSnapshot { id: tier_id, cards, promote_threshold: tier.promote_threshold, policy: tier.policy, name: tier.name, },);
+  }
+
+  Ok(TierListSnapshot { tiers, })
+}
+
+/// Writes a copy of a tier list into a collection and returns the identifier of its
+/// first tier.
+/// 
+/// The links between the tiers and cards are rebuilt from their order in the snapshot.
+/// The tiers in the history of each card are given the identifiers of the written
+/// tiers; tiers which are not in the snapshot keep their identifiers.
+/// 
+/// # Params
+/// 
+/// collection --- The collection to write the tier list to.  
+/// snapshot --- The tier list to write.  
+/// options --- How the tier list is written.  
+pub async fn import_list<Coll,>(collection: &Coll, snapshot: &TierListSnapshot, options: ImportOptions,) -> Result<DocumentId, ImportError<Coll::Error,>>
+  where Coll: TierListCollection<Document = TierDocument>, {
+  if snapshot.tiers.is_empty() { return Err(ImportError::Empty) }
+
+  let id = |id: &DocumentId,| if options.preserve_ids { *id } else { generate_id() };
+  let ids = snapshot.tiers.iter()
+    .map(|tier,| (id(&tier.id,), tier.cards.iter().map(|card,| id(&card.id,),).collect::<Vec<_>>(),),)
+    .collect::<Vec<_>>();
+  let tier_ids = snapshot.tiers.iter().zip(ids.iter(),)
+    .map(|(tier, (id, _,),),| (tier.id, *id,),)
+    .collect::<HashMap<_, _,>>();
+  let remap = |id: &TierId,| tier_ids.get(&**id,).map_or(*id, |&id,| id.into(),);
+
+  let mut documents = Vec::new();
+  for (index, (tier, (tier_id, card_ids,),),) in snapshot.tiers.iter().zip(ids.iter(),).enumerate() {
+    let previous_tier = index.checked_sub(1,).map(|index,| ids[index].0.into(),);
+    let next_tier = ids.get(index + 1,).map(|(id, _,),| (*id).into(),);
+    let ends = match (card_ids.first(), card_ids.last(),) {
+      (Some(front), Some(back),) => (NonZeroU64::new(card_ids.len() as u64,), (*front).into(), (*back).into(),),
+      _ => (None, CardId::NIL, CardId::NIL,),
+    };
+
+    let mut meta = TierMeta::new((*tier_id).into(), ends, previous_tier, next_tier,);
+
+    meta.promote_threshold = tier.promote_threshold;
+    meta.policy = tier.policy;
+    meta.name = tier.name;
+    documents.push(meta.into(),);
+    for (index, (card, id,),) in tier.cards.iter().zip(card_ids,).enumerate() {
+      documents.push(Card {
+        id: (*id).into(),
+        name: card.name.clone(),
+        description: card.description.clone(),
+        up_votes: card.up_votes,
+        down_votes: card.down_votes,
+        bias: card.bias,
+        status: card.status,
+        history: card.history.iter()
+          .map(|transition,| TierTransition { from: remap(&transition.from,), to: remap(&transition.to,), ..*transition },)
+          .collect(),
+        previous_card: index.checked_sub(1,).map(|index,| card_ids[index].into(),),
+        next_card: card_ids.get(index + 1,).map(|id,| (*id).into(),),
+      }.into(),);
+    }
+  }
+
+  //A nil identifier could be shared with other documents.
+  if let Some((id, e,)) = documents.iter().find_map(|document: &TierDocument,| Some((*document.get_id(), document.validate().err()?,)),) {
+    return Err(ImportError::Invalid(id, e,))
+  }
+
+  if !options.overwrite {
+    let all = ids.iter()
+      .flat_map(|(tier, cards,),| std::iter::once(tier,).chain(cards,),)
+      .collect::<Vec<_>>();
+
+    for (id, res,) in all.iter().zip(collection.get_documents(all.iter().copied(),).await.map_err(ImportError::Fetch,)?,) {
+      match res {
+        Ok(_) => return Err(ImportError::Exists(**id,)),
+        Err(e) if e.is_not_found() => {},
+        Err(e) => return Err(ImportError::Fetch(e,)),
+      }
+    }
+  }
+
+  for batch in documents.chunks(BATCH_SIZE,) {
+    let report = collection.write_documents_reported(batch,).await.map_err(ImportError::Write,)?;
+
+    if let Some(e) = report.into_iter().find_map(|(_, res,),| res.err(),) { return Err(ImportError::Write(e,)) }
+  }
+
+  Ok(ids[0].0)
+}
+
+/// Generates a new random `DocumentId`.
+#[cfg(feature = "rand",)]
+#[inline]
+fn generate_id() -> DocumentId { DocumentId::random() }
+
+/// Generates a new random `DocumentId`.
+#[cfg(not(feature = "rand",),)]
+fn generate_id() -> DocumentId {
+  use std::{collections::hash_map::RandomState, hash::{BuildHasher, Hasher,},};
+
+  let mut id = [0; 20];
+
+  for chunk in id.chunks_mut(8,) {
+    let random = RandomState::new().build_hasher().finish();
+
+    chunk.copy_from_slice(&random.to_le_bytes()[..chunk.len()],);
+  }
+
+  DocumentId::from_bytes(id,)
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{NameTooLong, MemoryCollection, MemoryError, RecordingCollection, Op,};
+  use futures::executor::block_on;
+
+  fn snapshot() -> TierListSnapshot {
+    let card = |id: u8, name: &str, up_votes,| CardSnapshot {
+      id: DocumentId::from_bytes([id; 20]),
+      name: name.to_owned(),
+      description: format!("The {} card", name,),
+      up_votes,
+      down_votes: 1,
+      bias: 0,
+      status: CardStatus::Open,
+      history: Vec::new(),
+    };
+    //Demoted from the second tier after being promoted into it from another list.
+    let history = vec![
+      TierTransition { from: TierId::from_bytes([77; 20]), to: TierId::from_bytes([2; 20]), score: 4, },
+      TierTransition { from: TierId::from_bytes([2; 20]), to: TierId::from_bytes([3; 20]), score: -1, },
+    ];
+
+    TierListSnapshot {
+      tiers: vec![
+        TierSnapshot { id: DocumentId::from_bytes([1; 20]), cards: vec![card(10, "gold", 9,), card(11, "silver", 7,),], promote_threshold: None, policy: TierPolicy::OPEN, name: TierName::new("S",).expect("Error naming tier"), },
+        TierSnapshot { id: DocumentId::from_bytes([2; 20]), cards: vec![], promote_threshold: Some(5,), policy: TierPolicy { frozen: true, capacity: None, }, name: TierName::new("A",).expect("Error naming tier"), },
+        TierSnapshot { id: DocumentId::from_bytes([3; 20]), cards: vec![CardSnapshot { history, ..card(12, "bronze", 2,) },], promote_threshold: Some(2,), policy: TierPolicy { frozen: false, capacity: NonZeroU64::new(4,), }, name: TierName::EMPTY, },
+      ],
+    }
+  }
+
+  /// The contents of a card with the tiers in its history replaced by their positions
+  /// in the snapshot where possible.
+  type CardContents = (String, String, u64, u64, u64, Vec<(Result<usize, TierId>, Result<usize, TierId>, i128,)>,);
+
+  /// Removes the identifiers from a snapshot.
+  fn contents(snapshot: &TierListSnapshot,) -> Vec<(Option<i128>, TierPolicy, TierName, Vec<CardContents>,)> {
+    let position = |id: &TierId,| snapshot.tiers.iter().position(|tier,| tier.id == **id,).ok_or(*id,);
+
+    snapshot.tiers.iter()
+      .map(|tier,| (
+        tier.promote_threshold,
+        tier.policy,
+        tier.name,
+        tier.cards.iter()
+          .map(|card,| (
+            card.name.clone(), card.description.clone(), card.up_votes, card.down_votes, card.bias,
+            card.history.iter().map(|transition,| (position(&transition.from,), position(&transition.to,), transition.score,),).collect(),
+          ),)
+          .collect(),
+      ),)
+      .collect()
+  }
+
+  #[test]
+  fn test_tier_name() {
+    //Names are serialized as strings and checked when deserialized.
+    let mut json = serde_json::to_value(snapshot(),).expect("Error serialising snapshot");
+    assert_eq!(json["tiers"][1]["name"], "A", "Error serialising the name",);
+    assert_eq!(serde_json::from_value::<TierListSnapshot>(json.clone(),).ok(), Some(snapshot(),), "Error deserialising the name",);
+
+    json["tiers"][1]["name"] = "a".repeat(TierName::MAX_LEN + 1,).into();
+    assert!(serde_json::from_value::<TierListSnapshot>(json,).is_err(), "Error deserialising a long name",);
+    assert_eq!(TierName::new(&"a".repeat(TierName::MAX_LEN + 1,),), Err(NameTooLong(TierName::MAX_LEN + 1,),), "Error naming a tier with a long name",);
+  }
+  #[test]
+  fn test_round_trip() {
+    let source = MemoryCollection::new();
+    let target = MemoryCollection::new();
+    let original = snapshot();
+
+    block_on(async {
+      let head = import_list(&source, &original, ImportOptions { preserve_ids: true, ..ImportOptions::default() },).await
+        .expect("Error importing list");
+      assert_eq!(head, DocumentId::from_bytes([1; 20]), "Error preserving ids",);
+
+      let exported = export_list(&source, &head,).await
+        .expect("Error exporting list");
+      assert_eq!(exported, original, "Error exporting list",);
+
+      //Round trip the snapshot through a JSON file.
+      let path = std::env::temp_dir().join(format!("galileo-tier-export-{}.json", std::process::id(),),);
+      std::fs::write(&path, serde_json::to_vec(&exported,).expect("Error serialising snapshot"),)
+        .expect("Error writing snapshot");
+      let read = serde_json::from_slice::<TierListSnapshot>(&std::fs::read(&path,).expect("Error reading snapshot"),)
+        .expect("Error deserialising snapshot");
+      std::fs::remove_file(&path,).expect("Error removing snapshot");
+
+      let head = import_list(&target, &read, ImportOptions::default(),).await
+        .expect("Error importing list");
+      assert_ne!(head, DocumentId::from_bytes([1; 20]), "Error generating ids",);
+
+      let copied = export_list(&target, &head,).await
+        .expect("Error exporting list");
+      assert_eq!(contents(&copied,), contents(&original,), "Error copying list",);
+      assert_eq!(target.len(), source.len(), "Error copying documents",);
+
+      //An imported card keeps its promotions, which name the imported tiers.
+      let second = target.get_item_flat::<TierMeta>(&head,).await
+        .expect("Error reading the first tier")
+        .next_tier.expect("Error missing the second tier");
+      let third = target.get_item_flat::<TierMeta>(&second,).await
+        .expect("Error reading the second tier")
+        .next_tier.expect("Error missing the third tier");
+      let front = *target.get_item_flat::<TierMeta>(&third,).await
+        .expect("Error reading the third tier")
+        .list_front();
+      let card = target.get_item_flat::<Card>(&front,).await
+        .expect("Error reading the promoted card");
+      assert_eq!(
+        card.history,
+        [
+          TierTransition { from: TierId::from_bytes([77; 20]), to: second, score: 4, },
+          TierTransition { from: second, to: third, score: -1, },
+        ],
+        "Error importing the history of a card",
+      );
+    },);
+  }
+  #[test]
+  fn test_unknown_length() {
+    let coll = MemoryCollection::new();
+    let original = snapshot();
+    let tier = |id: u8,| block_on(coll.get_item_flat::<TierMeta>(&DocumentId::from_bytes([id; 20]),),).expect("Error reading tier");
+    let lose_len = |mut tier: TierMeta, front: CardId,| {
+      tier.set_ends((None, front, *tier.list_back(),),);
+      block_on(coll.write_document(&TierDocument::from(tier,),),).expect("Error writing tier");
+    };
+
+    block_on(import_list(&coll, &original, ImportOptions { preserve_ids: true, ..ImportOptions::default() },),)
+      .expect("Error importing list");
+    let empty = tier(2,);
+    assert_eq!((empty.list_len(), empty.list_front(), empty.list_back(),), (None, &CardId::NIL, &CardId::NIL,), "Error importing an empty tier",);
+
+    //A tier whose length was lost is walked.
+    lose_len(tier(1,), *tier(1,).list_front(),);
+    assert_eq!(block_on(export_list(&coll, &DocumentId::from_bytes([1; 20]),),), Ok(original), "Error exporting a tier of unknown length",);
+
+    lose_len(tier(3,), CardId::from_bytes([99; 20]),);
+    assert_eq!(
+      block_on(export_list(&coll, &DocumentId::from_bytes([1; 20]),),),
+      Err(ExportError::Fetch(MemoryError::NotFound(DocumentId::from_bytes([99; 20]),),)),
+      "Error exporting a tier with a missing front",
+    );
+  }
+  #[test]
+  fn test_overwrite() {
+    let coll = RecordingCollection::new(MemoryCollection::new(),);
+    let original = snapshot();
+    let preserve = ImportOptions { preserve_ids: true, ..ImportOptions::default() };
+
+    block_on(async {
+      import_list(&coll, &original, preserve,).await
+        .expect("Error importing list");
+      coll.take_log();
+
+      let mut changed = original.clone();
+      changed.tiers[0].cards[0].up_votes = 0;
+      assert_eq!(
+        import_list(&coll, &changed, preserve,).await,
+        Err(ImportError::Exists(DocumentId::from_bytes([1; 20]),)),
+        "Error overwriting list",
+      );
+      //Existing documents are checked in a single batch and nothing is written.
+      crate::assert_ops!(
+        coll,
+        [Op::GetBatch(vec![DocumentId::from_bytes([1; 20]), DocumentId::from_bytes([10; 20]), DocumentId::from_bytes([11; 20]), DocumentId::from_bytes([2; 20]), DocumentId::from_bytes([3; 20]), DocumentId::from_bytes([12; 20]),]),],
+        "Error checking for existing documents",
+      );
+      assert_eq!(export_list(&coll, &DocumentId::from_bytes([1; 20]),).await, Ok(original), "Error refused import changed the list",);
+
+      import_list(&coll, &changed, ImportOptions { overwrite: true, ..preserve },).await
+        .expect("Error overwriting list");
+      assert_eq!(export_list(&coll, &DocumentId::from_bytes([1; 20]),).await, Ok(changed), "Error overwriting list",);
+      assert_eq!(
+        import_list(&coll, &TierListSnapshot::default(), preserve,).await,
+        Err(ImportError::Empty),
+        "Error importing empty list",
+      );
+
+      let mut nil = snapshot();
+      nil.tiers[2].cards[0].id = DocumentId::NIL;
+      coll.take_log();
+      assert_eq!(
+        import_list(&coll, &nil, ImportOptions { overwrite: true, ..preserve },).await,
+        Err(ImportError::Invalid(DocumentId::from_bytes([3; 20]), NilIdError::Front,)),
+        "Error importing a nil id",
+      );
+      crate::assert_ops!(coll, [], "Error touching the collection with a nil id",);
+    },);
+  }
+}
@@ -0,0 +1,423 @@
+//! Defines a document collection stored in Cloud Firestore.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, Envelope, CollectionError, ErrorKind, TierListCollection,};
+use futures::{Future, future::LocalBoxFuture,};
+use base64::{Engine, engine::general_purpose::STANDARD,};
+use reqwest::{Client, RequestBuilder, Response,};
+use serde::{Serialize, Deserialize,};
+use std::{
+  fmt,
+  error::Error,
+  collections::{HashMap, hash_map::Entry,},
+  borrow::Borrow,
+  sync::Arc,
+};
+
+/// The endpoint of the Firestore REST API.
+pub const FIRESTORE_ENDPOINT: &str = "https://firestore.googleapis.com/v1";
+
+/// The errors returned by a `FirestoreCollection`.
+#[derive(PartialEq, Eq, Clone, Debug,)]
+pub enum FirestoreError {
+  /// The document does not exist.
+  NotFound(DocumentId),
+  /// The stored document was missing its body or it was not valid base64.
+  Corrupt(DocumentId),
+  /// An access token could not be got.
+  Token(String),
+  /// A request to Firestore failed.
+  Request(ErrorKind, String),
+}
+
+impl fmt::Display for FirestoreError {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      FirestoreError::NotFound(id) => write!(fmt, "could not find a document with the id {:?}", id,),
+      FirestoreError::Corrupt(id) => write!(fmt, "the document with the id {:?} is malformed", id,),
+      FirestoreError::Token(message) => write!(fmt, "could not get an access token: {}", message,),
+      FirestoreError::Request(_, message) => write!(fmt, "the request to Firestore failed: {}", message,),
+    }
+  }
+}
+
+impl Error for FirestoreError {}
+
+impl CollectionError for FirestoreError {
+  #[inline]
+  fn kind(&self,) -> ErrorKind {
+    match self {
+      FirestoreError::NotFound(_) => ErrorKind::NotFound,
+      FirestoreError::Corrupt(_) => ErrorKind::Corrupt,
+      FirestoreError::Token(_) => ErrorKind::Transient,
+      FirestoreError::Request(kind, _) => *kind,
+    }
+  }
+}
+
+/// A source of access tokens for requests to Firestore.
+/// 
+/// Any `Fn() -> impl Future<Output = Result<String, String>>` is a `TokenSource`, so
+/// tokens can come from whichever credential library the application already uses.
+pub trait TokenSource {
+  /// The future type when getting a token.
+  type Token: Future<Output = Result<String, String>>;
+
+  /// Gets a bearer token which is valid for the next request.
+  fn token(&self,) -> Self::Token;
+}
+
+impl<F, Fut,> TokenSource for F
+  where F: Fn() -> Fut,
+    Fut: Future<Output = Result<String, String>>, {
+  type Token = Fut;
+
+  #[inline]
+  fn token(&self,) -> Self::Token { self() }
+}
+
+/// Classifies the status of a failed Firestore request.
+fn status_kind(status: &str,) -> ErrorKind {
+  match status {
+    "NOT_FOUND" => ErrorKind::NotFound,
+    "ABORTED" | "ALREADY_EXISTS" | "FAILED_PRECONDITION" => ErrorKind::Conflict,
+    "UNAVAILABLE" | "RESOURCE_EXHAUSTED" | "INTERNAL" => ErrorKind::Transient,
+    "DEADLINE_EXCEEDED" => ErrorKind::Timeout,
+    _ => ErrorKind::Permanent,
+  }
+}
+
+/// Converts the error from a request which could not be made.
+fn request_error(error: reqwest::Error,) -> FirestoreError {
+  let kind = if error.is_timeout() { ErrorKind::Timeout }
+    else if error.is_connect() || error.is_request() { ErrorKind::Transient }
+    else if error.is_decode() { ErrorKind::Corrupt }
+    else { ErrorKind::Permanent };
+
+  FirestoreError::Request(kind, error.to_string(),)
+}
+
+/// The body of a failed response.
+#[derive(Deserialize,)]
+struct ErrorResponse {
+  /// The error.
+  error: Status,
+}
+
+/// The status of a failed request.
+#[derive(Deserialize,)]
+struct Status {
+  /// The name of the status code.
+  #[serde(default,)]
+  status: String,
+  /// A description of the error.
+  #[serde(default,)]
+  message: String,
+}
+
+/// A Firestore document.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug,)]
+struct FsDocument {
+  /// The full resource name of the document.
+  name: String,
+  /// The fields of the document.
+  #[serde(default,)]
+  fields: Fields,
+}
+
+/// The fields of a stored document.
+#[derive(PartialEq, Eq, Default, Serialize, Deserialize, Debug,)]
+struct Fields {
+  /// The body of the document.
+  body: Option<BytesValue>,
+}
+
+/// A bytes value.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug,)]
+struct BytesValue {
+  /// The base64 encoded bytes.
+  #[serde(rename = "bytesValue",)]
+  bytes_value: String,
+}
+
+/// An element of the response to a `batchGet` request.
+#[derive(Deserialize,)]
+struct BatchGetResponse {
+  /// The document if it exists.
+  found: Option<FsDocument>,
+}
+
+/// A write in a `commit` request.
+#[derive(Serialize,)]
+struct Write<'a,> {
+  /// The document to write.
+  update: &'a FsDocument,
+}
+
+/// A `TierListCollection` of `Envelope`s stored in a Cloud Firestore collection.
+/// 
+/// Each document is stored under the hex encoded identifier of the document with its
+/// body in the bytes field `body`. Batch reads are made with `batchGet` and batch writes
+/// with a single atomic `commit`, so a batch write either succeeds or fails as a whole.
+/// 
+/// Every request is authorised with a bearer token from the `TokenSource`; the
+/// collection does not manage credentials itself.
+#[derive(Clone, Debug,)]
+pub struct FirestoreCollection<Tk,> {
+  /// The client to send requests with.
+  client: Client,
+  /// The endpoint of the REST API.
+  endpoint: Arc<str>,
+  /// The resource name of the documents of the database.
+  root: Arc<str>,
+  /// The path of the collection under `root`.
+  collection: Arc<str>,
+  /// The source of access tokens.
+  token: Tk,
+}
+
+impl<Tk,> FirestoreCollection<Tk,> {
+  /// Returns a new `FirestoreCollection`.
+  /// 
+  /// # Params
+  /// 
+  /// client --- The client to send requests with.  
+  /// endpoint --- The endpoint of the REST API, usually `FIRESTORE_ENDPOINT`.  
+  /// project --- The identifier of the Google Cloud project.  
+  /// database --- The identifier of the database, usually `(default)`.  
+  /// collection --- The path of the collection, such as `lists/my-list/documents`.  
+  /// token --- The source of access tokens.  
+  pub fn new(client: Client, endpoint: &str, project: &str, database: &str, collection: &str, token: Tk,) -> Self {
+    Self {
+      client,
+      endpoint: endpoint.trim_end_matches('/',).into(),
+      root: format!("projects/{}/databases/{}/documents", project, database,).into(),
+      collection: collection.trim_matches('/',).into(),
+      token,
+    }
+  }
+  /// Gets the path of the collection storing the documents.
+  #[inline]
+  pub fn get_collection(&self,) -> &str { &self.collection }
+  /// Gets the resource name of a document.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the document.  
+  fn name(&self, id: &DocumentId,) -> String {
+    format!("{}/{}/{:x}", self.root, self.collection, id,)
+  }
+  /// Gets the identifier of a document from its resource name.
+  /// 
+  /// # Params
+  /// 
+  /// name --- The resource name of a document in the collection.  
+  fn id(&self, name: &str,) -> Option<DocumentId> {
+    name.rsplit('/',).next()?.parse().ok()
+  }
+  /// Gets the Firestore document storing a document.
+  fn document(&self, document: &Envelope,) -> FsDocument {
+    FsDocument {
+      name: self.name(&document.id,),
+      fields: Fields { body: Some(BytesValue { bytes_value: STANDARD.encode(&document.body,), },), },
+    }
+  }
+}
+
+/// Gets the document stored in a Firestore document.
+fn envelope(id: DocumentId, document: &FsDocument,) -> Result<Envelope, FirestoreError> {
+  let body = document.fields.body.as_ref()
+    .and_then(|body,| STANDARD.decode(&body.bytes_value,).ok(),)
+    .ok_or(FirestoreError::Corrupt(id,),)?;
+
+  Ok(Envelope::new(id, body,))
+}
+
+impl<Tk,> FirestoreCollection<Tk,>
+  where Tk: TokenSource, {
+  /// Authorises and sends a request, failing if the response is an error.
+  /// 
+  /// # Params
+  /// 
+  /// request --- The request to send.  
+  async fn send(&self, request: RequestBuilder,) -> Result<Response, FirestoreError> {
+    let token = self.token.token().await.map_err(FirestoreError::Token,)?;
+    let response = request.bearer_auth(token,).send().await.map_err(request_error,)?;
+
+    if response.status().is_success() { return Ok(response) }
+
+    let code = response.status();
+    Err(match response.json::<ErrorResponse>().await {
+      Ok(ErrorResponse { error, },) => FirestoreError::Request(status_kind(&error.status,), error.message,),
+      Err(_) if code.is_server_error() => FirestoreError::Request(ErrorKind::Transient, code.to_string(),),
+      Err(_) => FirestoreError::Request(ErrorKind::Permanent, code.to_string(),),
+    })
+  }
+}
+
+impl<Tk,> TierListCollection for FirestoreCollection<Tk,>
+  where Tk: 'static + TokenSource + Clone, {
+  type Document = Envelope;
+  type Error = FirestoreError;
+  type GetBatchDocuments = LocalBoxFuture<'static, Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>>;
+  type GetDocument = LocalBoxFuture<'static, Result<Self::Document, Self::Error>>;
+  type WriteBatchDocuments = LocalBoxFuture<'static, Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>;
+  type WriteDocument = LocalBoxFuture<'static, Result<(), Self::Error>>;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    let ids = ids.into_iter().map(|id,| *id.borrow(),).collect::<Vec<_>>();
+    let coll = self.clone();
+
+    Box::pin(async move {
+      if ids.is_empty() { return Ok(Vec::new()) }
+
+      let mut names = ids.iter().map(|id,| coll.name(id,),).collect::<Vec<_>>();
+      names.sort_unstable();
+      names.dedup();
+
+      let url = format!("{}/{}:batchGet", coll.endpoint, coll.root,);
+      let request = coll.client.post(url,).json(&HashMap::from([("documents", names,),],),);
+      let responses = coll.send(request,).await?
+        .json::<Vec<BatchGetResponse>>().await
+        .map_err(request_error,)?;
+      let found = responses.iter()
+        .filter_map(|response,| response.found.as_ref(),)
+        .filter_map(|document,| Some((coll.id(&document.name,)?, document,)),)
+        .collect::<HashMap<_, _>>();
+
+      Ok(ids.iter().map(|id,| match found.get(id,) {
+        Some(document) => envelope(*id, document,),
+        None => Err(FirestoreError::NotFound(*id,)),
+      },).collect())
+    },)
+  }
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    let (coll, id,) = (self.clone(), *id,);
+
+    Box::pin(async move {
+      let request = coll.client.get(format!("{}/{}", coll.endpoint, coll.name(&id,),),);
+      let document = match coll.send(request,).await {
+        Ok(response) => response.json::<FsDocument>().await.map_err(request_error,)?,
+        Err(FirestoreError::Request(ErrorKind::NotFound, _)) => return Err(FirestoreError::NotFound(id,)),
+        Err(e) => return Err(e),
+      };
+
+      envelope(id, &document,)
+    },)
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    //A commit cannot write the same document twice so only the last write of each
+    //document is sent.
+    let mut index = HashMap::new();
+    let mut writes = Vec::new();
+    for document in documents {
+      let document = self.document(document.borrow(),);
+
+      match index.entry(document.name.clone(),) {
+        Entry::Occupied(entry) => writes[*entry.get()] = document,
+        Entry::Vacant(entry) => { entry.insert(writes.len(),); writes.push(document,) },
+      }
+    }
+    let coll = self.clone();
+
+    Box::pin(async move {
+      if writes.is_empty() { return Ok(Ok(())) }
+
+      let writes = writes.iter().map(|update,| Write { update, },).collect::<Vec<_>>();
+      let url = format!("{}/{}:commit", coll.endpoint, coll.root,);
+
+      coll.send(coll.client.post(url,).json(&HashMap::from([("writes", writes,),],),),).await?;
+      Ok(Ok(()))
+    },)
+  }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    let (coll, document,) = (self.clone(), self.document(document.borrow(),),);
+
+    Box::pin(async move {
+      let request = coll.client.patch(format!("{}/{}", coll.endpoint, document.name,),).json(&document,);
+
+      coll.send(request,).await.map(|_,| (),)
+    },)
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use futures::future::{self, Ready,};
+
+  fn owner() -> Ready<Result<String, String>> { future::ok("owner".to_owned(),) }
+
+  fn collection(endpoint: &str,) -> FirestoreCollection<fn() -> Ready<Result<String, String>>,> {
+    FirestoreCollection::new(Client::new(), endpoint, "galileo", "(default)", "/lists/test/documents/", owner,)
+  }
+
+  #[test]
+  fn test_documents() {
+    let coll = collection(FIRESTORE_ENDPOINT,);
+    let doc = Envelope::new(DocumentId::from_bytes([0xab; 20]), b"body".to_vec(),);
+    let name = format!("projects/galileo/databases/(default)/documents/lists/test/documents/{}", "ab".repeat(20,),);
+    let stored = coll.document(&doc,);
+
+    assert_eq!(stored.name, name, "Error naming document",);
+    assert_eq!(coll.id(&name,), Some(doc.id), "Error parsing name",);
+    assert_eq!(coll.id("projects/galileo/databases/(default)/documents/lists/test/documents/ab",), None, "Error parsing short name",);
+    assert_eq!(envelope(doc.id, &stored,), Ok(doc.clone()), "Error decoding document",);
+    assert_eq!(
+      serde_json::to_value(&stored,).expect("Error serialising document"),
+      serde_json::json!({ "name": name, "fields": { "body": { "bytesValue": "Ym9keQ==", }, }, }),
+      "Error serialising document",
+    );
+
+    let responses = serde_json::from_value::<Vec<BatchGetResponse>>(serde_json::json!([
+      { "found": { "name": name, "fields": {}, "createTime": "2026-10-14T00:00:00Z", }, "readTime": "2026-10-14T00:00:00Z", },
+      { "missing": format!("{}/{}", coll.root, "cd".repeat(20,),), "readTime": "2026-10-14T00:00:00Z", },
+    ]),).expect("Error parsing batch response");
+    let found = responses[0].found.as_ref().expect("Error parsing found document");
+    assert!(responses[1].found.is_none(), "Error parsing missing document",);
+    assert_eq!(envelope(doc.id, found,), Err(FirestoreError::Corrupt(doc.id,)), "Error decoding document without a body",);
+  }
+  #[test]
+  fn test_status_kind() {
+    assert_eq!(status_kind("NOT_FOUND",), ErrorKind::NotFound, "Error classifying NOT_FOUND",);
+    assert_eq!(status_kind("ABORTED",), ErrorKind::Conflict, "Error classifying ABORTED",);
+    assert_eq!(status_kind("UNAVAILABLE",), ErrorKind::Transient, "Error classifying UNAVAILABLE",);
+    assert_eq!(status_kind("PERMISSION_DENIED",), ErrorKind::Permanent, "Error classifying PERMISSION_DENIED",);
+  }
+  /// Runs against the Firestore emulator when `FIRESTORE_EMULATOR_HOST` is set, such as
+  /// `localhost:8080`.
+  #[test]
+  fn test_emulator() {
+    let host = match std::env::var("FIRESTORE_EMULATOR_HOST",) {
+      Ok(host) => host,
+      Err(_) => return,
+    };
+    let coll = collection(&format!("http://{}/v1", host,),);
+    let runtime = tokio::runtime::Runtime::new()
+      .expect("Error starting runtime");
+
+    runtime.block_on(async {
+      let docs = (0..3u8).map(|i,| Envelope::new(DocumentId::from_bytes([i; 20]), vec![i; 4],),).collect::<Vec<_>>();
+
+      assert_eq!(coll.write_documents(&docs,).await, Ok(Ok(())), "Error writing documents",);
+      assert_eq!(
+        coll.get_documents([DocumentId::from_bytes([2; 20]), DocumentId::from_bytes([9; 20]), DocumentId::from_bytes([0; 20]), DocumentId::from_bytes([2; 20]),],).await,
+        Ok(vec![Ok(docs[2].clone()), Err(FirestoreError::NotFound(DocumentId::from_bytes([9; 20]),)), Ok(docs[0].clone()), Ok(docs[2].clone()),]),
+        "Error reading documents",
+      );
+
+      let updated = Envelope::new(DocumentId::from_bytes([1; 20]), b"updated".to_vec(),);
+      coll.write_document(&updated,).await
+        .expect("Error writing document");
+      assert_eq!(coll.get_document(&DocumentId::from_bytes([1; 20]),).await, Ok(updated), "Error reading document",);
+      assert_eq!(coll.get_document(&DocumentId::from_bytes([9; 20]),).await, Err(FirestoreError::NotFound(DocumentId::from_bytes([9; 20]),)), "Error reading missing document",);
+    },);
+  }
+}
@@ -0,0 +1,58 @@
+//! Defines operations for checking that the backend of a collection is reachable.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::TierListCollection;
+use futures::Future;
+use std::{time::Duration, rc::Rc, sync::Arc,};
+
+/// The result of a successful health check.
+#[derive(PartialEq, Eq, Clone, Debug,)]
+pub struct HealthReport {
+  /// The round trip time to the backend.
+  pub latency: Duration,
+  /// The version of the backend if it reports one.
+  pub version: Option<String>,
+}
+
+/// A collection whose backend can be checked before it is used.
+pub trait HealthCheck: TierListCollection {
+  /// The future type when checking the backend.
+  type Ping: Future<Output = Result<HealthReport, Self::Error>>;
+
+  /// Checks that the backend of the collection is reachable and usable.
+  fn ping(&self,) -> Self::Ping;
+}
+
+impl<Coll,> HealthCheck for &Coll
+  where Coll: HealthCheck, {
+  type Ping = Coll::Ping;
+
+  #[inline]
+  fn ping(&self,) -> Self::Ping { Coll::ping(*self,) }
+}
+
+impl<Coll,> HealthCheck for Rc<Coll,>
+  where Coll: HealthCheck, {
+  type Ping = Coll::Ping;
+
+  #[inline]
+  fn ping(&self,) -> Self::Ping { Coll::ping(&**self,) }
+}
+
+impl<Coll,> HealthCheck for Arc<Coll,>
+  where Coll: HealthCheck, {
+  type Ping = Coll::Ping;
+
+  #[inline]
+  fn ping(&self,) -> Self::Ping { Coll::ping(&**self,) }
+}
+
+impl<Coll,> HealthCheck for Box<Coll,>
+  where Coll: HealthCheck, {
+  type Ping = Coll::Ping;
+
+  #[inline]
+  fn ping(&self,) -> Self::Ping { Coll::ping(&**self,) }
+}
@@ -0,0 +1,345 @@
+//! Defines a document collection stored in a browser's IndexedDB.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, Document, CollectionError, ErrorKind, TierListCollection, TierDocument,};
+use futures::{Future, future::{self, LocalBoxFuture,},};
+use serde::{Serialize, de::DeserializeOwned,};
+use wasm_bindgen::{JsCast, JsValue, closure::Closure,};
+use wasm_bindgen_futures::JsFuture;
+use js_sys::{Promise, Uint8Array,};
+use web_sys::{DomException, IdbDatabase, IdbObjectStore, IdbOpenDbRequest, IdbRequest, IdbTransaction, IdbTransactionMode,};
+use std::{fmt, error::Error, borrow::Borrow, marker::PhantomData, rc::Rc,};
+
+/// The errors returned by an `IdbCollection`.
+#[derive(PartialEq, Eq, Clone, Debug,)]
+pub enum IdbError {
+  /// The document does not exist.
+  NotFound(DocumentId),
+  /// The stored value could not be decoded as a document.
+  Corrupt(DocumentId),
+  /// An IndexedDB operation failed.
+  Js(ErrorKind, String),
+}
+
+impl fmt::Display for IdbError {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      IdbError::NotFound(id) => write!(fmt, "could not find a document with the id {:?}", id,),
+      IdbError::Corrupt(id) => write!(fmt, "the value of the document with the id {:?} could not be decoded", id,),
+      IdbError::Js(_, message) => write!(fmt, "the IndexedDB operation failed: {}", message,),
+    }
+  }
+}
+
+impl Error for IdbError {}
+
+impl CollectionError for IdbError {
+  #[inline]
+  fn kind(&self,) -> ErrorKind {
+    match self {
+      IdbError::NotFound(_) => ErrorKind::NotFound,
+      IdbError::Corrupt(_) => ErrorKind::Corrupt,
+      IdbError::Js(kind, _) => *kind,
+    }
+  }
+}
+
+/// Classifies the name of a `DOMException` raised by IndexedDB.
+fn exception_kind(name: &str,) -> ErrorKind {
+  match name {
+    "ConstraintError" => ErrorKind::Conflict,
+    "AbortError" | "TransactionInactiveError" | "UnknownError" => ErrorKind::Transient,
+    "TimeoutError" => ErrorKind::Timeout,
+    "DataError" | "DataCloneError" => ErrorKind::Corrupt,
+    _ => ErrorKind::Permanent,
+  }
+}
+
+/// Converts a value thrown by IndexedDB.
+fn js_error(error: JsValue,) -> IdbError {
+  match error.dyn_into::<DomException>() {
+    Ok(e) => IdbError::Js(exception_kind(&e.name(),), e.message(),),
+    Err(e) => IdbError::Js(ErrorKind::Permanent, format!("{:?}", e,),),
+  }
+}
+
+/// Gets the key of the value storing a document.
+fn key(id: &DocumentId,) -> JsValue { Uint8Array::from(&id.as_bytes()[..],).buffer().into() }
+
+/// Waits for a request to complete and returns its result.
+fn request(request: &IdbRequest,) -> JsFuture {
+  JsFuture::from(Promise::new(&mut |resolve, reject,| {
+    let (success, failure,) = (request.clone(), request.clone(),);
+    let onsuccess = Closure::once_into_js(move || {
+      let _ = resolve.call1(&JsValue::UNDEFINED, &success.result().unwrap_or(JsValue::UNDEFINED,),);
+    },);
+    let onerror = Closure::once_into_js(move || {
+      let error = failure.error().ok().flatten().map_or(JsValue::UNDEFINED, JsValue::from,);
+      let _ = reject.call1(&JsValue::UNDEFINED, &error,);
+    },);
+
+    request.set_onsuccess(Some(onsuccess.unchecked_ref(),),);
+    request.set_onerror(Some(onerror.unchecked_ref(),),);
+  },),)
+}
+
+/// Waits for a transaction to commit.
+fn commit(transaction: &IdbTransaction,) -> JsFuture {
+  JsFuture::from(Promise::new(&mut |resolve, reject,| {
+    let failure = transaction.clone();
+    let oncomplete = Closure::once_into_js(move || { let _ = resolve.call0(&JsValue::UNDEFINED,); },);
+    let onabort = Closure::once_into_js(move || {
+      let error = failure.error().map_or(JsValue::UNDEFINED, JsValue::from,);
+      let _ = reject.call1(&JsValue::UNDEFINED, &error,);
+    },);
+
+    transaction.set_oncomplete(Some(oncomplete.unchecked_ref(),),);
+    //A failed transaction is always aborted so `abort` covers errors too.
+    transaction.set_onabort(Some(onabort.unchecked_ref(),),);
+  },),)
+}
+
+/// Opens a database, creating the object store if it does not exist.
+/// 
+/// # Params
+/// 
+/// version --- The version to open, or `None` for the current version.  
+/// store --- The name of the object store to create when upgrading.  
+async fn open(name: &str, version: Option<u32>, store: &str,) -> Result<IdbDatabase, IdbError> {
+  let factory = web_sys::window().and_then(|window,| window.indexed_db().ok().flatten(),)
+    .ok_or_else(|| IdbError::Js(ErrorKind::Permanent, "IndexedDB is not available".to_owned(),),)?;
+  let open: IdbOpenDbRequest = match version {
+    Some(version) => factory.open_with_u32(name, version,),
+    None => factory.open(name,),
+  }.map_err(js_error,)?;
+  let upgrade = {
+    let (open, store,) = (open.clone(), store.to_owned(),);
+
+    Closure::once_into_js(move || {
+      if let Ok(db) = open.result().and_then(JsValue::dyn_into::<IdbDatabase>,) {
+        if !db.object_store_names().contains(&store,) { let _ = db.create_object_store(&store,); }
+      }
+    },)
+  };
+
+  open.set_onupgradeneeded(Some(upgrade.unchecked_ref(),),);
+  request(&open,).await.map_err(js_error,)?.dyn_into().map_err(js_error,)
+}
+
+/// A `TierListCollection` stored in an object store of a browser's IndexedDB.
+/// 
+/// Each document is stored as a structured value keyed by its identifier as an
+/// `ArrayBuffer`. Batch reads are made in one read only transaction and batch writes in
+/// one read write transaction, so a batch write either succeeds or fails as a whole.
+/// 
+/// The futures of an `IdbCollection` are not `Send`, which is fine in the single
+/// threaded browser; the collection cannot be shared with web workers.
+pub struct IdbCollection<D = TierDocument,> {
+  /// The database storing the documents.
+  db: IdbDatabase,
+  /// The name of the object store storing the documents.
+  store: Rc<str>,
+  /// The type of the documents stored.
+  document: PhantomData<fn() -> D>,
+}
+
+impl<D,> IdbCollection<D,> {
+  /// Opens an `IdbCollection`, creating the database and object store if they do not
+  /// exist.
+  /// 
+  /// # Params
+  /// 
+  /// database --- The name of the database.  
+  /// store --- The name of the object store storing the documents.  
+  pub async fn open(database: &str, store: &str,) -> Result<Self, IdbError> {
+    let mut db = open(database, None, store,).await?;
+
+    //The object store can only be created by upgrading the database.
+    if !db.object_store_names().contains(store,) {
+      let version = db.version() as u32 + 1;
+
+      db.close();
+      db = open(database, Some(version,), store,).await?;
+    }
+
+    Ok(Self { db, store: store.into(), document: PhantomData, })
+  }
+  /// Gets the database storing the documents.
+  #[inline]
+  pub const fn get_database(&self,) -> &IdbDatabase { &self.db }
+  /// Starts a transaction on the object store.
+  fn transaction(&self, mode: IdbTransactionMode,) -> Result<(IdbTransaction, IdbObjectStore,), IdbError> {
+    let transaction = self.db.transaction_with_str_and_mode(&self.store, mode,).map_err(js_error,)?;
+    let store = transaction.object_store(&self.store,).map_err(js_error,)?;
+
+    Ok((transaction, store,))
+  }
+}
+
+impl<D,> Clone for IdbCollection<D,> {
+  #[inline]
+  fn clone(&self,) -> Self { Self { db: self.db.clone(), store: self.store.clone(), document: PhantomData, } }
+}
+
+impl<D,> fmt::Debug for IdbCollection<D,> {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    fmt.debug_struct("IdbCollection",)
+    .field("database", &self.db.name(),)
+    .field("store", &self.store,)
+    .finish()
+  }
+}
+
+/// Decodes the value read for a document.
+fn decode<D,>(id: DocumentId, value: JsValue,) -> Result<D, IdbError>
+  where D: DeserializeOwned, {
+  if value.is_undefined() { return Err(IdbError::NotFound(id,)) }
+
+  serde_wasm_bindgen::from_value(value,).map_err(|_,| IdbError::Corrupt(id,),)
+}
+
+/// Requests are made when an operation is started and the returned futures wait for
+/// them to complete.
+impl<D,> TierListCollection for IdbCollection<D,>
+  where D: 'static + Document + Serialize + DeserializeOwned, {
+  type Document = D;
+  type Error = IdbError;
+  type GetBatchDocuments = LocalBoxFuture<'static, Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>>;
+  type GetDocument = LocalBoxFuture<'static, Result<Self::Document, Self::Error>>;
+  type WriteBatchDocuments = LocalBoxFuture<'static, Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>;
+  type WriteDocument = LocalBoxFuture<'static, Result<(), Self::Error>>;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    let store = match self.transaction(IdbTransactionMode::Readonly,) {
+      Ok((_, store,)) => store,
+      Err(e) => return Box::pin(future::err(e,),),
+    };
+    let reads = ids.into_iter().map(|id,| {
+      let id = *id.borrow();
+
+      (id, store.get(&key(&id,),).map(|get,| request(&get,),).map_err(js_error,),)
+    },).collect::<Vec<_>>();
+
+    Box::pin(async move {
+      let mut documents = Vec::with_capacity(reads.len(),);
+
+      for (id, read,) in reads {
+        documents.push(match read {
+          Ok(read) => decode(id, read.await.map_err(js_error,)?,),
+          Err(e) => Err(e),
+        },);
+      }
+
+      Ok(documents)
+    },)
+  }
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    let id = *id;
+    let read = self.transaction(IdbTransactionMode::Readonly,)
+      .and_then(|(_, store,),| store.get(&key(&id,),).map_err(js_error,),)
+      .map(|get,| request(&get,),);
+
+    Box::pin(async move { decode(id, read?.await.map_err(js_error,)?,) },)
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let (transaction, store,) = match self.transaction(IdbTransactionMode::Readwrite,) {
+      Ok(transaction) => transaction,
+      Err(e) => return Box::pin(future::err(e,),),
+    };
+
+    for document in documents {
+      let document = document.borrow();
+      let put = serde_wasm_bindgen::to_value(document,)
+        .map_err(|e,| IdbError::Js(ErrorKind::Permanent, e.to_string(),),)
+        .and_then(|value,| store.put_with_key(&value, &key(document.get_id(),),).map_err(js_error,),);
+
+      if let Err(e) = put {
+        let _ = transaction.abort();
+        return Box::pin(future::err(e,),)
+      }
+    }
+
+    let commit = commit(&transaction,);
+    Box::pin(async move { commit.await.map(|_,| Ok(()),).map_err(js_error,) },)
+  }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    let write = self.write_documents([document.borrow(),],);
+
+    Box::pin(async move { write.await.map(|_,| (),) },)
+  }
+  fn exists(&self, id: &DocumentId,) -> impl Future<Output = Result<bool, Self::Error>> {
+    let count = self.transaction(IdbTransactionMode::Readonly,)
+      .and_then(|(_, store,),| store.count_with_key(&key(id,),).map_err(js_error,),)
+      .map(|count,| request(&count,),);
+
+    async move { Ok(count?.await.map_err(js_error,)?.as_f64().unwrap_or_default() > 0.0) }
+  }
+}
+
+#[cfg(all(test, target_arch = "wasm32",),)]
+mod tests {
+  use super::*;
+  use crate::{CardId, Card, CardStatus, TierMeta, TierListCollectionExt,};
+  use wasm_bindgen_test::*;
+
+  wasm_bindgen_test_configure!(run_in_browser);
+
+  #[wasm_bindgen_test]
+  async fn test_card() {
+    let coll = IdbCollection::<Card,>::open("galileo-test-card", "cards",).await
+      .expect("Error opening collection");
+    let card = Card {
+      id: CardId::from_bytes([1; 20]),
+      name: "card".to_owned(),
+      description: "a card".to_owned(),
+      up_votes: 3,
+      down_votes: 1,
+      bias: 2,
+      status: CardStatus::Locked,
+      history: Vec::new(),
+      previous_card: None,
+      next_card: Some(CardId::from_bytes([2; 20]),),
+    };
+
+    coll.write_document(&card,).await
+      .expect("Error writing card");
+    assert_eq!(coll.get_document(&card.id,).await, Ok(card.clone()), "Error reading card",);
+    assert_eq!(
+      coll.get_documents([&*card.id, &DocumentId::from_bytes([9; 20]),],).await,
+      Ok(vec![Ok(card.clone()), Err(IdbError::NotFound(DocumentId::from_bytes([9; 20]),)),]),
+      "Error reading cards",
+    );
+    assert_eq!(coll.exists(&card.id,).await, Ok(true), "Error card does not exist",);
+    assert_eq!(coll.exists(&DocumentId::from_bytes([9; 20]),).await, Ok(false), "Error card exists",);
+  }
+  #[wasm_bindgen_test]
+  async fn test_cursor() {
+    let coll = IdbCollection::<TierMeta,>::open("galileo-test-cursor", "tiers",).await
+      .expect("Error opening collection");
+    let ids = (0..3u8).map(|i,| DocumentId::from_bytes([i; 20]),).collect::<Vec<_>>();
+    let tiers = ids.iter().enumerate().map(|(i, &id,),| TierMeta::new(
+      id.into(), (None, id.into(), id.into(),),
+      ids.get(i.wrapping_sub(1,),).map(|&id,| id.into(),), ids.get(i + 1,).map(|&id,| id.into(),),
+    ),).collect::<Vec<_>>();
+
+    coll.write_documents(&tiers,).await
+      .expect("Error writing tiers")
+      .expect("Error writing tiers");
+
+    let mut cursor = coll.clone().get_cursor::<TierMeta,>(&ids[0],).await
+      .expect("Error reading front");
+    let mut walked = vec![*cursor.get_item().id];
+    while let Some(next) = cursor.get_next().await.expect("Error reading next") {
+      walked.push(*next.get_item().id,);
+      cursor = next;
+    }
+    assert_eq!(walked, ids, "Error walking the list",);
+  }
+}
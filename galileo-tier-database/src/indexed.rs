@@ -0,0 +1,576 @@
+//! Defines a document collection which maintains an index of its cards by name.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{
+  DocumentId, Document, Card, TierDocument, CollectionError, LookupError,
+  TierListCollection, TransactionalCollection, Transaction, lookup::walk_cards,
+};
+use futures::future::LocalBoxFuture;
+use serde::{Serialize, Deserialize,};
+use std::{
+  fmt,
+  error::Error,
+  iter,
+  mem,
+  borrow::Borrow,
+  collections::{BTreeMap, BTreeSet, HashMap, hash_map::Entry,},
+};
+
+/// A document mapping the names of cards to their identifiers.
+/// 
+/// Names are stored in lower case so that they are compared ignoring case.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize,)]
+pub struct NameIndex {
+  /// The identifier of this `NameIndex`.
+  pub id: DocumentId,
+  /// The identifiers of the cards by their lower case names.
+  pub names: BTreeMap<String, DocumentId>,
+  /// The first letters whose names are stored in shards of this `NameIndex` instead.
+  pub shards: BTreeSet<char>,
+}
+
+impl NameIndex {
+  /// Returns a new empty `NameIndex`.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the `NameIndex`.  
+  #[inline]
+  pub const fn new(id: DocumentId,) -> Self {
+    Self { id, names: BTreeMap::new(), shards: BTreeSet::new(), }
+  }
+  /// Gets the identifier of the shard storing the names starting with a letter.
+  /// 
+  /// # Params
+  /// 
+  /// letter --- The first letter of the names in the shard.  
+  pub fn shard_id(&self, letter: char,) -> DocumentId {
+    let mut id = *self.id.as_bytes();
+
+    //Flip a byte the letters do not touch so no shard shares the root identifier.
+    id[15] ^= 0xff;
+    for (byte, letter,) in id[16..].iter_mut().zip((letter as u32).to_be_bytes(),) { *byte ^= letter }
+
+    DocumentId::from_bytes(id,)
+  }
+  /// Moves the names starting with the most common letters into shards until at most
+  /// `max_len` names are left.
+  /// 
+  /// # Params
+  /// 
+  /// shards --- The shards of this `NameIndex` by their letter.  
+  /// max_len --- The most names this `NameIndex` should store.  
+  fn split(&mut self, shards: &mut HashMap<char, NameIndex>, max_len: usize,) {
+    while self.names.len() > max_len {
+      let mut counts = BTreeMap::new();
+      for letter in self.names.keys().filter_map(|name,| name.chars().next(),) {
+        *counts.entry(letter,).or_insert(0usize,) += 1;
+      }
+
+      let letter = match counts.into_iter().max_by_key(|&(_, count,),| count,) {
+        Some((letter, _,)) => letter,
+        //Only the empty name is left.
+        None => break,
+      };
+      let (moved, kept,) = mem::take(&mut self.names,).into_iter()
+        .partition::<BTreeMap<_, _>, _>(|(name, _,),| name.starts_with(letter,),);
+      let shard_id = self.shard_id(letter,);
+
+      self.names = kept;
+      self.shards.insert(letter,);
+      shards.entry(letter,).or_insert_with(|| NameIndex::new(shard_id,),)
+        .names.extend(moved,);
+    }
+  }
+}
+
+impl Document for NameIndex {
+  #[inline]
+  fn get_id(&self,) -> &DocumentId { &self.id }
+}
+
+/// Gets the lower case name of a card and its first letter.
+fn key(name: &str,) -> (String, Option<char>,) {
+  let name = name.to_lowercase();
+  let letter = name.chars().next();
+
+  (name, letter,)
+}
+
+/// Gets the name of a document if it is a card.
+fn card_name(document: &TierDocument,) -> Option<String> {
+  match document {
+    TierDocument::Card(card) => Some(card.name.clone(),),
+    TierDocument::TierMeta(_) => None,
+  }
+}
+
+/// The errors when rebuilding the index of an `IndexedCollection`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum IndexError<E, I,> {
+  /// Walking the list failed.
+  Lookup(LookupError<E,>),
+  /// Writing the index failed.
+  Index(I),
+}
+
+impl<E, I,> fmt::Display for IndexError<E, I,>
+  where E: fmt::Display,
+    I: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      IndexError::Lookup(e) => e.fmt(fmt,),
+      IndexError::Index(e) => write!(fmt, "failed to write the index: {}", e,),
+    }
+  }
+}
+
+impl<E, I,> Error for IndexError<E, I,>
+  where E: Error,
+    I: Error, {}
+
+/// A change to the name of a card: its identifier, its old name and its new name.
+type Change = (DocumentId, Option<String>, Option<String>,);
+
+/// A `TierListCollection` of tier lists which keeps an index of its cards by name in a
+/// second collection so that cards can be found without walking the list.
+/// 
+/// Every card written or deleted through the `IndexedCollection` updates the index. The
+/// index is updated after the documents are written and a failed update does not fail
+/// the write, so the index can fall behind the documents; `rebuild_index` recovers it.
+/// 
+/// The index is a root `NameIndex` document; once it stores more than `max_len` names
+/// the names starting with the most common letter are moved into a shard of their own.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub struct IndexedCollection<Coll, Ix,> {
+  /// The collection storing the tier lists.
+  inner: Coll,
+  /// The collection storing the index.
+  index: Ix,
+  /// The identifier of the root `NameIndex` document.
+  index_id: DocumentId,
+  /// The most names stored in one `NameIndex` document before it is sharded.
+  max_len: usize,
+}
+
+impl<Coll, Ix,> IndexedCollection<Coll, Ix,> {
+  /// Returns a new `IndexedCollection`.
+  /// 
+  /// # Params
+  /// 
+  /// inner --- The collection storing the tier lists.  
+  /// index --- The collection storing the index.  
+  /// index_id --- The identifier of the root `NameIndex` document.  
+  /// max_len --- The most names stored in the root document before it is sharded.  
+  #[inline]
+  pub const fn new(inner: Coll, index: Ix, index_id: DocumentId, max_len: usize,) -> Self {
+    Self { inner, index, index_id, max_len, }
+  }
+  /// Gets the collection storing the tier lists.
+  #[inline]
+  pub const fn get_inner(&self,) -> &Coll { &self.inner }
+  /// Gets the collection storing the index.
+  #[inline]
+  pub const fn get_index(&self,) -> &Ix { &self.index }
+}
+
+impl<Coll, Ix,> IndexedCollection<Coll, Ix,>
+  where Coll: TierListCollection<Document = TierDocument>,
+    Ix: TierListCollection<Document = NameIndex>, {
+  /// Finds the identifier of a card by its name, ignoring case.
+  /// 
+  /// If several cards share a name the last one written is found.
+  /// 
+  /// # Params
+  /// 
+  /// name --- The name of the card.  
+  pub async fn lookup_name(&self, name: &str,) -> Result<Option<DocumentId>, Ix::Error> {
+    let (name, letter,) = key(name,);
+    let root = match self.index.get_document(&self.index_id,).await {
+      Ok(root) => root,
+      Err(e) if e.is_not_found() => return Ok(None),
+      Err(e) => return Err(e),
+    };
+
+    match letter.filter(|letter,| root.shards.contains(letter,),) {
+      Some(letter) => match self.index.get_document(&root.shard_id(letter,),).await {
+        Ok(shard) => Ok(shard.names.get(&name,).copied()),
+        Err(e) if e.is_not_found() => Ok(None),
+        Err(e) => Err(e),
+      },
+      None => Ok(root.names.get(&name,).copied()),
+    }
+  }
+  /// Replaces the index with the cards of a tier list and returns the number of names
+  /// indexed.
+  /// 
+  /// If several cards share a name the first one in list order is indexed.
+  /// 
+  /// # Params
+  /// 
+  /// list_head --- The identifier of the first tier of the list.  
+  pub async fn rebuild_index(&self, list_head: &DocumentId,) -> Result<usize, IndexError<Coll::Error, Ix::Error,>> {
+    let mut root = NameIndex::new(self.index_id,);
+
+    walk_cards(&self.inner, list_head, |card: &Card,| {
+      root.names.entry(key(&card.name,).0,).or_insert(*card.id,);
+      false
+    },).await.map_err(IndexError::Lookup,)?;
+
+    let len = root.names.len();
+    let mut shards = HashMap::new();
+
+    root.split(&mut shards, self.max_len,);
+    self.write_index(&root, &shards,).await.map_err(IndexError::Index,)?;
+
+    Ok(len)
+  }
+  /// Applies changes to the names of cards to the index.
+  /// 
+  /// # Params
+  /// 
+  /// changes --- The changes in the order they were made.  
+  async fn apply_changes(&self, changes: Vec<Change>,) -> Result<(), Ix::Error> {
+    let mut root = self.read_index(self.index_id,).await?;
+    let mut shards = HashMap::new();
+    //The latest name of every card changed so far.
+    let mut current = HashMap::new();
+    let mut changed = false;
+
+    for (id, old, new,) in changes {
+      let old = current.remove(&id,).unwrap_or(old,);
+
+      if old != new {
+        if let Some(old) = &old {
+          let (old, letter,) = key(old,);
+          let names = self.names_mut(&mut root, &mut shards, letter,).await?;
+
+          if names.get(&old,) == Some(&id) {
+            names.remove(&old,);
+            changed = true;
+          }
+        }
+      }
+      if let Some(new) = &new {
+        let (new, letter,) = key(new,);
+
+        changed |= self.names_mut(&mut root, &mut shards, letter,).await?
+          .insert(new, id,) != Some(id);
+      }
+
+      current.insert(id, new,);
+    }
+
+    if !changed { return Ok(()) }
+
+    root.split(&mut shards, self.max_len,);
+    self.write_index(&root, &shards,).await
+  }
+  /// Gets the names which a name starting with `letter` is stored in, reading its shard
+  /// if needed.
+  /// 
+  /// # Params
+  /// 
+  /// root --- The root `NameIndex`.  
+  /// shards --- The shards read so far.  
+  /// letter --- The first letter of the name.  
+  async fn names_mut<'a,>(&self, root: &'a mut NameIndex, shards: &'a mut HashMap<char, NameIndex>, letter: Option<char>,) -> Result<&'a mut BTreeMap<String, DocumentId>, Ix::Error> {
+    let letter = match letter.filter(|letter,| root.shards.contains(letter,),) {
+      Some(letter) => letter,
+      None => return Ok(&mut root.names),
+    };
+
+    let shard = match shards.entry(letter,) {
+      Entry::Occupied(shard) => shard.into_mut(),
+      Entry::Vacant(shard) => shard.insert(self.read_index(root.shard_id(letter,),).await?,),
+    };
+
+    Ok(&mut shard.names)
+  }
+  /// Reads an index document, treating a missing document as empty.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the index document.  
+  async fn read_index(&self, id: DocumentId,) -> Result<NameIndex, Ix::Error> {
+    match self.index.get_document(&id,).await {
+      Ok(index) => Ok(index),
+      Err(e) if e.is_not_found() => Ok(NameIndex::new(id,)),
+      Err(e) => Err(e),
+    }
+  }
+  /// Writes the root index document and its shards.
+  /// 
+  /// # Params
+  /// 
+  /// root --- The root `NameIndex`.  
+  /// shards --- The shards to write.  
+  async fn write_index(&self, root: &NameIndex, shards: &HashMap<char, NameIndex>,) -> Result<(), Ix::Error> {
+    //Write the shards first so the root never refers to a shard which is missing.
+    match self.index.write_documents(shards.values().chain(iter::once(root,),),).await? {
+      Ok(()) => Ok(()),
+      Err(results) => results.into_iter().collect(),
+    }
+  }
+  /// Updates the index, ignoring failures.
+  /// 
+  /// # Params
+  /// 
+  /// changes --- The changes in the order they were made.  
+  async fn update_index(&self, changes: Vec<Change>,) {
+    if changes.is_empty() { return }
+
+    //The index is best effort; `rebuild_index` recovers from a failed update.
+    let _ = self.apply_changes(changes,).await;
+  }
+}
+
+impl<Coll, Ix,> TierListCollection for IndexedCollection<Coll, Ix,>
+  where Coll: 'static + TierListCollection<Document = TierDocument> + Clone,
+    Ix: 'static + TierListCollection<Document = NameIndex> + Clone, {
+  type Document = TierDocument;
+  type Error = Coll::Error;
+  type GetBatchDocuments = Coll::GetBatchDocuments;
+  type GetDocument = Coll::GetDocument;
+  type WriteBatchDocuments = LocalBoxFuture<'static, Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>;
+  type WriteDocument = LocalBoxFuture<'static, Result<(), Self::Error>>;
+
+  #[inline]
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    self.inner.get_documents(ids,)
+  }
+  #[inline]
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument { self.inner.get_document(id,) }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let collection = self.clone();
+    let documents = documents.into_iter().map(|doc,| doc.borrow().clone(),).collect::<Vec<_>>();
+
+    Box::pin(async move {
+      let old = collection.inner.get_documents(documents.iter().map(Document::get_id,),).await
+        .unwrap_or_default();
+      let res = collection.inner.write_documents(&documents,).await?;
+      let changes = documents.iter().enumerate()
+        .filter(|&(i, _,),| match &res {
+          Ok(()) => true,
+          Err(results) => results.get(i,).is_some_and(Result::is_ok,),
+        },)
+        .map(|(i, doc,),| (
+          *doc.get_id(),
+          old.get(i,).and_then(|old,| old.as_ref().ok(),).and_then(card_name,),
+          card_name(doc,),
+        ),)
+        .collect();
+
+      collection.update_index(changes,).await;
+
+      Ok(res)
+    },)
+  }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    let collection = self.clone();
+    let document = document.borrow().clone();
+
+    Box::pin(async move {
+      let id = *document.get_id();
+      let old = collection.inner.get_document(&id,).await.ok();
+
+      collection.inner.write_document(&document,).await?;
+      collection.update_index(vec![(id, old.as_ref().and_then(card_name,), card_name(&document,),)],).await;
+
+      Ok(())
+    },)
+  }
+}
+
+impl<Coll, Ix,> TransactionalCollection for IndexedCollection<Coll, Ix,>
+  where Coll: 'static + TransactionalCollection<Document = TierDocument> + Clone,
+    Coll::Transaction: 'static,
+    Ix: 'static + TierListCollection<Document = NameIndex> + Clone, {
+  type Transaction = IndexedTransaction<Coll, Ix,>;
+
+  #[inline]
+  fn begin(&self,) -> Self::Transaction {
+    IndexedTransaction { inner: self.inner.begin(), collection: self.clone(), staged: Vec::new(), }
+  }
+}
+
+/// A `Transaction` against an `IndexedCollection` which updates the index once it is
+/// committed.
+pub struct IndexedTransaction<Coll, Ix,>
+  where Coll: TransactionalCollection, {
+  /// The transaction against the inner collection.
+  inner: Coll::Transaction,
+  /// The collection the transaction is against.
+  collection: IndexedCollection<Coll, Ix,>,
+  /// The identifier and new name of every staged change in the order they were staged.
+  staged: Vec<(DocumentId, Option<String>,)>,
+}
+
+impl<Coll, Ix,> Transaction for IndexedTransaction<Coll, Ix,>
+  where Coll: 'static + TransactionalCollection<Document = TierDocument> + Clone,
+    Coll::Transaction: 'static,
+    Ix: 'static + TierListCollection<Document = NameIndex> + Clone, {
+  type Document = TierDocument;
+  type Error = Coll::Error;
+  type Commit = LocalBoxFuture<'static, Result<(), Self::Error>>;
+
+  #[inline]
+  fn stage_write<T,>(&mut self, document: &T,)
+    where T: Borrow<Self::Document>, {
+    let document = document.borrow();
+
+    self.staged.push((*document.get_id(), card_name(document,),),);
+    self.inner.stage_write(document,)
+  }
+  #[inline]
+  fn stage_delete(&mut self, id: &DocumentId,) {
+    self.staged.push((*id, None,),);
+    self.inner.stage_delete(id,)
+  }
+  fn commit(self,) -> Self::Commit {
+    let Self { inner, collection, staged, } = self;
+
+    Box::pin(async move {
+      let old = collection.inner.get_documents(staged.iter().map(|(id, _,),| id,),).await
+        .unwrap_or_default();
+
+      inner.commit().await?;
+
+      let changes = staged.into_iter().enumerate()
+        .map(|(i, (id, new,),),| (id, old.get(i,).and_then(|old,| old.as_ref().ok(),).and_then(card_name,), new,),)
+        .collect();
+
+      collection.update_index(changes,).await;
+
+      Ok(())
+    },)
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{CardStatus, TierMeta, TierPolicy, TierName, TierListSnapshot, TierSnapshot, CardSnapshot, ImportOptions, MemoryCollection, import_list,};
+  use futures::executor::block_on;
+
+  const INDEX_ID: DocumentId = DocumentId::from_bytes([0xAA; 20]);
+
+  fn snapshot() -> TierListSnapshot {
+    let card = |id: u8, name: &str,| CardSnapshot {
+      id: DocumentId::from_bytes([id; 20]),
+      name: name.to_owned(),
+      description: String::new(),
+      up_votes: 0,
+      down_votes: 0,
+      bias: 0,
+      status: CardStatus::Open,
+      history: Vec::new(),
+    };
+
+    TierListSnapshot {
+      tiers: vec![
+        TierSnapshot { id: DocumentId::from_bytes([1; 20]), cards: vec![card(10, "Gold",), card(11, "Silver",),], promote_threshold: None, policy: TierPolicy::OPEN, name: TierName::EMPTY, },
+        TierSnapshot { id: DocumentId::from_bytes([2; 20]), cards: vec![card(12, "Bronze",), card(13, "Glass",), card(14, "Granite",),], promote_threshold: None, policy: TierPolicy::OPEN, name: TierName::EMPTY, },
+      ],
+    }
+  }
+  fn collection() -> IndexedCollection<MemoryCollection, MemoryCollection<NameIndex,>,> {
+    IndexedCollection::new(MemoryCollection::new(), MemoryCollection::new(), INDEX_ID, 2,)
+  }
+
+  #[test]
+  fn test_shard_id() {
+    let root = NameIndex::new(INDEX_ID,);
+
+    assert_ne!(root.shard_id('a',), root.id, "Error shard shares the root identifier",);
+    assert_ne!(root.shard_id('a',), root.shard_id('b',), "Error shards share an identifier",);
+    assert_eq!(root.shard_id('a',), root.shard_id('a',), "Error shard identifier changed",);
+  }
+  #[test]
+  fn test_lookup_name() {
+    let coll = collection();
+    let preserve = ImportOptions { preserve_ids: true, ..ImportOptions::default() };
+
+    block_on(async {
+      import_list(&coll, &snapshot(), preserve,).await
+        .expect("Error importing list");
+
+      assert_eq!(coll.lookup_name("gold",).await, Ok(Some(DocumentId::from_bytes([10; 20]))), "Error finding card",);
+      assert_eq!(coll.lookup_name("GRANITE",).await, Ok(Some(DocumentId::from_bytes([14; 20]))), "Error finding card ignoring case",);
+      assert_eq!(coll.lookup_name("Bronze",).await, Ok(Some(DocumentId::from_bytes([12; 20]))), "Error finding card",);
+      assert_eq!(coll.lookup_name("Platinum",).await, Ok(None), "Error finding missing card",);
+
+      //The names starting with the most common letters were moved into shards.
+      let root = coll.get_index().get_document(&INDEX_ID,).await
+        .expect("Error reading index");
+      assert!(!root.shards.is_empty(), "Error index was not sharded",);
+      assert!(root.names.len() <= 2, "Error index stores too many names",);
+
+      //Renaming a card moves its entry.
+      let mut card = coll.get_item_flat::<Card>(&DocumentId::from_bytes([11; 20]),).await
+        .expect("Error reading card");
+      card.name = "Steel".to_owned();
+      coll.write_item(card,).await
+        .expect("Error renaming card");
+      assert_eq!(coll.lookup_name("Steel",).await, Ok(Some(DocumentId::from_bytes([11; 20]))), "Error finding renamed card",);
+      assert_eq!(coll.lookup_name("Silver",).await, Ok(None), "Error finding old name",);
+    },);
+  }
+  #[test]
+  fn test_delete() {
+    let coll = collection();
+    let preserve = ImportOptions { preserve_ids: true, ..ImportOptions::default() };
+
+    block_on(async {
+      import_list(&coll, &snapshot(), preserve,).await
+        .expect("Error importing list");
+
+      let mut transaction = coll.begin();
+      transaction.stage_delete(&DocumentId::from_bytes([13; 20]),);
+      transaction.stage_delete(&DocumentId::from_bytes([11; 20]),);
+      transaction.commit().await
+        .expect("Error deleting cards");
+
+      assert_eq!(coll.lookup_name("Glass",).await, Ok(None), "Error finding deleted card",);
+      assert_eq!(coll.lookup_name("Silver",).await, Ok(None), "Error finding deleted card",);
+      assert_eq!(coll.lookup_name("Gold",).await, Ok(Some(DocumentId::from_bytes([10; 20]))), "Error lost card",);
+    },);
+  }
+  #[test]
+  fn test_rebuild_index() {
+    let coll = collection();
+    let preserve = ImportOptions { preserve_ids: true, ..ImportOptions::default() };
+
+    block_on(async {
+      let list_head = import_list(&coll, &snapshot(), preserve,).await
+        .expect("Error importing list");
+
+      //Corrupt the root index document.
+      let mut corrupt = NameIndex::new(INDEX_ID,);
+      corrupt.names.insert("silver".to_owned(), DocumentId::from_bytes([99; 20]),);
+      corrupt.shards.insert('x',);
+      coll.get_index().write_document(&corrupt,).await
+        .expect("Error corrupting index");
+      assert_eq!(coll.lookup_name("Gold",).await, Ok(None), "Error index was not corrupted",);
+
+      //A tier of unknown length is still indexed.
+      let mut tier = coll.get_item_flat::<TierMeta>(&DocumentId::from_bytes([2; 20]),).await
+        .expect("Error reading tier");
+      tier.set_ends((None, *tier.list_front(), *tier.list_back(),),);
+      coll.write_item(tier,).await
+        .expect("Error writing tier");
+
+      assert_eq!(coll.rebuild_index(&list_head,).await, Ok(5), "Error rebuilding index",);
+      for (name, id,) in [("Gold", 10,), ("Silver", 11,), ("Bronze", 12,), ("Glass", 13,), ("Granite", 14,),] {
+        assert_eq!(coll.lookup_name(name,).await, Ok(Some(DocumentId::from_bytes([id; 20]))), "Error finding {} after rebuilding", name,);
+      }
+    },);
+  }
+}
@@ -4,27 +4,124 @@
 //! move between tiers based on upvotes and downvotes.
 //! 
 //! Author --- daniel.bechaz@gmail.com  
-//! Last Moddified --- 2019-06-02
+//! Last Moddified --- 2026-10-14
 
 #![deny(missing_docs,)]
-#![feature(async_await, await_macro, associated_type_defaults,
-  const_fn, never_type, gen_future, try_trait, generator_trait,
-  generators,
-)]
+//The futures of the collections are named combinator types.
+#![allow(clippy::type_complexity,)]
 
+mod document_id;
+mod error;
 mod card;
 mod tier_meta;
+mod tier_document;
 mod tier_collection;
+mod transaction;
+mod batch;
+mod prefetch;
+mod window;
+mod list_cursor;
+mod zip;
+mod cursor_mut;
+mod list_stream;
+mod list;
+mod promote;
+mod vote;
+mod watch;
+mod revision;
+mod counters;
+mod snapshot;
+mod memory;
+mod sync_memory;
+mod blocking;
+mod timer;
+mod retry;
+mod rate_limited;
+mod parallel;
+mod timeout;
+mod metered;
+mod read_only;
+mod checked;
+mod namespaced;
+mod expiring;
+mod export;
+mod orphans;
+mod lookup;
+mod indexed;
+mod health;
+mod split;
+mod sharded;
+mod envelope;
+#[cfg(feature = "compress",)]
+mod compressed;
+#[cfg(feature = "encrypt",)]
+mod encrypted;
+#[cfg(feature = "tracing",)]
+mod traced;
+#[cfg(feature = "dynamo",)]
+mod dynamo;
+#[cfg(feature = "postgres",)]
+mod postgres;
+#[cfg(feature = "firestore",)]
+mod firestore;
+#[cfg(feature = "couch",)]
+mod couch;
+#[cfg(feature = "wasm",)]
+mod idb;
+#[cfg(any(test, feature = "test-util",),)]
+mod recording;
+#[cfg(test,)]
+mod mock;
+pub mod conformance;
 
-pub use self::{card::*, tier_meta::*, tier_collection::*,};
-
-/// The identifier for a document.
-pub type DocumentId = [u8; 20];
+pub use self::{document_id::*, error::*, card::*, tier_meta::*, tier_document::*, tier_collection::*, transaction::*, batch::*, prefetch::*, window::*, list_cursor::*, zip::*, cursor_mut::*, list_stream::*, list::*, promote::*, vote::*, watch::*, revision::*, counters::*, snapshot::*, memory::*,
+  sync_memory::*, blocking::*, timer::*, retry::*, rate_limited::*, parallel::*, timeout::*, metered::*, read_only::*, checked::*, namespaced::*, expiring::*, export::*, orphans::*, lookup::*, indexed::*, health::*, split::*, sharded::*,
+  envelope::*,
+};
+use std::{hash::{Hash, Hasher,}, collections::hash_map::DefaultHasher,};
+#[cfg(feature = "compress",)]
+pub use self::compressed::*;
+#[cfg(feature = "encrypt",)]
+pub use self::encrypted::*;
+#[cfg(feature = "tracing",)]
+pub use self::traced::*;
+#[cfg(feature = "dynamo",)]
+pub use self::dynamo::*;
+#[cfg(feature = "postgres",)]
+pub use self::postgres::*;
+#[cfg(feature = "firestore",)]
+pub use self::firestore::*;
+#[cfg(feature = "couch",)]
+pub use self::couch::*;
+#[cfg(feature = "wasm",)]
+pub use self::idb::*;
+#[cfg(any(test, feature = "test-util",),)]
+pub use self::recording::*;
 
 /// A trait which defines the common elements of database documents.
 pub trait Document {
   /// Gets the `DocumentId` of this document.
   fn get_id(&self,) -> &DocumentId;
+  /// Gets a fingerprint of the content of this document which is equal for equal
+  /// documents, used by `CheckedWrites` to tell a rewrite from a different document with
+  /// the same identifier.
+  /// 
+  /// By default the document is hashed so fingerprints should only be compared within
+  /// one build.
+  #[inline]
+  fn content_fingerprint(&self,) -> u64
+    where Self: Hash, {
+    let mut hasher = DefaultHasher::new();
+
+    self.hash(&mut hasher,);
+    hasher.finish()
+  }
+}
+
+/// A trait for documents which can report the size of their encoded form.
+pub trait EncodedLen {
+  /// Gets the number of bytes used to encode this document.
+  fn encoded_len(&self,) -> usize;
 }
 
 /// A trait for database documents which are also nodes in a doubly linked list.
@@ -40,3 +137,25 @@ pub trait LinkedList: Document {
   #[inline]
   fn is_back(&self,) -> bool { self.get_next_id().is_none() }
 }
+
+/// A trait for nodes in a doubly linked list whose links can be changed.
+pub trait LinkedListMut: LinkedList {
+  /// Sets the identifier of the previous document.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the previous document.  
+  fn set_previous_id(&mut self, id: Option<DocumentId>,);
+  /// Sets the identifier of the next document.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the next document.  
+  fn set_next_id(&mut self, id: Option<DocumentId>,);
+  /// Clears both links of this document.
+  #[inline]
+  fn detach(&mut self,) {
+    self.set_previous_id(None,);
+    self.set_next_id(None,);
+  }
+}
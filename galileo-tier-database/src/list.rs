@@ -0,0 +1,1865 @@
+//! Defines the low level operations which link, unlink, check and repair the nodes of a
+//! linked list stored in a collection.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, LinkedList, LinkedListMut, BatchReport, TierMeta, Card, ScoreKind, CollectError, DEFAULT_COLLECT_CAP, TierListCollection,};
+use futures::{stream, Stream, StreamExt, TryStreamExt,};
+use std::{fmt, error::Error, iter, mem, cmp::Ordering, ops::ControlFlow, num::NonZeroU64, collections::{HashSet, HashMap,},};
+
+/// The most steps `detect_cycle` takes while keeping the identifiers it visited; a
+/// longer search uses Floyd's algorithm instead.
+const CYCLE_SET_LIMIT: u64 = 1024;
+/// The most changed nodes `repair` and `reverse` write in each batch.
+const LIST_BATCH_SIZE: usize = 100;
+
+/// The documents touched when linking or unlinking a node.
+#[derive(PartialEq, Eq, Clone, Debug,)]
+pub struct LinkReport<E,> {
+  /// The identifiers of the documents read, in the order they were read.
+  pub read: Vec<DocumentId>,
+  /// The result of writing each changed document, in list order.
+  pub written: BatchReport<E,>,
+}
+
+impl<E,> LinkReport<E,> {
+  /// Returns `true` if every changed document was written.
+  #[inline]
+  pub fn is_complete_success(&self,) -> bool { self.written.is_complete_success() }
+}
+
+/// The length of a linked list, returned by `walk_len`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum WalkLen {
+  /// The list ended after this many nodes.
+  Exact(u64),
+  /// The cap was reached with more nodes after it.
+  AtLeast(u64),
+}
+
+impl WalkLen {
+  /// Gets the length if the list ended before the cap.
+  #[inline]
+  pub const fn exact(&self,) -> Option<u64> {
+    match self {
+      WalkLen::Exact(len) => Some(*len,),
+      WalkLen::AtLeast(_) => None,
+    }
+  }
+}
+
+/// A change made by `repair` to a node.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum LinkFix {
+  /// The previous link of the node did not match the node it was reached from.
+  Previous {
+    /// The identifier of the node.
+    id: DocumentId,
+    /// The old previous link.
+    old: Option<DocumentId>,
+    /// The new previous link.
+    new: Option<DocumentId>,
+  },
+  /// The next link of the node led back into the list and was cleared.
+  Truncated {
+    /// The identifier of the node.
+    id: DocumentId,
+    /// The old next link.
+    old: DocumentId,
+  },
+}
+
+/// The result of repairing a linked list, returned by `repair`.
+#[derive(PartialEq, Eq, Clone, Debug,)]
+pub struct RepairReport<E,> {
+  /// The changes made, in list order.
+  pub fixes: Vec<LinkFix>,
+  /// The number of nodes in the repaired list.
+  pub len: u64,
+  /// The identifier of the back of the repaired list.
+  pub back: DocumentId,
+  /// The result of writing each corrected node.
+  pub written: BatchReport<E,>,
+}
+
+impl<E,> RepairReport<E,> {
+  /// Returns `true` if the list needed no changes.
+  #[inline]
+  pub fn is_clean(&self,) -> bool { self.fixes.is_empty() }
+}
+
+/// The result of sorting the cards of a tier, returned by `sort_tier` and `apply_order`.
+#[derive(PartialEq, Eq, Clone, Debug,)]
+pub struct SortReport<E,> {
+  /// The number of cards in the tier.
+  pub len: u64,
+  /// The number of cards whose position changed.
+  pub moved: u64,
+  /// The result of writing each relinked card, in the new order.
+  pub written: BatchReport<E,>,
+}
+
+/// The errors when sorting the cards of a tier.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum SortError<E,> {
+  /// Getting a card failed; nothing was written.
+  Fetch(E),
+  /// The list reached the card with the identifier twice; nothing was written.
+  Cycle(DocumentId),
+  /// Writing a batch of relinked cards failed.
+  Write(E),
+}
+
+impl<E,> fmt::Display for SortError<E,>
+  where E: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      SortError::Fetch(e) => write!(fmt, "failed to get a card: {}", e,),
+      SortError::Cycle(id) => write!(fmt, "the card with the id {:?} was reached twice", id,),
+      SortError::Write(e) => write!(fmt, "failed to write the relinked cards: {}", e,),
+    }
+  }
+}
+
+impl<E,> Error for SortError<E,>
+  where E: Error, {}
+
+/// The errors when putting the nodes of a tier into an order.
+#[derive(PartialEq, Eq, Clone, Debug,)]
+pub enum OrderError<E,> {
+  /// Getting a node failed; nothing was written.
+  Fetch(E),
+  /// The list reached the node with the identifier twice; nothing was written.
+  Cycle(DocumentId),
+  /// The order does not hold each node of the tier exactly once; nothing was written.
+  Membership {
+    /// The identifiers of the nodes of the tier missing from the order.
+    missing: Vec<DocumentId>,
+    /// The identifiers in the order which are not nodes of the tier or are repeated.
+    extra: Vec<DocumentId>,
+  },
+  /// Writing a batch of relinked nodes failed.
+  Write(E),
+}
+
+impl<E,> fmt::Display for OrderError<E,>
+  where E: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      OrderError::Fetch(e) => write!(fmt, "failed to get a node: {}", e,),
+      OrderError::Cycle(id) => write!(fmt, "the node with the id {:?} was reached twice", id,),
+      OrderError::Membership { missing, extra, } => write!(fmt, "the order is missing {} nodes and has {} extra", missing.len(), extra.len(),),
+      OrderError::Write(e) => write!(fmt, "failed to write the relinked nodes: {}", e,),
+    }
+  }
+}
+
+impl<E,> Error for OrderError<E,>
+  where E: Error, {}
+
+/// The errors when rotating a tier.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum RotateError<E,> {
+  /// Getting a node failed; nothing was written.
+  Fetch(E),
+  /// The list reached the node with the identifier twice; nothing was written.
+  Cycle(DocumentId),
+  /// The tier has no length and counting it reached the cap; nothing was written.
+  TooLong(u64),
+  /// The list ended at the node with the identifier before the length of the tier;
+  /// nothing was written.
+  Ended(DocumentId),
+  /// Writing the relinked nodes failed.
+  Write(E),
+}
+
+impl<E,> fmt::Display for RotateError<E,>
+  where E: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      RotateError::Fetch(e) => write!(fmt, "failed to get a node: {}", e,),
+      RotateError::Cycle(id) => write!(fmt, "the node with the id {:?} was reached twice", id,),
+      RotateError::TooLong(cap) => write!(fmt, "the tier has more than {} nodes", cap,),
+      RotateError::Ended(id) => write!(fmt, "the list ended early at the node with the id {:?}", id,),
+      RotateError::Write(e) => write!(fmt, "failed to write the relinked nodes: {}", e,),
+    }
+  }
+}
+
+impl<E,> Error for RotateError<E,>
+  where E: Error, {}
+
+/// The errors when linking or unlinking a node.
+/// 
+/// Nothing is written after any error except `Write`, which means the batch write
+/// failed as a whole.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum LinkError<E,> {
+  /// Getting a node failed.
+  Fetch(E),
+  /// Writing the changed nodes failed.
+  Write(E),
+  /// The node with the identifier would be linked twice.
+  Duplicate(DocumentId),
+  /// The node with the first identifier does not link back to the node with the second
+  /// identifier so relinking would break the list further.
+  Inconsistent(DocumentId, DocumentId),
+  /// A walk to find where to link reached the node with the identifier twice.
+  Cycle(DocumentId),
+  /// The node with the identifier is not the back of its list.
+  NotBack(DocumentId),
+  /// The node with the identifier is not the front of its list.
+  NotFront(DocumentId),
+}
+
+impl<E,> fmt::Display for LinkError<E,>
+  where E: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      LinkError::Fetch(e) => write!(fmt, "failed to get a node: {}", e,),
+      LinkError::Write(e) => write!(fmt, "failed to write the changed nodes: {}", e,),
+      LinkError::Duplicate(id) => write!(fmt, "the node with the id {:?} would be linked twice", id,),
+      LinkError::Inconsistent(node, neighbour) => write!(fmt, "the node with the id {:?} does not link back to the node with the id {:?}", node, neighbour,),
+      LinkError::Cycle(id) => write!(fmt, "the node with the id {:?} was reached twice", id,),
+      LinkError::NotBack(id) => write!(fmt, "the node with the id {:?} is not the back of its list", id,),
+      LinkError::NotFront(id) => write!(fmt, "the node with the id {:?} is not the front of its list", id,),
+    }
+  }
+}
+
+impl<E,> Error for LinkError<E,>
+  where E: Error, {}
+
+/// Links a node into a linked list after another node.
+/// 
+/// The anchor and its next node are read, the three nodes are relinked and written using
+/// a single batch write. Documents which refer to the list from outside, such as the
+/// ends of a tier, are not updated.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the list.  
+/// anchor_id --- The identifier of the node to link `new` after.  
+/// new --- The node to link; its links are replaced.  
+pub async fn link_after<T, Coll,>(collection: &Coll, anchor_id: &DocumentId, new: T,) -> Result<LinkReport<Coll::Error,>, LinkError<Coll::Error,>>
+  where T: LinkedListMut + Clone + Into<Coll::Document>,
+    Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  let anchor: T = collection.get_document(anchor_id,).await
+    .map_err(LinkError::Fetch,)?
+    .into();
+  let (_, mut report,) = link_item_after(collection, anchor, new,).await?;
+
+  report.read.insert(0, *anchor_id,);
+  Ok(report)
+}
+
+/// Links a node into a linked list after a node which has already been read and returns
+/// the relinked anchor.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the list.  
+/// anchor --- The node to link `new` after.  
+/// new --- The node to link; its links are replaced.  
+pub(crate) async fn link_item_after<T, Coll,>(collection: &Coll, mut anchor: T, mut new: T,) -> Result<(T, LinkReport<Coll::Error,>,), LinkError<Coll::Error,>>
+  where T: LinkedListMut + Clone + Into<Coll::Document>,
+    Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  let anchor_id = *anchor.get_id();
+  let new_id = *new.get_id();
+  let next_id = anchor.get_next_id().copied();
+  let mut read = Vec::new();
+
+  if new_id == anchor_id || Some(new_id,) == next_id { return Err(LinkError::Duplicate(new_id,)) }
+
+  let next = match next_id {
+    Some(next_id) => {
+      read.push(next_id,);
+
+      let mut next: T = collection.get_document(&next_id,).await
+        .map_err(LinkError::Fetch,)?
+        .into();
+      if next.get_previous_id() != Some(&anchor_id,) { return Err(LinkError::Inconsistent(next_id, anchor_id,)) }
+
+      next.set_previous_id(Some(new_id,),);
+      Some(next,)
+    },
+    None => None,
+  };
+
+  new.set_previous_id(Some(anchor_id,),);
+  new.set_next_id(next_id,);
+  anchor.set_next_id(Some(new_id,),);
+
+  let documents = iter::once(anchor.clone(),).chain(Some(new,),).chain(next,)
+    .map(Into::<Coll::Document>::into,)
+    .collect::<Vec<_>>();
+  let written = collection.write_documents_reported(&documents,).await
+    .map_err(LinkError::Write,)?;
+
+  Ok((anchor, LinkReport { read, written, },))
+}
+
+/// Links a node into a linked list before another node.
+/// 
+/// The anchor and its previous node are read, the three nodes are relinked and written
+/// using a single batch write. Documents which refer to the list from outside, such as
+/// the ends of a tier, are not updated.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the list.  
+/// anchor_id --- The identifier of the node to link `new` before.  
+/// new --- The node to link; its links are replaced.  
+pub async fn link_before<T, Coll,>(collection: &Coll, anchor_id: &DocumentId, new: T,) -> Result<LinkReport<Coll::Error,>, LinkError<Coll::Error,>>
+  where T: LinkedListMut + Clone + Into<Coll::Document>,
+    Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  let anchor: T = collection.get_document(anchor_id,).await
+    .map_err(LinkError::Fetch,)?
+    .into();
+  let (_, mut report,) = link_item_before(collection, anchor, new,).await?;
+
+  report.read.insert(0, *anchor_id,);
+  Ok(report)
+}
+
+/// Links a node into a linked list before a node which has already been read and
+/// returns the relinked anchor.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the list.  
+/// anchor --- The node to link `new` before.  
+/// new --- The node to link; its links are replaced.  
+pub(crate) async fn link_item_before<T, Coll,>(collection: &Coll, mut anchor: T, mut new: T,) -> Result<(T, LinkReport<Coll::Error,>,), LinkError<Coll::Error,>>
+  where T: LinkedListMut + Clone + Into<Coll::Document>,
+    Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  let anchor_id = *anchor.get_id();
+  let new_id = *new.get_id();
+  let previous_id = anchor.get_previous_id().copied();
+  let mut read = Vec::new();
+
+  if new_id == anchor_id || Some(new_id,) == previous_id { return Err(LinkError::Duplicate(new_id,)) }
+
+  let previous = match previous_id {
+    Some(previous_id) => {
+      read.push(previous_id,);
+
+      let mut previous: T = collection.get_document(&previous_id,).await
+        .map_err(LinkError::Fetch,)?
+        .into();
+      if previous.get_next_id() != Some(&anchor_id,) { return Err(LinkError::Inconsistent(previous_id, anchor_id,)) }
+
+      previous.set_next_id(Some(new_id,),);
+      Some(previous,)
+    },
+    None => None,
+  };
+
+  new.set_previous_id(previous_id,);
+  new.set_next_id(Some(anchor_id,),);
+  anchor.set_previous_id(Some(new_id,),);
+
+  let documents = previous.into_iter().chain(Some(new,),).chain(Some(anchor.clone(),),)
+    .map(Into::<Coll::Document>::into,)
+    .collect::<Vec<_>>();
+  let written = collection.write_documents_reported(&documents,).await
+    .map_err(LinkError::Write,)?;
+
+  Ok((anchor, LinkReport { read, written, },))
+}
+
+/// Unlinks a node from a linked list and returns the node with its links as they were.
+/// 
+/// The node is read followed by its neighbours in a single batch read; the neighbours
+/// are linked to each other, the links of the node are cleared and the changed nodes are
+/// written using a single batch write. Documents which refer to the list from outside,
+/// such as the ends of a tier, are not updated.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the list.  
+/// node_id --- The identifier of the node to unlink.  
+pub async fn unlink<T, Coll,>(collection: &Coll, node_id: &DocumentId,) -> Result<(T, LinkReport<Coll::Error,>,), LinkError<Coll::Error,>>
+  where T: LinkedListMut + Clone + Into<Coll::Document>,
+    Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  let node: T = collection.get_document(node_id,).await
+    .map_err(LinkError::Fetch,)?
+    .into();
+  let previous_id = node.get_previous_id().copied();
+  let next_id = node.get_next_id().copied();
+  let ids = previous_id.into_iter().chain(next_id,).collect::<Vec<_>>();
+  let mut neighbours = if ids.is_empty() { Vec::new() } else {
+    collection.get_documents(&ids,).await
+      .map_err(LinkError::Fetch,)?
+      .into_iter()
+      .map(|doc,| doc.map(Into::<T>::into,).map_err(LinkError::Fetch,),)
+      .collect::<Result<Vec<_>, _>>()?
+  }.into_iter();
+  let mut previous = previous_id.and_then(|_,| neighbours.next(),);
+  let mut next = next_id.and_then(|_,| neighbours.next(),);
+
+  if let Some(previous) = &mut previous {
+    if previous.get_next_id() != Some(node_id,) { return Err(LinkError::Inconsistent(*previous.get_id(), *node_id,)) }
+
+    previous.set_next_id(next_id,);
+  }
+  if let Some(next) = &mut next {
+    if next.get_previous_id() != Some(node_id,) { return Err(LinkError::Inconsistent(*next.get_id(), *node_id,)) }
+
+    next.set_previous_id(previous_id,);
+  }
+
+  let mut detached = node.clone();
+  detached.detach();
+
+  let documents = previous.into_iter().chain(Some(detached,),).chain(next,)
+    .map(Into::<Coll::Document>::into,)
+    .collect::<Vec<_>>();
+  let written = collection.write_documents_reported(&documents,).await
+    .map_err(LinkError::Write,)?;
+  let read = iter::once(*node_id,).chain(ids,).collect();
+
+  Ok((node, LinkReport { read, written, },))
+}
+
+/// Links the back of one linked list to the front of another and returns both nodes
+/// after they were relinked.
+/// 
+/// Both nodes are read in a single batch read and written using a single batch write;
+/// no other node is read or written. Linking the ends of the same list would make a
+/// cycle and is not detected. Documents which refer to the lists from outside, such as
+/// the ends of a tier, are not updated.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the lists.  
+/// first_back_id --- The identifier of the back of the first list.  
+/// second_front_id --- The identifier of the front of the second list.  
+pub async fn concat<T, Coll,>(collection: &Coll, first_back_id: &DocumentId, second_front_id: &DocumentId,) -> Result<(T, T, LinkReport<Coll::Error,>,), LinkError<Coll::Error,>>
+  where T: LinkedListMut + Clone + Into<Coll::Document>,
+    Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  if first_back_id == second_front_id { return Err(LinkError::Duplicate(*first_back_id,)) }
+
+  let read = vec![*first_back_id, *second_front_id,];
+  let mut nodes = collection.get_documents(&read,).await
+    .map_err(LinkError::Fetch,)?
+    .into_iter()
+    .map(|doc,| doc.map(Into::<T>::into,).map_err(LinkError::Fetch,),)
+    .collect::<Result<Vec<_>, _>>()?
+    .into_iter();
+  let (mut back, mut front,) = match (nodes.next(), nodes.next(),) {
+    (Some(back), Some(front),) => (back, front,),
+    _ => unreachable!("a batch read returned fewer documents than requested",),
+  };
+
+  if !back.is_back() { return Err(LinkError::NotBack(*first_back_id,)) }
+  if !front.is_front() { return Err(LinkError::NotFront(*second_front_id,)) }
+
+  back.set_next_id(Some(*second_front_id,),);
+  front.set_previous_id(Some(*first_back_id,),);
+
+  let documents = [back.clone().into(), front.clone().into(),];
+  let written = collection.write_documents_reported(&documents,).await
+    .map_err(LinkError::Write,)?;
+
+  Ok((back, front, LinkReport { read, written, },))
+}
+
+/// Searches a linked list forward from a node for a cycle.
+/// 
+/// Returns the identifier of a node inside the cycle, or `None` if the list ended or no
+/// cycle was found within `max_steps` links. Up to `CYCLE_SET_LIMIT` steps each node
+/// is read once; a longer search uses Floyd's tortoise and hare, which keeps no
+/// identifiers but reads nodes more than once and can take up to twice as many steps
+/// as there are nodes before and in the cycle.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the list.  
+/// start_id --- The identifier of the node to search from.  
+/// max_steps --- The most links to follow.  
+pub async fn detect_cycle<T, Coll,>(collection: &Coll, start_id: &DocumentId, max_steps: u64,) -> Result<Option<DocumentId>, Coll::Error>
+  where T: LinkedList,
+    Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  /// Gets the identifier of the node after a node.
+  async fn next_id<T, Coll,>(collection: &Coll, id: &DocumentId,) -> Result<Option<DocumentId>, Coll::Error>
+    where T: LinkedList,
+      Coll: TierListCollection,
+      Coll::Document: Into<T>, {
+    let item: T = collection.get_document(id,).await?.into();
+
+    Ok(item.get_next_id().copied())
+  }
+
+  if max_steps <= CYCLE_SET_LIMIT {
+    let mut current = *start_id;
+    let mut seen = iter::once(current,).collect::<HashSet<_>>();
+
+    for _ in 0..max_steps {
+      match next_id::<T, _,>(collection, &current,).await? {
+        Some(next) if !seen.insert(next,) => return Ok(Some(next,)),
+        Some(next) => current = next,
+        None => return Ok(None),
+      }
+    }
+
+    return Ok(None)
+  }
+
+  let (mut tortoise, mut hare,) = (*start_id, *start_id,);
+  let mut steps = 0;
+
+  while steps < max_steps {
+    //The hare takes two steps for each step of the tortoise.
+    for _ in 0..2 {
+      match next_id::<T, _,>(collection, &hare,).await? {
+        Some(next) => hare = next,
+        None => return Ok(None),
+      }
+      steps += 1;
+    }
+    match next_id::<T, _,>(collection, &tortoise,).await? {
+      Some(next) => tortoise = next,
+      //The list changed behind the hare.
+      None => return Ok(None),
+    }
+
+    if tortoise == hare { return Ok(Some(hare,)) }
+  }
+
+  Ok(None)
+}
+
+/// Streams the documents of a linked list starting with the document with an
+/// identifier and following the next links.
+/// 
+/// Nothing is read until the stream is polled and the stream ends after yielding an
+/// error. As with `Cursor::into_stream` the stream yields `CollectError::Cycle` instead
+/// of reading a document twice or reading more than `DEFAULT_COLLECT_CAP` documents.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the list.  
+/// start_id --- The identifier of the first document to yield.  
+pub fn stream_from<Coll,>(collection: Coll, start_id: &DocumentId,) -> impl Stream<Item = Result<Coll::Document, CollectError<Coll::Error,>>>
+  where Coll: TierListCollection,
+    Coll::Document: LinkedList, {
+  let link = |document: &Coll::Document,| document.get_next_id().copied();
+
+  guard_cycles(walk_links(collection, Err(*start_id,), link,), link,)
+}
+
+/// Folds the documents of a linked list into an accumulator, walking forward from the
+/// document with an identifier using `stream_from`.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the list.  
+/// start_id --- The identifier of the first document to fold.  
+/// init --- The initial accumulator.  
+/// f --- Folds a document into the accumulator.  
+pub async fn fold<Coll, Acc, F,>(collection: Coll, start_id: &DocumentId, init: Acc, mut f: F,) -> Result<Acc, CollectError<Coll::Error,>>
+  where Coll: TierListCollection,
+    Coll::Document: LinkedList,
+    F: FnMut(Acc, &Coll::Document,) -> Acc, {
+  try_fold(collection, start_id, init, |acc, document,| Ok(ControlFlow::Continue(f(acc, document,),),),).await
+}
+
+/// Like `fold` but the closure can stop the walk early.
+/// 
+/// When the closure returns `ControlFlow::Break` the accumulator is returned without
+/// reading any more documents and when it returns an error the walk stops with the
+/// error. Errors from the walk are converted into the error of the closure.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the list.  
+/// start_id --- The identifier of the first document to fold.  
+/// init --- The initial accumulator.  
+/// f --- Folds a document into the accumulator.  
+pub async fn try_fold<Coll, Acc, F, E,>(collection: Coll, start_id: &DocumentId, init: Acc, mut f: F,) -> Result<Acc, E>
+  where Coll: TierListCollection,
+    Coll::Document: LinkedList,
+    F: FnMut(Acc, &Coll::Document,) -> Result<ControlFlow<Acc, Acc,>, E>,
+    E: From<CollectError<Coll::Error,>>, {
+  let mut walk = Box::pin(stream_from(collection, start_id,),);
+  let mut acc = init;
+
+  while let Some(document) = walk.next().await {
+    acc = match f(acc, &document?,)? {
+      ControlFlow::Continue(acc) => acc,
+      ControlFlow::Break(acc) => return Ok(acc),
+    };
+  }
+
+  Ok(acc)
+}
+
+/// Streams the nodes of a linked list following a link.
+/// 
+/// The stream ends after yielding an error.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the list.  
+/// start --- The first item to yield or the identifier of the first node to read.  
+/// link --- Gets the identifier of the node after an item in the stream.  
+pub(crate) fn walk_links<T, Coll,>(collection: Coll, start: Result<T, DocumentId>, link: fn(&T,) -> Option<DocumentId>,) -> impl Stream<Item = Result<T, Coll::Error>>
+  where Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  //The state holds either the item to yield or the identifier of the node to read.
+  stream::unfold(Some((collection, start,),), move |state,| async move {
+    let (collection, next,) = state?;
+    let item = match next {
+      Ok(item) => item,
+      Err(id) => match collection.get_document(&id,).await {
+        Ok(document) => document.into(),
+        Err(e) => return Some((Err(e), None,)),
+      },
+    };
+    let state = link(&item,).map(|id,| (collection, Err(id),),);
+
+    Some((Ok(item), state,))
+  },)
+}
+
+/// Guards a stream of the nodes of a linked list against cycles.
+/// 
+/// The identifiers of the yielded nodes are kept and the stream yields
+/// `CollectError::Cycle` instead of reading a node twice or reading more than
+/// `DEFAULT_COLLECT_CAP` nodes.
+/// 
+/// # Params
+/// 
+/// walk --- The stream of nodes.  
+/// link --- Gets the identifier of the node after an item in the stream.  
+pub(crate) fn guard_cycles<T, S, E,>(walk: S, link: fn(&T,) -> Option<DocumentId>,) -> impl Stream<Item = Result<T, CollectError<E,>>>
+  where T: LinkedList,
+    S: Stream<Item = Result<T, E>>, {
+  let walk = Box::pin(walk,);
+
+  //The state holds the identifiers yielded and the node the walk stopped at, if any.
+  stream::unfold(Some((walk, HashSet::new(), None,),), move |state,| async move {
+    let (mut walk, mut seen, cycle,) = state?;
+
+    //The repeated node is never read.
+    if let Some(id) = cycle { return Some((Err(CollectError::Cycle(id,),), None,)) }
+
+    match walk.next().await? {
+      Ok(item) => {
+        seen.insert(*item.get_id(),);
+
+        let cycle = link(&item,).filter(|id,| seen.contains(id,) || seen.len() >= DEFAULT_COLLECT_CAP,);
+        Some((Ok(item), Some((walk, seen, cycle,),),))
+      },
+      Err(e) => Some((Err(CollectError::Fetch(e,),), None,)),
+    }
+  },)
+}
+
+/// Counts the nodes of a linked list by walking it forward from its front.
+/// 
+/// Each node only knows the identifier of the node after it so the nodes are read one
+/// after another; nothing past the `cap`th node is read, which also bounds the walk of a
+/// list with a cycle.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the list.  
+/// front_id --- The identifier of the front of the list.  
+/// cap --- The most nodes to count.  
+pub async fn walk_len<T, Coll,>(collection: &Coll, front_id: &DocumentId, cap: u64,) -> Result<WalkLen, Coll::Error>
+  where T: LinkedList,
+    Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  let mut next_id = Some(*front_id,);
+  let mut len = 0;
+
+  while let Some(id) = next_id {
+    if len == cap { return Ok(WalkLen::AtLeast(len,)) }
+
+    let item: T = collection.get_document(&id,).await?.into();
+    next_id = item.get_next_id().copied();
+    len += 1;
+  }
+
+  Ok(WalkLen::Exact(len,))
+}
+
+/// Gets the length of a tier, counting it with `walk_len` up to `cap` nodes if its
+/// length is unknown.
+/// 
+/// A tier of unknown length is only empty if its front is nil or not stored; otherwise
+/// its nodes are counted from its front.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the list.  
+/// tier --- The tier the list makes up.  
+/// cap --- The most nodes to count.  
+pub async fn tier_len<T, Coll,>(collection: &Coll, tier: &TierMeta, cap: u64,) -> Result<WalkLen, Coll::Error>
+  where T: LinkedList,
+    Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  if let Some(len) = tier.list_len() { return Ok(WalkLen::Exact(len.get(),)) }
+  if tier.list_front().is_nil() || !collection.exists(tier.list_front(),).await? { return Ok(WalkLen::Exact(0,)) }
+
+  walk_len::<T, _,>(collection, tier.list_front(), cap,).await
+}
+
+/// Returns `true` if `tier` has no nodes, reading at most its front node.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the list.  
+/// tier --- The tier the list makes up.  
+pub(crate) async fn tier_is_empty<T, Coll,>(collection: &Coll, tier: &TierMeta,) -> Result<bool, Coll::Error>
+  where T: LinkedList,
+    Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  Ok(tier_len::<T, _,>(collection, tier, 1,).await? == WalkLen::Exact(0,))
+}
+
+/// Gets the `n`th node of a linked list by walking it forward from its front, counting
+/// from zero.
+/// 
+/// Nothing past the `n`th node is read and the walk is guarded by `guard_cycles`. If the
+/// list has `n` or fewer nodes this returns `None`.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the list.  
+/// front_id --- The identifier of the front of the list.  
+/// n --- The position of the node to get.  
+pub async fn nth<T, Coll,>(collection: &Coll, front_id: &DocumentId, n: u64,) -> Result<Option<T>, CollectError<Coll::Error,>>
+  where T: LinkedList,
+    Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  let link = |item: &T,| item.get_next_id().copied();
+  let mut walk = Box::pin(guard_cycles(walk_links(collection, Err(*front_id,), link,), link,),);
+  let mut position = 0;
+
+  while let Some(item) = walk.next().await {
+    let item = item?;
+
+    if position == n { return Ok(Some(item,)) }
+    position += 1;
+  }
+
+  Ok(None)
+}
+
+/// Gets the middle node of a tier using its cached length; of an even number of nodes
+/// the first of the two middle nodes is returned.
+/// 
+/// Only the nodes up to the middle are read. A tier without a length is counted with
+/// `tier_len` first and fails with `CollectError::Cycle` if it has more than
+/// `DEFAULT_COLLECT_CAP` nodes; an empty tier returns `None`.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the list.  
+/// tier --- The tier the list makes up.  
+pub async fn tier_median<T, Coll,>(collection: &Coll, tier: &TierMeta,) -> Result<Option<T>, CollectError<Coll::Error,>>
+  where T: LinkedList,
+    Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  let len = match tier_len::<T, _,>(collection, tier, DEFAULT_COLLECT_CAP as u64,).await
+    .map_err(CollectError::Fetch,)? {
+    WalkLen::Exact(0) => return Ok(None),
+    WalkLen::Exact(len) => len,
+    //Walking past the cap is reported by `guard_cycles`.
+    WalkLen::AtLeast(cap) => return nth(collection, tier.list_front(), cap,).await,
+  };
+
+  nth(collection, tier.list_front(), (len - 1) / 2,).await
+}
+
+/// Repairs the backward links of a linked list by walking it forward from its front.
+/// 
+/// The next links are trusted: each node whose previous link does not match the node it
+/// was reached from is corrected and a node whose next link leads back to a node already
+/// walked becomes the back of the list. The corrected nodes are written in batches as the
+/// walk goes so an error after the first batch leaves the list partly repaired; running
+/// the repair again finishes it.
+/// 
+/// If `tier` is given its length and ends are set to match the repaired list; the tier
+/// is not written.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the list.  
+/// front_id --- The identifier of the front of the list.  
+/// tier --- The tier the list makes up, if it should be updated.  
+pub async fn repair<T, Coll,>(collection: &Coll, front_id: &DocumentId, tier: Option<&mut TierMeta>,) -> Result<RepairReport<Coll::Error,>, LinkError<Coll::Error,>>
+  where T: LinkedListMut + Into<Coll::Document>,
+    Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  let mut fixes = Vec::new();
+  let mut written = Vec::new();
+  let mut batch = Vec::with_capacity(LIST_BATCH_SIZE,);
+  let mut seen = HashSet::new();
+  let (mut previous_id, mut next_id,) = (None, Some(*front_id,),);
+  let (mut len, mut back,) = (0, *front_id,);
+
+  while let Some(id) = next_id {
+    let mut item: T = collection.get_document(&id,).await
+      .map_err(LinkError::Fetch,)?
+      .into();
+    let old = item.get_previous_id().copied();
+    let mut changed = false;
+
+    seen.insert(id,);
+    len += 1;
+    back = id;
+
+    if old != previous_id {
+      fixes.push(LinkFix::Previous { id, old, new: previous_id, },);
+      item.set_previous_id(previous_id,);
+      changed = true;
+    }
+
+    next_id = item.get_next_id().copied();
+    if let Some(old) = next_id.filter(|next,| seen.contains(next,),) {
+      fixes.push(LinkFix::Truncated { id, old, },);
+      item.set_next_id(None,);
+      next_id = None;
+      changed = true;
+    }
+
+    if changed { batch.push(item.into(),) }
+    if batch.len() == LIST_BATCH_SIZE || (next_id.is_none() && !batch.is_empty()) {
+      let report = collection.write_documents_reported(&mem::take(&mut batch,),).await
+        .map_err(LinkError::Write,)?;
+
+      written.extend(report,);
+    }
+
+    previous_id = Some(id,);
+  }
+
+  if let Some(tier) = tier { tier.set_ends((NonZeroU64::new(len,), (*front_id).into(), back.into(),),) }
+
+  Ok(RepairReport { fixes, len, back, written: written.into_iter().collect(), })
+}
+
+/// Reverses the linked list of a tier and swaps the ends of the tier, returning the
+/// number of nodes in the list.
+/// 
+/// The list is reversed in two passes so that either can be restarted. The first pass
+/// walks backward from the back of the tier and sets each next link to the previous
+/// link, writing the nodes in batches; the previous links are not changed so running
+/// `reverse` again after an error in this pass walks the same nodes and finishes the
+/// pass. Once every next link is written the ends of `tier` are swapped and the second
+/// pass is a `repair` from the new front which corrects the previous links; after an
+/// error in this pass `tier` is already reversed and running `repair` from its new front
+/// finishes the reverse. Only batches of nodes are held in memory.
+/// 
+/// The tier is not written.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the list.  
+/// tier --- The tier the list makes up.  
+pub async fn reverse<T, Coll,>(collection: &Coll, tier: &mut TierMeta,) -> Result<u64, LinkError<Coll::Error,>>
+  where T: LinkedListMut + Into<Coll::Document>,
+    Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  /// Writes a batch of nodes and fails with the first error.
+  async fn write<Coll,>(collection: &Coll, batch: &[Coll::Document],) -> Result<(), LinkError<Coll::Error,>>
+    where Coll: TierListCollection, {
+    let report = collection.write_documents_reported(batch,).await
+      .map_err(LinkError::Write,)?;
+
+    match report.into_iter().find_map(|(_, res,),| res.err(),) {
+      Some(e) => Err(LinkError::Write(e,)),
+      None => Ok(()),
+    }
+  }
+
+  if tier_is_empty::<T, _,>(collection, tier,).await.map_err(LinkError::Fetch,)? { return Ok(0) }
+
+  let (front, back,) = (*tier.list_front(), *tier.list_back(),);
+  let mut batch = Vec::with_capacity(LIST_BATCH_SIZE,);
+  let mut previous_id = Some(*back,);
+  let mut len = 0;
+
+  while let Some(id) = previous_id {
+    let mut item: T = collection.get_document(&id,).await
+      .map_err(LinkError::Fetch,)?
+      .into();
+
+    previous_id = item.get_previous_id().copied();
+    item.set_next_id(previous_id,);
+    batch.push(item.into(),);
+    len += 1;
+
+    if batch.len() == LIST_BATCH_SIZE || previous_id.is_none() {
+      write(collection, &mem::take(&mut batch,),).await?;
+    }
+  }
+
+  tier.set_ends((NonZeroU64::new(len,), back, front,),);
+
+  let report = repair::<T, _,>(collection, &back, Some(tier,),).await?;
+  match report.written.into_iter().find_map(|(_, res,),| res.err(),) {
+    Some(e) => Err(LinkError::Write(e,)),
+    None => Ok(len),
+  }
+}
+
+/// Sorts the cards of a tier from the highest score to the lowest and sets the ends of
+/// the tier to match.
+/// 
+/// Every card is read before anything is written and cards with equal scores keep their
+/// order. Only the cards whose neighbours changed are written, in batches, so a sorted
+/// tier is not written at all. If only some of the cards were written the list may be
+/// broken; sorting again rewrites them. The length of the tier is set to the number of
+/// cards walked.
+/// 
+/// The tier is not written.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the cards.  
+/// tier --- The tier the cards make up.  
+/// order --- How to score the cards.  
+pub async fn sort_tier<Coll,>(collection: &Coll, tier: &mut TierMeta, order: ScoreKind,) -> Result<SortReport<Coll::Error,>, SortError<Coll::Error,>>
+  where Coll: TierListCollection,
+    Coll::Document: Into<Card>,
+    Card: Into<Coll::Document>, {
+  if tier_is_empty::<Card, _,>(collection, tier,).await.map_err(SortError::Fetch,)? { return Ok(SortReport { len: 0, moved: 0, written: None.into_iter().collect(), }) }
+
+  let link = |card: &Card,| card.get_next_id().copied();
+  let mut cards = guard_cycles(walk_links(collection, Err(**tier.list_front(),), link,), link,)
+    .map_err(|e,| match e {
+      CollectError::Fetch(e) => SortError::Fetch(e,),
+      CollectError::Cycle(id) => SortError::Cycle(id,),
+    },)
+    .try_collect::<Vec<_>>().await?;
+  let old = cards.iter().map(|card,| card.id,).collect::<Vec<_>>();
+
+  cards.sort_by(|a, b,| a.cmp_score(b, order,),);
+
+  let ids = cards.iter().map(|card,| card.id,).collect::<Vec<_>>();
+  let moved = ids.iter().zip(&old,).filter(|(new, old,),| new != old,).count() as u64;
+  let written = write_order(collection, cards,).await
+    .map_err(SortError::Write,)?;
+
+  let len = ids.len() as u64;
+  if let (Some(front), Some(back),) = (ids.first(), ids.last(),) { tier.set_ends((NonZeroU64::new(len,), *front, *back,),) }
+
+  Ok(SortReport { len, moved, written, })
+}
+
+/// Relinks the nodes of a tier into the order of `desired` and updates the ends of the
+/// tier.
+/// 
+/// The tier is walked from its front and `desired` must hold each of its nodes exactly
+/// once; otherwise this fails with `OrderError::Membership` and nothing is written. Only
+/// the nodes whose links change are written, in batches, so putting a tier into the
+/// order it is already in only reads it. As with `sort_tier` the ends of the tier are
+/// updated even if some nodes were not written.
+/// 
+/// The tier is not written.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the list.  
+/// tier --- The tier the list makes up.  
+/// desired --- The identifiers of the nodes of the tier in their new order.  
+pub async fn apply_order<T, Coll,>(collection: &Coll, tier: &mut TierMeta, desired: &[DocumentId],) -> Result<SortReport<Coll::Error,>, OrderError<Coll::Error,>>
+  where T: LinkedListMut + Into<Coll::Document>,
+    Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  let current = if tier_is_empty::<T, _,>(collection, tier,).await.map_err(OrderError::Fetch,)? { Vec::new() } else {
+    let link = |item: &T,| item.get_next_id().copied();
+
+    guard_cycles(walk_links(collection, Err(**tier.list_front(),), link,), link,)
+      .map_err(|e,| match e {
+        CollectError::Fetch(e) => OrderError::Fetch(e,),
+        CollectError::Cycle(id) => OrderError::Cycle(id,),
+      },)
+      .try_collect::<Vec<_>>().await?
+  };
+  let old = current.iter().map(|item,| *item.get_id(),).collect::<Vec<_>>();
+  let positions = old.iter().enumerate().map(|(position, id,),| (*id, position,),).collect::<HashMap<_, _,>>();
+  let mut current = current.into_iter().map(Some,).collect::<Vec<_>>();
+  let mut nodes = Vec::with_capacity(desired.len(),);
+  let mut extra = Vec::new();
+
+  for id in desired {
+    match positions.get(id,).and_then(|&position,| current[position].take(),) {
+      Some(node) => nodes.push(node,),
+      None => extra.push(*id,),
+    }
+  }
+
+  let missing = old.iter().zip(&current,).filter(|(_, node,),| node.is_some(),).map(|(id, _,),| *id,).collect::<Vec<_>>();
+  if !missing.is_empty() || !extra.is_empty() { return Err(OrderError::Membership { missing, extra, }) }
+
+  let moved = desired.iter().zip(&old,).filter(|(new, old,),| new != old,).count() as u64;
+  let written = write_order(collection, nodes,).await
+    .map_err(OrderError::Write,)?;
+
+  let len = desired.len() as u64;
+  if let (Some(front), Some(back),) = (desired.first(), desired.last(),) { tier.set_ends((NonZeroU64::new(len,), (*front).into(), (*back).into(),),) }
+
+  Ok(SortReport { len, moved, written, })
+}
+
+/// Links nodes to each other in order, writing only the nodes whose links change in
+/// batches of `LIST_BATCH_SIZE`.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the list.  
+/// nodes --- The nodes in their new order.  
+async fn write_order<T, Coll,>(collection: &Coll, nodes: Vec<T>,) -> Result<BatchReport<Coll::Error,>, Coll::Error>
+  where T: LinkedListMut + Into<Coll::Document>,
+    Coll: TierListCollection, {
+  let ids = nodes.iter().map(|node,| *node.get_id(),).collect::<Vec<_>>();
+  let mut changed = Vec::new();
+
+  for (index, mut node,) in nodes.into_iter().enumerate() {
+    let previous = index.checked_sub(1,).map(|index,| ids[index],);
+    let next = ids.get(index + 1,).copied();
+
+    if node.get_previous_id().copied() != previous || node.get_next_id().copied() != next {
+      node.set_previous_id(previous,);
+      node.set_next_id(next,);
+      changed.push(node.into(),);
+    }
+  }
+
+  let mut written = Vec::new();
+  for batch in changed.chunks(LIST_BATCH_SIZE,) {
+    written.extend(collection.write_documents_reported(batch,).await?,);
+  }
+
+  Ok(written.into_iter().collect())
+}
+
+/// Links a card into a tier before the first card with a lower score and updates the
+/// ends and length of the tier, returning the position of the card.
+/// 
+/// The tier is walked from its front so a card with the same score as others is linked
+/// after them. An empty tier is given the card as its only node; a tier of unknown
+/// length is counted with `tier_len` first and stays unknown if it has more than
+/// `DEFAULT_COLLECT_CAP` cards. If some of the changed nodes could not be written this
+/// fails with `LinkError::Write` and the tier is not updated.
+/// 
+/// The tier is not written.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the cards.  
+/// tier --- The tier to link the card into.  
+/// card --- The card to link; its links are replaced.  
+/// order --- How to score the cards.  
+pub async fn insert_sorted<Coll,>(collection: &Coll, tier: &mut TierMeta, mut card: Card, order: ScoreKind,) -> Result<u64, LinkError<Coll::Error,>>
+  where Coll: TierListCollection,
+    Coll::Document: Into<Card>,
+    Card: Into<Coll::Document>, {
+  /// Fails with the first error writing the changed nodes.
+  fn written<E,>(report: LinkReport<E,>,) -> Result<(), LinkError<E,>> {
+    match report.written.into_iter().find_map(|(_, res,),| res.err(),) {
+      Some(e) => Err(LinkError::Write(e,)),
+      None => Ok(()),
+    }
+  }
+
+  let id = card.id;
+  let len = match tier_len::<Card, _,>(collection, tier, DEFAULT_COLLECT_CAP as u64,).await
+    .map_err(LinkError::Fetch,)? {
+    WalkLen::Exact(0) => {
+      card.detach();
+      collection.write_document(&card.into(),).await
+        .map_err(LinkError::Write,)?;
+      tier.set_ends((NonZeroU64::new(1,), id, id,),);
+
+      return Ok(0)
+    },
+    WalkLen::Exact(len) => NonZeroU64::new(len,),
+    WalkLen::AtLeast(_) => None,
+  };
+  let (mut front, mut back,) = (*tier.list_front(), *tier.list_back(),);
+  let link = |card: &Card,| card.get_next_id().copied();
+  let mut walk = Box::pin(guard_cycles(walk_links(collection, Err(*front,), link,), link,),);
+  let mut position = 0;
+  let mut last = None;
+
+  while let Some(item) = walk.next().await {
+    let item = item.map_err(|e,| match e {
+      CollectError::Fetch(e) => LinkError::Fetch(e,),
+      CollectError::Cycle(id) => LinkError::Cycle(id,),
+    },)?;
+
+    if card.cmp_score(&item, order,) == Ordering::Less {
+      if item.previous_card.is_none() { front = id }
+
+      written(link_item_before(collection, item, card,).await?.1,)?;
+      tier.set_ends((len.and_then(|len,| len.checked_add(1,),), front, back,),);
+
+      return Ok(position)
+    }
+
+    position += 1;
+    last = Some(item,);
+  }
+
+  //Every card has the same or a higher score.
+  if let Some(last) = last {
+    back = id;
+    written(link_item_after(collection, last, card,).await?.1,)?;
+    tier.set_ends((len.and_then(|len,| len.checked_add(1,),), front, back,),);
+  }
+
+  Ok(position)
+}
+
+/// Rotates a tier so its front `k` nodes become its back, returning how many front
+/// nodes moved to the back; a negative `k` moves the back nodes to the front instead.
+/// 
+/// `k` wraps around the length of the tier. A tier without a length is counted with
+/// `tier_len` up to `DEFAULT_COLLECT_CAP` nodes first. Finding the new ends walks from
+/// whichever end of the tier is closer to them and at most four nodes are relinked and
+/// written using a single batch write; if some were not written this fails with
+/// `RotateError::Write` and the tier is not updated.
+/// 
+/// The tier is not written.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the list.  
+/// tier --- The tier the list makes up.  
+/// k --- The number of nodes to move from the front to the back.  
+pub async fn rotate<T, Coll,>(collection: &Coll, tier: &mut TierMeta, k: i64,) -> Result<u64, RotateError<Coll::Error,>>
+  where T: LinkedListMut + Clone + Into<Coll::Document>,
+    Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  let (front_id, back_id,) = (**tier.list_front(), **tier.list_back(),);
+  let len = match tier_len::<T, _,>(collection, tier, DEFAULT_COLLECT_CAP as u64,).await
+    .map_err(RotateError::Fetch,)? {
+    WalkLen::Exact(0) => return Ok(0),
+    WalkLen::Exact(len) => len,
+    WalkLen::AtLeast(cap) => return Err(RotateError::TooLong(cap,)),
+  };
+  //The number of nodes moved from the front to the back.
+  let moved = i128::from(k,).rem_euclid(i128::from(len,),) as u64;
+  if moved == 0 { return Ok(0) }
+
+  //Walk to the node on the closer side of the split.
+  let (forward, steps,) = if moved <= len - moved { (true, moved,) } else { (false, len - moved,) };
+  let (start, link,): (_, fn(&T,) -> Option<DocumentId>,) = if forward { (front_id, |item: &T,| item.get_next_id().copied(),) }
+    else { (back_id, |item: &T,| item.get_previous_id().copied(),) };
+  let mut walk = Box::pin(guard_cycles(walk_links(collection, Err(start,), link,), link,),);
+  let mut nodes = Vec::<T>::new();
+  let mut last = None::<T>;
+
+  for step in 0..steps {
+    let item = walk.next().await
+      .ok_or_else(|| RotateError::Ended(last.as_ref().map_or(start, |item: &T,| *item.get_id(),),),)?
+      .map_err(|e,| match e {
+        CollectError::Fetch(e) => RotateError::Fetch(e,),
+        CollectError::Cycle(id) => RotateError::Cycle(id,),
+      },)?;
+
+    if step == 0 && steps > 1 { nodes.push(item.clone(),) }
+    last = Some(item,);
+  }
+
+  let last = last.expect("the walk takes at least one step",);
+  let beyond = link(&last,).ok_or(RotateError::Ended(*last.get_id(),),)?;
+  let (new_back_id, new_front_id,) = if forward { (*last.get_id(), beyond,) } else { (beyond, *last.get_id(),) };
+  nodes.push(last,);
+
+  //Read the ends which were not walked.
+  let mut missing = Vec::new();
+  for &id in [front_id, back_id, new_front_id, new_back_id,].iter() {
+    if !missing.contains(&id,) && nodes.iter().all(|node,| *node.get_id() != id,) { missing.push(id,) }
+  }
+  if !missing.is_empty() {
+    for node in collection.get_documents(&missing,).await.map_err(RotateError::Fetch,)? {
+      nodes.push(node.map_err(RotateError::Fetch,)?.into(),);
+    }
+  }
+
+  for node in &mut nodes {
+    let id = *node.get_id();
+
+    if id == back_id { node.set_next_id(Some(front_id,),) }
+    if id == front_id { node.set_previous_id(Some(back_id,),) }
+    if id == new_back_id { node.set_next_id(None,) }
+    if id == new_front_id { node.set_previous_id(None,) }
+  }
+
+  let documents = nodes.into_iter().map(Into::<Coll::Document>::into,).collect::<Vec<_>>();
+  let report = collection.write_documents_reported(&documents,).await
+    .map_err(RotateError::Write,)?;
+  if let Some(e) = report.into_iter().find_map(|(_, res,),| res.err(),) { return Err(RotateError::Write(e,)) }
+
+  tier.set_ends((NonZeroU64::new(len,), new_front_id.into(), new_back_id.into(),),);
+  Ok(moved)
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{CardId, CardStatus, TierId, MemoryCollection, MemoryError, RecordingCollection, Op, mock::{ChaosCollection, ChaosError,},};
+  use futures::executor::block_on;
+
+  fn tier(id: u8, previous: Option<u8>, next: Option<u8>,) -> TierMeta {
+    TierMeta::new(TierId::from_bytes([id; 20]), (None, CardId::from_bytes([id; 20]), CardId::from_bytes([id; 20]),), previous.map(|id,| TierId::from_bytes([id; 20]),), next.map(|id,| TierId::from_bytes([id; 20]),),)
+  }
+  /// Returns a collection storing a list of tiers with the identifiers in order.
+  fn list(ids: &[u8],) -> MemoryCollection<TierMeta,> {
+    let coll = MemoryCollection::new();
+    let tiers = (0..ids.len())
+      .map(|i,| tier(ids[i], i.checked_sub(1,).map(|i,| ids[i],), ids.get(i + 1,).copied(),),)
+      .collect::<Vec<_>>();
+
+    block_on(coll.write_documents(&tiers,),)
+      .expect("Error writing list")
+      .expect("Error writing list");
+
+    coll
+  }
+  /// Walks a list forward from a node and checks the backward links on the way.
+  fn walk(coll: &MemoryCollection<TierMeta,>, head: u8,) -> Vec<u8> {
+    let mut ids = vec![head];
+    let mut tier = block_on(coll.get_document(&DocumentId::from_bytes([head; 20]),),).expect("Error reading head");
+
+    assert_eq!(tier.previous_tier, None, "Error head has a previous node",);
+    while let Some(next) = tier.next_tier {
+      let next = block_on(coll.get_document(&next,),).expect("Error reading next");
+
+      assert_eq!(next.previous_tier, Some(tier.id), "Error backward link",);
+      ids.push(next.id.as_bytes()[0],);
+      tier = next;
+    }
+
+    ids
+  }
+  fn written<E,>(report: &LinkReport<E,>,) -> Vec<u8> {
+    report.written.iter().map(|(id, _,),| id.as_bytes()[0],).collect()
+  }
+
+  #[test]
+  fn test_link_after() {
+    let coll = list(&[1, 2, 3,],);
+
+    //Link into the middle.
+    let report = block_on(link_after(&coll, &DocumentId::from_bytes([1; 20]), tier(9, None, None,),),)
+      .expect("Error linking into the middle");
+    assert!(report.is_complete_success(), "Error writing the linked nodes",);
+    assert_eq!(report.read, [DocumentId::from_bytes([1; 20]), DocumentId::from_bytes([2; 20]),], "Error reading the anchor and its next node",);
+    assert_eq!(written(&report,), [1, 9, 2,], "Error writing the linked nodes",);
+    assert_eq!(walk(&coll, 1,), [1, 9, 2, 3,], "Error linking into the middle",);
+
+    //Link at the tail.
+    let report = block_on(link_after(&coll, &DocumentId::from_bytes([3; 20]), tier(8, Some(5,), Some(6,),),),)
+      .expect("Error linking at the tail");
+    assert_eq!(report.read, [DocumentId::from_bytes([3; 20]),], "Error reading past the tail",);
+    assert_eq!(written(&report,), [3, 8,], "Error writing the linked nodes",);
+    assert_eq!(walk(&coll, 1,), [1, 9, 2, 3, 8,], "Error linking at the tail",);
+
+    //A node which is already linked next to the anchor.
+    assert_eq!(block_on(link_after(&coll, &DocumentId::from_bytes([9; 20]), tier(2, None, None,),),), Err(LinkError::Duplicate(DocumentId::from_bytes([2; 20]),)), "Error linking a node twice",);
+    assert_eq!(block_on(link_after(&coll, &DocumentId::from_bytes([9; 20]), tier(9, None, None,),),), Err(LinkError::Duplicate(DocumentId::from_bytes([9; 20]),)), "Error linking the anchor to itself",);
+    assert_eq!(walk(&coll, 1,), [1, 9, 2, 3, 8,], "Error changed the list after an error",);
+
+    //Link before the head and into the middle.
+    let report = block_on(link_before(&coll, &DocumentId::from_bytes([1; 20]), tier(7, None, None,),),)
+      .expect("Error linking before the head");
+    assert_eq!((&report.read[..], written(&report,),), (&[DocumentId::from_bytes([1; 20])][..], vec![7, 1,],), "Error linking before the head",);
+    let report = block_on(link_before(&coll, &DocumentId::from_bytes([2; 20]), tier(6, None, None,),),)
+      .expect("Error linking before a node");
+    assert_eq!(written(&report,), [9, 6, 2,], "Error writing the linked nodes",);
+    assert_eq!(walk(&coll, 7,), [7, 1, 9, 6, 2, 3, 8,], "Error linking before nodes",);
+  }
+  #[test]
+  fn test_link_inconsistent() {
+    //The next node of `1` links back to `3`.
+    let coll = list(&[1, 2,],);
+    block_on(coll.write_document(&tier(2, Some(3,), None,),),)
+      .expect("Error breaking list");
+
+    assert_eq!(
+      block_on(link_after(&coll, &DocumentId::from_bytes([1; 20]), tier(9, None, None,),),),
+      Err(LinkError::Inconsistent(DocumentId::from_bytes([2; 20]), DocumentId::from_bytes([1; 20]),)),
+      "Error reporting the inconsistent node",
+    );
+    assert_eq!(block_on(coll.exists(&DocumentId::from_bytes([9; 20]),),), Ok(false), "Error wrote after an inconsistency",);
+    assert_eq!(block_on(link_after(&coll, &DocumentId::from_bytes([7; 20]), tier(9, None, None,),),).map_err(|e,| matches!(e, LinkError::Fetch(_)),), Err(true), "Error linking after a missing node",);
+  }
+  #[test]
+  fn test_unlink() {
+    let coll = list(&[1, 2, 3, 4,],);
+
+    //Unlink from the middle.
+    let (node, report,) = block_on(unlink::<TierMeta, _,>(&coll, &DocumentId::from_bytes([2; 20]),),)
+      .expect("Error unlinking from the middle");
+    assert_eq!(node, tier(2, Some(1,), Some(3,),), "Error returning the node as it was",);
+    assert_eq!(report.read, [DocumentId::from_bytes([2; 20]), DocumentId::from_bytes([1; 20]), DocumentId::from_bytes([3; 20]),], "Error reading the neighbours",);
+    assert_eq!(written(&report,), [1, 2, 3,], "Error writing the relinked nodes",);
+    assert_eq!(block_on(coll.get_document(&DocumentId::from_bytes([2; 20]),),), Ok(tier(2, None, None,)), "Error detaching the node",);
+    assert_eq!(walk(&coll, 1,), [1, 3, 4,], "Error unlinking from the middle",);
+
+    //Unlink the head.
+    let (_, report,) = block_on(unlink::<TierMeta, _,>(&coll, &DocumentId::from_bytes([1; 20]),),)
+      .expect("Error unlinking the head");
+    assert_eq!(written(&report,), [1, 3,], "Error writing the relinked nodes",);
+    assert_eq!(walk(&coll, 3,), [3, 4,], "Error unlinking the head",);
+
+    //Unlink the tail.
+    block_on(unlink::<TierMeta, _,>(&coll, &DocumentId::from_bytes([4; 20]),),)
+      .expect("Error unlinking the tail");
+    assert_eq!(walk(&coll, 3,), [3,], "Error unlinking the tail",);
+
+    //Unlink the only node.
+    let (_, report,) = block_on(unlink::<TierMeta, _,>(&coll, &DocumentId::from_bytes([3; 20]),),)
+      .expect("Error unlinking the only node");
+    assert_eq!((report.read.len(), written(&report,),), (1, vec![3],), "Error touching other nodes",);
+  }
+  #[test]
+  fn test_concat() {
+    let coll = list(&[1, 2,],);
+    block_on(coll.write_documents([tier(3, None, Some(4,),), tier(4, Some(3,), None,),],),)
+      .expect("Error writing list")
+      .expect("Error writing list");
+
+    //Both ends must be the ends of their lists.
+    assert_eq!(block_on(concat::<TierMeta, _,>(&coll, &DocumentId::from_bytes([1; 20]), &DocumentId::from_bytes([3; 20]),),).map(|_,| (),), Err(LinkError::NotBack(DocumentId::from_bytes([1; 20]),)), "Error linking from the middle",);
+    assert_eq!(block_on(concat::<TierMeta, _,>(&coll, &DocumentId::from_bytes([2; 20]), &DocumentId::from_bytes([4; 20]),),).map(|_,| (),), Err(LinkError::NotFront(DocumentId::from_bytes([4; 20]),)), "Error linking into the middle",);
+    assert_eq!(block_on(concat::<TierMeta, _,>(&coll, &DocumentId::from_bytes([2; 20]), &DocumentId::from_bytes([2; 20]),),).map(|_,| (),), Err(LinkError::Duplicate(DocumentId::from_bytes([2; 20]),)), "Error linking a node to itself",);
+    assert_eq!((walk(&coll, 1,), walk(&coll, 3,),), (vec![1, 2,], vec![3, 4,],), "Error changed the lists after an error",);
+
+    let (back, front, report,) = block_on(concat::<TierMeta, _,>(&coll, &DocumentId::from_bytes([2; 20]), &DocumentId::from_bytes([3; 20]),),)
+      .expect("Error concatenating lists");
+    assert_eq!((back, front,), (tier(2, Some(1,), Some(3,),), tier(3, Some(2,), Some(4,),),), "Error returning the relinked ends",);
+    assert_eq!((&report.read[..], written(&report,),), (&[DocumentId::from_bytes([2; 20]), DocumentId::from_bytes([3; 20]),][..], vec![2, 3,],), "Error touching other nodes",);
+    assert_eq!(walk(&coll, 1,), [1, 2, 3, 4,], "Error concatenating lists",);
+  }
+  #[test]
+  fn test_unlink_inconsistent() {
+    let coll = list(&[1, 2, 3,],);
+
+    //The previous node of `2` links forward to `3`.
+    block_on(coll.write_document(&tier(1, None, Some(3,),),),)
+      .expect("Error breaking list");
+    assert_eq!(
+      block_on(unlink::<TierMeta, _,>(&coll, &DocumentId::from_bytes([2; 20]),),),
+      Err(LinkError::Inconsistent(DocumentId::from_bytes([1; 20]), DocumentId::from_bytes([2; 20]),)),
+      "Error reporting the inconsistent previous node",
+    );
+
+    //The next node of `2` links back to `1`.
+    block_on(coll.write_documents([tier(1, None, Some(2,),), tier(3, Some(1,), None,),],),)
+      .expect("Error breaking list")
+      .expect("Error breaking list");
+    assert_eq!(
+      block_on(unlink::<TierMeta, _,>(&coll, &DocumentId::from_bytes([2; 20]),),),
+      Err(LinkError::Inconsistent(DocumentId::from_bytes([3; 20]), DocumentId::from_bytes([2; 20]),)),
+      "Error reporting the inconsistent next node",
+    );
+    assert_eq!(block_on(coll.get_document(&DocumentId::from_bytes([2; 20]),),), Ok(tier(2, Some(1,), Some(3,),)), "Error wrote after an inconsistency",);
+  }
+  #[test]
+  fn test_detect_cycle() {
+    //`0` leads into the cycle `1, 2, 3, 4`.
+    let coll = list(&[0, 1, 2, 3, 4,],);
+    block_on(coll.write_document(&tier(4, Some(3,), Some(1,),),),)
+      .expect("Error writing cycle");
+
+    let found = block_on(detect_cycle::<TierMeta, _,>(&coll, &DocumentId::from_bytes([0; 20]), 10,),)
+      .expect("Error searching with a set");
+    assert_eq!(found, Some(DocumentId::from_bytes([1; 20]),), "Error detecting the cycle with a set",);
+    let found = block_on(detect_cycle::<TierMeta, _,>(&coll, &DocumentId::from_bytes([0; 20]), CYCLE_SET_LIMIT + 1,),)
+      .expect("Error searching with Floyd's algorithm")
+      .expect("Error missing the cycle with Floyd's algorithm");
+    assert!((1..=4).contains(&found.as_bytes()[0],), "Error naming a node outside the cycle",);
+    assert_eq!(block_on(detect_cycle::<TierMeta, _,>(&coll, &DocumentId::from_bytes([0; 20]), 4,),), Ok(None), "Error searching past the limit",);
+
+    let coll = list(&[1, 2, 3, 4,],);
+    assert_eq!(block_on(detect_cycle::<TierMeta, _,>(&coll, &DocumentId::from_bytes([1; 20]), 10,),), Ok(None), "Error detecting a cycle in a list",);
+    assert_eq!(block_on(detect_cycle::<TierMeta, _,>(&coll, &DocumentId::from_bytes([1; 20]), CYCLE_SET_LIMIT + 1,),), Ok(None), "Error detecting a cycle in a list",);
+    assert!(block_on(detect_cycle::<TierMeta, _,>(&coll, &DocumentId::from_bytes([7; 20]), 10,),).is_err(), "Error searching from a missing node",);
+  }
+  #[test]
+  fn test_stream_from() {
+    let coll = list(&[1, 2, 3, 4,],);
+    let streamed = block_on(stream_from(&coll, &DocumentId::from_bytes([2; 20]),).collect::<Vec<_>>(),);
+    let walked = block_on(async {
+      let mut cursor = coll.ref_cursor::<TierMeta>(&DocumentId::from_bytes([2; 20]),).await.expect("Error reading start");
+      let mut walked = vec![Ok(*cursor.get_item())];
+
+      while cursor.move_next_mut().await.expect("Error moving next") { walked.push(Ok(*cursor.get_item()),) }
+      walked
+    },);
+    assert_eq!(streamed, walked, "Error streaming like a Cursor",);
+    assert_eq!(block_on(stream_from(&coll, &DocumentId::from_bytes([1; 20]),).count(),), 4, "Error streaming the whole list",);
+
+    //A missing start and a cycle.
+    let streamed = block_on(stream_from(&coll, &DocumentId::from_bytes([7; 20]),).collect::<Vec<_>>(),);
+    assert!(matches!(streamed[..], [Err(CollectError::Fetch(_))],), "Error streaming a missing start",);
+    block_on(coll.write_document(&tier(4, Some(3,), Some(2,),),),)
+      .expect("Error writing cycle");
+    let ids = block_on(stream_from(&coll, &DocumentId::from_bytes([1; 20]),).map(|tier,| tier.map(|tier,| tier.id.as_bytes()[0],),).collect::<Vec<_>>(),);
+    assert_eq!(ids, [Ok(1), Ok(2), Ok(3), Ok(4), Err(CollectError::Cycle(DocumentId::from_bytes([2; 20]),)),], "Error ending at the cycle",);
+  }
+  #[test]
+  fn test_fold() {
+    let (coll, _,) = tier_of(&[3, 1, 4, 1, 5,],);
+    let folded = block_on(fold(&coll, &DocumentId::from_bytes([1; 20]), 0, |votes, card,| votes + card.up_votes,),);
+    let streamed = block_on(stream_from(&coll, &DocumentId::from_bytes([1; 20]),).map_ok(|card,| card.up_votes,).try_fold(0, |votes, up_votes,| async move { Ok(votes + up_votes) },),);
+    assert_eq!((folded, streamed,), (Ok(14), Ok(14),), "Error folding like the stream",);
+
+    //Stopping at the first card with one vote without reading past it.
+    coll.take_ops();
+    let until = block_on(try_fold(&coll, &DocumentId::from_bytes([1; 20]), Vec::new(), |mut ids, card,| {
+      ids.push(card.id.as_bytes()[0],);
+      Ok::<_, CollectError<_>>(if card.up_votes == 1 { ControlFlow::Break(ids,) } else { ControlFlow::Continue(ids,) })
+    },),);
+    assert_eq!(until, Ok(vec![1, 2,]), "Error breaking early",);
+    crate::assert_ops!(coll, [Op::Get(DocumentId::from_bytes([1; 20])), Op::Get(DocumentId::from_bytes([2; 20])),], "Error reading past the break",);
+
+    //Errors from the closure and the walk.
+    #[derive(PartialEq, Debug,)]
+    enum FoldError { Walk(CollectError<MemoryError>), TooMany, }
+    impl From<CollectError<MemoryError>> for FoldError {
+      fn from(from: CollectError<MemoryError>,) -> Self { FoldError::Walk(from,) }
+    }
+
+    let res = block_on(try_fold(&coll, &DocumentId::from_bytes([1; 20]), 0, |votes, card,| if votes > 5 { Err(FoldError::TooMany) } else { Ok(ControlFlow::Continue(votes + card.up_votes,),) },),);
+    assert_eq!(res, Err(FoldError::TooMany), "Error stopping with the closure error",);
+    let res = block_on(try_fold(&coll, &DocumentId::from_bytes([9; 20]), 0, |votes, card,| Ok::<_, FoldError>(ControlFlow::Continue(votes + card.up_votes,),),),);
+    assert!(matches!(res, Err(FoldError::Walk(CollectError::Fetch(_)))), "Error stopping with the walk error",);
+  }
+  #[test]
+  fn test_walk_len() {
+    let coll = list(&[1, 2, 3, 4,],);
+    assert_eq!(block_on(walk_len::<TierMeta, _,>(&coll, &DocumentId::from_bytes([1; 20]), 10,),), Ok(WalkLen::Exact(4,)), "Error counting the list",);
+    assert_eq!(block_on(walk_len::<TierMeta, _,>(&coll, &DocumentId::from_bytes([1; 20]), 4,),), Ok(WalkLen::Exact(4,)), "Error counting the list at the cap",);
+    assert_eq!(block_on(walk_len::<TierMeta, _,>(&coll, &DocumentId::from_bytes([2; 20]), 2,),), Ok(WalkLen::AtLeast(2,)), "Error stopping at the cap",);
+    assert!(block_on(walk_len::<TierMeta, _,>(&coll, &DocumentId::from_bytes([7; 20]), 2,),).is_err(), "Error counting a missing list",);
+
+    //A single node and a cycle.
+    assert_eq!(block_on(walk_len::<TierMeta, _,>(&list(&[1,],), &DocumentId::from_bytes([1; 20]), 10,),), Ok(WalkLen::Exact(1,)), "Error counting a single node",);
+    block_on(coll.write_document(&tier(4, Some(3,), Some(1,),),),)
+      .expect("Error writing cycle");
+    assert_eq!(block_on(walk_len::<TierMeta, _,>(&coll, &DocumentId::from_bytes([1; 20]), 10,),), Ok(WalkLen::AtLeast(10,)), "Error bounding a cycle",);
+
+    //The cached length of a tier over the list.
+    let coll = list(&[1, 2, 3,],);
+    let mut meta = TierMeta::new(TierId::from_bytes([0; 20]), (NonZeroU64::new(7,), CardId::from_bytes([1; 20]), CardId::from_bytes([3; 20]),), None, None,);
+    assert_eq!(block_on(meta.refresh_len::<TierMeta, _,>(&coll, 10,),), Ok(WalkLen::Exact(3,)), "Error refreshing the length",);
+    assert_eq!(meta.list_len().map(NonZeroU64::get,), Some(3), "Error updating the length",);
+    assert_eq!(block_on(meta.refresh_len::<TierMeta, _,>(&coll, 2,),), Ok(WalkLen::AtLeast(2,)), "Error refreshing past the cap",);
+    assert_eq!(meta.list_len(), None, "Error keeping a capped length",);
+  }
+  #[test]
+  fn test_nth() {
+    let coll = RecordingCollection::new(list(&[1, 2, 3, 4, 5,],),);
+    let nth = |n: u64,| block_on(nth::<TierMeta, _,>(&coll, &DocumentId::from_bytes([1; 20]), n,),)
+      .map(|tier,| tier.map(|tier,| tier.id.as_bytes()[0],),);
+
+    assert_eq!(nth(0,), Ok(Some(1,)), "Error getting the front",);
+    crate::assert_ops!(coll, [Op::Get(DocumentId::from_bytes([1; 20])),], "Error reading past the front",);
+    assert_eq!(nth(4,), Ok(Some(5,)), "Error getting the back",);
+    assert_eq!(nth(5,), Ok(None), "Error getting past the back",);
+    assert_eq!(nth(u64::MAX,), Ok(None), "Error getting far past the back",);
+
+    //A cycle is not walked forever.
+    coll.take_ops();
+    block_on(coll.write_document(&tier(5, Some(4,), Some(2,),),),)
+      .expect("Error writing cycle");
+    assert_eq!(nth(7,), Err(CollectError::Cycle(DocumentId::from_bytes([2; 20]),)), "Error walking a cycle",);
+  }
+  #[test]
+  fn test_tier_median() {
+    let (coll, meta,) = tier_of(&[0; 5],);
+    coll.take_ops();
+    let median = block_on(tier_median::<Card, _,>(&coll, &meta,),).map(|card,| card.map(|card,| card.id.as_bytes()[0],),);
+    assert_eq!(median, Ok(Some(3,)), "Error getting the median of an odd tier",);
+    crate::assert_ops!(coll, [Op::Get(DocumentId::from_bytes([1; 20])), Op::Get(DocumentId::from_bytes([2; 20])), Op::Get(DocumentId::from_bytes([3; 20])),], "Error reading past the median",);
+
+    let (coll, meta,) = tier_of(&[0; 6],);
+    let median = block_on(tier_median::<Card, _,>(&coll, &meta,),).map(|card,| card.map(|card,| card.id.as_bytes()[0],),);
+    assert_eq!(median, Ok(Some(3,)), "Error getting the median of an even tier",);
+
+    let (coll, meta,) = tier_of(&[0,],);
+    let median = block_on(tier_median::<Card, _,>(&coll, &meta,),).map(|card,| card.map(|card,| card.id.as_bytes()[0],),);
+    assert_eq!(median, Ok(Some(1,)), "Error getting the median of a single card",);
+
+    let meta = TierMeta::new(TierId::from_bytes([0; 20]), (None, CardId::NIL, CardId::NIL,), None, None,);
+    assert_eq!(block_on(tier_median::<Card, _,>(&coll, &meta,),), Ok(None), "Error getting the median of an empty tier",);
+
+    //A tier whose length was lost is counted.
+    let (coll, mut meta,) = tier_of(&[0; 5],);
+    assert_eq!(block_on(meta.refresh_len::<Card, _,>(&coll, 2,),), Ok(WalkLen::AtLeast(2,)), "Error refreshing the length",);
+    let median = block_on(tier_median::<Card, _,>(&coll, &meta,),).map(|card,| card.map(|card,| card.id.as_bytes()[0],),);
+    assert_eq!(median, Ok(Some(3,)), "Error getting the median of a tier of unknown length",);
+  }
+  #[test]
+  fn test_repair() {
+    let repair = |coll: &MemoryCollection<TierMeta,>, tier: Option<&mut TierMeta>,| block_on(repair::<TierMeta, _,>(coll, &DocumentId::from_bytes([1; 20]), tier,),)
+      .expect("Error repairing list");
+
+    //A healthy list is not written.
+    let coll = list(&[1, 2, 3, 4,],);
+    let report = repair(&coll, None,);
+    assert!(report.is_clean() && report.written.is_empty(), "Error changing a healthy list",);
+    assert_eq!((report.len, report.back,), (4, DocumentId::from_bytes([4; 20]),), "Error measuring the list",);
+
+    //A back link left behind by a partial insert.
+    block_on(coll.write_document(&tier(3, Some(9,), Some(4,),),),)
+      .expect("Error breaking list");
+    let report = repair(&coll, None,);
+    assert_eq!(report.fixes, [LinkFix::Previous { id: DocumentId::from_bytes([3; 20]), old: Some(DocumentId::from_bytes([9; 20]),), new: Some(DocumentId::from_bytes([2; 20]),), },], "Error fixing the back link",);
+    assert_eq!(report.written.succeeded_ids().collect::<Vec<_>>(), [&DocumentId::from_bytes([3; 20]),], "Error writing the fixed node",);
+    assert_eq!(walk(&coll, 1,), [1, 2, 3, 4,], "Error repairing the back link",);
+
+    //The front links backward and a node was skipped by the next links.
+    block_on(coll.write_documents([tier(1, Some(4,), Some(3,),), tier(4, Some(2,), None,),],),)
+      .expect("Error breaking list")
+      .expect("Error breaking list");
+    let mut meta = tier(0, None, None,);
+    let report = repair(&coll, Some(&mut meta,),);
+    assert_eq!(
+      report.fixes,
+      [
+        LinkFix::Previous { id: DocumentId::from_bytes([1; 20]), old: Some(DocumentId::from_bytes([4; 20]),), new: None, },
+        LinkFix::Previous { id: DocumentId::from_bytes([3; 20]), old: Some(DocumentId::from_bytes([2; 20]),), new: Some(DocumentId::from_bytes([1; 20]),), },
+        LinkFix::Previous { id: DocumentId::from_bytes([4; 20]), old: Some(DocumentId::from_bytes([2; 20]),), new: Some(DocumentId::from_bytes([3; 20]),), },
+      ],
+      "Error fixing the back links",
+    );
+    assert_eq!(walk(&coll, 1,), [1, 3, 4,], "Error repairing the skipped node",);
+    assert_eq!((meta.list_len().map(NonZeroU64::get,), meta.list_front(), meta.list_back(),), (Some(3), &CardId::from_bytes([1; 20]), &CardId::from_bytes([4; 20]),), "Error updating the tier",);
+
+    //The tail links back into the middle.
+    let coll = list(&[1, 2, 3, 4,],);
+    block_on(coll.write_document(&tier(4, Some(3,), Some(2,),),),)
+      .expect("Error breaking list");
+    let report = repair(&coll, None,);
+    assert_eq!(report.fixes, [LinkFix::Truncated { id: DocumentId::from_bytes([4; 20]), old: DocumentId::from_bytes([2; 20]), },], "Error truncating the cycle",);
+    assert_eq!(walk(&coll, 1,), [1, 2, 3, 4,], "Error repairing the cycle",);
+    assert!(repair(&coll, None,).is_clean(), "Error repaired list still broken",);
+  }
+  #[test]
+  fn test_reverse() {
+    let cards = |ids: &[u8],| {
+      let coll = ChaosCollection::new();
+      let cards = (0..ids.len()).map(|i,| Card {
+        id: CardId::from_bytes([ids[i]; 20]),
+        name: "card".to_owned(),
+        description: String::new(),
+        up_votes: 0,
+        down_votes: 0,
+        bias: 0,
+        status: CardStatus::Open,
+        history: Vec::new(),
+        previous_card: i.checked_sub(1,).map(|i,| CardId::from_bytes([ids[i]; 20]),),
+        next_card: ids.get(i + 1,).map(|&id,| CardId::from_bytes([id; 20]),),
+      },).collect::<Vec<_>>();
+
+      block_on(coll.write_documents(cards,),)
+        .expect("Error writing cards")
+        .expect("Error writing cards");
+      coll
+    };
+    //Walks the cards both ways from the front of the tier.
+    let links = |coll: &ChaosCollection<Card,>, tier: &TierMeta,| {
+      let forward = block_on(stream_from(coll, tier.list_front(),).map_ok(|card,| card.id.as_bytes()[0],).try_collect::<Vec<_>>(),)
+        .expect("Error walking forward");
+      let back = block_on(coll.ref_cursor::<Card>(tier.list_back(),),)
+        .expect("Error reading back");
+      let backward = block_on(back.into_stream_back().map_ok(|card,| card.id.as_bytes()[0],).try_collect::<Vec<_>>(),)
+        .expect("Error walking backward");
+
+      (forward, backward,)
+    };
+
+    let coll = cards(&[1, 2, 3, 4, 5, 6,],);
+    let mut meta = TierMeta::new(TierId::from_bytes([0; 20]), (NonZeroU64::new(6,), CardId::from_bytes([1; 20]), CardId::from_bytes([6; 20]),), None, None,);
+    assert_eq!(block_on(reverse::<Card, _,>(&coll, &mut meta,),), Ok(6), "Error reversing the list",);
+    assert_eq!((meta.list_front(), meta.list_back(),), (&CardId::from_bytes([6; 20]), &CardId::from_bytes([1; 20]),), "Error swapping the ends",);
+    assert_eq!(links(&coll, &meta,), (vec![6, 5, 4, 3, 2, 1,], vec![1, 2, 3, 4, 5, 6,],), "Error reversing the links",);
+    assert_eq!(block_on(reverse::<Card, _,>(&coll, &mut meta,),), Ok(6), "Error reversing the list back",);
+    assert_eq!(links(&coll, &meta,), (vec![1, 2, 3, 4, 5, 6,], vec![6, 5, 4, 3, 2, 1,],), "Error reversing the list back",);
+
+    //A failure in the first pass leaves some next links reversed; reversing again
+    //finishes the pass.
+    let coll = cards(&[1, 2, 3, 4, 5, 6,],);
+    let mut meta = TierMeta::new(TierId::from_bytes([0; 20]), (NonZeroU64::new(6,), CardId::from_bytes([1; 20]), CardId::from_bytes([6; 20]),), None, None,);
+    coll.flaky_writes(DocumentId::from_bytes([4; 20]), 1,);
+    assert_eq!(block_on(reverse::<Card, _,>(&coll, &mut meta,),), Err(LinkError::Write(ChaosError::Injected(DocumentId::from_bytes([4; 20]),),)), "Error writing a broken card",);
+    assert_eq!(meta.list_front(), &DocumentId::from_bytes([1; 20]), "Error swapping the ends before the links",);
+    assert_eq!(block_on(coll.get_document(&DocumentId::from_bytes([5; 20]),),).map(|card,| card.next_card,), Ok(Some(CardId::from_bytes([4; 20]),)), "Error writing the rest of the batch",);
+    assert_eq!(block_on(reverse::<Card, _,>(&coll, &mut meta,),), Ok(6), "Error restarting the reverse",);
+    assert_eq!(links(&coll, &meta,), (vec![6, 5, 4, 3, 2, 1,], vec![1, 2, 3, 4, 5, 6,],), "Error converging after a restart",);
+
+    //A failure in the second pass leaves every next link reversed and the ends swapped;
+    //repairing finishes the reverse.
+    let coll = cards(&[1, 2, 3, 4, 5, 6,],);
+    let mut meta = TierMeta::new(TierId::from_bytes([0; 20]), (NonZeroU64::new(6,), CardId::from_bytes([6; 20]), CardId::from_bytes([1; 20]),), None, None,);
+    for id in 1..=6 {
+      let mut card = block_on(coll.get_document(&DocumentId::from_bytes([id; 20]),),).expect("Error reading card");
+
+      card.next_card = card.previous_card;
+      block_on(coll.write_document(&card,),).expect("Error writing card");
+    }
+    block_on(repair::<Card, _,>(&coll, &DocumentId::from_bytes([6; 20]), Some(&mut meta,),),)
+      .expect("Error repairing the reverse");
+    assert_eq!(links(&coll, &meta,), (vec![6, 5, 4, 3, 2, 1,], vec![1, 2, 3, 4, 5, 6,],), "Error converging after a repair",);
+
+    //A tier of unknown length is reversed rather than treated as empty.
+    let coll = cards(&[1, 2, 3, 4, 5, 6,],);
+    let mut meta = TierMeta::new(TierId::from_bytes([0; 20]), (NonZeroU64::new(6,), CardId::from_bytes([1; 20]), CardId::from_bytes([6; 20]),), None, None,);
+    assert_eq!(block_on(meta.refresh_len::<Card, _,>(&coll, 2,),), Ok(WalkLen::AtLeast(2,)), "Error dropping the length",);
+    assert_eq!(block_on(reverse::<Card, _,>(&coll, &mut meta,),), Ok(6), "Error reversing a tier of unknown length",);
+    assert_eq!((meta.list_len(), meta.list_front(), meta.list_back(),), (NonZeroU64::new(6,), &CardId::from_bytes([6; 20]), &CardId::from_bytes([1; 20]),), "Error setting the ends of a tier of unknown length",);
+    assert_eq!(links(&coll, &meta,), (vec![6, 5, 4, 3, 2, 1,], vec![1, 2, 3, 4, 5, 6,],), "Error reversing the links of a tier of unknown length",);
+
+    //A tier with nil ends is empty.
+    let mut meta = TierMeta::new(TierId::from_bytes([0; 20]), (None, CardId::NIL, CardId::NIL,), None, None,);
+    assert_eq!(block_on(reverse::<Card, _,>(&coll, &mut meta,),), Ok(0), "Error reversing an empty tier",);
+  }
+  /// Writes a tier of cards with the up votes in order.
+  fn tier_of(votes: &[u64],) -> (RecordingCollection<MemoryCollection<Card,>>, TierMeta,) {
+    let coll = RecordingCollection::new(MemoryCollection::new(),);
+    let cards = (0..votes.len()).map(|i,| Card {
+      id: CardId::from_bytes([i as u8 + 1; 20]),
+      name: "card".to_owned(),
+      description: String::new(),
+      up_votes: votes[i],
+      down_votes: 0,
+      bias: 0,
+      status: CardStatus::Open,
+      history: Vec::new(),
+      previous_card: i.checked_sub(1,).map(|i,| CardId::from_bytes([i as u8 + 1; 20]),),
+      next_card: Some(CardId::from_bytes([i as u8 + 2; 20]),).filter(|_,| i + 1 < votes.len(),),
+    },).collect::<Vec<_>>();
+
+    block_on(coll.get_inner().write_documents(cards,),)
+      .expect("Error writing cards")
+      .expect("Error writing cards");
+    (coll, TierMeta::new(TierId::from_bytes([0; 20]), (NonZeroU64::new(votes.len() as u64,), CardId::from_bytes([1; 20]), CardId::from_bytes([votes.len() as u8; 20]),), None, None,),)
+  }
+  /// Walks the cards both ways from the ends of a tier.
+  fn order(coll: &RecordingCollection<MemoryCollection<Card,>>, tier: &TierMeta,) -> Vec<u8> {
+    let forward = block_on(stream_from(coll, tier.list_front(),).map_ok(|card,| card.id.as_bytes()[0],).try_collect::<Vec<_>>(),)
+      .expect("Error walking forward");
+    let back = block_on(coll.ref_cursor::<Card>(tier.list_back(),),)
+      .expect("Error reading back");
+    let mut backward = block_on(back.into_stream_back().map_ok(|card,| card.id.as_bytes()[0],).try_collect::<Vec<_>>(),)
+      .expect("Error walking backward");
+
+    backward.reverse();
+    assert_eq!(forward, backward, "Error links disagree",);
+    forward
+  }
+  #[test]
+  fn test_sort_tier() {
+
+    //A sorted tier is only read.
+    let (coll, mut meta,) = tier_of(&[50, 40, 40, 10,],);
+    let report = block_on(sort_tier(&coll, &mut meta, ScoreKind::UpVotes,),)
+      .expect("Error sorting a sorted tier");
+    assert_eq!((report.len, report.moved, report.written.len(),), (4, 0, 0,), "Error changing a sorted tier",);
+    assert!(coll.take_ops().iter().all(|op,| matches!(op, Op::Get(_)),), "Error writing a sorted tier",);
+
+    //Only the cards with new neighbours are written.
+    let (coll, mut meta,) = tier_of(&[30, 20, 5, 10,],);
+    let report = block_on(sort_tier(&coll, &mut meta, ScoreKind::UpVotes,),)
+      .expect("Error sorting the tier");
+    assert_eq!(report.moved, 2, "Error counting the moved cards",);
+    assert_eq!(report.written.succeeded_ids().map(|id,| id.as_bytes()[0],).collect::<Vec<_>>(), [2, 4, 3,], "Error writing unchanged cards",);
+    assert_eq!(order(&coll, &meta,), [1, 2, 4, 3,], "Error sorting the tier",);
+
+    //A shuffled tier.
+    let (coll, mut meta,) = tier_of(&[10, 50, 30, 40, 20,],);
+    let report = block_on(sort_tier(&coll, &mut meta, ScoreKind::UpVotes,),)
+      .expect("Error sorting the tier");
+    assert_eq!((report.len, report.moved,), (5, 4,), "Error reporting the sort",);
+    assert_eq!(order(&coll, &meta,), [2, 4, 3, 5, 1,], "Error sorting a shuffled tier",);
+    assert_eq!((meta.list_front(), meta.list_back(),), (&CardId::from_bytes([2; 20]), &CardId::from_bytes([1; 20]),), "Error setting the ends",);
+    assert!(block_on(sort_tier(&coll, &mut meta, ScoreKind::UpVotes,),).expect("Error sorting again").written.is_empty(), "Error sorting twice",);
+
+    //Ties keep their order.
+    let (coll, mut meta,) = tier_of(&[5, 9, 5, 9, 5,],);
+    block_on(sort_tier(&coll, &mut meta, ScoreKind::Net,),)
+      .expect("Error sorting ties");
+    assert_eq!(order(&coll, &meta,), [2, 4, 1, 3, 5,], "Error reordering ties",);
+
+    //A tier of unknown length is walked, sorted and given its length.
+    let (coll, mut meta,) = tier_of(&[10, 50, 30,],);
+    assert_eq!(block_on(meta.refresh_len::<Card, _,>(&coll, 2,),), Ok(WalkLen::AtLeast(2,)), "Error dropping the length",);
+    let report = block_on(sort_tier(&coll, &mut meta, ScoreKind::UpVotes,),)
+      .expect("Error sorting a tier of unknown length");
+    assert_eq!((report.len, report.moved,), (3, 3,), "Error reporting the sort of a tier of unknown length",);
+    assert_eq!((meta.list_len(), meta.list_front(), meta.list_back(),), (NonZeroU64::new(3,), &CardId::from_bytes([2; 20]), &CardId::from_bytes([1; 20]),), "Error setting the ends of a tier of unknown length",);
+    assert_eq!(order(&coll, &meta,), [2, 3, 1,], "Error sorting a tier of unknown length",);
+
+    //A tier with nil ends is empty.
+    let mut meta = TierMeta::new(TierId::from_bytes([0; 20]), (None, CardId::NIL, CardId::NIL,), None, None,);
+    let report = block_on(sort_tier(&coll, &mut meta, ScoreKind::UpVotes,),)
+      .expect("Error sorting an empty tier");
+    assert_eq!((report.len, report.moved,), (0, 0,), "Error sorting an empty tier",);
+  }
+  #[test]
+  fn test_apply_order() {
+    let ids = |ids: &[u8],| ids.iter().map(|&id,| DocumentId::from_bytes([id; 20]),).collect::<Vec<_>>();
+
+    //The current order is only read.
+    let (coll, mut meta,) = tier_of(&[0; 5],);
+    coll.take_ops();
+    let report = block_on(apply_order::<Card, _,>(&coll, &mut meta, &ids(&[1, 2, 3, 4, 5,],),),)
+      .expect("Error applying the current order");
+    assert_eq!((report.len, report.moved, report.written.len(),), (5, 0, 0,), "Error changing the order",);
+    crate::assert_ops!(coll, [Op::Get(DocumentId::from_bytes([1; 20])), Op::Get(DocumentId::from_bytes([2; 20])), Op::Get(DocumentId::from_bytes([3; 20])), Op::Get(DocumentId::from_bytes([4; 20])), Op::Get(DocumentId::from_bytes([5; 20])),], "Error writing the current order",);
+
+    //An adjacent swap writes the swapped cards and their neighbours.
+    let report = block_on(apply_order::<Card, _,>(&coll, &mut meta, &ids(&[1, 3, 2, 4, 5,],),),)
+      .expect("Error swapping cards");
+    assert_eq!(report.moved, 2, "Error counting the moved cards",);
+    assert_eq!(coll.take_ops().pop(), Some(Op::WriteBatch(ids(&[1, 3, 2, 4,],),)), "Error writing unchanged cards",);
+    assert_eq!(order(&coll, &meta,), [1, 3, 2, 4, 5,], "Error swapping cards",);
+
+    //A full shuffle.
+    let report = block_on(apply_order::<Card, _,>(&coll, &mut meta, &ids(&[5, 3, 1, 4, 2,],),),)
+      .expect("Error shuffling cards");
+    assert_eq!((report.moved, report.written.len(),), (3, 5,), "Error reporting the shuffle",);
+    assert_eq!(order(&coll, &meta,), [5, 3, 1, 4, 2,], "Error shuffling cards",);
+    assert_eq!((meta.list_front(), meta.list_back(),), (&CardId::from_bytes([5; 20]), &CardId::from_bytes([2; 20]),), "Error setting the ends",);
+
+    //The order must hold each card exactly once.
+    let membership = |missing: &[u8], extra: &[u8],| Err(OrderError::Membership { missing: ids(missing,), extra: ids(extra,), });
+    assert_eq!(block_on(apply_order::<Card, _,>(&coll, &mut meta, &ids(&[5, 3, 1, 9, 2,],),),), membership(&[4,], &[9,],), "Error replacing a card",);
+    assert_eq!(block_on(apply_order::<Card, _,>(&coll, &mut meta, &ids(&[5, 3, 1, 4, 2, 3,],),),), membership(&[], &[3,],), "Error repeating a card",);
+    assert_eq!(block_on(apply_order::<Card, _,>(&coll, &mut meta, &ids(&[5, 3,],),),), membership(&[1, 4, 2,], &[],), "Error dropping cards",);
+    assert_eq!(order(&coll, &meta,), [5, 3, 1, 4, 2,], "Error writing after an error",);
+
+    //An empty tier.
+    let mut empty = TierMeta::new(TierId::from_bytes([0; 20]), (None, CardId::from_bytes([0; 20]), CardId::from_bytes([0; 20]),), None, None,);
+    assert_eq!(block_on(apply_order::<Card, _,>(&coll, &mut empty, &[],),).map(|report,| report.len,), Ok(0), "Error ordering an empty tier",);
+
+    //A tier of unknown length is walked rather than treated as empty.
+    assert_eq!(block_on(meta.refresh_len::<Card, _,>(&coll, 2,),), Ok(WalkLen::AtLeast(2,)), "Error dropping the length",);
+    let report = block_on(apply_order::<Card, _,>(&coll, &mut meta, &ids(&[1, 2, 3, 4, 5,],),),)
+      .expect("Error ordering a tier of unknown length");
+    assert_eq!(report.len, 5, "Error reporting the order of a tier of unknown length",);
+    assert_eq!((meta.list_len(), meta.list_front(), meta.list_back(),), (NonZeroU64::new(5,), &CardId::from_bytes([1; 20]), &CardId::from_bytes([5; 20]),), "Error setting the ends of a tier of unknown length",);
+    assert_eq!(order(&coll, &meta,), [1, 2, 3, 4, 5,], "Error ordering a tier of unknown length",);
+  }
+  #[test]
+  fn test_insert_sorted() {
+    //A card with stale links which must be replaced.
+    let card = |id: u8, up_votes: u64,| Card {
+      id: CardId::from_bytes([id; 20]),
+      name: "card".to_owned(),
+      description: String::new(),
+      up_votes,
+      down_votes: 0,
+      bias: 0,
+      status: CardStatus::Open,
+      history: Vec::new(),
+      previous_card: Some(CardId::from_bytes([99; 20]),),
+      next_card: Some(CardId::from_bytes([98; 20]),),
+    };
+    let (coll, mut meta,) = tier_of(&[50, 30, 10,],);
+
+    assert_eq!(block_on(insert_sorted(&coll, &mut meta, card(4, 40,), ScoreKind::UpVotes,),), Ok(1), "Error inserting into the middle",);
+    assert_eq!(block_on(insert_sorted(&coll, &mut meta, card(5, 60,), ScoreKind::UpVotes,),), Ok(0), "Error inserting at the front",);
+    assert_eq!(block_on(insert_sorted(&coll, &mut meta, card(6, 5,), ScoreKind::UpVotes,),), Ok(5), "Error inserting at the back",);
+    assert_eq!(block_on(insert_sorted(&coll, &mut meta, card(7, 30,), ScoreKind::UpVotes,),), Ok(4), "Error inserting after a tie",);
+    assert_eq!(order(&coll, &meta,), [5, 1, 4, 2, 7, 3, 6,], "Error ordering the tier",);
+    assert_eq!((meta.list_len().map(NonZeroU64::get,), meta.list_front(), meta.list_back(),), (Some(7), &CardId::from_bytes([5; 20]), &CardId::from_bytes([6; 20]),), "Error updating the tier",);
+
+    //An empty tier.
+    let coll = RecordingCollection::new(MemoryCollection::new(),);
+    let mut meta = TierMeta::new(TierId::from_bytes([0; 20]), (None, CardId::from_bytes([0; 20]), CardId::from_bytes([0; 20]),), None, None,);
+    assert_eq!(block_on(insert_sorted(&coll, &mut meta, card(8, 1,), ScoreKind::Net,),), Ok(0), "Error inserting into an empty tier",);
+    assert_eq!(order(&coll, &meta,), [8,], "Error linking the only card",);
+    assert_eq!((meta.list_len().map(NonZeroU64::get,), meta.list_front(), meta.list_back(),), (Some(1), &CardId::from_bytes([8; 20]), &CardId::from_bytes([8; 20]),), "Error updating the empty tier",);
+
+    //A tier whose length was lost by a capped count keeps its cards.
+    let (coll, mut meta,) = tier_of(&[9, 7, 5,],);
+    assert_eq!(block_on(meta.refresh_len::<Card, _,>(&coll, 2,),), Ok(WalkLen::AtLeast(2,)), "Error refreshing the length",);
+    assert_eq!(block_on(insert_sorted(&coll, &mut meta, card(4, 6,), ScoreKind::UpVotes,),), Ok(2), "Error inserting after a capped refresh_len",);
+    assert_eq!(order(&coll, &meta,), [1, 2, 4, 3,], "Error orphaning the cards of the tier",);
+    assert_eq!(meta.list_len().map(NonZeroU64::get,), Some(4), "Error counting the tier",);
+  }
+  #[test]
+  fn test_rotate() {
+    let rotated = |k: i64,| {
+      let (coll, mut meta,) = tier_of(&[0; 5],);
+      let moved = block_on(rotate::<Card, _,>(&coll, &mut meta, k,),).expect("Error rotating");
+
+      (moved, order(&coll, &meta,),)
+    };
+
+    assert_eq!(rotated(2,), (2, vec![3, 4, 5, 1, 2,],), "Error rotating forward",);
+    assert_eq!(rotated(-1,), (4, vec![5, 1, 2, 3, 4,],), "Error rotating backward",);
+    assert_eq!(rotated(0,), (0, vec![1, 2, 3, 4, 5,],), "Error rotating by zero",);
+    assert_eq!(rotated(5,), (0, vec![1, 2, 3, 4, 5,],), "Error rotating by the length",);
+    assert_eq!(rotated(7,), (2, vec![3, 4, 5, 1, 2,],), "Error wrapping forward",);
+    assert_eq!(rotated(-6,), (4, vec![5, 1, 2, 3, 4,],), "Error wrapping backward",);
+    assert_eq!(rotated(i64::MIN,), (2, vec![3, 4, 5, 1, 2,],), "Error wrapping the smallest rotation",);
+
+    //Only the ends and the split are read and written.
+    let (coll, mut meta,) = tier_of(&[0; 5],);
+    coll.take_ops();
+    assert_eq!(block_on(rotate::<Card, _,>(&coll, &mut meta, 1,),), Ok(1), "Error rotating one card",);
+    crate::assert_ops!(coll, [Op::Get(DocumentId::from_bytes([1; 20])), Op::GetBatch(vec![DocumentId::from_bytes([5; 20]), DocumentId::from_bytes([2; 20]),]), Op::WriteBatch(vec![DocumentId::from_bytes([1; 20]), DocumentId::from_bytes([5; 20]), DocumentId::from_bytes([2; 20]),]),], "Error touching other cards",);
+    let (coll, mut meta,) = tier_of(&[0; 5],);
+    coll.take_ops();
+    assert_eq!(block_on(rotate::<Card, _,>(&coll, &mut meta, 3,),), Ok(3), "Error rotating from the back",);
+    crate::assert_ops!(coll, [Op::Get(DocumentId::from_bytes([5; 20])), Op::Get(DocumentId::from_bytes([4; 20])), Op::GetBatch(vec![DocumentId::from_bytes([1; 20]), DocumentId::from_bytes([3; 20]),]), Op::WriteBatch(vec![DocumentId::from_bytes([5; 20]), DocumentId::from_bytes([4; 20]), DocumentId::from_bytes([1; 20]), DocumentId::from_bytes([3; 20]),]),], "Error walking from the closer end",);
+    assert_eq!(order(&coll, &meta,), [4, 5, 1, 2, 3,], "Error rotating from the back",);
+
+    //A tier without a length is counted.
+    let (coll, _,) = tier_of(&[0; 3],);
+    let mut meta = TierMeta::new(TierId::from_bytes([0; 20]), (None, CardId::from_bytes([1; 20]), CardId::from_bytes([3; 20]),), None, None,);
+    assert_eq!(block_on(rotate::<Card, _,>(&coll, &mut meta, 1,),), Ok(1), "Error rotating a tier without a length",);
+    assert_eq!(order(&coll, &meta,), [2, 3, 1,], "Error rotating a counted tier",);
+
+    //A tier with nil ends or a missing front is empty and is not walked.
+    let mut meta = TierMeta::new(TierId::from_bytes([0; 20]), (None, CardId::NIL, CardId::NIL,), None, None,);
+    coll.take_ops();
+    assert_eq!(block_on(rotate::<Card, _,>(&coll, &mut meta, 1,),), Ok(0), "Error rotating an empty tier",);
+    crate::assert_ops!(coll, [], "Error walking an empty tier",);
+    let mut meta = TierMeta::new(TierId::from_bytes([0; 20]), (None, CardId::from_bytes([9; 20]), CardId::from_bytes([9; 20]),), None, None,);
+    assert_eq!(block_on(rotate::<Card, _,>(&coll, &mut meta, 1,),), Ok(0), "Error rotating a tier with a missing front",);
+
+    //A length longer than the list.
+    let (coll, _,) = tier_of(&[0; 3],);
+    let mut meta = TierMeta::new(TierId::from_bytes([0; 20]), (NonZeroU64::new(9,), CardId::from_bytes([1; 20]), CardId::from_bytes([3; 20]),), None, None,);
+    assert_eq!(block_on(rotate::<Card, _,>(&coll, &mut meta, 4,),), Err(RotateError::Ended(DocumentId::from_bytes([3; 20]),)), "Error rotating past the back",);
+    assert_eq!(order(&coll, &meta,), [1, 2, 3,], "Error writing after an error",);
+  }
+  #[test]
+  fn test_partial_write() {
+    let coll = ChaosCollection::new();
+    let tiers = [tier(1, None, Some(2,),), tier(2, Some(1,), None,),];
+
+    block_on(coll.write_documents(tiers,),)
+      .expect("Error writing list")
+      .expect("Error writing list");
+    coll.fail_writes(DocumentId::from_bytes([2; 20]),);
+
+    let report = block_on(link_after(&coll, &DocumentId::from_bytes([1; 20]), tier(9, None, None,),),)
+      .expect("Error linking");
+    assert!(!report.is_complete_success(), "Error hiding the failed write",);
+    assert_eq!(report.written.failures().collect::<Vec<_>>(), [(&DocumentId::from_bytes([2; 20]), &ChaosError::Injected(DocumentId::from_bytes([2; 20]),),),], "Error reporting the failed write",);
+  }
+}
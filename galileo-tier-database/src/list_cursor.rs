@@ -0,0 +1,274 @@
+//! Defines a cursor which walks every card of a tier list across its tiers.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, Card, TierMeta, TierDocument, Cursor, LookupError, TierListCollection,};
+use std::fmt;
+
+/// An item reached while walking a tier list using a `TierListCursor`.
+#[derive(PartialEq, Eq, Clone, Debug,)]
+pub enum TierListItem {
+  /// The start of a tier; the cards which follow belong to it.
+  TierBoundary(TierMeta),
+  /// A card in the current tier.
+  Card(Card),
+}
+
+/// Where a `TierListCursor` is within the current tier.
+#[derive(PartialEq, Eq, Clone,)]
+enum State<Coll,>
+  where Coll: TierListCollection, {
+  /// The boundary of the current tier has not been yielded.
+  Boundary,
+  /// No card of the current tier has been yielded.
+  Front,
+  /// At the last card yielded.
+  Card(Cursor<Card, Coll,>),
+  /// The end of the list was reached.
+  Done,
+}
+
+/// A view into a tier list which walks its cards in list order, moving on to the next
+/// tier when the cards of the current tier run out.
+/// 
+/// Empty tiers are passed over by `next_card` but still have their boundary yielded by
+/// `next_item`.
+#[derive(PartialEq, Eq, Clone,)]
+pub struct TierListCursor<Coll,>
+  where Coll: TierListCollection, {
+  /// The `Cursor` at the current tier.
+  tier: Cursor<TierMeta, Coll,>,
+  /// Where this `TierListCursor` is within the current tier.
+  state: State<Coll,>,
+}
+
+impl<Coll,> TierListCursor<Coll,>
+  where Coll: TierListCollection<Document = TierDocument> + Clone, {
+  /// Returns a new `TierListCursor` before the first card of a tier.
+  /// 
+  /// # Params
+  /// 
+  /// tier --- The `Cursor` at the tier to start from.  
+  #[inline]
+  pub const fn new(tier: Cursor<TierMeta, Coll,>,) -> Self {
+    Self { tier, state: State::Boundary, }
+  }
+  /// Returns a new `TierListCursor` before the first card of a tier list.
+  /// 
+  /// # Params
+  /// 
+  /// collection --- The collection storing the list.  
+  /// list_head --- The identifier of the first tier of the list.  
+  pub async fn start(collection: Coll, list_head: &DocumentId,) -> Result<Self, LookupError<Coll::Error,>> {
+    let tier = collection.get_item_flat::<TierMeta>(list_head,).await?;
+
+    Ok(Self::new(Cursor::new(collection, tier,).with_position(0,),))
+  }
+  /// Gets the `Cursor` at the current tier.
+  #[inline]
+  pub const fn get_tier(&self,) -> &Cursor<TierMeta, Coll,> { &self.tier }
+  /// Gets the `Cursor` at the last card yielded in the current tier; `None` if no card
+  /// of the current tier has been yielded.
+  #[inline]
+  pub fn get_card(&self,) -> Option<&Cursor<Card, Coll,>> {
+    match &self.state {
+      State::Card(card) => Some(card,),
+      _ => None,
+    }
+  }
+  /// Moves to the next card in the list, passing over empty tiers.
+  /// 
+  /// Returns the identifier of the tier owning the card and the card, or `None` at the
+  /// end of the list.
+  pub async fn next_card(&mut self,) -> Result<Option<(DocumentId, Card,)>, LookupError<Coll::Error,>> {
+    loop {
+      match self.next_item().await? {
+        Some(TierListItem::Card(card)) => return Ok(Some((*self.tier.get_item().id, card,),)),
+        Some(TierListItem::TierBoundary(_)) => {},
+        None => return Ok(None),
+      }
+    }
+  }
+  /// Moves to the next item in the list.
+  /// 
+  /// The boundary of each tier is yielded before its cards; `None` at the end of the
+  /// list. If there was an error getting a document this `TierListCursor` is
+  /// unchanged.
+  pub async fn next_item(&mut self,) -> Result<Option<TierListItem>, LookupError<Coll::Error,>> {
+    loop {
+      let collection = self.tier.get_collection();
+      let next_card = match &self.state {
+        State::Boundary => {
+          self.state = State::Front;
+          return Ok(Some(TierListItem::TierBoundary(*self.tier.get_item(),),))
+        },
+        State::Front => Some(*self.tier.get_item().list_front(),).filter(|id,| !id.is_nil(),),
+        State::Card(card) => card.get_item().next_card,
+        State::Done => return Ok(None),
+      };
+
+      if let Some(id) = next_card {
+        let card = collection.get_item_flat::<Card>(&id,).await?;
+
+        match &mut self.state {
+          State::Card(cursor) => { cursor.step(card.clone(), true,); },
+          state => *state = State::Card(Cursor::new(collection.clone(), card.clone(),).with_position(0,),),
+        }
+
+        return Ok(Some(TierListItem::Card(card,),))
+      }
+
+      //The cards of the current tier ran out.
+      match self.tier.get_item().next_tier {
+        Some(id) => {
+          let tier = collection.get_item_flat::<TierMeta>(&id,).await?;
+
+          self.tier.step(tier, true,);
+          self.state = State::Boundary;
+        },
+        None => self.state = State::Done,
+      }
+    }
+  }
+}
+
+impl<Coll,> fmt::Debug for State<Coll,>
+  where Coll: TierListCollection, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      State::Boundary => fmt.write_str("Boundary",),
+      State::Front => fmt.write_str("Front",),
+      State::Card(card) => fmt.debug_tuple("Card",).field(card,).finish(),
+      State::Done => fmt.write_str("Done",),
+    }
+  }
+}
+
+impl<Coll,> fmt::Debug for TierListCursor<Coll,>
+  where Coll: TierListCollection, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    fmt.debug_struct("TierListCursor",)
+    .field("tier", &self.tier,)
+    .field("state", &self.state,)
+    .finish()
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{CardId, CardStatus, TierId, MemoryCollection, mock::{ChaosCollection, ChaosError,},};
+  use futures::executor::block_on;
+  use std::num::NonZeroU64;
+
+  fn card(id: u8, previous: Option<u8>, next: Option<u8>,) -> TierDocument {
+    TierDocument::Card(Card {
+      id: CardId::from_bytes([id; 20]),
+      name: format!("card {}", id,),
+      description: String::new(),
+      up_votes: 0,
+      down_votes: 0,
+      bias: 0,
+      status: CardStatus::Open,
+      history: Vec::new(),
+      previous_card: previous.map(|id,| CardId::from_bytes([id; 20]),),
+      next_card: next.map(|id,| CardId::from_bytes([id; 20]),),
+    },)
+  }
+  /// Returns a list of three tiers where the middle tier is empty.
+  fn list() -> Vec<TierDocument> {
+    vec![
+      TierDocument::TierMeta(TierMeta::new(TierId::from_bytes([1; 20]), (NonZeroU64::new(2,), CardId::from_bytes([10; 20]), CardId::from_bytes([11; 20]),), None, Some(TierId::from_bytes([2; 20])),),),
+      TierDocument::TierMeta(TierMeta::new(TierId::from_bytes([2; 20]), (None, CardId::from_bytes([0; 20]), CardId::from_bytes([0; 20]),), Some(TierId::from_bytes([1; 20])), Some(TierId::from_bytes([3; 20])),),),
+      TierDocument::TierMeta(TierMeta::new(TierId::from_bytes([3; 20]), (NonZeroU64::new(3,), CardId::from_bytes([12; 20]), CardId::from_bytes([14; 20]),), Some(TierId::from_bytes([2; 20])), None,),),
+      card(10, None, Some(11,),), card(11, Some(10,), None,),
+      card(12, None, Some(13,),), card(13, Some(12,), Some(14,),), card(14, Some(13,), None,),
+    ]
+  }
+
+  #[test]
+  fn test_next_card() {
+    let coll = MemoryCollection::new();
+
+    block_on(async {
+      coll.write_documents(list(),).await
+        .expect("Error writing list")
+        .expect("Error writing list");
+
+      let mut cursor = TierListCursor::start(&coll, &DocumentId::from_bytes([1; 20]),).await
+        .expect("Error reading head");
+      let mut cards = Vec::new();
+      while let Some((tier, card,)) = cursor.next_card().await.expect("Error walking list") {
+        cards.push((tier.as_bytes()[0], card.id.as_bytes()[0],),);
+      }
+      assert_eq!(cards, [(1, 10,), (1, 11,), (3, 12,), (3, 13,), (3, 14,),], "Error walking cards in list order",);
+      assert_eq!(cursor.get_tier().position(), Some(2), "Error counting tiers",);
+      assert_eq!(cursor.next_card().await, Ok(None), "Error walking past the end",);
+
+      //A tier of unknown length is walked from its front.
+      coll.write_document(&TierDocument::TierMeta(TierMeta::new(TierId::from_bytes([3; 20]), (None, CardId::from_bytes([12; 20]), CardId::from_bytes([14; 20]),), Some(TierId::from_bytes([2; 20])), None,),),).await
+        .expect("Error writing tier");
+      let mut cursor = TierListCursor::start(&coll, &DocumentId::from_bytes([1; 20]),).await
+        .expect("Error reading head");
+      let mut cards = Vec::new();
+      while let Some((tier, card,)) = cursor.next_card().await.expect("Error walking list") {
+        cards.push((tier.as_bytes()[0], card.id.as_bytes()[0],),);
+      }
+      assert_eq!(cards, [(1, 10,), (1, 11,), (3, 12,), (3, 13,), (3, 14,),], "Error walking a tier of unknown length",);
+    },);
+  }
+  #[test]
+  fn test_next_item() {
+    let coll = MemoryCollection::new();
+
+    block_on(async {
+      coll.write_documents(list(),).await
+        .expect("Error writing list")
+        .expect("Error writing list");
+
+      let mut cursor = TierListCursor::start(&coll, &DocumentId::from_bytes([1; 20]),).await
+        .expect("Error reading head");
+      let mut items = Vec::new();
+      while let Some(item) = cursor.next_item().await.expect("Error walking list") {
+        items.push(match item {
+          TierListItem::TierBoundary(tier) => format!("tier {}", tier.id.as_bytes()[0],),
+          TierListItem::Card(card) => {
+            assert_eq!(cursor.get_card().map(|card,| card.get_item().id,), Some(card.id), "Error card Cursor not at card",);
+            format!("{} at {:?}", card.name, cursor.get_card().and_then(Cursor::position,),)
+          },
+        },);
+      }
+      assert_eq!(
+        items,
+        [
+          "tier 1", "card 10 at Some(0)", "card 11 at Some(1)",
+          "tier 2",
+          "tier 3", "card 12 at Some(0)", "card 13 at Some(1)", "card 14 at Some(2)",
+        ],
+        "Error walking items in list order",
+      );
+    },);
+  }
+  #[test]
+  fn test_failed_fetch() {
+    let coll = ChaosCollection::new();
+
+    block_on(async {
+      coll.write_documents(list(),).await
+        .expect("Error writing list")
+        .expect("Error writing list");
+      coll.flaky_reads(DocumentId::from_bytes([12; 20]), 1,);
+
+      let mut cursor = TierListCursor::start(&coll, &DocumentId::from_bytes([1; 20]),).await
+        .expect("Error reading head");
+      assert_eq!(cursor.next_card().await.map(|card,| card.map(|(_, card,),| card.id,),), Ok(Some(CardId::from_bytes([10; 20]))), "Error reading first card",);
+      assert_eq!(cursor.next_card().await.map(|card,| card.map(|(_, card,),| card.id,),), Ok(Some(CardId::from_bytes([11; 20]))), "Error reading second card",);
+      let e = cursor.next_card().await
+        .expect_err("Error reading broken card");
+      assert_eq!(e, LookupError::Fetch(ChaosError::Injected(DocumentId::from_bytes([12; 20]),),), "Error returning the read error",);
+      //The cursor resumes at the card which failed.
+      assert_eq!(cursor.next_card().await.map(|card,| card.map(|(tier, card,),| (tier, *card.id,),),), Ok(Some((DocumentId::from_bytes([3; 20]), DocumentId::from_bytes([12; 20]),),)), "Error resuming after an error",);
+    },);
+  }
+}
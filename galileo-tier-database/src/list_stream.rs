@@ -0,0 +1,276 @@
+//! Defines a stream over a linked list which can be read from both ends.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, LinkedList, Cursor, TierListCollection,};
+use futures::{Future, Stream, future,};
+use std::{
+  fmt,
+  mem,
+  collections::HashSet,
+  pin::Pin,
+  task::{Context, Poll,},
+};
+
+/// A stream which can also yield items from its back.
+pub trait DoubleEndedStream: Stream {
+  /// Attempts to pull out the next item from the back of this stream.
+  /// 
+  /// The front and the back of the stream never yield the same item and the stream is
+  /// finished once the ends meet.
+  fn poll_next_back(self: Pin<&mut Self>, cx: &mut Context,) -> Poll<Option<Self::Item>>;
+}
+
+/// The next node of one end of a `ListStream`.
+enum Frontier<T, Fut,> {
+  /// The item of the next node.
+  Item(T),
+  /// The identifier of the next node which has not been read.
+  Id(DocumentId),
+  /// The read of the next node.
+  Fetching(Pin<Box<Fut>>),
+  /// The end has finished.
+  Done,
+}
+
+/// A stream over the nodes of a linked list which yields nodes forward from a front
+/// `Cursor` and backward from a back `Cursor`, returned by `ListStream::new` and
+/// `ListStream::between`.
+/// 
+/// Each end only reads the nodes it yields and no document is yielded twice; once the
+/// ends meet both ends finish. Each end finishes after yielding an error.
+pub struct ListStream<T, Coll,>
+  where Coll: TierListCollection, {
+  /// The collection to read the nodes from.
+  collection: Coll,
+  /// The next node going forward.
+  front: Frontier<T, Coll::GetDocument,>,
+  /// The next node going backward.
+  back: Frontier<T, Coll::GetDocument,>,
+  /// The identifiers of the yielded nodes.
+  seen: HashSet<DocumentId>,
+}
+
+impl<T, Coll,> ListStream<T, Coll,>
+  where T: LinkedList,
+    Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  /// Returns a new `ListStream` which yields the item at a `Cursor` and each node after
+  /// it going forward and each node before it going backward.
+  /// 
+  /// # Params
+  /// 
+  /// cursor --- The `Cursor` to start at.  
+  pub fn new(cursor: Cursor<T, Coll,>,) -> Self {
+    let (collection, item,) = cursor.into_parts();
+    let back = item.get_previous_id().map_or(Frontier::Done, |&id,| Frontier::Id(id,),);
+
+    Self { collection, front: Frontier::Item(item,), back, seen: HashSet::new(), }
+  }
+  /// Returns a new `ListStream` which yields the nodes from the item at `front` to the
+  /// item at `back` going forward and from `back` to `front` going backward.
+  /// 
+  /// # Params
+  /// 
+  /// front --- The `Cursor` at the first node to yield going forward.  
+  /// back --- The `Cursor` at the first node to yield going backward.  
+  pub fn between(front: Cursor<T, Coll,>, back: Cursor<T, Coll,>,) -> Self {
+    let (collection, front,) = front.into_parts();
+    let (_, back,) = back.into_parts();
+
+    Self { collection, front: Frontier::Item(front,), back: Frontier::Item(back,), seen: HashSet::new(), }
+  }
+  /// Gets the collection used by this `ListStream`.
+  #[inline]
+  pub const fn get_collection(&self,) -> &Coll { &self.collection }
+  /// Gets the next item from the back of this stream.
+  pub async fn next_back(&mut self,) -> Option<Result<T, Coll::Error>> {
+    future::poll_fn(|cx,| Pin::new(&mut *self,).poll_next_back(cx,),).await
+  }
+  /// Attempts to pull out the next item from one end of this stream.
+  /// 
+  /// # Params
+  /// 
+  /// cx --- The context of the task polling the stream.  
+  /// forward --- Whether to pull from the front.  
+  fn poll_end(&mut self, cx: &mut Context, forward: bool,) -> Poll<Option<Result<T, Coll::Error>>> {
+    loop {
+      let frontier = if forward { &mut self.front } else { &mut self.back };
+      let next = match mem::replace(frontier, Frontier::Done,) {
+        Frontier::Done => return Poll::Ready(None),
+        //The node was yielded by the other end.
+        Frontier::Id(id) if self.seen.contains(&id,) => return self.meet(),
+        Frontier::Id(id) => Frontier::Fetching(Box::pin(self.collection.get_document(&id,),),),
+        Frontier::Fetching(mut fetching) => match fetching.as_mut().poll(cx,) {
+          Poll::Ready(Ok(document)) => Frontier::Item(document.into(),),
+          Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e),),),
+          Poll::Pending => {
+            *frontier = Frontier::Fetching(fetching,);
+            return Poll::Pending
+          },
+        },
+        Frontier::Item(item) => {
+          if !self.seen.insert(*item.get_id(),) { return self.meet() }
+
+          let link = if forward { item.get_next_id() } else { item.get_previous_id() };
+          let next = link.map_or(Frontier::Done, |&id,| Frontier::Id(id,),);
+
+          if forward { self.front = next } else { self.back = next }
+          return Poll::Ready(Some(Ok(item),),)
+        },
+      };
+
+      if forward { self.front = next } else { self.back = next }
+    }
+  }
+  /// Finishes both ends of this stream.
+  fn meet(&mut self,) -> Poll<Option<Result<T, Coll::Error>>> {
+    self.front = Frontier::Done;
+    self.back = Frontier::Done;
+
+    Poll::Ready(None)
+  }
+}
+
+//The items are never pinned and the reads are boxed.
+impl<T, Coll,> Unpin for ListStream<T, Coll,>
+  where Coll: TierListCollection, {}
+
+impl<T, Coll,> Stream for ListStream<T, Coll,>
+  where T: LinkedList,
+    Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  type Item = Result<T, Coll::Error>;
+
+  #[inline]
+  fn poll_next(self: Pin<&mut Self>, cx: &mut Context,) -> Poll<Option<Self::Item>> {
+    self.get_mut().poll_end(cx, true,)
+  }
+}
+
+impl<T, Coll,> DoubleEndedStream for ListStream<T, Coll,>
+  where T: LinkedList,
+    Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  #[inline]
+  fn poll_next_back(self: Pin<&mut Self>, cx: &mut Context,) -> Poll<Option<Self::Item>> {
+    self.get_mut().poll_end(cx, false,)
+  }
+}
+
+impl<T, Coll,> fmt::Debug for ListStream<T, Coll,>
+  where Coll: TierListCollection, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    fmt.debug_struct("ListStream",)
+    .field("collection", &format_args!("{}", std::any::type_name::<Coll>(),),)
+    .field("seen", &self.seen.len(),)
+    .finish()
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{CardId, TierId, TierMeta, MemoryCollection, RecordingCollection, Op, mock::{ChaosCollection, ChaosError,},};
+  use futures::{executor::block_on, StreamExt,};
+
+  /// Returns a collection storing a list of tiers with the identifiers one to `len`.
+  fn list(len: u8,) -> RecordingCollection<ChaosCollection<TierMeta,>> {
+    let coll = RecordingCollection::new(ChaosCollection::new(),);
+    let tiers = (1..=len).map(|i,| TierMeta::new(
+      TierId::from_bytes([i; 20]),
+      (None, CardId::from_bytes([i; 20]), CardId::from_bytes([i; 20]),),
+      Some(TierId::from_bytes([i - 1; 20]),).filter(|_,| i > 1,),
+      Some(TierId::from_bytes([i + 1; 20]),).filter(|_,| i < len,),
+    ),).collect::<Vec<_>>();
+
+    block_on(coll.get_inner().write_documents(&tiers,),)
+      .expect("Error writing list")
+      .expect("Error writing list");
+
+    coll
+  }
+  /// Pulls from alternating ends of a stream until both ends finish.
+  async fn alternate<Coll,>(stream: &mut ListStream<TierMeta, Coll,>,) -> Vec<u8>
+    where Coll: TierListCollection<Document = TierMeta>,
+      Coll::Error: fmt::Debug, {
+    let mut ids = Vec::new();
+    let (mut front, mut back,) = (true, true,);
+
+    while front || back {
+      if front {
+        match stream.next().await {
+          Some(tier) => ids.push(tier.expect("Error reading forward").id.as_bytes()[0],),
+          None => front = false,
+        }
+      }
+      if back {
+        match stream.next_back().await {
+          Some(tier) => ids.push(tier.expect("Error reading backward").id.as_bytes()[0],),
+          None => back = false,
+        }
+      }
+    }
+
+    ids
+  }
+
+  #[test]
+  fn test_between() {
+    let coll = list(7,);
+
+    block_on(async {
+      let front = coll.ref_cursor::<TierMeta>(&DocumentId::from_bytes([1; 20]),).await.expect("Error reading front");
+      let back = coll.ref_cursor::<TierMeta>(&DocumentId::from_bytes([7; 20]),).await.expect("Error reading back");
+      let mut stream = ListStream::between(front, back,);
+      coll.take_ops();
+
+      assert_eq!(alternate(&mut stream,).await, [1, 7, 2, 6, 3, 5, 4,], "Error meeting in the middle",);
+      crate::assert_ops!(coll, [Op::Get(DocumentId::from_bytes([2; 20])), Op::Get(DocumentId::from_bytes([6; 20])), Op::Get(DocumentId::from_bytes([3; 20])), Op::Get(DocumentId::from_bytes([5; 20])), Op::Get(DocumentId::from_bytes([4; 20])),], "Error reading a node twice",);
+      assert!(stream.next().await.is_none(), "Error front continued after meeting",);
+      assert!(stream.next_back().await.is_none(), "Error back continued after meeting",);
+      crate::assert_ops!(coll, [], "Error reading after meeting",);
+    },);
+  }
+  #[test]
+  fn test_new() {
+    let coll = list(7,);
+
+    block_on(async {
+      let cursor = coll.ref_cursor::<TierMeta>(&DocumentId::from_bytes([3; 20]),).await.expect("Error reading node");
+      let mut ids = alternate(&mut ListStream::new(cursor,),).await;
+      assert_eq!(ids, [3, 2, 4, 1, 5, 6, 7,], "Error reading both directions",);
+
+      ids.sort_unstable();
+      assert_eq!(ids, [1, 2, 3, 4, 5, 6, 7,], "Error yielding each node once",);
+    },);
+  }
+  #[test]
+  fn test_failed_read() {
+    let coll = list(4,);
+
+    block_on(async {
+      let cursor = coll.ref_cursor::<TierMeta>(&DocumentId::from_bytes([2; 20]),).await.expect("Error reading node");
+      let mut stream = ListStream::new(cursor,);
+
+      coll.get_inner().fail_reads(DocumentId::from_bytes([1; 20]),);
+      assert_eq!(stream.next_back().await, Some(Err(ChaosError::Injected(DocumentId::from_bytes([1; 20]),),)), "Error failing backward",);
+      assert_eq!(stream.next_back().await, None, "Error continuing backward after an error",);
+      let forward = stream.map(|tier,| tier.map(|tier,| tier.id.as_bytes()[0],),).collect::<Vec<_>>().await;
+      assert_eq!(forward, [Ok(2), Ok(3), Ok(4),], "Error forward stopped by an error backward",);
+    },);
+
+    //A single node is yielded once.
+    let coll = MemoryCollection::<TierMeta,>::new();
+    let tier = TierMeta::new(TierId::from_bytes([1; 20]), (None, CardId::from_bytes([1; 20]), CardId::from_bytes([1; 20]),), None, None,);
+
+    block_on(async {
+      coll.write_document(&tier,).await
+        .expect("Error writing tier");
+      let cursor = coll.ref_cursor::<TierMeta>(&DocumentId::from_bytes([1; 20]),).await.expect("Error reading node");
+      let mut stream = ListStream::between(cursor, cursor,);
+      assert_eq!(alternate(&mut stream,).await, [1,], "Error yielding a single node twice",);
+    },);
+  }
+}
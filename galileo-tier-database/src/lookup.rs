@@ -0,0 +1,497 @@
+//! Defines operations for finding the documents of a tier list by their names.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{
+  DocumentId, Card, TierMeta, TierDocument, WrongKind, ItemError, CollectionError, TierListCollection, TierListCollectionScan,
+};
+use futures::{Future, FutureExt, TryFutureExt,};
+use std::{fmt, error::Error, convert::TryFrom, collections::{HashMap, HashSet,}, rc::Rc, sync::Arc,};
+
+/// The errors when looking up a document by name.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum LookupError<E,> {
+  /// Getting a document failed.
+  Fetch(E),
+  /// A document had the wrong kind.
+  WrongKind(WrongKind),
+  /// The document was reached twice while walking the list.
+  Cycle(DocumentId),
+}
+
+impl<E,> From<ItemError<E, WrongKind,>> for LookupError<E,> {
+  #[inline]
+  fn from(from: ItemError<E, WrongKind,>,) -> Self {
+    match from {
+      ItemError::Fetch(e) => LookupError::Fetch(e,),
+      ItemError::Convert(e) => LookupError::WrongKind(e,),
+    }
+  }
+}
+
+impl<E,> From<WrongKind> for LookupError<E,> {
+  #[inline]
+  fn from(from: WrongKind,) -> Self { LookupError::WrongKind(from,) }
+}
+
+impl<E,> fmt::Display for LookupError<E,>
+  where E: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      LookupError::Fetch(e) => write!(fmt, "failed to get a document: {}", e,),
+      LookupError::WrongKind(e) => e.fmt(fmt,),
+      LookupError::Cycle(id) => write!(fmt, "the document with the id {:?} was reached twice", id,),
+    }
+  }
+}
+
+impl<E,> Error for LookupError<E,>
+  where E: Error, {}
+
+/// A collection of tier lists whose cards and tiers can be found by name.
+/// 
+/// Names are compared ignoring case.
+pub trait NamedLookup: TierListCollection<Document = TierDocument> {
+  /// Finds the first card in list order with a name and returns the identifier of the
+  /// tier owning it and the card.
+  /// 
+  /// The default implementation walks the list, reading the front card of each tier
+  /// in the same batch as the next tier.
+  /// 
+  /// # Params
+  /// 
+  /// list_head --- The identifier of the first tier of the list.  
+  /// name --- The name of the card.  
+  fn find_card_by_name(&self, list_head: &DocumentId, name: &str,) -> impl Future<Output = Result<Option<(DocumentId, Card,)>, LookupError<Self::Error,>>> {
+    walk_card_by_name(self, list_head, name,)
+  }
+  /// Finds the first tier in list order with a name.
+  /// 
+  /// The default implementation walks the tiers of the list without reading any cards.
+  /// 
+  /// # Params
+  /// 
+  /// list_head --- The identifier of the first tier of the list.  
+  /// name --- The name of the tier.  
+  fn find_tier_by_name(&self, list_head: &DocumentId, name: &str,) -> impl Future<Output = Result<Option<TierMeta>, LookupError<Self::Error,>>> {
+    walk_tier_by_name(self, list_head, name,)
+  }
+  /// Finds a card with a name by scanning the collection and returns the identifier of
+  /// the tier owning it and the card.
+  /// 
+  /// If the list can be walked in the scanned documents the first card in list order is
+  /// returned. Otherwise any card with the name whose tier can be found by following
+  /// its links to the front of a tier, and then from that tier back to `list_head`, is
+  /// returned so that broken lists can still be searched.
+  /// 
+  /// # Params
+  /// 
+  /// list_head --- The identifier of the first tier of the list.  
+  /// name --- The name of the card.  
+  fn scan_card_by_name(&self, list_head: &DocumentId, name: &str,) -> impl Future<Output = Result<Option<(DocumentId, Card,)>, Self::Error>>
+    where Self: TierListCollectionScan, {
+    let list_head = *list_head;
+    let name = name.to_owned();
+
+    self.snapshot().map_ok(move |snapshot,| {
+      //Reading from the snapshot is always ready.
+      if let Some(Ok(found)) = walk_card_by_name(&snapshot, &list_head, &name,).now_or_never() {
+        return found
+      }
+
+      let name = name.to_lowercase();
+      let documents = snapshot.documents();
+
+      documents.values()
+      .filter_map(|doc,| match doc {
+        TierDocument::Card(card) if card.name.to_lowercase() == name => Some(card,),
+        _ => None,
+      },)
+      .find_map(|card,| {
+        //Follow the card back to the front of its tier.
+        let mut visited = HashSet::new();
+        let mut front = card;
+        while let Some(previous) = front.previous_card {
+          if !visited.insert(previous,) { return None }
+
+          match documents.get(&previous,) {
+            Some(TierDocument::Card(previous)) => front = previous,
+            _ => return None,
+          }
+        }
+
+        documents.values().find_map(|doc,| match doc {
+          TierDocument::TierMeta(tier) if tier.list_front() == &front.id && in_list(documents, tier, &list_head,) => Some((*tier.id, card.clone(),),),
+          _ => None,
+        },)
+      },)
+    },)
+  }
+  /// Finds a tier with a name by scanning the collection.
+  /// 
+  /// As with `scan_card_by_name` the first tier in list order is returned if the list
+  /// can be walked in the scanned documents; otherwise any tier with the name whose
+  /// previous tiers lead back to `list_head` is returned.
+  /// 
+  /// # Params
+  /// 
+  /// list_head --- The identifier of the first tier of the list.  
+  /// name --- The name of the tier.  
+  fn scan_tier_by_name(&self, list_head: &DocumentId, name: &str,) -> impl Future<Output = Result<Option<TierMeta>, Self::Error>>
+    where Self: TierListCollectionScan, {
+    let list_head = *list_head;
+    let name = name.to_owned();
+
+    self.snapshot().map_ok(move |snapshot,| {
+      //Reading from the snapshot is always ready.
+      if let Some(Ok(found)) = walk_tier_by_name(&snapshot, &list_head, &name,).now_or_never() {
+        return found
+      }
+
+      let name = name.to_lowercase();
+      let documents = snapshot.documents();
+
+      documents.values().find_map(|doc,| match doc {
+        TierDocument::TierMeta(tier) if tier.name.as_str().to_lowercase() == name && in_list(documents, tier, &list_head,) => Some(*tier,),
+        _ => None,
+      },)
+    },)
+  }
+}
+
+impl<Coll,> NamedLookup for &Coll
+  where Coll: NamedLookup, {
+  #[inline]
+  fn find_card_by_name(&self, list_head: &DocumentId, name: &str,) -> impl Future<Output = Result<Option<(DocumentId, Card,)>, LookupError<Self::Error,>>> {
+    Coll::find_card_by_name(*self, list_head, name,)
+  }
+  #[inline]
+  fn find_tier_by_name(&self, list_head: &DocumentId, name: &str,) -> impl Future<Output = Result<Option<TierMeta>, LookupError<Self::Error,>>> {
+    Coll::find_tier_by_name(*self, list_head, name,)
+  }
+}
+
+impl<Coll,> NamedLookup for Rc<Coll,>
+  where Coll: NamedLookup, {
+  #[inline]
+  fn find_card_by_name(&self, list_head: &DocumentId, name: &str,) -> impl Future<Output = Result<Option<(DocumentId, Card,)>, LookupError<Self::Error,>>> {
+    Coll::find_card_by_name(&**self, list_head, name,)
+  }
+  #[inline]
+  fn find_tier_by_name(&self, list_head: &DocumentId, name: &str,) -> impl Future<Output = Result<Option<TierMeta>, LookupError<Self::Error,>>> {
+    Coll::find_tier_by_name(&**self, list_head, name,)
+  }
+}
+
+impl<Coll,> NamedLookup for Arc<Coll,>
+  where Coll: NamedLookup, {
+  #[inline]
+  fn find_card_by_name(&self, list_head: &DocumentId, name: &str,) -> impl Future<Output = Result<Option<(DocumentId, Card,)>, LookupError<Self::Error,>>> {
+    Coll::find_card_by_name(&**self, list_head, name,)
+  }
+  #[inline]
+  fn find_tier_by_name(&self, list_head: &DocumentId, name: &str,) -> impl Future<Output = Result<Option<TierMeta>, LookupError<Self::Error,>>> {
+    Coll::find_tier_by_name(&**self, list_head, name,)
+  }
+}
+
+impl<Coll,> NamedLookup for Box<Coll,>
+  where Coll: NamedLookup, {
+  #[inline]
+  fn find_card_by_name(&self, list_head: &DocumentId, name: &str,) -> impl Future<Output = Result<Option<(DocumentId, Card,)>, LookupError<Self::Error,>>> {
+    Coll::find_card_by_name(&**self, list_head, name,)
+  }
+  #[inline]
+  fn find_tier_by_name(&self, list_head: &DocumentId, name: &str,) -> impl Future<Output = Result<Option<TierMeta>, LookupError<Self::Error,>>> {
+    Coll::find_tier_by_name(&**self, list_head, name,)
+  }
+}
+
+/// Returns `true` if following the previous tiers of `tier` through scanned documents
+/// reaches the first tier of a list.
+/// 
+/// # Params
+/// 
+/// documents --- The scanned documents.  
+/// tier --- The tier to start from.  
+/// list_head --- The identifier of the first tier of the list.  
+fn in_list<'a,>(documents: &'a HashMap<DocumentId, TierDocument,>, mut tier: &'a TierMeta, list_head: &DocumentId,) -> bool {
+  let mut visited = HashSet::new();
+
+  while *tier.id != *list_head {
+    if !visited.insert(*tier.id,) { return false }
+
+    tier = match tier.previous_tier.as_deref().and_then(|id,| documents.get(id,),) {
+      Some(TierDocument::TierMeta(previous)) => previous,
+      _ => return false,
+    };
+  }
+
+  true
+}
+
+/// Walks the tiers of a tier list looking for the first tier with a name.
+async fn walk_tier_by_name<Coll,>(collection: &Coll, list_head: &DocumentId, name: &str,) -> Result<Option<TierMeta>, LookupError<Coll::Error,>>
+  where Coll: TierListCollection<Document = TierDocument>, {
+  let name = name.to_lowercase();
+  let mut visited = HashSet::new();
+  let mut next = Some(*list_head,);
+
+  while let Some(id) = next {
+    if !visited.insert(id,) { return Err(LookupError::Cycle(id,)) }
+
+    let tier = collection.get_item_flat::<TierMeta>(&id,).await?;
+    if tier.name.as_str().to_lowercase() == name { return Ok(Some(tier,)) }
+
+    next = tier.next_tier.as_deref().copied();
+  }
+
+  Ok(None)
+}
+
+/// Walks a tier list looking for the first card with a name.
+async fn walk_card_by_name<Coll,>(collection: &Coll, list_head: &DocumentId, name: &str,) -> Result<Option<(DocumentId, Card,)>, LookupError<Coll::Error,>>
+  where Coll: TierListCollection<Document = TierDocument>, {
+  let name = name.to_lowercase();
+
+  walk_cards(collection, list_head, |card,| card.name.to_lowercase() == name,).await
+}
+
+/// Walks the cards of a tier list in order until `visit` returns `true` and returns the
+/// identifier of the tier owning that card and the card.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the list.  
+/// list_head --- The identifier of the first tier of the list.  
+/// visit --- Called with each card in list order.  
+pub(crate) async fn walk_cards<Coll, F,>(collection: &Coll, list_head: &DocumentId, mut visit: F,) -> Result<Option<(DocumentId, Card,)>, LookupError<Coll::Error,>>
+  where Coll: TierListCollection<Document = TierDocument>,
+    F: FnMut(&Card,) -> bool, {
+  let mut visited = HashSet::new();
+  let mut tier = collection.get_item_flat::<TierMeta>(list_head,).await?;
+
+  visited.insert(*list_head,);
+  loop {
+    //A tier with a nil front is empty.
+    let front = Some(**tier.list_front(),).filter(|id,| !id.is_nil(),);
+    let ids = front.iter().chain(tier.next_tier.as_deref(),).collect::<Vec<_>>();
+    let mut documents = if ids.is_empty() { Vec::new() }
+      else { collection.get_documents(ids,).await.map_err(LookupError::Fetch,)? }
+      .into_iter();
+    let mut card = match front {
+      Some(_) => match documents.next() {
+        //A tier of unknown length whose front is not stored is empty.
+        Some(Err(e)) if tier.list_len().is_none() && e.is_not_found() => None,
+        doc => doc.map(|doc,| Card::try_from(doc.map_err(LookupError::Fetch,)?,).map_err(LookupError::from,),).transpose()?,
+      },
+      None => None,
+    };
+    let next_tier = documents.next().map(|doc,| TierMeta::try_from(doc.map_err(LookupError::Fetch,)?,).map_err(LookupError::from,),).transpose()?;
+
+    while let Some(current) = card {
+      if !visited.insert(*current.id,) { return Err(LookupError::Cycle(*current.id,)) }
+      if visit(&current,) { return Ok(Some((*tier.id, current,),)) }
+
+      card = match current.next_card {
+        Some(id) => Some(collection.get_item_flat::<Card>(&id,).await?,),
+        None => None,
+      };
+    }
+
+    match next_tier {
+      Some(next) => {
+        if !visited.insert(*next.id,) { return Err(LookupError::Cycle(*next.id,)) }
+
+        tier = next;
+      },
+      None => return Ok(None),
+    }
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{CardStatus, TierId, TierPolicy, TierName, TierListSnapshot, TierSnapshot, CardSnapshot, ImportOptions, MemoryCollection, RecordingCollection, Op, import_list, mock::ChaosCollection,};
+  use futures::executor::block_on;
+
+  fn snapshot() -> TierListSnapshot {
+    let name = |name: &str,| TierName::new(name,).expect("Error naming tier");
+    let card = |id: u8, name: &str,| CardSnapshot {
+      id: DocumentId::from_bytes([id; 20]),
+      name: name.to_owned(),
+      description: String::new(),
+      up_votes: 0,
+      down_votes: 0,
+      bias: 0,
+      status: CardStatus::Open,
+      history: Vec::new(),
+    };
+
+    TierListSnapshot {
+      tiers: vec![
+        TierSnapshot { id: DocumentId::from_bytes([1; 20]), cards: vec![card(10, "Gold",), card(11, "Silver",),], promote_threshold: None, policy: TierPolicy::OPEN, name: name("S",), },
+        TierSnapshot { id: DocumentId::from_bytes([2; 20]), cards: vec![], promote_threshold: None, policy: TierPolicy::OPEN, name: name("A",), },
+        TierSnapshot { id: DocumentId::from_bytes([3; 20]), cards: vec![card(12, "Bronze",), card(13, "silver",),], promote_threshold: None, policy: TierPolicy::OPEN, name: name("a",), },
+      ],
+    }
+  }
+
+  /// Returns a list of one tier named `B` holding a card named `Copper`.
+  fn other() -> TierListSnapshot {
+    let card = CardSnapshot {
+      id: DocumentId::from_bytes([30; 20]),
+      name: "Copper".to_owned(),
+      description: String::new(),
+      up_votes: 0,
+      down_votes: 0,
+      bias: 0,
+      status: CardStatus::Open,
+      history: Vec::new(),
+    };
+
+    TierListSnapshot {
+      tiers: vec![TierSnapshot { id: DocumentId::from_bytes([21; 20]), cards: vec![card,], promote_threshold: None, policy: TierPolicy::OPEN, name: TierName::new("B",).expect("Error naming tier"), },],
+    }
+  }
+
+  #[test]
+  fn test_find_card_by_name() {
+    let coll = RecordingCollection::new(ChaosCollection::new(),);
+    let preserve = ImportOptions { preserve_ids: true, ..ImportOptions::default() };
+
+    block_on(async {
+      import_list(&coll, &snapshot(), preserve,).await
+        .expect("Error importing list");
+      coll.take_log();
+
+      let found = coll.find_card_by_name(&DocumentId::from_bytes([1; 20]), "bronze",).await
+        .expect("Error finding card")
+        .expect("Error missing card");
+      assert_eq!((found.0, *found.1.id,), (DocumentId::from_bytes([3; 20]), DocumentId::from_bytes([12; 20]),), "Error finding card",);
+      //The front card of each tier is fetched in a batch with the next tier.
+      crate::assert_ops!(
+        coll,
+        [
+          Op::Get(DocumentId::from_bytes([1; 20]),),
+          Op::GetBatch(vec![DocumentId::from_bytes([10; 20]), DocumentId::from_bytes([2; 20]),]),
+          Op::Get(DocumentId::from_bytes([11; 20]),),
+          Op::GetBatch(vec![DocumentId::from_bytes([3; 20]),]),
+          Op::GetBatch(vec![DocumentId::from_bytes([12; 20]),]),
+        ],
+        "Error walking the list",
+      );
+      assert_eq!(coll.find_card_by_name(&DocumentId::from_bytes([1; 20]), "Platinum",).await, Ok(None), "Error finding missing card",);
+
+      //The first card in list order wins.
+      let found = coll.find_card_by_name(&DocumentId::from_bytes([1; 20]), "SILVER",).await
+        .expect("Error finding card")
+        .expect("Error missing card");
+      assert_eq!((found.0, *found.1.id,), (DocumentId::from_bytes([1; 20]), DocumentId::from_bytes([11; 20]),), "Error finding first card",);
+
+      //A tier of unknown length is searched.
+      let mut tier = coll.get_item_flat::<TierMeta>(&DocumentId::from_bytes([3; 20]),).await
+        .expect("Error reading tier");
+      tier.set_ends((None, *tier.list_front(), *tier.list_back(),),);
+      coll.write_item(tier,).await
+        .expect("Error writing tier");
+      let found = coll.find_card_by_name(&DocumentId::from_bytes([1; 20]), "bronze",).await
+        .expect("Error finding card")
+        .expect("Error missing card");
+      assert_eq!((found.0, *found.1.id,), (DocumentId::from_bytes([3; 20]), DocumentId::from_bytes([12; 20]),), "Error finding card in a tier of unknown length",);
+    },);
+  }
+  #[test]
+  fn test_find_tier_by_name() {
+    let coll = RecordingCollection::new(ChaosCollection::new(),);
+    let preserve = ImportOptions { preserve_ids: true, ..ImportOptions::default() };
+
+    block_on(async {
+      import_list(&coll, &snapshot(), preserve,).await
+        .expect("Error importing list");
+      coll.take_log();
+
+      //Only the tiers are read and the first tier in list order wins.
+      let found = coll.find_tier_by_name(&DocumentId::from_bytes([1; 20]), "a",).await
+        .expect("Error finding tier")
+        .map(|tier,| *tier.id,);
+      assert_eq!(found, Some(DocumentId::from_bytes([2; 20]),), "Error finding first tier",);
+      crate::assert_ops!(coll, [Op::Get(DocumentId::from_bytes([1; 20]),), Op::Get(DocumentId::from_bytes([2; 20]),),], "Error reading cards",);
+      assert_eq!(coll.find_tier_by_name(&DocumentId::from_bytes([1; 20]), "B",).await, Ok(None), "Error finding missing tier",);
+    },);
+  }
+  #[test]
+  fn test_scan_tier_by_name() {
+    let coll = MemoryCollection::new();
+    let preserve = ImportOptions { preserve_ids: true, ..ImportOptions::default() };
+
+    block_on(async {
+      import_list(&coll, &snapshot(), preserve,).await
+        .expect("Error importing list");
+
+      let found = coll.find_tier_by_name(&DocumentId::from_bytes([1; 20]), "A",).await
+        .expect("Error finding tier")
+        .map(|tier,| *tier.id,);
+      assert_eq!(found, Some(DocumentId::from_bytes([2; 20]),), "Error finding first tier",);
+
+      //Break the list between the first and second tiers.
+      let mut tier = coll.get_item_flat::<TierMeta>(&DocumentId::from_bytes([1; 20]),).await
+        .expect("Error reading tier");
+      tier.next_tier = Some(TierId::from_bytes([9; 20]),);
+      coll.write_item(tier,).await
+        .expect("Error breaking list");
+
+      let found = coll.find_tier_by_name(&DocumentId::from_bytes([1; 20]), "A",).await
+        .expect("Error finding tier")
+        .map(|tier,| tier.name.as_str().to_lowercase(),);
+      assert_eq!(found.as_deref(), Some("a",), "Error finding tier in broken list",);
+
+      //Tiers of other lists are not found in a broken list.
+      import_list(&coll, &other(), preserve,).await
+        .expect("Error importing other list");
+      assert_eq!(coll.find_tier_by_name(&DocumentId::from_bytes([1; 20]), "B",).await, Ok(None), "Error finding tier of another list",);
+      let found = coll.find_tier_by_name(&DocumentId::from_bytes([21; 20]), "B",).await
+        .expect("Error finding tier")
+        .map(|tier,| *tier.id,);
+      assert_eq!(found, Some(DocumentId::from_bytes([21; 20]),), "Error finding tier of the other list",);
+    },);
+  }
+  #[test]
+  fn test_scan_card_by_name() {
+    let coll = MemoryCollection::new();
+    let preserve = ImportOptions { preserve_ids: true, ..ImportOptions::default() };
+
+    block_on(async {
+      import_list(&coll, &snapshot(), preserve,).await
+        .expect("Error importing list");
+
+      let found = coll.find_card_by_name(&DocumentId::from_bytes([1; 20]), "silver",).await
+        .expect("Error finding card")
+        .expect("Error missing card");
+      assert_eq!((found.0, *found.1.id,), (DocumentId::from_bytes([1; 20]), DocumentId::from_bytes([11; 20]),), "Error finding first card",);
+      assert_eq!(coll.find_card_by_name(&DocumentId::from_bytes([1; 20]), "Platinum",).await, Ok(None), "Error finding missing card",);
+
+      //Break the list between the first and second tiers.
+      let tier = coll.get_item_flat::<TierMeta>(&DocumentId::from_bytes([1; 20]),).await
+        .expect("Error reading tier");
+      coll.write_item(TierMeta::new(tier.id, (tier.list_len(), *tier.list_front(), *tier.list_back(),), None, Some(TierId::from_bytes([9; 20]),),),).await
+        .expect("Error breaking list");
+
+      let found = coll.find_card_by_name(&DocumentId::from_bytes([1; 20]), "bronze",).await
+        .expect("Error finding card")
+        .expect("Error missing card");
+      assert_eq!((found.0, *found.1.id,), (DocumentId::from_bytes([3; 20]), DocumentId::from_bytes([12; 20]),), "Error finding card in broken list",);
+
+      //Cards of other lists are not found in a broken list.
+      import_list(&coll, &other(), preserve,).await
+        .expect("Error importing other list");
+      assert_eq!(coll.find_card_by_name(&DocumentId::from_bytes([1; 20]), "copper",).await, Ok(None), "Error finding card of another list",);
+      let found = coll.find_card_by_name(&DocumentId::from_bytes([21; 20]), "copper",).await
+        .expect("Error finding card")
+        .expect("Error missing card");
+      assert_eq!((found.0, *found.1.id,), (DocumentId::from_bytes([21; 20]), DocumentId::from_bytes([30; 20]),), "Error finding card of the other list",);
+    },);
+  }
+}
@@ -0,0 +1,1087 @@
+//! Defines an in memory document collection.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{
+  DocumentId, Document, CollectionError, ErrorKind, TierListCollection, TierListCollectionScan, TierMeta,
+  TransactionalCollection, Transaction, TierDocument, WatchableCollection, ChangeEvent, ChangeKind,
+  RevisionedCollection, SnapshotCollection, BatchReport, NamedLookup, LookupError, Card, HealthCheck, HealthReport, watch::Watchers,
+  AtomicCounters, Counters, CounterField, counters::add_delta,
+};
+use futures::{Future, TryFutureExt, future::{self, LocalBoxFuture,}, stream, channel::mpsc::UnboundedReceiver,};
+use std::{
+  fmt,
+  error::Error,
+  collections::HashMap,
+  cell::RefCell,
+  borrow::Borrow,
+  rc::Rc,
+  task::Poll,
+  time::Instant,
+};
+
+/// A `TierListCollection` which stores its documents in memory.
+/// 
+/// Cloning a `MemoryCollection` produces a new handle to the same documents.
+/// 
+/// By default the collection stores `TierDocument`s so that every document making up a
+/// tier list can be stored together.
+/// 
+/// Every change made through any handle is reported to the watchers of the collection.
+pub struct MemoryCollection<D = TierDocument,> {
+  /// The documents in the collection.
+  documents: Rc<RefCell<HashMap<DocumentId, D>>>,
+  /// The revisions and watchers of the documents.
+  shared: Rc<RefCell<Shared<D>>>,
+}
+
+impl<D,> MemoryCollection<D,> {
+  /// Returns a new empty `MemoryCollection`.
+  #[inline]
+  pub fn new() -> Self {
+    Self {
+      documents: Rc::new(RefCell::new(HashMap::new(),),),
+      shared: Rc::new(RefCell::new(Shared::new(),),),
+    }
+  }
+  /// Returns the number of documents in the collection.
+  #[inline]
+  pub fn len(&self,) -> usize { RefCell::borrow(&self.documents,).len() }
+  /// Returns `true` if there are no documents in the collection.
+  #[inline]
+  pub fn is_empty(&self,) -> bool { RefCell::borrow(&self.documents,).is_empty() }
+}
+
+impl<D,> Default for MemoryCollection<D,> {
+  #[inline]
+  fn default() -> Self { Self::new() }
+}
+
+impl<D,> Clone for MemoryCollection<D,> {
+  #[inline]
+  fn clone(&self,) -> Self {
+    Self { documents: self.documents.clone(), shared: self.shared.clone(), }
+  }
+}
+
+/// Two `MemoryCollection`s are equal if they are handles to the same documents.
+impl<D,> PartialEq for MemoryCollection<D,> {
+  #[inline]
+  fn eq(&self, rhs: &Self,) -> bool { Rc::ptr_eq(&self.documents, &rhs.documents,) }
+}
+
+impl<D,> Eq for MemoryCollection<D,> {}
+
+impl<D,> fmt::Debug for MemoryCollection<D,>
+  where D: fmt::Debug, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    fmt.debug_struct("MemoryCollection",)
+    .field("documents", &self.documents,)
+    .finish()
+  }
+}
+
+impl<D,> TierListCollection for MemoryCollection<D,>
+  where D: 'static + Document + Clone, {
+  type Document = D;
+  type Error = MemoryError;
+  type GetBatchDocuments = LocalBoxFuture<'static, Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>>;
+  type GetDocument = LocalBoxFuture<'static, Result<Self::Document, Self::Error>>;
+  type WriteBatchDocuments = LocalBoxFuture<'static, Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>;
+  type WriteDocument = LocalBoxFuture<'static, Result<(), Self::Error>>;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    let coll = self.clone();
+    let ids = ids.into_iter()
+      .map(|id,| *id.borrow(),)
+      .collect::<Vec<_>>();
+
+    Box::pin(async move {
+      Ok(future::join_all(
+        ids.into_iter().map(|id,| coll.get_document(&id,),),
+      ).await)
+    },)
+  }
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    let documents = self.documents.clone();
+    let id = *id;
+
+    Box::pin(future::poll_fn(move |ctx,| {
+      match documents.try_borrow() {
+        Ok(documents) => Poll::Ready(
+          documents.get(&id,).cloned()
+          .ok_or(MemoryError::NotFound(id),)
+        ),
+        //The documents are being written; try again later.
+        Err(_) => { ctx.waker().wake_by_ref(); Poll::Pending },
+      }
+    },),)
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let coll = self.clone();
+    let docs = documents.into_iter()
+      .map(|doc,| doc.borrow().clone(),)
+      .collect::<Vec<_>>();
+
+    Box::pin(async move {
+      let mut all_succeeded = true;
+      let mut results = Vec::with_capacity(docs.len(),);
+
+      for doc in docs {
+        let res = coll.write_document(&doc,).await;
+
+        all_succeeded = all_succeeded && res.is_ok();
+        results.push(res,);
+      }
+
+      if all_succeeded { Ok(Ok(())) }
+      else { Ok(Err(results)) }
+    },)
+  }
+  fn write_documents_reported<I, T,>(&self, documents: I,) -> impl Future<Output = Result<BatchReport<Self::Error,>, Self::Error>>
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let coll = self.clone();
+    let docs = documents.into_iter()
+      .map(|doc,| doc.borrow().clone(),)
+      .collect::<Vec<_>>();
+
+    async move {
+      let mut results = Vec::with_capacity(docs.len(),);
+
+      for doc in docs {
+        let res = coll.write_document(&doc,).await;
+
+        results.push((*doc.get_id(), res,),);
+      }
+
+      Ok(results.into_iter().collect())
+    }
+  }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    let documents = self.documents.clone();
+    let shared = self.shared.clone();
+    let mut document = Some(document.borrow().clone(),);
+
+    Box::pin(future::poll_fn(move |ctx,| {
+      match documents.try_borrow_mut() {
+        Ok(mut documents) => {
+          let document = document.take()
+            .expect("`write_document` polled after completion");
+
+          shared.borrow_mut().insert(&mut documents, document,);
+
+          Poll::Ready(Ok(()))
+        },
+        //The documents are being read; try again later.
+        Err(_) => { ctx.waker().wake_by_ref(); Poll::Pending },
+      }
+    },),)
+  }
+  fn exists(&self, id: &DocumentId,) -> impl Future<Output = Result<bool, Self::Error>> {
+    let documents = self.documents.clone();
+    let id = *id;
+
+    future::poll_fn(move |ctx,| {
+      match documents.try_borrow() {
+        Ok(documents) => Poll::Ready(Ok(documents.contains_key(&id,))),
+        //The documents are being written; try again later.
+        Err(_) => { ctx.waker().wake_by_ref(); Poll::Pending },
+      }
+    },)
+  }
+  fn write_document_if_absent<T,>(&self, document: &T,) -> impl Future<Output = Result<bool, Self::Error>>
+    where T: Borrow<Self::Document>, {
+    let documents = self.documents.clone();
+    let shared = self.shared.clone();
+    let mut document = Some(document.borrow().clone(),);
+
+    future::poll_fn(move |ctx,| {
+      match documents.try_borrow_mut() {
+        Ok(mut documents) => {
+          let document = document.take()
+            .expect("`write_document_if_absent` polled after completion");
+
+          if documents.contains_key(document.get_id(),) { return Poll::Ready(Ok(false)) }
+
+          shared.borrow_mut().insert(&mut documents, document,);
+
+          Poll::Ready(Ok(true))
+        },
+        //The documents are being read; try again later.
+        Err(_) => { ctx.waker().wake_by_ref(); Poll::Pending },
+      }
+    },)
+  }
+}
+
+impl<D,> TierListCollectionScan for MemoryCollection<D,>
+  where D: 'static + Document + Clone, {
+  type Scan = stream::Iter<std::vec::IntoIter<Result<Self::Document, Self::Error>>>;
+
+  fn scan(&self,) -> Self::Scan {
+    let documents = RefCell::borrow(&self.documents,).values()
+      .cloned()
+      .map(Ok,)
+      .collect::<Vec<_>>();
+
+    stream::iter(documents,)
+  }
+  #[inline]
+  fn count(&self,) -> impl Future<Output = Result<u64, Self::Error>> {
+    future::ready(Ok(self.len() as u64),)
+  }
+  /// Copies the documents immediately rather than when the future is polled.
+  #[inline]
+  fn snapshot(&self,) -> impl Future<Output = Result<SnapshotCollection<Self::Document,>, Self::Error>> {
+    future::ready(Ok(SnapshotCollection::new(RefCell::borrow(&self.documents,).clone(),)),)
+  }
+}
+
+impl<D,> TransactionalCollection for MemoryCollection<D,>
+  where D: 'static + Document + Clone, {
+  type Transaction = MemoryTransaction<D,>;
+
+  #[inline]
+  fn begin(&self,) -> Self::Transaction {
+    MemoryTransaction {
+      documents: self.documents.clone(),
+      shared: self.shared.clone(),
+      operations: Vec::new(),
+    }
+  }
+}
+
+impl<D,> WatchableCollection for MemoryCollection<D,>
+  where D: 'static + Document + Clone, {
+  type Watch = UnboundedReceiver<ChangeEvent<D>>;
+
+  #[inline]
+  fn watch(&self, ids: &[&DocumentId],) -> Self::Watch { self.shared.borrow_mut().watchers.subscribe(ids,) }
+}
+
+/// Revisions count the writes to each document starting from `1`.
+impl<D,> RevisionedCollection for MemoryCollection<D,>
+  where D: 'static + Document + Clone, {
+  type Revision = u64;
+  type GetDocumentRev = LocalBoxFuture<'static, Result<(Self::Document, Self::Revision,), Self::Error>>;
+  type WriteDocumentRev = LocalBoxFuture<'static, Result<Self::Revision, Self::Error>>;
+
+  fn get_document_rev(&self, id: &DocumentId,) -> Self::GetDocumentRev {
+    let documents = self.documents.clone();
+    let shared = self.shared.clone();
+    let id = *id;
+
+    Box::pin(future::poll_fn(move |ctx,| {
+      match documents.try_borrow() {
+        Ok(documents) => Poll::Ready(
+          documents.get(&id,).cloned()
+          .map(|document,| (document, RefCell::borrow(&shared,).revisions[&id],),)
+          .ok_or(MemoryError::NotFound(id),)
+        ),
+        //The documents are being written; try again later.
+        Err(_) => { ctx.waker().wake_by_ref(); Poll::Pending },
+      }
+    },),)
+  }
+  fn write_document_rev<T,>(&self, document: &T, expected: Option<&Self::Revision>,) -> Self::WriteDocumentRev
+    where T: Borrow<Self::Document>, {
+    let documents = self.documents.clone();
+    let shared = self.shared.clone();
+    let expected = expected.copied();
+    let mut document = Some(document.borrow().clone(),);
+
+    Box::pin(future::poll_fn(move |ctx,| {
+      match documents.try_borrow_mut() {
+        Ok(mut documents) => {
+          let document = document.take()
+            .expect("`write_document_rev` polled after completion");
+          let id = *document.get_id();
+          let mut shared = shared.borrow_mut();
+          let current = if documents.contains_key(&id,) { shared.revisions.get(&id,).copied() } else { None };
+
+          if current != expected { return Poll::Ready(Err(MemoryError::Conflict(id),)) }
+
+          Poll::Ready(Ok(shared.insert(&mut documents, document,)))
+        },
+        //The documents are being read; try again later.
+        Err(_) => { ctx.waker().wake_by_ref(); Poll::Pending },
+      }
+    },),)
+  }
+}
+
+/// Increments advance the revision of the document and are reported to its watchers.
+impl<D,> AtomicCounters for MemoryCollection<D,>
+  where D: 'static + Document + Counters + Clone, {
+  type Increment = LocalBoxFuture<'static, Result<u64, Self::Error>>;
+
+  fn increment_field(&self, id: &DocumentId, field: CounterField, delta: i64,) -> Self::Increment {
+    let documents = self.documents.clone();
+    let shared = self.shared.clone();
+    let id = *id;
+
+    Box::pin(future::poll_fn(move |ctx,| {
+      match documents.try_borrow_mut() {
+        Ok(mut documents) => {
+          let mut document = match documents.get(&id,) {
+            Some(document) => document.clone(),
+            None => return Poll::Ready(Err(MemoryError::NotFound(id),)),
+          };
+          let value = match document.counter_mut(field,) {
+            Some(counter) => { *counter = add_delta(*counter, delta,); *counter },
+            None => return Poll::Ready(Err(MemoryError::NoCounters(id),)),
+          };
+
+          shared.borrow_mut().insert(&mut documents, document,);
+
+          Poll::Ready(Ok(value))
+        },
+        //The documents are being read; try again later.
+        Err(_) => { ctx.waker().wake_by_ref(); Poll::Pending },
+      }
+    },),)
+  }
+}
+
+/// The state of a `MemoryCollection` other than its documents.
+struct Shared<D,> {
+  /// The revision of every document which has been written to the collection.
+  revisions: HashMap<DocumentId, u64>,
+  /// The subscribers to changes of the documents.
+  watchers: Watchers<D>,
+}
+
+impl<D,> Shared<D,> {
+  /// Returns the state of an empty collection.
+  #[inline]
+  fn new() -> Self { Self { revisions: HashMap::new(), watchers: Watchers::new(), } }
+}
+
+impl<D,> Shared<D,>
+  where D: Document + Clone, {
+  /// Stores a document, advancing its revision and notifying the watchers, and returns
+  /// the new revision.
+  fn insert(&mut self, documents: &mut HashMap<DocumentId, D>, document: D,) -> u64 {
+    let id = *document.get_id();
+    let kind = match documents.insert(id, document.clone(),) {
+      Some(_) => ChangeKind::Updated,
+      None => ChangeKind::Created,
+    };
+    let revision = self.revisions.entry(id,).or_insert(0,);
+
+    *revision += 1;
+    let revision = *revision;
+    self.watchers.notify(ChangeEvent { id, kind, document: Some(document), },);
+
+    revision
+  }
+  /// Removes a document and notifies the watchers.
+  /// 
+  /// The revision is kept so that revisions from before the removal stay stale.
+  fn remove(&mut self, documents: &mut HashMap<DocumentId, D>, id: &DocumentId,) {
+    documents.remove(id,);
+    self.watchers.notify(ChangeEvent { id: *id, kind: ChangeKind::Deleted, document: None, },);
+  }
+}
+
+/// A staged change to a `MemoryCollection`.
+#[derive(Clone, Debug,)]
+enum Operation<D,> {
+  /// Write the document.
+  Write(D),
+  /// Delete the document with the identifier.
+  Delete(DocumentId),
+}
+
+/// A `Transaction` against a `MemoryCollection`.
+/// 
+/// Committing the transaction fails with `MemoryError::NotFound` if a document staged
+/// for deletion does not exist at that point in the transaction.
+pub struct MemoryTransaction<D,> {
+  /// The documents in the collection.
+  documents: Rc<RefCell<HashMap<DocumentId, D>>>,
+  /// The revisions and watchers of the documents.
+  shared: Rc<RefCell<Shared<D>>>,
+  /// The staged changes in the order they were staged.
+  operations: Vec<Operation<D>>,
+}
+
+impl<D,> fmt::Debug for MemoryTransaction<D,>
+  where D: fmt::Debug, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    fmt.debug_struct("MemoryTransaction",)
+    .field("operations", &self.operations,)
+    .finish()
+  }
+}
+
+impl<D,> Transaction for MemoryTransaction<D,>
+  where D: 'static + Document + Clone, {
+  type Document = D;
+  type Error = MemoryError;
+  type Commit = LocalBoxFuture<'static, Result<(), Self::Error>>;
+
+  #[inline]
+  fn stage_write<T,>(&mut self, document: &T,)
+    where T: Borrow<Self::Document>, {
+    self.operations.push(Operation::Write(document.borrow().clone(),),)
+  }
+  #[inline]
+  fn stage_delete(&mut self, id: &DocumentId,) {
+    self.operations.push(Operation::Delete(*id),)
+  }
+  fn commit(self,) -> Self::Commit {
+    let Self { documents, shared, operations, } = self;
+    let mut operations = Some(operations,);
+
+    Box::pin(future::poll_fn(move |ctx,| {
+      let mut documents = match documents.try_borrow_mut() {
+        Ok(documents) => documents,
+        //The documents are being read; try again later.
+        Err(_) => { ctx.waker().wake_by_ref(); return Poll::Pending },
+      };
+      let operations = operations.take()
+        .expect("`commit` polled after completion");
+      //The state of every document touched by the transaction.
+      let mut changes = HashMap::with_capacity(operations.len(),);
+
+      //Check every operation succeeds before changing the collection.
+      for operation in operations {
+        match operation {
+          Operation::Write(document) => { changes.insert(*document.get_id(), Some(document,),); },
+          Operation::Delete(id) => {
+            let exists = match changes.get(&id,) {
+              Some(change) => change.is_some(),
+              None => documents.contains_key(&id,),
+            };
+
+            if !exists { return Poll::Ready(Err(MemoryError::NotFound(id),)) }
+
+            changes.insert(id, None,);
+          },
+        }
+      }
+
+      let mut shared = shared.borrow_mut();
+      for (id, change,) in changes {
+        match change {
+          Some(document) => { shared.insert(&mut documents, document,); },
+          None => shared.remove(&mut documents, &id,),
+        }
+      }
+
+      Poll::Ready(Ok(()))
+    },),)
+  }
+}
+
+/// The collection is healthy once its documents can be read and reports the version of
+/// this crate.
+impl<D,> HealthCheck for MemoryCollection<D,>
+  where D: 'static + Document + Clone, {
+  type Ping = LocalBoxFuture<'static, Result<HealthReport, Self::Error>>;
+
+  fn ping(&self,) -> Self::Ping {
+    let documents = self.documents.clone();
+    let start = Instant::now();
+
+    Box::pin(future::poll_fn(move |ctx,| {
+      match documents.try_borrow() {
+        Ok(_) => Poll::Ready(Ok(HealthReport {
+          latency: start.elapsed(),
+          version: Some(env!("CARGO_PKG_VERSION").to_owned(),),
+        })),
+        //The documents are being written; try again later.
+        Err(_) => { ctx.waker().wake_by_ref(); Poll::Pending },
+      }
+    },),)
+  }
+}
+
+/// Cards and tiers are found by scanning so that broken lists can still be searched.
+impl NamedLookup for MemoryCollection<TierDocument,> {
+  #[inline]
+  fn find_card_by_name(&self, list_head: &DocumentId, name: &str,) -> impl Future<Output = Result<Option<(DocumentId, Card,)>, LookupError<Self::Error,>>> {
+    self.scan_card_by_name(list_head, name,).map_err(LookupError::Fetch,)
+  }
+  #[inline]
+  fn find_tier_by_name(&self, list_head: &DocumentId, name: &str,) -> impl Future<Output = Result<Option<TierMeta>, LookupError<Self::Error,>>> {
+    self.scan_tier_by_name(list_head, name,).map_err(LookupError::Fetch,)
+  }
+}
+
+/// The errors returned by a `MemoryCollection`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum MemoryError {
+  /// There is no document with the identifier in the collection.
+  NotFound(DocumentId),
+  /// The document did not have the expected revision.
+  Conflict(DocumentId),
+  /// The document has no counters to increment.
+  NoCounters(DocumentId),
+}
+
+impl fmt::Display for MemoryError {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      MemoryError::NotFound(id) => write!(fmt, "no document with the id {:?}", id,),
+      MemoryError::Conflict(id) => write!(fmt, "the document with the id {:?} has changed", id,),
+      MemoryError::NoCounters(id) => write!(fmt, "the document with the id {:?} has no counters", id,),
+    }
+  }
+}
+
+impl Error for MemoryError {}
+
+impl CollectionError for MemoryError {
+  #[inline]
+  fn kind(&self,) -> ErrorKind {
+    match self {
+      MemoryError::NotFound(_) => ErrorKind::NotFound,
+      MemoryError::Conflict(_) => ErrorKind::Conflict,
+      MemoryError::NoCounters(_) => ErrorKind::Permanent,
+    }
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{CardId, CardStatus, TierId, TierMeta, Upsert,};
+  use futures::{executor::block_on, future, FutureExt, TryStreamExt,};
+
+  fn tier(id: DocumentId,) -> TierMeta { TierMeta::new(id.into(), (None, id.into(), id.into(),), None, None,) }
+
+  #[test]
+  fn test_scan() {
+    let coll = MemoryCollection::new();
+    let mut docs = (1..=5u8).map(|i,| tier(DocumentId::from_bytes([i; 20]),),).collect::<Vec<_>>();
+
+    block_on(async {
+      //Write a document twice to check it is only scanned once.
+      coll.write_document(&docs[0],).await
+        .expect("Error writing document");
+      coll.write_documents(&docs,).await
+        .expect("Error writing documents")
+        .expect("Error writing documents");
+
+      let mut scanned = coll.scan().try_collect::<Vec<_>>().await
+        .expect("Error scanning the collection");
+      scanned.sort_by_key(|doc,| doc.id,);
+      docs.sort_by_key(|doc,| doc.id,);
+
+      assert_eq!(scanned, docs, "Error scanned the wrong documents",);
+    },);
+  }
+  #[test]
+  fn test_ping() {
+    let coll = MemoryCollection::<TierMeta,>::new();
+    let report = block_on(coll.ping(),)
+      .expect("Error pinging collection");
+
+    assert_eq!(report.version.as_deref(), Some(env!("CARGO_PKG_VERSION"),), "Error reporting version",);
+    assert!(report.latency < std::time::Duration::from_secs(1,), "Error measuring latency",);
+  }
+  #[test]
+  fn test_count() {
+    let coll = MemoryCollection::new();
+
+    block_on(async {
+      assert_eq!(coll.count().await, Ok(0), "Error counting an empty collection",);
+
+      for i in 1..=3u8 {
+        coll.write_document(&tier(DocumentId::from_bytes([i; 20]),),).await
+          .expect("Error writing document");
+      }
+
+      assert_eq!(coll.count().await, Ok(3), "Error counting the collection",);
+      //The default implementation should agree with the override.
+      assert_eq!(
+        coll.scan().try_fold(0, |count, _,| future::ready(Ok(count + 1),),).await,
+        Ok(3),
+        "Error scanning the collection",
+      );
+    },);
+  }
+  #[test]
+  fn test_exists() {
+    let coll = MemoryCollection::new();
+    let id = DocumentId::from_bytes([1; 20]);
+
+    block_on(async {
+      assert_eq!(coll.exists(&id,).await, Ok(false), "Error document exists before writing",);
+
+      coll.write_document(&tier(id,),).await
+        .expect("Error writing document");
+
+      assert_eq!(coll.exists(&id,).await, Ok(true), "Error document missing after writing",);
+      assert_eq!(coll.exists(&DocumentId::from_bytes([2; 20]),).await, Ok(false), "Error unwritten document exists",);
+    },);
+  }
+  #[test]
+  fn test_get_or_create() {
+    let coll = MemoryCollection::new();
+    let id = DocumentId::from_bytes([1; 20]);
+
+    block_on(async {
+      assert_eq!(
+        coll.get_or_create(&id, || tier(id,),).await,
+        Ok((tier(id,), true,)),
+        "Error creating missing document",
+      );
+      assert_eq!(coll.len(), 1, "Error storing created document",);
+      assert_eq!(
+        coll.get_or_create(&id, || panic!("Error building existing document"),).await,
+        Ok((tier(id,), false,)),
+        "Error getting existing document",
+      );
+    },);
+  }
+  #[test]
+  fn test_get_or_create_raced() {
+    let coll = MemoryCollection::new();
+    let other = coll.clone();
+    let id = DocumentId::from_bytes([1; 20]);
+    let theirs = TierMeta::new(id.into(), (None, id.into(), id.into(),), None, Some(TierId::from_bytes([2; 20])),);
+
+    block_on(async {
+      //Another writer creates the document between the read and the write.
+      let res = coll.get_or_create(&id, || {
+        other.write_document(&theirs,).now_or_never()
+          .expect("Error raced write did not complete")
+          .expect("Error writing raced document");
+
+        tier(id,)
+      },).await;
+
+      assert_eq!(res, Ok((theirs, false,)), "Error overwrote raced document",);
+      assert_eq!(coll.get_document(&id,).await, Ok(theirs), "Error raced document changed",);
+      assert_eq!(coll.write_document_if_absent(&tier(id,),).await, Ok(false), "Error wrote existing document",);
+    },);
+  }
+  fn card(id: DocumentId, up_votes: u64,) -> Card {
+    Card {
+      id: id.into(),
+      name: "card".to_owned(),
+      description: String::new(),
+      up_votes,
+      down_votes: 0,
+      bias: 0,
+      status: CardStatus::Open,
+      history: Vec::new(),
+      previous_card: None,
+      next_card: None,
+    }
+  }
+  fn add_votes(existing: Card, incoming: Card,) -> Card {
+    Card { up_votes: existing.up_votes + incoming.up_votes, ..incoming }
+  }
+  #[test]
+  fn test_upsert_with() {
+    let coll = MemoryCollection::<Card,>::new();
+    let id = DocumentId::from_bytes([1; 20]);
+
+    block_on(async {
+      assert_eq!(
+        coll.upsert_with(card(id, 2,), add_votes,).await,
+        Ok((card(id, 2,), Upsert::Inserted,)),
+        "Error inserting missing card",
+      );
+      assert_eq!(
+        coll.upsert_with(card(id, 3,), add_votes,).await,
+        Ok((card(id, 5,), Upsert::Merged,)),
+        "Error merging existing card",
+      );
+      assert_eq!(
+        coll.upsert_with(card(id, 1,), |_, incoming,| incoming,).await,
+        Ok((card(id, 1,), Upsert::Overwrote,)),
+        "Error overwriting existing card",
+      );
+      assert_eq!(coll.get_document(&id,).await, Ok(card(id, 1,)), "Error reading upserted card",);
+
+      //The revisioned upsert reports the same outcomes.
+      let id = DocumentId::from_bytes([2; 20]);
+
+      assert_eq!(
+        coll.upsert_with_rev(card(id, 2,), 1, add_votes,).await,
+        Ok((card(id, 2,), Upsert::Inserted,)),
+        "Error inserting missing card with revisions",
+      );
+      assert_eq!(
+        coll.upsert_with_rev(card(id, 3,), 1, add_votes,).await,
+        Ok((card(id, 5,), Upsert::Merged,)),
+        "Error merging existing card with revisions",
+      );
+      assert_eq!(
+        coll.upsert_with_rev(card(id, 1,), 1, |_, incoming,| incoming,).await,
+        Ok((card(id, 1,), Upsert::Overwrote,)),
+        "Error overwriting existing card with revisions",
+      );
+    },);
+  }
+  #[test]
+  fn test_upsert_with_raced() {
+    let inner = MemoryCollection::<Card,>::new();
+    let coll = crate::mock::SlowReadCollection::new(inner.clone(),);
+    let id = DocumentId::from_bytes([1; 20]);
+
+    //The card is written after the upsert finds it missing but before it inserts.
+    let (res, written,) = block_on(future::join(
+      coll.upsert_with(card(id, 2,), add_votes,),
+      inner.write_document(&card(id, 10,),),
+    ),);
+
+    assert_eq!(written, Ok(()), "Error writing raced card",);
+    assert_eq!(res, Ok((card(id, 12,), Upsert::Merged,)), "Error merging raced card",);
+    assert_eq!(block_on(inner.get_document(&id,),), Ok(card(id, 12,)), "Error reading merged card",);
+  }
+  #[test]
+  fn test_upsert_with_rev_raced() {
+    let coll = MemoryCollection::<Card,>::new();
+    let other = coll.clone();
+    let id = DocumentId::from_bytes([1; 20]);
+
+    block_on(async {
+      coll.write_document(&card(id, 1,),).await
+        .expect("Error writing card");
+
+      //The first merge races with another write and is run again on the new card.
+      let mut merged = Vec::new();
+      let res = coll.upsert_with_rev(card(id, 2,), 2, |existing, incoming,| {
+        if merged.is_empty() {
+          other.write_document(&card(id, 10,),).now_or_never()
+            .expect("Error raced write did not complete")
+            .expect("Error writing raced card");
+        }
+
+        merged.push(existing.up_votes,);
+        add_votes(existing, incoming,)
+      },).await;
+
+      assert_eq!(res, Ok((card(id, 12,), Upsert::Merged,)), "Error merging raced card",);
+      assert_eq!(merged, vec![1, 10,], "Error merge did not run against the new card",);
+      assert_eq!(coll.get_document(&id,).await, Ok(card(id, 12,)), "Error reading merged card",);
+    },);
+  }
+  #[test]
+  fn test_increment_field() {
+    let coll = MemoryCollection::<TierDocument,>::new();
+    let id = DocumentId::from_bytes([1; 20]);
+    let tier_id = DocumentId::from_bytes([2; 20]);
+
+    block_on(async {
+      coll.write_item(card(id, 0,),).await
+        .expect("Error writing card");
+      coll.write_item(tier(tier_id,),).await
+        .expect("Error writing tier");
+
+      //Every concurrent increment is applied.
+      let increments = future::join_all((0..100).map(|_,| coll.increment_field(&id, CounterField::UpVotes, 1,),),).await;
+      assert!(increments.iter().all(Result::is_ok,), "Error incrementing counter",);
+      assert_eq!(coll.get_item_flat::<Card>(&id,).await.map(|card,| card.up_votes,), Ok(100), "Error lost increments",);
+
+      assert_eq!(coll.increment_field(&id, CounterField::DownVotes, -5,).await, Ok(0), "Error saturating counter",);
+      assert_eq!(coll.increment_field(&id, CounterField::Bias, 7,).await, Ok(7), "Error incrementing bias",);
+      assert_eq!(coll.get_document_rev(&id,).await.map(|(_, revision,),| revision,), Ok(103), "Error advancing revision",);
+      assert_eq!(
+        coll.increment_field(&tier_id, CounterField::UpVotes, 1,).await,
+        Err(MemoryError::NoCounters(tier_id,)),
+        "Error incrementing a tier",
+      );
+      assert_eq!(
+        coll.increment_field(&DocumentId::from_bytes([3; 20]), CounterField::UpVotes, 1,).await,
+        Err(MemoryError::NotFound(DocumentId::from_bytes([3; 20]),)),
+        "Error incrementing a missing document",
+      );
+    },);
+  }
+  #[test]
+  fn test_write_documents_reported() {
+    let coll = MemoryCollection::<TierMeta,>::new();
+    let tiers = (1..=3u8).map(|i,| tier(DocumentId::from_bytes([i; 20]),),).collect::<Vec<_>>();
+
+    let report = block_on(coll.write_documents_reported(&tiers,),)
+      .expect("Error writing documents");
+
+    assert!(report.is_complete_success(), "Error writing documents",);
+    assert_eq!(
+      report.succeeded_ids().copied().collect::<Vec<_>>(),
+      vec![DocumentId::from_bytes([1; 20]), DocumentId::from_bytes([2; 20]), DocumentId::from_bytes([3; 20]),],
+      "Error reporting written documents",
+    );
+    assert_eq!(coll.len(), 3, "Error storing documents",);
+  }
+  #[test]
+  fn test_write_documents_shapes() {
+    let coll = MemoryCollection::<Card,>::new();
+    let cards = (1..=6u8).map(|i,| Card {
+      id: CardId::from_bytes([i; 20]),
+      name: format!("Card {}", i,),
+      description: String::new(),
+      up_votes: 0,
+      down_votes: 0,
+      bias: 0,
+      status: CardStatus::Open,
+      history: Vec::new(),
+      previous_card: None,
+      next_card: None,
+    },).collect::<Vec<_>>();
+
+    block_on(async {
+      coll.write_documents(cards[..2].to_vec(),).await
+        .expect("Error writing an owned `Vec`")
+        .expect("Error writing an owned `Vec`");
+      coll.write_documents(&cards[2..4],).await
+        .expect("Error writing a slice")
+        .expect("Error writing a slice");
+      coll.write_documents(cards[4..].iter().filter(|card,| card.id != DocumentId::from_bytes([5; 20]),),).await
+        .expect("Error writing an iterator")
+        .expect("Error writing an iterator");
+    },);
+
+    assert_eq!(coll.len(), 5, "Error writing documents",);
+    assert!(block_on(coll.exists(&DocumentId::from_bytes([4; 20]),),) == Ok(true), "Error writing a slice",);
+    assert!(block_on(coll.exists(&DocumentId::from_bytes([5; 20]),),) == Ok(false), "Error writing filtered document",);
+  }
+  #[test]
+  fn test_transaction() {
+    let coll = MemoryCollection::new();
+    let (doc1, doc2, doc3,) = (tier(DocumentId::from_bytes([1; 20]),), tier(DocumentId::from_bytes([2; 20]),), tier(DocumentId::from_bytes([3; 20]),),);
+
+    block_on(async {
+      coll.write_document(&doc1,).await
+        .expect("Error writing document");
+
+      let mut transaction = coll.begin();
+      transaction.stage_write(&doc2,);
+      transaction.stage_write(&doc3,);
+      transaction.stage_delete(doc1.get_id(),);
+      //Nothing is visible before the commit.
+      assert_eq!(coll.len(), 1, "Error staged changes are visible",);
+
+      transaction.commit().await
+        .expect("Error committing transaction");
+
+      assert_eq!(coll.exists(doc1.get_id(),).await, Ok(false), "Error deleted document exists",);
+      assert_eq!(coll.get_document(doc2.get_id(),).await, Ok(doc2), "Error reading document",);
+      assert_eq!(coll.get_document(doc3.get_id(),).await, Ok(doc3), "Error reading document",);
+    },);
+  }
+  #[test]
+  fn test_transaction_failure() {
+    let coll = MemoryCollection::new();
+    let (doc1, doc2, doc3,) = (tier(DocumentId::from_bytes([1; 20]),), tier(DocumentId::from_bytes([2; 20]),), tier(DocumentId::from_bytes([3; 20]),),);
+    let missing = DocumentId::from_bytes([4; 20]);
+
+    block_on(async {
+      coll.write_document(&doc1,).await
+        .expect("Error writing document");
+
+      let mut transaction = coll.begin();
+      transaction.stage_write(&doc2,);
+      transaction.stage_delete(doc1.get_id(),);
+      //Deleting a missing document fails part way through the commit.
+      transaction.stage_delete(&missing,);
+      transaction.stage_write(&doc3,);
+
+      assert_eq!(
+        transaction.commit().await,
+        Err(MemoryError::NotFound(missing)),
+        "Error committing failing transaction",
+      );
+      assert_eq!(coll.len(), 1, "Error partial transaction is visible",);
+      assert_eq!(coll.get_document(doc1.get_id(),).await, Ok(doc1), "Error deleted document",);
+    },);
+  }
+  #[test]
+  fn test_transaction_delete_staged() {
+    let coll = MemoryCollection::new();
+    let doc1 = tier(DocumentId::from_bytes([1; 20]),);
+
+    block_on(async {
+      let mut transaction = coll.begin();
+      transaction.stage_write(&doc1,);
+      transaction.stage_delete(doc1.get_id(),);
+      //Deleting the document again fails.
+      transaction.stage_delete(doc1.get_id(),);
+
+      assert_eq!(
+        transaction.commit().await,
+        Err(MemoryError::NotFound(*doc1.id)),
+        "Error deleting a document twice",
+      );
+
+      let mut transaction = coll.begin();
+      transaction.stage_write(&doc1,);
+      transaction.stage_delete(doc1.get_id(),);
+      transaction.commit().await
+        .expect("Error deleting a staged document");
+
+      assert!(coll.is_empty(), "Error staged document was written",);
+    },);
+  }
+  #[test]
+  fn test_get_documents() {
+    use crate::{conformance, mock::ChaosCollection,};
+
+    let docs = (1..=3u8).map(|i,| tier(DocumentId::from_bytes([i; 20]),),).collect::<Vec<_>>();
+
+    block_on(conformance::check_get_documents(&MemoryCollection::new(), &docs, &DocumentId::from_bytes([4; 20]),),);
+    block_on(conformance::check_get_documents(&ChaosCollection::new(), &docs, &DocumentId::from_bytes([4; 20]),),);
+  }
+  #[test]
+  fn test_get_documents_shapes() {
+    let coll = MemoryCollection::new();
+    let tiers = (1..=3u8).map(|i,| tier(DocumentId::from_bytes([i; 20]),),).collect::<Vec<_>>();
+    let ids = tiers.iter().map(|tier,| *tier.id,).collect::<Vec<_>>();
+    let expected = Ok(tiers.iter().copied().map(Ok,).collect::<Vec<_>>());
+
+    block_on(async {
+      coll.write_documents(&tiers,).await
+        .expect("Error writing documents")
+        .expect("Error writing documents");
+
+      assert_eq!(coll.get_documents(&ids[..],).await, expected, "Error getting documents from a slice",);
+      assert_eq!(coll.get_documents(ids.clone(),).await, expected, "Error getting documents from a `Vec`",);
+      assert_eq!(
+        coll.get_documents(tiers.iter().map(|tier,| &*tier.id,),).await,
+        expected,
+        "Error getting documents from an iterator",
+      );
+    },);
+  }
+  #[test]
+  fn test_get_documents_checked() {
+    let coll = MemoryCollection::new();
+    let doc = tier(DocumentId::from_bytes([1; 20]),);
+
+    block_on(async {
+      coll.write_document(&doc,).await
+        .expect("Error writing document");
+
+      assert_eq!(
+        coll.get_documents_checked([&DocumentId::from_bytes([2; 20]), &doc.id, &doc.id,],).await,
+        Ok(vec![
+          (DocumentId::from_bytes([2; 20]), Err(MemoryError::NotFound(DocumentId::from_bytes([2; 20]))),),
+          (*doc.id, Ok(doc),),
+          (*doc.id, Ok(doc),),
+        ]),
+        "Error getting documents",
+      );
+    },);
+  }
+  #[test]
+  fn test_not_found() {
+    let coll = MemoryCollection::<TierMeta>::new();
+
+    assert_eq!(
+      block_on(coll.get_document(&DocumentId::from_bytes([1; 20]),),),
+      Err(MemoryError::NotFound(DocumentId::from_bytes([1; 20]))),
+      "Error getting a missing document",
+    );
+  }
+  #[test]
+  fn test_watch() {
+    let coll = MemoryCollection::new();
+    let watched = tier(DocumentId::from_bytes([1; 20]),);
+    let other = tier(DocumentId::from_bytes([2; 20]),);
+    let mut watch = coll.watch(&[watched.get_id(),],);
+
+    block_on(async {
+      coll.write_document(&watched,).await
+        .expect("Error writing document");
+      coll.write_document(&other,).await
+        .expect("Error writing document");
+      coll.write_document(&watched,).await
+        .expect("Error writing document");
+
+      let mut transaction = coll.begin();
+      transaction.stage_delete(watched.get_id(),);
+      transaction.stage_delete(other.get_id(),);
+      transaction.commit().await
+        .expect("Error deleting documents");
+    },);
+
+    let events = std::iter::from_fn(|| watch.try_recv().ok(),).collect::<Vec<_>>();
+    assert_eq!(
+      events,
+      vec![
+        ChangeEvent { id: *watched.id, kind: ChangeKind::Created, document: Some(watched), },
+        ChangeEvent { id: *watched.id, kind: ChangeKind::Updated, document: Some(watched), },
+        ChangeEvent { id: *watched.id, kind: ChangeKind::Deleted, document: None, },
+      ],
+      "Error watching document",
+    );
+
+    //Writing after the watcher is dropped does not fail.
+    drop(watch,);
+    block_on(coll.write_document(&watched,),).expect("Error writing document");
+  }
+  #[test]
+  fn test_revisions() {
+    let coll = MemoryCollection::new();
+    let doc = tier(DocumentId::from_bytes([1; 20]),);
+    let updated = TierMeta::new(doc.id, (None, (*doc.id).into(), (*doc.id).into(),), None, Some(TierId::from_bytes([2; 20])),);
+
+    block_on(async {
+      //`None` only creates documents.
+      assert_eq!(coll.write_document_rev(&doc, None,).await, Ok(1), "Error creating document",);
+      assert_eq!(
+        coll.write_document_rev(&doc, None,).await,
+        Err(MemoryError::Conflict(*doc.id,)),
+        "Error overwriting document with create only write",
+      );
+
+      let (read, revision,) = coll.get_document_rev(&doc.id,).await
+        .expect("Error reading document");
+      assert_eq!((read, revision,), (doc, 1,), "Error reading revision",);
+
+      //A blind write makes the read revision stale.
+      coll.write_document(&doc,).await
+        .expect("Error writing document");
+      let stale = coll.write_document_rev(&updated, Some(&revision),).await
+        .expect_err("Error writing stale revision");
+      assert_eq!(stale.kind(), ErrorKind::Conflict, "Error classifying stale revision",);
+
+      let (_, revision,) = coll.get_document_rev(&doc.id,).await
+        .expect("Error reading document");
+      assert_eq!(coll.write_document_rev(&updated, Some(&revision),).await, Ok(3), "Error writing fresh revision",);
+      assert_eq!(coll.get_document(&doc.id,).await, Ok(updated), "Error reading written document",);
+    },);
+  }
+  #[test]
+  fn test_update_document() {
+    let coll = MemoryCollection::new();
+    let doc = tier(DocumentId::from_bytes([1; 20]),);
+    let other = coll.clone();
+
+    block_on(async {
+      coll.write_document(&doc,).await
+        .expect("Error writing document");
+
+      //The first attempt races with another write and is retried.
+      let mut raced = false;
+      let updated = coll.update_document(&doc.id, 2, |doc,| {
+        if !raced {
+          raced = true;
+          other.write_document(&doc,).now_or_never()
+            .expect("Error raced write did not complete")
+            .expect("Error writing raced document");
+        }
+
+        TierMeta::new(doc.id, (None, (*doc.id).into(), (*doc.id).into(),), None, Some(TierId::from_bytes([2; 20])),)
+      },).await
+        .expect("Error updating document");
+      assert_eq!(updated.next_tier, Some(TierId::from_bytes([2; 20]),), "Error updating document",);
+      assert_eq!(coll.get_document(&doc.id,).await, Ok(updated), "Error reading updated document",);
+      assert_eq!(coll.get_document_rev(&doc.id,).await.map(|(_, revision,),| revision,), Ok(3), "Error counting revisions",);
+    },);
+  }
+}
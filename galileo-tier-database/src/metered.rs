@@ -0,0 +1,341 @@
+//! Defines a document collection which records metrics about the operations performed
+//! on another collection.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, EncodedLen, TierListCollection,};
+use futures::{FutureExt, future::Inspect,};
+use std::{
+  fmt,
+  time::{Duration, Instant,},
+  cell::RefCell,
+  borrow::Borrow,
+  rc::Rc,
+};
+
+/// The upper bounds of the latency buckets of a `LatencyHistogram`.
+/// 
+/// Operations slower than the last bound are counted in a final overflow bucket.
+pub const LATENCY_BOUNDS: [Duration; 8] = [
+  Duration::from_micros(100,),
+  Duration::from_millis(1,),
+  Duration::from_millis(5,),
+  Duration::from_millis(10,),
+  Duration::from_millis(50,),
+  Duration::from_millis(100,),
+  Duration::from_millis(500,),
+  Duration::from_secs(1,),
+];
+
+/// A count of operations by how long they took to complete.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug,)]
+pub struct LatencyHistogram {
+  /// The number of operations in each bucket of `LATENCY_BOUNDS` followed by the
+  /// number of operations slower than every bound.
+  pub buckets: [u64; LATENCY_BOUNDS.len() + 1],
+}
+
+impl LatencyHistogram {
+  /// Adds an operation to the histogram.
+  /// 
+  /// # Params
+  /// 
+  /// latency --- How long the operation took to complete.  
+  pub fn record(&mut self, latency: Duration,) {
+    let bucket = LATENCY_BOUNDS.iter()
+      .position(|&bound,| latency <= bound,)
+      .unwrap_or(LATENCY_BOUNDS.len(),);
+
+    self.buckets[bucket] += 1;
+  }
+  /// Returns the number of operations in the histogram.
+  #[inline]
+  pub fn total(&self,) -> u64 { self.buckets.iter().sum() }
+}
+
+/// The metrics for one kind of operation.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug,)]
+pub struct OperationMetrics {
+  /// The number of times the operation was started.
+  pub calls: u64,
+  /// The number of documents successfully read or written.
+  pub documents: u64,
+  /// The encoded size of the documents successfully read or written.
+  pub bytes: u64,
+  /// The number of failures, counting each failed document of a batch.
+  pub errors: u64,
+  /// How long the operations took to complete.
+  pub latency: LatencyHistogram,
+}
+
+/// The metrics recorded by a `MeteredCollection`.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug,)]
+pub struct MetricsSnapshot {
+  /// The metrics for `get_document`.
+  pub get_document: OperationMetrics,
+  /// The metrics for `get_documents`.
+  pub get_documents: OperationMetrics,
+  /// The metrics for `write_document`.
+  pub write_document: OperationMetrics,
+  /// The metrics for `write_documents`.
+  pub write_documents: OperationMetrics,
+}
+
+/// A `TierListCollection` which records metrics about the operations performed on
+/// another collection.
+/// 
+/// The futures of the inner collection are only wrapped to record their results.
+pub struct MeteredCollection<Coll,> {
+  /// The collection being metered.
+  inner: Coll,
+  /// The recorded metrics.
+  metrics: Rc<RefCell<MetricsSnapshot>>,
+}
+
+impl<Coll,> MeteredCollection<Coll,> {
+  /// Returns a new `MeteredCollection` with no recorded metrics.
+  /// 
+  /// # Params
+  /// 
+  /// inner --- The collection to meter.  
+  pub fn new(inner: Coll,) -> Self {
+    Self { inner, metrics: Rc::default(), }
+  }
+  /// Gets the collection being metered.
+  #[inline]
+  pub const fn get_inner(&self,) -> &Coll { &self.inner }
+  /// Returns a copy of the recorded metrics.
+  #[inline]
+  pub fn snapshot(&self,) -> MetricsSnapshot { *RefCell::borrow(&self.metrics,) }
+  /// Clears the recorded metrics.
+  #[inline]
+  pub fn reset(&self,) { *self.metrics.borrow_mut() = MetricsSnapshot::default() }
+  /// Records the start of an operation and returns when it started.
+  fn start(&self, operation: fn(&mut MetricsSnapshot,) -> &mut OperationMetrics,) -> Instant {
+    operation(&mut self.metrics.borrow_mut(),).calls += 1;
+
+    Instant::now()
+  }
+}
+
+impl<Coll,> Clone for MeteredCollection<Coll,>
+  where Coll: Clone, {
+  #[inline]
+  fn clone(&self,) -> Self {
+    Self { inner: self.inner.clone(), metrics: self.metrics.clone(), }
+  }
+}
+
+impl<Coll,> fmt::Debug for MeteredCollection<Coll,>
+  where Coll: fmt::Debug, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    fmt.debug_struct("MeteredCollection",)
+    .field("inner", &self.inner,)
+    .field("metrics", &*RefCell::borrow(&self.metrics,),)
+    .finish()
+  }
+}
+
+impl<Coll,> TierListCollection for MeteredCollection<Coll,>
+  where Coll: TierListCollection,
+    Coll::Document: EncodedLen, {
+  type Document = Coll::Document;
+  type Error = Coll::Error;
+  type GetBatchDocuments = Inspect<Coll::GetBatchDocuments, Box<dyn FnOnce(&Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>,)>>;
+  type GetDocument = Inspect<Coll::GetDocument, Box<dyn FnOnce(&Result<Self::Document, Self::Error>,)>>;
+  type WriteBatchDocuments = Inspect<Coll::WriteBatchDocuments, Box<dyn FnOnce(&Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>,)>>;
+  type WriteDocument = Inspect<Coll::WriteDocument, Box<dyn FnOnce(&Result<(), Self::Error>,)>>;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    let start = self.start(|metrics,| &mut metrics.get_documents,);
+    let metrics = self.metrics.clone();
+
+    self.inner.get_documents(ids,)
+    .inspect(Box::new(move |res: &Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>,| {
+      let metrics = &mut metrics.borrow_mut().get_documents;
+
+      metrics.latency.record(start.elapsed(),);
+      match res {
+        Ok(results) => for res in results {
+          match res {
+            Ok(doc) => {
+              metrics.documents += 1;
+              metrics.bytes += doc.encoded_len() as u64;
+            },
+            Err(_) => metrics.errors += 1,
+          }
+        },
+        Err(_) => metrics.errors += 1,
+      }
+    },),)
+  }
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    let start = self.start(|metrics,| &mut metrics.get_document,);
+    let metrics = self.metrics.clone();
+
+    self.inner.get_document(id,)
+    .inspect(Box::new(move |res: &Result<Self::Document, Self::Error>,| {
+      let metrics = &mut metrics.borrow_mut().get_document;
+
+      metrics.latency.record(start.elapsed(),);
+      match res {
+        Ok(doc) => {
+          metrics.documents += 1;
+          metrics.bytes += doc.encoded_len() as u64;
+        },
+        Err(_) => metrics.errors += 1,
+      }
+    },),)
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let start = self.start(|metrics,| &mut metrics.write_documents,);
+    let metrics = self.metrics.clone();
+    let documents = documents.into_iter().collect::<Vec<_>>();
+    let lengths = documents.iter()
+      .map(|doc,| doc.borrow().encoded_len() as u64,)
+      .collect::<Vec<_>>();
+
+    self.inner.write_documents(documents,)
+    .inspect(Box::new(move |res: &Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>,| {
+      let metrics = &mut metrics.borrow_mut().write_documents;
+
+      metrics.latency.record(start.elapsed(),);
+      match res {
+        Ok(Ok(())) => {
+          metrics.documents += lengths.len() as u64;
+          metrics.bytes += lengths.iter().sum::<u64>();
+        },
+        Ok(Err(results)) => for (len, res,) in lengths.into_iter().zip(results,) {
+          match res {
+            Ok(()) => {
+              metrics.documents += 1;
+              metrics.bytes += len;
+            },
+            Err(_) => metrics.errors += 1,
+          }
+        },
+        Err(_) => metrics.errors += 1,
+      }
+    },),)
+  }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    let start = self.start(|metrics,| &mut metrics.write_document,);
+    let metrics = self.metrics.clone();
+    let len = document.borrow().encoded_len() as u64;
+
+    self.inner.write_document(document,)
+    .inspect(Box::new(move |res: &Result<(), Self::Error>,| {
+      let metrics = &mut metrics.borrow_mut().write_document;
+
+      metrics.latency.record(start.elapsed(),);
+      match res {
+        Ok(()) => {
+          metrics.documents += 1;
+          metrics.bytes += len;
+        },
+        Err(_) => metrics.errors += 1,
+      }
+    },),)
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{Document, TierMeta, mock::ChaosCollection,};
+  use futures::executor::block_on;
+
+  /// Returns the tiers of a linked list of `len` tiers.
+  fn list(len: u8,) -> Vec<TierMeta> {
+    (0..len).map(|i,| {
+      let id = DocumentId::from_bytes([i; 20]);
+      let previous = if i == 0 { None } else { Some(DocumentId::from_bytes([i - 1; 20])) };
+      let next = if i + 1 == len { None } else { Some(DocumentId::from_bytes([i + 1; 20])) };
+
+      TierMeta::new(id.into(), (None, id.into(), id.into(),), previous.map(Into::into,), next.map(Into::into,),)
+    },).collect()
+  }
+
+  #[test]
+  fn test_cursor_reads() {
+    let inner = ChaosCollection::<TierMeta,>::new();
+    let coll = MeteredCollection::new(inner.clone(),);
+    let tiers = list(10,);
+
+    block_on(async {
+      inner.write_documents(&tiers,).await
+        .expect("Error writing tiers")
+        .expect("Error writing tiers");
+
+      let mut cursor = coll.ref_cursor::<TierMeta>(tiers[0].get_id(),).await
+        .expect("Error reading front");
+      let mut visited = 1;
+
+      while let Some(next) = cursor.get_next().await.expect("Error reading next") {
+        cursor = next;
+        visited += 1;
+      }
+      assert_eq!(visited, tiers.len(), "Error walking the list",);
+    },);
+
+    let metrics = coll.snapshot();
+    //Each node is fetched exactly once.
+    assert_eq!(metrics.get_document.calls, tiers.len() as u64, "Error counting reads",);
+    assert_eq!(metrics.get_document.documents, tiers.len() as u64, "Error counting documents",);
+    assert_eq!(inner.reads(), tiers.len(), "Error metered reads differ from inner reads",);
+    assert_eq!(
+      metrics.get_document.bytes,
+      tiers.iter().map(|tier,| tier.encoded_len() as u64,).sum::<u64>(),
+      "Error counting bytes",
+    );
+    assert_eq!(metrics.get_document.latency.total(), tiers.len() as u64, "Error recording latencies",);
+    assert_eq!(metrics.get_documents, OperationMetrics::default(), "Error counting batch reads",);
+  }
+  #[test]
+  fn test_errors() {
+    let inner = ChaosCollection::new();
+    let coll = MeteredCollection::new(inner.clone(),);
+    let tiers = list(3,);
+
+    inner.fail_writes(*tiers[1].get_id(),);
+    block_on(async {
+      coll.write_documents(&tiers,).await
+        .expect("Error writing tiers")
+        .expect_err("Error writing broken tier");
+      coll.write_document(&tiers[1],).await
+        .expect_err("Error writing broken tier");
+      coll.get_documents([tiers[0].get_id(), tiers[1].get_id(),],).await
+        .expect("Error reading tiers");
+    },);
+
+    let metrics = coll.snapshot();
+    assert_eq!(metrics.write_documents.calls, 1, "Error counting batch writes",);
+    assert_eq!(metrics.write_documents.documents, 2, "Error counting written documents",);
+    assert_eq!(metrics.write_documents.errors, 1, "Error counting batch write errors",);
+    assert_eq!(metrics.write_document.errors, 1, "Error counting write errors",);
+    assert_eq!(metrics.get_documents.documents, 1, "Error counting read documents",);
+    assert_eq!(metrics.get_documents.errors, 1, "Error counting read errors",);
+
+    coll.reset();
+    assert_eq!(coll.snapshot(), MetricsSnapshot::default(), "Error resetting metrics",);
+  }
+  #[test]
+  fn test_histogram() {
+    let mut histogram = LatencyHistogram::default();
+
+    histogram.record(Duration::from_micros(50,),);
+    histogram.record(Duration::from_millis(1,),);
+    histogram.record(Duration::from_secs(5,),);
+
+    assert_eq!(histogram.buckets[0], 1, "Error bucketing fast operation",);
+    assert_eq!(histogram.buckets[1], 1, "Error bucketing operation on a bound",);
+    assert_eq!(histogram.buckets[LATENCY_BOUNDS.len()], 1, "Error bucketing slow operation",);
+    assert_eq!(histogram.total(), 3, "Error totalling histogram",);
+  }
+}
@@ -0,0 +1,574 @@
+//! Defines collections used to inject failures in tests.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::*;
+use futures::{Future, FutureExt, TryFutureExt, future::{self, Ready, LocalBoxFuture,},};
+use std::{
+  time::Duration,
+  pin::Pin,
+  task::{Context, Poll,},
+  marker::PhantomData,
+  collections::{HashMap, HashSet,},
+  cell::{Cell, RefCell,},
+  borrow::Borrow,
+  rc::Rc,
+};
+
+/// The errors returned by a `ChaosCollection`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum ChaosError {
+  /// A transient failure injected while accessing the document.
+  Injected(DocumentId),
+  /// An error from the inner collection.
+  Memory(MemoryError),
+}
+
+impl From<MemoryError> for ChaosError {
+  #[inline]
+  fn from(from: MemoryError,) -> Self { ChaosError::Memory(from,) }
+}
+
+impl CollectionError for ChaosError {
+  #[inline]
+  fn kind(&self,) -> ErrorKind {
+    match self {
+      ChaosError::Injected(_) => ErrorKind::Transient,
+      ChaosError::Memory(e) => e.kind(),
+    }
+  }
+}
+
+/// The failures to inject and a record of the operations performed.
+#[derive(Default, Debug,)]
+struct State {
+  /// The number of documents read.
+  reads: usize,
+  /// The number of documents written.
+  writes: usize,
+  /// The documents which always fail to be written.
+  broken: HashSet<DocumentId>,
+  /// The documents which always fail to be read.
+  unreadable: HashSet<DocumentId>,
+  /// The documents which fail to be read and how many more times they fail.
+  flaky_reads: HashMap<DocumentId, usize>,
+  /// The documents which fail to be written and how many more times they fail.
+  flaky_writes: HashMap<DocumentId, usize>,
+  /// How many more batch reads fail as a whole.
+  flaky_batches: usize,
+}
+
+impl State {
+  /// Returns `true` if the next attempt fails, counting down a flaky failure.
+  fn flake(flaky: &mut HashMap<DocumentId, usize>, id: &DocumentId,) -> bool {
+    match flaky.get_mut(id,) {
+      Some(remaining) if *remaining > 0 => { *remaining -= 1; true },
+      _ => false,
+    }
+  }
+}
+
+/// A `MemoryCollection` which fails operations on chosen documents.
+#[derive(Debug,)]
+pub struct ChaosCollection<D,> {
+  /// The collection storing the documents.
+  inner: MemoryCollection<D,>,
+  /// The failures to inject and a record of the operations performed.
+  state: Rc<RefCell<State>>,
+}
+
+impl<D,> ChaosCollection<D,> {
+  /// Returns a new empty `ChaosCollection`.
+  pub fn new() -> Self {
+    Self { inner: MemoryCollection::new(), state: Rc::default(), }
+  }
+  /// Gets the collection storing the documents.
+  pub fn inner(&self,) -> &MemoryCollection<D,> { &self.inner }
+  /// Returns the number of documents read from the collection.
+  pub fn reads(&self,) -> usize { RefCell::borrow(&self.state,).reads }
+  /// Returns the number of documents written to the collection.
+  pub fn writes(&self,) -> usize { RefCell::borrow(&self.state,).writes }
+  /// Makes writes of the document fail.
+  pub fn fail_writes(&self, id: DocumentId,) { self.state.borrow_mut().broken.insert(id,); }
+  /// Makes reads of the document fail.
+  pub fn fail_reads(&self, id: DocumentId,) { self.state.borrow_mut().unreadable.insert(id,); }
+  /// Makes the next reads of the document fail.
+  pub fn flaky_reads(&self, id: DocumentId, times: usize,) { self.state.borrow_mut().flaky_reads.insert(id, times,); }
+  /// Makes the next writes of the document fail.
+  pub fn flaky_writes(&self, id: DocumentId, times: usize,) { self.state.borrow_mut().flaky_writes.insert(id, times,); }
+  /// Makes the next batch reads fail as a whole.
+  pub fn flaky_batches(&self, times: usize,) { self.state.borrow_mut().flaky_batches = times; }
+  /// Returns `true` if this attempt to read the document fails.
+  fn read_fails(&self, id: &DocumentId,) -> bool {
+    let mut state = self.state.borrow_mut();
+
+    state.unreadable.contains(id,) || State::flake(&mut state.flaky_reads, id,)
+  }
+  /// Returns `true` if this attempt to write the document fails.
+  fn write_fails(&self, id: &DocumentId,) -> bool {
+    let mut state = self.state.borrow_mut();
+
+    state.broken.contains(id,) || State::flake(&mut state.flaky_writes, id,)
+  }
+}
+
+impl<D,> Clone for ChaosCollection<D,> {
+  fn clone(&self,) -> Self {
+    Self { inner: self.inner.clone(), state: self.state.clone(), }
+  }
+}
+
+impl<D,> PartialEq for ChaosCollection<D,> {
+  fn eq(&self, rhs: &Self,) -> bool { self.inner == rhs.inner }
+}
+
+impl<D,> Eq for ChaosCollection<D,> {}
+
+impl<D,> TierListCollection for ChaosCollection<D,>
+  where D: 'static + Document + Clone, {
+  type Document = D;
+  type Error = ChaosError;
+  type GetBatchDocuments = LocalBoxFuture<'static, Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>>;
+  type GetDocument = LocalBoxFuture<'static, Result<Self::Document, Self::Error>>;
+  type WriteBatchDocuments = LocalBoxFuture<'static, Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>;
+  type WriteDocument = LocalBoxFuture<'static, Result<(), Self::Error>>;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    let ids = ids.into_iter().map(|id,| *id.borrow(),).collect::<Vec<_>>();
+    {
+      let mut state = self.state.borrow_mut();
+
+      if state.flaky_batches > 0 && !ids.is_empty() {
+        state.flaky_batches -= 1;
+        return future::err(ChaosError::Injected(ids[0],),).boxed_local()
+      }
+    }
+
+    let docs = ids.iter()
+      .map(|id,| self.get_document(id,),)
+      .collect::<Vec<_>>();
+
+    future::join_all(docs,).map(Ok,).boxed_local()
+  }
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    self.state.borrow_mut().reads += 1;
+
+    if self.read_fails(id,) { return future::err(ChaosError::Injected(*id),).boxed_local() }
+
+    self.inner.get_document(id,).err_into().boxed_local()
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let docs = documents.into_iter()
+      .map(|doc,| self.write_document(&doc,),)
+      .collect::<Vec<_>>();
+
+    future::join_all(docs,).map(|results,| {
+      if results.iter().all(Result::is_ok,) { Ok(Ok(())) }
+      else { Ok(Err(results)) }
+    },).boxed_local()
+  }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    let id = document.borrow().get_id();
+
+    self.state.borrow_mut().writes += 1;
+
+    if self.write_fails(id,) { return future::err(ChaosError::Injected(*id),).boxed_local() }
+
+    self.inner.write_document(document,).err_into().boxed_local()
+  }
+}
+
+/// Cards are found by walking the list.
+impl NamedLookup for ChaosCollection<TierDocument,> {}
+
+impl<D,> TransactionalCollection for ChaosCollection<D,>
+  where D: 'static + Document + Clone, {
+  type Transaction = ChaosTransaction<D,>;
+
+  fn begin(&self,) -> Self::Transaction {
+    ChaosTransaction { collection: self.clone(), inner: self.inner.begin(), failed: None, }
+  }
+}
+
+impl<D,> RevisionedCollection for ChaosCollection<D,>
+  where D: 'static + Document + Clone, {
+  type Revision = u64;
+  type GetDocumentRev = LocalBoxFuture<'static, Result<(Self::Document, Self::Revision,), Self::Error>>;
+  type WriteDocumentRev = LocalBoxFuture<'static, Result<Self::Revision, Self::Error>>;
+
+  fn get_document_rev(&self, id: &DocumentId,) -> Self::GetDocumentRev {
+    self.state.borrow_mut().reads += 1;
+
+    if self.read_fails(id,) { return future::err(ChaosError::Injected(*id),).boxed_local() }
+
+    self.inner.get_document_rev(id,).err_into().boxed_local()
+  }
+  fn write_document_rev<T,>(&self, document: &T, expected: Option<&Self::Revision>,) -> Self::WriteDocumentRev
+    where T: Borrow<Self::Document>, {
+    let id = document.borrow().get_id();
+
+    self.state.borrow_mut().writes += 1;
+
+    if self.write_fails(id,) { return future::err(ChaosError::Injected(*id),).boxed_local() }
+
+    self.inner.write_document_rev(document, expected,).err_into().boxed_local()
+  }
+}
+
+/// Increments count as writes of the document.
+impl<D,> AtomicCounters for ChaosCollection<D,>
+  where D: 'static + Document + Counters + Clone, {
+  type Increment = LocalBoxFuture<'static, Result<u64, Self::Error>>;
+
+  fn increment_field(&self, id: &DocumentId, field: CounterField, delta: i64,) -> Self::Increment {
+    self.state.borrow_mut().writes += 1;
+
+    if self.write_fails(id,) { return future::err(ChaosError::Injected(*id),).boxed_local() }
+
+    self.inner.increment_field(id, field, delta,).err_into().boxed_local()
+  }
+}
+
+/// A `Transaction` against a `ChaosCollection`.
+pub struct ChaosTransaction<D,> {
+  /// The collection the transaction is against.
+  collection: ChaosCollection<D,>,
+  /// The transaction against the inner collection.
+  inner: MemoryTransaction<D,>,
+  /// The first staged document which fails to be written.
+  failed: Option<DocumentId>,
+}
+
+impl<D,> Transaction for ChaosTransaction<D,>
+  where D: 'static + Document + Clone, {
+  type Document = D;
+  type Error = ChaosError;
+  type Commit = LocalBoxFuture<'static, Result<(), Self::Error>>;
+
+  fn stage_write<T,>(&mut self, document: &T,)
+    where T: Borrow<Self::Document>, {
+    let id = document.borrow().get_id();
+
+    if self.failed.is_none() && self.collection.write_fails(id,) { self.failed = Some(*id) }
+
+    self.inner.stage_write(document,)
+  }
+  fn stage_delete(&mut self, id: &DocumentId,) {
+    if self.failed.is_none() && self.collection.write_fails(id,) { self.failed = Some(*id) }
+
+    self.inner.stage_delete(id,)
+  }
+  fn commit(self,) -> Self::Commit {
+    match self.failed {
+      Some(id) => future::err(ChaosError::Injected(id),).boxed_local(),
+      None => self.inner.commit().err_into().boxed_local(),
+    }
+  }
+}
+
+/// A `Timer` which records the requested delays and completes immediately.
+#[derive(Clone, Default, Debug,)]
+pub struct RecordingTimer {
+  /// The requested delays.
+  sleeps: Rc<RefCell<Vec<Duration>>>,
+}
+
+impl RecordingTimer {
+  /// Returns the requested delays.
+  pub fn sleeps(&self,) -> Vec<Duration> { RefCell::borrow(&self.sleeps,).clone() }
+}
+
+impl Timer for RecordingTimer {
+  type Sleep = Ready<()>;
+
+  fn sleep(&self, duration: Duration,) -> Self::Sleep {
+    self.sleeps.borrow_mut().push(duration,);
+    future::ready(())
+  }
+}
+
+/// A collection which delays each write to another collection and writes the documents
+/// of a batch one after another.
+pub struct LatencyCollection<Coll, Tm,> {
+  /// The collection to write to.
+  inner: Coll,
+  /// The timer used to delay writes.
+  timer: Tm,
+  /// Gets the delay before writing a document.
+  latency: fn(&DocumentId,) -> Duration,
+  /// The number of writes in flight.
+  in_flight: Rc<Cell<usize>>,
+  /// The most writes which have been in flight at once.
+  peak: Rc<Cell<usize>>,
+}
+
+impl<Coll, Tm,> LatencyCollection<Coll, Tm,> {
+  /// Returns a new `LatencyCollection`.
+  pub fn new(inner: Coll, timer: Tm, latency: fn(&DocumentId,) -> Duration,) -> Self {
+    Self { inner, timer, latency, in_flight: Rc::default(), peak: Rc::default(), }
+  }
+  /// Returns the most writes which have been in flight at once.
+  pub fn peak_in_flight(&self,) -> usize { self.peak.get() }
+}
+
+impl<Coll, Tm,> Clone for LatencyCollection<Coll, Tm,>
+  where Coll: Clone,
+    Tm: Clone, {
+  fn clone(&self,) -> Self {
+    Self {
+      inner: self.inner.clone(),
+      timer: self.timer.clone(),
+      latency: self.latency,
+      in_flight: self.in_flight.clone(),
+      peak: self.peak.clone(),
+    }
+  }
+}
+
+impl<Coll, Tm,> TierListCollection for LatencyCollection<Coll, Tm,>
+  where Coll: 'static + TierListCollection + Clone,
+    Coll::Document: 'static + Clone,
+    Tm: 'static + Timer + Clone, {
+  type Document = Coll::Document;
+  type Error = Coll::Error;
+  type GetBatchDocuments = Coll::GetBatchDocuments;
+  type GetDocument = Coll::GetDocument;
+  type WriteBatchDocuments = LocalBoxFuture<'static, Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>;
+  type WriteDocument = LocalBoxFuture<'static, Result<(), Self::Error>>;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, { self.inner.get_documents(ids,) }
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument { self.inner.get_document(id,) }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let documents = documents.into_iter()
+      .map(|doc,| doc.borrow().clone(),)
+      .collect::<Vec<_>>();
+    let coll = self.clone();
+
+    Box::pin(async move {
+      let mut results = Vec::with_capacity(documents.len(),);
+      for document in &documents { results.push(coll.write_document(document,).await,) }
+
+      if results.iter().all(Result::is_ok,) { Ok(Ok(())) }
+      else { Ok(Err(results)) }
+    },)
+  }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    let document = document.borrow().clone();
+    let coll = self.clone();
+
+    Box::pin(async move {
+      coll.in_flight.set(coll.in_flight.get() + 1,);
+      coll.peak.set(coll.peak.get().max(coll.in_flight.get(),),);
+      coll.timer.sleep((coll.latency)(document.get_id(),),).await;
+      coll.in_flight.set(coll.in_flight.get() - 1,);
+
+      coll.inner.write_document(&document,).await
+    },)
+  }
+}
+
+/// A future which is pending once before it completes so that other futures run first.
+#[derive(Default, Debug,)]
+pub struct YieldOnce(bool);
+
+impl Future for YieldOnce {
+  type Output = ();
+
+  fn poll(mut self: Pin<&mut Self>, ctx: &mut Context,) -> Poll<Self::Output> {
+    if self.0 { return Poll::Ready(()) }
+
+    self.0 = true;
+    ctx.waker().wake_by_ref();
+    Poll::Pending
+  }
+}
+
+/// A collection which yields before every operation on another collection so that
+/// concurrent operations interleave.
+#[derive(Clone, Debug,)]
+pub struct YieldingCollection<Coll,> {
+  /// The collection to operate on.
+  inner: Coll,
+}
+
+impl<Coll,> YieldingCollection<Coll,> {
+  /// Returns a new `YieldingCollection`.
+  pub fn new(inner: Coll,) -> Self { Self { inner, } }
+}
+
+impl<Coll,> TierListCollection for YieldingCollection<Coll,>
+  where Coll: 'static + TierListCollection + Clone,
+    Coll::Document: 'static + Clone, {
+  type Document = Coll::Document;
+  type Error = Coll::Error;
+  type GetBatchDocuments = LocalBoxFuture<'static, Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>>;
+  type GetDocument = LocalBoxFuture<'static, Result<Self::Document, Self::Error>>;
+  type WriteBatchDocuments = LocalBoxFuture<'static, Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>;
+  type WriteDocument = LocalBoxFuture<'static, Result<(), Self::Error>>;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    let ids = ids.into_iter().map(|id,| *id.borrow(),).collect::<Vec<_>>();
+    let inner = self.inner.clone();
+
+    Box::pin(async move { YieldOnce::default().await; inner.get_documents(&ids,).await },)
+  }
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    let (id, inner,) = (*id, self.inner.clone(),);
+
+    Box::pin(async move { YieldOnce::default().await; inner.get_document(&id,).await },)
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let documents = documents.into_iter().map(|doc,| doc.borrow().clone(),).collect::<Vec<_>>();
+    let inner = self.inner.clone();
+
+    Box::pin(async move { YieldOnce::default().await; inner.write_documents(&documents,).await },)
+  }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    let (document, inner,) = (document.borrow().clone(), self.inner.clone(),);
+
+    Box::pin(async move { YieldOnce::default().await; inner.write_document(&document,).await },)
+  }
+}
+
+impl<Coll,> RevisionedCollection for YieldingCollection<Coll,>
+  where Coll: 'static + RevisionedCollection + Clone,
+    Coll::Document: 'static + Clone,
+    Coll::Revision: 'static, {
+  type Revision = Coll::Revision;
+  type GetDocumentRev = LocalBoxFuture<'static, Result<(Self::Document, Self::Revision,), Self::Error>>;
+  type WriteDocumentRev = LocalBoxFuture<'static, Result<Self::Revision, Self::Error>>;
+
+  fn get_document_rev(&self, id: &DocumentId,) -> Self::GetDocumentRev {
+    let (id, inner,) = (*id, self.inner.clone(),);
+
+    Box::pin(async move { YieldOnce::default().await; inner.get_document_rev(&id,).await },)
+  }
+  fn write_document_rev<T,>(&self, document: &T, expected: Option<&Self::Revision>,) -> Self::WriteDocumentRev
+    where T: Borrow<Self::Document>, {
+    let (document, expected, inner,) = (document.borrow().clone(), expected.cloned(), self.inner.clone(),);
+
+    Box::pin(async move { YieldOnce::default().await; inner.write_document_rev(&document, expected.as_ref(),).await },)
+  }
+}
+
+impl<Coll,> AtomicCounters for YieldingCollection<Coll,>
+  where Coll: 'static + AtomicCounters + Clone,
+    Coll::Document: 'static + Clone, {
+  type Increment = LocalBoxFuture<'static, Result<u64, Self::Error>>;
+
+  fn increment_field(&self, id: &DocumentId, field: CounterField, delta: i64,) -> Self::Increment {
+    let (id, inner,) = (*id, self.inner.clone(),);
+
+    Box::pin(async move { YieldOnce::default().await; inner.increment_field(&id, field, delta,).await },)
+  }
+}
+
+/// A collection which reads from another collection as soon as a read is polled but
+/// yields once before returning the documents, so that other futures can write while
+/// the read is in flight.
+pub struct SlowReadCollection<Coll,> {
+  /// The collection to operate on.
+  inner: Coll,
+}
+
+impl<Coll,> SlowReadCollection<Coll,> {
+  /// Returns a new `SlowReadCollection`.
+  pub fn new(inner: Coll,) -> Self { Self { inner, } }
+}
+
+impl<Coll,> TierListCollection for SlowReadCollection<Coll,>
+  where Coll: 'static + TierListCollection,
+    Coll::Document: 'static, {
+  type Document = Coll::Document;
+  type Error = Coll::Error;
+  type GetBatchDocuments = LocalBoxFuture<'static, Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>>;
+  type GetDocument = LocalBoxFuture<'static, Result<Self::Document, Self::Error>>;
+  type WriteBatchDocuments = Coll::WriteBatchDocuments;
+  type WriteDocument = Coll::WriteDocument;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    let read = self.inner.get_documents(ids,);
+
+    Box::pin(async move { let docs = read.await; YieldOnce::default().await; docs },)
+  }
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    let read = self.inner.get_document(id,);
+
+    Box::pin(async move { let doc = read.await; YieldOnce::default().await; doc },)
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, { self.inner.write_documents(documents,) }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, { self.inner.write_document(document,) }
+}
+
+/// A future which never completes and records when it is dropped.
+#[derive(Debug,)]
+pub struct Hung<T,> {
+  /// Set when the future is dropped.
+  dropped: Rc<Cell<bool>>,
+  /// The output of the future.
+  _output: PhantomData<fn() -> T>,
+}
+
+impl<T,> Future for Hung<T,> {
+  type Output = T;
+
+  fn poll(self: Pin<&mut Self>, _: &mut Context,) -> Poll<Self::Output> { Poll::Pending }
+}
+
+impl<T,> Drop for Hung<T,> {
+  fn drop(&mut self,) { self.dropped.set(true,) }
+}
+
+/// A collection whose operations never complete.
+#[derive(Clone, Default, Debug,)]
+pub struct HungCollection {
+  /// Set when an operation is dropped.
+  dropped: Rc<Cell<bool>>,
+}
+
+impl HungCollection {
+  /// Returns `true` if an operation has been dropped.
+  pub fn dropped(&self,) -> bool { self.dropped.get() }
+  /// Returns an operation which never completes.
+  fn hang<T,>(&self,) -> Hung<T,> { Hung { dropped: self.dropped.clone(), _output: PhantomData, } }
+}
+
+impl TierListCollection for HungCollection {
+  type Document = TierMeta;
+  type Error = MemoryError;
+  type GetBatchDocuments = Hung<Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>>;
+  type GetDocument = Hung<Result<Self::Document, Self::Error>>;
+  type WriteBatchDocuments = Hung<Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>;
+  type WriteDocument = Hung<Result<(), Self::Error>>;
+
+  fn get_documents<I, B,>(&self, _: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, { self.hang() }
+  fn get_document(&self, _: &DocumentId,) -> Self::GetDocument { self.hang() }
+  fn write_documents<I, T,>(&self, _: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, { self.hang() }
+  fn write_document<T,>(&self, _: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, { self.hang() }
+}
@@ -0,0 +1,196 @@
+//! Defines a view of a document collection which shares the collection with other
+//! independent namespaces.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, Document, EncodedLen, TierListCollection,};
+use futures::{Future, TryFutureExt, future::MapOk,};
+use std::borrow::Borrow;
+
+/// A document stored in a collection shared by several namespaces.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub struct NamespacedDocument<D,> {
+  /// The identifier of the document within the shared collection.
+  id: DocumentId,
+  /// The document.
+  pub document: D,
+}
+
+impl<D,> NamespacedDocument<D,> {
+  /// Unwraps the document.
+  #[inline]
+  pub fn into_inner(self,) -> D { self.document }
+}
+
+impl<D,> Document for NamespacedDocument<D,> {
+  #[inline]
+  fn get_id(&self,) -> &DocumentId { &self.id }
+}
+
+impl<D,> EncodedLen for NamespacedDocument<D,>
+  where D: EncodedLen, {
+  #[inline]
+  fn encoded_len(&self,) -> usize { DocumentId::LEN + self.document.encoded_len() }
+}
+
+/// A `TierListCollection` which stores its documents in a collection shared with other
+/// namespaces.
+/// 
+/// Every identifier passed to or read through a `Namespaced` collection is mapped to an
+/// identifier unique to its namespace so documents written under one namespace are
+/// invisible under every other namespace. The links between documents are mapped in
+/// the same way, so a link can only ever reach documents in its own namespace; links
+/// across namespaces are impossible by construction.
+/// 
+/// The mapping is a fixed hash of the namespace and the identifier so it is the same
+/// across runs and platforms. It is not cryptographic and must not be relied upon to
+/// keep namespaces secret from each other.
+#[derive(PartialEq, Eq, Clone, Debug,)]
+pub struct Namespaced<Coll,> {
+  /// The collection shared by the namespaces.
+  inner: Coll,
+  /// The name of this namespace.
+  namespace: String,
+}
+
+impl<Coll,> Namespaced<Coll,> {
+  /// Returns a new `Namespaced` view of a collection.
+  /// 
+  /// # Params
+  /// 
+  /// inner --- The collection shared by the namespaces.  
+  /// namespace --- The name of the namespace.  
+  pub fn namespace(inner: Coll, namespace: &str,) -> Self {
+    Self { inner, namespace: namespace.to_owned(), }
+  }
+  /// Gets the collection shared by the namespaces.
+  #[inline]
+  pub const fn get_inner(&self,) -> &Coll { &self.inner }
+  /// Gets the name of this namespace.
+  #[inline]
+  pub fn get_namespace(&self,) -> &str { &self.namespace }
+  /// Maps an identifier into this namespace.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the document within this namespace.  
+  pub fn map_id(&self, id: &DocumentId,) -> DocumentId {
+    /// The FNV-1a offset basis.
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    /// The FNV-1a prime.
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let namespace = self.namespace.as_bytes();
+    let len = (namespace.len() as u64).to_le_bytes();
+    let mut mapped = [0; 20];
+
+    //Hash the namespace and id once for each 8 bytes of the mapped id.
+    for (lane, chunk,) in mapped.chunks_mut(8,).enumerate() {
+      let bytes = std::iter::once(lane as u8,)
+        .chain(len.iter().copied(),)
+        .chain(namespace.iter().copied(),)
+        .chain(id.as_bytes().iter().copied(),);
+      let hash = bytes.fold(OFFSET, |hash, byte,| (hash ^ byte as u64).wrapping_mul(PRIME,),);
+
+      chunk.copy_from_slice(&hash.to_le_bytes()[..chunk.len()],);
+    }
+
+    DocumentId::from_bytes(mapped,)
+  }
+}
+
+impl<Coll, D,> TierListCollection for Namespaced<Coll,>
+  where Coll: TierListCollection<Document = NamespacedDocument<D,>>,
+    D: Document + Clone, {
+  type Document = D;
+  type Error = Coll::Error;
+  type GetBatchDocuments = MapOk<Coll::GetBatchDocuments, fn(Vec<Result<Coll::Document, Self::Error>>,) -> Vec<Result<D, Self::Error>>>;
+  type GetDocument = MapOk<Coll::GetDocument, fn(Coll::Document,) -> D>;
+  type WriteBatchDocuments = Coll::WriteBatchDocuments;
+  type WriteDocument = Coll::WriteDocument;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    /// Unwraps the read documents.
+    fn unwrap<D, E,>(docs: Vec<Result<NamespacedDocument<D,>, E>>,) -> Vec<Result<D, E>> {
+      docs.into_iter().map(|doc,| doc.map(NamespacedDocument::into_inner,),).collect()
+    }
+
+    self.inner.get_documents(ids.into_iter().map(|id,| self.map_id(id.borrow(),),),)
+    .map_ok(unwrap as fn(_,) -> _,)
+  }
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    self.inner.get_document(&self.map_id(id,),)
+    .map_ok(NamespacedDocument::into_inner as fn(_,) -> _,)
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    self.inner.write_documents(documents.into_iter().map(|doc,| self.wrap(doc.borrow(),),),)
+  }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    self.inner.write_document(&self.wrap(document.borrow(),),)
+  }
+  fn exists(&self, id: &DocumentId,) -> impl Future<Output = Result<bool, Self::Error>> {
+    let id = self.map_id(id,);
+
+    async move { self.inner.exists(&id,).await }
+  }
+}
+
+impl<Coll,> Namespaced<Coll,> {
+  /// Wraps a document to be stored in this namespace.
+  fn wrap<D,>(&self, document: &D,) -> NamespacedDocument<D,>
+    where D: Document + Clone, {
+    NamespacedDocument { id: self.map_id(document.get_id(),), document: document.clone(), }
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{CardId, TierMeta, MemoryCollection,};
+  use futures::executor::block_on;
+
+  #[test]
+  fn test_map_id() {
+    let movies = Namespaced::namespace((), "movies",);
+    let games = Namespaced::namespace((), "games",);
+
+    assert_eq!(movies.map_id(&DocumentId::from_bytes([1; 20]),), movies.map_id(&DocumentId::from_bytes([1; 20]),), "Error mapping is not deterministic",);
+    assert_ne!(movies.map_id(&DocumentId::from_bytes([1; 20]),), games.map_id(&DocumentId::from_bytes([1; 20]),), "Error namespaces collide",);
+    assert_ne!(movies.map_id(&DocumentId::from_bytes([1; 20]),), movies.map_id(&DocumentId::from_bytes([2; 20]),), "Error ids collide",);
+    //The mapping must not change between runs or releases.
+    assert_eq!(
+      movies.map_id(&DocumentId::from_bytes([0; 20]),),
+      DocumentId::from_bytes([40, 48, 164, 119, 94, 31, 201, 39, 3, 149, 197, 210, 242, 8, 230, 51, 254, 122, 126, 23,],),
+      "Error mapping changed",
+    );
+  }
+  #[test]
+  fn test_isolation() {
+    let inner = MemoryCollection::new();
+    let movies = Namespaced::namespace(inner.clone(), "movies",);
+    let games = Namespaced::namespace(inner.clone(), "games",);
+    let id = DocumentId::from_bytes([1; 20]);
+    let movie = TierMeta::new(id.into(), (None, CardId::from_bytes([2; 20]), CardId::from_bytes([3; 20]),), None, None,);
+    let game = TierMeta::new(id.into(), (None, CardId::from_bytes([4; 20]), CardId::from_bytes([5; 20]),), None, None,);
+
+    block_on(async {
+      movies.write_document(&movie,).await
+        .expect("Error writing movie");
+      assert!(games.get_document(&id,).await.is_err(), "Error movie visible to games",);
+      assert_eq!(games.exists(&id,).await, Ok(false), "Error movie exists in games",);
+
+      games.write_documents([&game,],).await
+        .expect("Error writing game")
+        .expect("Error writing game");
+      assert_eq!(movies.get_document(&id,).await, Ok(movie), "Error reading movie",);
+      assert_eq!(games.get_documents([&id,],).await, Ok(vec![Ok(game)]), "Error reading game",);
+      assert_eq!(inner.len(), 2, "Error namespaces share a document",);
+    },);
+  }
+}
@@ -0,0 +1,222 @@
+//! Defines finding and deleting documents which no tier list links to.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{
+  DocumentId, Document, LinkedList, TierDocument, DocumentKind, CollectionError,
+  TierListCollectionScan, TransactionalCollection, Transaction,
+};
+use futures::{TryStreamExt, future,};
+use std::{fmt, error::Error, collections::HashSet,};
+
+/// The documents which no tier list links to, returned by `find_orphans`.
+#[derive(PartialEq, Eq, Clone, Default, Debug,)]
+pub struct OrphanReport {
+  /// The number of documents linked to from the tier lists.
+  pub reachable: u64,
+  /// The identifiers and kinds of the unlinked documents, in scan order.
+  pub orphans: Vec<(DocumentId, DocumentKind,)>,
+}
+
+impl OrphanReport {
+  /// Returns `true` if every document is linked to.
+  #[inline]
+  pub fn is_empty(&self,) -> bool { self.orphans.is_empty() }
+  /// Gets the identifiers of the unlinked documents of a kind.
+  /// 
+  /// # Params
+  /// 
+  /// kind --- The kind of documents to get.  
+  pub fn of_kind(&self, kind: DocumentKind,) -> impl Iterator<Item = &DocumentId> {
+    self.orphans.iter().filter(move |(_, orphan,),| *orphan == kind,).map(|(id, _,),| id,)
+  }
+}
+
+/// The errors when finding the documents which no tier list links to.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum OrphanError<E,> {
+  /// Getting a linked document failed.
+  Fetch(E),
+  /// There is no document with the identifier of a head.
+  MissingHead(DocumentId),
+  /// Scanning the collection failed.
+  Scan(E),
+}
+
+impl<E,> fmt::Display for OrphanError<E,>
+  where E: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      OrphanError::Fetch(e) => write!(fmt, "failed to get a document: {}", e,),
+      OrphanError::MissingHead(id) => write!(fmt, "there is no tier list with the head {:?}", id,),
+      OrphanError::Scan(e) => write!(fmt, "failed to scan the collection: {}", e,),
+    }
+  }
+}
+
+impl<E,> Error for OrphanError<E,>
+  where E: Error, {}
+
+/// Finds the documents in a collection which are not linked to from any tier list.
+/// 
+/// Starting from the heads every link of every document reached is followed, both
+/// forward and backward, along with the ends of each tier; a tier without a length is
+/// still followed to its ends so the cards of a tier whose length was lost are never
+/// reported. The documents are read in batches a step at a time and links to missing
+/// documents are skipped. The collection is then scanned for the documents which were
+/// not reached.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the tier lists.  
+/// list_heads --- The identifiers of the first tier of every tier list.  
+pub async fn find_orphans<Coll,>(collection: &Coll, list_heads: &[DocumentId],) -> Result<OrphanReport, OrphanError<Coll::Error,>>
+  where Coll: TierListCollectionScan<Document = TierDocument>,
+    Coll::Error: CollectionError, {
+  let mut reachable = HashSet::new();
+  let mut frontier = list_heads.iter().copied().filter(|id,| reachable.insert(*id,),).collect::<Vec<_>>();
+  let mut heads = true;
+
+  while !frontier.is_empty() {
+    let documents = collection.get_documents(&frontier,).await
+      .map_err(OrphanError::Fetch,)?;
+    let mut next = Vec::new();
+
+    for (id, document,) in frontier.iter().zip(documents,) {
+      let document = match document {
+        Ok(document) => document,
+        //A missing head would make every document in the collection an orphan.
+        Err(e) if e.is_not_found() && heads => return Err(OrphanError::MissingHead(*id,)),
+        Err(e) if e.is_not_found() => { reachable.remove(id,); continue },
+        Err(e) => return Err(OrphanError::Fetch(e,)),
+      };
+      let ends = match &document {
+        TierDocument::TierMeta(tier) => vec![**tier.list_front(), **tier.list_back(),],
+        TierDocument::Card(_) => Vec::new(),
+      };
+      let links = document.get_previous_id().into_iter().chain(document.get_next_id(),).copied().chain(ends,);
+
+      for link in links {
+        if reachable.insert(link,) { next.push(link,) }
+      }
+    }
+
+    frontier = next;
+    heads = false;
+  }
+
+  let orphans = collection.scan()
+    .try_filter(|document,| future::ready(!reachable.contains(document.get_id(),),),)
+    .map_ok(|document,| (*document.get_id(), document.kind(),),)
+    .try_collect().await
+    .map_err(OrphanError::Scan,)?;
+
+  Ok(OrphanReport { reachable: reachable.len() as u64, orphans, })
+}
+
+/// Deletes the documents found by `find_orphans` in a single transaction, returning how
+/// many were deleted.
+/// 
+/// Nothing is deleted unless `confirm` is `true`. The documents are deleted as reported
+/// so the report should be taken just before purging.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the tier lists.  
+/// report --- The documents to delete.  
+/// confirm --- Whether to delete the documents.  
+pub async fn purge_orphans<Coll,>(collection: &Coll, report: &OrphanReport, confirm: bool,) -> Result<u64, Coll::Error>
+  where Coll: TransactionalCollection, {
+  if !confirm || report.is_empty() { return Ok(0) }
+
+  let mut transaction = collection.begin();
+  for (id, _,) in &report.orphans { transaction.stage_delete(id,) }
+  transaction.commit().await?;
+
+  Ok(report.orphans.len() as u64)
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{CardId, Card, CardStatus, TierMeta, MemoryCollection, TierListCollection, TierPolicy, TierName, TierListSnapshot, TierSnapshot, CardSnapshot, ImportOptions, import_list,};
+  use futures::executor::block_on;
+  use std::num::NonZeroU64;
+
+  /// Returns a collection storing a tier list with two orphan cards.
+  fn seeded() -> MemoryCollection<TierDocument,> {
+    let coll = MemoryCollection::new();
+    let card = |id: u8,| CardSnapshot { id: DocumentId::from_bytes([id; 20]), name: "card".to_owned(), description: String::new(), up_votes: 0, down_votes: 0, bias: 0, status: CardStatus::Open, history: Vec::new(), };
+    let snapshot = TierListSnapshot {
+      tiers: vec![
+        TierSnapshot { id: DocumentId::from_bytes([1; 20]), cards: vec![card(10,), card(11,),], promote_threshold: None, policy: TierPolicy::OPEN, name: TierName::EMPTY, },
+        TierSnapshot { id: DocumentId::from_bytes([2; 20]), cards: vec![], promote_threshold: None, policy: TierPolicy::OPEN, name: TierName::EMPTY, },
+        TierSnapshot { id: DocumentId::from_bytes([3; 20]), cards: vec![card(12,),], promote_threshold: None, policy: TierPolicy::OPEN, name: TierName::EMPTY, },
+      ],
+    };
+    let orphan = |id: u8, previous_card,| TierDocument::Card(Card {
+      id: CardId::from_bytes([id; 20]),
+      name: "orphan".to_owned(),
+      description: String::new(),
+      up_votes: 0,
+      down_votes: 0,
+      bias: 0,
+      status: CardStatus::Open,
+      history: Vec::new(),
+      previous_card,
+      next_card: None,
+    },);
+
+    block_on(async {
+      import_list(&coll, &snapshot, ImportOptions { preserve_ids: true, ..ImportOptions::default() },).await
+        .expect("Error importing list");
+      //An orphan which still links into the list is not linked to.
+      coll.write_documents(vec![orphan(20, Some(CardId::from_bytes([11; 20]),),), orphan(21, None,),],).await
+        .expect("Error writing orphans")
+        .expect("Error writing orphans");
+    },);
+
+    coll
+  }
+  fn sorted(ids: impl IntoIterator<Item = DocumentId>,) -> Vec<u8> {
+    let mut ids = ids.into_iter().map(|id,| id.as_bytes()[0],).collect::<Vec<_>>();
+
+    ids.sort_unstable();
+    ids
+  }
+
+  #[test]
+  fn test_find_orphans() {
+    let coll = seeded();
+    let report = block_on(find_orphans(&coll, &[DocumentId::from_bytes([1; 20]),],),).expect("Error finding orphans");
+    assert_eq!(sorted(report.orphans.iter().map(|(id, _,),| *id,),), [20, 21,], "Error finding the orphans",);
+    assert_eq!(sorted(report.of_kind(DocumentKind::Card,).copied(),), [20, 21,], "Error categorising the orphans",);
+    assert_eq!(report.of_kind(DocumentKind::TierMeta,).count(), 0, "Error reporting a tier",);
+    assert_eq!(report.reachable, 6, "Error counting the reachable documents",);
+
+    //A tier without a length keeps its cards.
+    let mut tier = block_on(coll.get_item_flat::<TierMeta>(&DocumentId::from_bytes([3; 20]),),).expect("Error reading tier");
+    tier.set_ends((None, CardId::from_bytes([12; 20]), CardId::from_bytes([12; 20]),),);
+    block_on(coll.write_document(&TierDocument::TierMeta(tier,),),).expect("Error writing tier");
+    let report = block_on(find_orphans(&coll, &[DocumentId::from_bytes([1; 20]),],),).expect("Error finding orphans");
+    assert_eq!(sorted(report.orphans.iter().map(|(id, _,),| *id,),), [20, 21,], "Error orphaning the cards of a tier without a length",);
+
+    //A dangling link is skipped but a missing head is an error.
+    tier.set_ends((NonZeroU64::new(2,), CardId::from_bytes([12; 20]), CardId::from_bytes([13; 20]),),);
+    block_on(coll.write_document(&TierDocument::TierMeta(tier,),),).expect("Error writing tier");
+    assert_eq!(block_on(find_orphans(&coll, &[DocumentId::from_bytes([1; 20]),],),).map(|report,| report.reachable,), Ok(6), "Error following a dangling link",);
+    assert_eq!(block_on(find_orphans(&coll, &[DocumentId::from_bytes([1; 20]), DocumentId::from_bytes([9; 20]),],),), Err(OrphanError::MissingHead(DocumentId::from_bytes([9; 20]),)), "Error finding orphans without a head",);
+  }
+  #[test]
+  fn test_purge_orphans() {
+    let coll = seeded();
+    let report = block_on(find_orphans(&coll, &[DocumentId::from_bytes([1; 20]),],),).expect("Error finding orphans");
+
+    assert_eq!(block_on(purge_orphans(&coll, &report, false,),), Ok(0), "Error purging without confirmation",);
+    assert_eq!(coll.len(), 8, "Error deleting without confirmation",);
+    assert_eq!(block_on(purge_orphans(&coll, &report, true,),), Ok(2), "Error purging the orphans",);
+    assert_eq!(coll.len(), 6, "Error deleting the orphans",);
+    assert_eq!(block_on(find_orphans(&coll, &[DocumentId::from_bytes([1; 20]),],),), Ok(OrphanReport { reachable: 6, orphans: Vec::new(), }), "Error leaving orphans",);
+  }
+}
@@ -0,0 +1,236 @@
+//! Defines a document collection which writes the documents of a batch to another
+//! collection concurrently.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, Document, TierListCollection,};
+use futures::{
+  Future, FutureExt, StreamExt,
+  future::LocalBoxFuture,
+  stream::FuturesUnordered,
+};
+use std::{
+  num::NonZeroUsize,
+  collections::HashSet,
+  borrow::Borrow,
+};
+
+/// A `TierListCollection` which writes a batch by writing each of its documents to
+/// another collection individually with several writes in flight at once.
+/// 
+/// This speeds up batches on collections which write the documents of a batch one
+/// after another. The results of the batch are in the order of the documents passed
+/// regardless of which writes complete first, and two writes of the same document are
+/// never in flight together so the last write of a document in the batch wins.
+/// 
+/// All other operations are passed straight through to the inner collection.
+#[derive(PartialEq, Eq, Clone, Debug,)]
+pub struct ParallelWrites<Coll,> {
+  /// The collection to write to.
+  inner: Coll,
+  /// The most writes in flight at once.
+  max_in_flight: NonZeroUsize,
+}
+
+impl<Coll,> ParallelWrites<Coll,> {
+  /// Returns a new `ParallelWrites` collection.
+  /// 
+  /// # Params
+  /// 
+  /// inner --- The collection to write to.  
+  /// max_in_flight --- The most writes in flight at once.  
+  #[inline]
+  pub const fn new(inner: Coll, max_in_flight: NonZeroUsize,) -> Self {
+    Self { inner, max_in_flight, }
+  }
+  /// Gets the collection written to.
+  #[inline]
+  pub const fn get_inner(&self,) -> &Coll { &self.inner }
+  /// Gets the most writes in flight at once.
+  #[inline]
+  pub const fn get_max_in_flight(&self,) -> NonZeroUsize { self.max_in_flight }
+}
+
+impl<Coll,> TierListCollection for ParallelWrites<Coll,>
+  where Coll: 'static + TierListCollection + Clone,
+    Coll::Document: 'static + Clone, {
+  type Document = Coll::Document;
+  type Error = Coll::Error;
+  type GetBatchDocuments = Coll::GetBatchDocuments;
+  type GetDocument = Coll::GetDocument;
+  type WriteBatchDocuments = LocalBoxFuture<'static, Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>;
+  type WriteDocument = Coll::WriteDocument;
+
+  #[inline]
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    self.inner.get_documents(ids,)
+  }
+  #[inline]
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    self.inner.get_document(id,)
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let documents = documents.into_iter()
+      .map(|doc,| doc.borrow().clone(),)
+      .collect::<Vec<_>>();
+    let inner = self.inner.clone();
+    let max_in_flight = self.max_in_flight.get();
+
+    Box::pin(async move {
+      let mut pending = documents.iter().enumerate().peekable();
+      let mut writing = HashSet::new();
+      let mut in_flight = FuturesUnordered::new();
+      let mut results = Vec::with_capacity(documents.len(),);
+
+      loop {
+        //Start writes until the limit is reached or the next document is already being
+        //written.
+        while in_flight.len() < max_in_flight {
+          match pending.next_if(|(_, doc,),| !writing.contains(doc.get_id(),),) {
+            Some((index, doc,)) => {
+              writing.insert(*doc.get_id(),);
+              in_flight.push(inner.write_document(doc,).map(move |res,| (index, res,),),);
+            },
+            None => break,
+          }
+        }
+
+        match in_flight.next().await {
+          Some((index, res,)) => {
+            writing.remove(documents[index].get_id(),);
+            results.push((index, res,),);
+          },
+          None => break,
+        }
+      }
+
+      if results.iter().all(|(_, res,),| res.is_ok(),) { return Ok(Ok(())) }
+
+      results.sort_unstable_by_key(|(index, _,),| *index,);
+      Ok(Err(results.into_iter().map(|(_, res,),| res,).collect()))
+    },)
+  }
+  #[inline]
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    self.inner.write_document(document,)
+  }
+  #[inline]
+  fn exists(&self, id: &DocumentId,) -> impl Future<Output = Result<bool, Self::Error>> {
+    self.inner.exists(id,)
+  }
+  #[inline]
+  fn write_document_if_absent<T,>(&self, document: &T,) -> impl Future<Output = Result<bool, Self::Error>>
+    where T: Borrow<Self::Document>, {
+    self.inner.write_document_if_absent(document,)
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{TierId, CardId, TierMeta, mock::{ChaosCollection, ChaosError, LatencyCollection,},};
+  use futures::executor::block_on;
+  use std::time::Duration;
+
+  fn tier(id: u8, next: Option<u8>,) -> TierMeta {
+    TierMeta::new(TierId::from_bytes([id; 20]), (None, CardId::from_bytes([id; 20]), CardId::from_bytes([id; 20]),), None, next.map(|next,| TierId::from_bytes([next; 20]),),)
+  }
+
+  #[cfg(not(target_arch = "wasm32",),)]
+  #[test]
+  fn test_parallel_writes() {
+    let inner = ChaosCollection::new();
+    //Later documents are written faster so the writes complete out of order.
+    let slow = LatencyCollection::new(inner.clone(), tokio::time::sleep, |id: &DocumentId,| Duration::from_millis(100 - id.as_bytes()[0] as u64,),);
+    let coll = ParallelWrites::new(slow, NonZeroUsize::new(3,).unwrap(),);
+    let tiers = (0..6).map(|i,| tier(i, None,),).collect::<Vec<_>>();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+      .enable_time()
+      .start_paused(true,)
+      .build()
+      .expect("Error starting runtime");
+
+    inner.fail_writes(DocumentId::from_bytes([1; 20]),);
+    inner.fail_writes(DocumentId::from_bytes([4; 20]),);
+    runtime.block_on(async {
+      let results = coll.write_documents(&tiers,).await
+        .expect("Error writing tiers")
+        .expect_err("Error writing broken tiers");
+      assert_eq!(
+        results,
+        vec![Ok(()), Err(ChaosError::Injected(DocumentId::from_bytes([1; 20]),)), Ok(()), Ok(()), Err(ChaosError::Injected(DocumentId::from_bytes([4; 20]),)), Ok(()),],
+        "Error ordering results",
+      );
+      let report = coll.write_documents_reported(&tiers,).await
+        .expect("Error writing tiers");
+      assert_eq!(
+        report.iter().map(|(id, res,),| (id.as_bytes()[0], res.is_ok(),),).collect::<Vec<_>>(),
+        [(0, true,), (1, false,), (2, true,), (3, true,), (4, false,), (5, true,),],
+        "Error reporting writes",
+      );
+      assert_eq!(coll.get_document(&DocumentId::from_bytes([5; 20]),).await, Ok(tiers[5]), "Error writing tier",);
+      assert_eq!(coll.write_documents(Vec::<TierMeta>::new(),).await, Ok(Ok(())), "Error writing empty batch",);
+    },);
+  }
+  #[cfg(not(target_arch = "wasm32",),)]
+  #[test]
+  fn test_latency() {
+    const LATENCY: Duration = Duration::from_millis(10,);
+    let batch = (0..40).map(|i,| tier(i, None,),).collect::<Vec<_>>();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+      .enable_time()
+      .start_paused(true,)
+      .build()
+      .expect("Error starting runtime");
+    let elapsed = |concurrency: usize,| {
+      let slow = LatencyCollection::new(ChaosCollection::<TierMeta,>::new(), tokio::time::sleep, |_: &DocumentId,| LATENCY,);
+      let coll = ParallelWrites::new(slow.clone(), NonZeroUsize::new(concurrency,).unwrap(),);
+
+      runtime.block_on(async {
+        let start = tokio::time::Instant::now();
+
+        coll.write_documents(&batch,).await
+          .expect("Error writing batch")
+          .expect("Error writing batch");
+        assert_eq!(slow.peak_in_flight(), concurrency, "Error limiting writes in flight",);
+
+        start.elapsed()
+      },)
+    };
+
+    //The naive batch writes one document after another.
+    let naive = LatencyCollection::new(ChaosCollection::<TierMeta,>::new(), tokio::time::sleep, |_: &DocumentId,| LATENCY,);
+    let sequential = runtime.block_on(async {
+      let start = tokio::time::Instant::now();
+
+      naive.write_documents(&batch,).await
+        .expect("Error writing batch")
+        .expect("Error writing batch");
+
+      start.elapsed()
+    },);
+    assert_eq!(sequential, LATENCY * 40, "Error naive batch is not sequential",);
+
+    assert_eq!(elapsed(1,), LATENCY * 40, "Error timing one write in flight",);
+    assert_eq!(elapsed(4,), LATENCY * 10, "Error timing four writes in flight",);
+    assert_eq!(elapsed(8,), LATENCY * 5, "Error timing eight writes in flight",);
+  }
+  #[test]
+  fn test_duplicates() {
+    let inner = ChaosCollection::new();
+    let coll = ParallelWrites::new(inner.clone(), NonZeroUsize::new(4,).unwrap(),);
+
+    block_on(async {
+      coll.write_documents([tier(1, None,), tier(1, Some(2,),), tier(2, None,), tier(1, Some(3,),),],).await
+        .expect("Error writing tiers")
+        .expect("Error writing tiers");
+      assert_eq!(inner.get_document(&DocumentId::from_bytes([1; 20]),).await, Ok(tier(1, Some(3,),)), "Error last write did not win",);
+    },);
+  }
+}
@@ -0,0 +1,537 @@
+//! Defines a document collection stored as JSONB in a PostgreSQL table.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{
+  DocumentId, Document, Card, TierDocument, CollectionError, ErrorKind, TierListCollection, WatchableCollection,
+  ChangeEvent, ChangeKind, AtomicCounters, Counters, CounterField,
+};
+use futures::{
+  Future, StreamExt,
+  future::{self, BoxFuture,},
+  stream::{self, BoxStream,},
+};
+use serde::{Serialize, de::DeserializeOwned,};
+use sqlx::{
+  Row,
+  postgres::{PgPool, PgListener, PgRow,},
+  types::Json,
+};
+use std::{
+  fmt,
+  error::Error,
+  convert::TryInto,
+  collections::{HashMap, HashSet, hash_map::Entry,},
+  borrow::Borrow,
+  marker::PhantomData,
+  sync::Arc,
+};
+
+/// The errors returned by a `PgCollection`.
+#[derive(PartialEq, Eq, Clone, Debug,)]
+pub enum PgError {
+  /// The document does not exist.
+  NotFound(DocumentId),
+  /// The stored body could not be decoded as a document.
+  Corrupt(DocumentId),
+  /// A query failed.
+  Database(ErrorKind, String),
+}
+
+impl fmt::Display for PgError {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      PgError::NotFound(id) => write!(fmt, "could not find a document with the id {:?}", id,),
+      PgError::Corrupt(id) => write!(fmt, "the body of the document with the id {:?} could not be decoded", id,),
+      PgError::Database(_, message) => write!(fmt, "the query failed: {}", message,),
+    }
+  }
+}
+
+impl Error for PgError {}
+
+impl CollectionError for PgError {
+  #[inline]
+  fn kind(&self,) -> ErrorKind {
+    match self {
+      PgError::NotFound(_) => ErrorKind::NotFound,
+      PgError::Corrupt(_) => ErrorKind::Corrupt,
+      PgError::Database(kind, _) => *kind,
+    }
+  }
+}
+
+/// Classifies the SQLSTATE code of a failed query.
+fn code_kind(code: &str,) -> ErrorKind {
+  match code {
+    //Serialisation failures and deadlocks.
+    "40001" | "40P01" => ErrorKind::Conflict,
+    //Cancelled by a statement timeout.
+    "57014" => ErrorKind::Timeout,
+    //Connection exceptions, insufficient resources and the server shutting down.
+    _ if code.starts_with("08",) || code.starts_with("53",) || code.starts_with("57P0",) => ErrorKind::Transient,
+    _ => ErrorKind::Permanent,
+  }
+}
+
+/// Converts the error from a failed query.
+fn query_error(error: sqlx::Error,) -> PgError {
+  let kind = match &error {
+    sqlx::Error::Database(e) => e.code().map_or(ErrorKind::Permanent, |code,| code_kind(&code,),),
+    sqlx::Error::Io(_) | sqlx::Error::Tls(_) | sqlx::Error::WorkerCrashed => ErrorKind::Transient,
+    sqlx::Error::PoolTimedOut => ErrorKind::Timeout,
+    sqlx::Error::ColumnDecode { .. } | sqlx::Error::Decode(_) => ErrorKind::Corrupt,
+    _ => ErrorKind::Permanent,
+  };
+
+  PgError::Database(kind, error.to_string(),)
+}
+
+/// Parses the payload of a change notification.
+fn change(payload: &str,) -> Option<(DocumentId, ChangeKind,)> {
+  let (id, kind,) = payload.split_once(':',)?;
+  let kind = match kind {
+    "created" => ChangeKind::Created,
+    "updated" => ChangeKind::Updated,
+    _ => return None,
+  };
+
+  Some((id.parse().ok()?, kind,))
+}
+
+/// Gets the identifier of the document stored in a row.
+fn row_id(row: &PgRow,) -> Option<DocumentId> {
+  row.try_get::<Vec<u8>, _>("id",).ok()?.try_into().ok()
+}
+
+/// Decodes the document stored in a row.
+fn row_document<D,>(id: DocumentId, row: &PgRow,) -> Result<D, PgError>
+  where D: DeserializeOwned, {
+  row.try_get::<Json<D>, _>("body",).map(|Json(document,),| document,).map_err(|_,| PgError::Corrupt(id,),)
+}
+
+/// A `TierListCollection` whose documents are stored as JSONB in a PostgreSQL table.
+/// 
+/// The table has the columns `id BYTEA PRIMARY KEY` and `body JSONB` and can be created
+/// with `create_table`. Cloning a `PgCollection` shares its connection pool so cursors
+/// can own a `PgCollection` cheaply.
+/// 
+/// Every write notifies the channel named after the table with the hex identifier of
+/// the written document, which is how `watch` sees changes made through any
+/// `PgCollection` using the same table, including those in other processes. Watching
+/// starts when the returned stream is first polled.
+pub struct PgCollection<D,> {
+  /// The connection pool to query with.
+  pool: PgPool,
+  /// The name of the table storing the documents.
+  table: Arc<str>,
+  /// The type of the documents stored.
+  document: PhantomData<fn() -> D>,
+}
+
+impl<D,> PgCollection<D,> {
+  /// Returns a new `PgCollection`.
+  /// 
+  /// # Params
+  /// 
+  /// pool --- The connection pool to query with.  
+  /// table --- The name of the table storing the documents.  
+  pub fn new(pool: PgPool, table: &str,) -> Self {
+    Self { pool, table: table.into(), document: PhantomData, }
+  }
+  /// Gets the connection pool queries are made with.
+  #[inline]
+  pub const fn get_pool(&self,) -> &PgPool { &self.pool }
+  /// Gets the name of the table storing the documents.
+  #[inline]
+  pub fn get_table(&self,) -> &str { &self.table }
+  /// Gets the quoted name of the table for use in queries.
+  fn quoted(&self,) -> String { format!("\"{}\"", self.table.replace('"', "\"\"",),) }
+  /// Creates the table storing the documents if it does not exist.
+  pub async fn create_table(&self,) -> Result<(), PgError> {
+    sqlx::query(&format!("CREATE TABLE IF NOT EXISTS {} (id BYTEA PRIMARY KEY, body JSONB NOT NULL)", self.quoted(),),)
+    .execute(&self.pool,).await
+    .map(|_,| (),)
+    .map_err(query_error,)
+  }
+}
+
+impl<D,> Clone for PgCollection<D,> {
+  #[inline]
+  fn clone(&self,) -> Self { Self { pool: self.pool.clone(), table: self.table.clone(), document: PhantomData, } }
+}
+
+impl<D,> fmt::Debug for PgCollection<D,> {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    fmt.debug_struct("PgCollection",)
+    .field("pool", &self.pool,)
+    .field("table", &self.table,)
+    .finish()
+  }
+}
+
+impl<D,> PgCollection<D,>
+  where D: Document + Serialize + Send + Sync, {
+  /// Upserts documents and notifies watchers in one transaction.
+  /// 
+  /// # Params
+  /// 
+  /// documents --- The documents to write, with distinct identifiers.  
+  async fn upsert(&self, documents: &[&D],) -> Result<(), PgError> {
+    let ids = documents.iter().map(|doc,| doc.get_id().as_bytes().to_vec(),).collect::<Vec<_>>();
+    let bodies = documents.iter().map(Json,).collect::<Vec<_>>();
+    let query = format!(
+      "WITH written AS (
+        INSERT INTO {} (id, body) SELECT * FROM UNNEST($1::BYTEA[], $2::JSONB[])
+        ON CONFLICT (id) DO UPDATE SET body = EXCLUDED.body
+        RETURNING id, xmax = 0 AS created
+      )
+      SELECT pg_notify($3, encode(id, 'hex') || CASE WHEN created THEN ':created' ELSE ':updated' END) FROM written",
+      self.quoted(),
+    );
+    let mut transaction = self.pool.begin().await.map_err(query_error,)?;
+
+    sqlx::query(&query,).bind(ids,).bind(bodies,).bind(&*self.table,)
+    .execute(&mut *transaction,).await
+    .map_err(query_error,)?;
+    transaction.commit().await.map_err(query_error,)
+  }
+}
+
+impl<D,> TierListCollection for PgCollection<D,>
+  where D: 'static + Document + Serialize + DeserializeOwned + Clone + Send + Sync, {
+  type Document = D;
+  type Error = PgError;
+  type GetBatchDocuments = BoxFuture<'static, Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>>;
+  type GetDocument = BoxFuture<'static, Result<Self::Document, Self::Error>>;
+  type WriteBatchDocuments = BoxFuture<'static, Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>;
+  type WriteDocument = BoxFuture<'static, Result<(), Self::Error>>;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    let ids = ids.into_iter().map(|id,| *id.borrow(),).collect::<Vec<_>>();
+    let coll = self.clone();
+
+    Box::pin(async move {
+      let keys = ids.iter().map(|id,| id.as_bytes().to_vec(),).collect::<Vec<_>>();
+      let rows = sqlx::query(&format!("SELECT id, body FROM {} WHERE id = ANY($1)", coll.quoted(),),)
+        .bind(keys,)
+        .fetch_all(&coll.pool,).await
+        .map_err(query_error,)?;
+      let rows = rows.iter().filter_map(|row,| Some((row_id(row,)?, row,)),).collect::<HashMap<_, _>>();
+
+      //Rows are returned in any order so they are put back into the requested order.
+      Ok(ids.iter().map(|id,| match rows.get(id,) {
+        Some(row) => row_document(*id, row,),
+        None => Err(PgError::NotFound(*id,)),
+      },).collect())
+    },)
+  }
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    let (coll, id,) = (self.clone(), *id,);
+
+    Box::pin(async move {
+      let row = sqlx::query(&format!("SELECT body FROM {} WHERE id = $1", coll.quoted(),),)
+        .bind(id.as_bytes().to_vec(),)
+        .fetch_optional(&coll.pool,).await
+        .map_err(query_error,)?;
+
+      row_document(id, &row.ok_or(PgError::NotFound(id,),)?,)
+    },)
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let documents = documents.into_iter().map(|doc,| doc.borrow().clone(),).collect::<Vec<_>>();
+    let coll = self.clone();
+
+    Box::pin(async move {
+      //An upsert cannot write the same row twice so only the last write of each
+      //document is sent.
+      let (mut index, mut distinct,) = (HashMap::with_capacity(documents.len(),), Vec::with_capacity(documents.len(),),);
+      for document in &documents {
+        match index.entry(*document.get_id(),) {
+          Entry::Occupied(entry) => distinct[*entry.get()] = document,
+          Entry::Vacant(entry) => { entry.insert(distinct.len(),); distinct.push(document,) },
+        }
+      }
+
+      coll.upsert(&distinct,).await.map(Ok,)
+    },)
+  }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    let (coll, document,) = (self.clone(), document.borrow().clone(),);
+
+    Box::pin(async move { coll.upsert(&[&document],).await },)
+  }
+  fn exists(&self, id: &DocumentId,) -> impl Future<Output = Result<bool, Self::Error>> {
+    let (coll, id,) = (self.clone(), *id,);
+
+    async move {
+      sqlx::query_scalar(&format!("SELECT EXISTS (SELECT 1 FROM {} WHERE id = $1)", coll.quoted(),),)
+      .bind(id.as_bytes().to_vec(),)
+      .fetch_one(&coll.pool,).await
+      .map_err(query_error,)
+    }
+  }
+}
+
+/// A document whose counters are stored as JSON numbers in its body.
+pub trait JsonCounters: Counters {
+  /// The path to the object holding the counters in the JSON body of the document; the
+  /// counters are the fields of that object named by `CounterField::name`.
+  const COUNTERS_PATH: &'static [&'static str];
+}
+
+impl JsonCounters for Card {
+  const COUNTERS_PATH: &'static [&'static str] = &[];
+}
+
+impl JsonCounters for TierDocument {
+  const COUNTERS_PATH: &'static [&'static str] = &["Card",];
+}
+
+/// Increments are applied by a single `UPDATE` of the JSON body so they never race.
+impl<D,> AtomicCounters for PgCollection<D,>
+  where D: 'static + Document + JsonCounters + Serialize + DeserializeOwned + Clone + Send + Sync, {
+  type Increment = BoxFuture<'static, Result<u64, Self::Error>>;
+
+  fn increment_field(&self, id: &DocumentId, field: CounterField, delta: i64,) -> Self::Increment {
+    let (coll, id,) = (self.clone(), *id,);
+    let path = D::COUNTERS_PATH.iter().copied().chain([field.name(),],).collect::<Vec<_>>();
+
+    Box::pin(async move {
+      let query = format!(
+        "WITH incremented AS (
+          UPDATE {} SET body = jsonb_set(body, $2::TEXT[], to_jsonb(LEAST(GREATEST((body #>> $2::TEXT[])::NUMERIC + $3, 0), 18446744073709551615)))
+          WHERE id = $1 AND jsonb_typeof(body #> $2::TEXT[]) = 'number'
+          RETURNING id, body #>> $2::TEXT[] AS value
+        )
+        SELECT value, pg_notify($4, encode(id, 'hex') || ':updated')::TEXT AS notified FROM incremented",
+        coll.quoted(),
+      );
+      let mut transaction = coll.pool.begin().await.map_err(query_error,)?;
+      let value = sqlx::query_scalar::<_, String>(&query,)
+        .bind(id.as_bytes().to_vec(),).bind(&path,).bind(delta,).bind(&*coll.table,)
+        .fetch_optional(&mut *transaction,).await
+        .map_err(query_error,)?;
+
+      transaction.commit().await.map_err(query_error,)?;
+      match value {
+        Some(value) => value.parse().map_err(|_,| PgError::Corrupt(id,),),
+        None => Err(if coll.exists(&id,).await? {
+          PgError::Database(ErrorKind::Permanent, format!("the document with the id {:?} has no counters", id,),)
+        } else { PgError::NotFound(id,) }),
+      }
+    },)
+  }
+}
+
+/// Changes are delivered without the written document.
+impl<D,> WatchableCollection for PgCollection<D,>
+  where D: 'static + Document + Serialize + DeserializeOwned + Clone + Send + Sync, {
+  type Watch = BoxStream<'static, ChangeEvent<D>>;
+
+  fn watch(&self, ids: &[&DocumentId],) -> Self::Watch {
+    let ids = ids.iter().map(|&&id,| id,).collect::<HashSet<_>>();
+    let coll = self.clone();
+    let listen = async move {
+      let mut listener = PgListener::connect_with(&coll.pool,).await.ok()?;
+
+      listener.listen(&coll.table,).await.ok()?;
+      Some(listener.into_stream())
+    };
+
+    stream::once(listen,).filter_map(future::ready,).flatten()
+    .filter_map(move |notification,| future::ready(match notification.ok().and_then(|notification,| change(notification.payload(),),) {
+      Some((id, kind,)) if ids.contains(&id,) => Some(ChangeEvent { id, kind, document: None, }),
+      _ => None,
+    },),)
+    .boxed()
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{CardId, CardStatus, TierId,};
+  use crate::TierListCollectionSend;
+  use serde::Deserialize;
+  use sqlx::postgres::PgPoolOptions;
+  use std::{
+    time::Duration,
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher,},
+  };
+
+  /// A document stored by the tests.
+  #[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize,)]
+  struct Doc {
+    id: DocumentId,
+    name: String,
+  }
+
+  impl Document for Doc {
+    #[inline]
+    fn get_id(&self,) -> &DocumentId { &self.id }
+  }
+
+  fn doc(i: u8, name: &str,) -> Doc { Doc { id: DocumentId::from_bytes([i; 20]), name: name.to_owned(), } }
+
+  #[test]
+  fn test_change() {
+    assert_eq!(change(&format!("{}:created", DocumentId::from_bytes([0xab; 20]),),), Some((DocumentId::from_bytes([0xab; 20]), ChangeKind::Created,)), "Error parsing creation",);
+    assert_eq!(change(&format!("{}:updated", DocumentId::from_bytes([1; 20]),),), Some((DocumentId::from_bytes([1; 20]), ChangeKind::Updated,)), "Error parsing update",);
+    assert_eq!(change(&format!("{}:deleted", DocumentId::from_bytes([1; 20]),),), None, "Error parsing unknown kind",);
+    assert_eq!(change("abc:created",), None, "Error parsing short id",);
+    assert_eq!(change("zz",), None, "Error parsing malformed payload",);
+  }
+  #[test]
+  fn test_code_kind() {
+    assert_eq!(code_kind("40001",), ErrorKind::Conflict, "Error classifying serialisation failure",);
+    assert_eq!(code_kind("08006",), ErrorKind::Transient, "Error classifying connection failure",);
+    assert_eq!(code_kind("57014",), ErrorKind::Timeout, "Error classifying cancellation",);
+    assert_eq!(code_kind("42P01",), ErrorKind::Permanent, "Error classifying missing table",);
+  }
+  /// Runs against the database at `DATABASE_URL` when it is set.
+  #[test]
+  fn test_database() {
+    let url = match std::env::var("DATABASE_URL",) {
+      Ok(url) => url,
+      Err(_) => return,
+    };
+    let runtime = tokio::runtime::Runtime::new()
+      .expect("Error starting runtime");
+
+    runtime.block_on(async {
+      let pool = PgPoolOptions::new().max_connections(4,).connect(&url,).await
+        .expect("Error connecting to the database");
+      let table = format!("galileo_{:x}", RandomState::new().build_hasher().finish(),);
+      let coll = PgCollection::<Doc,>::new(pool.clone(), &table,);
+      let watch = coll.watch(&[&DocumentId::from_bytes([1; 20]), &DocumentId::from_bytes([2; 20]),],);
+
+      coll.create_table().await
+        .expect("Error creating table");
+      //Start listening before writing.
+      let events = tokio::spawn(watch.take(4,).collect::<Vec<_>>(),);
+      tokio::time::sleep(Duration::from_millis(200,),).await;
+
+      let docs = (0..5u8).map(|i,| doc(i, "first",),).collect::<Vec<_>>();
+      assert_eq!(coll.write_documents(&docs,).await, Ok(Ok(())), "Error writing documents",);
+
+      //Documents are returned in the requested order.
+      assert_eq!(
+        coll.get_documents([DocumentId::from_bytes([4; 20]), DocumentId::from_bytes([1; 20]), DocumentId::from_bytes([9; 20]), DocumentId::from_bytes([4; 20]), DocumentId::from_bytes([0; 20]),],).await,
+        Ok(vec![Ok(docs[4].clone()), Ok(docs[1].clone()), Err(PgError::NotFound(DocumentId::from_bytes([9; 20]),)), Ok(docs[4].clone()), Ok(docs[0].clone()),]),
+        "Error reordering documents",
+      );
+
+      //Upserts overwrite existing documents and keep the last of duplicates.
+      let updated = [doc(1, "second",), doc(2, "second",), doc(2, "third",), doc(7, "first",),];
+      assert_eq!(coll.write_documents(&updated,).await, Ok(Ok(())), "Error upserting documents",);
+      assert_eq!(
+        coll.get_documents([DocumentId::from_bytes([1; 20]), DocumentId::from_bytes([2; 20]), DocumentId::from_bytes([7; 20]), DocumentId::from_bytes([3; 20]),],).await,
+        Ok(vec![Ok(updated[0].clone()), Ok(updated[2].clone()), Ok(updated[3].clone()), Ok(docs[3].clone()),]),
+        "Error overwriting documents",
+      );
+      coll.write_document(&doc(3, "fourth",),).await
+        .expect("Error writing document");
+      assert_eq!(coll.get_document(&DocumentId::from_bytes([3; 20]),).await, Ok(doc(3, "fourth",)), "Error overwriting document",);
+
+      assert_eq!(coll.exists(&DocumentId::from_bytes([7; 20]),).await, Ok(true), "Error document does not exist",);
+      assert_eq!(coll.exists(&DocumentId::from_bytes([8; 20]),).await, Ok(false), "Error document exists",);
+
+      //Bodies which are not documents are corrupt.
+      sqlx::query(&format!("INSERT INTO {} (id, body) VALUES ($1, '[1, 2]')", coll.quoted(),),)
+        .bind(vec![8u8; 20],)
+        .execute(&pool,).await
+        .expect("Error writing corrupt row");
+      assert_eq!(coll.get_document(&DocumentId::from_bytes([8; 20]),).await, Err(PgError::Corrupt(DocumentId::from_bytes([8; 20]),)), "Error reading corrupt document",);
+
+      //Watchers see the writes to the watched documents in order.
+      let events = events.await.expect("Error watching");
+      for id in [DocumentId::from_bytes([1; 20]), DocumentId::from_bytes([2; 20]),] {
+        assert_eq!(
+          events.iter().filter(|event,| event.id == id,).map(|event,| (event.kind, event.document.clone(),),).collect::<Vec<_>>(),
+          vec![(ChangeKind::Created, None,), (ChangeKind::Updated, None,),],
+          "Error watching writes",
+        );
+      }
+
+      //The collection can be used from spawned tasks.
+      let spawned = coll.clone();
+      let cursor = tokio::spawn(async move {
+        spawned.get_cursor_send::<Doc,>(&DocumentId::from_bytes([7; 20]),).await.map(|cursor,| cursor.get_item().clone(),)
+      },);
+      assert_eq!(cursor.await.expect("Error joining task"), Ok(updated[3].clone()), "Error reading on another task",);
+
+      sqlx::query(&format!("DROP TABLE {}", coll.quoted(),),).execute(&pool,).await
+        .expect("Error dropping table");
+    },);
+  }  /// Runs against the database at `DATABASE_URL` when it is set.
+  #[test]
+  fn test_database_counters() {
+    let url = match std::env::var("DATABASE_URL",) {
+      Ok(url) => url,
+      Err(_) => return,
+    };
+    let runtime = tokio::runtime::Runtime::new()
+      .expect("Error starting runtime");
+
+    runtime.block_on(async {
+      let pool = PgPoolOptions::new().max_connections(8,).connect(&url,).await
+        .expect("Error connecting to the database");
+      let table = format!("galileo_{:x}", RandomState::new().build_hasher().finish(),);
+      let coll = PgCollection::<TierDocument,>::new(pool.clone(), &table,);
+      let card = Card {
+        id: CardId::from_bytes([1; 20]),
+        name: "card".to_owned(),
+        description: String::new(),
+        up_votes: 0,
+        down_votes: 2,
+        bias: 0,
+        status: CardStatus::Open,
+        history: Vec::new(),
+        previous_card: None,
+        next_card: None,
+      };
+      let tier = crate::TierMeta::new(TierId::from_bytes([2; 20]), (None, CardId::from_bytes([2; 20]), CardId::from_bytes([2; 20]),), None, None,);
+
+      coll.create_table().await
+        .expect("Error creating table");
+      coll.write_documents([TierDocument::from(card.clone(),), tier.into(),],).await
+        .expect("Error writing documents")
+        .expect("Error writing documents");
+
+      //Every concurrent increment is applied.
+      let increments = (0..100).map(|_,| tokio::spawn(coll.increment_field(&card.id, CounterField::UpVotes, 1,),),)
+        .collect::<Vec<_>>();
+      for increment in increments {
+        increment.await.expect("Error joining task")
+          .expect("Error incrementing counter");
+      }
+      assert_eq!(coll.increment_field(&card.id, CounterField::DownVotes, -5,).await, Ok(0), "Error saturating counter",);
+      assert_eq!(
+        coll.get_document(&card.id,).await,
+        Ok(Card { up_votes: 100, down_votes: 0, ..card.clone() }.into()),
+        "Error lost increments",
+      );
+      assert_eq!(
+        coll.increment_field(&DocumentId::from_bytes([2; 20]), CounterField::Bias, 1,).await.map_err(|e,| e.kind(),),
+        Err(ErrorKind::Permanent),
+        "Error incrementing a tier",
+      );
+      assert_eq!(
+        coll.increment_field(&DocumentId::from_bytes([3; 20]), CounterField::Bias, 1,).await,
+        Err(PgError::NotFound(DocumentId::from_bytes([3; 20]),)),
+        "Error incrementing a missing document",
+      );
+
+      sqlx::query(&format!("DROP TABLE {}", coll.quoted(),),).execute(&pool,).await
+        .expect("Error dropping table");
+    },);
+  }
+}
@@ -0,0 +1,208 @@
+//! Defines a stream of documents fetched from a collection in chunks with one chunk of
+//! read-ahead.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, TierListCollection,};
+use futures::{Future, Stream,};
+use std::{
+  fmt,
+  error::Error,
+  vec,
+  iter::Fuse,
+  pin::Pin,
+  task::{Context, Poll,},
+  borrow::Borrow,
+};
+
+/// The error when a `Prefetch` stream is given a chunk size of zero.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub struct EmptyChunk;
+
+impl fmt::Display for EmptyChunk {
+  #[inline]
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result { write!(fmt, "the chunk size of a prefetch must be greater than zero",) }
+}
+
+impl Error for EmptyChunk {}
+
+/// A stream of the documents with a sequence of identifiers, returned by
+/// `TierListCollection::prefetch_ids`.
+/// 
+/// The identifiers are pulled from the iterator a chunk at a time and each chunk is
+/// fetched using a single batch read. The documents are yielded in the order of the
+/// identifiers and the next chunk is fetched while the documents of the current chunk
+/// are being yielded.
+pub struct Prefetch<'a, Coll, I,>
+  where Coll: TierListCollection, {
+  /// The collection to fetch the documents from.
+  collection: &'a Coll,
+  /// The identifiers which have not been fetched yet.
+  ids: Fuse<I>,
+  /// The number of identifiers in each chunk.
+  chunk: usize,
+  /// The documents of the current chunk which have not been yielded yet.
+  ready: vec::IntoIter<Result<Coll::Document, Coll::Error>>,
+  /// The length and batch read of the chunk being fetched.
+  fetching: Option<(usize, Pin<Box<Coll::GetBatchDocuments>>,)>,
+  /// The documents of the next chunk once it has been fetched.
+  fetched: Option<Vec<Result<Coll::Document, Coll::Error>>>,
+}
+
+impl<'a, Coll, I, B,> Prefetch<'a, Coll, I,>
+  where Coll: TierListCollection,
+    I: Iterator<Item = B>,
+    B: Borrow<DocumentId>, {
+  /// Returns a new `Prefetch` stream which starts fetching when it is first polled.
+  /// 
+  /// Fails with `EmptyChunk` if `chunk` is zero.
+  /// 
+  /// # Params
+  /// 
+  /// collection --- The collection to fetch the documents from.  
+  /// ids --- The identifiers of the documents in the collection.  
+  /// chunk --- The number of identifiers in each batch read.  
+  pub fn new(collection: &'a Coll, ids: I, chunk: usize,) -> Result<Self, EmptyChunk> {
+    if chunk == 0 { return Err(EmptyChunk) }
+
+    Ok(Self { collection, ids: ids.fuse(), chunk, ready: Vec::new().into_iter(), fetching: None, fetched: None, })
+  }
+  /// Starts fetching the next chunk of identifiers if there are any.
+  fn fetch(&mut self,) {
+    let ids = self.ids.by_ref().take(self.chunk,).map(|id,| *id.borrow(),).collect::<Vec<_>>();
+
+    if !ids.is_empty() {
+      self.fetching = Some((ids.len(), Box::pin(self.collection.get_documents(ids,),),),);
+    }
+  }
+}
+
+//The identifiers are never pinned and the batch read is boxed.
+impl<Coll, I,> Unpin for Prefetch<'_, Coll, I,>
+  where Coll: TierListCollection, {}
+
+impl<Coll, I, B,> Stream for Prefetch<'_, Coll, I,>
+  where Coll: TierListCollection,
+    Coll::Error: Clone,
+    I: Iterator<Item = B>,
+    B: Borrow<DocumentId>, {
+  type Item = Result<Coll::Document, Coll::Error>;
+
+  fn poll_next(self: Pin<&mut Self>, cx: &mut Context,) -> Poll<Option<Self::Item>> {
+    let this = self.get_mut();
+
+    loop {
+      if this.fetching.is_none() && this.fetched.is_none() { this.fetch() }
+
+      if let Some((len, fetching,)) = &mut this.fetching {
+        if let Poll::Ready(res) = fetching.as_mut().poll(cx,) {
+          //A failed chunk fails each of its documents.
+          this.fetched = Some(match res {
+            Ok(documents) => documents,
+            Err(e) => (0..*len).map(|_,| Err(e.clone(),),).collect(),
+          },);
+          this.fetching = None;
+        }
+      }
+
+      if let Some(document) = this.ready.next() { return Poll::Ready(Some(document,)) }
+
+      match this.fetched.take() {
+        Some(documents) => this.ready = documents.into_iter(),
+        None if this.fetching.is_some() => return Poll::Pending,
+        None => return Poll::Ready(None),
+      }
+    }
+  }
+}
+
+impl<Coll, I,> fmt::Debug for Prefetch<'_, Coll, I,>
+  where Coll: TierListCollection + fmt::Debug, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    fmt.debug_struct("Prefetch",)
+    .field("collection", &self.collection,)
+    .field("chunk", &self.chunk,)
+    .field("ready", &self.ready.len(),)
+    .field("fetching", &self.fetching.as_ref().map(|(len, _,),| len,),)
+    .field("fetched", &self.fetched.as_ref().map(Vec::len,),)
+    .finish()
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{CardId, TierId, TierMeta, MemoryError, RecordingCollection, Op, mock::{ChaosCollection, ChaosError,},};
+  use futures::{executor::block_on, StreamExt,};
+  use std::cell::Cell;
+
+  /// Returns a collection storing tiers with the identifiers `DocumentId::from_bytes([0; 20])` to `DocumentId::from_bytes([len - 1; 20])`.
+  fn tiers(len: u8,) -> (RecordingCollection<ChaosCollection<TierMeta,>>, Vec<TierMeta>,) {
+    let coll = RecordingCollection::new(ChaosCollection::new(),);
+    let tiers = (0..len).map(|i,| TierMeta::new(TierId::from_bytes([i; 20]), (None, CardId::from_bytes([i; 20]), CardId::from_bytes([i; 20]),), None, None,),).collect::<Vec<_>>();
+
+    block_on(coll.get_inner().write_documents(&tiers,),)
+      .expect("Error writing tiers")
+      .expect("Error writing tiers");
+
+    (coll, tiers,)
+  }
+
+  #[test]
+  fn test_prefetch() {
+    let (coll, tiers,) = tiers(6,);
+    let pulled = Cell::new(0,);
+    let ids = [0, 1, 9, 2, 3, 4, 5,].iter().map(|&i,| { pulled.set(pulled.get() + 1,); DocumentId::from_bytes([i; 20]) },);
+    let mut stream = coll.prefetch_ids(ids, 3,).expect("Error prefetching");
+
+    block_on(async {
+      assert_eq!(pulled.get(), 0, "Error pulled ids before polling",);
+      assert_eq!(stream.next().await, Some(Ok(tiers[0])), "Error yielding first tier",);
+      //The second chunk is fetched before the first chunk is yielded.
+      assert_eq!(pulled.get(), 6, "Error pulling ids eagerly",);
+      crate::assert_ops!(
+        coll,
+        [Op::GetBatch(vec![DocumentId::from_bytes([0; 20]), DocumentId::from_bytes([1; 20]), DocumentId::from_bytes([9; 20]),]), Op::GetBatch(vec![DocumentId::from_bytes([2; 20]), DocumentId::from_bytes([3; 20]), DocumentId::from_bytes([4; 20]),]),],
+        "Error reading ahead",
+      );
+
+      let rest = stream.by_ref().collect::<Vec<_>>().await;
+      assert_eq!(
+        rest,
+        vec![
+          Ok(tiers[1]),
+          Err(ChaosError::Memory(MemoryError::NotFound(DocumentId::from_bytes([9; 20]),),)),
+          Ok(tiers[2]), Ok(tiers[3]), Ok(tiers[4]), Ok(tiers[5]),
+        ],
+        "Error yielding tiers in order",
+      );
+      crate::assert_ops!(coll, [Op::GetBatch(vec![DocumentId::from_bytes([5; 20]),]),], "Error reading last chunk",);
+      assert_eq!(stream.next().await, None, "Error stream did not end",);
+      assert_eq!(coll.prefetch_ids(Vec::<DocumentId>::new(), 3,).expect("Error prefetching").next().await, None, "Error prefetching no ids",);
+      crate::assert_ops!(coll, [], "Error reading no ids",);
+    },);
+  }
+  #[test]
+  fn test_failed_chunk() {
+    let (coll, tiers,) = tiers(5,);
+
+    coll.get_inner().flaky_batches(1,);
+    let documents = block_on(coll.prefetch_ids(tiers.iter().map(|tier,| tier.id,), 2,).expect("Error prefetching").collect::<Vec<_>>(),);
+    assert_eq!(
+      documents,
+      vec![
+        Err(ChaosError::Injected(DocumentId::from_bytes([0; 20]),)), Err(ChaosError::Injected(DocumentId::from_bytes([0; 20]),)),
+        Ok(tiers[2]), Ok(tiers[3]), Ok(tiers[4]),
+      ],
+      "Error failing chunk",
+    );
+  }
+  #[test]
+  fn test_empty_chunk() {
+    let (coll, _,) = tiers(1,);
+
+    assert_eq!(coll.prefetch_ids([DocumentId::from_bytes([0; 20]),], 0,).err(), Some(EmptyChunk), "Error prefetching with an empty chunk",);
+    crate::assert_ops!(coll, [], "Error reading with an empty chunk",);
+  }
+}
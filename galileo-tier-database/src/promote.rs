@@ -0,0 +1,663 @@
+//! Defines moving a card from one tier to another and promoting cards by their score.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{
+  DocumentId, CardId, TierId, LinkedList, LinkedListMut, BatchReport, TierMeta, Card, TierTransition, ScoreKind, CollectError,
+  WalkLen, DEFAULT_COLLECT_CAP, TierListCollection, TransactionalCollection, Transaction, walk_links, guard_cycles, tier_len,
+};
+use futures::{Stream, StreamExt,};
+use std::{fmt, error::Error, cmp::Ordering, num::NonZeroU64,};
+
+/// Where `move_card` links a card into its new tier.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug,)]
+pub enum Placement {
+  /// At the front of the tier.
+  Front,
+  /// At the back of the tier.
+  Back,
+  /// Before the first card with a lower score, see `insert_sorted`.
+  Sorted(ScoreKind),
+}
+
+/// The errors when moving a card between tiers.
+#[derive(PartialEq, Eq, Clone, Debug,)]
+pub enum MoveError<E,> {
+  /// Getting a card failed; nothing was written.
+  Fetch(E),
+  /// A tier reached the card with the identifier twice; nothing was written.
+  Cycle(DocumentId),
+  /// The card with the identifier is not in the source tier; nothing was written.
+  NotInTier(DocumentId),
+  /// Both tiers have the identifier; nothing was written.
+  SameTier(DocumentId),
+  /// A tier has no length and counting it reached the cap; nothing was written.
+  TooLong(u64),
+  /// The card with the first identifier does not link back to the card with the
+  /// second identifier; nothing was written.
+  Inconsistent(DocumentId, DocumentId),
+  /// Writing the changed cards failed as a whole.
+  Write(E),
+  /// Some of the changed cards were not written; the report lists which were.
+  Partial(BatchReport<E,>),
+  /// Committing the changed cards failed; nothing was written.
+  Commit(E),
+}
+
+impl<E,> From<CollectError<E,>> for MoveError<E,> {
+  #[inline]
+  fn from(from: CollectError<E,>,) -> Self {
+    match from {
+      CollectError::Fetch(e) => MoveError::Fetch(e,),
+      CollectError::Cycle(id) => MoveError::Cycle(id,),
+    }
+  }
+}
+
+impl<E,> fmt::Display for MoveError<E,>
+  where E: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      MoveError::Fetch(e) => write!(fmt, "failed to get a card: {}", e,),
+      MoveError::Cycle(id) => write!(fmt, "the card with the id {:?} was reached twice", id,),
+      MoveError::NotInTier(id) => write!(fmt, "the card with the id {:?} is not in the source tier", id,),
+      MoveError::SameTier(id) => write!(fmt, "the card cannot be moved within the tier with the id {:?}", id,),
+      MoveError::TooLong(cap) => write!(fmt, "the tier has more than {} cards", cap,),
+      MoveError::Inconsistent(card, neighbour) => write!(fmt, "the card with the id {:?} does not link back to the card with the id {:?}", card, neighbour,),
+      MoveError::Write(e) => write!(fmt, "failed to write the changed cards: {}", e,),
+      MoveError::Partial(report) => write!(fmt, "only {} of {} changed cards were written", report.succeeded_ids().count(), report.len(),),
+      MoveError::Commit(e) => write!(fmt, "failed to commit the changed cards: {}", e,),
+    }
+  }
+}
+
+impl<E,> Error for MoveError<E,>
+  where E: Error, {}
+
+/// What `check_promotion` did with a card.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug,)]
+pub enum PromotionResult {
+  /// The card was moved to the previous tier.
+  Promoted {
+    /// The tier the card was moved out of.
+    from: TierId,
+    /// The tier the card was moved into.
+    to: TierId,
+    /// The position of the card in its new tier.
+    position: u64,
+  },
+  /// The net score of the card does not exceed the threshold of its tier or the tier
+  /// has no threshold.
+  BelowThreshold,
+  /// The card is in the top tier.
+  TopTier,
+  /// The policy of the tier with the identifier does not accept the card.
+  Blocked(TierId),
+}
+
+/// The errors when promoting a card.
+#[derive(PartialEq, Eq, Clone, Debug,)]
+pub enum PromotionError<E,> {
+  /// Getting a card failed; nothing was written.
+  Fetch(E),
+  /// Getting the previous tier failed; nothing was written.
+  Tier(E),
+  /// Moving the card failed, see `MoveError`; the tiers were not written.
+  Move(MoveError<E,>),
+  /// Writing the tiers failed after the card was moved.
+  WriteTiers(E),
+  /// Recording the promotion in the history of the card failed after it was moved.
+  History(E),
+}
+
+impl<E,> From<MoveError<E,>> for PromotionError<E,> {
+  #[inline]
+  fn from(from: MoveError<E,>,) -> Self { PromotionError::Move(from,) }
+}
+
+impl<E,> fmt::Display for PromotionError<E,>
+  where E: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      PromotionError::Fetch(e) => write!(fmt, "failed to get a card: {}", e,),
+      PromotionError::Tier(e) => write!(fmt, "failed to get the previous tier: {}", e,),
+      PromotionError::Move(e) => e.fmt(fmt,),
+      PromotionError::WriteTiers(e) => write!(fmt, "failed to write the tiers: {}", e,),
+      PromotionError::History(e) => write!(fmt, "failed to record the promotion: {}", e,),
+    }
+  }
+}
+
+impl<E,> Error for PromotionError<E,>
+  where E: Error, {}
+
+/// The changes which move a card between tiers, returned by `plan_move`.
+struct MovePlan {
+  /// The changed cards with the moved card last.
+  cards: Vec<Card>,
+  /// The source tier after the move.
+  from: TierMeta,
+  /// The destination tier after the move.
+  to: TierMeta,
+  /// The position of the card in the destination tier.
+  position: u64,
+}
+
+/// Moves a card from one tier to another and updates both tiers, returning the position
+/// of the card in its new tier.
+/// 
+/// The source tier is walked from its front to find the card so a card which is not in
+/// it fails with `MoveError::NotInTier`; with `Placement::Sorted` the destination tier
+/// is walked from its front as well. A tier of unknown length is counted using
+/// `tier_len` and fails with `MoveError::TooLong` past `DEFAULT_COLLECT_CAP` cards; a
+/// source tier which is emptied is given nil ends. Nothing is written until every changed card has
+/// been read and the changed cards are written using a single batch write.
+/// 
+/// If only some of the cards were written `MoveError::Partial` reports which and both
+/// tiers are updated as if the move succeeded. When only the previous links of the
+/// old neighbours were lost running `repair` from the front of each tier finishes the
+/// move; collections which support transactions should use `move_card_atomic`.
+/// 
+/// The tiers are not written.
+/// 
+/// ```no_run
+/// # use galileo_tier_database::*;
+/// # async fn example(collection: &MemoryCollection<Card,>, card: Card, mut from: TierMeta, mut to: TierMeta,) {
+/// move_card(collection, &card.id, &mut from, &mut to, Placement::Back,).await;
+/// # }
+/// ```
+/// 
+/// Passing the identifier of a tier instead of a card does not compile.
+/// 
+/// ```compile_fail
+/// # use galileo_tier_database::*;
+/// # async fn example(collection: &MemoryCollection<Card,>, mut from: TierMeta, mut to: TierMeta,) {
+/// let tier_id = to.id;
+/// move_card(collection, &tier_id, &mut from, &mut to, Placement::Back,).await;
+/// # }
+/// ```
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the cards.  
+/// card_id --- The identifier of the card to move.  
+/// from --- The tier the card is in.  
+/// to --- The tier to move the card to.  
+/// placement --- Where to link the card into `to`.  
+pub async fn move_card<Coll,>(collection: &Coll, card_id: &CardId, from: &mut TierMeta, to: &mut TierMeta, placement: Placement,) -> Result<u64, MoveError<Coll::Error,>>
+  where Coll: TierListCollection,
+    Coll::Document: Into<Card>,
+    Card: Into<Coll::Document>, {
+  let plan = plan_move(collection, card_id, from, to, placement,).await?;
+  let documents = plan.cards.into_iter().map(Into::<Coll::Document>::into,).collect::<Vec<_>>();
+  let report = collection.write_documents_reported(&documents,).await
+    .map_err(MoveError::Write,)?;
+
+  *from = plan.from;
+  *to = plan.to;
+  if report.is_complete_success() { Ok(plan.position) }
+  else { Err(MoveError::Partial(report,)) }
+}
+
+/// Like `move_card` but the changed cards are committed in a single transaction so
+/// nothing is changed after any error.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the cards.  
+/// card_id --- The identifier of the card to move.  
+/// from --- The tier the card is in.  
+/// to --- The tier to move the card to.  
+/// placement --- Where to link the card into `to`.  
+pub async fn move_card_atomic<Coll,>(collection: &Coll, card_id: &CardId, from: &mut TierMeta, to: &mut TierMeta, placement: Placement,) -> Result<u64, MoveError<Coll::Error,>>
+  where Coll: TransactionalCollection,
+    Coll::Document: Into<Card>,
+    Card: Into<Coll::Document>, {
+  let plan = plan_move(collection, card_id, from, to, placement,).await?;
+  let mut transaction = collection.begin();
+
+  for card in plan.cards { transaction.stage_write(&Into::<Coll::Document>::into(card,),) }
+  transaction.commit().await.map_err(MoveError::Commit,)?;
+
+  *from = plan.from;
+  *to = plan.to;
+  Ok(plan.position)
+}
+
+/// Promotes a card to the previous tier if its net score exceeds the
+/// `promote_threshold` of its tier and returns what was done.
+/// 
+/// Nothing is written if the card is in the top tier, its tier has no threshold or the
+/// policy of the previous tier does not accept it; a previous tier of unknown length
+/// is counted using `tier_len` and is `Blocked` if it has more than
+/// `DEFAULT_COLLECT_CAP` cards. Otherwise the card is moved using `move_card`, both tiers are
+/// written to `tiers` and the promotion is appended to the history of the card.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the cards.  
+/// tiers --- The collection storing the tiers.  
+/// card_id --- The identifier of the card to check.  
+/// current_tier --- The tier the card is in.  
+/// placement --- Where to link the card into the previous tier.  
+pub async fn check_promotion<Coll, Tiers,>(collection: &Coll, tiers: &Tiers, card_id: &CardId, current_tier: &mut TierMeta, placement: Placement,) -> Result<PromotionResult, PromotionError<Coll::Error,>>
+  where Coll: TierListCollection,
+    Coll::Document: Into<Card>,
+    Card: Into<Coll::Document>,
+    Tiers: TierListCollection<Document = TierMeta, Error = Coll::Error>, {
+  let previous_id = match current_tier.previous_tier {
+    Some(id) => id,
+    None => return Ok(PromotionResult::TopTier),
+  };
+  let threshold = match current_tier.promote_threshold {
+    Some(threshold) => threshold,
+    None => return Ok(PromotionResult::BelowThreshold),
+  };
+  let card: Card = collection.get_document(card_id,).await
+    .map_err(PromotionError::Fetch,)?
+    .into();
+  let score = card.score(ScoreKind::Net,);
+  if score <= threshold { return Ok(PromotionResult::BelowThreshold) }
+
+  let mut to = tiers.get_document(&previous_id,).await
+    .map_err(PromotionError::Tier,)?;
+  let len = match tier_len::<Card, _,>(collection, &to, DEFAULT_COLLECT_CAP as u64,).await
+    .map_err(PromotionError::Fetch,)? {
+    WalkLen::Exact(len) => len,
+    //The capacity cannot be checked without the length.
+    WalkLen::AtLeast(_) => return Ok(PromotionResult::Blocked(previous_id,)),
+  };
+  if !to.policy.accepts(len,) { return Ok(PromotionResult::Blocked(previous_id,)) }
+
+  let from_id = current_tier.id;
+  let position = move_card(collection, card_id, current_tier, &mut to, placement,).await?;
+  if let Err(results) = tiers.write_documents([&*current_tier, &to,],).await.map_err(PromotionError::WriteTiers,)? {
+    results.into_iter().collect::<Result<(), _>>().map_err(PromotionError::WriteTiers,)?;
+  }
+
+  let mut card: Card = collection.get_document(card_id,).await
+    .map_err(PromotionError::History,)?
+    .into();
+  card.history.push(TierTransition { from: from_id, to: previous_id, score, },);
+  collection.write_document(&Into::<Coll::Document>::into(card,),).await
+    .map_err(PromotionError::History,)?;
+
+  Ok(PromotionResult::Promoted { from: from_id, to: previous_id, position, })
+}
+
+/// Reads the cards changed by moving a card between tiers and relinks them.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the cards.  
+/// card_id --- The identifier of the card to move.  
+/// from --- The tier the card is in.  
+/// to --- The tier to move the card to.  
+/// placement --- Where to link the card into `to`.  
+async fn plan_move<Coll,>(collection: &Coll, card_id: &CardId, from: &TierMeta, to: &TierMeta, placement: Placement,) -> Result<MovePlan, MoveError<Coll::Error,>>
+  where Coll: TierListCollection,
+    Coll::Document: Into<Card>, {
+  /// Walks the cards of a tier from its front.
+  fn cards<'a, Coll,>(collection: &'a Coll, tier: &TierMeta,) -> impl 'a + Stream<Item = Result<Card, CollectError<Coll::Error,>>>
+    where Coll: TierListCollection,
+      Coll::Document: Into<Card>, {
+    let link = |card: &Card,| card.get_next_id().copied();
+
+    guard_cycles(walk_links(collection, Err(**tier.list_front(),), link,), link,)
+  }
+
+  /// Gets the length of a tier, counting it if it is unknown.
+  async fn len<Coll,>(collection: &Coll, tier: &TierMeta,) -> Result<Option<NonZeroU64>, MoveError<Coll::Error,>>
+    where Coll: TierListCollection,
+      Coll::Document: Into<Card>, {
+    match tier_len::<Card, _,>(collection, tier, DEFAULT_COLLECT_CAP as u64,).await
+      .map_err(MoveError::Fetch,)? {
+      WalkLen::Exact(len) => Ok(NonZeroU64::new(len,)),
+      WalkLen::AtLeast(cap) => Err(MoveError::TooLong(cap,)),
+    }
+  }
+
+  if from.id == to.id { return Err(MoveError::SameTier(*from.id,)) }
+
+  let old_len = len(collection, from,).await?.ok_or(MoveError::NotInTier(**card_id,),)?;
+  let to_len = len(collection, to,).await?;
+  let mut walk = Box::pin(cards(collection, from,),);
+  let mut previous = None::<Card>;
+  let mut card = loop {
+    match walk.next().await {
+      Some(item) => {
+        let item = item?;
+
+        if item.id == *card_id { break item }
+        previous = Some(item,);
+      },
+      None => return Err(MoveError::NotInTier(**card_id,)),
+    }
+  };
+  let mut next = match card.next_card {
+    Some(id) => {
+      let next: Card = collection.get_document(&id,).await
+        .map_err(MoveError::Fetch,)?
+        .into();
+      if next.previous_card != Some(*card_id,) { return Err(MoveError::Inconsistent(*id, **card_id,)) }
+
+      Some(next,)
+    },
+    None => None,
+  };
+
+  //Unlink the card from the source tier; the walk is trusted over its previous link.
+  let previous_id = previous.as_ref().map(|item,| item.id,);
+  let next_id = card.next_card;
+  if let Some(previous) = &mut previous { previous.next_card = next_id }
+  if let Some(next) = &mut next { next.previous_card = previous_id }
+
+  let mut from_tier = *from;
+  let (mut front, mut back,) = (*from.list_front(), *from.list_back(),);
+  if previous_id.is_none() { front = next_id.unwrap_or(front,) }
+  if next_id.is_none() { back = previous_id.unwrap_or(back,) }
+  //An empty tier is given nil ends so it cannot be walked into the destination tier.
+  match NonZeroU64::new(old_len.get() - 1,) {
+    Some(len) => from_tier.set_ends((Some(len,), front, back,),),
+    None => from_tier.set_ends((None, CardId::NIL, CardId::NIL,),),
+  }
+
+  //Find the neighbours of the card in the destination tier.
+  let (mut before, mut after, position,) = match (to_len, placement,) {
+    (None, _,) => (None, None, 0,),
+    (Some(_), Placement::Front,) => {
+      let front: Card = collection.get_document(to.list_front(),).await
+        .map_err(MoveError::Fetch,)?
+        .into();
+
+      (None, Some(front,), 0,)
+    },
+    (Some(len), Placement::Back,) => {
+      let back: Card = collection.get_document(to.list_back(),).await
+        .map_err(MoveError::Fetch,)?
+        .into();
+
+      (Some(back,), None, len.get(),)
+    },
+    (Some(_), Placement::Sorted(order),) => {
+      let mut walk = Box::pin(cards(collection, to,),);
+      let mut before = None;
+      let mut position = 0;
+
+      loop {
+        match walk.next().await {
+          Some(item) => {
+            let item = item?;
+
+            if card.cmp_score(&item, order,) == Ordering::Less { break (before, Some(item,), position,) }
+            before = Some(item,);
+            position += 1;
+          },
+          None => break (before, None, position,),
+        }
+      }
+    },
+  };
+
+  //Link the card into the destination tier.
+  card.detach();
+  card.previous_card = before.as_ref().map(|item,| item.id,);
+  card.next_card = after.as_ref().map(|item,| item.id,);
+  if let Some(before) = &mut before { before.next_card = Some(*card_id,) }
+  if let Some(after) = &mut after { after.previous_card = Some(*card_id,) }
+
+  let mut to_tier = *to;
+  to_tier.set_ends((
+    to_len.map_or(NonZeroU64::new(1,), |len,| len.checked_add(1,),),
+    if before.is_none() { *card_id } else { *to.list_front() },
+    if after.is_none() { *card_id } else { *to.list_back() },
+  ),);
+
+  let cards = previous.into_iter().chain(next,).chain(before,).chain(after,).chain(Some(card,),).collect();
+
+  Ok(MovePlan { cards, from: from_tier, to: to_tier, position, })
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{
+    CardStatus, TierPolicy, Vote, VoteDirection, MemoryCollection, repair, stream_from, cast_vote,
+    mock::{ChaosCollection, ChaosError,},
+  };
+  use futures::{executor::block_on, TryStreamExt,};
+  use std::ops::Range;
+
+  /// Writes a tier of cards with the identifiers and up votes in order.
+  fn write_tier<Coll,>(coll: &Coll, tier: u8, cards: &[(u8, u64,)],) -> TierMeta
+    where Coll: TierListCollection<Document = Card>,
+      Coll::Error: fmt::Debug, {
+    let documents = cards.iter().enumerate().map(|(i, &(id, up_votes,),),| Card {
+      id: CardId::from_bytes([id; 20]),
+      name: "card".to_owned(),
+      description: String::new(),
+      up_votes,
+      down_votes: 0,
+      bias: 0,
+      status: CardStatus::Open,
+      history: Vec::new(),
+      previous_card: i.checked_sub(1,).map(|i,| CardId::from_bytes([cards[i].0; 20]),),
+      next_card: cards.get(i + 1,).map(|&(id, _,),| CardId::from_bytes([id; 20]),),
+    },).collect::<Vec<_>>();
+
+    block_on(coll.write_documents(documents,),)
+      .expect("Error writing cards")
+      .expect("Error writing cards");
+    match (cards.first(), cards.last(),) {
+      (Some(front), Some(back),) => TierMeta::new(TierId::from_bytes([tier; 20]), (NonZeroU64::new(cards.len() as u64,), CardId::from_bytes([front.0; 20]), CardId::from_bytes([back.0; 20]),), None, None,),
+      _ => TierMeta::new(TierId::from_bytes([tier; 20]), (None, CardId::from_bytes([0; 20]), CardId::from_bytes([0; 20]),), None, None,),
+    }
+  }
+  /// Walks the cards both ways from the ends of a tier and checks its length.
+  fn order<Coll,>(coll: &Coll, tier: &TierMeta,) -> Vec<u8>
+    where Coll: TierListCollection<Document = Card>,
+      Coll::Error: fmt::Debug, {
+    let len = match tier.list_len() {
+      Some(len) => len.get() as usize,
+      None => return Vec::new(),
+    };
+    let forward = block_on(stream_from(coll, tier.list_front(),).map_ok(|card,| card.id.as_bytes()[0],).try_collect::<Vec<_>>(),)
+      .expect("Error walking forward");
+    let back = block_on(coll.ref_cursor::<Card>(tier.list_back(),),)
+      .expect("Error reading back");
+    let mut backward = block_on(back.into_stream_back().map_ok(|card,| card.id.as_bytes()[0],).try_collect::<Vec<_>>(),)
+      .expect("Error walking backward");
+
+    backward.reverse();
+    assert_eq!(forward, backward, "Error links disagree",);
+    assert_eq!(forward.len(), len, "Error tier length",);
+    forward
+  }
+
+  #[test]
+  fn test_move_card() {
+    let coll = ChaosCollection::new();
+    let mut from = write_tier(&coll, 1, &[(1, 0,), (2, 0,), (3, 0,),],);
+    let mut to = write_tier(&coll, 2, &[(4, 0,), (5, 0,),],);
+
+    //Moving the front card.
+    assert_eq!(block_on(move_card(&coll, &CardId::from_bytes([1; 20]), &mut from, &mut to, Placement::Back,),), Ok(2), "Error moving the front card",);
+    assert_eq!((order(&coll, &from,), order(&coll, &to,),), (vec![2, 3,], vec![4, 5, 1,],), "Error linking the front card",);
+
+    //Moving the back card.
+    assert_eq!(block_on(move_card(&coll, &CardId::from_bytes([3; 20]), &mut from, &mut to, Placement::Front,),), Ok(0), "Error moving the back card",);
+    assert_eq!((order(&coll, &from,), order(&coll, &to,),), (vec![2,], vec![3, 4, 5, 1,],), "Error linking the back card",);
+
+    //Moving the only card, equal scores are linked after.
+    assert_eq!(block_on(move_card(&coll, &CardId::from_bytes([2; 20]), &mut from, &mut to, Placement::Sorted(ScoreKind::UpVotes,),),), Ok(4), "Error moving the only card",);
+    assert_eq!((order(&coll, &from,), order(&coll, &to,),), (vec![], vec![3, 4, 5, 1, 2,],), "Error emptying the tier",);
+    assert_eq!((from.list_front(), from.list_back(),), (&CardId::NIL, &CardId::NIL,), "Error clearing the ends of the empty tier",);
+    assert_eq!(block_on(from.refresh_len::<Card, _,>(&coll, 10,),), Ok(WalkLen::Exact(0,)), "Error counting the empty tier",);
+
+    //Moving a middle card into an empty tier.
+    assert_eq!(block_on(move_card(&coll, &CardId::from_bytes([5; 20]), &mut to, &mut from, Placement::Back,),), Ok(0), "Error moving a middle card",);
+    assert_eq!((order(&coll, &from,), order(&coll, &to,),), (vec![5,], vec![3, 4, 1, 2,],), "Error linking into an empty tier",);
+
+    //Moving between sorted tiers.
+    let coll = ChaosCollection::new();
+    let mut from = write_tier(&coll, 1, &[(1, 5,), (2, 7,), (3, 1,),],);
+    let mut to = write_tier(&coll, 2, &[(4, 9,), (5, 6,), (6, 2,),],);
+    assert_eq!(block_on(move_card(&coll, &CardId::from_bytes([2; 20]), &mut from, &mut to, Placement::Sorted(ScoreKind::UpVotes,),),), Ok(1), "Error moving a sorted card",);
+    assert_eq!((order(&coll, &from,), order(&coll, &to,),), (vec![1, 3,], vec![4, 2, 5, 6,],), "Error linking a sorted card",);
+
+    //Moving between tiers whose lengths were lost by capped counts.
+    assert_eq!(block_on(from.refresh_len::<Card, _,>(&coll, 1,),), Ok(WalkLen::AtLeast(1,)), "Error refreshing the source",);
+    assert_eq!(block_on(to.refresh_len::<Card, _,>(&coll, 2,),), Ok(WalkLen::AtLeast(2,)), "Error refreshing the destination",);
+    assert_eq!(block_on(move_card(&coll, &CardId::from_bytes([3; 20]), &mut from, &mut to, Placement::Back,),), Ok(4), "Error moving into a tier of unknown length",);
+    assert_eq!((order(&coll, &from,), order(&coll, &to,),), (vec![1,], vec![4, 2, 5, 6, 3,],), "Error orphaning the cards of the tier",);
+  }
+  #[test]
+  fn test_move_errors() {
+    let coll = ChaosCollection::new();
+    let mut from = write_tier(&coll, 1, &[(1, 0,), (2, 0,),],);
+    let mut to = write_tier(&coll, 2, &[(3, 0,),],);
+    let (old_from, old_to,) = (from, to,);
+
+    assert_eq!(block_on(move_card(&coll, &CardId::from_bytes([3; 20]), &mut from, &mut to, Placement::Back,),), Err(MoveError::NotInTier(DocumentId::from_bytes([3; 20]),)), "Error moving a card from another tier",);
+    assert_eq!(block_on(move_card(&coll, &CardId::from_bytes([9; 20]), &mut from, &mut to, Placement::Back,),), Err(MoveError::NotInTier(DocumentId::from_bytes([9; 20]),)), "Error moving a missing card",);
+    let mut same = from;
+    assert_eq!(block_on(move_card(&coll, &CardId::from_bytes([1; 20]), &mut from, &mut same, Placement::Back,),), Err(MoveError::SameTier(DocumentId::from_bytes([1; 20]),)), "Error moving within a tier",);
+    assert_eq!((from, to,), (old_from, old_to,), "Error changing the tiers after an error",);
+    assert_eq!((order(&coll, &from,), order(&coll, &to,),), (vec![1, 2,], vec![3,],), "Error writing after an error",);
+
+    //A failed read writes nothing.
+    coll.flaky_reads(DocumentId::from_bytes([3; 20]), 1,);
+    assert_eq!(block_on(move_card(&coll, &CardId::from_bytes([1; 20]), &mut from, &mut to, Placement::Front,),), Err(MoveError::Fetch(ChaosError::Injected(DocumentId::from_bytes([3; 20]),),)), "Error reading the destination",);
+    assert_eq!((order(&coll, &from,), order(&coll, &to,),), (vec![1, 2,], vec![3,],), "Error writing after a failed read",);
+  }
+  #[test]
+  fn test_move_partial() {
+    //A lost previous link is fixed by a repair.
+    let coll = ChaosCollection::new();
+    let mut from = write_tier(&coll, 1, &[(1, 0,), (2, 0,), (3, 0,),],);
+    let mut to = write_tier(&coll, 2, &[(4, 0,), (5, 0,),],);
+
+    coll.flaky_writes(DocumentId::from_bytes([2; 20]), 1,);
+    match block_on(move_card(&coll, &CardId::from_bytes([1; 20]), &mut from, &mut to, Placement::Back,),) {
+      Err(MoveError::Partial(report)) => assert_eq!(report.failures().map(|(id, _,),| id.as_bytes()[0],).collect::<Vec<_>>(), [2,], "Error reporting the failed write",),
+      res => panic!("Error expected a partial write: {:?}", res,),
+    }
+    assert_eq!((from.list_front(), to.list_back(),), (&CardId::from_bytes([2; 20]), &CardId::from_bytes([1; 20]),), "Error updating the tiers after a partial write",);
+    assert_eq!(order(&coll, &to,), [4, 5, 1,], "Error linking into the destination",);
+
+    let report = block_on(repair::<Card, _,>(&coll, &DocumentId::from_bytes([2; 20]), Some(&mut from,),),)
+      .expect("Error repairing the source");
+    assert!(!report.is_clean(), "Error finding the lost link",);
+    assert_eq!(order(&coll, &from,), [2, 3,], "Error repairing the source",);
+
+    //A failed commit changes nothing.
+    let coll = ChaosCollection::new();
+    let mut from = write_tier(&coll, 1, &[(1, 0,), (2, 0,),],);
+    let mut to = write_tier(&coll, 2, &[],);
+    let (old_from, old_to,) = (from, to,);
+
+    coll.flaky_writes(DocumentId::from_bytes([2; 20]), 1,);
+    assert_eq!(block_on(move_card_atomic(&coll, &CardId::from_bytes([1; 20]), &mut from, &mut to, Placement::Front,),), Err(MoveError::Commit(ChaosError::Injected(DocumentId::from_bytes([2; 20]),),)), "Error failing the commit",);
+    assert_eq!((from, to,), (old_from, old_to,), "Error changing the tiers after a failed commit",);
+    assert_eq!(order(&coll, &from,), [1, 2,], "Error writing after a failed commit",);
+    assert_eq!(block_on(move_card_atomic(&coll, &CardId::from_bytes([1; 20]), &mut from, &mut to, Placement::Front,),), Ok(0), "Error retrying the commit",);
+    assert_eq!((order(&coll, &from,), order(&coll, &to,),), (vec![2,], vec![1,],), "Error committing the move",);
+  }
+
+  /// Writes three linked tiers, promoting out of the bottom two at the thresholds.
+  fn write_tiers(cards: &MemoryCollection<Card,>, tiers: &MemoryCollection<TierMeta,>, thresholds: (i128, i128,),) -> (TierMeta, TierMeta, TierMeta,) {
+    let mut top = write_tier(cards, 1, &[(10, 9,),],);
+    let mut middle = write_tier(cards, 2, &[(20, 8,), (21, 4,),],);
+    let mut bottom = write_tier(cards, 3, &[(30, 0,), (31, 0,),],);
+
+    top.next_tier = Some(middle.id,);
+    middle.previous_tier = Some(top.id,);
+    middle.next_tier = Some(bottom.id,);
+    middle.promote_threshold = Some(thresholds.0,);
+    bottom.previous_tier = Some(middle.id,);
+    bottom.promote_threshold = Some(thresholds.1,);
+    block_on(tiers.write_documents([&top, &middle, &bottom,],),)
+      .expect("Error writing tiers")
+      .expect("Error writing tiers");
+
+    (top, middle, bottom,)
+  }
+  /// Casts an up vote on a card for each voter.
+  fn vote(cards: &MemoryCollection<Card,>, votes: &MemoryCollection<Vote,>, id: &CardId, voters: Range<u32>,) {
+    block_on(async {
+      for voter in voters {
+        cast_vote(cards, votes, id, &format!("voter {}", voter,), VoteDirection::Up,).await
+          .expect("Error casting a vote");
+      }
+    },);
+  }
+
+  #[test]
+  fn test_check_promotion() {
+    let (cards, tiers, votes,) = (MemoryCollection::new(), MemoryCollection::new(), MemoryCollection::new(),);
+    let (mut top, mut middle, mut bottom,) = write_tiers(&cards, &tiers, (5, 2,),);
+    let id = CardId::from_bytes([31; 20]);
+
+    //A score equal to the threshold is not promoted.
+    vote(&cards, &votes, &id, 0..2,);
+    assert_eq!(block_on(check_promotion(&cards, &tiers, &id, &mut bottom, Placement::Back,),), Ok(PromotionResult::BelowThreshold), "Error promoting at the threshold",);
+
+    vote(&cards, &votes, &id, 2..3,);
+    assert_eq!(
+      block_on(check_promotion(&cards, &tiers, &id, &mut bottom, Placement::Back,),),
+      Ok(PromotionResult::Promoted { from: bottom.id, to: middle.id, position: 2, }),
+      "Error promoting out of the bottom tier",
+    );
+    middle = block_on(tiers.get_document(&middle.id,),).expect("Error reading the middle tier");
+    assert_eq!(block_on(tiers.get_document(&bottom.id,),), Ok(bottom), "Error writing the bottom tier",);
+    assert_eq!((order(&cards, &middle,), order(&cards, &bottom,),), (vec![20, 21, 31,], vec![30,],), "Error moving into the middle tier",);
+    assert_eq!(block_on(check_promotion(&cards, &tiers, &id, &mut middle, Placement::Back,),), Ok(PromotionResult::BelowThreshold), "Error promoting below the threshold",);
+
+    vote(&cards, &votes, &id, 3..6,);
+    assert_eq!(
+      block_on(check_promotion(&cards, &tiers, &id, &mut middle, Placement::Sorted(ScoreKind::Net,),),),
+      Ok(PromotionResult::Promoted { from: middle.id, to: top.id, position: 1, }),
+      "Error promoting out of the middle tier",
+    );
+    top = block_on(tiers.get_document(&top.id,),).expect("Error reading the top tier");
+    assert_eq!((order(&cards, &top,), order(&cards, &middle,),), (vec![10, 31,], vec![20, 21,],), "Error moving into the top tier",);
+    assert_eq!(block_on(check_promotion(&cards, &tiers, &id, &mut top, Placement::Back,),), Ok(PromotionResult::TopTier), "Error promoting out of the top tier",);
+
+    let card = block_on(cards.get_document(&id,),).expect("Error reading the card");
+    assert_eq!(
+      card.history,
+      [TierTransition { from: bottom.id, to: middle.id, score: 3, }, TierTransition { from: middle.id, to: top.id, score: 6, },],
+      "Error recording the promotions",
+    );
+  }
+  #[test]
+  fn test_promotion_policy() {
+    let (cards, tiers, votes,) = (MemoryCollection::new(), MemoryCollection::new(), MemoryCollection::new(),);
+    let (_, mut middle, mut bottom,) = write_tiers(&cards, &tiers, (5, 2,),);
+    let id = CardId::from_bytes([31; 20]);
+
+    vote(&cards, &votes, &id, 0..3,);
+    middle.policy = TierPolicy { frozen: true, capacity: None, };
+    block_on(tiers.write_document(&middle,),).expect("Error freezing the tier");
+    assert_eq!(block_on(check_promotion(&cards, &tiers, &id, &mut bottom, Placement::Back,),), Ok(PromotionResult::Blocked(middle.id,)), "Error promoting into a frozen tier",);
+
+    middle.policy = TierPolicy { frozen: false, capacity: NonZeroU64::new(2,), };
+    block_on(tiers.write_document(&middle,),).expect("Error filling the tier");
+    assert_eq!(block_on(check_promotion(&cards, &tiers, &id, &mut bottom, Placement::Back,),), Ok(PromotionResult::Blocked(middle.id,)), "Error promoting into a full tier",);
+    assert_eq!((order(&cards, &middle,), order(&cards, &bottom,),), (vec![20, 21,], vec![30, 31,],), "Error moving a blocked card",);
+
+    //A full tier whose length was lost is counted.
+    assert_eq!(block_on(middle.refresh_len::<Card, _,>(&cards, 1,),), Ok(WalkLen::AtLeast(1,)), "Error refreshing the tier",);
+    block_on(tiers.write_document(&middle,),).expect("Error losing the length");
+    assert_eq!(block_on(check_promotion(&cards, &tiers, &id, &mut bottom, Placement::Back,),), Ok(PromotionResult::Blocked(middle.id,)), "Error promoting into a full tier of unknown length",);
+
+    bottom.promote_threshold = None;
+    assert_eq!(block_on(check_promotion(&cards, &tiers, &id, &mut bottom, Placement::Back,),), Ok(PromotionResult::BelowThreshold), "Error promoting without a threshold",);
+
+    bottom.promote_threshold = Some(2,);
+    middle.policy.capacity = NonZeroU64::new(3,);
+    block_on(tiers.write_document(&middle,),).expect("Error growing the tier");
+    assert!(matches!(block_on(check_promotion(&cards, &tiers, &id, &mut bottom, Placement::Front,),), Ok(PromotionResult::Promoted { position: 0, .. })), "Error promoting into a tier with room",);
+  }
+}
@@ -0,0 +1,214 @@
+//! Defines a document collection which limits the rate of operations on another
+//! collection.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, TierListCollection, Timer, Clock,};
+use futures::{Future, future::LocalBoxFuture,};
+use std::{
+  time::{Duration, SystemTime,},
+  cell::RefCell,
+  borrow::Borrow,
+  rc::Rc,
+};
+
+/// How fast a `RateLimited` collection allows operations.
+#[derive(PartialEq, Clone, Copy, Debug,)]
+pub struct RateLimit {
+  /// The number of documents which can be operated on each second.
+  pub per_second: f64,
+  /// The number of documents which can be operated on at once after the collection has
+  /// been idle.
+  pub burst: u32,
+}
+
+/// A token bucket.
+#[derive(Debug,)]
+struct Bucket {
+  /// The tokens available, negative if tokens have been reserved ahead of time.
+  tokens: f64,
+  /// The time the tokens were last counted.
+  updated: SystemTime,
+}
+
+/// A `TierListCollection` which makes operations on another collection wait until the
+/// rate limit allows them.
+/// 
+/// Each document read or written takes one token; a batch takes a token for each of
+/// its documents. Tokens are taken when the operation is started so operations proceed
+/// in the order they were started, and a batch larger than the burst waits for the
+/// extra tokens rather than failing.
+/// 
+/// Cloning a `RateLimited` collection shares its rate limit.
+#[derive(Clone, Debug,)]
+pub struct RateLimited<Coll, Tm, Ck,> {
+  /// The collection to limit operations on.
+  inner: Coll,
+  /// The timer used to wait for tokens.
+  timer: Tm,
+  /// The clock used to count tokens.
+  clock: Ck,
+  /// The rate operations are allowed.
+  limit: RateLimit,
+  /// The available tokens.
+  bucket: Rc<RefCell<Bucket>>,
+}
+
+impl<Coll, Tm, Ck,> RateLimited<Coll, Tm, Ck,>
+  where Ck: Clock, {
+  /// Returns a new `RateLimited` collection which starts with a full burst of tokens.
+  /// 
+  /// # Params
+  /// 
+  /// inner --- The collection to limit operations on.  
+  /// timer --- The timer used to wait for tokens.  
+  /// clock --- The clock used to count tokens.  
+  /// limit --- The rate operations are allowed.  
+  pub fn new(inner: Coll, timer: Tm, clock: Ck, limit: RateLimit,) -> Self {
+    let bucket = Bucket { tokens: limit.burst as f64, updated: clock.now(), };
+
+    Self { inner, timer, clock, limit, bucket: Rc::new(RefCell::new(bucket,),), }
+  }
+  /// Gets the collection operations are limited on.
+  #[inline]
+  pub const fn get_inner(&self,) -> &Coll { &self.inner }
+  /// Gets the rate operations are allowed.
+  #[inline]
+  pub const fn get_limit(&self,) -> &RateLimit { &self.limit }
+  /// Takes tokens and returns how long to wait until they are available.
+  /// 
+  /// # Params
+  /// 
+  /// tokens --- The number of tokens to take.  
+  fn reserve(&self, tokens: usize,) -> Duration {
+    let now = self.clock.now();
+    let mut bucket = self.bucket.borrow_mut();
+    let elapsed = now.duration_since(bucket.updated,).unwrap_or_default();
+
+    bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * self.limit.per_second).min(self.limit.burst as f64,);
+    bucket.updated = bucket.updated.max(now,);
+    bucket.tokens -= tokens as f64;
+
+    if bucket.tokens >= 0.0 { Duration::ZERO }
+    else { Duration::from_secs_f64(-bucket.tokens / self.limit.per_second,) }
+  }
+}
+
+/// Waits for a delay if there is one.
+async fn wait<Tm,>(timer: &Tm, delay: Duration,)
+  where Tm: Timer, {
+  if !delay.is_zero() { timer.sleep(delay,).await }
+}
+
+impl<Coll, Tm, Ck,> TierListCollection for RateLimited<Coll, Tm, Ck,>
+  where Coll: 'static + TierListCollection + Clone,
+    Coll::Document: Clone,
+    Tm: 'static + Timer + Clone,
+    Ck: Clock, {
+  type Document = Coll::Document;
+  type Error = Coll::Error;
+  type GetBatchDocuments = LocalBoxFuture<'static, Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>>;
+  type GetDocument = LocalBoxFuture<'static, Result<Self::Document, Self::Error>>;
+  type WriteBatchDocuments = LocalBoxFuture<'static, Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>;
+  type WriteDocument = LocalBoxFuture<'static, Result<(), Self::Error>>;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    let ids = ids.into_iter().map(|id,| *id.borrow(),).collect::<Vec<_>>();
+    let delay = self.reserve(ids.len(),);
+    let (inner, timer,) = (self.inner.clone(), self.timer.clone(),);
+
+    Box::pin(async move {
+      wait(&timer, delay,).await;
+      inner.get_documents(&ids,).await
+    },)
+  }
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    let delay = self.reserve(1,);
+    let (inner, timer,) = (self.inner.clone(), self.timer.clone(),);
+    let id = *id;
+
+    Box::pin(async move {
+      wait(&timer, delay,).await;
+      inner.get_document(&id,).await
+    },)
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let documents = documents.into_iter()
+      .map(|doc,| doc.borrow().clone(),)
+      .collect::<Vec<_>>();
+    let delay = self.reserve(documents.len(),);
+    let (inner, timer,) = (self.inner.clone(), self.timer.clone(),);
+
+    Box::pin(async move {
+      wait(&timer, delay,).await;
+      inner.write_documents(&documents,).await
+    },)
+  }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    let delay = self.reserve(1,);
+    let (inner, timer,) = (self.inner.clone(), self.timer.clone(),);
+    let document = document.borrow().clone();
+
+    Box::pin(async move {
+      wait(&timer, delay,).await;
+      inner.write_document(&document,).await
+    },)
+  }
+  fn exists(&self, id: &DocumentId,) -> impl Future<Output = Result<bool, Self::Error>> {
+    let delay = self.reserve(1,);
+
+    async move {
+      wait(&self.timer, delay,).await;
+      self.inner.exists(id,).await
+    }
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{CardId, TierId, TierMeta, mock::{ChaosCollection, RecordingTimer,},};
+  use futures::executor::block_on;
+  use std::cell::Cell;
+
+  #[test]
+  fn test_rate_limited() {
+    let inner = ChaosCollection::new();
+    let timer = RecordingTimer::default();
+    let now = Rc::new(Cell::new(SystemTime::UNIX_EPOCH,),);
+    let clock = { let now = now.clone(); move || now.get() };
+    let coll = RateLimited::new(inner.clone(), timer.clone(), clock, RateLimit { per_second: 10.0, burst: 2, },);
+    let tiers = (0..4u8).map(|i,| TierMeta::new(TierId::from_bytes([i; 20]), (None, CardId::from_bytes([i; 20]), CardId::from_bytes([i; 20]),), None, None,),).collect::<Vec<_>>();
+
+    block_on(async {
+      //The burst passes straight through and the third operation waits for a token.
+      coll.write_document(&tiers[0],).await
+        .expect("Error writing tier");
+      coll.write_document(&tiers[1],).await
+        .expect("Error writing tier");
+      assert_eq!(timer.sleeps(), Vec::<Duration,>::new(), "Error delaying burst",);
+      coll.write_document(&tiers[2],).await
+        .expect("Error writing tier");
+      assert_eq!(timer.sleeps(), vec![Duration::from_millis(100,),], "Error delaying third operation",);
+
+      //Idle time refills the bucket up to the burst.
+      now.set(now.get() + Duration::from_secs(10,),);
+      coll.get_document(&tiers[0].id,).await
+        .expect("Error reading tier");
+      assert_eq!(timer.sleeps().len(), 1, "Error refilling tokens",);
+
+      //A batch takes a token for each document.
+      coll.write_documents(&tiers,).await
+        .expect("Error writing tiers")
+        .expect("Error writing tiers");
+      assert_eq!(timer.sleeps()[1], Duration::from_millis(300,), "Error delaying batch",);
+      assert_eq!(inner.writes(), 7, "Error delegating writes",);
+    },);
+  }
+}
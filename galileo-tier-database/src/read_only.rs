@@ -0,0 +1,183 @@
+//! Defines a view of a document collection which cannot be written to.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, CollectionError, ErrorKind, TierListCollection, TierListCollectionScan,};
+use futures::{
+  Future, FutureExt, TryFutureExt, TryStreamExt,
+  future::{self, Map, MapErr, Ready,},
+  stream,
+};
+use std::{fmt, error::Error, borrow::Borrow,};
+
+/// The errors returned by a `ReadOnly` collection.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum ReadOnlyError<E,> {
+  /// A write was attempted through the read only view.
+  ReadOnly,
+  /// An error from the inner collection.
+  Inner(E),
+}
+
+impl<E,> fmt::Display for ReadOnlyError<E,>
+  where E: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      ReadOnlyError::ReadOnly => write!(fmt, "the collection is read only",),
+      ReadOnlyError::Inner(e) => e.fmt(fmt,),
+    }
+  }
+}
+
+impl<E,> Error for ReadOnlyError<E,>
+  where E: Error, {}
+
+impl<E,> CollectionError for ReadOnlyError<E,>
+  where E: CollectionError, {
+  #[inline]
+  fn kind(&self,) -> ErrorKind {
+    match self {
+      ReadOnlyError::ReadOnly => ErrorKind::Permanent,
+      ReadOnlyError::Inner(e) => e.kind(),
+    }
+  }
+  #[inline]
+  fn is_retryable(&self,) -> bool {
+    match self {
+      ReadOnlyError::ReadOnly => false,
+      ReadOnlyError::Inner(e) => e.is_retryable(),
+    }
+  }
+}
+
+/// The result of a batch read through a `ReadOnly` collection.
+type BatchResult<D, E,> = Result<Vec<Result<D, ReadOnlyError<E,>>>, ReadOnlyError<E,>>;
+
+/// Wraps the errors of a batch read.
+fn batch_error<D, E,>(res: Result<Vec<Result<D, E>>, E>,) -> BatchResult<D, E,> {
+  match res {
+    Ok(docs) => Ok(docs.into_iter().map(|doc,| doc.map_err(ReadOnlyError::Inner,),).collect()),
+    Err(e) => Err(ReadOnlyError::Inner(e,)),
+  }
+}
+
+/// A `TierListCollection` which reads from another collection and rejects every write
+/// with `ReadOnlyError::ReadOnly` without touching the inner collection.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub struct ReadOnly<Coll,> {
+  /// The collection to read from.
+  inner: Coll,
+}
+
+impl<Coll,> ReadOnly<Coll,> {
+  /// Returns a new `ReadOnly` view of a collection.
+  /// 
+  /// # Params
+  /// 
+  /// inner --- The collection to read from.  
+  #[inline]
+  pub const fn new(inner: Coll,) -> Self { Self { inner, } }
+  /// Gets the collection being read from.
+  #[inline]
+  pub const fn get_inner(&self,) -> &Coll { &self.inner }
+}
+
+impl<Coll,> TierListCollection for ReadOnly<Coll,>
+  where Coll: TierListCollection, {
+  type Document = Coll::Document;
+  type Error = ReadOnlyError<Coll::Error,>;
+  type GetBatchDocuments = Map<Coll::GetBatchDocuments, fn(Result<Vec<Result<Self::Document, Coll::Error>>, Coll::Error>,) -> BatchResult<Self::Document, Coll::Error,>>;
+  type GetDocument = MapErr<Coll::GetDocument, fn(Coll::Error,) -> Self::Error>;
+  type WriteBatchDocuments = Ready<Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>;
+  type WriteDocument = Ready<Result<(), Self::Error>>;
+
+  #[inline]
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    self.inner.get_documents(ids,).map(batch_error as fn(_,) -> _,)
+  }
+  #[inline]
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    self.inner.get_document(id,).map_err(ReadOnlyError::Inner as fn(_,) -> _,)
+  }
+  #[inline]
+  fn write_documents<I, T,>(&self, _: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    future::err(ReadOnlyError::ReadOnly,)
+  }
+  #[inline]
+  fn write_document<T,>(&self, _: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    future::err(ReadOnlyError::ReadOnly,)
+  }
+  #[inline]
+  fn exists(&self, id: &DocumentId,) -> impl Future<Output = Result<bool, Self::Error>> {
+    self.inner.exists(id,).map_err(ReadOnlyError::Inner,)
+  }
+}
+
+impl<Coll,> TierListCollectionScan for ReadOnly<Coll,>
+  where Coll: TierListCollectionScan, {
+  type Scan = stream::MapErr<Coll::Scan, fn(Coll::Error,) -> Self::Error>;
+
+  #[inline]
+  fn scan(&self,) -> Self::Scan {
+    self.inner.scan().map_err(ReadOnlyError::Inner as fn(_,) -> _,)
+  }
+  #[inline]
+  fn count(&self,) -> impl Future<Output = Result<u64, Self::Error>> {
+    self.inner.count().map_err(ReadOnlyError::Inner,)
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{Document, TierMeta, MemoryError, mock::ChaosCollection,};
+  use futures::executor::block_on;
+
+  #[test]
+  fn test_read_only() {
+    let inner = ChaosCollection::new();
+    let ids = (0..3u8).map(|i,| DocumentId::from_bytes([i; 20]),).collect::<Vec<_>>();
+    let tiers = ids.iter().enumerate().map(|(i, &id,),| TierMeta::new(
+      id.into(), (None, id.into(), id.into(),),
+      ids.get(i.wrapping_sub(1,),).map(|&id,| id.into(),), ids.get(i + 1,).map(|&id,| id.into(),),
+    ),).collect::<Vec<_>>();
+    let coll = inner.clone().read_only();
+
+    block_on(async {
+      inner.write_documents(&tiers,).await
+        .expect("Error writing tiers")
+        .expect("Error writing tiers");
+      let writes = inner.writes();
+
+      assert_eq!(coll.get_document(&ids[0],).await, Ok(tiers[0]), "Error reading tier",);
+      assert_eq!(
+        coll.get_documents([&ids[2], &DocumentId::from_bytes([9; 20]),],).await,
+        Ok(vec![Ok(tiers[2]), Err(ReadOnlyError::Inner(MemoryError::NotFound(DocumentId::from_bytes([9; 20]),).into(),)),]),
+        "Error reading tiers",
+      );
+      assert_eq!(coll.exists(&ids[1],).await, Ok(true), "Error checking tier",);
+
+      //Walk the list through the read only view.
+      let mut cursor = coll.ref_cursor::<TierMeta>(&ids[0],).await
+        .expect("Error reading front");
+      while let Some(next) = cursor.get_next().await.expect("Error reading next") { cursor = next }
+      assert_eq!(cursor.get_item(), &tiers[2], "Error walking the list",);
+
+      assert_eq!(coll.write_document(&tiers[0],).await, Err(ReadOnlyError::ReadOnly), "Error writing tier",);
+      assert_eq!(
+        coll.write_documents([&tiers[1],],).await,
+        Err(ReadOnlyError::ReadOnly),
+        "Error writing tiers",
+      );
+      assert_eq!(inner.writes(), writes, "Error rejected writes reached the inner collection",);
+      assert!(!ReadOnlyError::<MemoryError,>::ReadOnly.is_retryable(), "Error read only writes are retryable",);
+      assert_eq!(coll.get_document(tiers[0].get_id(),).await, Ok(tiers[0]), "Error rejected write changed the tier",);
+    },);
+  }
+}
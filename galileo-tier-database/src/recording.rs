@@ -0,0 +1,257 @@
+//! Defines a collection which records the operations performed on another collection
+//! so that tests can assert exactly which documents were accessed.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, Document, TierDocument, BatchReport, SnapshotCollection, NamedLookup, TierListCollection, TierListCollectionScan,};
+use futures::Future;
+use std::{
+  fmt,
+  mem,
+  cell::RefCell,
+  borrow::Borrow,
+  rc::Rc,
+};
+
+/// An operation recorded by a `RecordingCollection`.
+#[derive(PartialEq, Eq, Clone, Hash, Debug,)]
+pub enum Op {
+  /// A call to `get_document`.
+  Get(DocumentId),
+  /// A call to `get_documents` with the requested ids in order.
+  GetBatch(Vec<DocumentId>),
+  /// A call to `write_document`.
+  Write(DocumentId),
+  /// A call to `write_documents` or `write_documents_reported` with the ids of the
+  /// documents in order.
+  WriteBatch(Vec<DocumentId>),
+  /// A call to `exists`.
+  Exists(DocumentId),
+  /// A call to `write_document_if_absent`.
+  WriteIfAbsent(DocumentId),
+  /// A call to `scan`.
+  Scan,
+  /// A call to `count`.
+  Count,
+  /// A call to `snapshot`.
+  Snapshot,
+}
+
+/// An operation and when it was recorded.
+#[derive(PartialEq, Eq, Clone, Hash, Debug,)]
+pub struct Record {
+  /// The position of the operation among every operation recorded by the collection.
+  pub seq: u64,
+  /// The operation performed.
+  pub op: Op,
+}
+
+/// The recorded operations.
+#[derive(Default, Debug,)]
+struct Log {
+  /// The sequence number of the next operation.
+  next: u64,
+  /// The operations recorded since the log was last taken.
+  records: Vec<Record>,
+}
+
+/// A `TierListCollection` which records every operation performed on another
+/// collection.
+/// 
+/// Operations are recorded when they are called rather than when their futures
+/// complete and the futures of the inner collection are returned unchanged.
+/// Operations the inner collection performs on itself are not recorded.
+pub struct RecordingCollection<Coll,> {
+  /// The collection being recorded.
+  inner: Coll,
+  /// The recorded operations.
+  log: Rc<RefCell<Log>>,
+}
+
+impl<Coll,> RecordingCollection<Coll,> {
+  /// Returns a new `RecordingCollection` with no recorded operations.
+  /// 
+  /// # Params
+  /// 
+  /// inner --- The collection to record.  
+  pub fn new(inner: Coll,) -> Self {
+    Self { inner, log: Rc::default(), }
+  }
+  /// Gets the collection being recorded.
+  #[inline]
+  pub const fn get_inner(&self,) -> &Coll { &self.inner }
+  /// Takes the operations recorded since the log was last taken.
+  /// 
+  /// Sequence numbers keep increasing across calls.
+  #[inline]
+  pub fn take_log(&self,) -> Vec<Record> { mem::take(&mut self.log.borrow_mut().records,) }
+  /// Takes the operations recorded since the log was last taken without their sequence
+  /// numbers.
+  pub fn take_ops(&self,) -> Vec<Op> {
+    self.take_log().into_iter().map(|record,| record.op,).collect()
+  }
+  /// Records an operation.
+  fn record(&self, op: Op,) {
+    let mut log = self.log.borrow_mut();
+    let seq = log.next;
+
+    log.next += 1;
+    log.records.push(Record { seq, op, },);
+  }
+}
+
+/// Asserts that the operations recorded by a `RecordingCollection` since its log was
+/// last taken are exactly the operations listed, taking the log.
+#[macro_export]
+macro_rules! assert_ops {
+  ($collection:expr, [$($op:expr),* $(,)?] $(,)?) => {
+    $crate::assert_ops!($collection, [$($op,)*], "Error recorded the wrong operations",)
+  };
+  ($collection:expr, [$($op:expr),* $(,)?], $($arg:tt)+) => {
+    ::std::assert_eq!($collection.take_ops(), ::std::vec![$($op,)*] as ::std::vec::Vec<$crate::Op>, $($arg)+)
+  };
+}
+
+impl<Coll,> Clone for RecordingCollection<Coll,>
+  where Coll: Clone, {
+  #[inline]
+  fn clone(&self,) -> Self {
+    Self { inner: self.inner.clone(), log: self.log.clone(), }
+  }
+}
+
+impl<Coll,> fmt::Debug for RecordingCollection<Coll,>
+  where Coll: fmt::Debug, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    fmt.debug_struct("RecordingCollection",)
+    .field("inner", &self.inner,)
+    .field("log", &*RefCell::borrow(&self.log,),)
+    .finish()
+  }
+}
+
+impl<Coll,> TierListCollection for RecordingCollection<Coll,>
+  where Coll: TierListCollection, {
+  type Document = Coll::Document;
+  type Error = Coll::Error;
+  type GetBatchDocuments = Coll::GetBatchDocuments;
+  type GetDocument = Coll::GetDocument;
+  type WriteBatchDocuments = Coll::WriteBatchDocuments;
+  type WriteDocument = Coll::WriteDocument;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    let ids = ids.into_iter().map(|id,| *id.borrow(),).collect::<Vec<_>>();
+
+    self.record(Op::GetBatch(ids.clone(),),);
+    self.inner.get_documents(ids,)
+  }
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    self.record(Op::Get(*id,),);
+    self.inner.get_document(id,)
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let documents = documents.into_iter().collect::<Vec<_>>();
+
+    self.record(Op::WriteBatch(documents.iter().map(|doc,| *doc.borrow().get_id(),).collect(),),);
+    self.inner.write_documents(documents,)
+  }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    self.record(Op::Write(*document.borrow().get_id(),),);
+    self.inner.write_document(document,)
+  }
+  fn write_documents_reported<I, T,>(&self, documents: I,) -> impl Future<Output = Result<BatchReport<Self::Error,>, Self::Error>>
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let documents = documents.into_iter().collect::<Vec<_>>();
+
+    self.record(Op::WriteBatch(documents.iter().map(|doc,| *doc.borrow().get_id(),).collect(),),);
+    self.inner.write_documents_reported(documents,)
+  }
+  fn exists(&self, id: &DocumentId,) -> impl Future<Output = Result<bool, Self::Error>> {
+    self.record(Op::Exists(*id,),);
+    self.inner.exists(id,)
+  }
+  fn write_document_if_absent<T,>(&self, document: &T,) -> impl Future<Output = Result<bool, Self::Error>>
+    where T: Borrow<Self::Document>, {
+    self.record(Op::WriteIfAbsent(*document.borrow().get_id(),),);
+    self.inner.write_document_if_absent(document,)
+  }
+}
+
+impl<Coll,> TierListCollectionScan for RecordingCollection<Coll,>
+  where Coll: TierListCollectionScan, {
+  type Scan = Coll::Scan;
+
+  fn scan(&self,) -> Self::Scan {
+    self.record(Op::Scan,);
+    self.inner.scan()
+  }
+  fn count(&self,) -> impl Future<Output = Result<u64, Self::Error>> {
+    self.record(Op::Count,);
+    self.inner.count()
+  }
+  fn snapshot(&self,) -> impl Future<Output = Result<SnapshotCollection<Self::Document,>, Self::Error>> {
+    self.record(Op::Snapshot,);
+    self.inner.snapshot()
+  }
+}
+
+//Uses the default walk rather than the lookup of the inner collection so that the reads
+//of the walk are recorded.
+impl<Coll,> NamedLookup for RecordingCollection<Coll,>
+  where Coll: TierListCollection<Document = TierDocument>, {}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{CardId, TierId, MemoryCollection, TierMeta,};
+  use futures::{executor::block_on, TryStreamExt,};
+
+  #[test]
+  fn test_recording() {
+    let coll = RecordingCollection::new(MemoryCollection::<TierMeta,>::new(),);
+    let tiers = (1..=3u8).map(|i,| TierMeta::new(TierId::from_bytes([i; 20]), (None, CardId::from_bytes([i; 20]), CardId::from_bytes([i; 20]),), None, None,),).collect::<Vec<_>>();
+
+    block_on(async {
+      coll.write_documents(&tiers,).await
+        .expect("Error writing tiers")
+        .expect("Error writing tiers");
+      coll.write_document(&tiers[0],).await
+        .expect("Error writing tier");
+      coll.get_documents([&DocumentId::from_bytes([3; 20]), &DocumentId::from_bytes([1; 20]),],).await
+        .expect("Error reading tiers");
+      coll.get_document(&DocumentId::from_bytes([2; 20]),).await
+        .expect("Error reading tier");
+      assert_eq!(coll.exists(&DocumentId::from_bytes([9; 20]),).await, Ok(false), "Error tier exists",);
+    },);
+
+    let log = coll.take_log();
+    assert_eq!(log.iter().map(|record,| record.seq,).collect::<Vec<_>>(), [0, 1, 2, 3, 4,], "Error numbering operations",);
+    assert_eq!(
+      log.into_iter().map(|record,| record.op,).collect::<Vec<_>>(),
+      [
+        Op::WriteBatch(vec![DocumentId::from_bytes([1; 20]), DocumentId::from_bytes([2; 20]), DocumentId::from_bytes([3; 20]),]),
+        Op::Write(DocumentId::from_bytes([1; 20]),),
+        Op::GetBatch(vec![DocumentId::from_bytes([3; 20]), DocumentId::from_bytes([1; 20]),]),
+        Op::Get(DocumentId::from_bytes([2; 20]),),
+        Op::Exists(DocumentId::from_bytes([9; 20]),),
+      ],
+      "Error recording operations",
+    );
+    assert_eq!(coll.take_log(), [], "Error log not taken",);
+
+    block_on(async {
+      assert_eq!(coll.count().await, Ok(3), "Error counting tiers",);
+      coll.scan().try_collect::<Vec<_>>().await
+        .expect("Error scanning tiers");
+    },);
+    assert_eq!(coll.take_log()[0].seq, 5, "Error restarting sequence numbers",);
+    assert_ops!(coll, [],);
+  }
+}
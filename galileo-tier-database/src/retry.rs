@@ -0,0 +1,340 @@
+//! Defines a document collection which retries operations which fail transiently.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, CollectionError, TierListCollection, Timer,};
+use futures::{Future, TryFutureExt, future::LocalBoxFuture,};
+use std::{
+  time::Duration,
+  collections::hash_map::RandomState,
+  hash::{BuildHasher, Hasher,},
+  borrow::Borrow,
+};
+
+/// How a `RetryCollection` retries failed operations.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub struct RetryPolicy {
+  /// The maximum number of attempts at an operation, including the first attempt.
+  pub attempts: u32,
+  /// The delay before the first retry; each following retry waits twice as long.
+  pub base_delay: Duration,
+  /// The longest delay between attempts.
+  pub max_delay: Duration,
+  /// If `true` each delay is randomly shortened by up to half.
+  pub jitter: bool,
+}
+
+impl RetryPolicy {
+  /// Gets the delay before a retry.
+  /// 
+  /// # Params
+  /// 
+  /// retry --- The number of the retry, starting from `1`.  
+  pub fn delay(&self, retry: u32,) -> Duration {
+    let delay = self.base_delay.checked_mul(1 << retry.saturating_sub(1,).min(31,),)
+      .map_or(self.max_delay, |delay,| delay.min(self.max_delay,),);
+
+    if !self.jitter { return delay }
+
+    let half = delay / 2;
+    let mut random = RandomState::new().build_hasher();
+    random.write_u32(retry,);
+
+    half + Duration::from_nanos(random.finish() % (half.as_nanos() as u64 + 1),)
+  }
+}
+
+impl Default for RetryPolicy {
+  #[inline]
+  fn default() -> Self {
+    Self {
+      attempts: 3,
+      base_delay: Duration::from_millis(100,),
+      max_delay: Duration::from_secs(10,),
+      jitter: true,
+    }
+  }
+}
+
+/// A `TierListCollection` which retries operations on another collection when they
+/// fail with a retryable error.
+/// 
+/// Batch operations only retry the documents which failed.
+#[derive(Clone, Debug,)]
+pub struct RetryCollection<Coll, Tm,> {
+  /// The collection to retry operations on.
+  inner: Coll,
+  /// The timer used to wait between attempts.
+  timer: Tm,
+  /// How operations are retried.
+  policy: RetryPolicy,
+}
+
+impl<Coll, Tm,> RetryCollection<Coll, Tm,> {
+  /// Returns a new `RetryCollection`.
+  /// 
+  /// # Params
+  /// 
+  /// inner --- The collection to retry operations on.  
+  /// timer --- The timer used to wait between attempts.  
+  /// policy --- How operations are retried.  
+  #[inline]
+  pub const fn new(inner: Coll, timer: Tm, policy: RetryPolicy,) -> Self {
+    Self { inner, timer, policy, }
+  }
+  /// Gets the collection operations are retried on.
+  #[inline]
+  pub const fn get_inner(&self,) -> &Coll { &self.inner }
+  /// Gets how operations are retried.
+  #[inline]
+  pub const fn get_policy(&self,) -> &RetryPolicy { &self.policy }
+}
+
+/// Performs an operation until it succeeds, fails with an error which is not
+/// retryable, or runs out of attempts.
+async fn retry<T, E, Tm, F, Fut,>(policy: &RetryPolicy, timer: &Tm, mut operation: F,) -> Result<T, E>
+  where E: CollectionError,
+    Tm: Timer,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>, {
+  let mut attempt = 1;
+
+  loop {
+    match operation().await {
+      Err(e) if e.is_retryable() && attempt < policy.attempts => {
+        timer.sleep(policy.delay(attempt,),).await;
+        attempt += 1;
+      },
+      res => return res,
+    }
+  }
+}
+
+/// Returns `true` if the result is a failure which can be retried.
+fn failed_retryable<T, E,>(result: &Result<T, E>,) -> bool
+  where E: CollectionError, {
+  match result {
+    Err(e) => e.is_retryable(),
+    Ok(_) => false,
+  }
+}
+
+/// Performs a batch operation, retrying only the items which failed with a retryable
+/// error, until every item succeeds or it runs out of attempts.
+async fn retry_batch<T, E, Tm, F, Fut,>(policy: &RetryPolicy, timer: &Tm, len: usize, mut operation: F,) -> Result<Vec<Result<T, E>>, E>
+  where E: CollectionError,
+    Tm: Timer,
+    F: FnMut(&[usize],) -> Fut,
+    Fut: Future<Output = Result<Vec<Result<T, E>>, E>>, {
+  let mut results = None::<Vec<Result<T, E>>>;
+  let mut attempt = 1;
+
+  loop {
+    //The positions of the items to attempt.
+    let pending = match &results {
+      None => (0..len).collect::<Vec<_>>(),
+      Some(results) => results.iter().enumerate()
+        .filter(|(_, res,),| failed_retryable(res,),)
+        .map(|(index, _,),| index,)
+        .collect(),
+    };
+
+    match operation(&pending,).await {
+      Ok(attempted) => match &mut results {
+        None => results = Some(attempted,),
+        Some(results) => for (index, res,) in pending.into_iter().zip(attempted,) {
+          results[index] = res;
+        },
+      },
+      Err(e) => if !e.is_retryable() || attempt >= policy.attempts {
+        //Report the results of earlier attempts if there were any.
+        return results.ok_or(e,)
+      },
+    }
+
+    let retryable = results.as_ref().is_none_or(|results,| results.iter().any(failed_retryable,),);
+
+    if !retryable || attempt >= policy.attempts {
+      if let Some(results) = results { return Ok(results) }
+    }
+
+    timer.sleep(policy.delay(attempt,),).await;
+    attempt += 1;
+  }
+}
+
+impl<Coll, Tm,> TierListCollection for RetryCollection<Coll, Tm,>
+  where Coll: 'static + TierListCollection + Clone,
+    Coll::Document: Clone,
+    Tm: 'static + Timer + Clone, {
+  type Document = Coll::Document;
+  type Error = Coll::Error;
+  type GetBatchDocuments = LocalBoxFuture<'static, Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>>;
+  type GetDocument = LocalBoxFuture<'static, Result<Self::Document, Self::Error>>;
+  type WriteBatchDocuments = LocalBoxFuture<'static, Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>;
+  type WriteDocument = LocalBoxFuture<'static, Result<(), Self::Error>>;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    let Self { inner, timer, policy, } = self.clone();
+    let ids = ids.into_iter().map(|id,| *id.borrow(),).collect::<Vec<_>>();
+
+    Box::pin(async move {
+      retry_batch(&policy, &timer, ids.len(), |pending,| {
+        inner.get_documents(pending.iter().map(|&index,| &ids[index],),)
+      },).await
+    },)
+  }
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    let Self { inner, timer, policy, } = self.clone();
+    let id = *id;
+
+    Box::pin(async move {
+      retry(&policy, &timer, || inner.get_document(&id,),).await
+    },)
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let Self { inner, timer, policy, } = self.clone();
+    let documents = documents.into_iter()
+      .map(|doc,| doc.borrow().clone(),)
+      .collect::<Vec<_>>();
+
+    Box::pin(async move {
+      let results = retry_batch(&policy, &timer, documents.len(), |pending,| {
+        let len = pending.len();
+
+        inner.write_documents(pending.iter().map(|&index,| &documents[index],),)
+          .map_ok(move |res,| match res {
+            Ok(()) => (0..len).map(|_,| Ok(()),).collect(),
+            Err(results) => results,
+          },)
+      },).await?;
+
+      if results.iter().all(Result::is_ok,) { Ok(Ok(())) }
+      else { Ok(Err(results)) }
+    },)
+  }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    let Self { inner, timer, policy, } = self.clone();
+    let document = document.borrow().clone();
+
+    Box::pin(async move {
+      retry(&policy, &timer, || inner.write_document(&document,),).await
+    },)
+  }
+  fn exists(&self, id: &DocumentId,) -> impl Future<Output = Result<bool, Self::Error>> {
+    retry(&self.policy, &self.timer, move || self.inner.exists(id,),)
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{Document, TierMeta, mock::{ChaosCollection, ChaosError, RecordingTimer,},};
+  use futures::executor::block_on;
+
+  fn tier(id: u8,) -> TierMeta {
+    let id = DocumentId::from_bytes([id; 20]);
+
+    TierMeta::new(id.into(), (None, id.into(), id.into(),), None, None,)
+  }
+
+  fn policy() -> RetryPolicy { RetryPolicy { jitter: false, ..RetryPolicy::default() } }
+
+  #[test]
+  fn test_delay() {
+    let policy = RetryPolicy { max_delay: Duration::from_millis(300,), ..policy() };
+
+    assert_eq!(policy.delay(1,), Duration::from_millis(100,), "Error first delay",);
+    assert_eq!(policy.delay(2,), Duration::from_millis(200,), "Error second delay",);
+    assert_eq!(policy.delay(3,), Duration::from_millis(300,), "Error delay not capped",);
+    assert_eq!(policy.delay(64,), Duration::from_millis(300,), "Error delay overflowed",);
+
+    let policy = RetryPolicy { jitter: true, ..policy };
+    for retry in 1..=3 {
+      let delay = policy.delay(retry,);
+      let max = RetryPolicy { jitter: false, ..policy }.delay(retry,);
+
+      assert!(max / 2 <= delay && delay <= max, "Error jitter out of bounds {:?}", delay,);
+    }
+  }
+
+  #[test]
+  fn test_retry() {
+    let chaos = ChaosCollection::new();
+    let timer = RecordingTimer::default();
+    let collection = RetryCollection::new(chaos.clone(), timer.clone(), policy(),);
+    let doc = tier(0,);
+
+    block_on(chaos.inner().write_document(&doc,),).expect("Error writing document");
+    chaos.flaky_reads(*doc.get_id(), 2,);
+
+    assert_eq!(block_on(collection.get_document(doc.get_id(),),), Ok(doc), "Error retrying read",);
+    assert_eq!(chaos.reads(), 3, "Error counting attempts",);
+    assert_eq!(
+      timer.sleeps(),
+      vec![Duration::from_millis(100,), Duration::from_millis(200,)],
+      "Error backing off",
+    );
+
+    chaos.flaky_writes(*doc.get_id(), 2,);
+    assert_eq!(block_on(collection.write_document(&doc,),), Ok(()), "Error retrying write",);
+    assert_eq!(chaos.writes(), 3, "Error counting write attempts",);
+  }
+
+  #[test]
+  fn test_give_up() {
+    let chaos = ChaosCollection::<TierMeta,>::new();
+    let timer = RecordingTimer::default();
+    let collection = RetryCollection::new(chaos.clone(), timer.clone(), policy(),);
+    let doc = tier(0,);
+
+    chaos.flaky_reads(*doc.get_id(), 3,);
+    assert_eq!(
+      block_on(collection.get_document(doc.get_id(),),),
+      Err(ChaosError::Injected(*doc.get_id(),)),
+      "Error retrying past the attempt limit",
+    );
+    assert_eq!(chaos.reads(), 3, "Error counting attempts",);
+
+    //Errors which are not retryable are returned immediately.
+    assert!(
+      block_on(collection.get_document(&DocumentId::from_bytes([1; 20]),),).expect_err("Error reading missing document").is_not_found(),
+      "Error reading missing document",
+    );
+    assert_eq!(chaos.reads(), 4, "Error retrying missing document",);
+    assert_eq!(timer.sleeps().len(), 2, "Error sleeping without retrying",);
+  }
+
+  #[test]
+  fn test_retry_batch() {
+    let chaos = ChaosCollection::new();
+    let timer = RecordingTimer::default();
+    let collection = RetryCollection::new(chaos.clone(), timer.clone(), policy(),);
+    let docs = (0..4).map(tier,).collect::<Vec<_>>();
+
+    chaos.flaky_writes(*docs[1].get_id(), 1,);
+    chaos.flaky_writes(*docs[2].get_id(), 2,);
+    assert_eq!(block_on(collection.write_documents(&docs,),), Ok(Ok(())), "Error retrying batch write",);
+    //Only the failed writes are retried.
+    assert_eq!(chaos.writes(), 4 + 2 + 1, "Error retrying successful writes",);
+
+    chaos.flaky_reads(*docs[3].get_id(), 1,);
+    let results = block_on(collection.get_documents(docs.iter().map(Document::get_id,),),).expect("Error reading documents");
+    assert_eq!(results, docs.iter().cloned().map(Ok,).collect::<Vec<_>>(), "Error retrying batch read",);
+    assert_eq!(chaos.reads(), 4 + 1, "Error retrying successful reads",);
+    assert_eq!(timer.sleeps().len(), 3, "Error backing off batches",);
+
+    //Permanent failures are reported once the retries run out.
+    chaos.fail_writes(*docs[0].get_id(),);
+    let results = block_on(collection.write_documents(&docs,),).expect("Error writing documents")
+      .expect_err("Error writing broken document");
+    assert_eq!(results[0], Err(ChaosError::Injected(*docs[0].get_id(),)), "Error reporting failure",);
+    assert!(results[1..].iter().all(Result::is_ok,), "Error reporting successes",);
+  }
+}
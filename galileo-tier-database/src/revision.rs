@@ -0,0 +1,164 @@
+//! Defines operations for conditionally writing documents based on their revision.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, Document, CollectionError, ErrorKind, TierListCollection, Upsert,};
+use futures::Future;
+use std::{borrow::Borrow, rc::Rc, sync::Arc,};
+
+/// A collection which tracks a revision for each document so that a document can be
+/// written only if it has not changed since it was read.
+pub trait RevisionedCollection: TierListCollection {
+  /// The revision of a document.
+  type Revision: Clone + Eq;
+  /// The future type when fetching a document and its revision from the collection.
+  type GetDocumentRev: Future<Output = Result<(Self::Document, Self::Revision,), Self::Error>>;
+  /// The future type when conditionally writing a document to the collection.
+  type WriteDocumentRev: Future<Output = Result<Self::Revision, Self::Error>>;
+
+  /// Gets a document and its current revision from the collection.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the document in the collection.  
+  fn get_document_rev(&self, id: &DocumentId,) -> Self::GetDocumentRev;
+  /// Writes a document to the collection if its revision is `expected` and returns the
+  /// new revision.
+  /// 
+  /// The write fails with an error of kind `Conflict` if the revision of the stored
+  /// document is not `expected`; if `expected` is `None` the write fails unless the
+  /// document does not exist.
+  /// 
+  /// # Params
+  /// 
+  /// document --- The document to write to the collection.  
+  /// expected --- The revision the stored document must have.  
+  fn write_document_rev<T,>(&self, document: &T, expected: Option<&Self::Revision>,) -> Self::WriteDocumentRev
+    where T: Borrow<Self::Document>;
+  /// Reads, updates and writes back a document, starting again if the document changes
+  /// between being read and written, and returns the written document.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the document in the collection.  
+  /// attempts --- The maximum number of times to try the update.  
+  /// update --- Updates the document.  
+  fn update_document<F,>(&self, id: &DocumentId, attempts: usize, mut update: F,) -> impl Future<Output = Result<Self::Document, Self::Error>>
+    where F: FnMut(Self::Document,) -> Self::Document, {
+    async move {
+      let mut attempt = 1;
+
+      loop {
+        let (document, revision,) = self.get_document_rev(id,).await?;
+        let document = update(document,);
+
+        match self.write_document_rev(&document, Some(&revision),).await {
+          Ok(_) => return Ok(document),
+          Err(e) if e.kind() == ErrorKind::Conflict && attempt < attempts => attempt += 1,
+          Err(e) => return Err(e),
+        }
+      }
+    }
+  }
+  /// Writes a document to the collection, merging it with the stored document if there
+  /// is one, starting again if the stored document changes between being read and
+  /// written, and returns the written document and how it was written.
+  /// 
+  /// `merge` is called with the stored document and `document` each time the stored
+  /// document is read. As with `TierListCollection::upsert_with` a merged document equal
+  /// to `document` is reported as `Upsert::Overwrote`.
+  /// 
+  /// # Params
+  /// 
+  /// document --- The document to write to the collection.  
+  /// attempts --- The maximum number of times to try the write.  
+  /// merge --- Merges the stored document and `document`.  
+  fn upsert_with_rev<F,>(&self, document: Self::Document, attempts: usize, mut merge: F,) -> impl Future<Output = Result<(Self::Document, Upsert,), Self::Error>>
+    where F: FnMut(Self::Document, Self::Document,) -> Self::Document,
+      Self::Document: Clone + PartialEq, {
+    async move {
+      let id = *document.get_id();
+      let mut attempt = 1;
+
+      loop {
+        let (merged, upsert, revision,) = match self.get_document_rev(&id,).await {
+          Ok((existing, revision,)) => {
+            let merged = merge(existing, document.clone(),);
+            let upsert = if merged == document { Upsert::Overwrote } else { Upsert::Merged };
+
+            (merged, upsert, Some(revision),)
+          },
+          Err(e) if e.is_not_found() => (document.clone(), Upsert::Inserted, None,),
+          Err(e) => return Err(e),
+        };
+
+        match self.write_document_rev(&merged, revision.as_ref(),).await {
+          Ok(_) => return Ok((merged, upsert,)),
+          Err(e) if e.kind() == ErrorKind::Conflict && attempt < attempts => attempt += 1,
+          Err(e) => return Err(e),
+        }
+      }
+    }
+  }
+}
+
+impl<Coll,> RevisionedCollection for &Coll
+  where Coll: RevisionedCollection, {
+  type Revision = Coll::Revision;
+  type GetDocumentRev = Coll::GetDocumentRev;
+  type WriteDocumentRev = Coll::WriteDocumentRev;
+
+  #[inline]
+  fn get_document_rev(&self, id: &DocumentId,) -> Self::GetDocumentRev { Coll::get_document_rev(*self, id,) }
+  #[inline]
+  fn write_document_rev<T,>(&self, document: &T, expected: Option<&Self::Revision>,) -> Self::WriteDocumentRev
+    where T: Borrow<Self::Document>, {
+    Coll::write_document_rev(*self, document, expected,)
+  }
+}
+
+impl<Coll,> RevisionedCollection for Rc<Coll,>
+  where Coll: RevisionedCollection, {
+  type Revision = Coll::Revision;
+  type GetDocumentRev = Coll::GetDocumentRev;
+  type WriteDocumentRev = Coll::WriteDocumentRev;
+
+  #[inline]
+  fn get_document_rev(&self, id: &DocumentId,) -> Self::GetDocumentRev { Coll::get_document_rev(&**self, id,) }
+  #[inline]
+  fn write_document_rev<T,>(&self, document: &T, expected: Option<&Self::Revision>,) -> Self::WriteDocumentRev
+    where T: Borrow<Self::Document>, {
+    Coll::write_document_rev(&**self, document, expected,)
+  }
+}
+
+impl<Coll,> RevisionedCollection for Arc<Coll,>
+  where Coll: RevisionedCollection, {
+  type Revision = Coll::Revision;
+  type GetDocumentRev = Coll::GetDocumentRev;
+  type WriteDocumentRev = Coll::WriteDocumentRev;
+
+  #[inline]
+  fn get_document_rev(&self, id: &DocumentId,) -> Self::GetDocumentRev { Coll::get_document_rev(&**self, id,) }
+  #[inline]
+  fn write_document_rev<T,>(&self, document: &T, expected: Option<&Self::Revision>,) -> Self::WriteDocumentRev
+    where T: Borrow<Self::Document>, {
+    Coll::write_document_rev(&**self, document, expected,)
+  }
+}
+
+impl<Coll,> RevisionedCollection for Box<Coll,>
+  where Coll: RevisionedCollection, {
+  type Revision = Coll::Revision;
+  type GetDocumentRev = Coll::GetDocumentRev;
+  type WriteDocumentRev = Coll::WriteDocumentRev;
+
+  #[inline]
+  fn get_document_rev(&self, id: &DocumentId,) -> Self::GetDocumentRev { Coll::get_document_rev(&**self, id,) }
+  #[inline]
+  fn write_document_rev<T,>(&self, document: &T, expected: Option<&Self::Revision>,) -> Self::WriteDocumentRev
+    where T: Borrow<Self::Document>, {
+    Coll::write_document_rev(&**self, document, expected,)
+  }
+}
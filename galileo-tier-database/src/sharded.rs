@@ -0,0 +1,245 @@
+//! Defines a document collection which spreads its documents across several shards.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, Document, TierListCollection, TierListCollectionScan,};
+use futures::{
+  Future, FutureExt, TryFutureExt, StreamExt,
+  future::{self, LocalBoxFuture,},
+  stream::{self, Flatten, Iter,},
+};
+use std::{fmt, borrow::Borrow,};
+
+/// A `TierListCollection` which stores each document in one of several shards chosen by
+/// the first byte of its identifier.
+/// 
+/// Batches are split by shard, the shards are accessed concurrently and the results are
+/// returned in the order of the request. If a shard fails a whole batch the batch fails
+/// with its error even though other shards may have completed their part.
+#[derive(Clone,)]
+pub struct ShardedCollection<Coll,> {
+  /// The shards storing the documents.
+  shards: Vec<Coll>,
+}
+
+impl<Coll,> ShardedCollection<Coll,> {
+  /// Returns a new `ShardedCollection`.
+  /// 
+  /// The shards must always be given in the same order for documents to be found again.
+  /// 
+  /// # Params
+  /// 
+  /// shards --- The shards to store the documents in.  
+  /// 
+  /// # Panics
+  /// 
+  /// If there are no shards.
+  pub fn new(shards: Vec<Coll>,) -> Self {
+    assert!(!shards.is_empty(), "A `ShardedCollection` needs at least one shard",);
+
+    Self { shards, }
+  }
+  /// Gets the shards storing the documents.
+  #[inline]
+  pub fn get_shards(&self,) -> &[Coll] { &self.shards }
+  /// Returns the index of the shard storing a document.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the document.  
+  #[inline]
+  pub fn shard_index(&self, id: &DocumentId,) -> usize { id.as_bytes()[0] as usize % self.shards.len() }
+  /// Splits the positions of a batch by the shards storing them.
+  fn split<'a, T,>(&self, batch: &[&'a T], id: impl Fn(&T,) -> &DocumentId,) -> Vec<(Vec<usize>, Vec<&'a T>,)>
+    where T: ?Sized, {
+    let mut split = self.shards.iter().map(|_,| (Vec::new(), Vec::new(),),).collect::<Vec<_>>();
+
+    for (position, &item,) in batch.iter().enumerate() {
+      let (positions, items,) = &mut split[self.shard_index(id(item,),)];
+
+      positions.push(position,);
+      items.push(item,);
+    }
+
+    split
+  }
+}
+
+impl<Coll,> fmt::Debug for ShardedCollection<Coll,>
+  where Coll: fmt::Debug, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    fmt.debug_struct("ShardedCollection",)
+    .field("shards", &self.shards,)
+    .finish()
+  }
+}
+
+/// Stitches the results of the shards back into the order of the request.
+/// 
+/// # Params
+/// 
+/// len --- The length of the request.  
+/// results --- The positions of the documents in each shard and their results.  
+fn stitch<T,>(len: usize, results: impl IntoIterator<Item = (Vec<usize>, Vec<T>,)>,) -> Vec<T> {
+  let mut stitched = (0..len).map(|_,| None,).collect::<Vec<_>>();
+
+  for (positions, results,) in results {
+    for (position, result,) in positions.into_iter().zip(results,) { stitched[position] = Some(result,) }
+  }
+
+  stitched.into_iter().map(|result,| result.expect("Every position has a result"),).collect()
+}
+
+impl<Coll,> TierListCollection for ShardedCollection<Coll,>
+  where Coll: TierListCollection,
+    Coll::Document: 'static,
+    Coll::Error: 'static,
+    Coll::GetBatchDocuments: 'static,
+    Coll::WriteBatchDocuments: 'static, {
+  type Document = Coll::Document;
+  type Error = Coll::Error;
+  type GetBatchDocuments = LocalBoxFuture<'static, Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>>;
+  type GetDocument = Coll::GetDocument;
+  type WriteBatchDocuments = LocalBoxFuture<'static, Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>;
+  type WriteDocument = Coll::WriteDocument;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    let ids = ids.into_iter().map(|id,| *id.borrow(),).collect::<Vec<_>>();
+    let len = ids.len();
+    let batches = self.shards.iter().zip(self.split(&ids.iter().collect::<Vec<_>>(), |id,| id,),)
+      .filter(|(_, (positions, _,),),| !positions.is_empty(),)
+      .map(|(shard, (positions, ids,),),| shard.get_documents(ids,).map_ok(move |docs,| (positions, docs,),),)
+      .collect::<Vec<_>>();
+
+    future::try_join_all(batches,)
+    .map_ok(move |results,| stitch(len, results,),)
+    .boxed_local()
+  }
+  #[inline]
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    self.shards[self.shard_index(id,)].get_document(id,)
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let documents = documents.into_iter().collect::<Vec<_>>();
+    let documents = documents.iter().map(Borrow::borrow,).collect::<Vec<&Coll::Document>>();
+    let len = documents.len();
+    let batches = self.shards.iter().zip(self.split(&documents, Document::get_id,),)
+      .filter(|(_, (positions, _,),),| !positions.is_empty(),)
+      .map(|(shard, (positions, documents,),),| shard.write_documents(documents,).map_ok(move |res,| (positions, res,),),)
+      .collect::<Vec<_>>();
+
+    future::try_join_all(batches,)
+    .map_ok(move |results,| {
+      if results.iter().all(|(_, res,),| res.is_ok(),) { return Ok(()) }
+
+      Err(stitch(len, results.into_iter().map(|(positions, res,),| match res {
+        Ok(()) => {
+          let res = positions.iter().map(|_,| Ok(()),).collect();
+          (positions, res,)
+        },
+        Err(res) => (positions, res,),
+      },),),)
+    },)
+    .boxed_local()
+  }
+  #[inline]
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    self.shards[self.shard_index(document.borrow().get_id(),)].write_document(document,)
+  }
+  #[inline]
+  fn exists(&self, id: &DocumentId,) -> impl Future<Output = Result<bool, Self::Error>> {
+    self.shards[self.shard_index(id,)].exists(id,)
+  }
+}
+
+impl<Coll,> TierListCollectionScan for ShardedCollection<Coll,>
+  where Coll: TierListCollectionScan,
+    Coll::Document: 'static,
+    Coll::Error: 'static,
+    Coll::GetBatchDocuments: 'static,
+    Coll::WriteBatchDocuments: 'static, {
+  type Scan = Flatten<Iter<std::vec::IntoIter<Coll::Scan>>>;
+
+  fn scan(&self,) -> Self::Scan {
+    stream::iter(self.shards.iter().map(Coll::scan,).collect::<Vec<_>>(),).flatten()
+  }
+  fn count(&self,) -> impl Future<Output = Result<u64, Self::Error>> {
+    future::try_join_all(self.shards.iter().map(Coll::count,),)
+    .map_ok(|counts,| counts.into_iter().sum(),)
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{TierMeta, MemoryCollection, MemoryError, mock::{ChaosCollection, ChaosError,},};
+  use futures::{executor::block_on, TryStreamExt,};
+
+  fn tier(id: DocumentId,) -> TierMeta { TierMeta::new(id.into(), (None, id.into(), id.into(),), None, None,) }
+
+  #[test]
+  fn test_sharded() {
+    let shards = (0..4).map(|_,| MemoryCollection::new(),).collect::<Vec<_>>();
+    let coll = ShardedCollection::new(shards.clone(),);
+    let tiers = (0..10u8).rev().map(|i,| tier(DocumentId::from_bytes([i; 20]),),).collect::<Vec<_>>();
+
+    block_on(async {
+      coll.write_documents(&tiers,).await
+        .expect("Error writing tiers")
+        .expect("Error writing tiers");
+
+      //Every document is in the shard chosen by its first byte.
+      for tier in tiers.iter() {
+        let index = tier.id.as_bytes()[0] as usize % 4;
+
+        assert_eq!(coll.shard_index(&tier.id,), index, "Error routing tier",);
+        for (i, shard,) in shards.iter().enumerate() {
+          assert_eq!(shard.exists(&tier.id,).await, Ok(i == index), "Error storing tier",);
+        }
+      }
+      assert_eq!(shards.iter().map(MemoryCollection::len,).collect::<Vec<_>>(), vec![3, 3, 2, 2,], "Error spreading tiers",);
+
+      //A batch spanning every shard is returned in request order.
+      let ids = [DocumentId::from_bytes([7; 20]), DocumentId::from_bytes([0; 20]), DocumentId::from_bytes([42; 20]), DocumentId::from_bytes([5; 20]), DocumentId::from_bytes([2; 20]), DocumentId::from_bytes([7; 20]),];
+      assert_eq!(
+        coll.get_documents(ids,).await,
+        Ok(vec![
+          Ok(tier(DocumentId::from_bytes([7; 20]),)), Ok(tier(DocumentId::from_bytes([0; 20]),)), Err(MemoryError::NotFound(DocumentId::from_bytes([42; 20]),)),
+          Ok(tier(DocumentId::from_bytes([5; 20]),)), Ok(tier(DocumentId::from_bytes([2; 20]),)), Ok(tier(DocumentId::from_bytes([7; 20]),)),
+        ]),
+        "Error reading tiers",
+      );
+      assert_eq!(coll.get_document(&DocumentId::from_bytes([3; 20]),).await, Ok(tier(DocumentId::from_bytes([3; 20]),)), "Error reading tier",);
+      assert_eq!(coll.count().await, Ok(10), "Error counting tiers",);
+
+      let mut scanned = coll.scan().try_collect::<Vec<_>>().await
+        .expect("Error scanning tiers");
+      scanned.sort_by_key(|tier,| std::cmp::Reverse(tier.id,),);
+      assert_eq!(scanned, tiers, "Error scanning tiers",);
+    },);
+  }
+  #[test]
+  fn test_partial_failure() {
+    let shards = (0..4).map(|_,| ChaosCollection::<TierMeta,>::new(),).collect::<Vec<_>>();
+    let coll = ShardedCollection::new(shards.clone(),);
+    let tiers = (0..8u8).map(|i,| tier(DocumentId::from_bytes([i; 20]),),).collect::<Vec<_>>();
+
+    shards[1].fail_writes(DocumentId::from_bytes([5; 20]),);
+    block_on(async {
+      let results = coll.write_documents(&tiers,).await
+        .expect("Error writing tiers")
+        .expect_err("Error writing broken tier");
+
+      for (tier, res,) in tiers.iter().zip(results,) {
+        if tier.id == DocumentId::from_bytes([5; 20]) { assert_eq!(res, Err(ChaosError::Injected(DocumentId::from_bytes([5; 20]),)), "Error reporting failed tier",) }
+        else { assert_eq!(res, Ok(()), "Error reporting written tier",) }
+      }
+    },);
+  }
+}
@@ -0,0 +1,226 @@
+//! Defines an immutable point in time copy of a document collection.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{
+  DocumentId, Document, CollectionError, ErrorKind, TierListCollection, TierListCollectionScan, TierDocument,
+  NamedLookup, LookupError, Card, TierMeta,
+};
+use futures::{Future, TryFutureExt, future::{self, Ready,}, stream,};
+use std::{
+  fmt,
+  error::Error,
+  collections::HashMap,
+  iter::FromIterator,
+  borrow::Borrow,
+  rc::Rc,
+};
+
+/// The errors returned by a `SnapshotCollection`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum SnapshotError {
+  /// There is no document with the identifier in the snapshot.
+  NotFound(DocumentId),
+  /// A write was attempted on the snapshot.
+  ReadOnly,
+}
+
+impl fmt::Display for SnapshotError {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      SnapshotError::NotFound(id) => write!(fmt, "no document with the id {:?} in the snapshot", id,),
+      SnapshotError::ReadOnly => write!(fmt, "the snapshot cannot be written to",),
+    }
+  }
+}
+
+impl Error for SnapshotError {}
+
+impl CollectionError for SnapshotError {
+  #[inline]
+  fn kind(&self,) -> ErrorKind {
+    match self {
+      SnapshotError::NotFound(_) => ErrorKind::NotFound,
+      SnapshotError::ReadOnly => ErrorKind::Permanent,
+    }
+  }
+}
+
+/// A `TierListCollection` which serves reads from a fixed copy of the documents of
+/// another collection.
+/// 
+/// Nothing is read from the collection the snapshot was taken from so reads, cursors and
+/// scans never observe changes made after the snapshot was taken. Every write is
+/// rejected with `SnapshotError::ReadOnly`.
+/// 
+/// Cloning a `SnapshotCollection` produces a new handle to the same documents.
+pub struct SnapshotCollection<D,> {
+  /// The documents in the snapshot.
+  documents: Rc<HashMap<DocumentId, D>>,
+}
+
+impl<D,> SnapshotCollection<D,> {
+  /// Returns a new `SnapshotCollection` of documents.
+  /// 
+  /// # Params
+  /// 
+  /// documents --- The documents in the snapshot by their identifiers.  
+  #[inline]
+  pub fn new(documents: HashMap<DocumentId, D>,) -> Self { Self { documents: Rc::new(documents,), } }
+  /// Returns the number of documents in the snapshot.
+  #[inline]
+  pub fn len(&self,) -> usize { self.documents.len() }
+  /// Returns `true` if there are no documents in the snapshot.
+  #[inline]
+  pub fn is_empty(&self,) -> bool { self.documents.is_empty() }
+  /// Gets the documents in the snapshot by their identifiers.
+  #[inline]
+  pub fn documents(&self,) -> &HashMap<DocumentId, D> { &self.documents }
+}
+
+impl<D,> FromIterator<D> for SnapshotCollection<D,>
+  where D: Document, {
+  #[inline]
+  fn from_iter<I,>(iter: I,) -> Self
+    where I: IntoIterator<Item = D>, {
+    Self::new(iter.into_iter().map(|doc,| (*doc.get_id(), doc,),).collect(),)
+  }
+}
+
+impl<D,> Clone for SnapshotCollection<D,> {
+  #[inline]
+  fn clone(&self,) -> Self { Self { documents: self.documents.clone(), } }
+}
+
+impl<D,> fmt::Debug for SnapshotCollection<D,>
+  where D: fmt::Debug, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    fmt.debug_struct("SnapshotCollection",)
+    .field("documents", &self.documents,)
+    .finish()
+  }
+}
+
+impl<D,> TierListCollection for SnapshotCollection<D,>
+  where D: Document + Clone, {
+  type Document = D;
+  type Error = SnapshotError;
+  type GetBatchDocuments = Ready<Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>>;
+  type GetDocument = Ready<Result<Self::Document, Self::Error>>;
+  type WriteBatchDocuments = Ready<Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>;
+  type WriteDocument = Ready<Result<(), Self::Error>>;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    future::ok(ids.into_iter().map(|id,| {
+      let id = *id.borrow();
+
+      self.documents.get(&id,).cloned().ok_or(SnapshotError::NotFound(id),)
+    },).collect(),)
+  }
+  #[inline]
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    future::ready(self.documents.get(id,).cloned().ok_or(SnapshotError::NotFound(*id),),)
+  }
+  #[inline]
+  fn write_documents<I, T,>(&self, _: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    future::err(SnapshotError::ReadOnly,)
+  }
+  #[inline]
+  fn write_document<T,>(&self, _: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    future::err(SnapshotError::ReadOnly,)
+  }
+  #[inline]
+  fn exists(&self, id: &DocumentId,) -> impl Future<Output = Result<bool, Self::Error>> {
+    future::ok(self.documents.contains_key(id,),)
+  }
+}
+
+impl<D,> TierListCollectionScan for SnapshotCollection<D,>
+  where D: Document + Clone, {
+  type Scan = stream::Iter<std::vec::IntoIter<Result<Self::Document, Self::Error>>>;
+
+  fn scan(&self,) -> Self::Scan {
+    stream::iter(self.documents.values().cloned().map(Ok,).collect::<Vec<_>>(),)
+  }
+  #[inline]
+  fn count(&self,) -> impl Future<Output = Result<u64, Self::Error>> {
+    future::ok(self.len() as u64,)
+  }
+  #[inline]
+  fn snapshot(&self,) -> impl Future<Output = Result<SnapshotCollection<Self::Document,>, Self::Error>> {
+    future::ok(self.clone(),)
+  }
+}
+
+impl NamedLookup for SnapshotCollection<TierDocument,> {
+  #[inline]
+  fn find_card_by_name(&self, list_head: &DocumentId, name: &str,) -> impl Future<Output = Result<Option<(DocumentId, Card,)>, LookupError<Self::Error,>>> {
+    self.scan_card_by_name(list_head, name,).map_err(LookupError::Fetch,)
+  }
+  #[inline]
+  fn find_tier_by_name(&self, list_head: &DocumentId, name: &str,) -> impl Future<Output = Result<Option<TierMeta>, LookupError<Self::Error,>>> {
+    self.scan_tier_by_name(list_head, name,).map_err(LookupError::Fetch,)
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{CardId, TierId, TierMeta, MemoryCollection,};
+  use futures::{executor::block_on, TryStreamExt,};
+
+  #[test]
+  fn test_snapshot() {
+    let live = MemoryCollection::new();
+    let ids = (0..3u8).map(|i,| DocumentId::from_bytes([i; 20]),).collect::<Vec<_>>();
+    let tiers = ids.iter().enumerate().map(|(i, &id,),| TierMeta::new(
+      id.into(), (None, id.into(), id.into(),),
+      ids.get(i.wrapping_sub(1,),).map(|&id,| id.into(),), ids.get(i + 1,).map(|&id,| id.into(),),
+    ),).collect::<Vec<_>>();
+
+    block_on(async {
+      live.write_documents(&tiers,).await
+        .expect("Error writing tiers")
+        .expect("Error writing tiers");
+
+      let snapshot = live.snapshot().await
+        .expect("Error taking snapshot");
+
+      //Cut the last tier out of the live list and add a new tier.
+      let cut = TierMeta::new(ids[1].into(), (None, ids[1].into(), ids[1].into(),), Some(ids[0].into(),), Some(TierId::from_bytes([9; 20]),),);
+      live.write_documents([&cut, &TierMeta::new(TierId::from_bytes([9; 20]), (None, CardId::from_bytes([9; 20]), CardId::from_bytes([9; 20]),), Some(ids[1].into(),), None,),],).await
+        .expect("Error writing tiers")
+        .expect("Error writing tiers");
+
+      assert_eq!(snapshot.get_document(&ids[1],).await, Ok(tiers[1]), "Error reading old tier",);
+      assert_eq!(
+        snapshot.get_documents([&ids[2], &DocumentId::from_bytes([9; 20]),],).await,
+        Ok(vec![Ok(tiers[2]), Err(SnapshotError::NotFound(DocumentId::from_bytes([9; 20]),)),]),
+        "Error reading tiers",
+      );
+      assert_eq!(snapshot.exists(&DocumentId::from_bytes([9; 20]),).await, Ok(false), "Error checking new tier",);
+      assert_eq!(snapshot.count().await, Ok(3), "Error counting tiers",);
+
+      let mut scanned = snapshot.scan().try_collect::<Vec<_>>().await
+        .expect("Error scanning snapshot");
+      scanned.sort_by_key(|tier,| tier.id,);
+      assert_eq!(scanned, tiers, "Error scanning snapshot",);
+
+      //Walk the list as it was when the snapshot was taken.
+      let mut cursor = snapshot.ref_cursor::<TierMeta>(&ids[0],).await
+        .expect("Error reading front");
+      while let Some(next) = cursor.get_next().await.expect("Error reading next") { cursor = next }
+      assert_eq!(cursor.get_item(), &tiers[2], "Error walking the snapshot",);
+
+      assert_eq!(snapshot.write_document(&cut,).await, Err(SnapshotError::ReadOnly), "Error writing tier",);
+      assert_eq!(snapshot.write_documents([&cut,],).await, Err(SnapshotError::ReadOnly), "Error writing tiers",);
+      assert_eq!(snapshot.get_document(&ids[1],).await, Ok(tiers[1]), "Error rejected write changed the tier",);
+    },);
+  }
+}
@@ -0,0 +1,277 @@
+//! Defines a document collection which writes to a primary collection and reads from its
+//! replicas.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, CollectionError, ErrorKind, TierListCollection,};
+use futures::{
+  FutureExt, TryFutureExt,
+  future::{LocalBoxFuture, Map, MapErr,},
+};
+use std::{fmt, error::Error, cell::Cell, borrow::Borrow, rc::Rc,};
+
+/// The errors returned by a `SplitCollection`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum SplitError<W, R,> {
+  /// An error from the primary collection.
+  Primary(W),
+  /// An error from the replica at the index.
+  Replica(usize, R),
+}
+
+impl<W, R,> fmt::Display for SplitError<W, R,>
+  where W: fmt::Display, R: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      SplitError::Primary(e) => write!(fmt, "primary: {}", e,),
+      SplitError::Replica(index, e) => write!(fmt, "replica {}: {}", index, e,),
+    }
+  }
+}
+
+impl<W, R,> Error for SplitError<W, R,>
+  where W: Error, R: Error, {}
+
+impl<W, R,> CollectionError for SplitError<W, R,>
+  where W: CollectionError, R: CollectionError, {
+  #[inline]
+  fn kind(&self,) -> ErrorKind {
+    match self {
+      SplitError::Primary(e) => e.kind(),
+      SplitError::Replica(_, e) => e.kind(),
+    }
+  }
+  #[inline]
+  fn is_retryable(&self,) -> bool {
+    match self {
+      SplitError::Primary(e) => e.is_retryable(),
+      SplitError::Replica(_, e) => e.is_retryable(),
+    }
+  }
+}
+
+/// The result of a batch write through a `SplitCollection`.
+type BatchResult<W, R,> = Result<Result<(), Vec<Result<(), SplitError<W, R,>>>>, SplitError<W, R,>>;
+
+/// Wraps the errors of a batch write to the primary.
+fn batch_error<W, R,>(res: Result<Result<(), Vec<Result<(), W>>>, W>,) -> BatchResult<W, R,> {
+  match res {
+    Ok(Ok(())) => Ok(Ok(())),
+    Ok(Err(results)) => Ok(Err(results.into_iter().map(|res,| res.map_err(SplitError::Primary,),).collect())),
+    Err(e) => Err(SplitError::Primary(e,)),
+  }
+}
+
+/// A `TierListCollection` which writes to a primary collection and reads from replicas
+/// of it.
+/// 
+/// Reads are spread across the replicas in turn; if a replica fails with a retryable
+/// error the read moves on to the next replica until every replica has been tried. If
+/// there are no replicas reads go to the primary.
+/// 
+/// Cloning a `SplitCollection` shares the replicas and their turn order.
+#[derive(Clone,)]
+pub struct SplitCollection<W, R,> {
+  /// The collection written to.
+  primary: W,
+  /// The collections read from.
+  replicas: Rc<Vec<R>>,
+  /// The index of the replica to read from next.
+  next: Rc<Cell<usize>>,
+}
+
+impl<W, R,> SplitCollection<W, R,> {
+  /// Returns a new `SplitCollection`.
+  /// 
+  /// # Params
+  /// 
+  /// primary --- The collection to write to.  
+  /// replicas --- The collections to read from.  
+  #[inline]
+  pub fn new(primary: W, replicas: Vec<R>,) -> Self {
+    Self { primary, replicas: Rc::new(replicas,), next: Rc::default(), }
+  }
+  /// Gets the collection being written to.
+  #[inline]
+  pub const fn get_primary(&self,) -> &W { &self.primary }
+  /// Gets the collections being read from.
+  #[inline]
+  pub fn get_replicas(&self,) -> &[R] { &self.replicas }
+  /// Returns the index of the replica to start the next read from.
+  fn turn(&self,) -> usize {
+    let turn = self.next.get();
+
+    self.next.set(turn.wrapping_add(1,),);
+    turn % self.replicas.len()
+  }
+}
+
+impl<W, R,> fmt::Debug for SplitCollection<W, R,>
+  where W: fmt::Debug, R: fmt::Debug, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    fmt.debug_struct("SplitCollection",)
+    .field("primary", &self.primary,)
+    .field("replicas", &self.replicas,)
+    .finish()
+  }
+}
+
+impl<W, R,> TierListCollection for SplitCollection<W, R,>
+  where W: TierListCollection,
+    W::Document: 'static,
+    W::Error: 'static,
+    W::GetBatchDocuments: 'static,
+    W::GetDocument: 'static,
+    R: 'static + TierListCollection<Document = W::Document>, {
+  type Document = W::Document;
+  type Error = SplitError<W::Error, R::Error,>;
+  type GetBatchDocuments = LocalBoxFuture<'static, Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>>;
+  type GetDocument = LocalBoxFuture<'static, Result<Self::Document, Self::Error>>;
+  type WriteBatchDocuments = Map<W::WriteBatchDocuments, fn(Result<Result<(), Vec<Result<(), W::Error>>>, W::Error>,) -> BatchResult<W::Error, R::Error,>>;
+  type WriteDocument = MapErr<W::WriteDocument, fn(W::Error,) -> Self::Error>;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    if self.replicas.is_empty() {
+      return self.primary.get_documents(ids,)
+        .map(|res,| match res {
+          Ok(docs) => Ok(docs.into_iter().map(|doc,| doc.map_err(SplitError::Primary,),).collect()),
+          Err(e) => Err(SplitError::Primary(e,)),
+        },)
+        .boxed_local()
+    }
+
+    let replicas = self.replicas.clone();
+    let start = self.turn();
+    let ids = ids.into_iter().map(|id,| *id.borrow(),).collect::<Vec<_>>();
+
+    async move {
+      let mut results = ids.iter().map(|_,| None,).collect::<Vec<_>>();
+      //The positions of the documents still to be read.
+      let mut pending = (0..ids.len()).collect::<Vec<_>>();
+      //The last retryable failure of a whole batch.
+      let mut error = None;
+
+      for offset in 0..replicas.len() {
+        if pending.is_empty() { break }
+
+        let index = (start + offset) % replicas.len();
+        let batch = pending.iter().map(|&position,| &ids[position],);
+
+        match replicas[index].get_documents(batch,).await {
+          Ok(docs) => {
+            let mut failed = Vec::new();
+
+            for (position, doc,) in pending.into_iter().zip(docs,) {
+              if doc.as_ref().err().is_some_and(R::Error::is_retryable,) { failed.push(position,) }
+
+              results[position] = Some(doc.map_err(|e,| SplitError::Replica(index, e,),),);
+            }
+
+            pending = failed;
+          },
+          Err(e) if e.is_retryable() => error = Some(SplitError::Replica(index, e,),),
+          Err(e) => return Err(SplitError::Replica(index, e,)),
+        }
+      }
+
+      //Documents without a result were only in batches which failed.
+      results.into_iter().collect::<Option<Vec<_>>>()
+      .ok_or_else(|| error.expect("A batch failed"),)
+    }.boxed_local()
+  }
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    if self.replicas.is_empty() { return self.primary.get_document(id,).map_err(SplitError::Primary,).boxed_local() }
+
+    let replicas = self.replicas.clone();
+    let start = self.turn();
+    let id = *id;
+
+    async move {
+      let mut offset = 0;
+
+      loop {
+        let index = (start + offset) % replicas.len();
+
+        match replicas[index].get_document(&id,).await {
+          Ok(doc) => return Ok(doc),
+          Err(e) if e.is_retryable() && offset + 1 < replicas.len() => offset += 1,
+          Err(e) => return Err(SplitError::Replica(index, e,)),
+        }
+      }
+    }.boxed_local()
+  }
+  #[inline]
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    self.primary.write_documents(documents,).map(batch_error as fn(_,) -> _,)
+  }
+  #[inline]
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    self.primary.write_document(document,).map_err(SplitError::Primary as fn(_,) -> _,)
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{TierMeta, mock::{ChaosCollection, ChaosError,},};
+  use futures::executor::block_on;
+
+  #[test]
+  fn test_split() {
+    let ids = (0..3u8).map(|i,| DocumentId::from_bytes([i; 20]),).collect::<Vec<_>>();
+    let tiers = ids.iter().map(|&id,| TierMeta::new(id.into(), (None, id.into(), id.into(),), None, None,),).collect::<Vec<_>>();
+    let primary = ChaosCollection::new();
+    let replicas = (0..3).map(|_,| ChaosCollection::new(),).collect::<Vec<_>>();
+    let coll = SplitCollection::new(primary.clone(), replicas.clone(),);
+
+    block_on(async {
+      for replica in replicas.iter() {
+        replica.write_documents(&tiers,).await
+          .expect("Error writing tiers")
+          .expect("Error writing tiers");
+      }
+
+      //Reads take turns across the replicas.
+      for tier in tiers.iter().chain(tiers.iter(),) {
+        assert_eq!(coll.get_document(&tier.id,).await, Ok(*tier), "Error reading tier",);
+      }
+      for replica in replicas.iter() {
+        assert_eq!(replica.reads(), 2, "Error spreading reads",);
+      }
+
+      //A failing replica is skipped.
+      for id in ids.iter() { replicas[1].fail_reads(*id,); }
+      for tier in tiers.iter() {
+        assert_eq!(coll.get_document(&tier.id,).await, Ok(*tier), "Error failing over",);
+      }
+      assert_eq!(
+        coll.get_documents(&ids,).await,
+        Ok(tiers.iter().copied().map(Ok,).collect()),
+        "Error failing over batch",
+      );
+      assert!(
+        matches!(coll.get_documents([&DocumentId::from_bytes([9; 20]),],).await.as_deref(), Ok([Err(SplitError::Replica(_, ChaosError::Memory(_,),)),])),
+        "Error reading missing tier",
+      );
+
+      //Writes only go to the primary.
+      coll.write_document(&tiers[0],).await
+        .expect("Error writing tier");
+      assert_eq!(primary.writes(), 1, "Error writing to primary",);
+      assert!(replicas.iter().all(|replica,| replica.writes() == 3,), "Error writing to replicas",);
+
+      //Every replica failing fails the read.
+      for replica in replicas.iter() { replica.fail_reads(ids[0],); }
+      assert!(
+        matches!(coll.get_document(&ids[0],).await, Err(SplitError::Replica(_, ChaosError::Injected(id,),)) if id == ids[0]),
+        "Error failing read",
+      );
+    },);
+  }
+}
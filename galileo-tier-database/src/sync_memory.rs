@@ -0,0 +1,248 @@
+//! Defines a thread safe in memory document collection.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{
+  DocumentId, Document, TierListCollection, TierListCollectionScan, TierDocument, MemoryError,
+  AtomicCounters, Counters, CounterField, counters::add_delta,
+};
+use futures::{Future, future::{self, Ready,}, stream,};
+use std::{
+  fmt,
+  collections::{HashMap, hash_map::Entry,},
+  borrow::Borrow,
+  sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard, PoisonError,},
+};
+
+/// A `TierListCollection` which stores its documents in memory and can be shared
+/// between threads.
+/// 
+/// Cloning a `SyncMemoryCollection` produces a new handle to the same documents. Unlike
+/// `MemoryCollection` it does not support transactions, watchers or revisions, but it
+/// implements `TierListCollectionSend` so it can be used from a multithreaded executor.
+pub struct SyncMemoryCollection<D = TierDocument,> {
+  /// The documents in the collection.
+  documents: Arc<RwLock<HashMap<DocumentId, D>>>,
+}
+
+impl<D,> SyncMemoryCollection<D,> {
+  /// Returns a new empty `SyncMemoryCollection`.
+  #[inline]
+  pub fn new() -> Self {
+    Self { documents: Arc::new(RwLock::new(HashMap::new(),),), }
+  }
+  /// Returns the number of documents in the collection.
+  #[inline]
+  pub fn len(&self,) -> usize { self.read().len() }
+  /// Returns `true` if there are no documents in the collection.
+  #[inline]
+  pub fn is_empty(&self,) -> bool { self.read().is_empty() }
+  /// Locks the documents for reading.
+  /// 
+  /// A thread panicking while it held the lock cannot leave a document half written so
+  /// a poisoned lock is used as normal.
+  fn read(&self,) -> RwLockReadGuard<'_, HashMap<DocumentId, D>> {
+    self.documents.read().unwrap_or_else(PoisonError::into_inner,)
+  }
+  /// Locks the documents for writing.
+  fn write(&self,) -> RwLockWriteGuard<'_, HashMap<DocumentId, D>> {
+    self.documents.write().unwrap_or_else(PoisonError::into_inner,)
+  }
+}
+
+impl<D,> Default for SyncMemoryCollection<D,> {
+  #[inline]
+  fn default() -> Self { Self::new() }
+}
+
+impl<D,> Clone for SyncMemoryCollection<D,> {
+  #[inline]
+  fn clone(&self,) -> Self { Self { documents: self.documents.clone(), } }
+}
+
+/// Two `SyncMemoryCollection`s are equal if they are handles to the same documents.
+impl<D,> PartialEq for SyncMemoryCollection<D,> {
+  #[inline]
+  fn eq(&self, rhs: &Self,) -> bool { Arc::ptr_eq(&self.documents, &rhs.documents,) }
+}
+
+impl<D,> Eq for SyncMemoryCollection<D,> {}
+
+impl<D,> fmt::Debug for SyncMemoryCollection<D,>
+  where D: fmt::Debug, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    fmt.debug_struct("SyncMemoryCollection",)
+    .field("documents", &*self.read(),)
+    .finish()
+  }
+}
+
+impl<D,> TierListCollection for SyncMemoryCollection<D,>
+  where D: Document + Clone, {
+  type Document = D;
+  type Error = MemoryError;
+  type GetBatchDocuments = Ready<Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>>;
+  type GetDocument = Ready<Result<Self::Document, Self::Error>>;
+  type WriteBatchDocuments = Ready<Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>;
+  type WriteDocument = Ready<Result<(), Self::Error>>;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    let documents = self.read();
+
+    future::ok(ids.into_iter().map(|id,| {
+      let id = *id.borrow();
+
+      documents.get(&id,).cloned().ok_or(MemoryError::NotFound(id),)
+    },).collect(),)
+  }
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    future::ready(self.read().get(id,).cloned().ok_or(MemoryError::NotFound(*id),),)
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let mut stored = self.write();
+
+    for document in documents {
+      let document = document.borrow();
+
+      stored.insert(*document.get_id(), document.clone(),);
+    }
+
+    future::ok(Ok(()),)
+  }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    let document = document.borrow();
+
+    self.write().insert(*document.get_id(), document.clone(),);
+    future::ok((),)
+  }
+  #[inline]
+  fn exists(&self, id: &DocumentId,) -> impl Future<Output = Result<bool, Self::Error>> {
+    future::ok(self.read().contains_key(id,),)
+  }
+  fn write_document_if_absent<T,>(&self, document: &T,) -> impl Future<Output = Result<bool, Self::Error>>
+    where T: Borrow<Self::Document>, {
+    let document = document.borrow();
+
+    future::ok(match self.write().entry(*document.get_id(),) {
+      Entry::Occupied(_) => false,
+      Entry::Vacant(entry) => { entry.insert(document.clone(),); true },
+    },)
+  }
+}
+
+impl<D,> TierListCollectionScan for SyncMemoryCollection<D,>
+  where D: Document + Clone, {
+  type Scan = stream::Iter<std::vec::IntoIter<Result<Self::Document, Self::Error>>>;
+
+  fn scan(&self,) -> Self::Scan {
+    stream::iter(self.read().values().cloned().map(Ok,).collect::<Vec<_>>(),)
+  }
+  #[inline]
+  fn count(&self,) -> impl Future<Output = Result<u64, Self::Error>> {
+    future::ok(self.len() as u64,)
+  }
+}
+
+impl<D,> AtomicCounters for SyncMemoryCollection<D,>
+  where D: Document + Counters + Clone, {
+  type Increment = Ready<Result<u64, Self::Error>>;
+
+  fn increment_field(&self, id: &DocumentId, field: CounterField, delta: i64,) -> Self::Increment {
+    let mut documents = self.write();
+    let counter = documents.get_mut(id,).ok_or(MemoryError::NotFound(*id),)
+      .and_then(|document,| document.counter_mut(field,).ok_or(MemoryError::NoCounters(*id),),);
+
+    future::ready(counter.map(|counter,| { *counter = add_delta(*counter, delta,); *counter },),)
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{TierMeta, Card, CardStatus, TierListCollectionSend,};
+  use futures::{executor::{block_on, ThreadPool,}, task::SpawnExt,};
+
+  #[test]
+  fn test_concurrent_increments() {
+    let coll = SyncMemoryCollection::new();
+    let id = DocumentId::from_bytes([1; 20]);
+    let pool = ThreadPool::builder().pool_size(4,).create()
+      .expect("Error creating thread pool");
+    let card = Card {
+      id: id.into(),
+      name: "card".to_owned(),
+      description: String::new(),
+      up_votes: 0,
+      down_votes: 0,
+      bias: 0,
+      status: CardStatus::Open,
+      history: Vec::new(),
+      previous_card: None,
+      next_card: None,
+    };
+
+    block_on(coll.write_document(&card,),)
+      .expect("Error writing card");
+
+    let increments = (0..100).map(|_,| {
+      let coll = coll.clone();
+
+      pool.spawn_with_handle(async move { coll.increment_field(&id, CounterField::UpVotes, 1,).await },)
+        .expect("Error spawning increment")
+    },).collect::<Vec<_>>();
+    let values = block_on(future::join_all(increments,),);
+
+    assert!(values.iter().all(Result::is_ok,), "Error incrementing counter",);
+    assert_eq!(block_on(coll.get_document(&id,),).map(|card,| card.up_votes,), Ok(100), "Error lost increments",);
+  }
+  #[test]
+  fn test_spawned_cursor() {
+    let coll = SyncMemoryCollection::<TierMeta,>::new();
+    let ids = (0..4u8).map(|i,| DocumentId::from_bytes([i; 20]),).collect::<Vec<_>>();
+    let tiers = ids.iter().enumerate().map(|(i, &id,),| TierMeta::new(
+      id.into(), (None, id.into(), id.into(),),
+      ids.get(i.wrapping_sub(1,),).map(|&id,| id.into(),), ids.get(i + 1,).map(|&id,| id.into(),),
+    ),).collect::<Vec<_>>();
+    let pool = ThreadPool::builder().pool_size(2,).create()
+      .expect("Error creating thread pool");
+
+    block_on(coll.write_documents(&tiers,),)
+      .expect("Error writing tiers")
+      .expect("Error writing tiers");
+
+    //Walk forwards and backwards on the pool.
+    let walk = {
+      let (coll, front,) = (coll.clone(), ids[0],);
+
+      pool.spawn_with_handle(async move {
+        let mut cursor = coll.get_cursor_send::<TierMeta,>(&front,).await
+          .expect("Error reading front");
+        let mut walked = vec![cursor.get_item().id];
+
+        while let Some(next) = cursor.get_next_send().await.expect("Error reading next") {
+          walked.push(next.get_item().id,);
+          cursor = next;
+        }
+        while let Some(previous) = cursor.get_previous_send().await.expect("Error reading previous") {
+          walked.push(previous.get_item().id,);
+          cursor = previous;
+        }
+
+        walked
+      },).expect("Error spawning walk")
+    };
+
+    assert_eq!(
+      block_on(walk,),
+      vec![ids[0], ids[1], ids[2], ids[3], ids[2], ids[1], ids[0],],
+      "Error walking the list on another thread",
+    );
+    assert_eq!(coll.len(), 4, "Error storing tiers",);
+  }
+}
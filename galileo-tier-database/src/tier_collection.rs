@@ -1,18 +1,32 @@
 //! Defines a operations on a document collection which stores one or more tier lists.
 //! 
 //! Author --- daniel.bechaz@gmail.com  
-//! Last Moddified --- 2019-06-05
+//! Last Moddified --- 2026-10-14
 
-use crate::{DocumentId, Document, LinkedList,};
-use futures::{Future, TryFuture, FutureExt, TryFutureExt, future::MapOk,};
-use std::{convert::TryInto, borrow::Borrow,};
+use crate::{DocumentId, Document, LinkedList, LinkedListMut, CollectionError, ErrorKind, ItemError, WriteBatch, BatchReport, ReadOnly, CheckedWrites, SnapshotCollection, Prefetch, EmptyChunk, TransactionalCollection, Transaction, Card, TierMeta, LinkError, link_item_after, walk_links, guard_cycles,};
+use futures::{
+  Future, TryFuture, FutureExt, TryFutureExt, Stream, StreamExt, TryStreamExt,
+  future::{self, MapOk, Either, Ready,},
+  stream,
+};
+use std::{
+  fmt,
+  mem,
+  error::Error,
+  convert::TryInto,
+  collections::{HashMap, HashSet,},
+  cell::RefCell,
+  borrow::Borrow,
+  rc::Rc,
+  sync::Arc,
+};
 
 /// A collection of documents which make up a tier list.
 pub trait TierListCollection: Sized {
   /// The document type stored in the collection.
   type Document: Document;
   /// The error type returned by DB operations.
-  type Error;
+  type Error: CollectionError;
   /// The future type when batch fetching documents from the collection.
   type GetBatchDocuments: Future<Output = Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>>;
   /// The future type when fetching a document from the collection.
@@ -24,10 +38,18 @@ pub trait TierListCollection: Sized {
 
   /// Gets a batch of documents from the collection.
   /// 
+  /// The results are returned in the order of `ids` with one result for each id; an
+  /// id which is requested more than once gets a result each time.
+  /// 
+  /// Any iterator of identifiers can be passed, such as a `&[DocumentId]`, a
+  /// `Vec<DocumentId>` or an array of `&DocumentId`s.
+  /// 
   /// # Params
   /// 
   /// ids --- The identifiers of the documents in the collection.  
-  fn get_documents(&self, ids: &[&DocumentId],) -> Self::GetBatchDocuments;
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>;
   /// Gets a document from the collection.
   /// 
   /// # Params
@@ -36,11 +58,15 @@ pub trait TierListCollection: Sized {
   fn get_document(&self, id: &DocumentId,) -> Self::GetDocument;
   /// Writes documents to the collection.
   /// 
+  /// Any iterator of documents can be passed, such as a `Vec` of owned documents, a
+  /// slice of documents or an array of references.
+  /// 
   /// # Params
   /// 
   /// documents --- The documents to write to the collection.  
-  fn write_documents<T,>(&self, document: &[&T],) -> Self::WriteBatchDocuments
-    where T: Borrow<Self::Document>;
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>;
   /// Writes a document to the collection.
   /// 
   /// # Params
@@ -55,8 +81,10 @@ pub trait TierListCollection: Sized {
   /// # Params
   /// 
   /// ids --- The identifiers of the documents in the collection.  
-  fn get_items<T,>(&self, ids: &[&DocumentId],) -> MapOk<Self::GetBatchDocuments, fn(<Self::GetBatchDocuments as TryFuture>::Ok,) -> Vec<Result<T, <<<Self::GetBatchDocuments as TryFuture>::Ok as IntoIterator>::Item as TryInto<T>>::Error>>>
-    where Self::GetBatchDocuments: TryFutureExt,
+  fn get_items<T, I, B,>(&self, ids: I,) -> MapOk<Self::GetBatchDocuments, fn(<Self::GetBatchDocuments as TryFuture>::Ok,) -> Vec<Result<T, <<<Self::GetBatchDocuments as TryFuture>::Ok as IntoIterator>::Item as TryInto<T>>::Error>>>
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>,
+      Self::GetBatchDocuments: TryFutureExt,
       <Self::GetBatchDocuments as TryFuture>::Ok: IntoIterator,
       <<Self::GetBatchDocuments as TryFuture>::Ok as IntoIterator>::Item: TryInto<T>, {
     self.get_documents(ids,)
@@ -75,6 +103,80 @@ pub trait TierListCollection: Sized {
     self.get_document(id,)
     .map_ok(|doc,| doc.try_into(),)
   }
+  /// Gets documents from the collection and converts them to a return type.
+  /// 
+  /// Unlike `get_items` the failure to get and the failure to convert a document are
+  /// returned as a single `ItemError` for each document.
+  /// 
+  /// # Params
+  /// 
+  /// ids --- The identifiers of the documents in the collection.  
+  fn get_items_flat<T, I, B,>(&self, ids: I,) -> impl Future<Output = Result<Vec<Result<T, ItemError<Self::Error, <Self::Document as TryInto<T>>::Error>>>, Self::Error>>
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>,
+      Self::Document: TryInto<T>, {
+    self.get_documents(ids,)
+    .map_ok(|docs,| docs.into_iter()
+      .map(|doc,| doc.map_err(ItemError::Fetch,)?.try_into().map_err(ItemError::Convert,),)
+      .collect(),
+    )
+  }
+  /// Gets a document from the collection and converts it to a return type.
+  /// 
+  /// Unlike `get_item` the failure to get and the failure to convert the document are
+  /// returned as a single `ItemError`.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the document in the collection.  
+  fn get_item_flat<T,>(&self, id: &DocumentId,) -> impl Future<Output = Result<T, ItemError<Self::Error, <Self::Document as TryInto<T>>::Error>>>
+    where Self::Document: TryInto<T>, {
+    self.get_document(id,)
+    .map(|doc,| doc.map_err(ItemError::Fetch,)?.try_into().map_err(ItemError::Convert,),)
+  }
+  /// Converts items to documents and writes them to the collection.
+  /// 
+  /// The results are the same as for `write_documents`.
+  /// 
+  /// # Params
+  /// 
+  /// items --- The items to write to the collection.  
+  fn write_items<I, T,>(&self, items: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Into<Self::Document>, {
+    self.write_documents(items.into_iter().map(Into::<Self::Document>::into,),)
+  }
+  /// Converts an item to a document and writes it to the collection.
+  /// 
+  /// # Params
+  /// 
+  /// item --- The item to write to the collection.  
+  #[inline]
+  fn write_item<T,>(&self, item: T,) -> Self::WriteDocument
+    where T: Into<Self::Document>, {
+    self.write_document(&item.into(),)
+  }
+  /// Writes documents to the collection and reports the result of each write by the
+  /// identifier of its document.
+  /// 
+  /// The default implementation pairs the results of `write_documents` with the
+  /// documents in the order they were passed.
+  /// 
+  /// # Params
+  /// 
+  /// documents --- The documents to write to the collection.  
+  fn write_documents_reported<I, T,>(&self, documents: I,) -> impl Future<Output = Result<BatchReport<Self::Error,>, Self::Error>>
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let documents = documents.into_iter().collect::<Vec<_>>();
+    let ids = documents.iter().map(|doc,| *doc.borrow().get_id(),).collect::<Vec<_>>();
+
+    self.write_documents(documents,)
+    .map_ok(move |res,| match res {
+      Ok(()) => ids.into_iter().map(|id,| (id, Ok(()),),).collect(),
+      Err(results) => ids.into_iter().zip(results,).collect(),
+    },)
+  }
   /// Gets a cursor at an item in the collection.
   /// 
   /// # Params
@@ -86,10 +188,210 @@ pub trait TierListCollection: Sized {
     self.get_document(id,)
     .map_ok(Box::new(move |item,| Cursor::new(self, item.into(),),),)
   }
+  /// Gets a cursor at the front of the list of a tier with its position tracked from
+  /// zero.
+  /// 
+  /// # Params
+  /// 
+  /// tier --- The tier whose list to get the front of; it must not be empty.  
+  fn ref_front_cursor<'a, T,>(&'a self, tier: &TierMeta,) -> MapOk<Self::GetDocument, Box<dyn 'a + FnOnce(<Self::GetDocument as TryFuture>::Ok,) -> Cursor<T, &'a Self,>>>
+    where Self::GetDocument: TryFutureExt,
+      <Self::GetDocument as TryFuture>::Ok: Into<T>, {
+    self.get_document(tier.list_front(),)
+    .map_ok(Box::new(move |item,| Cursor::new(self, item.into(),).with_position(0,),),)
+  }
+  /// Gets a cursor at the front card of a tier using a single read, with its position
+  /// tracked from zero.
+  /// 
+  /// Fails with `JumpError::Empty` without reading anything if the front of the tier
+  /// is nil.
+  /// 
+  /// # Params
+  /// 
+  /// tier --- The tier whose front card to get.  
+  fn cursor_front(&self, tier: &TierMeta,) -> impl Future<Output = Result<Cursor<Card, &Self,>, JumpError<Self::Error,>>>
+    where Self::Document: Into<Card>, {
+    let (id, tier,) = (**tier.list_front(), *tier.id,);
+
+    async move {
+      if id.is_nil() { return Err(JumpError::Empty(tier,)) }
+
+      self.get_document(&id,).await
+        .map(|card,| Cursor::new(self, card.into(),).with_position(0,),)
+        .map_err(JumpError::Fetch,)
+    }
+  }
+  /// Checks if a document is in the collection.
+  /// 
+  /// The default implementation fetches the whole document and treats a `NotFound`
+  /// error as the document not existing; collections which can check for a document
+  /// more cheaply should override it.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the document in the collection.  
+  fn exists(&self, id: &DocumentId,) -> impl Future<Output = Result<bool, Self::Error>> {
+    self.get_document(id,).map(|res,| match res {
+      Ok(_) => Ok(true),
+      Err(e) if e.is_not_found() => Ok(false),
+      Err(e) => Err(e),
+    },)
+  }
+  /// Writes a document to the collection unless a document with the same identifier is
+  /// already stored and returns `true` if the document was written.
+  /// 
+  /// The default implementation checks `exists` before writing so another writer can
+  /// store the document between the check and the write; collections which can write
+  /// conditionally should override it.
+  /// 
+  /// # Params
+  /// 
+  /// document --- The document to write to the collection.  
+  fn write_document_if_absent<T,>(&self, document: &T,) -> impl Future<Output = Result<bool, Self::Error>>
+    where T: Borrow<Self::Document>, {
+    async move {
+      if self.exists(document.borrow().get_id(),).await? { return Ok(false) }
+
+      self.write_document(document,).await.map(|_,| true,)
+    }
+  }
+  /// Gets a document from the collection, writing the document returned by `make` if
+  /// there is no document, and returns the stored document and whether it was created.
+  /// 
+  /// The document is created using `write_document_if_absent` so if another writer
+  /// creates the document first their document is returned instead; for collections
+  /// which do not override `write_document_if_absent` the race between the check and
+  /// the write remains.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the document in the collection.  
+  /// make --- Builds the document to create; it must have the identifier `id`.  
+  fn get_or_create<F,>(&self, id: &DocumentId, make: F,) -> impl Future<Output = Result<(Self::Document, bool,), Self::Error>>
+    where F: FnOnce() -> Self::Document, {
+    let id = *id;
+
+    async move {
+      match self.get_document(&id,).await {
+        Ok(document) => return Ok((document, false,)),
+        Err(e) if e.is_not_found() => (),
+        Err(e) => return Err(e),
+      }
+
+      let document = make();
+
+      debug_assert_eq!(document.get_id(), &id, "`make` built a document with the wrong identifier",);
+      if self.write_document_if_absent(&document,).await? { return Ok((document, true,)) }
+
+      //Another writer created the document first.
+      self.get_document(&id,).await.map(|document,| (document, false,),)
+    }
+  }
+  /// Writes a document to the collection, merging it with the stored document if there
+  /// is one, and returns the written document and how it was written.
+  /// 
+  /// The document is inserted using `write_document_if_absent`; if a document is already
+  /// stored `merge` is called with the stored document and `document` and its result is
+  /// written over the stored document. Another writer can change the stored document
+  /// between it being read and the merged document being written; collections which
+  /// track revisions should use `RevisionedCollection::upsert_with_rev` instead.
+  /// 
+  /// `merge` does not say how it combined the documents so a merged document equal to
+  /// `document` is reported as `Upsert::Overwrote` even if the stored document was used,
+  /// such as when merging in a card with no votes.
+  /// 
+  /// # Params
+  /// 
+  /// document --- The document to write to the collection.  
+  /// merge --- Merges the stored document and `document`.  
+  fn upsert_with<F,>(&self, document: Self::Document, merge: F,) -> impl Future<Output = Result<(Self::Document, Upsert,), Self::Error>>
+    where F: FnOnce(Self::Document, Self::Document,) -> Self::Document,
+      Self::Document: Clone + PartialEq, {
+    async move {
+      let id = *document.get_id();
+      let existing = match self.get_document(&id,).await {
+        Ok(existing) => existing,
+        Err(e) if e.is_not_found() => {
+          if self.write_document_if_absent(&document,).await? { return Ok((document, Upsert::Inserted,)) }
+
+          //Another writer created the document first.
+          self.get_document(&id,).await?
+        },
+        Err(e) => return Err(e),
+      };
+      let merged = merge(existing, document.clone(),);
+      let upsert = if merged == document { Upsert::Overwrote } else { Upsert::Merged };
+
+      self.write_document(&merged,).await.map(|_,| (merged, upsert,),)
+    }
+  }
+  /// Gets a batch of documents from the collection paired with their identifiers.
+  /// 
+  /// # Params
+  /// 
+  /// ids --- The identifiers of the documents in the collection.  
+  /// 
+  /// # Panics
+  /// 
+  /// Panics if the collection does not return one result for each id in the order
+  /// they were requested.
+  fn get_documents_checked<I, B,>(&self, ids: I,) -> impl Future<Output = Result<Vec<(DocumentId, Result<Self::Document, Self::Error>,)>, Self::Error>>
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    let requested = ids.into_iter().map(|id,| *id.borrow(),).collect::<Vec<_>>();
+
+    self.get_documents(&requested,)
+    .map_ok(move |docs,| {
+      assert_eq!(
+        docs.len(), requested.len(),
+        "`get_documents` returned {} results for {} ids", docs.len(), requested.len(),
+      );
+
+      requested.into_iter().zip(docs,)
+      .inspect(|(id, doc,),| if let Ok(doc) = doc {
+        assert_eq!(doc.get_id(), id, "`get_documents` returned a document out of order",);
+      },)
+      .collect()
+    },)
+  }
+  /// Gets the documents with a sequence of identifiers as a stream.
+  /// 
+  /// The identifiers are pulled from `ids` lazily and fetched in chunks using
+  /// `get_documents`, fetching the next chunk while the documents of the current
+  /// chunk are yielded. The documents are yielded in the order of `ids`; if the batch
+  /// read of a chunk fails each document in the chunk yields the error rather than
+  /// the stream ending. Fails with `EmptyChunk` if `chunk` is zero.
+  /// 
+  /// # Params
+  /// 
+  /// ids --- The identifiers of the documents in the collection.  
+  /// chunk --- The number of identifiers in each batch read; it must not be zero.  
+  #[inline]
+  fn prefetch_ids<I, B,>(&self, ids: I, chunk: usize,) -> Result<Prefetch<'_, Self, I::IntoIter,>, EmptyChunk>
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>,
+      Self::Error: Clone, {
+    Prefetch::new(self, ids.into_iter(), chunk,)
+  }
+  /// Starts a new batch of writes to the collection.
+  #[inline]
+  fn write_batch(&self,) -> WriteBatch<'_, Self,> { WriteBatch::new(self,) }
+  /// Wraps this collection so that it can only be read from.
+  #[inline]
+  fn read_only(self,) -> ReadOnly<Self,>
+    where Self: Sized, { ReadOnly::new(self,) }
+  /// Wraps this collection so that writes never replace a different document with the
+  /// same identifier.
+  #[inline]
+  fn checked_writes(self,) -> CheckedWrites<Self,>
+    where Self: Sized, { CheckedWrites::new(self,) }
 }
 
 /// Extended behaviour for collection types.
-pub trait TierListCollectionExt: 'static + TierListCollection + Copy {
+/// 
+/// Implemented for every collection which can be cloned, such as a handle to shared
+/// state; the returned `Cursor`s own a clone of the collection.
+pub trait TierListCollectionExt: 'static + TierListCollection + Clone {
   /// Gets a cursor at an item in the collection.
   /// 
   /// # Params
@@ -101,9 +403,105 @@ pub trait TierListCollectionExt: 'static + TierListCollection + Copy {
     self.get_document(id,)
     .map_ok(Box::new(move |item,| Cursor::new(self, item.into(),),),)
   }
+  /// Gets a cursor at the front of the list of a tier with its position tracked from
+  /// zero.
+  /// 
+  /// # Params
+  /// 
+  /// tier --- The tier whose list to get the front of; it must not be empty.  
+  fn get_front_cursor<T,>(self, tier: &TierMeta,) -> MapOk<Self::GetDocument, Box<dyn FnOnce(<Self::GetDocument as TryFuture>::Ok,) -> Cursor<T, Self,>>>
+    where Self::GetDocument: TryFutureExt,
+      <Self::GetDocument as TryFuture>::Ok: Into<T>, {
+    self.get_document(tier.list_front(),)
+    .map_ok(Box::new(move |item,| Cursor::new(self, item.into(),).with_position(0,),),)
+  }
+}
+
+impl<Coll,> TierListCollectionExt for Coll
+  where Coll: 'static + TierListCollection + Clone, {}
+
+/// Extended behaviour for collections which can be used from a multithreaded executor.
+/// 
+/// Implemented for every collection which can be cloned and shared between threads
+/// whose documents, errors and futures can also be sent between threads, such as a
+/// `SyncMemoryCollection`. The futures returned by `get_cursor_send` and by
+/// `Cursor::get_next_send` and `Cursor::get_previous_send` own a clone of the
+/// collection and are `Send` so they can be spawned as tasks.
+pub trait TierListCollectionSend: 'static + Send + Sync + Clone + TierListCollection<
+  Document: Send,
+  Error: Send,
+  GetBatchDocuments: Send,
+  GetDocument: Send,
+  WriteBatchDocuments: Send,
+  WriteDocument: Send,
+> {
+  /// Gets a cursor at an item in the collection.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the document in the collection.  
+  fn get_cursor_send<T,>(&self, id: &DocumentId,) -> impl Future<Output = Result<Cursor<T, Self,>, Self::Error>> + Send
+    where Self::Document: Into<T>,
+      T: Send, {
+    cursor_send(self.clone(), *id,)
+  }
+}
+
+impl<Coll,> TierListCollectionSend for Coll
+  where Coll: 'static + Send + Sync + Clone + TierListCollection,
+    Coll::Document: Send,
+    Coll::Error: Send,
+    Coll::GetBatchDocuments: Send,
+    Coll::GetDocument: Send,
+    Coll::WriteBatchDocuments: Send,
+    Coll::WriteDocument: Send, {}
+
+/// Gets a cursor which owns its collection at an item in the collection.
+/// 
+/// # Params
+/// 
+/// collection --- The collection to get the item from.  
+/// id --- The identifier of the document in the collection.  
+async fn cursor_send<T, Coll,>(collection: Coll, id: DocumentId,) -> Result<Cursor<T, Coll,>, Coll::Error>
+  where Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  let item = collection.get_document(&id,).await?;
+
+  Ok(Cursor::new(collection, item.into(),))
+}
+
+/// A collection which is able to enumerate every document it stores.
+pub trait TierListCollectionScan: TierListCollection {
+  /// The stream type when scanning the documents in the collection.
+  type Scan: Stream<Item = Result<Self::Document, Self::Error>>;
+
+  /// Gets every document in the collection.
+  /// 
+  /// The order of the documents is unspecified but each document is yielded only once.
+  fn scan(&self,) -> Self::Scan;
+  /// Counts the documents in the collection.
+  /// 
+  /// The default implementation scans the whole collection; collections which track
+  /// their size should override it.
+  fn count(&self,) -> impl Future<Output = Result<u64, Self::Error>> {
+    self.scan().try_fold(0, |count, _,| future::ready(Ok(count + 1),),)
+  }
+  /// Takes a copy of every document in the collection which later changes to the
+  /// collection do not affect.
+  /// 
+  /// The default implementation scans the whole collection; collections which can copy
+  /// their documents at once should override it so that the copy is consistent.
+  fn snapshot(&self,) -> impl Future<Output = Result<SnapshotCollection<Self::Document,>, Self::Error>> {
+    self.scan()
+    .try_fold(HashMap::new(), |mut documents, doc,| {
+      documents.insert(*doc.get_id(), doc,);
+      future::ok(documents,)
+    },)
+    .map_ok(SnapshotCollection::new,)
+  }
 }
 
-impl<'a, Coll,> TierListCollection for &'a Coll
+impl<Coll,> TierListCollection for &Coll
   where Coll: TierListCollection, {
   type Document = Coll::Document;
   type Error = Coll::Error;
@@ -113,7 +511,9 @@ impl<'a, Coll,> TierListCollection for &'a Coll
   type WriteDocument = Coll::WriteDocument;
 
   #[inline]
-  fn get_documents(&self, id: &[&DocumentId],) -> Self::GetBatchDocuments {
+  fn get_documents<I, B,>(&self, id: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
     Coll::get_documents(*self, id,)
   }
   #[inline]
@@ -121,206 +521,1903 @@ impl<'a, Coll,> TierListCollection for &'a Coll
     Coll::get_document(*self, id,)
   }
   #[inline]
-  fn write_documents<T,>(&self, document: &[&T],) -> Self::WriteBatchDocuments
-    where T: Borrow<Self::Document> {
-    Coll::write_documents(*self, document,)
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    Coll::write_documents(*self, documents,)
   }
   #[inline]
   fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
     where T: Borrow<Self::Document> {
     Coll::write_document(*self, document,)
   }
+  #[inline]
+  fn write_documents_reported<I, T,>(&self, documents: I,) -> impl Future<Output = Result<BatchReport<Self::Error,>, Self::Error>>
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    Coll::write_documents_reported(*self, documents,)
+  }
+  #[inline]
+  fn exists(&self, id: &DocumentId,) -> impl Future<Output = Result<bool, Self::Error>> {
+    Coll::exists(*self, id,)
+  }
+  #[inline]
+  fn write_document_if_absent<T,>(&self, document: &T,) -> impl Future<Output = Result<bool, Self::Error>>
+    where T: Borrow<Self::Document>, {
+    Coll::write_document_if_absent(*self, document,)
+  }
 }
 
-/// A view into a collection.
-#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
-pub struct Cursor<T, Coll,>
+impl<Coll,> TierListCollection for Rc<Coll,>
   where Coll: TierListCollection, {
-  /// The `TierCollection` to get items from.
-  collection: Coll,
-  /// The item at this `Cursor`.
-  item: T,
-}
+  type Document = Coll::Document;
+  type Error = Coll::Error;
+  type GetBatchDocuments = Coll::GetBatchDocuments;
+  type GetDocument = Coll::GetDocument;
+  type WriteBatchDocuments = Coll::WriteBatchDocuments;
+  type WriteDocument = Coll::WriteDocument;
 
-impl<T, Coll,> Cursor<T, Coll,>
-  where Coll: TierListCollection, {
   #[inline]
-  const fn new(collection: Coll, item: T,) -> Self {
-    Self { collection, item, }
+  fn get_documents<I, B,>(&self, id: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    Coll::get_documents(&**self, id,)
   }
-  /// Breaks the cursor into its component parts.
   #[inline]
-  pub fn into_parts(self,) -> (Coll, T,) { (self.collection, self.item,) }
-  /// Maps the value stored in this `Cursor`.
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    Coll::get_document(&**self, id,)
+  }
   #[inline]
-  pub fn map<U, F,>(self, map: F,) -> Cursor<U, Coll,>
-    where F: FnOnce(T,) -> U, {
-    Cursor::new(self.collection, map(self.item,),)
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    Coll::write_documents(&**self, documents,)
   }
-  /// Gets the collection used by this `Cursor`.
   #[inline]
-  pub const fn get_collection(&self,) -> &Coll { &self.collection }
-  /// Gets the item at this cursor.
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document> {
+    Coll::write_document(&**self, document,)
+  }
   #[inline]
-  pub const fn get_item(&self,) -> &T { &self.item }
-  /// References the value inside this `Cursor`.
+  fn write_documents_reported<I, T,>(&self, documents: I,) -> impl Future<Output = Result<BatchReport<Self::Error,>, Self::Error>>
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    Coll::write_documents_reported(&**self, documents,)
+  }
   #[inline]
-  pub const fn as_ref(&self,) -> Cursor<&T, &Coll,> {
-    Cursor::new(self.get_collection(), self.get_item(),)
+  fn exists(&self, id: &DocumentId,) -> impl Future<Output = Result<bool, Self::Error>> {
+    Coll::exists(&**self, id,)
+  }
+  #[inline]
+  fn write_document_if_absent<T,>(&self, document: &T,) -> impl Future<Output = Result<bool, Self::Error>>
+    where T: Borrow<Self::Document>, {
+    Coll::write_document_if_absent(&**self, document,)
   }
 }
 
-impl<T, Coll,> Cursor<T, Coll,>
-  where T: LinkedList,
-    Coll: TierListCollection, {
-  /// Moves this `Cursor` to the next node in the linked list.
-  /// 
-  /// If there is no next node this `Cursor` is returned unchanged as `Error(self)`.
-  /// 
-  /// If there was an error getting the next node this `Cursor` is returned unchanged as
-  /// `Err((self, error))`.
-  pub fn move_next(self,) -> Result<impl Future<Output = Result<Self, (Self, Coll::Error,)>>, Self>
-    where Coll::GetDocument: FutureExt,
-      Coll::Document: Into<T>, {
-    //Get the id of the next node.
-    match self.item.get_next_id() {
-      //There is a next node.
-      Some(next_id) => Ok(
-        //Get the next node.
-        self.collection.get_document(next_id,)
-        .map(move |res,| match res {
-          Ok(item) => Ok(Self { item: item.into(), ..self }),
-          Err(e) => Err((self, e,))
-        },)
-      ),
-      //There is no node.
-      None => Err(self)
-    }
+impl<Coll,> TierListCollection for Arc<Coll,>
+  where Coll: TierListCollection, {
+  type Document = Coll::Document;
+  type Error = Coll::Error;
+  type GetBatchDocuments = Coll::GetBatchDocuments;
+  type GetDocument = Coll::GetDocument;
+  type WriteBatchDocuments = Coll::WriteBatchDocuments;
+  type WriteDocument = Coll::WriteDocument;
+
+  #[inline]
+  fn get_documents<I, B,>(&self, id: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    Coll::get_documents(&**self, id,)
   }
-  /// Moves this `Cursor` to the previous node in the linked list.
-  /// 
-  /// If there is no previous node this `Cursor` is returned unchanged.
-  /// 
-  /// If there was an error getting the previous node this `Cursor` is returned unchanged.
-  pub fn move_previous(self,) -> Result<impl Future<Output = Result<Self, (Self, Coll::Error,)>>, Self>
-    where Coll::GetDocument: FutureExt,
-      Coll::Document: Into<T>, {
-    //Get the id of the next node.
-    match self.item.get_previous_id() {
-      //There is a next node.
-      Some(previous_id) => Ok(
-        //Get the next node.
-        self.collection.get_document(previous_id,)
-        .map(move |res,| match res {
-          Ok(item) => Ok(Self { item: item.into(), ..self }),
-          Err(e) => Err((self, e,))
-        },)
-      ),
-      //There is no node.
-      None => Err(self)
-    }
+  #[inline]
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    Coll::get_document(&**self, id,)
   }
-  /// Gets a `Cursor` to the next node in the linked list.
-  pub async fn ref_next(&self,) -> Result<Option<Cursor<T, &Coll,>>, <Coll::GetDocument as TryFuture>::Error>
-    where Coll::GetDocument: TryFutureExt,
-      <Coll::GetDocument as TryFuture>::Ok: Into<T>, {
-    //Get the next id.
-    match self.item.get_next_id() {
-      //Get the cursor.
-      Some(next_id) => self.collection.ref_cursor(next_id,).await.map(Some),
-      None => Ok(None),
-    }
+  #[inline]
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    Coll::write_documents(&**self, documents,)
   }
-  /// Gets a `Cursor` to the previous node in the linked list.
-  pub async fn ref_previous(&self,) -> Result<Option<Cursor<T, &Coll,>>, <Coll::GetDocument as TryFuture>::Error>
-    where Coll::GetDocument: TryFutureExt,
-      <Coll::GetDocument as TryFuture>::Ok: Into<T>, {
-    //Get the previous id.
-    match self.item.get_previous_id().cloned() {
-      //Get the cursor.
-      Some(previous_id) => self.collection.ref_cursor(&previous_id,).await.map(Some),
-      None => Ok(None),
-    }
+  #[inline]
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document> {
+    Coll::write_document(&**self, document,)
+  }
+  #[inline]
+  fn write_documents_reported<I, T,>(&self, documents: I,) -> impl Future<Output = Result<BatchReport<Self::Error,>, Self::Error>>
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    Coll::write_documents_reported(&**self, documents,)
+  }
+  #[inline]
+  fn exists(&self, id: &DocumentId,) -> impl Future<Output = Result<bool, Self::Error>> {
+    Coll::exists(&**self, id,)
+  }
+  #[inline]
+  fn write_document_if_absent<T,>(&self, document: &T,) -> impl Future<Output = Result<bool, Self::Error>>
+    where T: Borrow<Self::Document>, {
+    Coll::write_document_if_absent(&**self, document,)
   }
 }
 
-impl<T, Coll,> Cursor<T, Coll,>
-  where T: LinkedList,
-    Coll: TierListCollection + Copy, {
-  /// Gets a `Cursor` to the next node in the linked list.
-  pub async fn get_next(&self,) -> Result<Option<Cursor<T, Coll,>>, <Coll::GetDocument as TryFuture>::Error>
-    where Coll::GetDocument: TryFutureExt,
-      <Coll::GetDocument as TryFuture>::Ok: Into<T>, {
-    //Get the next id.
-    match self.item.get_next_id() {
+impl<Coll,> TierListCollection for Box<Coll,>
+  where Coll: TierListCollection, {
+  type Document = Coll::Document;
+  type Error = Coll::Error;
+  type GetBatchDocuments = Coll::GetBatchDocuments;
+  type GetDocument = Coll::GetDocument;
+  type WriteBatchDocuments = Coll::WriteBatchDocuments;
+  type WriteDocument = Coll::WriteDocument;
+
+  #[inline]
+  fn get_documents<I, B,>(&self, id: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    Coll::get_documents(&**self, id,)
+  }
+  #[inline]
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    Coll::get_document(&**self, id,)
+  }
+  #[inline]
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    Coll::write_documents(&**self, documents,)
+  }
+  #[inline]
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document> {
+    Coll::write_document(&**self, document,)
+  }
+  #[inline]
+  fn write_documents_reported<I, T,>(&self, documents: I,) -> impl Future<Output = Result<BatchReport<Self::Error,>, Self::Error>>
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    Coll::write_documents_reported(&**self, documents,)
+  }
+  #[inline]
+  fn exists(&self, id: &DocumentId,) -> impl Future<Output = Result<bool, Self::Error>> {
+    Coll::exists(&**self, id,)
+  }
+  #[inline]
+  fn write_document_if_absent<T,>(&self, document: &T,) -> impl Future<Output = Result<bool, Self::Error>>
+    where T: Borrow<Self::Document>, {
+    Coll::write_document_if_absent(&**self, document,)
+  }
+}
+
+impl<Coll,> TierListCollectionScan for &Coll
+  where Coll: TierListCollectionScan, {
+  type Scan = Coll::Scan;
+
+  #[inline]
+  fn scan(&self,) -> Self::Scan { Coll::scan(*self,) }
+  #[inline]
+  fn count(&self,) -> impl Future<Output = Result<u64, Self::Error>> { Coll::count(*self,) }
+  #[inline]
+  fn snapshot(&self,) -> impl Future<Output = Result<SnapshotCollection<Self::Document,>, Self::Error>> { Coll::snapshot(*self,) }
+}
+
+impl<Coll,> TierListCollectionScan for Rc<Coll,>
+  where Coll: TierListCollectionScan, {
+  type Scan = Coll::Scan;
+
+  #[inline]
+  fn scan(&self,) -> Self::Scan { Coll::scan(&**self,) }
+  #[inline]
+  fn count(&self,) -> impl Future<Output = Result<u64, Self::Error>> { Coll::count(&**self,) }
+  #[inline]
+  fn snapshot(&self,) -> impl Future<Output = Result<SnapshotCollection<Self::Document,>, Self::Error>> { Coll::snapshot(&**self,) }
+}
+
+impl<Coll,> TierListCollectionScan for Arc<Coll,>
+  where Coll: TierListCollectionScan, {
+  type Scan = Coll::Scan;
+
+  #[inline]
+  fn scan(&self,) -> Self::Scan { Coll::scan(&**self,) }
+  #[inline]
+  fn count(&self,) -> impl Future<Output = Result<u64, Self::Error>> { Coll::count(&**self,) }
+  #[inline]
+  fn snapshot(&self,) -> impl Future<Output = Result<SnapshotCollection<Self::Document,>, Self::Error>> { Coll::snapshot(&**self,) }
+}
+
+impl<Coll,> TierListCollectionScan for Box<Coll,>
+  where Coll: TierListCollectionScan, {
+  type Scan = Coll::Scan;
+
+  #[inline]
+  fn scan(&self,) -> Self::Scan { Coll::scan(&**self,) }
+  #[inline]
+  fn count(&self,) -> impl Future<Output = Result<u64, Self::Error>> { Coll::count(&**self,) }
+  #[inline]
+  fn snapshot(&self,) -> impl Future<Output = Result<SnapshotCollection<Self::Document,>, Self::Error>> { Coll::snapshot(&**self,) }
+}
+
+/// How a document was written by an upsert.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum Upsert {
+  /// There was no stored document so the document was written as is.
+  Inserted,
+  /// The merge returned a document equal to the new document so it replaced the stored
+  /// document.
+  Overwrote,
+  /// The stored document was replaced by the merge of it and the new document.
+  Merged,
+}
+
+/// The errors when inserting a node into a linked list using a `Cursor`.
+#[derive(PartialEq, Eq, Clone, Debug,)]
+pub enum InsertError<E,> {
+  /// Getting the neighbouring node failed; nothing was written.
+  Fetch(E),
+  /// Writing the changed nodes failed.
+  Write(E),
+  /// Some of the changed nodes were not written; the report lists which were.
+  Partial(BatchReport<E,>),
+  /// A node to insert is already linked at the `Cursor` or is inserted twice; nothing
+  /// was written.
+  Duplicate(DocumentId),
+  /// The node with the first identifier does not link back to the node with the second
+  /// identifier; nothing was written.
+  Inconsistent(DocumentId, DocumentId),
+}
+
+impl<E,> fmt::Display for InsertError<E,>
+  where E: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      InsertError::Fetch(e) => write!(fmt, "failed to get the neighbouring node: {}", e,),
+      InsertError::Write(e) => write!(fmt, "failed to write the changed nodes: {}", e,),
+      InsertError::Partial(report) => write!(fmt, "only {} of {} changed nodes were written", report.succeeded_ids().count(), report.len(),),
+      InsertError::Duplicate(id) => write!(fmt, "the node with the id {:?} would be linked twice", id,),
+      InsertError::Inconsistent(node, neighbour) => write!(fmt, "the node with the id {:?} does not link back to the node with the id {:?}", node, neighbour,),
+    }
+  }
+}
+
+impl<E,> From<LinkError<E,>> for InsertError<E,> {
+  fn from(from: LinkError<E,>,) -> Self {
+    match from {
+      LinkError::Fetch(e) => InsertError::Fetch(e,),
+      LinkError::Write(e) => InsertError::Write(e,),
+      LinkError::Duplicate(id) => InsertError::Duplicate(id,),
+      LinkError::Inconsistent(node, neighbour) => InsertError::Inconsistent(node, neighbour,),
+      //A node reached twice links back to itself through the list.
+      LinkError::Cycle(id) => InsertError::Inconsistent(id, id,),
+      //Only `concat` checks the ends of lists.
+      LinkError::NotBack(id) | LinkError::NotFront(id) => InsertError::Inconsistent(id, id,),
+    }
+  }
+}
+
+impl<E,> Error for InsertError<E,>
+  where E: Error, {}
+
+/// The errors when removing a node from a linked list using a `Cursor`.
+/// 
+/// The list is unchanged after either error.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum RemoveError<E,> {
+  /// Getting a neighbouring node failed.
+  Fetch(E),
+  /// Committing the changed nodes failed.
+  Commit(E),
+}
+
+impl<E,> fmt::Display for RemoveError<E,>
+  where E: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      RemoveError::Fetch(e) => write!(fmt, "failed to get a neighbouring node: {}", e,),
+      RemoveError::Commit(e) => write!(fmt, "failed to commit the changed nodes: {}", e,),
+    }
+  }
+}
+
+impl<E,> Error for RemoveError<E,>
+  where E: Error, {}
+
+/// The errors when swapping nodes in a linked list using a `Cursor`.
+/// 
+/// The list is unchanged after either error.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum SwapError<E,> {
+  /// Getting a node failed.
+  Fetch(E),
+  /// Committing the changed nodes failed.
+  Commit(E),
+}
+
+impl<E,> fmt::Display for SwapError<E,>
+  where E: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      SwapError::Fetch(e) => write!(fmt, "failed to get a node: {}", e,),
+      SwapError::Commit(e) => write!(fmt, "failed to commit the changed nodes: {}", e,),
+    }
+  }
+}
+
+impl<E,> Error for SwapError<E,>
+  where E: Error, {}
+
+/// The errors when jumping a `Cursor` to an end of the list of a tier.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum JumpError<E,> {
+  /// The tier with the identifier has no cards.
+  Empty(DocumentId),
+  /// Getting the node at the end of the list failed.
+  Fetch(E),
+}
+
+impl<E,> fmt::Display for JumpError<E,>
+  where E: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      JumpError::Empty(id) => write!(fmt, "the tier with the id {:?} has no cards", id,),
+      JumpError::Fetch(e) => write!(fmt, "failed to get the end of the list: {}", e,),
+    }
+  }
+}
+
+impl<E,> Error for JumpError<E,>
+  where E: Error, {}
+
+/// The most items `Cursor::collect_forward` and `Cursor::collect_backward` collect when
+/// they are not limited.
+pub const DEFAULT_COLLECT_CAP: usize = 100_000;
+
+/// The errors when collecting the items of a linked list using a `Cursor`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum CollectError<E,> {
+  /// Getting a node failed.
+  Fetch(E),
+  /// The cap on the number of items was reached before the end of the list, which
+  /// suggests that the list contains a cycle; the identifier of the next node.
+  Cycle(DocumentId),
+}
+
+impl<E,> fmt::Display for CollectError<E,>
+  where E: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      CollectError::Fetch(e) => write!(fmt, "failed to get a node: {}", e,),
+      CollectError::Cycle(id) => write!(fmt, "the list did not end before the document with the id {:?}, suspected cycle", id,),
+    }
+  }
+}
+
+impl<E,> Error for CollectError<E,>
+  where E: Error, {}
+
+/// The error when getting a neighbour of a `Cursor` failed.
+/// 
+/// The error records where the `Cursor` was so that the walk can be recovered from the
+/// error alone.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub struct CursorError<E,> {
+  /// The identifier of the node the `Cursor` was at.
+  pub id: DocumentId,
+  /// The position of the `Cursor` in the list if it was tracked.
+  pub position: Option<u64>,
+  /// The error getting the neighbour.
+  pub error: E,
+}
+
+impl<E,> fmt::Display for CursorError<E,>
+  where E: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    write!(fmt, "failed to get a neighbour of the node with the id {:?}: {}", self.id, self.error,)
+  }
+}
+
+impl<E,> Error for CursorError<E,>
+  where E: Error, {}
+
+impl<E,> CollectionError for CursorError<E,>
+  where E: CollectionError, {
+  #[inline]
+  fn kind(&self,) -> ErrorKind { self.error.kind() }
+}
+
+/// How `Cursor::for_each_forward` writes the nodes it changes.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub struct ForEachOptions {
+  /// The most changed nodes written in each batch write.
+  pub batch_size: usize,
+  /// If `true` the walk stops after the first batch with a failed write; otherwise the
+  /// failed nodes are reported and the walk continues.
+  pub abort_on_failure: bool,
+}
+
+impl Default for ForEachOptions {
+  #[inline]
+  fn default() -> Self { Self { batch_size: 100, abort_on_failure: false, } }
+}
+
+/// The nodes visited by `Cursor::for_each_forward`.
+#[derive(PartialEq, Eq, Clone, Hash, Default, Debug,)]
+pub struct ForEachSummary {
+  /// The number of nodes passed to the mutation.
+  pub visited: u64,
+  /// The number of nodes the mutation changed, including the nodes which failed to
+  /// write.
+  pub modified: u64,
+  /// The identifiers of the changed nodes which failed to write, in list order.
+  pub failed: Vec<DocumentId>,
+}
+
+/// The errors which stop `Cursor::for_each_forward`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum ForEachError<E,> {
+  /// Getting a node failed.
+  Fetch(E),
+  /// Writing a batch of changed nodes failed and the walk was set to stop.
+  Write(E),
+  /// The walk reached the node with the identifier twice; the nodes already visited
+  /// were written.
+  Cycle(DocumentId),
+}
+
+impl<E,> fmt::Display for ForEachError<E,>
+  where E: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      ForEachError::Fetch(e) => write!(fmt, "failed to get a node: {}", e,),
+      ForEachError::Write(e) => write!(fmt, "failed to write the changed nodes: {}", e,),
+      ForEachError::Cycle(id) => write!(fmt, "the document with the id {:?} was reached twice", id,),
+    }
+  }
+}
+
+impl<E,> Error for ForEachError<E,>
+  where E: Error, {}
+
+/// A spot in a collection which a `Cursor` can be rewound to, returned by
+/// `Cursor::checkpoint`.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug,)]
+pub struct Checkpoint {
+  /// The identifier of the checkpointed document.
+  id: DocumentId,
+  /// The position of the checkpointed document if it was tracked.
+  position: Option<u64>,
+}
+
+impl Checkpoint {
+  /// Gets the identifier of the checkpointed document.
+  #[inline]
+  pub const fn get_id(&self,) -> &DocumentId { &self.id }
+  /// Gets the position of the checkpointed document if it was tracked.
+  #[inline]
+  pub const fn position(&self,) -> Option<u64> { self.position }
+}
+
+/// The outcome of swapping the node at a `Cursor` with a neighbour.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum Swap {
+  /// There was no neighbour to swap with; nothing was written.
+  NoNeighbour,
+  /// The nodes were swapped.
+  Swapped,
+  /// The nodes were swapped and an end of the list changed so documents which refer
+  /// to the ends of the list, such as the `TierMeta` of a tier, need updating.
+  NeedsEndsUpdate {
+    /// The new front of the list if it changed.
+    front: Option<DocumentId>,
+    /// The new back of the list if it changed.
+    back: Option<DocumentId>,
+  },
+}
+
+impl Swap {
+  /// Updates the ends of a tier to match the list after the swap.
+  /// 
+  /// # Params
+  /// 
+  /// tier --- The tier owning the list.  
+  pub fn update_ends(&self, tier: &mut TierMeta,) {
+    if let Swap::NeedsEndsUpdate { front, back, } = *self {
+      let ends = (tier.list_len(), front.map_or(*tier.list_front(), Into::into,), back.map_or(*tier.list_back(), Into::into,),);
+
+      *tier = TierMeta::new(tier.id, ends, tier.previous_tier, tier.next_tier,);
+    }
+  }
+}
+
+/// Moves a tracked position one node forward or backward; the position stops being
+/// tracked if it would leave the range of `u64`.
+/// 
+/// # Params
+/// 
+/// position --- The tracked position.  
+/// forward --- Whether to move forward.  
+#[inline]
+pub(crate) fn step_position(position: Option<u64>, forward: bool,) -> Option<u64> {
+  if forward { position?.checked_add(1,) } else { position?.checked_sub(1,) }
+}
+
+/// A view into a collection.
+/// 
+/// `Cursor` is `Debug` whenever its item is; only the type of the collection is
+/// printed.
+#[derive(PartialEq, Eq, Clone, Copy,)]
+pub struct Cursor<T, Coll,>
+  where Coll: TierListCollection, {
+  /// The `TierCollection` to get items from.
+  collection: Coll,
+  /// The item at this `Cursor`.
+  item: T,
+  /// The position of the item in its linked list if it is being tracked.
+  position: Option<u64>,
+  /// Whether the item may have been changed since it was read or written.
+  dirty: bool,
+}
+
+impl<T, Coll,> Cursor<T, Coll,>
+  where Coll: TierListCollection, {
+  #[inline]
+  pub(crate) const fn new(collection: Coll, item: T,) -> Self {
+    Self { collection, item, position: None, dirty: false, }
+  }
+  /// Starts tracking the position of the item at this `Cursor` in its linked list.
+  /// 
+  /// The position counts up as this `Cursor` moves forward and down as it moves
+  /// backward.
+  /// 
+  /// # Params
+  /// 
+  /// start --- The position of the current item.  
+  #[inline]
+  pub const fn with_position(mut self, start: u64,) -> Self {
+    self.position = Some(start,);
+    self
+  }
+  /// Gets the position of the item at this `Cursor` in its linked list; `None` if it
+  /// is not being tracked.
+  #[inline]
+  pub const fn position(&self,) -> Option<u64> { self.position }
+  /// Replaces the item at this `Cursor` with a neighbour, updating the position.
+  /// 
+  /// Returns the old item.
+  /// 
+  /// # Params
+  /// 
+  /// item --- The neighbouring item.  
+  /// forward --- Whether `item` is after the old item.  
+  pub(crate) fn step(&mut self, item: T, forward: bool,) -> T {
+    self.assert_clean();
+    self.position = step_position(self.position, forward,);
+    self.dirty = false;
+    mem::replace(&mut self.item, item,)
+  }
+  /// Catches moving away from a changed item which was not saved in debug builds.
+  #[inline]
+  fn assert_clean(&self,) {
+    debug_assert!(!self.dirty, "`Cursor` moved away from an item which was changed but not saved",);
+  }
+  /// Breaks the cursor into its component parts.
+  #[inline]
+  pub fn into_parts(self,) -> (Coll, T,) { (self.collection, self.item,) }
+  /// Maps the value stored in this `Cursor`.
+  #[inline]
+  pub fn map<U, F,>(self, map: F,) -> Cursor<U, Coll,>
+    where F: FnOnce(T,) -> U, {
+    Cursor { collection: self.collection, item: map(self.item,), position: self.position, dirty: self.dirty, }
+  }
+  /// Maps the value stored in this `Cursor` using a fallible conversion.
+  /// 
+  /// If the conversion fails this `Cursor` is returned unchanged as
+  /// `Err((self, error))`.
+  /// 
+  /// # Params
+  /// 
+  /// map --- Converts a copy of the item.  
+  pub fn try_map<U, E, F,>(self, map: F,) -> Result<Cursor<U, Coll,>, (Self, E,)>
+    where T: Clone,
+      F: FnOnce(T,) -> Result<U, E>, {
+    match map(self.item.clone(),) {
+      Ok(item) => Ok(Cursor { collection: self.collection, item, position: self.position, dirty: self.dirty, }),
+      Err(e) => Err((self, e,)),
+    }
+  }
+  /// Gets the collection used by this `Cursor`.
+  #[inline]
+  pub const fn get_collection(&self,) -> &Coll { &self.collection }
+  /// Gets the item at this cursor.
+  #[inline]
+  pub const fn get_item(&self,) -> &T { &self.item }
+  /// Gets the item at this cursor mutably and marks it as dirty.
+  /// 
+  /// Changes are not written to the collection until `save` is called. Moving away
+  /// from a dirty item panics in debug builds so that unsaved changes are not lost
+  /// silently.
+  #[inline]
+  pub fn get_item_mut(&mut self,) -> &mut T {
+    self.dirty = true;
+    &mut self.item
+  }
+  /// Returns `true` if the item at this cursor was accessed mutably since it was last
+  /// read or written.
+  #[inline]
+  pub const fn is_dirty(&self,) -> bool { self.dirty }
+  /// References the value inside this `Cursor`.
+  #[inline]
+  pub const fn as_ref(&self,) -> Cursor<&T, &Coll,> {
+    Cursor { collection: self.get_collection(), item: self.get_item(), position: self.position, dirty: self.dirty, }
+  }
+}
+
+impl<T, Coll,> Cursor<T, Coll,>
+  where T: LinkedList,
+    Coll: TierListCollection, {
+  /// Starts getting a neighbouring node of this `Cursor` if it has one.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the neighbouring node.  
+  #[inline]
+  fn get_neighbour(&self, id: Option<&DocumentId>,) -> Option<Coll::GetDocument> {
+    id.map(|id,| self.collection.get_document(id,),)
+  }
+  /// Wraps an error getting a neighbouring node with where this `Cursor` is.
+  /// 
+  /// # Params
+  /// 
+  /// error --- The error getting the neighbouring node.  
+  #[inline]
+  fn cursor_error<E,>(&self, error: E,) -> CursorError<E,> {
+    CursorError { id: *self.item.get_id(), position: self.position, error, }
+  }
+  /// Moves this `Cursor` to the next node in the linked list.
+  /// 
+  /// If there is no next node this `Cursor` is returned unchanged as `Error(self)`.
+  /// 
+  /// If there was an error getting the next node this `Cursor` is returned unchanged as
+  /// `Err((self, error))`.
+  pub fn move_next(self,) -> Result<impl Future<Output = Result<Self, (Self, Coll::Error,)>>, Self>
+    where Coll::GetDocument: FutureExt,
+      Coll::Document: Into<T>, {
+    self.assert_clean();
+    //Get the next node.
+    match self.get_neighbour(self.item.get_next_id(),) {
+      //There is a next node.
+      Some(next) => Ok(
+        next.map(move |res,| match res {
+          Ok(item) => Ok(Self { item: item.into(), position: step_position(self.position, true,), dirty: false, ..self }),
+          Err(e) => Err((self, e,))
+        },)
+      ),
+      //There is no node.
+      None => Err(self)
+    }
+  }
+  /// Moves this `Cursor` to the previous node in the linked list.
+  /// 
+  /// If there is no previous node this `Cursor` is returned unchanged.
+  /// 
+  /// If there was an error getting the previous node this `Cursor` is returned unchanged.
+  pub fn move_previous(self,) -> Result<impl Future<Output = Result<Self, (Self, Coll::Error,)>>, Self>
+    where Coll::GetDocument: FutureExt,
+      Coll::Document: Into<T>, {
+    self.assert_clean();
+    //Get the previous node.
+    match self.get_neighbour(self.item.get_previous_id(),) {
+      //There is a previous node.
+      Some(previous) => Ok(
+        previous.map(move |res,| match res {
+          Ok(item) => Ok(Self { item: item.into(), position: step_position(self.position, false,), dirty: false, ..self }),
+          Err(e) => Err((self, e,))
+        },)
+      ),
+      //There is no node.
+      None => Err(self)
+    }
+  }
+  /// Moves this `Cursor` to the next node in the linked list in place.
+  /// 
+  /// Returns `Ok(false)` if there is no next node. If there was an error getting the
+  /// next node this `Cursor` is unchanged.
+  pub async fn move_next_mut(&mut self,) -> Result<bool, Coll::Error>
+    where Coll::Document: Into<T>, {
+    match self.get_neighbour(self.item.get_next_id(),) {
+      Some(next) => { self.step(next.await?.into(), true,); Ok(true) },
+      None => Ok(false),
+    }
+  }
+  /// Moves this `Cursor` to the previous node in the linked list in place.
+  /// 
+  /// Returns `Ok(false)` if there is no previous node. If there was an error getting
+  /// the previous node this `Cursor` is unchanged.
+  pub async fn move_previous_mut(&mut self,) -> Result<bool, Coll::Error>
+    where Coll::Document: Into<T>, {
+    match self.get_neighbour(self.item.get_previous_id(),) {
+      Some(previous) => { self.step(previous.await?.into(), false,); Ok(true) },
+      None => Ok(false),
+    }
+  }
+  /// Moves this `Cursor` forward up to `n` nodes in the linked list.
+  /// 
+  /// Returns this `Cursor` at its final node and the number of nodes moved, which is
+  /// less than `n` if the list ended first. If there was an error getting a node this
+  /// `Cursor` is returned at the last node reached.
+  /// 
+  /// Each node only knows the identifiers of its neighbours so every node moved over
+  /// is one read; no node is read more than once.
+  /// 
+  /// # Params
+  /// 
+  /// n --- The number of nodes to move.  
+  pub async fn seek_forward(mut self, n: u64,) -> Result<(Self, u64,), (Self, Coll::Error,)>
+    where Coll::Document: Into<T>, {
+    for moved in 0..n {
+      match self.move_next_mut().await {
+        Ok(true) => {},
+        Ok(false) => return Ok((self, moved,)),
+        Err(e) => return Err((self, e,)),
+      }
+    }
+
+    Ok((self, n,))
+  }
+  /// Moves this `Cursor` backward up to `n` nodes in the linked list.
+  /// 
+  /// Returns this `Cursor` at its final node and the number of nodes moved, which is
+  /// less than `n` if the list ended first. If there was an error getting a node this
+  /// `Cursor` is returned at the last node reached.
+  /// 
+  /// # Params
+  /// 
+  /// n --- The number of nodes to move.  
+  pub async fn seek_backward(mut self, n: u64,) -> Result<(Self, u64,), (Self, Coll::Error,)>
+    where Coll::Document: Into<T>, {
+    for moved in 0..n {
+      match self.move_previous_mut().await {
+        Ok(true) => {},
+        Ok(false) => return Ok((self, moved,)),
+        Err(e) => return Err((self, e,)),
+      }
+    }
+
+    Ok((self, n,))
+  }
+  /// Moves this `Cursor` forward to the first node in the linked list whose item
+  /// matches a predicate, starting with the item at this `Cursor`.
+  /// 
+  /// Returns `None` if no item matched before the end of the list. If there was an
+  /// error getting a node this `Cursor` is returned at the last node checked so that
+  /// the search can be resumed. Fails with `CollectError::Cycle` if no item matched in
+  /// `DEFAULT_COLLECT_CAP` nodes, which suggests that the list contains a cycle.
+  /// 
+  /// # Params
+  /// 
+  /// pred --- Returns `true` for the item to find.  
+  pub async fn find_forward<P,>(mut self, mut pred: P,) -> Result<Option<Self>, (Self, CollectError<Coll::Error,>,)>
+    where P: FnMut(&T,) -> bool,
+      Coll::Document: Into<T>, {
+    for _ in 0..DEFAULT_COLLECT_CAP {
+      if pred(&self.item,) { return Ok(Some(self,)) }
+
+      match self.move_next_mut().await {
+        Ok(true) => {},
+        Ok(false) => return Ok(None),
+        Err(e) => return Err((self, CollectError::Fetch(e,),)),
+      }
+    }
+
+    let id = *self.item.get_id();
+
+    Err((self, CollectError::Cycle(id,),))
+  }
+  /// Maps the value stored in this `Cursor` using a fallible conversion, moving
+  /// forward through the linked list past the nodes which fail to convert.
+  /// 
+  /// Returns `None` if no item converted before the end of the list. If there was an
+  /// error getting a node this `Cursor` is returned at the last node tried. Fails with
+  /// `CollectError::Cycle` if no item converted in `DEFAULT_COLLECT_CAP` nodes, which
+  /// suggests that the list contains a cycle.
+  /// 
+  /// # Params
+  /// 
+  /// map --- Converts a copy of an item.  
+  pub async fn try_map_or_skip_next<U, E, F,>(mut self, mut map: F,) -> Result<Option<Cursor<U, Coll,>>, (Self, CollectError<Coll::Error,>,)>
+    where T: Clone,
+      F: FnMut(T,) -> Result<U, E>,
+      Coll::Document: Into<T>, {
+    for _ in 0..DEFAULT_COLLECT_CAP {
+      self = match self.try_map(&mut map,) {
+        Ok(cursor) => return Ok(Some(cursor,)),
+        Err((cursor, _,)) => cursor,
+      };
+
+      match self.move_next_mut().await {
+        Ok(true) => {},
+        Ok(false) => return Ok(None),
+        Err(e) => return Err((self, CollectError::Fetch(e,),)),
+      }
+    }
+
+    let id = *self.item.get_id();
+
+    Err((self, CollectError::Cycle(id,),))
+  }
+  /// Moves this `Cursor` to the front of the list of a tier using a single read.
+  /// 
+  /// The position of this `Cursor` is tracked from zero afterwards. If the tier is
+  /// empty or there was an error getting the front node this `Cursor` is returned
+  /// unchanged as `Err((self, error))`.
+  /// 
+  /// # Params
+  /// 
+  /// tier --- The tier owning the list.  
+  pub async fn jump_to_front(self, tier: &TierMeta,) -> Result<Self, (Self, JumpError<Coll::Error,>,)>
+    where Coll::Document: Into<T>, {
+    self.jump(tier, **tier.list_front(), Some(0,),).await
+  }
+  /// Moves this `Cursor` to the back of the list of a tier using a single read.
+  /// 
+  /// The position of this `Cursor` is tracked from the length of the list afterwards,
+  /// or is unknown if the length of the list is unknown. If the tier is empty or there was an error getting the back node this `Cursor` is
+  /// returned unchanged as `Err((self, error))`.
+  /// 
+  /// # Params
+  /// 
+  /// tier --- The tier owning the list.  
+  pub async fn jump_to_back(self, tier: &TierMeta,) -> Result<Self, (Self, JumpError<Coll::Error,>,)>
+    where Coll::Document: Into<T>, {
+    self.jump(tier, **tier.list_back(), tier.list_len().map(|len,| len.get() - 1,),).await
+  }
+  /// Remembers where this `Cursor` is so that it can be rewound there later.
+  #[inline]
+  pub fn checkpoint(&self,) -> Checkpoint {
+    Checkpoint { id: *self.item.get_id(), position: self.position, }
+  }
+  /// Moves this `Cursor` back to a checkpoint.
+  /// 
+  /// The checkpointed document is read again so any changes since the checkpoint are
+  /// seen. If there was an error getting the document this `Cursor` is returned
+  /// unchanged; a document which has since been deleted fails with the `NotFound`
+  /// error of the collection.
+  /// 
+  /// # Params
+  /// 
+  /// checkpoint --- The checkpoint to move to.  
+  pub async fn rewind(self, checkpoint: &Checkpoint,) -> Result<Self, (Self, Coll::Error,)>
+    where Coll::Document: Into<T>, {
+    self.assert_clean();
+    match self.collection.get_document(&checkpoint.id,).await {
+      Ok(item) => Ok(Self { item: item.into(), position: checkpoint.position, dirty: false, ..self }),
+      Err(e) => Err((self, e,)),
+    }
+  }
+  /// Moves this `Cursor` to an end of the list of a tier.
+  /// 
+  /// # Params
+  /// 
+  /// tier --- The tier owning the list.  
+  /// id --- The identifier of the end node.  
+  /// position --- The position of the end node.  
+  async fn jump(self, tier: &TierMeta, id: DocumentId, position: Option<u64>,) -> Result<Self, (Self, JumpError<Coll::Error,>,)>
+    where Coll::Document: Into<T>, {
+    self.assert_clean();
+    if id.is_nil() { return Err((self, JumpError::Empty(*tier.id,),)) }
+
+    match self.collection.get_document(&id,).await {
+      Ok(item) => Ok(Self { item: item.into(), position, dirty: false, ..self }),
+      Err(e) => Err((self, JumpError::Fetch(e,),)),
+    }
+  }
+  /// Converts this `Cursor` into a stream of its item followed by each node after it
+  /// in the linked list.
+  /// 
+  /// Nothing is read until the stream is polled and the stream ends after yielding an
+  /// error. The identifiers of the yielded nodes are kept and the stream yields
+  /// `CollectError::Cycle` instead of reading a node twice or reading more than
+  /// `DEFAULT_COLLECT_CAP` nodes.
+  pub fn into_stream(self,) -> impl Stream<Item = Result<T, CollectError<Coll::Error,>>>
+    where Coll::Document: Into<T>, {
+    self.into_checked_stream_by(|item,| item.get_next_id().copied(),)
+  }
+  /// Converts this `Cursor` into a stream of its item followed by each node before it
+  /// in the linked list.
+  /// 
+  /// Nothing is read until the stream is polled and the stream ends after yielding an
+  /// error. As with `into_stream` a cycle yields `CollectError::Cycle`.
+  pub fn into_stream_back(self,) -> impl Stream<Item = Result<T, CollectError<Coll::Error,>>>
+    where Coll::Document: Into<T>, {
+    self.into_checked_stream_by(|item,| item.get_previous_id().copied(),)
+  }
+  /// Converts this `Cursor` into a stream of its item followed by each node after it
+  /// in the linked list, ending before the first item which fails a predicate.
+  /// 
+  /// Nothing after the failing node is read. The stream ends after yielding an error.
+  /// 
+  /// # Params
+  /// 
+  /// pred --- Returns `true` for the items to yield.  
+  pub fn stream_while<P,>(self, pred: P,) -> impl Stream<Item = Result<T, CollectError<Coll::Error,>>>
+    where P: FnMut(&T,) -> bool,
+      Coll::Document: Into<T>, {
+    let stream = Box::pin(self.into_stream(),);
+
+    stream::unfold(Some((stream, pred,),), |state,| async move {
+      let (mut stream, mut pred,) = state?;
+
+      match stream.next().await? {
+        Ok(item) if pred(&item,) => Some((Ok(item), Some((stream, pred,),),)),
+        //Dropping the stream stops the walk at the failing node.
+        Ok(_) => None,
+        Err(e) => Some((Err(e), None,)),
+      }
+    },)
+  }
+  /// Converts this `Cursor` into a stream of each node in the linked list from the
+  /// first node which fails a predicate, starting with the item at this `Cursor`.
+  /// 
+  /// The items which match the predicate before the first failing node are skipped
+  /// and the predicate is not called again once an item fails it. The stream ends
+  /// after yielding an error.
+  /// 
+  /// # Params
+  /// 
+  /// pred --- Returns `true` for the items to skip.  
+  pub fn skip_until<P,>(self, pred: P,) -> impl Stream<Item = Result<T, CollectError<Coll::Error,>>>
+    where P: FnMut(&T,) -> bool,
+      Coll::Document: Into<T>, {
+    let stream = Box::pin(self.into_stream(),);
+
+    stream::unfold(Some((stream, Some(pred,),),), |state,| async move {
+      let (mut stream, mut pred,) = state?;
+
+      loop {
+        match stream.next().await? {
+          Ok(item) if pred.as_mut().is_some_and(|skip,| skip(&item,),) => continue,
+          Ok(item) => return Some((Ok(item), Some((stream, None,),),)),
+          Err(e) => return Some((Err(e), None,)),
+        }
+      }
+    },)
+  }
+  /// Collects up to `limit` items starting with the item at this `Cursor` and
+  /// continuing with each node after it in the linked list.
+  /// 
+  /// A `limit` of zero collects to the end of the list but fails with
+  /// `CollectError::Cycle` if the list is longer than `DEFAULT_COLLECT_CAP`.
+  /// 
+  /// # Params
+  /// 
+  /// limit --- The most items to collect.  
+  pub async fn collect_forward(&self, limit: usize,) -> Result<Vec<T>, CollectError<Coll::Error,>>
+    where T: Clone,
+      Coll::Document: Into<T>, {
+    self.collect_forward_capped(limit, DEFAULT_COLLECT_CAP,).await
+  }
+  /// Collects up to `limit` items starting with the item at this `Cursor` and
+  /// continuing with each node before it in the linked list.
+  /// 
+  /// A `limit` of zero collects to the front of the list but fails with
+  /// `CollectError::Cycle` if the list is longer than `DEFAULT_COLLECT_CAP`.
+  /// 
+  /// # Params
+  /// 
+  /// limit --- The most items to collect.  
+  pub async fn collect_backward(&self, limit: usize,) -> Result<Vec<T>, CollectError<Coll::Error,>>
+    where T: Clone,
+      Coll::Document: Into<T>, {
+    self.collect_backward_capped(limit, DEFAULT_COLLECT_CAP,).await
+  }
+  /// Like `collect_forward` with a different cap on the items collected when `limit`
+  /// is zero.
+  /// 
+  /// # Params
+  /// 
+  /// limit --- The most items to collect.  
+  /// cap --- The most items to collect when `limit` is zero.  
+  pub async fn collect_forward_capped(&self, limit: usize, cap: usize,) -> Result<Vec<T>, CollectError<Coll::Error,>>
+    where T: Clone,
+      Coll::Document: Into<T>, {
+    self.collect_by(|item,| item.get_next_id().copied(), limit, cap,).await
+  }
+  /// Like `collect_backward` with a different cap on the items collected when `limit`
+  /// is zero.
+  /// 
+  /// # Params
+  /// 
+  /// limit --- The most items to collect.  
+  /// cap --- The most items to collect when `limit` is zero.  
+  pub async fn collect_backward_capped(&self, limit: usize, cap: usize,) -> Result<Vec<T>, CollectError<Coll::Error,>>
+    where T: Clone,
+      Coll::Document: Into<T>, {
+    self.collect_by(|item,| item.get_previous_id().copied(), limit, cap,).await
+  }
+  /// Gets up to `n` nodes after this `Cursor` in the linked list, in order.
+  /// 
+  /// The result is the same as calling `get_next` up to `n` times. Each node only knows
+  /// the identifier of the node after it so the nodes are read one after another, but
+  /// no node is read more than once and nothing past the `n`th node is read.
+  /// 
+  /// # Params
+  /// 
+  /// n --- The most nodes to get.  
+  pub async fn prefetch_next(&self, n: usize,) -> Result<Vec<T>, Coll::Error>
+    where T: Clone,
+      Coll::Document: Into<T>, {
+    Cursor::new(&self.collection, self.item.clone(),).into_stream_by(|item,| item.get_next_id().copied(),)
+    .skip(1,)
+    .take(n,)
+    .try_collect().await
+  }
+  /// Counts the steps forward from this `Cursor` to the node with an identifier.
+  /// 
+  /// Returns `None` if the list ended or `max` steps were taken before the node was
+  /// found; a node behind this `Cursor` is never found. Each node only knows the
+  /// identifier of the node after it so the nodes are read one after another, but
+  /// nothing past the `max`th node is read.
+  /// 
+  /// # Params
+  /// 
+  /// other_id --- The identifier of the node to count to.  
+  /// max --- The most steps to take.  
+  pub async fn distance_to(&self, other_id: &DocumentId, max: u64,) -> Result<Option<u64>, Coll::Error>
+    where T: Clone,
+      Coll::Document: Into<T>, {
+    let mut stream = Box::pin(Cursor::new(&self.collection, self.item.clone(),).into_stream_by(|item,| item.get_next_id().copied(),),);
+    let mut steps = 0;
+
+    while let Some(item) = stream.next().await {
+      if item?.get_id() == other_id { return Ok(Some(steps,)) }
+      if steps == max { break }
+
+      steps += 1;
+    }
+
+    Ok(None)
+  }
+  /// Collects the items of nodes following a link.
+  /// 
+  /// # Params
+  /// 
+  /// link --- Gets the identifier of the node after an item.  
+  /// limit --- The most items to collect; zero for no limit.  
+  /// cap --- The most items to collect when `limit` is zero.  
+  async fn collect_by(&self, link: fn(&T,) -> Option<DocumentId>, limit: usize, cap: usize,) -> Result<Vec<T>, CollectError<Coll::Error,>>
+    where T: Clone,
+      Coll::Document: Into<T>, {
+    let max = if limit == 0 { cap } else { limit };
+    let mut stream = Box::pin(Cursor::new(&self.collection, self.item.clone(),).into_stream_by(link,),);
+    let mut items = Vec::new();
+
+    while items.len() < max {
+      match stream.next().await {
+        Some(item) => items.push(item.map_err(CollectError::Fetch,)?,),
+        None => return Ok(items),
+      }
+    }
+
+    //Reaching the cap before the end of the list is assumed to be a cycle.
+    match items.last().and_then(link,) {
+      Some(id) if limit == 0 => Err(CollectError::Cycle(id,)),
+      _ => Ok(items),
+    }
+  }
+  /// Converts this `Cursor` into a stream of nodes following a link.
+  /// 
+  /// # Params
+  /// 
+  /// link --- Gets the identifier of the node after an item in the stream.  
+  fn into_stream_by(self, link: fn(&T,) -> Option<DocumentId>,) -> impl Stream<Item = Result<T, Coll::Error>>
+    where Coll::Document: Into<T>, {
+    walk_links(self.collection, Ok(self.item,), link,)
+  }
+  /// Converts this `Cursor` into a stream of nodes following a link which is guarded
+  /// by `guard_cycles`.
+  /// 
+  /// # Params
+  /// 
+  /// link --- Gets the identifier of the node after an item in the stream.  
+  fn into_checked_stream_by(self, link: fn(&T,) -> Option<DocumentId>,) -> impl Stream<Item = Result<T, CollectError<Coll::Error,>>>
+    where Coll::Document: Into<T>, {
+    guard_cycles(self.into_stream_by(link,), link,)
+  }
+  /// Gets a `Cursor` to the next node in the linked list.
+  /// 
+  /// If there was an error getting the next node the error records where this `Cursor`
+  /// is.
+  pub async fn ref_next(&self,) -> Result<Option<Cursor<T, &Coll,>>, CursorError<<Coll::GetDocument as TryFuture>::Error,>>
+    where Coll::GetDocument: TryFutureExt,
+      <Coll::GetDocument as TryFuture>::Ok: Into<T>, {
+    //Get the next id.
+    match self.item.get_next_id() {
       //Get the cursor.
       Some(next_id) => self.collection.ref_cursor(next_id,).await
-        .map(|cursor,| Some(cursor.copied_coll()),),
+        .map(|cursor,| Some(Cursor { position: step_position(self.position, true,), ..cursor }),)
+        .map_err(|e,| self.cursor_error(e,),),
       None => Ok(None),
     }
   }
   /// Gets a `Cursor` to the previous node in the linked list.
-  pub async fn get_previous(&self,) -> Result<Option<Cursor<T, Coll,>>, <Coll::GetDocument as TryFuture>::Error>
+  /// 
+  /// If there was an error getting the previous node the error records where this
+  /// `Cursor` is.
+  pub async fn ref_previous(&self,) -> Result<Option<Cursor<T, &Coll,>>, CursorError<<Coll::GetDocument as TryFuture>::Error,>>
     where Coll::GetDocument: TryFutureExt,
       <Coll::GetDocument as TryFuture>::Ok: Into<T>, {
     //Get the previous id.
-    match self.item.get_previous_id().cloned() {
+    match self.item.get_previous_id() {
       //Get the cursor.
-      Some(previous_id) => self.collection.ref_cursor(&previous_id,).await
-        .map(|cursor,| Some(cursor.copied_coll()),),
+      Some(previous_id) => self.collection.ref_cursor(previous_id,).await
+        .map(|cursor,| Some(Cursor { position: step_position(self.position, false,), ..cursor }),)
+        .map_err(|e,| self.cursor_error(e,),),
       None => Ok(None),
     }
   }
 }
 
-impl<'t, T, Coll,> Cursor<&'t T, Coll,>
+impl<T, Coll,> Cursor<T, Coll,>
+  where T: Clone + Into<Coll::Document>,
+    Coll: TierListCollection, {
+  /// Writes the item at this `Cursor` to the collection and clears its dirty flag.
+  pub async fn save(&mut self,) -> Result<(), Coll::Error> {
+    self.collection.write_document(&Into::<Coll::Document>::into(self.item.clone(),),).await?;
+    self.dirty = false;
+
+    Ok(())
+  }
+  /// Writes the item at this `Cursor` to the collection and then moves this `Cursor` to
+  /// the next node in the linked list in place.
+  /// 
+  /// Returns `Ok(false)` if there is no next node. If there was an error writing the
+  /// item or getting the next node this `Cursor` is unchanged.
+  pub async fn save_and_move_next(&mut self,) -> Result<bool, Coll::Error>
+    where T: LinkedList,
+      Coll::Document: Into<T>, {
+    self.save().await?;
+    self.move_next_mut().await
+  }
+  /// Applies a mutation to the item at this `Cursor` and each node after it in the
+  /// linked list, writing the changed nodes back in batches.
+  /// 
+  /// If there was an error getting a node or the walk reached a node twice the changed
+  /// nodes already visited are written before the walk stops. The summary of the nodes visited is returned with any
+  /// error.
+  /// 
+  /// # Params
+  /// 
+  /// f --- Changes an item; returns `false` to skip writing it.  
+  /// options --- How the changed nodes are written.  
+  /// 
+  /// # Panics
+  /// 
+  /// Panics if the batch size is zero.
+  pub async fn for_each_forward<F,>(self, mut f: F, options: ForEachOptions,) -> Result<ForEachSummary, (ForEachSummary, ForEachError<Coll::Error,>,)>
+    where T: LinkedList,
+      F: FnMut(&mut T,) -> bool,
+      Coll::Document: Into<T>, {
+    assert!(options.batch_size > 0, "`for_each_forward` requires a batch size greater than zero",);
+
+    let Self { collection, item, .. } = self;
+    let mut stream = Box::pin(Cursor::new(&collection, item,).into_stream(),);
+    let mut summary = ForEachSummary::default();
+    let mut batch = Vec::with_capacity(options.batch_size,);
+
+    loop {
+      let next = match stream.next().await {
+        Some(Ok(mut item)) => {
+          summary.visited += 1;
+          if f(&mut item,) {
+            summary.modified += 1;
+            batch.push(item.into(),);
+          }
+
+          Ok(true)
+        },
+        Some(Err(CollectError::Fetch(e))) => Err(ForEachError::Fetch(e,),),
+        Some(Err(CollectError::Cycle(id))) => Err(ForEachError::Cycle(id,),),
+        None => Ok(false),
+      };
+
+      //Write the batch once it is full or the walk is ending.
+      if !batch.is_empty() && (batch.len() == options.batch_size || !matches!(next, Ok(true))) {
+        let batch = mem::replace(&mut batch, Vec::with_capacity(options.batch_size,),);
+        let error = match collection.write_documents_reported(&batch,).await {
+          Ok(report) => {
+            let failed = summary.failed.len();
+
+            summary.failed.extend(report.failures().map(|(id, _,),| *id,),);
+            if summary.failed.len() == failed { None }
+            else { report.into_iter().find_map(|(_, res,),| res.err(),) }
+          },
+          Err(e) => {
+            summary.failed.extend(batch.iter().map(|doc,| *doc.get_id(),),);
+            Some(e)
+          },
+        };
+
+        if let (true, Some(e),) = (options.abort_on_failure, error,) { return Err((summary, ForEachError::Write(e,),)) }
+      }
+
+      match next {
+        Ok(true) => {},
+        Ok(false) => return Ok(summary),
+        Err(e) => return Err((summary, e,)),
+      }
+    }
+  }
+}
+
+impl<T, Coll,> Cursor<T, Coll,>
+  where T: LinkedListMut + Clone + Into<Coll::Document>,
+    Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  /// Inserts a node into the linked list after this `Cursor`.
+  /// 
+  /// The links of `new` are set, this node and its old next node are linked to `new`
+  /// and the changed nodes are written using a single batch write. This `Cursor` stays
+  /// at its node but its item is updated so that moving next reaches `new`.
+  /// 
+  /// If only some of the nodes were written `InsertError::Partial` reports which were
+  /// and the item of this `Cursor` is only updated if its own node was written.
+  /// 
+  /// Fails with `InsertError::Inconsistent` if the old next node does not link back to
+  /// this node; see `link_after`.
+  /// 
+  /// # Params
+  /// 
+  /// new --- The node to insert.  
+  pub async fn insert_after(&mut self, new: T,) -> Result<(), InsertError<Coll::Error,>> {
+    let (item, report,) = link_item_after(&self.collection, self.item.clone(), new,).await?;
+
+    self.apply_linked(item, report.written,)
+  }
+  /// Inserts a node into the linked list before this `Cursor`.
+  /// 
+  /// The links of `new` are set, this node and its old previous node are linked to
+  /// `new` and the changed nodes are written using a single batch write. This `Cursor`
+  /// stays at its node but its item is updated so that moving previous reaches `new`.
+  /// 
+  /// If only some of the nodes were written `InsertError::Partial` reports which were
+  /// and the item of this `Cursor` is only updated if its own node was written.
+  /// 
+  /// # Params
+  /// 
+  /// new --- The node to insert.  
+  pub async fn insert_before(&mut self, mut new: T,) -> Result<(), InsertError<Coll::Error,>> {
+    let previous_id = self.item.get_previous_id().copied();
+    let mut item = self.item.clone();
+
+    new.set_previous_id(previous_id,);
+    new.set_next_id(Some(*item.get_id()),);
+    item.set_previous_id(Some(*new.get_id()),);
+
+    let previous = match self.get_neighbour(previous_id.as_ref(),) {
+      Some(previous) => {
+        let mut previous: T = previous.await.map_err(InsertError::Fetch,)?.into();
+
+        previous.set_next_id(Some(*new.get_id()),);
+        Some(previous)
+      },
+      None => None,
+    };
+
+    let res = self.write_linked(item.clone(), previous.into_iter().chain(Some(new,),).chain(Some(item,),),).await;
+
+    //The item is one position later unless the list was only partly relinked.
+    match res {
+      Ok(()) => self.position = step_position(self.position, true,),
+      Err(InsertError::Partial(_)) => self.position = None,
+      Err(_) => {},
+    }
+
+    res
+  }
+  /// Inserts a chain of nodes into the linked list after this `Cursor`.
+  /// 
+  /// The nodes of `chain` are linked to each other in order, the chain is linked
+  /// between this node and its old next node and the changed nodes are written using a
+  /// single batch write. This `Cursor` stays at its node but its item is updated so
+  /// that moving next reaches the first node of the chain. An empty chain writes
+  /// nothing.
+  /// 
+  /// Fails with `InsertError::Duplicate` if the chain contains this node, its old next
+  /// node or the same node twice.
+  /// 
+  /// If only some of the nodes were written `InsertError::Partial` reports which were
+  /// and the item of this `Cursor` is only updated if its own node was written.
+  /// 
+  /// # Params
+  /// 
+  /// chain --- The nodes to insert in order.  
+  pub async fn splice_after(&mut self, mut chain: Vec<T>,) -> Result<(), InsertError<Coll::Error,>> {
+    let (first, last,) = match (chain.first(), chain.last(),) {
+      (Some(first), Some(last),) => (*first.get_id(), *last.get_id(),),
+      _ => return Ok(()),
+    };
+    let next_id = self.item.get_next_id().copied();
+    let mut linked = std::iter::once(*self.item.get_id(),).chain(next_id,).collect::<HashSet<_>>();
+
+    if let Some(node) = chain.iter().find(|node,| !linked.insert(*node.get_id(),),) {
+      return Err(InsertError::Duplicate(*node.get_id(),))
+    }
+
+    let ids = chain.iter().map(|node,| *node.get_id(),).collect::<Vec<_>>();
+    for (index, node,) in chain.iter_mut().enumerate() {
+      node.set_previous_id(Some(index.checked_sub(1,).map_or(*self.item.get_id(), |index,| ids[index],),),);
+      node.set_next_id(ids.get(index + 1,).copied().or(next_id,),);
+    }
+
+    let mut item = self.item.clone();
+    item.set_next_id(Some(first,),);
+
+    let next = match self.get_neighbour(next_id.as_ref(),) {
+      Some(next) => {
+        let mut next: T = next.await.map_err(InsertError::Fetch,)?.into();
+
+        next.set_previous_id(Some(last,),);
+        Some(next)
+      },
+      None => None,
+    };
+
+    self.write_linked(item.clone(), std::iter::once(item,).chain(chain,).chain(next,),).await
+  }
+  /// Writes the nodes changed by an insert and updates the item of this `Cursor` if its
+  /// node was written.
+  /// 
+  /// # Params
+  /// 
+  /// item --- The changed item of this `Cursor`.  
+  /// nodes --- The changed nodes in list order.  
+  async fn write_linked<I,>(&mut self, item: T, nodes: I,) -> Result<(), InsertError<Coll::Error,>>
+    where I: IntoIterator<Item = T>, {
+    let documents = nodes.into_iter().map(Into::<Coll::Document>::into,).collect::<Vec<_>>();
+    let report = self.collection.write_documents_reported(&documents,).await
+      .map_err(InsertError::Write,)?;
+
+    self.apply_linked(item, report,)
+  }
+  /// Updates the item of this `Cursor` if its node was written by an insert.
+  /// 
+  /// # Params
+  /// 
+  /// item --- The changed item of this `Cursor`.  
+  /// report --- The result of writing the changed nodes.  
+  fn apply_linked(&mut self, item: T, report: BatchReport<Coll::Error,>,) -> Result<(), InsertError<Coll::Error,>> {
+    if let Some(Ok(())) = report.get(item.get_id(),) {
+      self.item = item;
+      self.dirty = false;
+    }
+
+    if report.is_complete_success() { Ok(()) }
+    else { Err(InsertError::Partial(report,)) }
+  }
+}
+
+impl<T, Coll,> Cursor<T, Coll,>
+  where T: LinkedListMut + Clone + Into<Coll::Document>,
+    Coll: TransactionalCollection,
+    Coll::Document: Into<T>, {
+  /// Removes the node at this `Cursor` from the linked list.
+  /// 
+  /// The neighbours of the node are linked to each other and, if `delete` is `true`,
+  /// the document of the node is deleted; the changes are committed in a single
+  /// transaction so the list is unchanged if removing fails. Documents which refer to
+  /// the list from outside, such as the ends of a tier, are not updated.
+  /// 
+  /// Returns the removed item with its links as they were and a `Cursor` at the next
+  /// node, at the previous node if there is no next node, or `None` if it was the only
+  /// node.
+  /// 
+  /// # Params
+  /// 
+  /// delete --- Whether to delete the document of the removed node.  
+  pub async fn remove(self, delete: bool,) -> Result<(T, Option<Self>,), RemoveError<Coll::Error,>> {
+    let previous_id = self.item.get_previous_id().copied();
+    let next_id = self.item.get_next_id().copied();
+    let mut previous = match self.get_neighbour(previous_id.as_ref(),) {
+      Some(previous) => Some(previous.await.map_err(RemoveError::Fetch,)?.into(),),
+      None => None,
+    };
+    let mut next = match self.get_neighbour(next_id.as_ref(),) {
+      Some(next) => Some(next.await.map_err(RemoveError::Fetch,)?.into(),),
+      None => None,
+    };
+    let mut transaction = self.collection.begin();
+
+    if let Some(previous) = &mut previous {
+      previous.set_next_id(next_id,);
+      transaction.stage_write(&Into::<Coll::Document>::into(previous.clone(),),);
+    }
+    if let Some(next) = &mut next {
+      next.set_previous_id(previous_id,);
+      transaction.stage_write(&Into::<Coll::Document>::into(next.clone(),),);
+    }
+    if delete { transaction.stage_delete(self.item.get_id(),) }
+
+    transaction.commit().await.map_err(RemoveError::Commit,)?;
+
+    let Self { collection, item, position, .. } = self;
+    //The next node takes the position of the removed node.
+    let neighbour = match (next, previous,) {
+      (Some(next), _,) => Some(Cursor { collection, item: next, position, dirty: false, }),
+      (None, Some(previous),) => Some(Cursor { collection, item: previous, position: step_position(position, false,), dirty: false, }),
+      (None, None,) => None,
+    };
+
+    Ok((item, neighbour,))
+  }
+  /// Swaps the node at this `Cursor` with the next node in the linked list.
+  /// 
+  /// Up to four nodes are relinked in a single transaction so the list is unchanged if
+  /// swapping fails. This `Cursor` stays at its item, which is now one position later
+  /// in the list.
+  pub async fn swap_with_next(&mut self,) -> Result<Swap, SwapError<Coll::Error,>> {
+    let next = match self.get_neighbour(self.item.get_next_id(),) {
+      Some(next) => next.await.map_err(SwapError::Fetch,)?.into(),
+      None => return Ok(Swap::NoNeighbour),
+    };
+    let (item, _, swap,) = self.swap_pair(self.item.clone(), next,).await?;
+
+    //The item was written with any changes.
+    self.dirty = false;
+    self.step(item, true,);
+    Ok(swap)
+  }
+  /// Swaps the node at this `Cursor` with the previous node in the linked list.
+  /// 
+  /// Up to four nodes are relinked in a single transaction so the list is unchanged if
+  /// swapping fails. This `Cursor` stays at its item, which is now one position earlier
+  /// in the list.
+  pub async fn swap_with_previous(&mut self,) -> Result<Swap, SwapError<Coll::Error,>> {
+    let previous = match self.get_neighbour(self.item.get_previous_id(),) {
+      Some(previous) => previous.await.map_err(SwapError::Fetch,)?.into(),
+      None => return Ok(Swap::NoNeighbour),
+    };
+    let (_, item, swap,) = self.swap_pair(previous, self.item.clone(),).await?;
+
+    //The item was written with any changes.
+    self.dirty = false;
+    self.step(item, false,);
+    Ok(swap)
+  }
+  /// Swaps two adjacent nodes and returns them relinked.
+  /// 
+  /// # Params
+  /// 
+  /// first --- The earlier node.  
+  /// second --- The node after `first`.  
+  async fn swap_pair(&self, mut first: T, mut second: T,) -> Result<(T, T, Swap,), SwapError<Coll::Error,>> {
+    let before_id = first.get_previous_id().copied();
+    let after_id = second.get_next_id().copied();
+    let mut before = match self.get_neighbour(before_id.as_ref(),) {
+      Some(before) => Some(Into::<T>::into(before.await.map_err(SwapError::Fetch,)?,),),
+      None => None,
+    };
+    let mut after = match self.get_neighbour(after_id.as_ref(),) {
+      Some(after) => Some(Into::<T>::into(after.await.map_err(SwapError::Fetch,)?,),),
+      None => None,
+    };
+    let (first_id, second_id,) = (*first.get_id(), *second.get_id(),);
+    let mut transaction = self.collection.begin();
+
+    first.set_previous_id(Some(second_id,),);
+    first.set_next_id(after_id,);
+    second.set_previous_id(before_id,);
+    second.set_next_id(Some(first_id,),);
+    if let Some(before) = &mut before {
+      before.set_next_id(Some(second_id,),);
+      transaction.stage_write(&Into::<Coll::Document>::into(before.clone(),),);
+    }
+    transaction.stage_write(&Into::<Coll::Document>::into(second.clone(),),);
+    transaction.stage_write(&Into::<Coll::Document>::into(first.clone(),),);
+    if let Some(after) = &mut after {
+      after.set_previous_id(Some(first_id,),);
+      transaction.stage_write(&Into::<Coll::Document>::into(after.clone(),),);
+    }
+
+    transaction.commit().await.map_err(SwapError::Commit,)?;
+
+    let swap = match (before, after,) {
+      (Some(_), Some(_),) => Swap::Swapped,
+      (before, after,) => Swap::NeedsEndsUpdate {
+        front: before.map_or(Some(second_id,), |_,| None,),
+        back: after.map_or(Some(first_id,), |_,| None,),
+      },
+    };
+
+    Ok((first, second, swap,))
+  }
+}
+
+impl<T, Coll,> Cursor<T, Coll,>
+  where T: LinkedList,
+    Coll: TierListCollection + Clone, {
+  /// Gets a `Cursor` to the next node in the linked list.
+  /// 
+  /// If there was an error getting the next node the error records where this `Cursor`
+  /// is.
+  pub async fn get_next(&self,) -> Result<Option<Cursor<T, Coll,>>, CursorError<<Coll::GetDocument as TryFuture>::Error,>>
+    where Coll::GetDocument: TryFutureExt,
+      <Coll::GetDocument as TryFuture>::Ok: Into<T>, {
+    self.ref_next().await.map(|cursor,| cursor.map(Cursor::cloned_coll,),)
+  }
+  /// Gets a `Cursor` to the previous node in the linked list.
+  /// 
+  /// If there was an error getting the previous node the error records where this
+  /// `Cursor` is.
+  pub async fn get_previous(&self,) -> Result<Option<Cursor<T, Coll,>>, CursorError<<Coll::GetDocument as TryFuture>::Error,>>
+    where Coll::GetDocument: TryFutureExt,
+      <Coll::GetDocument as TryFuture>::Ok: Into<T>, {
+    self.ref_previous().await.map(|cursor,| cursor.map(Cursor::cloned_coll,),)
+  }
+}
+
+impl<T, Coll,> Cursor<T, Coll,>
+  where T: LinkedList + Send,
+    Coll: TierListCollectionSend,
+    Coll::Document: Into<T>, {
+  /// Gets a `Cursor` to the next node in the linked list.
+  /// 
+  /// The returned future does not borrow this `Cursor` and can be spawned onto a
+  /// multithreaded executor.
+  pub fn get_next_send(&self,) -> impl Future<Output = Result<Option<Cursor<T, Coll,>>, CursorError<Coll::Error,>>> + Send {
+    let next = self.item.get_next_id().map(|next_id,| cursor_send(self.collection.clone(), *next_id,),);
+    let (id, position,) = (*self.item.get_id(), self.position,);
+
+    async move {
+      match next {
+        Some(cursor) => cursor.await
+          .map(|cursor,| Some(Cursor { position: step_position(position, true,), ..cursor }),)
+          .map_err(|error,| CursorError { id, position, error, },),
+        None => Ok(None),
+      }
+    }
+  }
+  /// Gets a `Cursor` to the previous node in the linked list.
+  /// 
+  /// The returned future does not borrow this `Cursor` and can be spawned onto a
+  /// multithreaded executor.
+  pub fn get_previous_send(&self,) -> impl Future<Output = Result<Option<Cursor<T, Coll,>>, CursorError<Coll::Error,>>> + Send {
+    let previous = self.item.get_previous_id().map(|previous_id,| cursor_send(self.collection.clone(), *previous_id,),);
+    let (id, position,) = (*self.item.get_id(), self.position,);
+
+    async move {
+      match previous {
+        Some(cursor) => cursor.await
+          .map(|cursor,| Some(Cursor { position: step_position(position, false,), ..cursor }),)
+          .map_err(|error,| CursorError { id, position, error, },),
+        None => Ok(None),
+      }
+    }
+  }
+}
+
+impl<T, Coll,> Cursor<&T, Coll,>
   where T: Clone,
     Coll: TierListCollection, {
   /// Clones the item stored by this Cursor.
   #[inline]
   pub fn cloned(self,) -> Cursor<T, Coll,> {
-    Cursor::new(self.collection, self.item.clone(),)
+    Cursor { collection: self.collection, item: self.item.clone(), position: self.position, dirty: self.dirty, }
   }
 }
 
-impl<'t, T, Coll,> Cursor<&'t T, Coll,>
+impl<T, Coll,> Cursor<&T, Coll,>
   where T: Copy,
     Coll: TierListCollection, {
   /// Copies the item stored by this Cursor.
   #[inline]
   pub fn copied(self,) -> Cursor<T, Coll,> {
-    Cursor::new(self.collection, *self.item,)
+    Cursor { collection: self.collection, item: *self.item, position: self.position, dirty: self.dirty, }
   }
 }
 
-impl<'coll, T, Coll,> Cursor<T, &'coll Coll,>
+impl<T, Coll,> Cursor<T, &Coll,>
   where Coll: TierListCollection + Clone, {
   /// Clones the collection interface used by this Cursor.
   #[inline]
   pub fn cloned_coll(self,) -> Cursor<T, Coll,> {
-    Cursor::new(self.collection.clone(), self.item,)
+    Cursor { collection: self.collection.clone(), item: self.item, position: self.position, dirty: self.dirty, }
   }
 }
 
-impl<'coll, T, Coll,> Cursor<T, &'coll Coll,>
+impl<T, Coll,> Cursor<T, &Coll,>
   where Coll: TierListCollection + Copy, {
   /// Copies the item stored by this Cursor.
   #[inline]
   pub fn copied_coll(self,) -> Cursor<T, Coll,> {
-    Cursor::new(*self.collection, self.item,)
+    Cursor { collection: *self.collection, item: self.item, position: self.position, dirty: self.dirty, }
+  }
+}
+
+impl<T, Coll,> fmt::Debug for Cursor<T, Coll,>
+  where T: fmt::Debug,
+    Coll: TierListCollection, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    fmt.debug_struct("Cursor",)
+    .field("collection", &format_args!("{}", std::any::type_name::<Coll>(),),)
+    .field("item", &self.item,)
+    .field("position", &self.position,)
+    .field("dirty", &self.dirty,)
+    .finish()
+  }
+}
+
+/// A `TierListCollection` which caches the most recently used documents of another
+/// collection.
+/// 
+/// Documents are read from the cache when possible and writes through the
+/// `CachedCollection` update the cache; writes made to the inner collection by other
+/// means are not seen until the document is evicted or invalidated. A read or write
+/// which was in flight while another write or an invalidation started does not fill
+/// the cache, so a slow read cannot cache a document older than a write.
+pub struct CachedCollection<Coll,>
+  where Coll: TierListCollection, {
+  /// The collection being cached.
+  inner: Coll,
+  /// The cached documents.
+  cache: Rc<RefCell<Cache<Coll::Document>>>,
+}
+
+impl<Coll,> CachedCollection<Coll,>
+  where Coll: TierListCollection, {
+  /// Returns a new `CachedCollection` with an empty cache.
+  /// 
+  /// # Params
+  /// 
+  /// inner --- The collection to cache.  
+  /// capacity --- The maximum number of documents to cache.  
+  pub fn new(inner: Coll, capacity: usize,) -> Self {
+    let cache = Cache {
+      capacity,
+      tick: 0,
+      epoch: 0,
+      documents: HashMap::with_capacity(capacity,),
+      hits: 0,
+      misses: 0,
+    };
+
+    Self { inner, cache: Rc::new(RefCell::new(cache,),), }
+  }
+  /// Gets the collection being cached.
+  #[inline]
+  pub fn get_inner(&self,) -> &Coll { &self.inner }
+  /// Returns the fraction of document reads which were served from the cache.
+  /// 
+  /// Returns `0` if no documents have been read.
+  pub fn hit_rate(&self,) -> f64 {
+    let cache = RefCell::borrow(&self.cache,);
+    let reads = cache.hits + cache.misses;
+
+    if reads == 0 { 0.0 }
+    else { cache.hits as f64 / reads as f64 }
+  }
+  /// Removes a document from the cache.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the document.  
+  #[inline]
+  pub fn invalidate(&self, id: &DocumentId,) { self.cache.borrow_mut().remove(id,) }
+  /// Removes every document from the cache.
+  #[inline]
+  pub fn invalidate_all(&self,) {
+    let mut cache = self.cache.borrow_mut();
+
+    cache.epoch += 1;
+    cache.documents.clear()
+  }
+}
+
+impl<Coll,> Clone for CachedCollection<Coll,>
+  where Coll: TierListCollection + Clone, {
+  #[inline]
+  fn clone(&self,) -> Self {
+    Self { inner: self.inner.clone(), cache: self.cache.clone(), }
+  }
+}
+
+impl<Coll,> fmt::Debug for CachedCollection<Coll,>
+  where Coll: TierListCollection + fmt::Debug, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    fmt.debug_struct("CachedCollection",)
+    .field("inner", &self.inner,)
+    .field("cached", &RefCell::borrow(&self.cache,).documents.len(),)
+    .finish()
+  }
+}
+
+impl<Coll,> TierListCollection for CachedCollection<Coll,>
+  where Coll: 'static + TierListCollection,
+    Coll::Document: Clone, {
+  type Document = Coll::Document;
+  type Error = Coll::Error;
+  type GetBatchDocuments = Either<
+    Ready<Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>>,
+    MapOk<Coll::GetBatchDocuments, Box<dyn FnOnce(Vec<Result<Self::Document, Self::Error>>,) -> Vec<Result<Self::Document, Self::Error>>>>,
+  >;
+  type GetDocument = Either<
+    Ready<Result<Self::Document, Self::Error>>,
+    MapOk<Coll::GetDocument, Box<dyn FnOnce(Self::Document,) -> Self::Document>>,
+  >;
+  type WriteBatchDocuments = MapOk<Coll::WriteBatchDocuments, Box<dyn FnOnce(Result<(), Vec<Result<(), Self::Error>>>,) -> Result<(), Vec<Result<(), Self::Error>>>>>;
+  type WriteDocument = MapOk<Coll::WriteDocument, Box<dyn FnOnce((),)>>;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    let ids = ids.into_iter().map(|id,| *id.borrow(),).collect::<Vec<_>>();
+    let cached = {
+      let mut cache = self.cache.borrow_mut();
+
+      ids.iter().map(|id,| cache.get(id,),).collect::<Vec<_>>()
+    };
+    let misses = ids.iter().zip(cached.iter(),)
+      .filter(|(_, doc,),| doc.is_none(),)
+      .map(|(&id, _,),| id,)
+      .collect::<Vec<_>>();
+
+    //Every document was cached.
+    if misses.is_empty() {
+      return Either::Left(future::ok(cached.into_iter().flatten().map(Ok,).collect(),),)
+    }
+
+    let cache = self.cache.clone();
+    let epoch = RefCell::borrow(&cache,).epoch;
+
+    Either::Right(self.inner.get_documents(&misses,)
+      .map_ok(Box::new(move |fetched,| {
+        let mut cache = cache.borrow_mut();
+        let mut fetched = fetched.into_iter();
+
+        //Fill the gaps in the cached documents with the fetched documents.
+        cached.into_iter()
+        .map(|doc,| match doc {
+          Some(doc) => Ok(doc),
+          None => {
+            let doc = fetched.next()
+              .expect("`get_documents` returned fewer results than requested");
+
+            if let Ok(doc) = &doc { cache.fill(epoch, doc.clone(),) }
+
+            doc
+          },
+        },)
+        .collect()
+      },),),
+    )
+  }
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    if let Some(doc) = self.cache.borrow_mut().get(id,) { return Either::Left(future::ok(doc,),) }
+
+    let cache = self.cache.clone();
+    let epoch = RefCell::borrow(&cache,).epoch;
+
+    Either::Right(self.inner.get_document(id,)
+      .map_ok(Box::new(move |doc,| { cache.borrow_mut().fill(epoch, doc.clone(),); doc },),),
+    )
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let documents = documents.into_iter()
+      .map(|doc,| doc.borrow().clone(),)
+      .collect::<Vec<_>>();
+    let cache = self.cache.clone();
+
+    //Stop serving stale documents while they are written.
+    for doc in documents.iter() { cache.borrow_mut().remove(doc.get_id(),) }
+
+    let epoch = RefCell::borrow(&cache,).epoch;
+
+    self.inner.write_documents(&documents,)
+    .map_ok(Box::new(move |res,| {
+      let mut cache = cache.borrow_mut();
+
+      match &res {
+        Ok(()) => for doc in documents { cache.fill(epoch, doc,) },
+        Err(results) => for (doc, _,) in documents.into_iter().zip(results,).filter(|(_, res,),| res.is_ok(),) {
+          cache.fill(epoch, doc,)
+        },
+      }
+
+      res
+    },),)
+  }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    let document = document.borrow().clone();
+    let cache = self.cache.clone();
+
+    //Stop serving the stale document while it is written.
+    cache.borrow_mut().remove(document.get_id(),);
+
+    let epoch = RefCell::borrow(&cache,).epoch;
+
+    self.inner.write_document(&document,)
+    .map_ok(Box::new(move |(),| cache.borrow_mut().fill(epoch, document,),),)
+  }
+  fn exists(&self, id: &DocumentId,) -> impl Future<Output = Result<bool, Self::Error>> {
+    if RefCell::borrow(&self.cache,).documents.contains_key(id,) { Either::Left(future::ok(true,),) }
+    else { Either::Right(self.inner.exists(id,),) }
+  }
+}
+
+/// A least recently used cache of documents.
+struct Cache<D,> {
+  /// The maximum number of documents to cache.
+  capacity: usize,
+  /// The counter used to order document usage.
+  tick: u64,
+  /// Changed whenever a document may have changed; reads and writes which were in
+  /// flight when it changed do not fill the cache.
+  epoch: u64,
+  /// The cached documents and when they were last used.
+  documents: HashMap<DocumentId, (u64, D,)>,
+  /// The number of reads served from the cache.
+  hits: u64,
+  /// The number of reads not served from the cache.
+  misses: u64,
+}
+
+impl<D,> Cache<D,>
+  where D: Document + Clone, {
+  /// Gets a document from the cache.
+  fn get(&mut self, id: &DocumentId,) -> Option<D> {
+    self.tick += 1;
+
+    match self.documents.get_mut(id,) {
+      Some((used, doc,)) => {
+        *used = self.tick;
+        self.hits += 1;
+        Some(doc.clone())
+      },
+      None => { self.misses += 1; None },
+    }
+  }
+  /// Adds a document to the cache, evicting the least recently used document if the
+  /// cache is full.
+  fn insert(&mut self, doc: D,) {
+    if self.capacity == 0 { return }
+
+    self.tick += 1;
+
+    let id = *doc.get_id();
+
+    if !self.documents.contains_key(&id,) && self.documents.len() >= self.capacity {
+      let evicted = self.documents.iter()
+        .min_by_key(|(_, (used, _,),),| *used,)
+        .map(|(&id, _,),| id,);
+
+      if let Some(evicted) = evicted { self.documents.remove(&evicted,); }
+    }
+
+    self.documents.insert(id, (self.tick, doc,),);
+  }
+  /// Adds a document read or written since `epoch` to the cache unless a document may
+  /// have changed since.
+  fn fill(&mut self, epoch: u64, doc: D,) {
+    if self.epoch == epoch { self.insert(doc,) }
+  }
+}
+
+impl<D,> Cache<D,> {
+  /// Removes a document from the cache.
+  #[inline]
+  fn remove(&mut self, id: &DocumentId,) {
+    self.epoch += 1;
+    self.documents.remove(id,);
   }
 }
 
 #[cfg(test,)]
 mod tests {
   use super::*;
-  use futures::{future, Future,};
-  use std::{
-    collections::HashMap,
-    cell::RefCell,
-    pin::Pin,
-    rc::Rc,
-  };
+  use crate::{CardId, TierId, Card, CardStatus, TierDocument, DocumentKind, ErrorKind, MemoryCollection, MemoryError, RecordingCollection, Op, mock::{ChaosCollection, ChaosError, SlowReadCollection,},};
+  use futures::{executor::block_on, future::Ready,};
+  use std::{num::NonZeroU64, convert::TryFrom,};
 
   #[derive(PartialEq, Eq, Clone, Copy, Debug,)]
   struct Doc {
@@ -345,92 +2442,208 @@ mod tests {
     }
   }
 
-  impl TierListCollection for Rc<RefCell<HashMap<DocumentId, Doc>>> {
+  impl LinkedListMut for Doc {
+    #[inline]
+    fn set_previous_id(&mut self, id: Option<DocumentId>,) { self.prev = id }
+    #[inline]
+    fn set_next_id(&mut self, id: Option<DocumentId>,) { self.next = id }
+  }
+
+  /// The error returned by an `Unreachable` collection.
+  #[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+  struct Disconnected;
+
+  impl CollectionError for Disconnected {
+    #[inline]
+    fn kind(&self,) -> ErrorKind { ErrorKind::Transient }
+  }
+
+  /// A collection which fails every operation.
+  #[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+  struct Unreachable;
+
+  impl TierListCollection for Unreachable {
     type Document = Doc;
-    type Error = !;
-    type GetDocument = Pin<Box<dyn Future<Output = Result<Self::Document, Self::Error>>>>;
-    type GetBatchDocuments = Pin<Box<dyn Future<Output = Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>>>>;
-    type WriteDocument = Pin<Box<dyn Future<Output = Result<(), Self::Error>>>>;
-    type WriteBatchDocuments = Pin<Box<dyn Future<Output = Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>>>;
-
-    fn get_documents(&self, ids: &[&DocumentId],) -> Self::GetBatchDocuments {
-      let coll = self.clone();
-      let ids = ids.into_iter()
-        .map(|&&id,| id,)
-        .collect::<Vec<_>>();
+    type Error = Disconnected;
+    type GetBatchDocuments = Ready<Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>>;
+    type GetDocument = Ready<Result<Self::Document, Self::Error>>;
+    type WriteBatchDocuments = Ready<Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>;
+    type WriteDocument = Ready<Result<(), Self::Error>>;
 
-      Box::pin(async move {
-        Ok(future::join_all(
-          ids.into_iter().map(|id,| coll.get_document(&id,),),
-        ).await)
-      },)
-    }
-    fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
-      use std::task::Poll;
+    fn get_documents<I, B,>(&self, _: I,) -> Self::GetBatchDocuments
+      where I: IntoIterator<Item = B>,
+        B: Borrow<DocumentId>, { future::err(Disconnected,) }
+    fn get_document(&self, _: &DocumentId,) -> Self::GetDocument { future::err(Disconnected,) }
+    fn write_documents<I, T,>(&self, _: I,) -> Self::WriteBatchDocuments
+      where I: IntoIterator<Item = T>,
+        T: Borrow<Self::Document>, { future::err(Disconnected,) }
+    fn write_document<T,>(&self, _: &T,) -> Self::WriteDocument
+      where T: Borrow<Self::Document>, { future::err(Disconnected,) }
+  }
 
-      let coll = self.clone();
-      let id = *id;
+  #[test]
+  fn test_exists_error() {
+    assert_eq!(
+      block_on(Unreachable.exists(&DocumentId::from_bytes([1; 20]),),),
+      Err(Disconnected),
+      "Error `exists` did not propagate the error",
+    );
+  }
+  #[test]
+  fn test_exists_default() {
+    //`ChaosCollection` uses the default implementation of `exists`.
+    let coll = ChaosCollection::new();
+    let doc = Doc { id: DocumentId::from_bytes([1; 20]), prev: None, next: None, };
 
-      Box::pin(future::poll_fn(move |_,| {
-        match coll.try_borrow() {
-          Ok(coll) => Poll::Ready(Ok(coll[&id])),
-          Err(_) => Poll::Pending,
-        }
-      },),)
-    }
-    fn write_documents<T,>(&self, documents: &[&T],) -> Self::WriteBatchDocuments
-      where T: Borrow<Self::Document>, {
-      let coll = self.clone();
-      let docs = documents.into_iter()
-        .map(|&doc,| *doc.borrow(),)
-        .collect::<Vec<_>>();
+    block_on(async {
+      assert_eq!(coll.exists(&doc.id,).await, Ok(false), "Error document exists before writing",);
+
+      coll.write_document(&doc,).await
+        .expect("Error writing document");
 
-      Box::pin(async move {
-        let mut all_succeeded = true;
-        let mut results = Vec::with_capacity(docs.len(),);
-        let docs = docs.into_iter()
-          .map(|doc,| coll.write_document(&doc,),);
+      assert_eq!(coll.exists(&doc.id,).await, Ok(true), "Error document missing after writing",);
+    },);
+  }
+  #[test]
+  fn test_write_documents_reported() {
+    //`ChaosCollection` uses the default implementation of `write_documents_reported`.
+    let coll = ChaosCollection::<Doc,>::new();
+    let docs = (1..=5u8).map(|i,| Doc { id: DocumentId::from_bytes([i; 20]), prev: None, next: None, },).collect::<Vec<_>>();
 
-        for res in docs {
-          let res = res.await;
+    coll.fail_writes(DocumentId::from_bytes([2; 20]),);
+    coll.fail_writes(DocumentId::from_bytes([4; 20]),);
 
-          all_succeeded = all_succeeded && res.is_ok();
-          results.push(res,);
-        }
+    let report = block_on(coll.write_documents_reported(&docs,),)
+      .expect("Error writing documents");
 
-        if all_succeeded { Ok(Ok(())) }
-        else { Ok(Err(results)) }
-      },)
-    }
-    fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
-      where T: Borrow<Self::Document>, {
-      use std::task::Poll;
+    assert_eq!(report.len(), 5, "Error reporting the wrong number of writes",);
+    assert!(!report.is_complete_success(), "Error failed writes were reported as succeeding",);
+    assert_eq!(
+      report.failures().map(|(id, _,),| *id,).collect::<Vec<_>>(),
+      vec![DocumentId::from_bytes([2; 20]), DocumentId::from_bytes([4; 20]),],
+      "Error reporting failed writes",
+    );
+    assert_eq!(
+      report.succeeded_ids().copied().collect::<Vec<_>>(),
+      vec![DocumentId::from_bytes([1; 20]), DocumentId::from_bytes([3; 20]), DocumentId::from_bytes([5; 20]),],
+      "Error reporting written documents",
+    );
+    assert_eq!(report.retryable_failures().count(), 2, "Error classifying failed writes",);
+  }
+  #[test]
+  fn test_cached_collection() {
+    let inner = ChaosCollection::new();
+    let coll = CachedCollection::new(inner.clone(), 2,);
+    let docs = (1..=3u8).map(|i,| Doc { id: DocumentId::from_bytes([i; 20]), prev: None, next: None, },).collect::<Vec<_>>();
 
-      let coll = self.clone();
-      let document = *document.borrow();
+    block_on(async {
+      for doc in docs.iter() {
+        inner.write_document(doc,).await
+          .expect("Error writing document");
+      }
 
-      Box::pin(future::poll_fn(move |_,| {
-        match coll.try_borrow_mut() {
-          Ok(mut coll) => {
-            coll.insert(*document.get_id(), document,);
+      assert_eq!(coll.get_document(&docs[0].id,).await, Ok(docs[0]), "Error reading document",);
+      assert_eq!(coll.get_document(&docs[0].id,).await, Ok(docs[0]), "Error reading cached document",);
+      assert_eq!(inner.reads(), 1, "Error cached document was read from the inner collection",);
+      assert_eq!(coll.hit_rate(), 0.5, "Error reporting the hit rate",);
 
-            Poll::Ready(Ok(()))
-          },
-          Err(_) => Poll::Pending,
-        }
-      },),)
-    }
+      //Only the uncached document is fetched.
+      assert_eq!(
+        coll.get_documents([&docs[1].id, &docs[0].id,],).await,
+        Ok(vec![Ok(docs[1]), Ok(docs[0]),]),
+        "Error reading documents",
+      );
+      assert_eq!(inner.reads(), 2, "Error cached documents were read from the inner collection",);
+
+      //The least recently used document is evicted.
+      coll.get_document(&docs[2].id,).await
+        .expect("Error reading document");
+      coll.get_documents([&docs[1].id, &docs[2].id,],).await
+        .expect("Error reading documents");
+      assert_eq!(inner.reads(), 3, "Error cached documents were read from the inner collection",);
+      coll.get_document(&docs[0].id,).await
+        .expect("Error reading document");
+      assert_eq!(inner.reads(), 4, "Error evicted document was not read from the inner collection",);
+
+      coll.invalidate(&docs[1].id,);
+      coll.get_document(&docs[1].id,).await
+        .expect("Error reading document");
+      assert_eq!(inner.reads(), 5, "Error invalidated document was not read from the inner collection",);
+    },);
   }
+  #[test]
+  fn test_cached_collection_write() {
+    let inner = ChaosCollection::new();
+    let coll = CachedCollection::new(inner.clone(), 4,);
+    let doc = Doc { id: DocumentId::from_bytes([1; 20]), prev: None, next: None, };
+    let updated = Doc { next: Some(DocumentId::from_bytes([2; 20])), ..doc };
+    let other = Doc { id: DocumentId::from_bytes([2; 20]), prev: Some(DocumentId::from_bytes([1; 20])), next: None, };
+
+    block_on(async {
+      coll.write_document(&doc,).await
+        .expect("Error writing document");
+      assert_eq!(coll.get_document(&doc.id,).await, Ok(doc), "Error reading document",);
+
+      coll.write_documents([&updated, &other,],).await
+        .expect("Error writing documents")
+        .expect("Error writing documents");
+      assert_eq!(coll.get_document(&doc.id,).await, Ok(updated), "Error reading updated document",);
+      assert_eq!(coll.get_document(&other.id,).await, Ok(other), "Error reading written document",);
+      assert_eq!(inner.reads(), 0, "Error written documents were read from the inner collection",);
+      assert_eq!(inner.get_document(&doc.id,).await, Ok(updated), "Error writing through the cache",);
 
+      //A failed write leaves the document uncached.
+      inner.fail_writes(doc.id,);
+      assert!(coll.write_document(&doc,).await.is_err(), "Error injected failure succeeded",);
+      assert_eq!(coll.get_document(&doc.id,).await, Ok(updated), "Error reading document",);
+      assert_eq!(inner.reads(), 2, "Error failed write was cached",);
+    },);
+  }
   #[test]
-  fn test_cursor() {
+  fn test_cached_collection_slow_read() {
+    let inner = ChaosCollection::new();
+    let coll = CachedCollection::new(SlowReadCollection::new(inner.clone(),), 4,);
+    let doc = Doc { id: DocumentId::from_bytes([1; 20]), prev: None, next: None, };
+    let updated = Doc { next: Some(DocumentId::from_bytes([2; 20])), ..doc };
+
+    block_on(async {
+      inner.write_document(&doc,).await
+        .expect("Error writing document");
+
+      //The read gets the old document before the write but finishes after it.
+      let (read, written,) = future::join(coll.get_document(&doc.id,), coll.write_document(&updated,),).await;
+      assert_eq!((read, written,), (Ok(doc), Ok(()),), "Error interleaving a read and a write",);
+      assert_eq!(coll.get_document(&doc.id,).await, Ok(updated), "Error caching a document read before a write",);
+      assert_eq!(inner.reads(), 1, "Error the written document was not cached",);
+
+      //The same holds for batches.
+      coll.invalidate(&doc.id,);
+      let (read, written,) = future::join(coll.get_documents([&doc.id,],), coll.write_document(&doc,),).await;
+      assert_eq!((read, written,), (Ok(vec![Ok(updated),]), Ok(()),), "Error interleaving a batch read and a write",);
+      assert_eq!(coll.get_documents([&doc.id,],).await, Ok(vec![Ok(doc),]), "Error caching a batch read before a write",);
+
+      //An invalidation during a read also stops the read filling the cache.
+      coll.invalidate(&doc.id,);
+      let read = coll.get_document(&doc.id,);
+      coll.invalidate(&doc.id,);
+      inner.write_document(&updated,).await
+        .expect("Error writing document");
+      assert_eq!(read.await, Ok(updated), "Error reading document",);
+      coll.get_document(&doc.id,).await
+        .expect("Error reading document");
+      assert_eq!(inner.reads(), 4, "Error a read which overlapped an invalidation filled the cache",);
+    },);
+  }
+  /// Walks a list using owned cursors over a collection.
+  fn check_cursor<Coll,>(coll: Coll,)
+    where Coll: 'static + TierListCollection<Document = Doc> + Clone + PartialEq + fmt::Debug,
+      Coll::Error: fmt::Debug, {
     use futures::{executor::LocalPool, task::LocalSpawnExt,};
     
-    let coll = Rc::new(RefCell::new(HashMap::new(),),);
-    let id1 = [1u8; 20];
-    let id2 = [2u8; 20];
-    let id3 = [3u8; 20];
-    let id4 = [4u8; 20];
+    let id1 = DocumentId::from_bytes([1u8; 20]);
+    let id2 = DocumentId::from_bytes([2u8; 20]);
+    let id3 = DocumentId::from_bytes([3u8; 20]);
+    let id4 = DocumentId::from_bytes([4u8; 20]);
     let doc1 = Doc {
       id: id1,
       prev: None,
@@ -456,17 +2669,18 @@ mod tests {
       coll.write_document(&doc1,).await
         .expect("Error writing document");
       
-      let docs = coll.write_documents(&[&doc2, &doc3, &doc4,],).await
+      let docs = coll.write_documents([&doc2, &doc3, &doc4,],).await
         .expect("Error writing documents");
     
       if let Err(res) = docs {
         for (i, res) in res.into_iter().enumerate().filter(|(_, res,),| res.is_err(),) {
-          res.expect(&format!("Error writing id{}", i,),)
+          res.unwrap_or_else(|_,| panic!("Error writing id{}", i,),)
         }
       }
 
-      let cursor = coll.ref_cursor::<Doc>(&id3,).await.unwrap();
+      let cursor = coll.clone().get_cursor::<Doc>(&id3,).await.unwrap();
       assert_eq!(cursor.get_item(), &doc3, "Error Cursor at wrong document",);
+      assert_eq!(cursor.get_collection(), &coll, "Error Cursor owns a different collection",);
 
       let next_cursor = cursor.get_next().await.unwrap()
         .expect("No next Cursor");
@@ -495,4 +2709,1026 @@ mod tests {
       .expect("Error spawning task");
     pool.run();
   }
+  #[test]
+  fn test_move_mut() {
+    let coll = ChaosCollection::<Doc,>::new();
+    let ids = [DocumentId::from_bytes([1u8; 20]), DocumentId::from_bytes([2u8; 20]), DocumentId::from_bytes([3u8; 20]), DocumentId::from_bytes([4u8; 20]),];
+    let docs = (0..ids.len()).map(|i,| Doc {
+      id: ids[i],
+      prev: i.checked_sub(1,).map(|i,| ids[i],),
+      next: ids.get(i + 1,).copied(),
+    },).collect::<Vec<_>>();
+
+    block_on(async {
+      coll.write_documents(&docs,).await
+        .expect("Error writing documents")
+        .expect("Error writing documents");
+
+      let mut cursor = coll.ref_cursor::<Doc>(&ids[0],).await
+        .expect("Error reading front");
+      let walk = async {
+        let mut visited = vec![cursor.get_item().id];
+        while cursor.move_next_mut().await? { visited.push(cursor.get_item().id,) }
+
+        Ok::<_, ChaosError>(visited)
+      };
+      assert_eq!(walk.await, Ok(ids.to_vec()), "Error walking the list",);
+      assert_eq!(cursor.get_item(), &docs[3], "Error Cursor did not stop at the back",);
+      assert_eq!(cursor.move_next_mut().await, Ok(false), "Error moved past the back",);
+
+      //A failed move leaves the cursor in place.
+      coll.fail_reads(ids[2],);
+      assert_eq!(cursor.move_previous_mut().await, Err(ChaosError::Injected(ids[2],)), "Error moving to unreadable node",);
+      assert_eq!(cursor.get_item(), &docs[3], "Error failed move changed the Cursor",);
+    },);
+  }
+  #[test]
+  fn test_insert() {
+    let coll = ChaosCollection::<Doc,>::new();
+    let doc = |i: u8,| Doc { id: DocumentId::from_bytes([i; 20]), prev: None, next: None, };
+
+    block_on(async {
+      coll.write_document(&doc(2,),).await
+        .expect("Error writing document");
+
+      let mut cursor = coll.ref_cursor::<Doc>(&DocumentId::from_bytes([2; 20]),).await
+        .expect("Error reading document");
+      //Insert at the tail, between two nodes and at the head.
+      cursor.insert_after(doc(4,),).await
+        .expect("Error inserting at the tail");
+      cursor.insert_after(doc(3,),).await
+        .expect("Error inserting between nodes");
+      cursor.insert_before(doc(1,),).await
+        .expect("Error inserting at the head");
+      assert_eq!(cursor.get_item(), &Doc { id: DocumentId::from_bytes([2; 20]), prev: Some(DocumentId::from_bytes([1; 20])), next: Some(DocumentId::from_bytes([3; 20])), }, "Error updating Cursor",);
+
+      assert!(cursor.move_next_mut().await.expect("Error moving next"), "Error no next node",);
+      assert!(cursor.move_next_mut().await.expect("Error moving next"), "Error no next node",);
+      cursor.insert_after(doc(5,),).await
+        .expect("Error inserting at the new tail");
+
+      let mut cursor = coll.ref_cursor::<Doc>(&DocumentId::from_bytes([1; 20]),).await
+        .expect("Error reading head");
+      let mut forward = vec![cursor.get_item().id.as_bytes()[0]];
+      while cursor.move_next_mut().await.expect("Error moving next") { forward.push(cursor.get_item().id.as_bytes()[0],) }
+      assert_eq!(forward, [1, 2, 3, 4, 5,], "Error walking forward",);
+
+      let mut backward = vec![cursor.get_item().id.as_bytes()[0]];
+      while cursor.move_previous_mut().await.expect("Error moving previous") { backward.push(cursor.get_item().id.as_bytes()[0],) }
+      assert_eq!(backward, [5, 4, 3, 2, 1,], "Error walking backward",);
+
+      //A failed write of the old neighbour is reported with the nodes which landed.
+      coll.fail_writes(DocumentId::from_bytes([2; 20]),);
+      let before = *cursor.get_item();
+      match cursor.insert_after(doc(9,),).await {
+        Err(InsertError::Partial(report)) => assert_eq!(
+          report.succeeded_ids().collect::<Vec<_>>(),
+          [&DocumentId::from_bytes([1; 20]), &DocumentId::from_bytes([9; 20]),],
+          "Error reporting written nodes",
+        ),
+        res => panic!("Error inserting with a broken neighbour: {:?}", res,),
+      }
+      assert_eq!(cursor.get_item().next, Some(DocumentId::from_bytes([9; 20])), "Error Cursor did not see its written node",);
+      assert_ne!(cursor.get_item(), &before, "Error Cursor unchanged",);
+
+      coll.fail_writes(DocumentId::from_bytes([6; 20]),);
+      let before = *cursor.get_item();
+      assert!(matches!(cursor.insert_before(doc(6,),).await, Err(InsertError::Partial(_,)),), "Error inserting broken node",);
+      assert_eq!(cursor.get_item().prev, Some(DocumentId::from_bytes([6; 20])), "Error Cursor did not see its written node",);
+      assert_ne!(cursor.get_item(), &before, "Error Cursor unchanged",);
+    },);
+  }
+  /// Writes a list of the ids and returns a cursor at a node.
+  async fn write_list<'a,>(coll: &'a MemoryCollection<Doc,>, ids: &[u8], at: u8,) -> Cursor<Doc, &'a MemoryCollection<Doc,>,> {
+    let docs = (0..ids.len()).map(|i,| Doc {
+      id: DocumentId::from_bytes([ids[i]; 20]),
+      prev: i.checked_sub(1,).map(|i,| DocumentId::from_bytes([ids[i]; 20]),),
+      next: ids.get(i + 1,).map(|&id,| DocumentId::from_bytes([id; 20]),),
+    },).collect::<Vec<_>>();
+
+    coll.write_documents(&docs,).await
+      .expect("Error writing list")
+      .expect("Error writing list");
+    coll.ref_cursor(&DocumentId::from_bytes([at; 20]),).await
+      .expect("Error reading node")
+  }
+  /// Walks a list forward from a node and checks the backward links on the way.
+  async fn walk_list(coll: &MemoryCollection<Doc,>, head: u8,) -> Vec<u8> {
+    let mut cursor = coll.ref_cursor::<Doc>(&DocumentId::from_bytes([head; 20]),).await
+      .expect("Error reading head");
+    let mut ids = vec![head];
+
+    assert_eq!(cursor.get_item().prev, None, "Error head has a previous node",);
+    while cursor.move_next_mut().await.expect("Error moving next") {
+      assert_eq!(cursor.get_item().prev, Some(DocumentId::from_bytes([*ids.last().unwrap(); 20])), "Error backward link",);
+      ids.push(cursor.get_item().id.as_bytes()[0],);
+    }
+
+    ids
+  }
+
+  #[test]
+  fn test_remove() {
+    block_on(async {
+      //Remove the middle node keeping its document.
+      let coll = MemoryCollection::new();
+      let cursor = write_list(&coll, &[1, 2, 3,], 2,).await;
+      let (removed, cursor,) = cursor.remove(false,).await
+        .expect("Error removing middle node");
+      assert_eq!(removed, Doc { id: DocumentId::from_bytes([2; 20]), prev: Some(DocumentId::from_bytes([1; 20])), next: Some(DocumentId::from_bytes([3; 20])), }, "Error returning removed node",);
+      assert_eq!(cursor.map(|cursor,| cursor.get_item().id,), Some(DocumentId::from_bytes([3; 20])), "Error Cursor not at next node",);
+      assert_eq!(walk_list(&coll, 1,).await, [1, 3,], "Error unlinking middle node",);
+      assert_eq!(coll.exists(&DocumentId::from_bytes([2; 20]),).await, Ok(true), "Error deleted kept node",);
+
+      //Remove the head.
+      let coll = MemoryCollection::new();
+      let cursor = write_list(&coll, &[1, 2, 3,], 1,).await;
+      let (_, cursor,) = cursor.remove(true,).await
+        .expect("Error removing head");
+      assert_eq!(cursor.map(|cursor,| *cursor.get_item(),), Some(Doc { id: DocumentId::from_bytes([2; 20]), prev: None, next: Some(DocumentId::from_bytes([3; 20])), }), "Error Cursor not at new head",);
+      assert_eq!(walk_list(&coll, 2,).await, [2, 3,], "Error unlinking head",);
+      assert_eq!(coll.exists(&DocumentId::from_bytes([1; 20]),).await, Ok(false), "Error head not deleted",);
+
+      //Remove the tail.
+      let coll = MemoryCollection::new();
+      let cursor = write_list(&coll, &[1, 2, 3,], 3,).await;
+      let (_, cursor,) = cursor.remove(true,).await
+        .expect("Error removing tail");
+      assert_eq!(cursor.map(|cursor,| *cursor.get_item(),), Some(Doc { id: DocumentId::from_bytes([2; 20]), prev: Some(DocumentId::from_bytes([1; 20])), next: None, }), "Error Cursor not at new tail",);
+      assert_eq!(walk_list(&coll, 1,).await, [1, 2,], "Error unlinking tail",);
+      assert_eq!(coll.exists(&DocumentId::from_bytes([3; 20]),).await, Ok(false), "Error tail not deleted",);
+
+      //Remove the only node.
+      let coll = MemoryCollection::new();
+      let cursor = write_list(&coll, &[1,], 1,).await;
+      let (removed, cursor,) = cursor.remove(true,).await
+        .expect("Error removing only node");
+      assert_eq!(removed.id, DocumentId::from_bytes([1; 20]), "Error returning only node",);
+      assert!(cursor.is_none(), "Error Cursor left at removed list",);
+      assert_eq!(coll.len(), 0, "Error only node not deleted",);
+
+      //A missing neighbour fails without changing the list.
+      let coll = MemoryCollection::new();
+      let cursor = write_list(&coll, &[1, 2,], 1,).await;
+      let broken = Cursor::new(&coll, Doc { next: Some(DocumentId::from_bytes([9; 20])), ..*cursor.get_item() },);
+      assert_eq!(broken.remove(true,).await.map(|_,| (),), Err(RemoveError::Fetch(MemoryError::NotFound(DocumentId::from_bytes([9; 20]),),)), "Error removing from broken list",);
+      assert_eq!(walk_list(&coll, 1,).await, [1, 2,], "Error failed remove changed the list",);
+    },);
+  }
+  #[test]
+  fn test_seek() {
+    let coll = RecordingCollection::new(MemoryCollection::new(),);
+
+    block_on(async {
+      let ids = (1..=60).collect::<Vec<u8>>();
+      let cursor = write_list(coll.get_inner(), &ids, 1,).await;
+      let cursor = Cursor::new(&coll, cursor.into_parts().1,);
+      let (cursor, moved,) = cursor.seek_forward(0,).await
+        .expect("Error seeking nowhere");
+      assert_eq!((cursor.get_item().id.as_bytes()[0], moved,), (1, 0,), "Error seeking nowhere",);
+      crate::assert_ops!(coll, [], "Error read while seeking nowhere",);
+
+      let (cursor, moved,) = cursor.seek_forward(50,).await
+        .expect("Error seeking forward");
+      assert_eq!((cursor.get_item().id.as_bytes()[0], moved,), (51, 50,), "Error seeking forward",);
+      assert_eq!(coll.take_ops(), (2..=51).map(|i,| Op::Get(DocumentId::from_bytes([i; 20]),),).collect::<Vec<_>>(), "Error reading nodes once",);
+
+      let (cursor, moved,) = cursor.seek_forward(20,).await
+        .expect("Error seeking past the tail");
+      assert_eq!((cursor.get_item().id.as_bytes()[0], moved,), (60, 9,), "Error stopping at the tail",);
+      coll.take_ops();
+
+      let (cursor, moved,) = cursor.seek_backward(100,).await
+        .expect("Error seeking past the head");
+      assert_eq!((cursor.get_item().id.as_bytes()[0], moved,), (1, 59,), "Error stopping at the head",);
+      coll.take_ops();
+
+      let (cursor, moved,) = cursor.seek_backward(1,).await
+        .expect("Error seeking before the head");
+      assert_eq!((cursor.get_item().id.as_bytes()[0], moved,), (1, 0,), "Error seeking before the head",);
+      crate::assert_ops!(coll, [], "Error read before the head",);
+    },);
+
+    //Failing part way stops at the last node reached.
+    let coll = ChaosCollection::<Doc,>::new();
+    let docs = (1..=5u8).map(|i,| Doc { id: DocumentId::from_bytes([i; 20]), prev: None, next: Some(DocumentId::from_bytes([i + 1; 20])), },).collect::<Vec<_>>();
+
+    block_on(async {
+      coll.write_documents(&docs,).await
+        .expect("Error writing list")
+        .expect("Error writing list");
+      coll.fail_reads(DocumentId::from_bytes([4; 20]),);
+      let cursor = coll.ref_cursor::<Doc>(&DocumentId::from_bytes([1; 20]),).await
+        .expect("Error reading head");
+      let (cursor, e,) = cursor.seek_forward(4,).await
+        .expect_err("Error seeking through a broken node");
+      assert_eq!(e, ChaosError::Injected(DocumentId::from_bytes([4; 20]),), "Error returning the read error",);
+      assert_eq!(cursor.get_item().id, DocumentId::from_bytes([3; 20]), "Error Cursor not at last node reached",);
+    },);
+  }
+  #[test]
+  fn test_stream() {
+    use futures::StreamExt;
+
+    let coll = RecordingCollection::new(MemoryCollection::new(),);
+
+    block_on(async {
+      let head = *write_list(coll.get_inner(), &[2, 3, 4,], 2,).await.get_item();
+      let mut stream = Box::pin(Cursor::new(&coll, head,).into_stream(),);
+      crate::assert_ops!(coll, [], "Error read before polling",);
+      assert_eq!(stream.next().await.map(|doc,| doc.map(|doc,| doc.id.as_bytes()[0],),), Some(Ok(2)), "Error yielding current item",);
+      crate::assert_ops!(coll, [], "Error read the current item",);
+
+      let rest = stream.map(|doc,| doc.map(|doc,| doc.id.as_bytes()[0],),).collect::<Vec<_>>().await;
+      assert_eq!(rest, [Ok(3), Ok(4),], "Error streaming forward",);
+      crate::assert_ops!(coll, [Op::Get(DocumentId::from_bytes([3; 20]),), Op::Get(DocumentId::from_bytes([4; 20]),),], "Error reading each node once",);
+
+      let tail = coll.ref_cursor::<Doc>(&DocumentId::from_bytes([4; 20]),).await
+        .expect("Error reading tail");
+      let back = tail.into_stream_back().map(|doc,| doc.map(|doc,| doc.id.as_bytes()[0],),).collect::<Vec<_>>().await;
+      assert_eq!(back, [Ok(4), Ok(3), Ok(2),], "Error streaming backward",);
+    },);
+
+    //A failed read ends the stream.
+    let coll = ChaosCollection::<Doc,>::new();
+    let docs = [
+      Doc { id: DocumentId::from_bytes([2; 20]), prev: None, next: Some(DocumentId::from_bytes([3; 20])), },
+      Doc { id: DocumentId::from_bytes([3; 20]), prev: Some(DocumentId::from_bytes([2; 20])), next: Some(DocumentId::from_bytes([4; 20])), },
+      Doc { id: DocumentId::from_bytes([4; 20]), prev: Some(DocumentId::from_bytes([3; 20])), next: None, },
+    ];
+
+    block_on(async {
+      coll.write_documents(docs,).await
+        .expect("Error writing list")
+        .expect("Error writing list");
+      coll.flaky_reads(DocumentId::from_bytes([3; 20]), 1,);
+      let head = coll.ref_cursor::<Doc>(&DocumentId::from_bytes([2; 20]),).await
+        .expect("Error reading head");
+      let items = head.into_stream().collect::<Vec<_>>().await;
+      assert_eq!(items, [Ok(docs[0]), Err(CollectError::Fetch(ChaosError::Injected(DocumentId::from_bytes([3; 20]),),)),], "Error ending after a failed read",);
+    },);
+
+    //A corrupted list loops back on itself.
+    let coll = RecordingCollection::new(MemoryCollection::new(),);
+
+    block_on(async {
+      let head = *write_list(coll.get_inner(), &[1, 2, 3, 4,], 1,).await.get_item();
+      coll.get_inner().write_document(&Doc { id: DocumentId::from_bytes([4; 20]), prev: Some(DocumentId::from_bytes([3; 20])), next: Some(DocumentId::from_bytes([1; 20])), },).await
+        .expect("Error writing cycle");
+      let items = Cursor::new(&coll, head,).into_stream().map(|doc,| doc.map(|doc,| doc.id.as_bytes()[0],),).collect::<Vec<_>>().await;
+      assert_eq!(items, [Ok(1), Ok(2), Ok(3), Ok(4), Err(CollectError::Cycle(DocumentId::from_bytes([1; 20]),)),], "Error ending at the cycle",);
+      crate::assert_ops!(coll, [Op::Get(DocumentId::from_bytes([2; 20]),), Op::Get(DocumentId::from_bytes([3; 20]),), Op::Get(DocumentId::from_bytes([4; 20]),),], "Error read the repeated node",);
+    },);
+  }
+  #[test]
+  fn test_stream_while() {
+    use futures::StreamExt;
+
+    let coll = RecordingCollection::new(MemoryCollection::new(),);
+
+    block_on(async {
+      let head = *write_list(coll.get_inner(), &[1, 2, 3, 4, 5, 6,], 1,).await.get_item();
+      let items = Cursor::new(&coll, head,).stream_while(|doc,| doc.id.as_bytes()[0] < 4,)
+        .map(|doc,| doc.map(|doc,| doc.id.as_bytes()[0],),)
+        .collect::<Vec<_>>().await;
+      assert_eq!(items, [Ok(1), Ok(2), Ok(3),], "Error ending at the failing item",);
+      crate::assert_ops!(coll, [Op::Get(DocumentId::from_bytes([2; 20]),), Op::Get(DocumentId::from_bytes([3; 20]),), Op::Get(DocumentId::from_bytes([4; 20]),),], "Error read past the failing node",);
+
+      let items = Cursor::new(&coll, head,).stream_while(|_,| false,).collect::<Vec<_>>().await;
+      assert!(items.is_empty(), "Error yielding a failing item",);
+      crate::assert_ops!(coll, [], "Error read past the failing item",);
+
+      let mut rest = Box::pin(Cursor::new(&coll, head,).skip_until(|doc,| doc.id.as_bytes()[0] < 3,),);
+      assert_eq!(rest.next().await.map(|doc,| doc.map(|doc,| doc.id.as_bytes()[0],),), Some(Ok(3)), "Error skipping the matching items",);
+      crate::assert_ops!(coll, [Op::Get(DocumentId::from_bytes([2; 20]),), Op::Get(DocumentId::from_bytes([3; 20]),),], "Error read past the first failing node",);
+      let rest = rest.map(|doc,| doc.map(|doc,| doc.id.as_bytes()[0],),).collect::<Vec<_>>().await;
+      assert_eq!(rest, [Ok(4), Ok(5), Ok(6),], "Error yielding the rest of the list",);
+    },);
+  }
+  #[test]
+  fn test_collect() {
+    let coll = MemoryCollection::new();
+
+    block_on(async {
+      let cursor = write_list(&coll, &[1, 2, 3, 4, 5,], 2,).await;
+      let ids = |items: Vec<Doc>,| items.iter().map(|doc,| doc.id.as_bytes()[0],).collect::<Vec<_>>();
+      assert_eq!(cursor.collect_forward(0,).await.map(ids,), Ok(vec![2, 3, 4, 5,]), "Error collecting to the tail",);
+      assert_eq!(cursor.collect_forward(2,).await.map(ids,), Ok(vec![2, 3,]), "Error limiting items",);
+      assert_eq!(cursor.collect_forward(10,).await.map(ids,), Ok(vec![2, 3, 4, 5,]), "Error stopping at the tail",);
+      assert_eq!(cursor.collect_backward(0,).await.map(ids,), Ok(vec![2, 1,]), "Error collecting to the head",);
+      assert_eq!(cursor.collect_forward_capped(0, 4,).await.map(ids,), Ok(vec![2, 3, 4, 5,]), "Error ending at the cap",);
+      assert_eq!(cursor.get_item().id, DocumentId::from_bytes([2; 20]), "Error Cursor moved",);
+    },);
+
+    //A corrupted list loops back on itself.
+    let coll = MemoryCollection::new();
+
+    block_on(async {
+      let cursor = write_list(&coll, &[1, 2, 3,], 1,).await;
+      coll.write_document(&Doc { id: DocumentId::from_bytes([3; 20]), prev: Some(DocumentId::from_bytes([2; 20])), next: Some(DocumentId::from_bytes([1; 20])), },).await
+        .expect("Error writing cycle");
+      assert_eq!(cursor.collect_forward_capped(0, 10,).await, Err(CollectError::Cycle(DocumentId::from_bytes([2; 20]),)), "Error tripping the cap",);
+      assert_eq!(cursor.collect_forward(7,).await.map(|items,| items.len(),), Ok(7), "Error limiting a cycle",);
+    },);
+  }
+  #[test]
+  fn test_save() {
+    let coll = MemoryCollection::<Card,>::new();
+    let card = |id: u8, previous: Option<u8>, next: Option<u8>,| Card {
+      id: CardId::from_bytes([id; 20]),
+      name: "card".to_owned(),
+      description: String::new(),
+      up_votes: 0,
+      down_votes: 0,
+      bias: 0,
+      status: CardStatus::Open,
+      history: Vec::new(),
+      previous_card: previous.map(|id,| CardId::from_bytes([id; 20]),),
+      next_card: next.map(|id,| CardId::from_bytes([id; 20]),),
+    };
+
+    block_on(async {
+      coll.write_documents([card(1, None, Some(2,),), card(2, Some(1,), Some(3,),), card(3, Some(2,), None,),],).await
+        .expect("Error writing cards")
+        .expect("Error writing cards");
+
+      let mut cursor = coll.ref_cursor::<Card>(&DocumentId::from_bytes([1; 20]),).await
+        .expect("Error reading card");
+      cursor.get_item_mut().up_votes += 1;
+      assert_eq!(coll.get_document(&DocumentId::from_bytes([1; 20]),).await.map(|card,| card.up_votes,), Ok(0), "Error saved before save",);
+      cursor.save().await
+        .expect("Error saving card");
+      assert_eq!(coll.get_document(&DocumentId::from_bytes([1; 20]),).await.map(|card,| card.up_votes,), Ok(1), "Error saving card",);
+
+      //Vote on every card.
+      let mut cursor = coll.ref_cursor::<Card>(&DocumentId::from_bytes([1; 20]),).await
+        .expect("Error reading card");
+      loop {
+        cursor.get_item_mut().down_votes += 2;
+        if !cursor.save_and_move_next().await.expect("Error saving card") { break }
+      }
+      let cards = coll.get_documents([DocumentId::from_bytes([1; 20]), DocumentId::from_bytes([2; 20]), DocumentId::from_bytes([3; 20]),],).await
+        .expect("Error reading cards");
+      assert_eq!(
+        cards.into_iter().map(|card,| card.map(|card,| (card.up_votes, card.down_votes,),),).collect::<Vec<_>>(),
+        [Ok((1, 2,)), Ok((0, 2,)), Ok((0, 2,)),],
+        "Error saving every card",
+      );
+    },);
+  }
+  #[test]
+  fn test_position() {
+    let coll = MemoryCollection::new();
+
+    block_on(async {
+      write_list(&coll, &[1, 2, 3, 4, 5,], 1,).await;
+      let tier = TierMeta::new(TierId::from_bytes([9; 20]), (NonZeroU64::new(5,), CardId::from_bytes([1; 20]), CardId::from_bytes([5; 20]),), None, None,);
+      let mut cursor = coll.ref_front_cursor::<Doc>(&tier,).await
+        .expect("Error reading front");
+      assert_eq!(cursor.position(), Some(0), "Error front not at zero",);
+      assert_eq!(coll.ref_cursor::<Doc>(&DocumentId::from_bytes([3; 20]),).await.map(|cursor,| cursor.position(),), Ok(None), "Error tracking an untracked Cursor",);
+
+      //Walk forward.
+      assert_eq!(cursor.move_next_mut().await, Ok(true), "Error moving next",);
+      assert_eq!(cursor.position(), Some(1), "Error counting up",);
+      let next = cursor.ref_next().await
+        .expect("Error reading next")
+        .expect("Error no next");
+      assert_eq!(next.position(), Some(2), "Error counting the next Cursor",);
+      let (mut cursor, moved,) = cursor.seek_forward(10,).await
+        .expect("Error seeking forward");
+      assert_eq!((moved, cursor.position(),), (3, Some(4),), "Error counting a seek",);
+      assert_eq!(cursor.move_next_mut().await, Ok(false), "Error moving past the tail",);
+      assert_eq!(cursor.position(), Some(4), "Error counting past the tail",);
+
+      //Walk back.
+      let cursor = match cursor.move_previous() {
+        Ok(previous) => previous.await.unwrap_or_else(|(_, e,),| panic!("Error moving previous: {:?}", e,),),
+        Err(_) => panic!("Error no previous"),
+      };
+      assert_eq!(cursor.position(), Some(3), "Error counting down",);
+      let (cursor, _,) = cursor.seek_backward(10,).await
+        .expect("Error seeking backward");
+      assert_eq!((cursor.get_item().id, cursor.position(),), (DocumentId::from_bytes([1; 20]), Some(0),), "Error round tripping the position",);
+      assert_eq!(cursor.as_ref().position(), Some(0), "Error referencing the position",);
+    },);
+  }
+  /// Builds and relinks a list using only the generic helpers and returns the
+  /// identifiers of the list in order.
+  async fn relink<T,>(node: fn(u8,) -> T,) -> Vec<u8>
+    where T: 'static + LinkedListMut + Clone + fmt::Debug, {
+    let coll = MemoryCollection::<T,>::new();
+
+    coll.write_document(&node(1,),).await
+      .expect("Error writing node");
+    let mut cursor = coll.ref_cursor::<T>(&DocumentId::from_bytes([1; 20]),).await
+      .expect("Error reading node");
+    cursor.insert_after(node(4,),).await
+      .expect("Error inserting after");
+    cursor.splice_after(vec![node(2,), node(3,),],).await
+      .expect("Error splicing after");
+    cursor.insert_before(node(0,),).await
+      .expect("Error inserting before");
+    assert_eq!(cursor.swap_with_next().await, Ok(Swap::Swapped), "Error swapping",);
+    cursor.remove(true,).await
+      .expect("Error removing");
+
+    let mut cursor = coll.ref_cursor::<T>(&DocumentId::from_bytes([0; 20]),).await
+      .expect("Error reading head");
+    let mut ids = vec![cursor.get_item().get_id().as_bytes()[0]];
+    while cursor.move_next_mut().await.expect("Error moving next") { ids.push(cursor.get_item().get_id().as_bytes()[0],) }
+
+    ids
+  }
+  #[test]
+  fn test_generic_links() {
+    let card = |id: u8,| Card {
+      id: CardId::from_bytes([id; 20]),
+      name: "card".to_owned(),
+      description: String::new(),
+      up_votes: 0,
+      down_votes: 0,
+      bias: 0,
+      status: CardStatus::Open,
+      history: Vec::new(),
+      previous_card: None,
+      next_card: None,
+    };
+    let doc = |id: u8,| Doc { id: DocumentId::from_bytes([id; 20]), prev: None, next: None, };
+
+    assert_eq!(block_on(relink(card,),), [0, 2, 3, 4,], "Error relinking cards",);
+    assert_eq!(block_on(relink(doc,),), [0, 2, 3, 4,], "Error relinking nodes",);
+
+    let mut linked = Card { previous_card: Some(CardId::from_bytes([1; 20])), next_card: Some(CardId::from_bytes([3; 20])), ..card(2,) };
+    linked.detach();
+    assert_eq!(linked, card(2,), "Error detaching card",);
+    let mut linked = Doc { prev: Some(DocumentId::from_bytes([1; 20])), next: Some(DocumentId::from_bytes([3; 20])), ..doc(2,) };
+    linked.detach();
+    assert_eq!(linked, doc(2,), "Error detaching node",);
+  }
+  #[test]
+  fn test_splice() {
+    let coll = RecordingCollection::new(MemoryCollection::new(),);
+    let node = |id: u8,| Doc { id: DocumentId::from_bytes([id; 20]), prev: None, next: None, };
+
+    block_on(async {
+      let item = *write_list(coll.get_inner(), &[1, 2, 3,], 2,).await.get_item();
+      let mut cursor = Cursor::new(&coll, item,);
+
+      //Splice into the middle.
+      cursor.splice_after(vec![node(10,), node(11,), node(12,),],).await
+        .expect("Error splicing into the middle");
+      crate::assert_ops!(
+        coll,
+        [Op::Get(DocumentId::from_bytes([3; 20]),), Op::WriteBatch(vec![DocumentId::from_bytes([2; 20]), DocumentId::from_bytes([10; 20]), DocumentId::from_bytes([11; 20]), DocumentId::from_bytes([12; 20]), DocumentId::from_bytes([3; 20]),]),],
+        "Error splicing in one batch",
+      );
+      assert_eq!(cursor.get_item().next, Some(DocumentId::from_bytes([10; 20])), "Error updating the Cursor",);
+      assert_eq!(walk_list(coll.get_inner(), 1,).await, [1, 2, 10, 11, 12, 3,], "Error splicing into the middle",);
+
+      //Splice at the tail.
+      let tail = *coll.get_inner().ref_cursor::<Doc>(&DocumentId::from_bytes([3; 20]),).await
+        .expect("Error reading tail")
+        .get_item();
+      let mut cursor = Cursor::new(&coll, tail,);
+      cursor.splice_after(vec![node(20,), node(21,), node(22,),],).await
+        .expect("Error splicing at the tail");
+      crate::assert_ops!(coll, [Op::WriteBatch(vec![DocumentId::from_bytes([3; 20]), DocumentId::from_bytes([20; 20]), DocumentId::from_bytes([21; 20]), DocumentId::from_bytes([22; 20]),]),], "Error splicing in one batch",);
+      assert_eq!(walk_list(coll.get_inner(), 1,).await, [1, 2, 10, 11, 12, 3, 20, 21, 22,], "Error splicing at the tail",);
+
+      //Nothing is written for an empty or invalid chain.
+      assert_eq!(cursor.splice_after(Vec::new(),).await, Ok(()), "Error splicing an empty chain",);
+      assert_eq!(cursor.splice_after(vec![node(30,), node(3,),],).await, Err(InsertError::Duplicate(DocumentId::from_bytes([3; 20]),)), "Error splicing this node",);
+      assert_eq!(cursor.splice_after(vec![node(30,), node(31,), node(30,),],).await, Err(InsertError::Duplicate(DocumentId::from_bytes([30; 20]),)), "Error splicing a node twice",);
+      crate::assert_ops!(coll, [], "Error wrote an empty or invalid chain",);
+      assert_eq!(walk_list(coll.get_inner(), 1,).await, [1, 2, 10, 11, 12, 3, 20, 21, 22,], "Error changed the list",);
+    },);
+  }
+  #[test]
+  fn test_jump() {
+    let coll = RecordingCollection::new(MemoryCollection::new(),);
+    let tier = TierMeta::new(TierId::from_bytes([99; 20]), (NonZeroU64::new(50,), CardId::from_bytes([1; 20]), CardId::from_bytes([50; 20]),), None, None,);
+
+    block_on(async {
+      let ids = (1..=50).collect::<Vec<u8>>();
+      let item = *write_list(coll.get_inner(), &ids, 25,).await.get_item();
+
+      let cursor = Cursor::new(&coll, item,).jump_to_front(&tier,).await
+        .unwrap_or_else(|(_, e,),| panic!("Error jumping to the front: {:?}", e,),);
+      assert_eq!((cursor.get_item().id, cursor.position(),), (DocumentId::from_bytes([1; 20]), Some(0),), "Error jumping to the front",);
+      crate::assert_ops!(coll, [Op::Get(DocumentId::from_bytes([1; 20]),),], "Error jumping in one read",);
+
+      let cursor = cursor.jump_to_back(&tier,).await
+        .unwrap_or_else(|(_, e,),| panic!("Error jumping to the back: {:?}", e,),);
+      assert_eq!((cursor.get_item().id, cursor.position(),), (DocumentId::from_bytes([50; 20]), Some(49),), "Error jumping to the back",);
+      crate::assert_ops!(coll, [Op::Get(DocumentId::from_bytes([50; 20]),),], "Error jumping in one read",);
+
+      //Jumping fails for an empty tier or a missing end.
+      let empty = TierMeta::new(TierId::from_bytes([98; 20]), (None, CardId::from_bytes([0; 20]), CardId::from_bytes([0; 20]),), None, None,);
+      let (cursor, e,) = cursor.jump_to_front(&empty,).await
+        .expect_err("Error jumping into an empty tier");
+      assert_eq!(e, JumpError::Empty(DocumentId::from_bytes([98; 20]),), "Error jumping into an empty tier",);
+      crate::assert_ops!(coll, [], "Error read for an empty tier",);
+
+      let broken = TierMeta::new(TierId::from_bytes([97; 20]), (NonZeroU64::new(1,), CardId::from_bytes([77; 20]), CardId::from_bytes([77; 20]),), None, None,);
+      let (cursor, e,) = cursor.jump_to_back(&broken,).await
+        .expect_err("Error jumping to a missing end");
+      assert_eq!(e, JumpError::Fetch(MemoryError::NotFound(DocumentId::from_bytes([77; 20]),),), "Error jumping to a missing end",);
+      assert_eq!((cursor.get_item().id, cursor.position(),), (DocumentId::from_bytes([50; 20]), Some(49),), "Error Cursor changed after an error",);
+      coll.take_ops();
+
+      //A tier of unknown length is not empty; the position at its back is unknown.
+      let unknown = TierMeta::new(TierId::from_bytes([96; 20]), (None, CardId::from_bytes([1; 20]), CardId::from_bytes([50; 20]),), None, None,);
+      let cursor = cursor.jump_to_front(&unknown,).await
+        .unwrap_or_else(|(_, e,),| panic!("Error jumping to the front of a tier of unknown length: {:?}", e,),);
+      assert_eq!((cursor.get_item().id, cursor.position(),), (DocumentId::from_bytes([1; 20]), Some(0),), "Error jumping to the front of a tier of unknown length",);
+      let cursor = cursor.jump_to_back(&unknown,).await
+        .unwrap_or_else(|(_, e,),| panic!("Error jumping to the back of a tier of unknown length: {:?}", e,),);
+      assert_eq!((cursor.get_item().id, cursor.position(),), (DocumentId::from_bytes([50; 20]), None,), "Error jumping to the back of a tier of unknown length",);
+      crate::assert_ops!(coll, [Op::Get(DocumentId::from_bytes([1; 20]),), Op::Get(DocumentId::from_bytes([50; 20]),),], "Error jumping in one read",);
+    },);
+  }
+  #[test]
+  fn test_cursor_front() {
+    let coll = RecordingCollection::new(MemoryCollection::<Card,>::new(),);
+    let cards = (1..=30u8).map(|id,| Card {
+      id: CardId::from_bytes([id; 20]),
+      name: "card".to_owned(),
+      description: String::new(),
+      up_votes: 0,
+      down_votes: 0,
+      bias: 0,
+      status: CardStatus::Open,
+      history: Vec::new(),
+      previous_card: Some(CardId::from_bytes([id - 1; 20]),).filter(|_,| id > 1,),
+      next_card: Some(CardId::from_bytes([id + 1; 20]),).filter(|_,| id < 30,),
+    },).collect::<Vec<_>>();
+
+    block_on(async {
+      coll.get_inner().write_documents(cards,).await
+        .expect("Error writing cards")
+        .expect("Error writing cards");
+
+      //The front card is read once whatever the length of the tier.
+      for len in [NonZeroU64::new(30,), None,] {
+        let tier = TierMeta::new(TierId::from_bytes([99; 20]), (len, CardId::from_bytes([1; 20]), CardId::from_bytes([30; 20]),), None, None,);
+        let cursor = coll.cursor_front(&tier,).await
+          .expect("Error opening a cursor at the front");
+        assert_eq!((cursor.get_item().id, cursor.position(),), (CardId::from_bytes([1; 20]), Some(0),), "Error opening a cursor at the front",);
+        crate::assert_ops!(coll, [Op::Get(DocumentId::from_bytes([1; 20]),),], "Error opening a cursor in one read",);
+      }
+
+      //An empty tier is not read.
+      let empty = TierMeta::new(TierId::from_bytes([98; 20]), (None, CardId::NIL, CardId::NIL,), None, None,);
+      assert_eq!(coll.cursor_front(&empty,).await.map(|cursor,| cursor.get_item().id,), Err(JumpError::Empty(DocumentId::from_bytes([98; 20]),),), "Error opening a cursor in an empty tier",);
+      crate::assert_ops!(coll, [], "Error read for an empty tier",);
+    },);
+  }
+  #[test]
+  fn test_find_forward() {
+    let coll = MemoryCollection::new();
+
+    block_on(async {
+      let cursor = write_list(&coll, &[1, 2, 3, 4, 5,], 1,).await;
+      let found = cursor.find_forward(|doc,| doc.id.as_bytes()[0] % 3 == 0,).await
+        .unwrap_or_else(|(_, e,),| panic!("Error finding node: {:?}", e,),)
+        .expect("Error node not found");
+      assert_eq!(found.get_item().id, DocumentId::from_bytes([3; 20]), "Error finding the middle node",);
+      let found = found.find_forward(|doc,| doc.id.as_bytes()[0] % 3 == 0,).await
+        .unwrap_or_else(|(_, e,),| panic!("Error finding node: {:?}", e,),)
+        .expect("Error node not found");
+      assert_eq!(found.get_item().id, DocumentId::from_bytes([3; 20]), "Error not checking the current item",);
+      let missing = found.find_forward(|doc,| doc.id.as_bytes()[0] > 5,).await
+        .unwrap_or_else(|(_, e,),| panic!("Error finding node: {:?}", e,),);
+      assert_eq!(missing, None, "Error found a missing node",);
+    },);
+
+    //A failed read returns the last node checked.
+    let coll = ChaosCollection::<Doc,>::new();
+    let docs = (1..=5u8).map(|i,| Doc { id: DocumentId::from_bytes([i; 20]), prev: None, next: Some(DocumentId::from_bytes([i + 1; 20])), },).collect::<Vec<_>>();
+
+    block_on(async {
+      coll.write_documents(&docs,).await
+        .expect("Error writing list")
+        .expect("Error writing list");
+      coll.flaky_reads(DocumentId::from_bytes([4; 20]), 1,);
+      let cursor = coll.ref_cursor::<Doc>(&DocumentId::from_bytes([1; 20]),).await
+        .expect("Error reading head");
+      let (cursor, e,) = cursor.find_forward(|doc,| doc.id.as_bytes()[0] == 5,).await
+        .expect_err("Error finding through a broken node");
+      assert_eq!((cursor.get_item().id, e,), (DocumentId::from_bytes([3; 20]), CollectError::Fetch(ChaosError::Injected(DocumentId::from_bytes([4; 20]),),),), "Error returning the last node checked",);
+      let found = cursor.find_forward(|doc,| doc.id.as_bytes()[0] == 5,).await
+        .unwrap_or_else(|(_, e,),| panic!("Error resuming the search: {:?}", e,),);
+      assert_eq!(found.map(|cursor,| cursor.get_item().id,), Some(DocumentId::from_bytes([5; 20])), "Error resuming the search",);
+    },);
+
+    //A cycle trips the cap.
+    let coll = MemoryCollection::new();
+
+    block_on(async {
+      let cursor = write_list(&coll, &[1, 2, 3,], 1,).await;
+      coll.write_document(&Doc { id: DocumentId::from_bytes([3; 20]), prev: Some(DocumentId::from_bytes([2; 20])), next: Some(DocumentId::from_bytes([1; 20])), },).await
+        .expect("Error writing cycle");
+      let (_, e,) = cursor.find_forward(|_,| false,).await
+        .expect_err("Error searching a cycle");
+      assert_eq!(e, CollectError::Cycle(DocumentId::from_bytes([(DEFAULT_COLLECT_CAP % 3) as u8 + 1; 20]),), "Error tripping the cap",);
+    },);
+  }
+  #[test]
+  fn test_prefetch_next() {
+    let coll = RecordingCollection::new(MemoryCollection::new(),);
+
+    block_on(async {
+      let ids = (1..=20).collect::<Vec<u8>>();
+      let item = *write_list(coll.get_inner(), &ids, 5,).await.get_item();
+      let cursor = Cursor::new(&coll, item,);
+
+      //Walk with `get_next` for the expected nodes and reads.
+      let mut expected = Vec::new();
+      let mut next = cursor.get_next().await
+        .expect("Error reading next");
+      while let Some(cursor) = next.filter(|_,| expected.len() < 10,) {
+        expected.push(*cursor.get_item(),);
+        next = cursor.get_next().await
+          .expect("Error reading next");
+      }
+      let sequential = coll.take_ops();
+
+      assert_eq!(cursor.prefetch_next(10,).await, Ok(expected), "Error prefetching nodes",);
+      let prefetched = coll.take_ops();
+      assert!(prefetched.len() <= sequential.len(), "Error prefetching read more than `get_next`",);
+      assert_eq!(prefetched, (6..=15).map(|i,| Op::Get(DocumentId::from_bytes([i; 20]),),).collect::<Vec<_>>(), "Error reading past the nodes",);
+
+      assert_eq!(cursor.prefetch_next(0,).await, Ok(Vec::new()), "Error prefetching no nodes",);
+      assert_eq!(cursor.prefetch_next(100,).await.map(|docs,| docs.len(),), Ok(15), "Error stopping at the tail",);
+    },);
+  }
+  #[test]
+  fn test_distance_to() {
+    let coll = RecordingCollection::new(MemoryCollection::new(),);
+
+    block_on(async {
+      let item = *write_list(coll.get_inner(), &[1, 2, 3, 4, 5, 6,], 2,).await.get_item();
+      let cursor = Cursor::new(&coll, item,);
+
+      assert_eq!(cursor.distance_to(&DocumentId::from_bytes([2; 20]), 10,).await, Ok(Some(0)), "Error counting to the Cursor",);
+      crate::assert_ops!(coll, [], "Error reading to count no steps",);
+      assert_eq!(cursor.distance_to(&DocumentId::from_bytes([5; 20]), 10,).await, Ok(Some(3)), "Error counting to a node ahead",);
+      crate::assert_ops!(coll, [Op::Get(DocumentId::from_bytes([3; 20])), Op::Get(DocumentId::from_bytes([4; 20])), Op::Get(DocumentId::from_bytes([5; 20])),], "Error reading past the node",);
+      assert_eq!(cursor.distance_to(&DocumentId::from_bytes([1; 20]), 10,).await, Ok(None), "Error found a node behind",);
+      assert_eq!(coll.take_ops().len(), 4, "Error reading past the tail",);
+      assert_eq!(cursor.distance_to(&DocumentId::from_bytes([9; 20]), 10,).await, Ok(None), "Error found a missing node",);
+      coll.take_ops();
+
+      //The cap.
+      assert_eq!(cursor.distance_to(&DocumentId::from_bytes([6; 20]), 4,).await, Ok(Some(4)), "Error counting to the cap",);
+      coll.take_ops();
+      assert_eq!(cursor.distance_to(&DocumentId::from_bytes([6; 20]), 3,).await, Ok(None), "Error counting past the cap",);
+      crate::assert_ops!(coll, [Op::Get(DocumentId::from_bytes([3; 20])), Op::Get(DocumentId::from_bytes([4; 20])), Op::Get(DocumentId::from_bytes([5; 20])),], "Error reading past the cap",);
+    },);
+  }
+  #[test]
+  fn test_checkpoint() {
+    let coll = MemoryCollection::new();
+
+    block_on(async {
+      let cursor = write_list(&coll, &[1, 2, 3, 4,], 2,).await.with_position(1,);
+      let checkpoint = cursor.checkpoint();
+      assert_eq!((checkpoint.get_id(), checkpoint.position(),), (&DocumentId::from_bytes([2; 20]), Some(1),), "Error checkpointing",);
+
+      //Explore and change the checkpointed node.
+      let (cursor, _,) = cursor.seek_forward(2,).await
+        .expect("Error seeking forward");
+      coll.write_document(&Doc { id: DocumentId::from_bytes([2; 20]), prev: Some(DocumentId::from_bytes([1; 20])), next: None, },).await
+        .expect("Error changing node");
+      let cursor = cursor.rewind(&checkpoint,).await
+        .expect("Error rewinding");
+      assert_eq!(*cursor.get_item(), Doc { id: DocumentId::from_bytes([2; 20]), prev: Some(DocumentId::from_bytes([1; 20])), next: None, }, "Error reading the node again",);
+      assert_eq!(cursor.position(), Some(1), "Error restoring the position",);
+
+      //Delete the checkpointed node.
+      let cursor = write_list(&coll, &[1, 2, 3, 4,], 2,).await;
+      let checkpoint = cursor.checkpoint();
+      let (_, cursor,) = cursor.remove(true,).await
+        .expect("Error removing node");
+      let cursor = cursor.expect("Error no Cursor after removing")
+        .with_position(5,);
+      let (cursor, e,) = cursor.rewind(&checkpoint,).await
+        .expect_err("Error rewinding to a deleted node");
+      assert!(e.is_not_found(), "Error deleted node not classified as not found",);
+      assert_eq!((cursor.get_item().id, cursor.position(),), (DocumentId::from_bytes([3; 20]), Some(5),), "Error Cursor moved after failure",);
+    },);
+  }
+  #[test]
+  fn test_for_each_forward() {
+    let votes = [100, 50, 5, 0, 200, 9,];
+    let list = || (0..votes.len()).map(|i,| Card {
+      id: CardId::from_bytes([i as u8; 20]),
+      name: "card".to_owned(),
+      description: String::new(),
+      up_votes: votes[i],
+      down_votes: 0,
+      bias: 0,
+      status: CardStatus::Open,
+      history: Vec::new(),
+      previous_card: i.checked_sub(1,).map(|i,| CardId::from_bytes([i as u8; 20]),),
+      next_card: Some(CardId::from_bytes([i as u8 + 1; 20]),).filter(|_,| i + 1 < votes.len(),),
+    },).collect::<Vec<_>>();
+    //Decays the up votes by 10%, skipping the cards which do not change.
+    let decay = |card: &mut Card,| {
+      let decay = card.up_votes / 10;
+
+      card.up_votes -= decay;
+      decay > 0
+    };
+    let options = ForEachOptions { batch_size: 2, abort_on_failure: false, };
+
+    block_on(async {
+      let coll = RecordingCollection::new(ChaosCollection::new(),);
+      coll.get_inner().write_documents(list(),).await
+        .expect("Error writing cards")
+        .expect("Error writing cards");
+      let cursor = coll.ref_cursor::<Card>(&DocumentId::from_bytes([0; 20]),).await
+        .expect("Error reading front");
+      coll.take_ops();
+
+      let summary = cursor.for_each_forward(decay, options,).await
+        .expect("Error decaying cards");
+      assert_eq!(summary, ForEachSummary { visited: 6, modified: 3, failed: Vec::new(), }, "Error summarising the walk",);
+      let writes = coll.take_ops().into_iter().filter(|op,| matches!(op, Op::WriteBatch(_)),).collect::<Vec<_>>();
+      assert_eq!(writes, [Op::WriteBatch(vec![DocumentId::from_bytes([0; 20]), DocumentId::from_bytes([1; 20]),]), Op::WriteBatch(vec![DocumentId::from_bytes([4; 20]),]),], "Error batching the writes",);
+      let stored = coll.get_documents((0..6u8).map(|i,| DocumentId::from_bytes([i; 20]),),).await
+        .expect("Error reading cards")
+        .into_iter()
+        .map(|card,| card.map(|card,| card.up_votes,),)
+        .collect::<Vec<_>>();
+      assert_eq!(stored, [Ok(90), Ok(45), Ok(5), Ok(0), Ok(180), Ok(9),], "Error persisting the decay",);
+
+      //A failed write does not stop the walk.
+      let coll = ChaosCollection::new();
+      coll.write_documents(list(),).await
+        .expect("Error writing cards")
+        .expect("Error writing cards");
+      coll.fail_writes(DocumentId::from_bytes([1; 20]),);
+      let summary = coll.ref_cursor::<Card>(&DocumentId::from_bytes([0; 20]),).await
+        .expect("Error reading front")
+        .for_each_forward(decay, options,).await
+        .expect("Error decaying cards");
+      assert_eq!(summary, ForEachSummary { visited: 6, modified: 3, failed: vec![DocumentId::from_bytes([1; 20]),], }, "Error reporting the failed write",);
+      assert_eq!(coll.get_document(&DocumentId::from_bytes([4; 20]),).await.map(|card,| card.up_votes,), Ok(180), "Error continuing after a failed write",);
+
+      //Unless it is set to stop.
+      let coll = ChaosCollection::new();
+      coll.write_documents(list(),).await
+        .expect("Error writing cards")
+        .expect("Error writing cards");
+      coll.fail_writes(DocumentId::from_bytes([1; 20]),);
+      let (summary, e,) = coll.ref_cursor::<Card>(&DocumentId::from_bytes([0; 20]),).await
+        .expect("Error reading front")
+        .for_each_forward(decay, ForEachOptions { abort_on_failure: true, ..options },).await
+        .expect_err("Error continuing after a failed write");
+      assert_eq!(e, ForEachError::Write(ChaosError::Injected(DocumentId::from_bytes([1; 20]),),), "Error returning the write error",);
+      assert_eq!(summary, ForEachSummary { visited: 2, modified: 2, failed: vec![DocumentId::from_bytes([1; 20]),], }, "Error summarising the stopped walk",);
+
+      //A failed read writes the changes already made.
+      let coll = ChaosCollection::new();
+      coll.write_documents(list(),).await
+        .expect("Error writing cards")
+        .expect("Error writing cards");
+      coll.fail_reads(DocumentId::from_bytes([2; 20]),);
+      let (summary, e,) = coll.ref_cursor::<Card>(&DocumentId::from_bytes([0; 20]),).await
+        .expect("Error reading front")
+        .for_each_forward(decay, ForEachOptions { batch_size: 10, ..options },).await
+        .expect_err("Error reading a broken card");
+      assert_eq!(e, ForEachError::Fetch(ChaosError::Injected(DocumentId::from_bytes([2; 20]),),), "Error returning the read error",);
+      assert_eq!(summary, ForEachSummary { visited: 2, modified: 2, failed: Vec::new(), }, "Error summarising the broken walk",);
+      assert_eq!(coll.get_document(&DocumentId::from_bytes([1; 20]),).await.map(|card,| card.up_votes,), Ok(45), "Error writing the changes before the error",);
+
+      //A cycle stops the walk before a card is decayed twice.
+      let coll = MemoryCollection::new();
+      let mut cards = list();
+      cards[5].next_card = Some(CardId::from_bytes([2; 20]),);
+      coll.write_documents(cards,).await
+        .expect("Error writing cards")
+        .expect("Error writing cards");
+      let (summary, e,) = coll.ref_cursor::<Card>(&DocumentId::from_bytes([0; 20]),).await
+        .expect("Error reading front")
+        .for_each_forward(decay, options,).await
+        .expect_err("Error walking a cycle");
+      assert_eq!(e, ForEachError::Cycle(DocumentId::from_bytes([2; 20]),), "Error detecting the cycle",);
+      assert_eq!(summary, ForEachSummary { visited: 6, modified: 3, failed: Vec::new(), }, "Error summarising the walk",);
+      assert_eq!(coll.get_document(&DocumentId::from_bytes([4; 20]),).await.map(|card,| card.up_votes,), Ok(180), "Error writing the changes before the cycle",);
+    },);
+  }
+  #[test]
+  fn test_try_map() {
+    let coll = MemoryCollection::new();
+    let card = |id: u8, previous: Option<u8>, next: Option<u8>,| TierDocument::Card(Card {
+      id: CardId::from_bytes([id; 20]),
+      name: "card".to_owned(),
+      description: String::new(),
+      up_votes: 0,
+      down_votes: 0,
+      bias: 0,
+      status: CardStatus::Open,
+      history: Vec::new(),
+      previous_card: previous.map(|id,| CardId::from_bytes([id; 20]),),
+      next_card: next.map(|id,| CardId::from_bytes([id; 20]),),
+    },);
+    //A corrupted list with tiers linked between its cards.
+    let tier = |id: u8, previous: u8, next: u8,| TierDocument::TierMeta(TierMeta::new(TierId::from_bytes([id; 20]), (None, CardId::from_bytes([0; 20]), CardId::from_bytes([0; 20]),), Some(TierId::from_bytes([previous; 20])), Some(TierId::from_bytes([next; 20])),),);
+
+    block_on(async {
+      coll.write_documents([card(1, None, Some(2,),), tier(2, 1, 3,), tier(3, 2, 4,), card(4, Some(3,), None,),],).await
+        .expect("Error writing list")
+        .expect("Error writing list");
+
+      let cursor = coll.ref_cursor::<TierDocument>(&DocumentId::from_bytes([1; 20]),).await
+        .expect("Error reading card")
+        .with_position(0,);
+      let cards = cursor.clone().try_map(Card::try_from,)
+        .expect("Error converting card");
+      assert_eq!((cards.get_item().id, cards.position(),), (CardId::from_bytes([1; 20]), Some(0),), "Error converting card",);
+
+      let cursor = cursor.get_next().await
+        .expect("Error reading tier")
+        .expect("Error no next node");
+      let (cursor, e,) = cursor.try_map(Card::try_from,)
+        .expect_err("Error converting tier to card");
+      assert_eq!(e.found, DocumentKind::TierMeta, "Error returning the conversion error",);
+      assert_eq!(cursor.get_item().get_id(), &DocumentId::from_bytes([2; 20]), "Error Cursor changed after failure",);
+      assert!(cursor.clone().try_map(TierMeta::try_from,).is_ok(), "Error retrying with another type",);
+
+      //Skip the tiers to the next card.
+      let cards = cursor.try_map_or_skip_next(Card::try_from,).await
+        .unwrap_or_else(|(_, e,),| panic!("Error skipping tiers: {:?}", e,),)
+        .expect("Error no card found");
+      assert_eq!(cards.get_item().id, DocumentId::from_bytes([4; 20]), "Error skipping to the next card",);
+
+      let cursor = coll.ref_cursor::<TierDocument>(&DocumentId::from_bytes([2; 20]),).await
+        .expect("Error reading tier");
+      let none = cursor.try_map_or_skip_next(|doc,| doc.get_id().as_bytes()[0].checked_sub(10,).ok_or(()),).await
+        .unwrap_or_else(|(_, e,),| panic!("Error skipping nodes: {:?}", e,),);
+      assert!(none.is_none(), "Error converted past the tail",);
+    },);
+  }
+  #[test]
+  fn test_dirty() {
+    let coll = MemoryCollection::<Card,>::new();
+    let card = |id: u8, next: Option<u8>,| Card {
+      id: CardId::from_bytes([id; 20]),
+      name: "card".to_owned(),
+      description: String::new(),
+      up_votes: 0,
+      down_votes: 0,
+      bias: 0,
+      status: CardStatus::Open,
+      history: Vec::new(),
+      previous_card: None,
+      next_card: next.map(|id,| CardId::from_bytes([id; 20]),),
+    };
+
+    block_on(async {
+      coll.write_documents([card(1, Some(2,),), card(2, None,),],).await
+        .expect("Error writing cards")
+        .expect("Error writing cards");
+
+      let mut cursor = coll.ref_cursor::<Card>(&DocumentId::from_bytes([1; 20]),).await
+        .expect("Error reading card");
+      assert!(!cursor.is_dirty(), "Error read item is dirty",);
+      cursor.get_item_mut().up_votes += 1;
+      assert!(cursor.is_dirty(), "Error changed item is not dirty",);
+      assert!(cursor.as_ref().is_dirty(), "Error referenced item is not dirty",);
+      cursor.save().await
+        .expect("Error saving card");
+      assert!(!cursor.is_dirty(), "Error saved item is dirty",);
+      assert_eq!(cursor.move_next_mut().await, Ok(true), "Error moving from a saved item",);
+      assert!(!cursor.is_dirty(), "Error next item is dirty",);
+
+      cursor.get_item_mut().up_votes += 1;
+      assert_eq!(cursor.save_and_move_next().await, Ok(false), "Error saving the tail",);
+      assert!(!cursor.is_dirty(), "Error saved item is dirty",);
+    },);
+  }
+  #[cfg(debug_assertions,)]
+  #[test]
+  #[should_panic(expected = "changed but not saved",)]
+  fn test_dirty_move() {
+    let coll = MemoryCollection::new();
+
+    block_on(async {
+      let mut cursor = write_list(&coll, &[1, 2,], 1,).await;
+      cursor.get_item_mut();
+      cursor.move_next_mut().await
+        .expect("Error moving next");
+    },);
+  }
+  #[test]
+  fn test_cursor_error() {
+    let coll = ChaosCollection::new();
+
+    block_on(async {
+      let item = *write_list(coll.inner(), &[1, 2, 3,], 2,).await.get_item();
+      let cursor = Cursor::new(&coll, item,).with_position(1,);
+
+      coll.flaky_reads(DocumentId::from_bytes([3; 20]), 1,);
+      let e = cursor.get_next().await
+        .expect_err("Error reading a flaky node");
+      assert_eq!(e, CursorError { id: DocumentId::from_bytes([2; 20]), position: Some(1), error: ChaosError::Injected(DocumentId::from_bytes([3; 20]),), }, "Error recording the Cursor",);
+
+      //Recover the walk from the error alone.
+      let cursor = coll.ref_cursor::<Doc>(&e.id,).await
+        .expect("Error reading node");
+      let cursor = match e.position { Some(position) => cursor.with_position(position,), None => cursor, };
+      let next = cursor.get_next().await
+        .expect("Error reading next")
+        .expect("Error no next");
+      assert_eq!((next.get_item().id, next.position(),), (DocumentId::from_bytes([3; 20]), Some(2),), "Error recovering the walk",);
+
+      coll.fail_reads(DocumentId::from_bytes([1; 20]),);
+      let e = cursor.ref_previous().await
+        .expect_err("Error reading a broken node");
+      assert_eq!((e.id, e.position,), (DocumentId::from_bytes([2; 20]), Some(1),), "Error recording the Cursor",);
+      assert_eq!(e.kind(), ErrorKind::Transient, "Error classifying the error",);
+    },);
+  }
+  #[test]
+  fn test_debug() {
+    use crate::mock::{LatencyCollection, RecordingTimer,};
+    use std::time::Duration;
+
+    //`LatencyCollection` is not `Debug`.
+    let coll = LatencyCollection::new(MemoryCollection::<Doc,>::new(), RecordingTimer::default(), |_: &DocumentId,| Duration::from_secs(0,),);
+    let doc = Doc { id: DocumentId::from_bytes([1; 20]), prev: None, next: None, };
+
+    block_on(async {
+      coll.write_document(&doc,).await
+        .expect("Error writing document");
+
+      let cursor = coll.ref_cursor::<Doc>(&DocumentId::from_bytes([1; 20]),).await.unwrap();
+      let debug = format!("{:?}", cursor,);
+      assert!(debug.starts_with("Cursor { collection: &galileo_tier_database::mock::LatencyCollection<",), "Error printing the collection type: {}", debug,);
+      assert!(debug.contains("item: Doc {",), "Error printing the item: {}", debug,);
+    },);
+  }
+  #[test]
+  fn test_swap() {
+    block_on(async {
+      //Swap in the middle.
+      let coll = MemoryCollection::new();
+      let mut cursor = write_list(&coll, &[1, 2, 3, 4,], 2,).await;
+      assert_eq!(cursor.swap_with_next().await, Ok(Swap::Swapped), "Error swapping middle nodes",);
+      assert_eq!(walk_list(&coll, 1,).await, [1, 3, 2, 4,], "Error swapping middle nodes",);
+      assert_eq!(cursor.get_item().id, DocumentId::from_bytes([2; 20]), "Error Cursor changed item",);
+      assert_eq!(cursor.get_item().prev, Some(DocumentId::from_bytes([3; 20])), "Error Cursor did not move",);
+      assert_eq!(cursor.swap_with_previous().await, Ok(Swap::Swapped), "Error swapping back",);
+      assert_eq!(walk_list(&coll, 1,).await, [1, 2, 3, 4,], "Error swapping back",);
+
+      //Swap the head forward and the tail back.
+      let mut tier = TierMeta::new(TierId::from_bytes([9; 20]), (NonZeroU64::new(4,), CardId::from_bytes([1; 20]), CardId::from_bytes([4; 20]),), None, None,);
+      let mut cursor = coll.ref_cursor::<Doc>(&DocumentId::from_bytes([1; 20]),).await
+        .expect("Error reading head");
+      let swap = cursor.swap_with_next().await
+        .expect("Error swapping head");
+      assert_eq!(swap, Swap::NeedsEndsUpdate { front: Some(DocumentId::from_bytes([2; 20])), back: None, }, "Error reporting new head",);
+      swap.update_ends(&mut tier,);
+      assert_eq!((*tier.list_front(), *tier.list_back(),), (CardId::from_bytes([2; 20]), CardId::from_bytes([4; 20]),), "Error updating head",);
+      assert_eq!(walk_list(&coll, 2,).await, [2, 1, 3, 4,], "Error swapping head",);
+
+      let mut cursor = coll.ref_cursor::<Doc>(&DocumentId::from_bytes([4; 20]),).await
+        .expect("Error reading tail");
+      let swap = cursor.swap_with_previous().await
+        .expect("Error swapping tail");
+      assert_eq!(swap, Swap::NeedsEndsUpdate { front: None, back: Some(DocumentId::from_bytes([3; 20])), }, "Error reporting new tail",);
+      swap.update_ends(&mut tier,);
+      assert_eq!((*tier.list_front(), *tier.list_back(),), (CardId::from_bytes([2; 20]), CardId::from_bytes([3; 20]),), "Error updating tail",);
+      assert_eq!(walk_list(&coll, 2,).await, [2, 1, 4, 3,], "Error swapping tail",);
+      let mut cursor = coll.ref_cursor::<Doc>(&DocumentId::from_bytes([3; 20]),).await
+        .expect("Error reading tail");
+      assert_eq!(cursor.swap_with_next().await, Ok(Swap::NoNeighbour), "Error swapping past the tail",);
+      assert_eq!(walk_list(&coll, 2,).await, [2, 1, 4, 3,], "Error changed list without a neighbour",);
+
+      //Swap the only two nodes.
+      let coll = MemoryCollection::new();
+      let mut cursor = write_list(&coll, &[1, 2,], 1,).await;
+      assert_eq!(
+        cursor.swap_with_next().await,
+        Ok(Swap::NeedsEndsUpdate { front: Some(DocumentId::from_bytes([2; 20])), back: Some(DocumentId::from_bytes([1; 20])), }),
+        "Error swapping both ends",
+      );
+      assert_eq!(walk_list(&coll, 2,).await, [2, 1,], "Error swapping both ends",);
+      assert_eq!(cursor.swap_with_next().await, Ok(Swap::NoNeighbour), "Error swapping past the tail",);
+    },);
+  }
+  #[test]
+  fn test_cursor() {
+    //`MemoryCollection` is a shared handle which is `Clone` but not `Copy`.
+    check_cursor(MemoryCollection::new(),);
+  }
+  #[test]
+  #[allow(clippy::arc_with_non_send_sync,)]
+  fn test_cursor_arc() {
+    //The collection is shared through an `Arc` but only used on one thread.
+    check_cursor(Arc::new(MemoryCollection::new(),),);
+  }
 }
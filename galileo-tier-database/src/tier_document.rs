@@ -0,0 +1,284 @@
+//! Defines a document type which can hold any of the documents making up a tier list so
+//! that they can be stored in one collection.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, NilIdError, Document, LinkedList, LinkedListMut, EncodedLen, Card, TierMeta,};
+use serde::{Serialize, Deserialize,};
+use std::{fmt, error::Error, convert::{TryFrom, Infallible,},};
+
+/// The kinds of document making up a tier list.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug,)]
+pub enum DocumentKind {
+  /// A `Card`.
+  Card,
+  /// A `TierMeta`.
+  TierMeta,
+}
+
+/// Any of the documents making up a tier list.
+#[derive(PartialEq, Eq, Clone, Hash, Debug, Serialize, Deserialize,)]
+pub enum TierDocument {
+  /// A `Card` document.
+  Card(Card),
+  /// A `TierMeta` document.
+  TierMeta(TierMeta),
+}
+
+impl TierDocument {
+  /// Gets the kind of this document.
+  #[inline]
+  pub fn kind(&self,) -> DocumentKind {
+    match self {
+      TierDocument::Card(_) => DocumentKind::Card,
+      TierDocument::TierMeta(_) => DocumentKind::TierMeta,
+    }
+  }
+  /// Checks that this document is not identified by and does not link to the nil
+  /// identifier.
+  #[inline]
+  pub fn validate(&self,) -> Result<(), NilIdError> {
+    match self {
+      TierDocument::Card(card) => card.validate(),
+      TierDocument::TierMeta(tier) => tier.validate(),
+    }
+  }
+}
+
+impl Document for TierDocument {
+  #[inline]
+  fn get_id(&self,) -> &DocumentId {
+    match self {
+      TierDocument::Card(card) => card.get_id(),
+      TierDocument::TierMeta(tier) => tier.get_id(),
+    }
+  }
+}
+
+impl LinkedList for TierDocument {
+  #[inline]
+  fn get_previous_id(&self,) -> Option<&DocumentId> {
+    match self {
+      TierDocument::Card(card) => card.get_previous_id(),
+      TierDocument::TierMeta(tier) => tier.get_previous_id(),
+    }
+  }
+  #[inline]
+  fn get_next_id(&self,) -> Option<&DocumentId> {
+    match self {
+      TierDocument::Card(card) => card.get_next_id(),
+      TierDocument::TierMeta(tier) => tier.get_next_id(),
+    }
+  }
+}
+
+impl LinkedListMut for TierDocument {
+  #[inline]
+  fn set_previous_id(&mut self, id: Option<DocumentId>,) {
+    match self {
+      TierDocument::Card(card) => card.set_previous_id(id,),
+      TierDocument::TierMeta(tier) => tier.set_previous_id(id,),
+    }
+  }
+  #[inline]
+  fn set_next_id(&mut self, id: Option<DocumentId>,) {
+    match self {
+      TierDocument::Card(card) => card.set_next_id(id,),
+      TierDocument::TierMeta(tier) => tier.set_next_id(id,),
+    }
+  }
+}
+
+impl EncodedLen for TierDocument {
+  #[inline]
+  fn encoded_len(&self,) -> usize {
+    //The tag and the document.
+    1 + match self {
+      TierDocument::Card(card) => card.encoded_len(),
+      TierDocument::TierMeta(tier) => tier.encoded_len(),
+    }
+  }
+}
+
+impl From<Card> for TierDocument {
+  #[inline]
+  fn from(from: Card,) -> Self { TierDocument::Card(from,) }
+}
+
+impl From<TierMeta> for TierDocument {
+  #[inline]
+  fn from(from: TierMeta,) -> Self { TierDocument::TierMeta(from,) }
+}
+
+impl TryFrom<TierDocument> for Card {
+  type Error = WrongKind;
+
+  #[inline]
+  fn try_from(from: TierDocument,) -> Result<Self, Self::Error> {
+    match from {
+      TierDocument::Card(card) => Ok(card),
+      doc => Err(WrongKind { expected: DocumentKind::Card, found: doc.kind(), }),
+    }
+  }
+}
+
+impl TryFrom<TierDocument> for TierMeta {
+  type Error = WrongKind;
+
+  #[inline]
+  fn try_from(from: TierDocument,) -> Result<Self, Self::Error> {
+    match from {
+      TierDocument::TierMeta(tier) => Ok(tier),
+      doc => Err(WrongKind { expected: DocumentKind::TierMeta, found: doc.kind(), }),
+    }
+  }
+}
+
+/// The error when converting a `TierDocument` to the wrong kind of document.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub struct WrongKind {
+  /// The kind of document requested.
+  pub expected: DocumentKind,
+  /// The kind of document found.
+  pub found: DocumentKind,
+}
+
+impl fmt::Display for WrongKind {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    write!(fmt, "expected a {:?} document but found a {:?} document", self.expected, self.found,)
+  }
+}
+
+impl Error for WrongKind {}
+
+impl From<Infallible> for WrongKind {
+  #[inline]
+  fn from(from: Infallible,) -> Self { match from {} }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{CardId, CardStatus, TierId, TierListCollection, MemoryCollection, MemoryError, ItemError, mock::{ChaosCollection, ChaosError,},};
+  use futures::executor::block_on;
+  use std::num::NonZeroU64;
+
+  fn card(id: DocumentId,) -> Card {
+    Card {
+      id: id.into(),
+      name: "Card".to_owned(),
+      description: String::new(),
+      up_votes: 0,
+      down_votes: 0,
+      bias: 0,
+      status: CardStatus::Open,
+      history: Vec::new(),
+      previous_card: None,
+      next_card: None,
+    }
+  }
+
+  #[test]
+  fn test_validate() {
+    let mut card = card(DocumentId::from_bytes([1; 20]),);
+    assert_eq!(card.validate(), Ok(()), "Error validating a card",);
+    card.next_card = Some(CardId::NIL,);
+    assert_eq!(card.validate(), Err(NilIdError::Next), "Error validating a nil next card",);
+    card.previous_card = Some(CardId::NIL,);
+    assert_eq!(card.validate(), Err(NilIdError::Previous), "Error validating a nil previous card",);
+    card.id = CardId::NIL;
+    assert_eq!(TierDocument::from(card,).validate(), Err(NilIdError::Id), "Error validating a nil card",);
+
+    let id = TierId::from_bytes([2; 20]);
+    let front = CardId::from_bytes([3; 20]);
+    //The ends of a tier without a length are never followed.
+    assert_eq!(TierMeta::new(id, (None, CardId::NIL, CardId::NIL,), None, None,).validate(), Ok(()), "Error validating an empty tier",);
+    let len = NonZeroU64::new(1,);
+    assert_eq!(TierMeta::new(id, (len, CardId::NIL, front,), None, None,).validate(), Err(NilIdError::Front), "Error validating a nil front card",);
+    assert_eq!(TierMeta::new(id, (len, front, CardId::NIL,), None, None,).validate(), Err(NilIdError::Back), "Error validating a nil back card",);
+    assert_eq!(TierMeta::new(id, (len, front, front,), Some(TierId::NIL,), None,).validate(), Err(NilIdError::Previous), "Error validating a nil previous tier",);
+    assert_eq!(TierMeta::new(id, (len, front, front,), None, Some(TierId::NIL,),).validate(), Err(NilIdError::Next), "Error validating a nil next tier",);
+    assert_eq!(TierDocument::from(TierMeta::new(TierId::NIL, (len, front, front,), None, None,),).validate(), Err(NilIdError::Id), "Error validating a nil tier",);
+  }
+  #[test]
+  fn test_mixed_collection() {
+    let coll = MemoryCollection::<TierDocument,>::new();
+    let card = card(DocumentId::from_bytes([1; 20]),);
+    let tier = TierMeta::new(TierId::from_bytes([2; 20]), (None, card.id, card.id,), None, None,);
+
+    block_on(async {
+      coll.write_documents([&TierDocument::from(card.clone(),), &tier.into(),],).await
+        .expect("Error writing documents")
+        .expect("Error writing documents");
+
+      assert_eq!(coll.get_item::<Card>(&card.id,).await, Ok(Ok(card.clone())), "Error reading card",);
+      assert_eq!(coll.get_item::<TierMeta>(&tier.id,).await, Ok(Ok(tier)), "Error reading tier",);
+      assert_eq!(
+        coll.get_item::<TierMeta>(&card.id,).await,
+        Ok(Err(WrongKind { expected: DocumentKind::TierMeta, found: DocumentKind::Card, })),
+        "Error reading card as a tier",
+      );
+      assert_eq!(
+        coll.get_item::<Card>(&tier.id,).await,
+        Ok(Err(WrongKind { expected: DocumentKind::Card, found: DocumentKind::TierMeta, })),
+        "Error reading tier as a card",
+      );
+    },);
+  }
+  #[test]
+  fn test_get_item_flat() {
+    let coll = MemoryCollection::<TierDocument,>::new();
+    let card = card(DocumentId::from_bytes([1; 20]),);
+    let missing = DocumentId::from_bytes([3; 20]);
+
+    block_on(async {
+      coll.write_document(&TierDocument::from(card.clone(),),).await
+        .expect("Error writing card");
+
+      assert_eq!(coll.get_item_flat::<Card>(&card.id,).await, Ok(card.clone()), "Error reading card",);
+      assert_eq!(
+        coll.get_item_flat::<TierMeta>(&card.id,).await,
+        Err(ItemError::Convert(WrongKind { expected: DocumentKind::TierMeta, found: DocumentKind::Card, },)),
+        "Error reading card as a tier",
+      );
+      assert_eq!(
+        coll.get_item_flat::<Card>(&missing,).await,
+        Err(ItemError::Fetch(MemoryError::NotFound(missing,),)),
+        "Error reading missing card",
+      );
+      assert_eq!(
+        coll.get_items_flat::<Card, _, _>([&missing, &card.id,],).await,
+        Ok(vec![Err(ItemError::Fetch(MemoryError::NotFound(missing,),)), Ok(card),]),
+        "Error reading cards",
+      );
+    },);
+  }
+  #[test]
+  fn test_write_items() {
+    let coll = MemoryCollection::<TierDocument,>::new();
+    let cards = (1..=3u8).map(|i,| card(DocumentId::from_bytes([i; 20]),),).collect::<Vec<_>>();
+
+    block_on(async {
+      coll.write_item(cards[0].clone(),).await
+        .expect("Error writing card");
+      coll.write_items(cards[1..].iter().cloned(),).await
+        .expect("Error writing cards")
+        .expect("Error writing cards");
+
+      for card in cards.iter() {
+        assert_eq!(coll.get_item::<Card>(&card.id,).await, Ok(Ok(card.clone())), "Error reading card",);
+      }
+      assert_eq!(coll.len(), cards.len(), "Error card count",);
+
+      //Failures are reported for each item.
+      let chaos = ChaosCollection::<TierDocument,>::new();
+      chaos.fail_writes(*cards[1].id,);
+      let results = chaos.write_items(cards.iter().cloned(),).await
+        .expect("Error writing cards")
+        .expect_err("Error writing broken card");
+      assert_eq!(results[1], Err(ChaosError::Injected(*cards[1].id,)), "Error reporting failed card",);
+      assert!(results[0].is_ok() && results[2].is_ok(), "Error reporting written cards",);
+    },);
+  }
+}
@@ -1,22 +1,33 @@
 //! Defines a representation of a tier making up a tier list in a document collection.
 //! 
 //! Author --- daniel.bechaz@gmail.com  
-//! Last Moddified --- 2019-06-02
+//! Last Moddified --- 2026-10-14
 
-use crate::{DocumentId, Document, LinkedList,};
-use std::num::NonZeroU64;
+use crate::{DocumentId, CardId, TierId, NilIdError, Document, LinkedList, LinkedListMut, EncodedLen, TierListCollection, WalkLen, walk_len,};
+use serde::{Serialize, Deserialize,};
+use std::{fmt, error::Error, str, convert::TryFrom, num::NonZeroU64,};
 
 /// Metadata for a collection of `Card`s making up a tier.
-#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug, Serialize, Deserialize,)]
 pub struct TierMeta {
   /// The Id of this `TierMeta`.
-  pub id: DocumentId,
+  pub id: TierId,
   /// The length and ends of the doubly linked list of `Card`s making up the tier.
-  ends: (Option<NonZeroU64>, DocumentId, DocumentId,),
+  ends: (Option<NonZeroU64>, CardId, CardId,),
   /// The Id of the previous tier.
-  pub previous_tier: Option<DocumentId>,
+  pub previous_tier: Option<TierId>,
   /// The Id of the next tier.
-  pub next_tier: Option<DocumentId>,
+  pub next_tier: Option<TierId>,
+  /// The net score a `Card` must exceed to be promoted to the previous tier; `None` if
+  /// no `Card` is promoted out of this tier.
+  #[serde(default,)]
+  pub promote_threshold: Option<i128>,
+  /// Which `Card`s may be moved into this tier.
+  #[serde(default,)]
+  pub policy: TierPolicy,
+  /// The name of this tier.
+  #[serde(default,)]
+  pub name: TierName,
 }
 
 impl TierMeta {
@@ -30,30 +41,193 @@ impl TierMeta {
   /// next_tier --- The Id of the next tier  
   #[inline]
   pub const fn new(
-    id: DocumentId, ends: (Option<NonZeroU64>, DocumentId, DocumentId,),
-    previous_tier: Option<DocumentId>, next_tier: Option<DocumentId>,
+    id: TierId, ends: (Option<NonZeroU64>, CardId, CardId,),
+    previous_tier: Option<TierId>, next_tier: Option<TierId>,
   ) -> Self {
-    Self { id, ends, previous_tier, next_tier, }
+    Self { id, ends, previous_tier, next_tier, promote_threshold: None, policy: TierPolicy::OPEN, name: TierName::EMPTY, }
   }
   /// Returns the length of the linked list if known.
   #[inline]
   pub fn list_len(&self,) -> Option<NonZeroU64> { self.ends.0 }
   /// Returns the Id of the document at the front of the list.
   #[inline]
-  pub const fn list_front(&self,) -> &DocumentId { &self.ends.1 }
+  pub const fn list_front(&self,) -> &CardId { &self.ends.1 }
   /// Returns the Id of the document at the back of the list.
   #[inline]
-  pub const fn list_back(&self,) -> &DocumentId { &self.ends.2 }
+  pub const fn list_back(&self,) -> &CardId { &self.ends.2 }
+  /// Sets the length and ends of the linked list.
+  /// 
+  /// # Params
+  /// 
+  /// ends --- The length and ends of the doubly linked list of `Card`s making up the tier  
+  #[inline]
+  pub fn set_ends(&mut self, ends: (Option<NonZeroU64>, CardId, CardId,),) { self.ends = ends }
+  /// Checks that this `TierMeta` is not identified by and does not link to the nil
+  /// identifier.
+  /// 
+  /// The ends may only be nil if the length is unknown.
+  pub fn validate(&self,) -> Result<(), NilIdError> {
+    if self.id.is_nil() { return Err(NilIdError::Id) }
+    if self.previous_tier.is_some_and(|id,| id.is_nil(),) { return Err(NilIdError::Previous) }
+    if self.next_tier.is_some_and(|id,| id.is_nil(),) { return Err(NilIdError::Next) }
+    if self.list_len().is_some() {
+      if self.list_front().is_nil() { return Err(NilIdError::Front) }
+      if self.list_back().is_nil() { return Err(NilIdError::Back) }
+    }
+
+    Ok(())
+  }
+  /// Counts the linked list using `walk_len` and updates the cached length.
+  /// 
+  /// The length becomes unknown if the cap was reached. A tier with a nil front is empty
+  /// and nothing is read.
+  /// 
+  /// # Params
+  /// 
+  /// collection --- The collection storing the list  
+  /// cap --- The most nodes to count  
+  pub async fn refresh_len<T, Coll,>(&mut self, collection: &Coll, cap: u64,) -> Result<WalkLen, Coll::Error>
+    where T: LinkedList,
+      Coll: TierListCollection,
+      Coll::Document: Into<T>, {
+    let len = if self.list_front().is_nil() { WalkLen::Exact(0,) }
+      else { walk_len::<T, _,>(collection, self.list_front(), cap,).await? };
+
+    self.ends.0 = len.exact().and_then(NonZeroU64::new,);
+    Ok(len)
+  }
+}
+
+/// Which `Card`s may be moved into a tier.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Default, Debug, Serialize, Deserialize,)]
+pub struct TierPolicy {
+  /// If `true` no `Card`s are moved into the tier.
+  pub frozen: bool,
+  /// The most `Card`s the tier holds; `None` if the tier has no limit.
+  pub capacity: Option<NonZeroU64>,
+}
+
+impl TierPolicy {
+  /// A policy which accepts every `Card`.
+  pub const OPEN: Self = Self { frozen: false, capacity: None, };
+
+  /// Returns `true` if a `Card` can be moved into a tier holding `len` `Card`s.
+  /// 
+  /// # Params
+  /// 
+  /// len --- The number of `Card`s in the tier.  
+  #[inline]
+  pub fn accepts(&self, len: u64,) -> bool {
+    !self.frozen && self.capacity.is_none_or(|capacity,| len < capacity.get(),)
+  }
+}
+
+/// The name of a tier.
+/// 
+/// A name is at most `TierName::MAX_LEN` bytes long and is held inline so that a
+/// `TierMeta` can be copied. Names are serialized as strings.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Default, Serialize, Deserialize,)]
+#[serde(try_from = "String", into = "String",)]
+pub struct TierName {
+  /// The number of bytes in the name.
+  len: u8,
+  /// The bytes of the name followed by zeros.
+  bytes: [u8; TierName::MAX_LEN],
+}
+
+impl TierName {
+  /// The most bytes in a name.
+  pub const MAX_LEN: usize = 32;
+  /// The empty name.
+  pub const EMPTY: Self = Self { len: 0, bytes: [0; Self::MAX_LEN], };
+
+  /// Returns the name.
+  /// 
+  /// # Params
+  /// 
+  /// name --- The name of the tier.  
+  pub fn new(name: &str,) -> Result<Self, NameTooLong> {
+    if name.len() > Self::MAX_LEN { return Err(NameTooLong(name.len(),)) }
+
+    let mut bytes = [0; Self::MAX_LEN];
+    bytes[..name.len()].copy_from_slice(name.as_bytes(),);
+    Ok(Self { len: name.len() as u8, bytes, })
+  }
+  /// Gets the name as a string.
+  #[inline]
+  pub fn as_str(&self,) -> &str {
+    //The bytes are only ever copied from a string.
+    str::from_utf8(&self.bytes[..self.len as usize],).unwrap_or_default()
+  }
+}
+
+impl<'a,> TryFrom<&'a str> for TierName {
+  type Error = NameTooLong;
+
+  #[inline]
+  fn try_from(from: &'a str,) -> Result<Self, Self::Error> { Self::new(from,) }
+}
+
+impl TryFrom<String> for TierName {
+  type Error = NameTooLong;
+
+  #[inline]
+  fn try_from(from: String,) -> Result<Self, Self::Error> { Self::new(&from,) }
+}
+
+impl From<TierName> for String {
+  #[inline]
+  fn from(from: TierName,) -> Self { from.as_str().to_owned() }
+}
+
+impl fmt::Display for TierName {
+  #[inline]
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result { fmt::Display::fmt(self.as_str(), fmt,) }
 }
 
+impl fmt::Debug for TierName {
+  #[inline]
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result { fmt::Debug::fmt(self.as_str(), fmt,) }
+}
+
+/// The error when a name is longer than `TierName::MAX_LEN`; the number of bytes in the
+/// name.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub struct NameTooLong(pub usize);
+
+impl fmt::Display for NameTooLong {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    write!(fmt, "the name is {} bytes long which is more than {}", self.0, TierName::MAX_LEN,)
+  }
+}
+
+impl Error for NameTooLong {}
+
 impl Document for TierMeta {
   #[inline]
   fn get_id(&self,) -> &DocumentId { &self.id }
 }
 
+impl EncodedLen for TierMeta {
+  fn encoded_len(&self,) -> usize {
+    //The identifiers, the length, the policy and the tags of the optional fields.
+    let fixed = 3 * 20 + 8 + 1 + 8 + 4;
+    let threshold = 16 * self.promote_threshold.is_some() as usize;
+
+    fixed + threshold + 20 * (self.previous_tier.is_some() as usize + self.next_tier.is_some() as usize) + self.name.as_str().len()
+  }
+}
+
 impl LinkedList for TierMeta {
   #[inline]
-  fn get_previous_id(&self,) -> Option<&DocumentId> { self.previous_tier.as_ref() }
+  fn get_previous_id(&self,) -> Option<&DocumentId> { self.previous_tier.as_deref() }
+  #[inline]
+  fn get_next_id(&self,) -> Option<&DocumentId> { self.next_tier.as_deref() }
+}
+
+impl LinkedListMut for TierMeta {
+  #[inline]
+  fn set_previous_id(&mut self, id: Option<DocumentId>,) { self.previous_tier = id.map(TierId::from,) }
   #[inline]
-  fn get_next_id(&self,) -> Option<&DocumentId> { self.next_tier.as_ref() }
+  fn set_next_id(&mut self, id: Option<DocumentId>,) { self.next_tier = id.map(TierId::from,) }
 }
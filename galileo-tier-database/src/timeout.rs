@@ -0,0 +1,206 @@
+//! Defines a document collection which fails operations on another collection which do
+//! not complete in time.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, CollectionError, ErrorKind, TierListCollection, Timer,};
+use futures::{Future, future::{self, Either, LocalBoxFuture,},};
+use std::{fmt, error::Error, time::Duration, borrow::Borrow,};
+
+/// The errors returned by a `TimeoutCollection`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum TimeoutError<E,> {
+  /// The operation did not complete before its deadline.
+  Timeout,
+  /// An error from the inner collection.
+  Inner(E),
+}
+
+impl<E,> fmt::Display for TimeoutError<E,>
+  where E: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      TimeoutError::Timeout => write!(fmt, "the operation timed out",),
+      TimeoutError::Inner(e) => e.fmt(fmt,),
+    }
+  }
+}
+
+impl<E,> Error for TimeoutError<E,>
+  where E: Error, {}
+
+impl<E,> CollectionError for TimeoutError<E,>
+  where E: CollectionError, {
+  #[inline]
+  fn kind(&self,) -> ErrorKind {
+    match self {
+      TimeoutError::Timeout => ErrorKind::Timeout,
+      TimeoutError::Inner(e) => e.kind(),
+    }
+  }
+  #[inline]
+  fn is_retryable(&self,) -> bool {
+    match self {
+      TimeoutError::Timeout => true,
+      TimeoutError::Inner(e) => e.is_retryable(),
+    }
+  }
+}
+
+/// A `TierListCollection` which races each operation on another collection against a
+/// deadline.
+/// 
+/// An operation which misses its deadline is dropped and fails with
+/// `TimeoutError::Timeout`.
+#[derive(Clone, Debug,)]
+pub struct TimeoutCollection<Coll, Tm,> {
+  /// The collection to perform operations on.
+  inner: Coll,
+  /// The timer used to wait for deadlines.
+  timer: Tm,
+  /// The time allowed for reads.
+  read_timeout: Duration,
+  /// The time allowed for writes.
+  write_timeout: Duration,
+}
+
+impl<Coll, Tm,> TimeoutCollection<Coll, Tm,> {
+  /// Returns a new `TimeoutCollection`.
+  /// 
+  /// # Params
+  /// 
+  /// inner --- The collection to perform operations on.  
+  /// timer --- The timer used to wait for deadlines.  
+  /// read_timeout --- The time allowed for reads.  
+  /// write_timeout --- The time allowed for writes.  
+  #[inline]
+  pub const fn new(inner: Coll, timer: Tm, read_timeout: Duration, write_timeout: Duration,) -> Self {
+    Self { inner, timer, read_timeout, write_timeout, }
+  }
+  /// Gets the collection operations are performed on.
+  #[inline]
+  pub const fn get_inner(&self,) -> &Coll { &self.inner }
+  /// Gets the time allowed for reads.
+  #[inline]
+  pub const fn read_timeout(&self,) -> Duration { self.read_timeout }
+  /// Gets the time allowed for writes.
+  #[inline]
+  pub const fn write_timeout(&self,) -> Duration { self.write_timeout }
+  /// Returns a copy of this collection which allows reads and writes the same time.
+  /// 
+  /// # Params
+  /// 
+  /// timeout --- The time allowed for each operation.  
+  pub fn with_timeout(&self, timeout: Duration,) -> Self
+    where Coll: Clone, Tm: Clone, {
+    Self::new(self.inner.clone(), self.timer.clone(), timeout, timeout,)
+  }
+}
+
+/// Races an operation against a delay, dropping the operation if the delay completes
+/// first.
+fn race<F, S, T, E,>(operation: F, delay: S,) -> LocalBoxFuture<'static, Result<T, TimeoutError<E,>>>
+  where F: 'static + Future<Output = Result<T, E>>,
+    S: 'static + Future<Output = ()>, {
+  Box::pin(async move {
+    match future::select(Box::pin(operation,), Box::pin(delay,),).await {
+      Either::Left((res, _,),) => res.map_err(TimeoutError::Inner,),
+      Either::Right(_) => Err(TimeoutError::Timeout),
+    }
+  },)
+}
+
+impl<Coll, Tm,> TierListCollection for TimeoutCollection<Coll, Tm,>
+  where Coll: TierListCollection,
+    Coll::Document: 'static,
+    Coll::Error: 'static,
+    Coll::GetBatchDocuments: 'static,
+    Coll::GetDocument: 'static,
+    Coll::WriteBatchDocuments: 'static,
+    Coll::WriteDocument: 'static,
+    Tm: Timer,
+    Tm::Sleep: 'static, {
+  type Document = Coll::Document;
+  type Error = TimeoutError<Coll::Error,>;
+  type GetBatchDocuments = LocalBoxFuture<'static, Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>>;
+  type GetDocument = LocalBoxFuture<'static, Result<Self::Document, Self::Error>>;
+  type WriteBatchDocuments = LocalBoxFuture<'static, Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>;
+  type WriteDocument = LocalBoxFuture<'static, Result<(), Self::Error>>;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    let operation = race(self.inner.get_documents(ids,), self.timer.sleep(self.read_timeout,),);
+
+    Box::pin(async move {
+      Ok(operation.await?.into_iter().map(|doc,| doc.map_err(TimeoutError::Inner,),).collect())
+    },)
+  }
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    race(self.inner.get_document(id,), self.timer.sleep(self.read_timeout,),)
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let operation = race(self.inner.write_documents(documents,), self.timer.sleep(self.write_timeout,),);
+
+    Box::pin(async move {
+      Ok(operation.await?.map_err(|results,| results.into_iter().map(|res,| res.map_err(TimeoutError::Inner,),).collect(),))
+    },)
+  }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    race(self.inner.write_document(document,), self.timer.sleep(self.write_timeout,),)
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{CardId, TierId, TierMeta, MemoryCollection, MemoryError, mock::{HungCollection, RecordingTimer,},};
+  use futures::executor::block_on;
+
+  const READ: Duration = Duration::from_secs(1,);
+  const WRITE: Duration = Duration::from_secs(2,);
+
+  #[test]
+  fn test_timeout() {
+    let inner = HungCollection::default();
+    let timer = RecordingTimer::default();
+    let coll = TimeoutCollection::new(inner.clone(), timer.clone(), READ, WRITE,);
+    let tier = TierMeta::new(TierId::from_bytes([1; 20]), (None, CardId::from_bytes([1; 20]), CardId::from_bytes([1; 20]),), None, None,);
+
+    block_on(async {
+      assert_eq!(coll.get_document(&tier.id,).await, Err(TimeoutError::Timeout), "Error timing out read",);
+      assert!(inner.dropped(), "Error dropping timed out operation",);
+      assert_eq!(coll.get_documents([&*tier.id,],).await, Err(TimeoutError::Timeout), "Error timing out batch read",);
+      assert_eq!(coll.write_document(&tier,).await, Err(TimeoutError::Timeout), "Error timing out write",);
+      assert_eq!(coll.write_documents([&tier,],).await, Err(TimeoutError::Timeout), "Error timing out batch write",);
+      assert_eq!(timer.sleeps(), vec![READ, READ, WRITE, WRITE,], "Error using the timeouts",);
+
+      let quick = Duration::from_millis(10,);
+      assert_eq!(coll.with_timeout(quick,).get_document(&tier.id,).await, Err(TimeoutError::Timeout), "Error timing out read",);
+      assert_eq!(timer.sleeps().last(), Some(&quick,), "Error overriding the timeout",);
+    },);
+
+    assert!(TimeoutError::<MemoryError,>::Timeout.is_retryable(), "Error timeouts are not retryable",);
+  }
+  #[test]
+  fn test_in_time() {
+    let inner = MemoryCollection::new();
+    let coll = TimeoutCollection::new(inner.clone(), RecordingTimer::default(), READ, WRITE,);
+    let tier = TierMeta::new(TierId::from_bytes([1; 20]), (None, CardId::from_bytes([1; 20]), CardId::from_bytes([1; 20]),), None, None,);
+
+    block_on(async {
+      coll.write_document(&tier,).await
+        .expect("Error writing tier");
+      assert_eq!(coll.get_document(&tier.id,).await, Ok(tier), "Error reading tier",);
+      assert_eq!(
+        coll.get_documents([&tier.id, &DocumentId::from_bytes([2; 20]),],).await,
+        Ok(vec![Ok(tier), Err(TimeoutError::Inner(MemoryError::NotFound(DocumentId::from_bytes([2; 20]),),)),]),
+        "Error reading tiers",
+      );
+    },);
+  }
+}
@@ -0,0 +1,56 @@
+//! Defines sources of delays and of the current time so that collections can wait and
+//! tell the time without depending on a specific async runtime.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use futures::Future;
+use std::time::{Duration, SystemTime,};
+
+/// A source of delays.
+/// 
+/// Any `Fn(Duration) -> impl Future<Output = ()>` is a `Timer`, so the sleep function of
+/// a runtime can be used directly.
+pub trait Timer {
+  /// The future type when waiting for a delay.
+  type Sleep: Future<Output = ()>;
+
+  /// Waits for a delay.
+  /// 
+  /// # Params
+  /// 
+  /// duration --- The length of the delay.  
+  fn sleep(&self, duration: Duration,) -> Self::Sleep;
+}
+
+impl<F, Fut,> Timer for F
+  where F: Fn(Duration,) -> Fut,
+    Fut: Future<Output = ()>, {
+  type Sleep = Fut;
+
+  #[inline]
+  fn sleep(&self, duration: Duration,) -> Self::Sleep { self(duration,) }
+}
+
+/// A source of the current time.
+/// 
+/// Any `Fn() -> SystemTime` is a `Clock`, so tests can control the time.
+pub trait Clock {
+  /// Gets the current time.
+  fn now(&self,) -> SystemTime;
+}
+
+impl<F,> Clock for F
+  where F: Fn() -> SystemTime, {
+  #[inline]
+  fn now(&self,) -> SystemTime { self() }
+}
+
+/// A `Clock` which reads the system time.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug,)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+  #[inline]
+  fn now(&self,) -> SystemTime { SystemTime::now() }
+}
@@ -0,0 +1,255 @@
+//! Defines a document collection which emits a `tracing` span for each operation on
+//! another collection.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, TierListCollection,};
+use futures::{Future, future::LocalBoxFuture,};
+use tracing::{Span, Instrument, field,};
+use std::{time::Instant, borrow::Borrow,};
+
+/// A `TierListCollection` which opens a span for each operation on another collection.
+/// 
+/// Spans are named `galileo.get_document`, `galileo.get_documents`,
+/// `galileo.write_document` and `galileo.write_documents` and record the hex identifier
+/// of the document (or the size of the batch), the `outcome` of the operation and the
+/// `elapsed_us` it took.
+/// 
+/// Spans are opened when the operation is started so they are children of the span the
+/// caller is in, and the returned future runs inside its span so any operations the
+/// inner collection starts are children of it in turn.
+#[derive(Clone, Debug,)]
+pub struct TracedCollection<Coll,> {
+  /// The collection to trace operations on.
+  inner: Coll,
+}
+
+impl<Coll,> TracedCollection<Coll,> {
+  /// Returns a new `TracedCollection`.
+  /// 
+  /// # Params
+  /// 
+  /// inner --- The collection to trace operations on.  
+  #[inline]
+  pub const fn new(inner: Coll,) -> Self { Self { inner, } }
+  /// Gets the collection operations are traced on.
+  #[inline]
+  pub const fn get_inner(&self,) -> &Coll { &self.inner }
+}
+
+/// Runs an operation inside a span, recording its outcome and elapsed time when it
+/// completes.
+/// 
+/// # Params
+/// 
+/// span --- The span of the operation.  
+/// operation --- Starts the operation.  
+/// outcome --- Describes the result of the operation.  
+fn traced<F, T,>(span: Span, operation: impl FnOnce() -> F, outcome: fn(&T,) -> &'static str,) -> LocalBoxFuture<'static, T>
+  where F: 'static + Future<Output = T>,
+    T: 'static, {
+  let start = Instant::now();
+  let operation = span.in_scope(operation,);
+  let record = span.clone();
+
+  Box::pin(async move {
+    let res = operation.await;
+
+    record.record("outcome", outcome(&res,),);
+    record.record("elapsed_us", start.elapsed().as_micros() as u64,);
+    res
+  }.instrument(span,),)
+}
+
+/// Describes the result of a single operation.
+fn outcome<T, E,>(res: &Result<T, E>,) -> &'static str {
+  if res.is_ok() { "ok" } else { "error" }
+}
+
+/// Describes the result of a batch read.
+fn batch_outcome<T, E,>(res: &Result<Vec<Result<T, E>>, E>,) -> &'static str {
+  match res {
+    Ok(docs) if docs.iter().all(Result::is_ok,) => "ok",
+    Ok(_) => "partial",
+    Err(_) => "error",
+  }
+}
+
+/// Describes the result of a batch write.
+fn batch_write_outcome<E,>(res: &Result<Result<(), Vec<Result<(), E>>>, E>,) -> &'static str {
+  match res {
+    Ok(Ok(())) => "ok",
+    Ok(Err(_)) => "partial",
+    Err(_) => "error",
+  }
+}
+
+impl<Coll,> TierListCollection for TracedCollection<Coll,>
+  where Coll: TierListCollection,
+    Coll::Document: 'static,
+    Coll::Error: 'static,
+    Coll::GetBatchDocuments: 'static,
+    Coll::GetDocument: 'static,
+    Coll::WriteBatchDocuments: 'static,
+    Coll::WriteDocument: 'static, {
+  type Document = Coll::Document;
+  type Error = Coll::Error;
+  type GetBatchDocuments = LocalBoxFuture<'static, Result<Vec<Result<Self::Document, Self::Error>>, Self::Error>>;
+  type GetDocument = LocalBoxFuture<'static, Result<Self::Document, Self::Error>>;
+  type WriteBatchDocuments = LocalBoxFuture<'static, Result<Result<(), Vec<Result<(), Self::Error>>>, Self::Error>>;
+  type WriteDocument = LocalBoxFuture<'static, Result<(), Self::Error>>;
+
+  fn get_documents<I, B,>(&self, ids: I,) -> Self::GetBatchDocuments
+    where I: IntoIterator<Item = B>,
+      B: Borrow<DocumentId>, {
+    let ids = ids.into_iter().map(|id,| *id.borrow(),).collect::<Vec<_>>();
+    let span = tracing::info_span!("galileo.get_documents", len = ids.len(), outcome = field::Empty, elapsed_us = field::Empty,);
+
+    traced(span, || self.inner.get_documents(&ids,), batch_outcome,)
+  }
+  fn get_document(&self, id: &DocumentId,) -> Self::GetDocument {
+    let span = tracing::info_span!("galileo.get_document", id = id.to_string().as_str(), outcome = field::Empty, elapsed_us = field::Empty,);
+
+    traced(span, || self.inner.get_document(id,), outcome,)
+  }
+  fn write_documents<I, T,>(&self, documents: I,) -> Self::WriteBatchDocuments
+    where I: IntoIterator<Item = T>,
+      T: Borrow<Self::Document>, {
+    let documents = documents.into_iter().collect::<Vec<_>>();
+    let span = tracing::info_span!("galileo.write_documents", len = documents.len(), outcome = field::Empty, elapsed_us = field::Empty,);
+
+    traced(span, || self.inner.write_documents(documents,), batch_write_outcome,)
+  }
+  fn write_document<T,>(&self, document: &T,) -> Self::WriteDocument
+    where T: Borrow<Self::Document>, {
+    use crate::Document;
+
+    let id = document.borrow().get_id().to_string();
+    let span = tracing::info_span!("galileo.write_document", id = id.as_str(), outcome = field::Empty, elapsed_us = field::Empty,);
+
+    traced(span, || self.inner.write_document(document,), outcome,)
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{TierMeta, MemoryCollection,};
+  use futures::executor::block_on;
+  use tracing::{
+    Subscriber, Event, Metadata,
+    span::{Attributes, Record, Id,},
+    field::{Field, Visit,},
+  };
+  use std::{
+    fmt,
+    collections::HashMap,
+    sync::{Arc, Mutex,},
+  };
+
+  /// A span seen by the `Recorder`.
+  #[derive(Debug,)]
+  struct SpanRecord {
+    /// The name of the span.
+    name: &'static str,
+    /// The index of the parent span.
+    parent: Option<usize>,
+    /// The fields recorded on the span.
+    fields: HashMap<&'static str, String>,
+  }
+
+  impl Visit for SpanRecord {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug,) {
+      self.fields.insert(field.name(), format!("{:?}", value,),);
+    }
+    fn record_str(&mut self, field: &Field, value: &str,) {
+      self.fields.insert(field.name(), value.to_owned(),);
+    }
+  }
+
+  /// The state of a `Recorder`.
+  #[derive(Default, Debug,)]
+  struct Recorded {
+    /// Every span opened in order.
+    spans: Vec<SpanRecord>,
+    /// The indices of the entered spans.
+    entered: Vec<usize>,
+  }
+
+  /// A `Subscriber` which records every span opened.
+  #[derive(Clone, Default, Debug,)]
+  struct Recorder(Arc<Mutex<Recorded>>,);
+
+  impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata,) -> bool { true }
+    fn new_span(&self, attrs: &Attributes,) -> Id {
+      let mut recorded = self.0.lock().unwrap();
+      let parent = if attrs.is_contextual() { recorded.entered.last().copied() }
+        else { attrs.parent().map(|id,| id.into_u64() as usize - 1,) };
+      let mut span = SpanRecord { name: attrs.metadata().name(), parent, fields: HashMap::new(), };
+
+      attrs.record(&mut span,);
+      recorded.spans.push(span,);
+      Id::from_u64(recorded.spans.len() as u64,)
+    }
+    fn record(&self, id: &Id, values: &Record,) {
+      let mut recorded = self.0.lock().unwrap();
+
+      values.record(&mut recorded.spans[id.into_u64() as usize - 1],);
+    }
+    fn record_follows_from(&self, _: &Id, _: &Id,) {}
+    fn event(&self, _: &Event,) {}
+    fn enter(&self, id: &Id,) {
+      self.0.lock().unwrap().entered.push(id.into_u64() as usize - 1,);
+    }
+    fn exit(&self, _: &Id,) {
+      self.0.lock().unwrap().entered.pop();
+    }
+  }
+
+  #[test]
+  fn test_cursor_walk() {
+    let recorder = Recorder::default();
+    let ids = (0..3u8).map(|i,| DocumentId::from_bytes([i; 20]),).collect::<Vec<_>>();
+    let tiers = ids.iter().enumerate().map(|(i, &id,),| TierMeta::new(
+      id.into(), (None, id.into(), id.into(),),
+      ids.get(i.wrapping_sub(1,),).map(|&id,| id.into(),), ids.get(i + 1,).map(|&id,| id.into(),),
+    ),).collect::<Vec<_>>();
+    let coll = TracedCollection::new(MemoryCollection::<TierMeta,>::new(),);
+
+    tracing::subscriber::with_default(recorder.clone(), || block_on(async {
+      coll.write_documents(&tiers,).await
+        .expect("Error writing tiers")
+        .expect("Error writing tiers");
+      coll.get_document(&DocumentId::from_bytes([9; 20]),).await
+        .expect_err("Error reading missing tier");
+
+      async {
+        let mut cursor = coll.ref_cursor::<TierMeta>(&ids[0],).await
+          .expect("Error reading front");
+        while let Some(next) = cursor.get_next().await.expect("Error reading next") { cursor = next }
+        assert_eq!(cursor.get_item(), &tiers[2], "Error walking the list",);
+      }.instrument(tracing::info_span!("walk",),).await;
+    },),);
+
+    let recorded = recorder.0.lock().unwrap();
+    let spans = &recorded.spans;
+    assert_eq!(
+      spans.iter().map(|span,| span.name,).collect::<Vec<_>>(),
+      vec!["galileo.write_documents", "galileo.get_document", "walk", "galileo.get_document", "galileo.get_document", "galileo.get_document",],
+      "Error emitting spans",
+    );
+    assert_eq!(spans[0].fields.get("len",).map(String::as_str,), Some("3",), "Error recording batch size",);
+    assert_eq!(spans[0].fields.get("outcome",).map(String::as_str,), Some("ok",), "Error recording batch outcome",);
+    assert_eq!(spans[1].fields.get("outcome",).map(String::as_str,), Some("error",), "Error recording failure",);
+    assert_eq!(spans[1].parent, None, "Error parenting top level span",);
+    for (span, id,) in spans[3..].iter().zip(&ids,) {
+      assert_eq!(span.parent, Some(2,), "Error parenting cursor walk",);
+      assert_eq!(span.fields.get("id",), Some(&id.to_string(),), "Error recording id",);
+      assert_eq!(span.fields.get("outcome",).map(String::as_str,), Some("ok",), "Error recording outcome",);
+      assert!(span.fields.contains_key("elapsed_us",), "Error recording elapsed time",);
+    }
+    assert_eq!(DocumentId::from_bytes([0xab; 20]).to_string(), "ab".repeat(20,), "Error formatting id",);
+  }
+}
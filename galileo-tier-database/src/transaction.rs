@@ -0,0 +1,80 @@
+//! Defines operations for applying several changes to a document collection atomically.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, Document, TierListCollection,};
+use futures::Future;
+use std::{borrow::Borrow, rc::Rc, sync::Arc,};
+
+/// A collection which can apply several changes atomically.
+pub trait TransactionalCollection: TierListCollection {
+  /// The transaction type used to stage changes to the collection.
+  type Transaction: Transaction<Document = Self::Document, Error = Self::Error>;
+
+  /// Starts a new transaction against the collection.
+  fn begin(&self,) -> Self::Transaction;
+}
+
+/// A set of changes staged to be applied to a collection together.
+/// 
+/// Nothing is applied to the collection until the `Transaction` is committed and
+/// dropping a `Transaction` without committing it discards the staged changes.
+pub trait Transaction {
+  /// The document type stored in the collection.
+  type Document: Document;
+  /// The error type returned by DB operations.
+  type Error;
+  /// The future type when committing the transaction.
+  type Commit: Future<Output = Result<(), Self::Error>>;
+
+  /// Stages a document to be written to the collection.
+  /// 
+  /// # Params
+  /// 
+  /// document --- The document to write to the collection.  
+  fn stage_write<T,>(&mut self, document: &T,)
+    where T: Borrow<Self::Document>;
+  /// Stages a document to be deleted from the collection.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The identifier of the document in the collection.  
+  fn stage_delete(&mut self, id: &DocumentId,);
+  /// Applies all of the staged changes to the collection.
+  /// 
+  /// Either every staged change is applied or none of them are.
+  fn commit(self,) -> Self::Commit;
+}
+
+impl<Coll,> TransactionalCollection for &Coll
+  where Coll: TransactionalCollection, {
+  type Transaction = Coll::Transaction;
+
+  #[inline]
+  fn begin(&self,) -> Self::Transaction { Coll::begin(*self,) }
+}
+
+impl<Coll,> TransactionalCollection for Rc<Coll,>
+  where Coll: TransactionalCollection, {
+  type Transaction = Coll::Transaction;
+
+  #[inline]
+  fn begin(&self,) -> Self::Transaction { Coll::begin(&**self,) }
+}
+
+impl<Coll,> TransactionalCollection for Arc<Coll,>
+  where Coll: TransactionalCollection, {
+  type Transaction = Coll::Transaction;
+
+  #[inline]
+  fn begin(&self,) -> Self::Transaction { Coll::begin(&**self,) }
+}
+
+impl<Coll,> TransactionalCollection for Box<Coll,>
+  where Coll: TransactionalCollection, {
+  type Transaction = Coll::Transaction;
+
+  #[inline]
+  fn begin(&self,) -> Self::Transaction { Coll::begin(&**self,) }
+}
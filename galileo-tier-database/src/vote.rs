@@ -0,0 +1,425 @@
+//! Defines casting votes on cards.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{
+  DocumentId, CardId, Document, Card, CardStatus, ScoreKind, WrongKind, ItemError,
+  CollectionError, ErrorKind, Counters, CounterField, AtomicCounters, RevisionedCollection, TierListCollection,
+  counters::add_delta,
+};
+use futures::Future;
+use serde::{Serialize, Deserialize,};
+use std::{fmt, error::Error, convert::TryInto,};
+
+/// The number of times a vote or a card is read and written before giving up on racing
+/// with other votes.
+const ATTEMPTS: usize = 16;
+
+/// The direction of a vote on a card.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug, Serialize, Deserialize,)]
+pub enum VoteDirection {
+  /// An up vote.
+  Up,
+  /// A down vote.
+  Down,
+}
+
+impl VoteDirection {
+  /// Gets the opposite direction.
+  #[inline]
+  pub const fn reverse(self,) -> Self {
+    match self {
+      VoteDirection::Up => VoteDirection::Down,
+      VoteDirection::Down => VoteDirection::Up,
+    }
+  }
+  /// Gets the counter of a `Card` which counts the votes in this direction.
+  #[inline]
+  pub const fn field(self,) -> CounterField {
+    match self {
+      VoteDirection::Up => CounterField::UpVotes,
+      VoteDirection::Down => CounterField::DownVotes,
+    }
+  }
+}
+
+/// The vote of one voter on a card.
+#[derive(PartialEq, Eq, Clone, Hash, Debug, Serialize, Deserialize,)]
+pub struct Vote {
+  /// The identifier of this `Vote`, derived from the card and the voter.
+  pub id: DocumentId,
+  /// The card voted on.
+  pub card: CardId,
+  /// The voter.
+  pub voter: String,
+  /// The direction of the vote.
+  pub direction: VoteDirection,
+}
+
+impl Vote {
+  /// Returns a new `Vote`.
+  /// 
+  /// # Params
+  /// 
+  /// card --- The card voted on.  
+  /// voter --- The voter.  
+  /// direction --- The direction of the vote.  
+  pub fn new(card: CardId, voter: &str, direction: VoteDirection,) -> Self {
+    Self { id: Self::id_for(&card, voter,), card, voter: voter.to_owned(), direction, }
+  }
+  /// Gets the identifier of the vote of a voter on a card.
+  /// 
+  /// # Params
+  /// 
+  /// card --- The card voted on.  
+  /// voter --- The voter.  
+  #[inline]
+  pub fn id_for(card: &CardId, voter: &str,) -> DocumentId {
+    DocumentId::digest(&[b"vote", card.as_bytes(), voter.as_bytes(),],)
+  }
+}
+
+impl Document for Vote {
+  #[inline]
+  fn get_id(&self,) -> &DocumentId { &self.id }
+}
+
+/// How casting a vote changed the vote of the voter.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug,)]
+pub enum VoteChange {
+  /// The voter had not voted on the card.
+  New,
+  /// The voter had already voted in the same direction; nothing changed.
+  Repeated,
+  /// The voter had voted in the other direction.
+  Flipped,
+}
+
+/// The result of casting a vote, returned by `cast_vote`.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug,)]
+pub struct VoteOutcome {
+  /// The net score of the card after the vote.
+  pub score: i128,
+  /// How the vote of the voter changed.
+  pub change: VoteChange,
+}
+
+/// The errors when casting a vote.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum VoteError<E,> {
+  /// Getting the card failed.
+  Fetch(E),
+  /// The identifier is not of a card; nothing was written.
+  WrongKind(WrongKind),
+  /// The card with the identifier is locked; nothing was written.
+  Locked(CardId),
+  /// The card with the identifier is archived; nothing was written.
+  Archived(CardId),
+  /// Reading or writing the vote failed; the counters of the card were restored.
+  Vote(E),
+  /// Updating the counters of the card failed; the vote was not recorded.
+  Count(E),
+  /// Restoring the counters of the card failed after writing the vote failed; the card
+  /// counts a vote which was not recorded.
+  Restore(E),
+}
+
+impl<E, C,> From<ItemError<E, C,>> for VoteError<E,>
+  where C: Into<WrongKind>, {
+  #[inline]
+  fn from(from: ItemError<E, C,>,) -> Self {
+    match from {
+      ItemError::Fetch(e) => VoteError::Fetch(e,),
+      ItemError::Convert(e) => VoteError::WrongKind(e.into(),),
+    }
+  }
+}
+
+impl<E,> fmt::Display for VoteError<E,>
+  where E: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      VoteError::Fetch(e) => write!(fmt, "failed to get the card: {}", e,),
+      VoteError::WrongKind(e) => e.fmt(fmt,),
+      VoteError::Locked(id) => write!(fmt, "the card with the id {:?} is locked", id,),
+      VoteError::Archived(id) => write!(fmt, "the card with the id {:?} is archived", id,),
+      VoteError::Vote(e) => write!(fmt, "failed to record the vote: {}", e,),
+      VoteError::Count(e) => write!(fmt, "failed to count the vote: {}", e,),
+      VoteError::Restore(e) => write!(fmt, "failed to take back the unrecorded vote: {}", e,),
+    }
+  }
+}
+
+impl<E,> Error for VoteError<E,>
+  where E: Error, {}
+
+/// Casts the vote of a voter on a card and returns the new score of the card.
+/// 
+/// The vote of each voter on each card is stored in `votes` with an identifier derived
+/// from both and is only written if it has not changed since it was read, so concurrent
+/// votes by one voter are counted once. Repeating a vote writes nothing and flipping a
+/// vote moves it from one counter of the card to the other. The counters are updated by
+/// reading and writing back the card, starting again if it changes in between;
+/// collections with atomic counters should use `cast_vote_atomic`.
+/// 
+/// Locked and archived cards are rejected before anything is written. The vote is
+/// counted before it is written so if updating the card fails nothing is recorded and
+/// the vote can be cast again; if writing the vote fails the counters are restored.
+/// The cards may be stored alone or among the tiers of a `TierDocument` collection.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the card.  
+/// votes --- The collection storing the votes.  
+/// card_id --- The identifier of the card to vote on.  
+/// voter --- The voter.  
+/// direction --- The direction of the vote.  
+pub async fn cast_vote<Coll, Vt,>(collection: &Coll, votes: &Vt, card_id: &CardId, voter: &str, direction: VoteDirection,) -> Result<VoteOutcome, VoteError<Coll::Error,>>
+  where Coll: RevisionedCollection,
+    Coll::Document: Counters + TryInto<Card>,
+    <Coll::Document as TryInto<Card>>::Error: Into<WrongKind>,
+    Vt: RevisionedCollection<Document = Vote, Error = Coll::Error>, {
+  let card = open_card(collection, card_id,).await?;
+  let (change, counted,) = record_vote(votes, &Vote::new(*card_id, voter, direction,), move |deltas,| {
+    collection.update_document(card_id, ATTEMPTS, move |mut document,| {
+      for &(field, delta,) in &deltas {
+        if let Some(counter) = document.counter_mut(field,) { *counter = add_delta(*counter, delta,) }
+      }
+
+      document
+    },)
+  },).await?;
+  let card: Card = match counted {
+    Some(document) => document.try_into().map_err(|e,| VoteError::WrongKind(Into::into(e,),),)?,
+    None => card,
+  };
+
+  Ok(VoteOutcome { score: card.score(ScoreKind::Net,), change, })
+}
+
+/// Like `cast_vote` but the counters of the card are incremented atomically and the
+/// card is read again for its score.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the card.  
+/// votes --- The collection storing the votes.  
+/// card_id --- The identifier of the card to vote on.  
+/// voter --- The voter.  
+/// direction --- The direction of the vote.  
+pub async fn cast_vote_atomic<Coll, Vt,>(collection: &Coll, votes: &Vt, card_id: &CardId, voter: &str, direction: VoteDirection,) -> Result<VoteOutcome, VoteError<Coll::Error,>>
+  where Coll: AtomicCounters,
+    Coll::Document: TryInto<Card>,
+    <Coll::Document as TryInto<Card>>::Error: Into<WrongKind>,
+    Vt: RevisionedCollection<Document = Vote, Error = Coll::Error>, {
+  let card = open_card(collection, card_id,).await?;
+  let (change, counted,) = record_vote(votes, &Vote::new(*card_id, voter, direction,), move |deltas,| async move {
+    for (field, delta,) in deltas {
+      collection.increment_field(card_id, field, delta,).await?;
+    }
+
+    Ok(())
+  },).await?;
+  let card = match counted {
+    Some(()) => collection.get_item_flat::<Card>(card_id,).await?,
+    None => card,
+  };
+
+  Ok(VoteOutcome { score: card.score(ScoreKind::Net,), change, })
+}
+
+/// Reads a card and checks that it can be voted on.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the card.  
+/// card_id --- The identifier of the card.  
+async fn open_card<Coll,>(collection: &Coll, card_id: &CardId,) -> Result<Card, VoteError<Coll::Error,>>
+  where Coll: TierListCollection,
+    Coll::Document: TryInto<Card>,
+    <Coll::Document as TryInto<Card>>::Error: Into<WrongKind>, {
+  let card = collection.get_item_flat::<Card>(card_id,).await?;
+
+  match card.status {
+    CardStatus::Open => Ok(card),
+    CardStatus::Locked => Err(VoteError::Locked(*card_id,)),
+    CardStatus::Archived => Err(VoteError::Archived(*card_id,)),
+  }
+}
+
+/// Counts a vote and then writes it unless it is already stored, and returns how the
+/// vote changed with the result of the last count.
+/// 
+/// If writing the vote fails the count is taken back, starting again if the stored
+/// vote changed between being read and written.
+/// 
+/// # Params
+/// 
+/// votes --- The collection storing the votes.  
+/// vote --- The vote to write.  
+/// count --- Changes the counters of the card voted on.  
+async fn record_vote<Vt, T, F, Fut,>(votes: &Vt, vote: &Vote, mut count: F,) -> Result<(VoteChange, Option<T>,), VoteError<Vt::Error,>>
+  where Vt: RevisionedCollection<Document = Vote>,
+    F: FnMut(Vec<(CounterField, i64,)>,) -> Fut,
+    Fut: Future<Output = Result<T, Vt::Error>>, {
+  let mut attempt = 1;
+  let mut counted = None;
+
+  loop {
+    let (change, revision,) = match votes.get_document_rev(&vote.id,).await {
+      Ok((stored, _,)) if stored.direction == vote.direction => return Ok((VoteChange::Repeated, counted,)),
+      Ok((_, revision,)) => (VoteChange::Flipped, Some(revision),),
+      Err(e) if e.is_not_found() => (VoteChange::New, None,),
+      Err(e) => return Err(VoteError::Vote(e,)),
+    };
+    let deltas = deltas(vote.direction, change,);
+
+    counted = Some(count(deltas.clone(),).await.map_err(VoteError::Count,)?,);
+    let e = match votes.write_document_rev(vote, revision.as_ref(),).await {
+      Ok(_) => return Ok((change, counted,)),
+      Err(e) => e,
+    };
+
+    let undo = deltas.into_iter().map(|(field, delta,),| (field, -delta,),).collect();
+    counted = Some(count(undo,).await.map_err(VoteError::Restore,)?,);
+    if e.kind() != ErrorKind::Conflict || attempt == ATTEMPTS { return Err(VoteError::Vote(e,)) }
+    attempt += 1;
+  }
+}
+
+/// Gets the changes to the counters of a card made by a vote.
+/// 
+/// # Params
+/// 
+/// direction --- The direction of the vote.  
+/// change --- How the vote changed.  
+fn deltas(direction: VoteDirection, change: VoteChange,) -> Vec<(CounterField, i64,)> {
+  match change {
+    VoteChange::New => vec![(direction.field(), 1,),],
+    VoteChange::Repeated => Vec::new(),
+    VoteChange::Flipped => vec![(direction.field(), 1,), (direction.reverse().field(), -1,),],
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{TierId, TierMeta, TierDocument, MemoryCollection, mock::{YieldingCollection, ChaosCollection, ChaosError,},};
+  use futures::{executor::block_on, future,};
+
+  fn card(id: u8, status: CardStatus,) -> TierDocument {
+    TierDocument::Card(Card {
+      id: CardId::from_bytes([id; 20]),
+      name: "card".to_owned(),
+      description: String::new(),
+      up_votes: 0,
+      down_votes: 0,
+      bias: 1,
+      status,
+      history: Vec::new(),
+      previous_card: None,
+      next_card: None,
+    },)
+  }
+  fn seeded() -> (MemoryCollection<TierDocument,>, MemoryCollection<Vote,>,) {
+    let coll = MemoryCollection::new();
+    let tier = TierMeta::new(TierId::from_bytes([9; 20]), (None, CardId::NIL, CardId::NIL,), None, None,);
+
+    block_on(coll.write_documents(&[card(1, CardStatus::Open,), card(2, CardStatus::Locked,), card(3, CardStatus::Archived,), tier.into(),],),)
+      .expect("Error writing cards")
+      .expect("Error writing cards");
+
+    (coll, MemoryCollection::new(),)
+  }
+  fn counts(coll: &MemoryCollection<TierDocument,>, id: u8,) -> (u64, u64,) {
+    let card = block_on(coll.get_item_flat::<Card>(&DocumentId::from_bytes([id; 20]),),).expect("Error reading card");
+
+    (card.up_votes, card.down_votes,)
+  }
+
+  #[test]
+  fn test_cast_vote() {
+    let (coll, votes,) = seeded();
+    let id = CardId::from_bytes([1; 20]);
+    let outcome = |score, change,| Ok(VoteOutcome { score, change, });
+
+    block_on(async {
+      assert_eq!(cast_vote(&coll, &votes, &id, "ada", VoteDirection::Up,).await, outcome(0, VoteChange::New,), "Error casting a new vote",);
+      assert_eq!(cast_vote(&coll, &votes, &id, "ada", VoteDirection::Up,).await, outcome(0, VoteChange::Repeated,), "Error repeating a vote",);
+      assert_eq!(votes.len(), 1, "Error storing the vote",);
+      assert_eq!(cast_vote(&coll, &votes, &id, "ada", VoteDirection::Down,).await, outcome(-2, VoteChange::Flipped,), "Error flipping a vote",);
+      assert_eq!(cast_vote(&coll, &votes, &id, "bob", VoteDirection::Up,).await, outcome(-1, VoteChange::New,), "Error casting a second vote",);
+      assert_eq!(votes.get_document(&Vote::id_for(&id, "ada",),).await.map(|vote,| vote.direction,), Ok(VoteDirection::Down), "Error updating the vote",);
+    },);
+    assert_eq!(counts(&coll, 1,), (1, 1,), "Error counting the votes",);
+
+    block_on(async {
+      assert_eq!(cast_vote(&coll, &votes, &CardId::from_bytes([2; 20]), "ada", VoteDirection::Up,).await, Err(VoteError::Locked(CardId::from_bytes([2; 20]),)), "Error voting on a locked card",);
+      assert_eq!(cast_vote(&coll, &votes, &CardId::from_bytes([3; 20]), "ada", VoteDirection::Up,).await, Err(VoteError::Archived(CardId::from_bytes([3; 20]),)), "Error voting on an archived card",);
+      assert!(matches!(cast_vote(&coll, &votes, &CardId::from_bytes([9; 20]), "ada", VoteDirection::Up,).await, Err(VoteError::WrongKind(_,))), "Error voting on a tier",);
+    },);
+    assert_eq!(votes.len(), 2, "Error recording a rejected vote",);
+  }
+  #[test]
+  fn test_cast_vote_atomic() {
+    let (coll, votes,) = seeded();
+    let id = CardId::from_bytes([1; 20]);
+    let outcome = |score, change,| Ok(VoteOutcome { score, change, });
+
+    block_on(async {
+      assert_eq!(cast_vote_atomic(&coll, &votes, &id, "ada", VoteDirection::Down,).await, outcome(-2, VoteChange::New,), "Error casting a new vote",);
+      assert_eq!(cast_vote_atomic(&coll, &votes, &id, "ada", VoteDirection::Down,).await, outcome(-2, VoteChange::Repeated,), "Error repeating a vote",);
+      assert_eq!(cast_vote_atomic(&coll, &votes, &id, "ada", VoteDirection::Up,).await, outcome(0, VoteChange::Flipped,), "Error flipping a vote",);
+      assert_eq!(cast_vote_atomic(&coll, &votes, &CardId::from_bytes([2; 20]), "ada", VoteDirection::Up,).await, Err(VoteError::Locked(CardId::from_bytes([2; 20]),)), "Error voting on a locked card",);
+    },);
+    assert_eq!(counts(&coll, 1,), (1, 0,), "Error counting the votes",);
+  }
+  #[test]
+  fn test_vote_failures() {
+    let (coll, votes,) = (ChaosCollection::new(), ChaosCollection::new(),);
+    let id = CardId::from_bytes([1; 20]);
+
+    block_on(coll.write_document(&card(1, CardStatus::Open,),),).expect("Error writing card");
+    block_on(async {
+      //A failed count records nothing so the vote can be cast again.
+      coll.flaky_writes(*id, 1,);
+      assert_eq!(cast_vote(&coll, &votes, &id, "ada", VoteDirection::Up,).await, Err(VoteError::Count(ChaosError::Injected(*id,),)), "Error failing the count",);
+      assert_eq!(votes.inner().len(), 0, "Error recording an uncounted vote",);
+      assert_eq!(cast_vote(&coll, &votes, &id, "ada", VoteDirection::Up,).await.map(|outcome,| outcome.change,), Ok(VoteChange::New), "Error retrying the vote",);
+
+      //A failed write of the vote takes back the count.
+      let ballot = Vote::id_for(&id, "bob",);
+      votes.flaky_writes(ballot, 1,);
+      assert_eq!(cast_vote(&coll, &votes, &id, "bob", VoteDirection::Up,).await, Err(VoteError::Vote(ChaosError::Injected(ballot,),)), "Error failing the vote",);
+      assert_eq!(coll.get_item_flat::<Card>(&id,).await.map(|card,| (card.up_votes, card.down_votes,),), Ok((1, 0,)), "Error restoring the count",);
+      assert_eq!(cast_vote(&coll, &votes, &id, "bob", VoteDirection::Up,).await.map(|outcome,| outcome.change,), Ok(VoteChange::New), "Error retrying the vote",);
+
+      coll.flaky_writes(*id, 1,);
+      assert_eq!(cast_vote_atomic(&coll, &votes, &id, "cy", VoteDirection::Down,).await, Err(VoteError::Count(ChaosError::Injected(*id,),)), "Error failing the atomic count",);
+      assert_eq!(cast_vote_atomic(&coll, &votes, &id, "cy", VoteDirection::Down,).await.map(|outcome,| outcome.change,), Ok(VoteChange::New), "Error retrying the atomic vote",);
+    },);
+    assert_eq!(counts(coll.inner(), 1,), (2, 1,), "Error losing the retried votes",);
+  }
+  #[test]
+  fn test_concurrent_votes() {
+    let (coll, votes,) = seeded();
+    let (cards, ballots,) = (YieldingCollection::new(coll.clone(),), YieldingCollection::new(votes.clone(),),);
+    let id = CardId::from_bytes([1; 20]);
+    let voters = (0..8).map(|i,| format!("voter {}", i,),).collect::<Vec<_>>();
+
+    //Every vote reads the card before any writes it.
+    let outcomes = block_on(future::join_all(voters.iter().map(|voter,| cast_vote(&cards, &ballots, &id, voter, VoteDirection::Up,),),),);
+    assert!(outcomes.iter().all(|outcome,| matches!(outcome, Ok(VoteOutcome { change: VoteChange::New, .. })),), "Error casting concurrent votes",);
+    assert_eq!(counts(&coll, 1,), (8, 0,), "Error lost votes",);
+
+    let outcomes = block_on(future::join_all(voters.iter().map(|voter,| cast_vote_atomic(&cards, &ballots, &id, voter, VoteDirection::Down,),),),);
+    assert!(outcomes.iter().all(|outcome,| matches!(outcome, Ok(VoteOutcome { change: VoteChange::Flipped, .. })),), "Error flipping concurrent votes",);
+    assert_eq!(counts(&coll, 1,), (0, 8,), "Error lost atomic votes",);
+
+    //Concurrent repeats of one vote are counted once.
+    let outcomes = block_on(future::join_all((0..4).map(|_,| cast_vote(&cards, &ballots, &id, "ada", VoteDirection::Up,),),),);
+    assert_eq!(outcomes.iter().filter(|outcome,| matches!(outcome, Ok(VoteOutcome { change: VoteChange::New, .. })),).count(), 1, "Error counting a repeated vote twice",);
+    assert_eq!(counts(&coll, 1,), (1, 8,), "Error counting concurrent repeats",);
+  }
+}
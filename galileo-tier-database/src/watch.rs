@@ -0,0 +1,113 @@
+//! Defines operations for subscribing to the changes made to documents in a collection.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, TierListCollection,};
+use futures::{Stream, channel::mpsc::{self, UnboundedSender, UnboundedReceiver,},};
+use std::{rc::Rc, sync::Arc,};
+
+/// The kinds of change made to a document.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug,)]
+pub enum ChangeKind {
+  /// The document was written and did not exist before.
+  Created,
+  /// The document was written and replaced an existing document.
+  Updated,
+  /// The document was deleted.
+  Deleted,
+}
+
+/// A change made to a document in a collection.
+#[derive(PartialEq, Eq, Clone, Debug,)]
+pub struct ChangeEvent<D,> {
+  /// The identifier of the changed document.
+  pub id: DocumentId,
+  /// The kind of change.
+  pub kind: ChangeKind,
+  /// The new document if the collection provides it; always `None` for deletions.
+  pub document: Option<D>,
+}
+
+/// A collection which can report changes made to its documents.
+/// 
+/// Delivery is best effort: a collection may coalesce several changes to a document
+/// into one event and events made by other processes may not be seen at all. Events
+/// for one document are delivered in the order the changes were made.
+pub trait WatchableCollection: TierListCollection {
+  /// The stream of changes to the watched documents.
+  type Watch: Stream<Item = ChangeEvent<Self::Document>>;
+
+  /// Subscribes to the changes made to documents.
+  /// 
+  /// Only changes made after subscribing are reported.
+  /// 
+  /// # Params
+  /// 
+  /// ids --- The identifiers of the documents to watch.  
+  fn watch(&self, ids: &[&DocumentId],) -> Self::Watch;
+}
+
+impl<Coll,> WatchableCollection for &Coll
+  where Coll: WatchableCollection, {
+  type Watch = Coll::Watch;
+
+  #[inline]
+  fn watch(&self, ids: &[&DocumentId],) -> Self::Watch { Coll::watch(*self, ids,) }
+}
+
+impl<Coll,> WatchableCollection for Rc<Coll,>
+  where Coll: WatchableCollection, {
+  type Watch = Coll::Watch;
+
+  #[inline]
+  fn watch(&self, ids: &[&DocumentId],) -> Self::Watch { Coll::watch(&**self, ids,) }
+}
+
+impl<Coll,> WatchableCollection for Arc<Coll,>
+  where Coll: WatchableCollection, {
+  type Watch = Coll::Watch;
+
+  #[inline]
+  fn watch(&self, ids: &[&DocumentId],) -> Self::Watch { Coll::watch(&**self, ids,) }
+}
+
+impl<Coll,> WatchableCollection for Box<Coll,>
+  where Coll: WatchableCollection, {
+  type Watch = Coll::Watch;
+
+  #[inline]
+  fn watch(&self, ids: &[&DocumentId],) -> Self::Watch { Coll::watch(&**self, ids,) }
+}
+
+/// The subscribers to the changes of an in process collection.
+pub(crate) struct Watchers<D,> {
+  /// The watched identifiers and the channel to send their changes to.
+  subscribers: Vec<(Vec<DocumentId>, UnboundedSender<ChangeEvent<D>>,)>,
+}
+
+impl<D,> Watchers<D,> {
+  /// Returns a new set of subscribers.
+  #[inline]
+  pub(crate) const fn new() -> Self { Self { subscribers: Vec::new(), } }
+  /// Adds a subscriber to the changes of documents.
+  pub(crate) fn subscribe(&mut self, ids: &[&DocumentId],) -> UnboundedReceiver<ChangeEvent<D>> {
+    let (sender, receiver,) = mpsc::unbounded();
+
+    self.subscribers.push((ids.iter().map(|&&id,| id,).collect(), sender,),);
+    receiver
+  }
+}
+
+impl<D,> Watchers<D,>
+  where D: Clone, {
+  /// Sends a change to its subscribers, dropping subscribers which have gone away.
+  pub(crate) fn notify(&mut self, event: ChangeEvent<D>,) {
+    self.subscribers.retain(|(ids, sender,),| {
+      if sender.is_closed() { return false }
+      if !ids.contains(&event.id,) { return true }
+
+      sender.unbounded_send(event.clone(),).is_ok()
+    },)
+  }
+}
@@ -0,0 +1,224 @@
+//! Defines a cursor which caches the neighbours of its item.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{LinkedList, Cursor, TierListCollection,};
+use std::{fmt, mem,};
+
+/// A view into a collection which holds the previous and next nodes of its item as well
+/// as the item.
+/// 
+/// The neighbours are fetched when the `WindowCursor` is created so reading them does
+/// not touch the collection. Moving the window reuses the cached neighbour and only
+/// fetches the one node which enters the window.
+#[derive(PartialEq, Eq, Clone, Copy,)]
+pub struct WindowCursor<T, Coll,>
+  where Coll: TierListCollection, {
+  /// The `Cursor` at the current item.
+  cursor: Cursor<T, Coll,>,
+  /// The previous node of the current item.
+  previous: Option<T>,
+  /// The next node of the current item.
+  next: Option<T>,
+}
+
+impl<T, Coll,> WindowCursor<T, Coll,>
+  where Coll: TierListCollection, {
+  /// Breaks the cursor into its component parts.
+  #[inline]
+  pub fn into_parts(self,) -> (Coll, T,) { self.cursor.into_parts() }
+  /// Unwraps the `Cursor` at the current item, dropping the cached neighbours.
+  #[inline]
+  pub fn into_cursor(self,) -> Cursor<T, Coll,> { self.cursor }
+  /// Gets the collection used by this `WindowCursor`.
+  #[inline]
+  pub const fn get_collection(&self,) -> &Coll { self.cursor.get_collection() }
+  /// Gets the previous node of the current item.
+  #[inline]
+  pub const fn get_previous(&self,) -> Option<&T> { self.previous.as_ref() }
+  /// Gets the current item.
+  #[inline]
+  pub const fn get_item(&self,) -> &T { self.cursor.get_item() }
+  /// Gets the next node of the current item.
+  #[inline]
+  pub const fn get_next(&self,) -> Option<&T> { self.next.as_ref() }
+}
+
+impl<T, Coll,> WindowCursor<T, Coll,>
+  where T: LinkedList,
+    Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  /// Returns a new `WindowCursor` at the item of a `Cursor`, fetching its neighbours
+  /// using a single batch read.
+  /// 
+  /// If there was an error getting the neighbours the `Cursor` is returned as
+  /// `Err((cursor, error))`.
+  /// 
+  /// # Params
+  /// 
+  /// cursor --- The `Cursor` at the current item.  
+  pub async fn new(cursor: Cursor<T, Coll,>,) -> Result<Self, (Cursor<T, Coll,>, Coll::Error,)> {
+    let item = cursor.get_item();
+    let ids = item.get_previous_id().iter().chain(item.get_next_id().iter(),)
+      .map(|&&id,| id,)
+      .collect::<Vec<_>>();
+    let (has_previous, has_next,) = (item.get_previous_id().is_some(), item.get_next_id().is_some(),);
+    let mut documents = if ids.is_empty() { Vec::new() }
+      else {
+        match cursor.get_collection().get_documents(ids,).await {
+          Ok(documents) => documents,
+          Err(e) => return Err((cursor, e,)),
+        }
+      }.into_iter();
+    let mut neighbour = |present: bool,| if present { documents.next().map(|document,| document.map(Into::into,),).transpose() }
+      else { Ok(None) };
+    let previous = neighbour(has_previous,);
+    let next = neighbour(has_next,);
+
+    match (previous, next,) {
+      (Ok(previous), Ok(next),) => Ok(Self { cursor, previous, next, }),
+      (Err(e), _,) | (_, Err(e),) => Err((cursor, e,)),
+    }
+  }
+  /// Moves this `WindowCursor` to the next node in the linked list.
+  /// 
+  /// Returns `Ok(false)` if there is no next node. Only the node after the next node is
+  /// fetched. If there was an error getting it this `WindowCursor` is unchanged.
+  pub async fn advance(&mut self,) -> Result<bool, Coll::Error> {
+    let after = match self.next.as_ref().map(|next,| next.get_next_id(),) {
+      None => return Ok(false),
+      Some(None) => None,
+      Some(Some(id)) => Some(self.cursor.get_collection().get_document(id,).await?.into(),),
+    };
+
+    if let Some(next) = mem::replace(&mut self.next, after,) {
+      self.previous = Some(self.cursor.step(next, true,),);
+    }
+
+    Ok(true)
+  }
+  /// Moves this `WindowCursor` to the previous node in the linked list.
+  /// 
+  /// Returns `Ok(false)` if there is no previous node. Only the node before the
+  /// previous node is fetched. If there was an error getting it this `WindowCursor` is
+  /// unchanged.
+  pub async fn retreat(&mut self,) -> Result<bool, Coll::Error> {
+    let before = match self.previous.as_ref().map(|previous,| previous.get_previous_id(),) {
+      None => return Ok(false),
+      Some(None) => None,
+      Some(Some(id)) => Some(self.cursor.get_collection().get_document(id,).await?.into(),),
+    };
+
+    if let Some(previous) = mem::replace(&mut self.previous, before,) {
+      self.next = Some(self.cursor.step(previous, false,),);
+    }
+
+    Ok(true)
+  }
+}
+
+impl<T, Coll,> fmt::Debug for WindowCursor<T, Coll,>
+  where T: fmt::Debug,
+    Coll: TierListCollection, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    fmt.debug_struct("WindowCursor",)
+    .field("cursor", &self.cursor,)
+    .field("previous", &self.previous,)
+    .field("next", &self.next,)
+    .finish()
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{CardId, DocumentId, Card, CardStatus, MemoryCollection, RecordingCollection, Op, mock::{ChaosCollection, ChaosError,},};
+  use futures::executor::block_on;
+
+  fn card(id: u8, len: u8,) -> Card {
+    Card {
+      id: CardId::from_bytes([id; 20]),
+      name: "card".to_owned(),
+      description: String::new(),
+      up_votes: 0,
+      down_votes: 0,
+      bias: 0,
+      status: CardStatus::Open,
+      history: Vec::new(),
+      previous_card: if id > 1 { Some(CardId::from_bytes([id - 1; 20])) } else { None },
+      next_card: if id < len { Some(CardId::from_bytes([id + 1; 20])) } else { None },
+    }
+  }
+  fn ids(window: &WindowCursor<Card, &RecordingCollection<MemoryCollection<Card,>>,>,) -> (Option<u8>, u8, Option<u8>,) {
+    (window.get_previous().map(|card,| card.id.as_bytes()[0],), window.get_item().id.as_bytes()[0], window.get_next().map(|card,| card.id.as_bytes()[0],),)
+  }
+
+  #[test]
+  fn test_window() {
+    let coll = RecordingCollection::new(MemoryCollection::new(),);
+
+    block_on(async {
+      coll.get_inner().write_documents((1..=5).map(|id,| card(id, 5,),),).await
+        .expect("Error writing cards")
+        .expect("Error writing cards");
+
+      let cursor = coll.ref_cursor::<Card>(&DocumentId::from_bytes([2; 20]),).await
+        .expect("Error reading card");
+      coll.take_log();
+      let mut window = WindowCursor::new(cursor.with_position(1,),).await
+        .expect("Error reading neighbours");
+      assert_eq!(ids(&window,), (Some(1,), 2, Some(3,),), "Error filling window",);
+      crate::assert_ops!(coll, [Op::GetBatch(vec![DocumentId::from_bytes([1; 20]), DocumentId::from_bytes([3; 20]),]),], "Error fetching neighbours in one batch",);
+
+      for current in 3..=4 {
+        assert_eq!(window.advance().await, Ok(true), "Error advancing",);
+        assert_eq!(ids(&window,), (Some(current - 1,), current, Some(current + 1,),), "Error shifting window",);
+        crate::assert_ops!(coll, [Op::Get(DocumentId::from_bytes([current + 1; 20]),),], "Error fetching more than the new node",);
+      }
+
+      assert_eq!(window.advance().await, Ok(true), "Error advancing to the tail",);
+      assert_eq!(ids(&window,), (Some(4,), 5, None,), "Error shifting to the tail",);
+      assert_eq!(window.advance().await, Ok(false), "Error advancing past the tail",);
+      assert_eq!(ids(&window,), (Some(4,), 5, None,), "Error window changed at the tail",);
+      crate::assert_ops!(coll, [], "Error fetching past the tail",);
+
+      assert_eq!(window.retreat().await, Ok(true), "Error retreating",);
+      assert_eq!(ids(&window,), (Some(3,), 4, Some(5,),), "Error shifting window back",);
+      assert_eq!(window.into_cursor().position(), Some(3), "Error tracking the position",);
+      crate::assert_ops!(coll, [Op::Get(DocumentId::from_bytes([3; 20]),),], "Error fetching more than the new node",);
+
+      let cursor = coll.ref_cursor::<Card>(&DocumentId::from_bytes([1; 20]),).await
+        .expect("Error reading head");
+      let window = WindowCursor::new(cursor,).await
+        .expect("Error reading neighbours");
+      assert_eq!(ids(&window,), (None, 1, Some(2,),), "Error filling window at the head",);
+      assert_eq!(window.into_cursor().get_item().id, DocumentId::from_bytes([1; 20]), "Error unwrapping Cursor",);
+    },);
+  }
+  #[test]
+  fn test_failed_fetch() {
+    let coll = ChaosCollection::<Card,>::new();
+    let id = |id: u8,| -> DocumentId { DocumentId::from_bytes([id; 20]) };
+
+    block_on(async {
+      coll.write_documents((1..=4).map(|id,| card(id, 4,),),).await
+        .expect("Error writing cards")
+        .expect("Error writing cards");
+      coll.fail_reads(id(3,),);
+
+      let cursor = coll.ref_cursor::<Card>(&id(2,),).await
+        .expect("Error reading card");
+      let (cursor, e,) = WindowCursor::new(cursor,).await
+        .expect_err("Error reading broken neighbour");
+      assert_eq!((*cursor.get_item().id, e,), (id(2,), ChaosError::Injected(id(3,),),), "Error returning the Cursor",);
+
+      let cursor = coll.ref_cursor::<Card>(&id(1,),).await
+        .expect("Error reading card");
+      let mut window = WindowCursor::new(cursor,).await
+        .expect("Error reading neighbours");
+      assert_eq!(window.advance().await, Err(ChaosError::Injected(id(3,),)), "Error advancing onto a broken node",);
+      assert_eq!(window.get_item().id, id(1,), "Error window changed after an error",);
+    },);
+  }
+}
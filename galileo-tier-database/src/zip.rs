@@ -0,0 +1,321 @@
+//! Defines walking two linked lists in lockstep to compare them.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-14
+
+use crate::{DocumentId, LinkedList, Cursor, CollectError, TierMeta, TierListCollection, walk_links, guard_cycles, list::tier_is_empty,};
+use futures::{future, stream, Stream, StreamExt, TryStreamExt,};
+use std::{fmt, error::Error, collections::HashMap,};
+
+/// The errors when walking two linked lists in lockstep.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum ZipError<E1, E2,> {
+  /// Getting a node of the first list failed.
+  First(E1),
+  /// Getting a node of the second list failed.
+  Second(E2),
+  /// A list reached the document with the identifier twice.
+  Cycle(DocumentId),
+}
+
+impl<E1, E2,> fmt::Display for ZipError<E1, E2,>
+  where E1: fmt::Display,
+    E2: fmt::Display, {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      ZipError::First(e) => write!(fmt, "failed to get a node of the first list: {}", e,),
+      ZipError::Second(e) => write!(fmt, "failed to get a node of the second list: {}", e,),
+      ZipError::Cycle(id) => write!(fmt, "the document with the id {:?} was reached twice", id,),
+    }
+  }
+}
+
+impl<E1, E2,> Error for ZipError<E1, E2,>
+  where E1: Error,
+    E2: Error, {}
+
+/// The differences between two linked lists, returned by `diff_lists`.
+#[derive(PartialEq, Eq, Clone, Default, Debug,)]
+pub struct ListDiff {
+  /// The identifiers of the nodes only in the new list, in the order of the new list.
+  pub added: Vec<DocumentId>,
+  /// The identifiers of the nodes only in the old list, in the order of the old list.
+  pub removed: Vec<DocumentId>,
+  /// The identifiers of the nodes in both lists at different positions with their old
+  /// and new positions, in the order of the new list.
+  pub moved: Vec<(DocumentId, u64, u64,)>,
+}
+
+/// A change to the position of a node between two orders, returned by `diff_order`.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug,)]
+pub enum OrderChange {
+  /// The node is in both orders at different positions.
+  Moved {
+    /// The identifier of the node.
+    id: DocumentId,
+    /// The position of the node in the old order.
+    from: u64,
+    /// The position of the node in the new order.
+    to: u64,
+  },
+  /// The node is only in the new order.
+  Added {
+    /// The identifier of the node.
+    id: DocumentId,
+    /// The position of the node in the new order.
+    at: u64,
+  },
+  /// The node is only in the old order.
+  Removed {
+    /// The identifier of the node.
+    id: DocumentId,
+    /// The position of the node in the old order.
+    from: u64,
+  },
+}
+
+impl ListDiff {
+  /// Returns `true` if the lists were the same.
+  #[inline]
+  pub fn is_empty(&self,) -> bool { self.added.is_empty() && self.removed.is_empty() && self.moved.is_empty() }
+}
+
+/// Walks the linked lists after two `Cursor`s in lockstep, yielding their items in
+/// pairs starting with the items at the `Cursor`s.
+/// 
+/// Once one list ends `None` is paired with the items of the other until both lists end.
+/// Nothing is read until the stream is polled and the stream ends after yielding an
+/// error, including `ZipError::Cycle` if either list reaches a node twice.
+/// 
+/// # Params
+/// 
+/// a --- The `Cursor` at the front of the first list.  
+/// b --- The `Cursor` at the front of the second list.  
+pub fn zip_walk<T, C1, C2,>(a: Cursor<T, C1,>, b: Cursor<T, C2,>,) -> impl Stream<Item = Result<(Option<T>, Option<T>,), ZipError<C1::Error, C2::Error,>>>
+  where T: LinkedList,
+    C1: TierListCollection,
+    C1::Document: Into<T>,
+    C2: TierListCollection,
+    C2::Document: Into<T>, {
+  let a = Box::pin(a.into_stream().fuse(),);
+  let b = Box::pin(b.into_stream().fuse(),);
+
+  stream::unfold(Some((a, b,),), |state,| async move {
+    let (mut a, mut b,) = state?;
+    let (first, second,) = future::join(a.next(), b.next(),).await;
+    let pair = match (first, second,) {
+      (None, None,) => return None,
+      (Some(Err(CollectError::Fetch(e))), _,) => return Some((Err(ZipError::First(e,),), None,)),
+      (_, Some(Err(CollectError::Fetch(e))),) => return Some((Err(ZipError::Second(e,),), None,)),
+      (Some(Err(CollectError::Cycle(id))), _,) | (_, Some(Err(CollectError::Cycle(id))),) => return Some((Err(ZipError::Cycle(id,),), None,)),
+      (first, second,) => (first.and_then(Result::ok,), second.and_then(Result::ok,),),
+    };
+
+    Some((Ok(pair), Some((a, b,),),))
+  },)
+}
+
+/// Compares the linked lists after two `Cursor`s by the positions of their nodes.
+/// 
+/// # Params
+/// 
+/// old --- The `Cursor` at the front of the old list.  
+/// new --- The `Cursor` at the front of the new list.  
+pub async fn diff_lists<T, C1, C2,>(old: Cursor<T, C1,>, new: Cursor<T, C2,>,) -> Result<ListDiff, ZipError<C1::Error, C2::Error,>>
+  where T: LinkedList,
+    C1: TierListCollection,
+    C1::Document: Into<T>,
+    C2: TierListCollection,
+    C2::Document: Into<T>, {
+  let mut old_ids = Vec::new();
+  let mut new_ids = Vec::new();
+  let mut old_positions = HashMap::new();
+  let mut new_positions = HashMap::new();
+  let mut pairs = Box::pin(zip_walk(old, new,),);
+
+  while let Some((first, second,)) = pairs.try_next().await? {
+    for (item, ids, positions,) in [(first, &mut old_ids, &mut old_positions,), (second, &mut new_ids, &mut new_positions,),] {
+      if let Some(item) = item {
+        positions.insert(*item.get_id(), ids.len() as u64,);
+        ids.push(*item.get_id(),);
+      }
+    }
+  }
+
+  let mut diff = ListDiff::default();
+  for (position, id,) in new_ids.iter().enumerate() {
+    match old_positions.get(id,) {
+      Some(&old,) if old != position as u64 => diff.moved.push((*id, old, position as u64,),),
+      Some(_) => {},
+      None => diff.added.push(*id,),
+    }
+  }
+  diff.removed = old_ids.into_iter().filter(|id,| !new_positions.contains_key(id,),).collect();
+
+  Ok(diff)
+}
+
+/// Gets the identifiers of the nodes of a tier in order, to be compared later using
+/// `diff_order`.
+/// 
+/// The tier is walked from its front and the walk is guarded by `guard_cycles`, whether
+/// or not its length is known. A tier whose front is nil or not stored is empty.
+/// 
+/// # Params
+/// 
+/// collection --- The collection storing the list.  
+/// tier --- The tier the list makes up.  
+pub async fn tier_order_snapshot<T, Coll,>(collection: &Coll, tier: &TierMeta,) -> Result<Vec<DocumentId>, CollectError<Coll::Error,>>
+  where T: LinkedList,
+    Coll: TierListCollection,
+    Coll::Document: Into<T>, {
+  if tier_is_empty::<T, _,>(collection, tier,).await.map_err(CollectError::Fetch,)? { return Ok(Vec::new()) }
+
+  let link = |item: &T,| item.get_next_id().copied();
+
+  guard_cycles(walk_links(collection, Err(**tier.list_front(),), link,), link,)
+    .map_ok(|item,| *item.get_id(),)
+    .try_collect().await
+}
+
+/// Compares two orders of identifiers by the positions of their nodes.
+/// 
+/// The moved and added nodes come first in the new order followed by the removed nodes
+/// in the old order.
+/// 
+/// # Params
+/// 
+/// old --- The old order.  
+/// new --- The new order.  
+pub fn diff_order(old: &[DocumentId], new: &[DocumentId],) -> Vec<OrderChange> {
+  let old_positions = old.iter().enumerate().map(|(position, id,),| (*id, position as u64,),).collect::<HashMap<_, _,>>();
+  let new_positions = new.iter().enumerate().map(|(position, id,),| (*id, position as u64,),).collect::<HashMap<_, _,>>();
+  let mut changes = Vec::new();
+
+  for (to, &id,) in new.iter().enumerate() {
+    let to = to as u64;
+
+    match old_positions.get(&id,) {
+      Some(&from,) if from != to => changes.push(OrderChange::Moved { id, from, to, },),
+      Some(_) => {},
+      None => changes.push(OrderChange::Added { id, at: to, },),
+    }
+  }
+  for (from, &id,) in old.iter().enumerate() {
+    if !new_positions.contains_key(&id,) { changes.push(OrderChange::Removed { id, from: from as u64, },) }
+  }
+
+  changes
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{CardId, TierId, MemoryCollection, mock::{ChaosCollection, ChaosError,},};
+  use futures::executor::block_on;
+  use std::num::NonZeroU64;
+
+  /// Returns a collection storing a list of tiers with the identifiers in order.
+  fn list(ids: &[u8],) -> ChaosCollection<TierMeta,> {
+    let coll = ChaosCollection::new();
+    let tiers = (0..ids.len()).map(|i,| TierMeta::new(
+      TierId::from_bytes([ids[i]; 20]),
+      (None, CardId::from_bytes([ids[i]; 20]), CardId::from_bytes([ids[i]; 20]),),
+      i.checked_sub(1,).map(|i,| TierId::from_bytes([ids[i]; 20]),),
+      ids.get(i + 1,).map(|&id,| TierId::from_bytes([id; 20]),),
+    ),).collect::<Vec<_>>();
+
+    block_on(coll.write_documents(&tiers,),)
+      .expect("Error writing list")
+      .expect("Error writing list");
+
+    coll
+  }
+  fn front(coll: &ChaosCollection<TierMeta,>, id: u8,) -> Cursor<TierMeta, &ChaosCollection<TierMeta,>,> {
+    block_on(coll.ref_cursor(&DocumentId::from_bytes([id; 20]),),)
+      .expect("Error reading front")
+  }
+
+  #[test]
+  fn test_zip_walk() {
+    let (a, b,) = (list(&[1, 2, 3,],), list(&[4,],),);
+    let pairs = block_on(zip_walk(front(&a, 1,), front(&b, 4,),).map_ok(|(first, second,),| (first.map(|tier,| tier.id.as_bytes()[0],), second.map(|tier,| tier.id.as_bytes()[0],),),).try_collect::<Vec<_>>(),);
+    assert_eq!(pairs, Ok(vec![(Some(1), Some(4)), (Some(2), None), (Some(3), None),]), "Error filling the shorter list",);
+
+    a.fail_reads(DocumentId::from_bytes([3; 20]),);
+    let pairs = block_on(zip_walk(front(&b, 4,), front(&a, 1,),).collect::<Vec<_>>(),);
+    assert_eq!(pairs.len(), 3, "Error not ending after an error",);
+    assert_eq!(pairs[2], Err(ZipError::Second(ChaosError::Injected(DocumentId::from_bytes([3; 20]),),)), "Error failing the second list",);
+  }
+  #[test]
+  fn test_diff_lists() {
+    let (old, new,) = (list(&[1, 2, 3, 4,],), list(&[2, 1, 3, 5, 6,],),);
+    let diff = block_on(diff_lists(front(&old, 1,), front(&new, 2,),),)
+      .expect("Error comparing lists");
+    assert_eq!(
+      diff,
+      ListDiff { added: vec![DocumentId::from_bytes([5; 20]), DocumentId::from_bytes([6; 20]),], removed: vec![DocumentId::from_bytes([4; 20]),], moved: vec![(DocumentId::from_bytes([2; 20]), 1, 0,), (DocumentId::from_bytes([1; 20]), 0, 1,),], },
+      "Error comparing a reordered list",
+    );
+
+    let diff = block_on(diff_lists(front(&old, 1,), front(&old, 1,),),)
+      .expect("Error comparing lists");
+    assert!(diff.is_empty(), "Error comparing a list with itself",);
+
+    //A list whose tail links back to its head.
+    let cycle = MemoryCollection::<TierMeta,>::new();
+    let tiers = [
+      TierMeta::new(TierId::from_bytes([1; 20]), (None, CardId::from_bytes([1; 20]), CardId::from_bytes([1; 20]),), None, Some(TierId::from_bytes([2; 20])),),
+      TierMeta::new(TierId::from_bytes([2; 20]), (None, CardId::from_bytes([2; 20]), CardId::from_bytes([2; 20]),), Some(TierId::from_bytes([1; 20])), Some(TierId::from_bytes([1; 20])),),
+    ];
+    block_on(cycle.write_documents(tiers,),)
+      .expect("Error writing list")
+      .expect("Error writing list");
+    let head = block_on(cycle.ref_cursor(&DocumentId::from_bytes([1; 20]),),)
+      .expect("Error reading head");
+    assert_eq!(block_on(diff_lists(front(&old, 1,), head,),), Err(ZipError::Cycle(DocumentId::from_bytes([1; 20]),)), "Error detecting a cycle",);
+  }
+  #[test]
+  fn test_tier_order_snapshot() {
+    let coll = list(&[3, 1, 2,],);
+    let tier = TierMeta::new(TierId::from_bytes([0; 20]), (NonZeroU64::new(3,), CardId::from_bytes([3; 20]), CardId::from_bytes([2; 20]),), None, None,);
+    assert_eq!(block_on(tier_order_snapshot::<TierMeta, _,>(&coll, &tier,),), Ok(vec![DocumentId::from_bytes([3; 20]), DocumentId::from_bytes([1; 20]), DocumentId::from_bytes([2; 20]),]), "Error snapshotting the order",);
+
+    let empty = TierMeta::new(TierId::from_bytes([0; 20]), (None, CardId::NIL, CardId::NIL,), None, None,);
+    assert_eq!(block_on(tier_order_snapshot::<TierMeta, _,>(&coll, &empty,),), Ok(Vec::new()), "Error snapshotting an empty tier",);
+
+    //A tier of unknown length is walked.
+    let unknown = TierMeta::new(TierId::from_bytes([0; 20]), (None, CardId::from_bytes([3; 20]), CardId::from_bytes([2; 20]),), None, None,);
+    assert_eq!(block_on(tier_order_snapshot::<TierMeta, _,>(&coll, &unknown,),), Ok(vec![DocumentId::from_bytes([3; 20]), DocumentId::from_bytes([1; 20]), DocumentId::from_bytes([2; 20]),]), "Error snapshotting a tier of unknown length",);
+
+    coll.flaky_reads(DocumentId::from_bytes([1; 20]), 1,);
+    assert_eq!(block_on(tier_order_snapshot::<TierMeta, _,>(&coll, &tier,),), Err(CollectError::Fetch(ChaosError::Injected(DocumentId::from_bytes([1; 20]),),)), "Error failing the snapshot",);
+  }
+  #[test]
+  fn test_diff_order() {
+    let ids = |ids: &[u8],| ids.iter().map(|&id,| DocumentId::from_bytes([id; 20]),).collect::<Vec<_>>();
+
+    assert_eq!(diff_order(&ids(&[1, 2, 3,],), &ids(&[1, 2, 3,],),), [], "Error comparing identical orders",);
+    assert_eq!(diff_order(&[], &[],), [], "Error comparing empty orders",);
+    assert_eq!(
+      diff_order(&ids(&[1, 2, 3,],), &ids(&[3, 1, 2,],),),
+      [OrderChange::Moved { id: DocumentId::from_bytes([3; 20]), from: 2, to: 0, }, OrderChange::Moved { id: DocumentId::from_bytes([1; 20]), from: 0, to: 1, }, OrderChange::Moved { id: DocumentId::from_bytes([2; 20]), from: 1, to: 2, },],
+      "Error comparing a reorder",
+    );
+    assert_eq!(
+      diff_order(&ids(&[1, 2,],), &ids(&[1, 2, 4,],),),
+      [OrderChange::Added { id: DocumentId::from_bytes([4; 20]), at: 2, },],
+      "Error comparing an insertion",
+    );
+    assert_eq!(
+      diff_order(&ids(&[1, 2, 3,],), &ids(&[1, 3,],),),
+      [OrderChange::Moved { id: DocumentId::from_bytes([3; 20]), from: 2, to: 1, }, OrderChange::Removed { id: DocumentId::from_bytes([2; 20]), from: 1, },],
+      "Error comparing a removal",
+    );
+    assert_eq!(
+      diff_order(&ids(&[1, 2,],), &ids(&[2, 5,],),),
+      [OrderChange::Moved { id: DocumentId::from_bytes([2; 20]), from: 1, to: 0, }, OrderChange::Added { id: DocumentId::from_bytes([5; 20]), at: 1, }, OrderChange::Removed { id: DocumentId::from_bytes([1; 20]), from: 0, },],
+      "Error comparing several changes",
+    );
+  }
+}
@@ -3,7 +3,73 @@
 //! The concept of a `galileo tier list` is a publicly viewable tier list where items
 //! move between tiers based on upvotes and downvotes.
 //! 
+//! Each argument is a vote of the form `voter=up` or `voter=down` which is cast on a
+//! single card with `cast_vote`, printing the new score of the card after each vote.
+//! 
 //! Author --- daniel.bechaz@gmail.com  
-//! Last Moddified --- 2019-06-02
+//! Last Moddified --- 2026-10-14
+
+use galileo_tier_database::{CardId, Card, CardStatus, TierDocument, Vote, VoteDirection, MemoryCollection, TierListCollection, cast_vote,};
+use futures::executor::block_on;
+use std::{env, process,};
+
+/// Parses a vote argument of the form `voter=up` or `voter=down`.
+/// 
+/// # Params
+/// 
+/// arg --- The argument to parse.  
+fn parse_vote(arg: &str,) -> Option<(&str, VoteDirection,)> {
+  let (voter, direction,) = arg.split_at(arg.find('=',)?,);
+  let direction = match &direction[1..] {
+    "up" => VoteDirection::Up,
+    "down" => VoteDirection::Down,
+    _ => return None,
+  };
+
+  if voter.is_empty() { None } else { Some((voter, direction,)) }
+}
+
+fn main() {
+  let args = env::args().skip(1,).collect::<Vec<_>>();
+  let mut ballots = Vec::with_capacity(args.len(),);
+  for arg in &args {
+    match parse_vote(arg,) {
+      Some(ballot) => ballots.push(ballot,),
+      None => {
+        eprintln!("expected a vote of the form `voter=up` or `voter=down`, found `{}`", arg,);
+        process::exit(2,)
+      },
+    }
+  }
+
+  let cards = MemoryCollection::<TierDocument,>::new();
+  let votes = MemoryCollection::<Vote,>::new();
+  let card = Card {
+    id: CardId::from_bytes([1; 20],),
+    name: "card".to_owned(),
+    description: String::new(),
+    up_votes: 0,
+    down_votes: 0,
+    bias: 0,
+    status: CardStatus::Open,
+    history: Vec::new(),
+    previous_card: None,
+    next_card: None,
+  };
+  let id = card.id;
+
+  if let Err(e) = block_on(cards.write_document(&TierDocument::from(card,),),) {
+    eprintln!("failed to write the card: {}", e,);
+    process::exit(1,)
+  }
 
-#![feature(async_await, await_macro, associated_type_defaults, const_fn,)]
+  for (voter, direction,) in ballots {
+    match block_on(cast_vote(&cards, &votes, &id, voter, direction,),) {
+      Ok(outcome) => println!("{}: {:?}, score {}", voter, outcome.change, outcome.score,),
+      Err(e) => {
+        eprintln!("failed to cast the vote of {}: {}", voter, e,);
+        process::exit(1,)
+      },
+    }
+  }
+}